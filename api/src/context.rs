@@ -23,7 +23,7 @@ use aptos_logger::{error, info, Schema};
 use aptos_mempool::{MempoolClientRequest, MempoolClientSender, SubmissionStatus};
 use aptos_storage_interface::{
     state_store::state_view::db_state_view::{
-        DbStateView, DbStateViewAtVersion, LatestDbStateCheckpointView,
+        DbStateView, DbStateViewAtVersion, LatestDbStateCheckpointView, OptionalProofDbStateView,
     },
     AptosDbError, DbReader, Order, MAX_REQUEST_LIMIT,
 };
@@ -182,6 +182,29 @@ impl Context {
         Ok((latest_ledger_info, requested_ledger_version, state_view))
     }
 
+    /// Like [Self::state_view], but marks the state read at versions whose state Merkle tree is
+    /// already pruned as unproven, if unproven historical state reads are enabled.
+    pub fn optional_proof_state_view<E: StdApiError>(
+        &self,
+        requested_ledger_version: Option<u64>,
+    ) -> Result<(LedgerInfo, u64, OptionalProofDbStateView), E> {
+        let (latest_ledger_info, requested_ledger_version) =
+            self.get_latest_ledger_info_and_verify_lookup_version(requested_ledger_version)?;
+
+        let state_view = OptionalProofDbStateView::new(
+            self.db.clone(),
+            requested_ledger_version,
+            self.node_config
+                .storage
+                .enable_unproven_historical_state_reads,
+        )
+        .map_err(|err| {
+            E::internal_with_code(err, AptosErrorCode::InternalError, &latest_ledger_info)
+        })?;
+
+        Ok((latest_ledger_info, requested_ledger_version, state_view))
+    }
+
     pub fn state_view_at_version(&self, version: Version) -> Result<DbStateView> {
         Ok(self.db.state_view_at_version(Some(version))?)
     }
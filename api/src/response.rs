@@ -314,6 +314,9 @@ macro_rules! generate_success_response {
                 /// pagination. Pass this to the `start` field of the endpoint
                 /// on the next call to get the next page of results.
                 #[oai(header = "X-Aptos-Cursor")] Option<String>,
+                /// Set to true if the returned state was read without a proof, because
+                /// the state Merkle tree at the requested version is already pruned
+                #[oai(header = "X-Aptos-State-Unproven")] Option<bool>,
             ),
             )*
         }
@@ -355,6 +358,7 @@ macro_rules! generate_success_response {
                             ledger_info.oldest_block_height.into(),
                             None,
                             None,
+                            None,
                         )
                     },
                     )*
@@ -478,7 +482,7 @@ macro_rules! generate_success_response {
             pub fn with_cursor(mut self, new_cursor: Option<aptos_types::state_store::state_key::StateKey>) -> Self {
                 match self {
                     $(
-                    [<$enum_name>]::$name(_, _, _, _, _, _, _, _, _, ref mut cursor) => {
+                    [<$enum_name>]::$name(_, _, _, _, _, _, _, _, _, ref mut cursor, _) => {
                         *cursor = new_cursor.map(|c| aptos_api_types::StateKeyWrapper::from(c).to_string());
                     }
                     )*
@@ -489,13 +493,24 @@ macro_rules! generate_success_response {
             pub fn with_gas_used(mut self, new_gas_used: Option<u64>) -> Self {
                 match self {
                     $(
-                    [<$enum_name>]::$name(_, _, _, _, _, _, _, _, ref mut gas_used, _) => {
+                    [<$enum_name>]::$name(_, _, _, _, _, _, _, _, ref mut gas_used, _, _) => {
                         *gas_used = new_gas_used;
                     }
                     )*
                 }
                 self
             }
+
+            pub fn with_state_unproven(mut self, unproven: bool) -> Self {
+                match self {
+                    $(
+                    [<$enum_name>]::$name(_, _, _, _, _, _, _, _, _, _, ref mut state_unproven) => {
+                        *state_unproven = unproven.then_some(true);
+                    }
+                    )*
+                }
+                self
+            }
         }
         }
     };
@@ -285,7 +285,8 @@ impl StateApi {
                 BasicErrorWith404::bad_request_with_code_no_info(err, AptosErrorCode::InvalidInput)
             })?;
 
-        let (ledger_info, ledger_version, state_view) = self.context.state_view(ledger_version)?;
+        let (ledger_info, ledger_version, state_view) =
+            self.context.optional_proof_state_view(ledger_version)?;
         let bytes = state_view
             .as_converter(self.context.db.clone(), self.context.indexer_reader.clone())
             .find_resource(&state_view, address, &tag)
@@ -324,6 +325,7 @@ impl StateApi {
                 BasicResponseStatus::Ok,
             )),
         }
+        .map(|response| response.with_state_unproven(state_view.is_unproven()))
     }
 
     /// Retrieve the module
@@ -340,7 +342,7 @@ impl StateApi {
         let state_key = StateKey::module(address.inner(), &name);
         let (ledger_info, ledger_version, state_view) = self
             .context
-            .state_view(ledger_version.map(|inner| inner.0))?;
+            .optional_proof_state_view(ledger_version.map(|inner| inner.0))?;
         let bytes = state_view
             .get_state_value_bytes(&state_key)
             .context(format!("Failed to query DB to check for {:?}", state_key))
@@ -374,6 +376,7 @@ impl StateApi {
                 BasicResponseStatus::Ok,
             )),
         }
+        .map(|response| response.with_state_unproven(state_view.is_unproven()))
     }
 
     /// Retrieve table item for a specific ledger version
@@ -404,7 +407,7 @@ impl StateApi {
         // Retrieve local state
         let (ledger_info, ledger_version, state_view) = self
             .context
-            .state_view(ledger_version.map(|inner| inner.0))?;
+            .optional_proof_state_view(ledger_version.map(|inner| inner.0))?;
 
         let converter =
             state_view.as_converter(self.context.db.clone(), self.context.indexer_reader.clone());
@@ -467,6 +470,7 @@ impl StateApi {
                 BasicResponseStatus::Ok,
             )),
         }
+        .map(|response| response.with_state_unproven(state_view.is_unproven()))
     }
 
     /// Retrieve table item for a specific ledger version
@@ -480,7 +484,7 @@ impl StateApi {
         // Retrieve local state
         let (ledger_info, ledger_version, state_view) = self
             .context
-            .state_view(ledger_version.map(|inner| inner.0))?;
+            .optional_proof_state_view(ledger_version.map(|inner| inner.0))?;
 
         let state_key =
             StateKey::table_item(&TableHandle(table_handle.into()), &table_item_request.key.0);
@@ -520,6 +524,7 @@ impl StateApi {
                 BasicResponseStatus::Ok,
             )),
         }
+        .map(|response| response.with_state_unproven(state_view.is_unproven()))
     }
 
     /// Retrieve state value for a specific ledger version
@@ -532,7 +537,7 @@ impl StateApi {
         // Retrieve local state
         let (ledger_info, ledger_version, state_view) = self
             .context
-            .state_view(ledger_version.map(|inner| inner.0))?;
+            .optional_proof_state_view(ledger_version.map(|inner| inner.0))?;
 
         let state_key = bcs::from_bytes(&request.key.0)
             .context(format!(
@@ -589,5 +594,6 @@ impl StateApi {
                 BasicResponse::try_from_encoded((bytes, &ledger_info, BasicResponseStatus::Ok))
             },
         }
+        .map(|response| response.with_state_unproven(state_view.is_unproven()))
     }
 }
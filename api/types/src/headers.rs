@@ -20,5 +20,8 @@ pub const X_APTOS_CURSOR: &str = "X-Aptos-Cursor";
 /// The cost of the call in terms of gas. Only applicable to calls that result in
 /// function execution in the VM, e.g. view functions, txn simulation.
 pub const X_APTOS_GAS_USED: &str = "X-Aptos-Gas-Used";
+/// Set to true if the returned state was read without a proof, because the state Merkle tree at
+/// the requested version is already pruned.
+pub const X_APTOS_STATE_UNPROVEN: &str = "X-Aptos-State-Unproven";
 /// Provided by the client to identify what client it is.
 pub const X_APTOS_CLIENT: &str = "x-aptos-client";
@@ -5,9 +5,11 @@
 //! It also defines traits that enable composability of gas meters and algebra.
 
 mod algebra;
+mod instruction_counter;
 mod meter;
 mod traits;
 
 pub use algebra::StandardGasAlgebra;
+pub use instruction_counter::InstructionCountingGasMeter;
 pub use meter::StandardGasMeter;
 pub use traits::{AptosGasMeter, GasAlgebra};
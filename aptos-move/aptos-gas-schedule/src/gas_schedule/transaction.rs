@@ -6,7 +6,9 @@
 
 use crate::{
     gas_schedule::VMGasParameters,
-    ver::gas_feature_versions::{RELEASE_V1_11, RELEASE_V1_12, RELEASE_V1_13, RELEASE_V1_15},
+    ver::gas_feature_versions::{
+        RELEASE_V1_11, RELEASE_V1_12, RELEASE_V1_13, RELEASE_V1_15, RELEASE_V1_25,
+    },
 };
 use aptos_gas_algebra::{
     AbstractValueSize, Fee, FeePerByte, FeePerGasUnit, FeePerSlot, Gas, GasExpression,
@@ -268,6 +270,13 @@ crate::gas_schedule::macros::define_gas_parameters!(
             max_ty_depth: NumTypeNodes,
             { RELEASE_V1_15.. => "max_ty_depth" },
             20,
+        ],
+        // Execution gas charged instead of executing `0x1::aptos_account::transfer` in Move, when
+        // the transaction is executed by its native fast path.
+        [
+            native_fast_path_aptos_account_transfer: InternalGas,
+            { RELEASE_V1_25.. => "native_fast_path.aptos_account_transfer" },
+            3_000_000,
         ]
     ]
 );
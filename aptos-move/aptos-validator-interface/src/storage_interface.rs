@@ -33,6 +33,8 @@ impl DBDebuggerInterface {
                 BUFFERED_STATE_TARGET_ITEMS,
                 DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
                 None,
+                None,
+                None,
             )
             .map_err(anyhow::Error::from)?,
         )))
@@ -2,6 +2,8 @@
 // Parts of the project are originally copyright © Meta Platforms, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+#[cfg(feature = "native-fast-paths")]
+use crate::native_fast_paths::{self, NativeFastPath, NativeFastPathContext};
 use crate::{
     block_executor::{AptosTransactionOutput, AptosVMBlockExecutorWrapper},
     counters::*,
@@ -38,7 +40,7 @@ use aptos_framework::{
     RuntimeModuleMetadataV1,
 };
 use aptos_gas_algebra::{Gas, GasQuantity, NumBytes, Octa};
-use aptos_gas_meter::{AptosGasMeter, GasAlgebra};
+use aptos_gas_meter::{AptosGasMeter, GasAlgebra, InstructionCountingGasMeter};
 use aptos_gas_schedule::{AptosGasParameters, VMGasParameters};
 use aptos_logger::{enabled, prelude::*, Level};
 use aptos_metrics_core::TimerHelper;
@@ -67,7 +69,9 @@ use aptos_types::{
     randomness::Randomness,
     state_store::{state_key::StateKey, StateView, TStateView},
     transaction::{
-        authenticator::AnySignature, signature_verified_transaction::SignatureVerifiedTransaction,
+        authenticator::AnySignature,
+        resource_usage::{is_resource_accounting_enabled, record_resource_usage},
+        signature_verified_transaction::SignatureVerifiedTransaction,
         BlockOutput, EntryFunction, ExecutionError, ExecutionStatus, ModuleBundle, Multisig,
         MultisigTransactionPayload, Script, SignedTransaction, Transaction, TransactionArgument,
         TransactionOutput, TransactionPayload, TransactionStatus, VMValidatorResult,
@@ -938,6 +942,23 @@ impl AptosVM {
                 })?;
             },
             TransactionPayload::EntryFunction(entry_fn) => {
+                #[cfg(feature = "native-fast-paths")]
+                if let Some(fast_path) = self.native_fast_path(txn_data, entry_fn) {
+                    return self.execute_entry_function_with_native_fast_path(
+                        fast_path.as_ref(),
+                        resolver,
+                        code_storage,
+                        session,
+                        gas_meter,
+                        traversal_context,
+                        txn_data,
+                        entry_fn,
+                        log_context,
+                        new_published_modules_loaded,
+                        change_set_configs,
+                    );
+                }
+
                 session.execute(|session| {
                     self.validate_and_execute_entry_function(
                         resolver,
@@ -966,6 +987,29 @@ impl AptosVM {
             new_published_modules_loaded,
             change_set_configs,
         )?;
+        self.charge_user_session_change_set_and_cleanup(
+            user_session_change_set,
+            resolver,
+            code_storage,
+            gas_meter,
+            traversal_context,
+            txn_data,
+            log_context,
+            change_set_configs,
+        )
+    }
+
+    fn charge_user_session_change_set_and_cleanup(
+        &self,
+        user_session_change_set: UserSessionChangeSet,
+        resolver: &impl AptosMoveResolver,
+        code_storage: &impl AptosCodeStorage,
+        gas_meter: &mut impl AptosGasMeter,
+        traversal_context: &mut TraversalContext,
+        txn_data: &TransactionMetadata,
+        log_context: &AdapterLogSchema,
+        change_set_configs: &ChangeSetConfigs,
+    ) -> Result<(VMStatus, VMOutput), VMStatus> {
         let has_modules_published_to_special_address =
             user_session_change_set.has_modules_published_to_special_address();
 
@@ -989,6 +1033,111 @@ impl AptosVM {
         )
     }
 
+    /// Returns the native fast path of the entry function, if the transaction can be executed
+    /// natively.
+    #[cfg(feature = "native-fast-paths")]
+    fn native_fast_path(
+        &self,
+        txn_data: &TransactionMetadata,
+        entry_fn: &EntryFunction,
+    ) -> Option<Arc<dyn NativeFastPath>> {
+        // Native implementations read module metadata through the code storage of the new loader,
+        // and produce resource group changes in the split format.
+        if !self.features().is_native_fast_paths_enabled()
+            || txn_data.is_multi_agent()
+            || !self.features().is_loader_v2_enabled()
+            || !self
+                .features()
+                .is_resource_groups_split_in_vm_change_set_enabled()
+        {
+            return None;
+        }
+        native_fast_paths::get_native_fast_path(entry_fn)
+    }
+
+    /// Executes the entry function natively if its fast path supports the inputs, and in Move
+    /// otherwise. See [native_fast_paths] for details.
+    #[cfg(feature = "native-fast-paths")]
+    fn execute_entry_function_with_native_fast_path<'a, 'r, 'l>(
+        &'l self,
+        fast_path: &dyn NativeFastPath,
+        resolver: &'r impl AptosMoveResolver,
+        code_storage: &impl AptosCodeStorage,
+        mut session: UserSession<'r, 'l>,
+        gas_meter: &mut impl AptosGasMeter,
+        traversal_context: &mut TraversalContext<'a>,
+        txn_data: &TransactionMetadata,
+        entry_fn: &'a EntryFunction,
+        log_context: &AdapterLogSchema,
+        new_published_modules_loaded: &mut bool,
+        change_set_configs: &ChangeSetConfigs,
+    ) -> Result<(VMStatus, VMOutput), VMStatus> {
+        let native_execution = {
+            let native_resolver = self.as_move_resolver_with_group_view(session.executor_view());
+            let context = NativeFastPathContext {
+                resolver: &native_resolver,
+                module_storage: code_storage,
+                features: self.features(),
+                vm_gas_params: gas_meter.algebra().vm_gas_params(),
+                sender: txn_data.sender(),
+            };
+            fast_path.execute(&context, entry_fn)
+        };
+
+        if let Ok(Some(native_execution)) = native_execution {
+            native_execution
+                .charge_gas(gas_meter)
+                .map_err(|e| e.finish(Location::Undefined))?;
+            let user_session_change_set = session
+                .finish_with_native_change_set(native_execution.change_set, change_set_configs)?;
+            native_fast_paths::inc_counter(fast_path, "native");
+            return self.charge_user_session_change_set_and_cleanup(
+                user_session_change_set,
+                resolver,
+                code_storage,
+                gas_meter,
+                traversal_context,
+                txn_data,
+                log_context,
+                change_set_configs,
+            );
+        }
+
+        native_fast_paths::inc_counter(fast_path, "fallback");
+        session.execute(|session| {
+            self.validate_and_execute_entry_function(
+                resolver,
+                code_storage,
+                session,
+                gas_meter,
+                traversal_context,
+                txn_data.senders(),
+                entry_fn,
+                txn_data,
+            )
+        })?;
+        let user_session_change_set = self.resolve_pending_code_publish_and_finish_user_session(
+            session,
+            resolver,
+            code_storage,
+            gas_meter,
+            traversal_context,
+            new_published_modules_loaded,
+            change_set_configs,
+        )?;
+
+        self.charge_user_session_change_set_and_cleanup(
+            user_session_change_set,
+            resolver,
+            code_storage,
+            gas_meter,
+            traversal_context,
+            txn_data,
+            log_context,
+            change_set_configs,
+        )
+    }
+
     fn charge_change_set(
         &self,
         change_set: &mut impl ChangeSetInterface,
@@ -2116,14 +2265,35 @@ impl AptosVM {
         txn: &SignedTransaction,
         log_context: &AdapterLogSchema,
     ) -> (VMStatus, VMOutput) {
-        match self.execute_user_transaction_with_custom_gas_meter(
-            resolver,
-            code_storage,
-            txn,
-            log_context,
-            make_prod_gas_meter,
-        ) {
-            Ok((vm_status, vm_output, _gas_meter)) => (vm_status, vm_output),
+        let result = if is_resource_accounting_enabled() {
+            // Count executed instructions and native calls, which are not otherwise observable.
+            self.execute_user_transaction_with_modified_gas_meter(
+                resolver,
+                code_storage,
+                txn,
+                log_context,
+                InstructionCountingGasMeter::new,
+            )
+            .map(|(vm_status, vm_output, gas_meter)| {
+                record_resource_usage(|usage| {
+                    usage.num_instructions += gas_meter.num_instructions();
+                    usage.num_native_calls += gas_meter.num_native_calls();
+                });
+                (vm_status, vm_output)
+            })
+        } else {
+            self.execute_user_transaction_with_custom_gas_meter(
+                resolver,
+                code_storage,
+                txn,
+                log_context,
+                make_prod_gas_meter,
+            )
+            .map(|(vm_status, vm_output, _gas_meter)| (vm_status, vm_output))
+        };
+
+        match result {
+            Ok((vm_status, vm_output)) => (vm_status, vm_output),
             Err(vm_status) => {
                 let vm_output = discarded_output(vm_status.status_code());
                 (vm_status, vm_output)
@@ -28,6 +28,7 @@ use aptos_types::{
     fee_statement::FeeStatement,
     state_store::{state_key::StateKey, state_value::StateValueMetadata, StateView, StateViewId},
     transaction::{
+        resource_usage::TransactionResourceUsage,
         signature_verified_transaction::SignatureVerifiedTransaction, BlockOutput,
         TransactionOutput, TransactionStatus,
     },
@@ -69,13 +70,23 @@ pub struct AptosTransactionOutput {
     // Note: should these mutexes be changed to ExplicitSyncSwapper?
     vm_output: Mutex<Option<VMOutput>>,
     committed_output: OnceCell<TransactionOutput>,
+    // Set only if resource accounting is enabled, attached to the output when it is taken.
+    resource_usage: Option<TransactionResourceUsage>,
 }
 
 impl AptosTransactionOutput {
     pub fn new(output: VMOutput) -> Self {
+        Self::new_with_resource_usage(output, None)
+    }
+
+    pub(crate) fn new_with_resource_usage(
+        output: VMOutput,
+        resource_usage: Option<TransactionResourceUsage>,
+    ) -> Self {
         Self {
             vm_output: Mutex::new(Some(output)),
             committed_output: OnceCell::new(),
+            resource_usage,
         }
     }
 
@@ -84,7 +95,7 @@ impl AptosTransactionOutput {
     }
 
     fn take_output(mut self) -> TransactionOutput {
-        match self.committed_output.take() {
+        let mut output = match self.committed_output.take() {
             Some(output) => output,
             // TODO: revisit whether we should always get it via committed, or o.w. create a
             // dedicated API without creating empty data structures.
@@ -96,7 +107,13 @@ impl AptosTransactionOutput {
                 .expect("Output must be set")
                 .into_transaction_output()
                 .expect("Transaction output is not alerady materialized"),
+        };
+
+        if let Some(mut resource_usage) = self.resource_usage.take() {
+            resource_usage.set_bytes_written(&output);
+            output.set_resource_usage(resource_usage);
         }
+        output
     }
 }
 
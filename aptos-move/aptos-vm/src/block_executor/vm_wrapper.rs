@@ -9,7 +9,9 @@ use aptos_mvhashmap::types::TxnIndex;
 use aptos_types::{
     state_store::{StateView, StateViewId},
     transaction::{
-        signature_verified_transaction::SignatureVerifiedTransaction, Transaction, WriteSetPayload,
+        resource_usage::{finish_resource_accounting, start_resource_accounting},
+        signature_verified_transaction::SignatureVerifiedTransaction,
+        Transaction, WriteSetPayload,
     },
 };
 use aptos_vm_environment::environment::AptosEnvironment;
@@ -20,6 +22,7 @@ use aptos_vm_types::{
 };
 use fail::fail_point;
 use move_core_types::vm_status::{StatusCode, VMStatus};
+use std::time::Instant;
 
 pub struct AptosExecutorTask {
     vm: AptosVM,
@@ -52,10 +55,18 @@ impl ExecutorTask for AptosExecutorTask {
 
         let log_context = AdapterLogSchema::new(self.id, txn_idx as usize);
         let resolver = self.vm.as_move_resolver_with_group_view(view);
-        match self
+
+        start_resource_accounting();
+        let start_time = Instant::now();
+        let result = self
             .vm
-            .execute_single_transaction(txn, &resolver, view, &log_context)
-        {
+            .execute_single_transaction(txn, &resolver, view, &log_context);
+        let resource_usage = finish_resource_accounting().map(|mut usage| {
+            usage.execution_time = start_time.elapsed();
+            usage
+        });
+
+        match result {
             Ok((vm_status, vm_output)) => {
                 if vm_output.status().is_discarded() {
                     speculative_trace!(
@@ -78,13 +89,19 @@ impl ExecutorTask for AptosExecutorTask {
                         &log_context,
                         "Reconfiguration occurred: restart required".into()
                     );
-                    ExecutionStatus::SkipRest(AptosTransactionOutput::new(vm_output))
+                    ExecutionStatus::SkipRest(AptosTransactionOutput::new_with_resource_usage(
+                        vm_output,
+                        resource_usage,
+                    ))
                 } else {
                     assert!(
                         Self::is_transaction_dynamic_change_set_capable(txn),
                         "DirectWriteSet should always create SkipRest transaction, validate_waypoint_change_set provides this guarantee"
                     );
-                    ExecutionStatus::Success(AptosTransactionOutput::new(vm_output))
+                    ExecutionStatus::Success(AptosTransactionOutput::new_with_resource_usage(
+                        vm_output,
+                        resource_usage,
+                    ))
                 }
             },
             // execute_single_transaction only returns an error when transactions that should never fail
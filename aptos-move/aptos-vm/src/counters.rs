@@ -83,6 +83,17 @@ pub static SYSTEM_TRANSACTIONS_EXECUTED: Lazy<IntCounter> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Count the number of user transactions with a native fast path, with an "outcome" label to
+/// distinguish native execution from fallback to Move.
+pub static NATIVE_FAST_PATH_TRANSACTIONS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_vm_native_fast_path_transactions",
+        "Number of user transactions with a native fast path",
+        &["function", "outcome"]
+    )
+    .unwrap()
+});
+
 const NUM_BLOCK_TRANSACTIONS_BUCKETS: [f64; 24] = [
     5.0, 10.0, 20.0, 40.0, 75.0, 100.0, 200.0, 400.0, 800.0, 1200.0, 1800.0, 2500.0, 3300.0,
     4000.0, 5000.0, 6500.0, 8000.0, 10000.0, 12500.0, 15000.0, 18000.0, 21000.0, 25000.0, 30000.0,
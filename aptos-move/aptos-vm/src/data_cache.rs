@@ -23,6 +23,7 @@ use aptos_types::{
         state_value::{StateValue, StateValueMetadata},
         StateView, StateViewId,
     },
+    transaction::resource_usage::record_resource_usage,
 };
 use aptos_vm_environment::{
     gas::get_gas_feature_version, prod_configs::aptos_prod_deserializer_config,
@@ -128,6 +129,10 @@ impl<'e, E: ExecutorView> StorageAdapter<'e, E> {
             };
 
             let buf_size = resource_size(&buf);
+            record_resource_usage(|usage| {
+                usage.resource_group_reads += 1;
+                usage.bytes_read += (buf_size + group_size as usize) as u64;
+            });
             Ok((buf, buf_size + group_size as usize))
         } else {
             let state_key = resource_state_key(address, struct_tag)?;
@@ -135,6 +140,10 @@ impl<'e, E: ExecutorView> StorageAdapter<'e, E> {
                 .executor_view
                 .get_resource_bytes(&state_key, maybe_layout)?;
             let buf_size = resource_size(&buf);
+            record_resource_usage(|usage| {
+                usage.resource_reads += 1;
+                usage.bytes_read += buf_size as u64;
+            });
             Ok((buf, buf_size))
         }
     }
@@ -213,8 +222,14 @@ impl<'e, E: ExecutorView> TableResolver for StorageAdapter<'e, E> {
         maybe_layout: Option<&MoveTypeLayout>,
     ) -> Result<Option<Bytes>, PartialVMError> {
         let state_key = StateKey::table_item(&(*handle).into(), key);
-        self.executor_view
-            .get_resource_bytes(&state_key, maybe_layout)
+        let bytes = self
+            .executor_view
+            .get_resource_bytes(&state_key, maybe_layout)?;
+        record_resource_usage(|usage| {
+            usage.table_item_reads += 1;
+            usage.bytes_read += resource_size(&bytes) as u64;
+        });
+        Ok(bytes)
     }
 }
 
@@ -225,7 +240,12 @@ impl<'e, E: ExecutorView> TAggregatorV1View for StorageAdapter<'e, E> {
         &self,
         id: &Self::Identifier,
     ) -> PartialVMResult<Option<StateValue>> {
-        self.executor_view.get_aggregator_v1_state_value(id)
+        let state_value = self.executor_view.get_aggregator_v1_state_value(id)?;
+        record_resource_usage(|usage| {
+            usage.aggregator_reads += 1;
+            usage.bytes_read += state_value.as_ref().map_or(0, |v| v.size()) as u64;
+        });
+        Ok(state_value)
     }
 }
 
@@ -238,6 +258,7 @@ impl<'e, E: ExecutorView> TDelayedFieldView for StorageAdapter<'e, E> {
         &self,
         id: &Self::Identifier,
     ) -> Result<DelayedFieldValue, PanicOr<DelayedFieldsSpeculativeError>> {
+        record_resource_usage(|usage| usage.aggregator_reads += 1);
         self.executor_view.get_delayed_field_value(id)
     }
 
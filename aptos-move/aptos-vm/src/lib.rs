@@ -115,6 +115,8 @@ mod keyless_validation;
 #[cfg(feature = "testing")]
 pub mod keyless_validation;
 pub mod move_vm_ext;
+#[cfg(feature = "native-fast-paths")]
+pub mod native_fast_paths;
 pub mod natives;
 pub mod sharded_block_executor;
 pub mod system_module_names;
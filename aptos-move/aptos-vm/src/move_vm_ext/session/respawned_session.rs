@@ -69,6 +69,31 @@ impl<'r, 'l> RespawnedSession<'r, 'l> {
         })
     }
 
+    /// Returns the view of the state with the change set of the previous session applied.
+    #[cfg(feature = "native-fast-paths")]
+    pub fn executor_view(&self) -> &ExecutorViewWithChangeSet<'r> {
+        self.borrow_executor_view()
+    }
+
+    /// Returns the change set of the previous session squashed with the given one, as if the
+    /// latter was produced by this session (e.g., when the transaction is executed natively).
+    #[cfg(feature = "native-fast-paths")]
+    pub fn squash_with_previous_change_set(
+        &self,
+        additional_change_set: VMChangeSet,
+    ) -> Result<VMChangeSet, VMStatus> {
+        let mut change_set = self.borrow_executor_view().change_set.clone();
+        change_set
+            .squash_additional_change_set(additional_change_set)
+            .map_err(|_err| {
+                VMStatus::error(
+                    StatusCode::UNKNOWN_INVARIANT_VIOLATION_ERROR,
+                    err_msg("Failed to squash VMChangeSet"),
+                )
+            })?;
+        Ok(change_set)
+    }
+
     pub fn finish_with_squashed_change_set(
         mut self,
         change_set_configs: &ChangeSetConfigs,
@@ -70,6 +70,20 @@ impl<'r, 'l> UserSession<'r, 'l> {
         UserSessionChangeSet::new(change_set, module_write_set, change_set_configs)
     }
 
+    /// Finishes the session with the change set produced by a native execution of the
+    /// transaction, instead of the changes made in the session.
+    #[cfg(feature = "native-fast-paths")]
+    pub fn finish_with_native_change_set(
+        self,
+        native_change_set: VMChangeSet,
+        change_set_configs: &ChangeSetConfigs,
+    ) -> Result<UserSessionChangeSet, VMStatus> {
+        let change_set = self
+            .session
+            .squash_with_previous_change_set(native_change_set)?;
+        UserSessionChangeSet::new(change_set, ModuleWriteSet::empty(), change_set_configs)
+    }
+
     /// Finishes the session while also processing the publish request, and running module
     /// initialization if necessary. This function is used by the new loader and code cache.
     pub fn finish_with_module_publishing_and_initialization(
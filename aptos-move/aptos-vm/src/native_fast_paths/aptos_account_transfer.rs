@@ -0,0 +1,334 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    move_vm_ext::{
+        session::BytesWithResourceLayout, write_op_converter::WriteOpConverter, AptosMoveResolver,
+        ResourceGroupResolver,
+    },
+    native_fast_paths::{NativeExecution, NativeFastPath, NativeFastPathContext},
+};
+use aptos_gas_algebra::NumBytes;
+use aptos_types::{
+    account_config::{
+        primary_apt_store, AccountResource, DepositFAEvent, FungibleStoreResource,
+        ObjectCoreResource, ObjectGroupResource, WithdrawFAEvent,
+    },
+    move_utils::move_event_v2::MoveEventV2Type,
+    on_chain_config::{FeatureFlag, Features, OnChainConfig},
+    state_store::state_key::StateKey,
+    transaction::EntryFunction,
+};
+use aptos_vm_types::change_set::VMChangeSet;
+use bytes::Bytes;
+use move_binary_format::errors::{PartialVMError, PartialVMResult};
+use move_core_types::{
+    account_address::AccountAddress,
+    effects::Op as MoveStorageOp,
+    ident_str,
+    identifier::{IdentStr, Identifier},
+    language_storage::{ModuleId, StructTag},
+    metadata::Metadata,
+    move_resource::MoveStructType,
+    vm_status::StatusCode,
+};
+use move_vm_types::resolver::ResourceResolver;
+use std::collections::BTreeMap;
+
+/// Native implementation of `0x1::aptos_account::transfer`, when APT is stored in primary
+/// fungible stores.
+///
+/// Supports transfers of non-zero amounts between different existing accounts, which both have
+/// unfrozen primary APT stores with non-zero balances (so that concurrent balances are never
+/// accessed), where the sender directly owns its store and has enough funds, and APT has no
+/// dispatch functions.
+pub(crate) struct Transfer;
+
+impl NativeFastPath for Transfer {
+    fn name(&self) -> &'static str {
+        "aptos_account::transfer"
+    }
+
+    fn function(&self) -> (ModuleId, Identifier) {
+        (
+            ModuleId::new(AccountAddress::ONE, ident_str!("aptos_account").to_owned()),
+            ident_str!("transfer").to_owned(),
+        )
+    }
+
+    fn execute(
+        &self,
+        context: &NativeFastPathContext,
+        entry_fn: &EntryFunction,
+    ) -> PartialVMResult<Option<NativeExecution>> {
+        if !entry_fn.ty_args().is_empty()
+            || !context
+                .features
+                .is_enabled(FeatureFlag::OPERATIONS_DEFAULT_TO_FA_APT_STORE)
+        {
+            return Ok(None);
+        }
+        let (recipient, amount) = match entry_fn.args() {
+            [recipient, amount] => match (
+                bcs::from_bytes::<AccountAddress>(recipient),
+                bcs::from_bytes::<u64>(amount),
+            ) {
+                (Ok(recipient), Ok(amount)) => (recipient, amount),
+                _ => return Ok(None),
+            },
+            _ => return Ok(None),
+        };
+        let sender = context.sender;
+        if sender == recipient || amount == 0 {
+            return Ok(None);
+        }
+
+        let resolver = context.resolver;
+        let fetch_metadata = |name: &IdentStr| {
+            context
+                .module_storage
+                .fetch_existing_module_metadata(&AccountAddress::ONE, name)
+                .map_err(|e| e.to_partial())
+        };
+
+        // Read everything the Move implementation reads: the on-chain features, the recipient's
+        // account, both primary stores, the owner of the sender's store and the dispatch
+        // functions of APT.
+        let features_size = match resolver.get_resource_bytes_with_metadata_and_layout(
+            Features::address(),
+            &Features::struct_tag(),
+            &fetch_metadata(ident_str!("features"))?,
+            None,
+        )? {
+            (Some(bytes), _) => bytes.len() as u64,
+            (None, _) => return Ok(None),
+        };
+        let account_size = match resolver.get_resource_bytes_with_metadata_and_layout(
+            &recipient,
+            &AccountResource::struct_tag(),
+            &fetch_metadata(ident_str!("account"))?,
+            None,
+        )? {
+            (Some(bytes), _) => bytes.len() as u64,
+            (None, _) => return Ok(None),
+        };
+
+        let fungible_asset_metadata = fetch_metadata(ident_str!("fungible_asset"))?;
+        let Some(sender_store) = PrimaryStore::read(
+            resolver,
+            &fungible_asset_metadata,
+            primary_apt_store(sender),
+        )?
+        else {
+            return Ok(None);
+        };
+        let Some(recipient_store) = PrimaryStore::read(
+            resolver,
+            &fungible_asset_metadata,
+            primary_apt_store(recipient),
+        )?
+        else {
+            return Ok(None);
+        };
+
+        let Some(sender_store_object_size) =
+            sender_store.read_object(resolver, &fetch_metadata(ident_str!("object"))?, sender)?
+        else {
+            return Ok(None);
+        };
+        let (dispatch_functions, _) = resolver.get_resource_bytes_with_metadata_and_layout(
+            &AccountAddress::TEN,
+            &dispatch_function_store_struct_tag(),
+            &fungible_asset_metadata,
+            None,
+        )?;
+        if dispatch_functions.is_some() {
+            return Ok(None);
+        }
+        let apt_group_size = resolver
+            .resource_group_size(&PrimaryStore::group_state_key(&AccountAddress::TEN))?
+            .get();
+
+        if sender_store.resource.metadata != AccountAddress::TEN
+            || recipient_store.resource.metadata != AccountAddress::TEN
+            || sender_store.resource.frozen
+            || recipient_store.resource.frozen
+            || sender_store.resource.balance == 0
+            || sender_store.resource.balance < amount
+            || recipient_store.resource.balance == 0
+            || recipient_store
+                .resource
+                .balance
+                .checked_add(amount)
+                .is_none()
+        {
+            return Ok(None);
+        }
+
+        // The dispatch functions of APT do not exist.
+        let reads = [
+            Some(features_size),
+            Some(account_size),
+            Some(sender_store.size),
+            Some(sender_store.group_size),
+            Some(recipient_store.size),
+            Some(recipient_store.group_size),
+            Some(sender_store_object_size),
+            None,
+            Some(apt_group_size),
+        ]
+        .into_iter()
+        .map(|size| size.map(NumBytes::new))
+        .collect();
+
+        let events = vec![
+            (
+                WithdrawFAEvent {
+                    store: sender_store.address,
+                    amount,
+                }
+                .create_event_v2(),
+                None,
+            ),
+            (
+                DepositFAEvent {
+                    store: recipient_store.address,
+                    amount,
+                }
+                .create_event_v2(),
+                None,
+            ),
+        ];
+
+        let woc = WriteOpConverter::new(
+            resolver,
+            context.features.is_storage_slot_metadata_enabled(),
+        );
+        let sender_balance = sender_store.resource.balance - amount;
+        let recipient_balance = recipient_store.resource.balance + amount;
+        let mut resource_group_write_set = BTreeMap::new();
+        for (store, new_balance) in [
+            (sender_store, sender_balance),
+            (recipient_store, recipient_balance),
+        ] {
+            let (state_key, group_changes) = store.modify_balance(new_balance)?;
+            let group_write = woc.convert_resource_group_v1(&state_key, group_changes)?;
+            resource_group_write_set.insert(state_key, group_write);
+        }
+
+        let change_set = VMChangeSet::new_expanded(
+            BTreeMap::new(),
+            resource_group_write_set,
+            BTreeMap::new(),
+            BTreeMap::new(),
+            BTreeMap::new(),
+            BTreeMap::new(),
+            BTreeMap::new(),
+            events,
+        )?;
+        Ok(Some(NativeExecution {
+            execution_gas: context
+                .vm_gas_params
+                .txn
+                .native_fast_path_aptos_account_transfer,
+            reads,
+            change_set,
+        }))
+    }
+}
+
+fn dispatch_function_store_struct_tag() -> StructTag {
+    StructTag {
+        address: AccountAddress::ONE,
+        module: ident_str!("fungible_asset").to_owned(),
+        name: ident_str!("DispatchFunctionStore").to_owned(),
+        type_args: vec![],
+    }
+}
+
+/// Primary fungible store, stored in the object resource group at its address.
+struct PrimaryStore {
+    address: AccountAddress,
+    resource: FungibleStoreResource,
+    size: u64,
+    group_size: u64,
+}
+
+impl PrimaryStore {
+    fn read(
+        resolver: &dyn AptosMoveResolver,
+        fungible_asset_metadata: &[Metadata],
+        address: AccountAddress,
+    ) -> PartialVMResult<Option<Self>> {
+        let (bytes, _) = resolver.get_resource_bytes_with_metadata_and_layout(
+            &address,
+            &FungibleStoreResource::struct_tag(),
+            fungible_asset_metadata,
+            None,
+        )?;
+        let Some(bytes) = bytes else {
+            return Ok(None);
+        };
+        let Ok(resource) = bcs::from_bytes(&bytes) else {
+            return Ok(None);
+        };
+        let group_size = resolver
+            .resource_group_size(&Self::group_state_key(&address))?
+            .get();
+        Ok(Some(Self {
+            address,
+            resource,
+            size: bytes.len() as u64,
+            group_size,
+        }))
+    }
+
+    /// Reads the object of the store, as `object::owns` does. Returns the size of the object if
+    /// the store is directly owned by `owner`, and [None] otherwise.
+    fn read_object(
+        &self,
+        resolver: &dyn AptosMoveResolver,
+        object_metadata: &[Metadata],
+        owner: AccountAddress,
+    ) -> PartialVMResult<Option<u64>> {
+        let (bytes, _) = resolver.get_resource_bytes_with_metadata_and_layout(
+            &self.address,
+            &ObjectCoreResource::struct_tag(),
+            object_metadata,
+            None,
+        )?;
+        let Some(bytes) = bytes else {
+            return Ok(None);
+        };
+        match bcs::from_bytes::<ObjectCoreResource>(&bytes) {
+            Ok(object) if object.owner == owner => Ok(Some(bytes.len() as u64)),
+            _ => Ok(None),
+        }
+    }
+
+    fn group_state_key(address: &AccountAddress) -> StateKey {
+        StateKey::resource_group(address, &ObjectGroupResource::struct_tag())
+    }
+
+    fn modify_balance(
+        &self,
+        new_balance: u64,
+    ) -> PartialVMResult<(
+        StateKey,
+        BTreeMap<StructTag, MoveStorageOp<BytesWithResourceLayout>>,
+    )> {
+        let resource = FungibleStoreResource {
+            balance: new_balance,
+            ..self.resource.clone()
+        };
+        let bytes = bcs::to_bytes(&resource).map_err(|_| {
+            PartialVMError::new(StatusCode::VALUE_SERIALIZATION_ERROR)
+                .with_message("Failed to serialize FungibleStore".to_string())
+        })?;
+        let group_changes = BTreeMap::from([(
+            FungibleStoreResource::struct_tag(),
+            MoveStorageOp::Modify((Bytes::from(bytes), None)),
+        )]);
+        Ok((Self::group_state_key(&self.address), group_changes))
+    }
+}
@@ -0,0 +1,130 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Native implementations of common framework entry functions.
+//!
+//! A native fast path replaces the execution of an entry function in Move with Rust code that
+//! produces the same change set. Fast paths are only compiled with the `native-fast-paths`
+//! feature, and only used when the `NATIVE_FAST_PATHS` on-chain feature is enabled, so that
+//! whether a transaction is executed natively only depends on the state it is executed against,
+//! and is the same on all nodes.
+//!
+//! Native execution is part of the protocol: instead of the gas the Move implementation would
+//! charge, a fast path charges IO gas for every value it reads, priced as reads in Move are, and
+//! the execution gas defined for it in the gas schedule. Native implementations replicate the code
+//! of framework modules, so the feature must be disabled before any of these modules is upgraded,
+//! and only enabled again once the native implementations match the new code.
+
+mod aptos_account_transfer;
+
+use crate::{counters::NATIVE_FAST_PATH_TRANSACTIONS, move_vm_ext::AptosMoveResolver};
+use aptos_gas_algebra::{InternalGas, NumBytes};
+use aptos_gas_meter::{AptosGasMeter, GasAlgebra};
+use aptos_gas_schedule::VMGasParameters;
+use aptos_infallible::RwLock;
+use aptos_types::{on_chain_config::Features, transaction::EntryFunction};
+use aptos_vm_types::change_set::VMChangeSet;
+use move_binary_format::errors::PartialVMResult;
+use move_core_types::{
+    account_address::AccountAddress, identifier::Identifier, language_storage::ModuleId,
+};
+use move_vm_runtime::ModuleStorage;
+use once_cell::sync::Lazy;
+use std::{collections::HashMap, sync::Arc};
+
+static NATIVE_FAST_PATHS: Lazy<RwLock<HashMap<(ModuleId, Identifier), Arc<dyn NativeFastPath>>>> =
+    Lazy::new(|| {
+        let mut fast_paths = HashMap::new();
+        let transfer: Arc<dyn NativeFastPath> = Arc::new(aptos_account_transfer::Transfer);
+        fast_paths.insert(transfer.function(), transfer);
+        RwLock::new(fast_paths)
+    });
+
+/// Registers a native fast path, replacing the existing one for the same entry function. Only
+/// entry functions of modules at special addresses can have fast paths.
+pub fn register_native_fast_path(fast_path: Arc<dyn NativeFastPath>) {
+    let (module_id, function_name) = fast_path.function();
+    assert!(
+        module_id.address().is_special(),
+        "Native fast paths are only supported for framework entry functions, got {}::{}",
+        module_id,
+        function_name,
+    );
+    NATIVE_FAST_PATHS
+        .write()
+        .insert((module_id, function_name), fast_path);
+}
+
+/// Returns the native fast path registered for the entry function, if any.
+pub fn get_native_fast_path(entry_fn: &EntryFunction) -> Option<Arc<dyn NativeFastPath>> {
+    NATIVE_FAST_PATHS
+        .read()
+        .get(&(entry_fn.module().clone(), entry_fn.function().to_owned()))
+        .cloned()
+}
+
+/// State the native implementation of an entry function is executed against.
+pub struct NativeFastPathContext<'a> {
+    /// Resolver over the state after the prologue. Native implementations must read everything
+    /// the Move implementation reads through it, so that the reads are tracked by the block
+    /// executor.
+    pub resolver: &'a dyn AptosMoveResolver,
+    pub module_storage: &'a dyn ModuleStorage,
+    pub features: &'a Features,
+    pub vm_gas_params: &'a VMGasParameters,
+    /// The only signer of the transaction.
+    pub sender: AccountAddress,
+}
+
+/// Result of a native execution of an entry function.
+pub struct NativeExecution {
+    /// Execution gas charged instead of executing the entry function in Move.
+    pub execution_gas: InternalGas,
+    /// Sizes of the values read by the native implementation, or [None] for values which do not
+    /// exist, charged as IO gas.
+    pub reads: Vec<Option<NumBytes>>,
+    /// Changes made by the entry function, not including the changes made by the prologue.
+    pub change_set: VMChangeSet,
+}
+
+impl NativeExecution {
+    /// Charges the gas of the native execution. Fails, as the execution in Move would, if the
+    /// transaction runs out of gas or reaches the execution or IO limits.
+    pub(crate) fn charge_gas(&self, gas_meter: &mut impl AptosGasMeter) -> PartialVMResult<()> {
+        gas_meter
+            .algebra_mut()
+            .charge_execution(self.execution_gas)?;
+        for bytes_loaded in &self.reads {
+            let cost = gas_meter.algebra().io_pricing().calculate_read_gas(
+                bytes_loaded.is_some(),
+                bytes_loaded.unwrap_or_else(|| 0.into()),
+            );
+            gas_meter.algebra_mut().charge_io(cost)?;
+        }
+        Ok(())
+    }
+}
+
+/// Native implementation of a framework entry function.
+pub trait NativeFastPath: Send + Sync {
+    /// Name of the fast path, used in metrics.
+    fn name(&self) -> &'static str;
+
+    /// Returns the entry function this fast path implements.
+    fn function(&self) -> (ModuleId, Identifier);
+
+    /// Executes the entry function natively. Returns [None] if the inputs are not supported by
+    /// the native implementation (e.g., the execution in Move aborts or takes a branch which is
+    /// not implemented), in which case the transaction is executed in Move.
+    fn execute(
+        &self,
+        context: &NativeFastPathContext,
+        entry_fn: &EntryFunction,
+    ) -> PartialVMResult<Option<NativeExecution>>;
+}
+
+pub(crate) fn inc_counter(fast_path: &dyn NativeFastPath, outcome: &str) {
+    NATIVE_FAST_PATH_TRANSACTIONS
+        .with_label_values(&[fast_path.name(), outcome])
+        .inc();
+}
@@ -42,6 +42,11 @@ impl CrossShardCommitReceiver {
                     trace!("Cross shard commit receiver stopped for round {}", round);
                     break;
                 },
+                // Execution fails on the reads of the missing values, and then stops the receiver.
+                CrossShardMsg::AbortMsg => {
+                    trace!("Cross shard commit receiver aborted for round {}", round);
+                    cross_shard_state_view.abort();
+                },
             }
         }
     }
@@ -55,6 +55,13 @@ impl<'a, S: StateView + Sync + Send> CrossShardStateView<'a, S> {
         // trace!("waiting count for shard id {} is {}", self.shard_id, self.waiting_count());
     }
 
+    /// Stops waiting for the values that have not been received yet, so that reads of them fail.
+    pub fn abort(&self) {
+        for value in self.cross_shard_data.values() {
+            value.abort();
+        }
+    }
+
     pub fn create_cross_shard_state_view(
         base_view: &'a S,
         transactions: &[TransactionWithDependencies<AnalyzedTransaction>],
@@ -76,7 +83,7 @@ impl<'a, S: StateView + Sync + Send> TStateView for CrossShardStateView<'a, S> {
 
     fn get_state_value(&self, state_key: &StateKey) -> Result<Option<StateValue>, StateViewError> {
         if let Some(value) = self.cross_shard_data.get(state_key) {
-            return Ok(value.get_value());
+            return value.get_value();
         }
         self.base_view.get_state_value(state_key)
     }
@@ -138,4 +145,33 @@ mod tests {
 
         wait_thread.join().unwrap();
     }
+
+    #[test]
+    fn test_cross_shard_state_view_abort() {
+        let received_key = StateKey::raw(b"key1");
+        let missing_key = StateKey::raw(b"key2");
+        let state_value = StateValue::from("value1".as_bytes().to_owned());
+        let state_keys = HashSet::from([received_key.clone(), missing_key.clone()]);
+
+        let cross_shard_state_view = Arc::new(CrossShardStateView::new(state_keys, &EmptyView));
+        cross_shard_state_view.set_value(&received_key, Some(state_value.clone()));
+        let cross_shard_state_view_clone = cross_shard_state_view.clone();
+        let wait_thread = thread::spawn(move || {
+            assert!(cross_shard_state_view_clone
+                .get_state_value(&missing_key)
+                .is_err());
+        });
+
+        thread::sleep(Duration::from_millis(100));
+        cross_shard_state_view.abort();
+        wait_thread.join().unwrap();
+
+        // Values received before the abort can still be read.
+        assert_eq!(
+            cross_shard_state_view
+                .get_state_value(&received_key)
+                .unwrap(),
+            Some(state_value)
+        );
+    }
 }
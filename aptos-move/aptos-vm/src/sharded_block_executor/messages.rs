@@ -8,6 +8,9 @@ use serde::{Deserialize, Serialize};
 pub enum CrossShardMsg {
     RemoteTxnWriteMsg(RemoteTxnWrite),
     StopMsg,
+    /// Sent when the block is aborted, e.g., because a shard it depends on failed: the cross-shard
+    /// values that have not been received yet never will be.
+    AbortMsg,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -1,7 +1,7 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use aptos_types::state_store::state_value::StateValue;
+use aptos_types::state_store::{errors::StateViewError, state_value::StateValue};
 use std::sync::{Arc, Condvar, Mutex};
 
 #[derive(Clone)]
@@ -26,14 +26,28 @@ impl RemoteStateValue {
         cvar.notify_all();
     }
 
-    pub fn get_value(&self) -> Option<StateValue> {
+    /// Stops waiting for the value, e.g., because the block it is read by was aborted. Reads of
+    /// the value fail, unless it was already set.
+    pub fn abort(&self) {
+        let (lock, cvar) = &*self.value_condition;
+        let mut status = lock.lock().unwrap();
+        if let RemoteValueStatus::Waiting = *status {
+            *status = RemoteValueStatus::Aborted;
+            cvar.notify_all();
+        }
+    }
+
+    pub fn get_value(&self) -> Result<Option<StateValue>, StateViewError> {
         let (lock, cvar) = &*self.value_condition;
         let mut status = lock.lock().unwrap();
         while let RemoteValueStatus::Waiting = *status {
             status = cvar.wait(status).unwrap();
         }
         match &*status {
-            RemoteValueStatus::Ready(value) => value.clone(),
+            RemoteValueStatus::Ready(value) => Ok(value.clone()),
+            RemoteValueStatus::Aborted => Err(StateViewError::Other(
+                "Remote state value will not be received, the block was aborted".to_string(),
+            )),
             RemoteValueStatus::Waiting => unreachable!(),
         }
     }
@@ -51,4 +65,6 @@ pub enum RemoteValueStatus {
     Ready(Option<StateValue>),
     /// We are still waiting for remote shard to push the state value
     Waiting,
+    /// The state value will never be pushed, because the block was aborted
+    Aborted,
 }
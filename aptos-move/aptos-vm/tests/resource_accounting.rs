@@ -0,0 +1,63 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+#![forbid(unsafe_code)]
+
+/// Resource accounting is enabled process-wide, so it is tested in its own binary, to not affect
+/// the transactions executed by other tests.
+use aptos_language_e2e_tests::{common_transactions::peer_to_peer_txn, executor::FakeExecutor};
+use aptos_types::transaction::{
+    resource_usage::set_resource_accounting_enabled, ExecutionStatus, TransactionStatus,
+};
+
+const NUM_ACCOUNTS: usize = 10;
+const NUM_TXNS: usize = 30;
+
+#[test]
+fn test_resource_accounting_does_not_change_outputs() {
+    let mut executor = FakeExecutor::from_head_genesis().set_parallel();
+    let accounts: Vec<_> = (0..NUM_ACCOUNTS)
+        .map(|_| {
+            let account = executor.create_raw_account_data(1_000_000_000, 0);
+            executor.add_account_data(&account);
+            account
+        })
+        .collect();
+    let txns: Vec<_> = (0..NUM_TXNS)
+        .map(|idx| {
+            peer_to_peer_txn(
+                accounts[idx % NUM_ACCOUNTS].account(),
+                accounts[(idx + 1) % NUM_ACCOUNTS].account(),
+                (idx / NUM_ACCOUNTS) as u64,
+                1_000,
+                100,
+            )
+        })
+        .collect();
+
+    let outputs = executor.execute_block(txns.clone()).unwrap();
+    set_resource_accounting_enabled(true);
+    let accounted_outputs = executor.execute_block(txns);
+    set_resource_accounting_enabled(false);
+    let accounted_outputs = accounted_outputs.unwrap();
+
+    // Resource usage is not part of the output equality.
+    assert_eq!(outputs, accounted_outputs);
+    for (output, accounted_output) in outputs.iter().zip(&accounted_outputs).take(NUM_TXNS) {
+        assert_eq!(
+            output.status(),
+            &TransactionStatus::Keep(ExecutionStatus::Success)
+        );
+        assert_eq!(output.gas_used(), accounted_output.gas_used());
+        assert!(output.resource_usage().is_none());
+
+        let usage = accounted_output
+            .resource_usage()
+            .expect("Resource usage must be set when accounting is enabled");
+        assert!(usage.num_instructions > 0);
+        assert!(usage.num_native_calls > 0);
+        assert!(usage.num_state_reads() > 0);
+        assert!(usage.bytes_read > 0);
+        assert!(usage.bytes_written > 0);
+    }
+}
@@ -2,7 +2,9 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    code_cache_global::GlobalModuleCache, types::InputOutputKey,
+    code_cache_global::GlobalModuleCache,
+    conflict_analytics::{ConflictKey, ReadConflict},
+    types::InputOutputKey,
     value_exchange::filter_value_for_exchange,
 };
 use anyhow::bail;
@@ -38,6 +40,7 @@ use std::{
         },
         BTreeMap, HashMap, HashSet,
     },
+    fmt::Debug,
     hash::Hash,
     ops::Deref,
     sync::Arc,
@@ -760,6 +763,173 @@ where
         Ok(true)
     }
 
+    /// Returns the data, group and module reads that do not validate anymore, together with the
+    /// transaction that wrote what is observed instead. Unlike validation, does not stop at the
+    /// first invalid read: only used to explain validation failures for conflict analytics.
+    pub(crate) fn find_conflicts(
+        &self,
+        data_map: &VersionedData<T::Key, T::Value>,
+        group_map: &VersionedGroupData<T::Key, T::Tag, T::Value>,
+        module_caches: Option<(
+            &GlobalModuleCache<K, DC, VC, S>,
+            &SyncModuleCache<K, DC, VC, S, Option<TxnIndex>>,
+        )>,
+        idx_to_validate: TxnIndex,
+    ) -> Vec<ReadConflict>
+    where
+        K: Debug,
+    {
+        let mut conflicts = vec![];
+        if self.non_delayed_field_speculative_failure {
+            // The failure is not attributable to a particular read.
+            return conflicts;
+        }
+
+        let writer_of = |version: &Version| version.as_ref().ok().map(|(idx, _)| *idx);
+
+        for (k, r) in &self.data_reads {
+            let (current, writer) = match data_map.fetch_data(k, idx_to_validate) {
+                Ok(MVDataOutput::Versioned(version, v)) => {
+                    let writer = writer_of(&version);
+                    (Some(DataRead::from_value_with_layout(version, v)), writer)
+                },
+                Ok(MVDataOutput::Resolved(value)) => (Some(DataRead::Resolved(value)), None),
+                Err(MVDataError::Dependency(idx)) => (None, Some(idx)),
+                Err(_) => (None, None),
+            };
+            if current
+                .is_some_and(|current| matches!(current.contains(r), DataReadComparison::Contains))
+            {
+                continue;
+            }
+            conflicts.push(ReadConflict {
+                key: ConflictKey::Resource(format!("{:?}", k)),
+                writer,
+            });
+        }
+
+        for (key, group) in &self.group_reads {
+            if let Some(size) = group.collected_size {
+                if !group_map.validate_group_size(key, idx_to_validate, size) {
+                    conflicts.push(ReadConflict {
+                        key: ConflictKey::Group(format!("{:?}", key)),
+                        writer: None,
+                    });
+                }
+            }
+
+            for (tag, r) in &group.inner_reads {
+                let (current, writer) = match group_map.fetch_tagged_data(key, tag, idx_to_validate)
+                {
+                    Ok((version, v)) => {
+                        let writer = writer_of(&version);
+                        (Some(DataRead::from_value_with_layout(version, v)), writer)
+                    },
+                    Err(MVGroupError::TagNotFound) => {
+                        let sentinel_deletion =
+                            Arc::<T::Value>::new(TransactionWrite::from_state_value(None));
+                        (
+                            Some(DataRead::Versioned(
+                                Err(StorageVersion),
+                                sentinel_deletion,
+                                None,
+                            )),
+                            None,
+                        )
+                    },
+                    Err(MVGroupError::Dependency(idx)) => (None, Some(idx)),
+                    Err(MVGroupError::Uninitialized) => continue,
+                };
+                if current.is_some_and(|current| {
+                    matches!(current.contains(r), DataReadComparison::Contains)
+                }) {
+                    continue;
+                }
+                conflicts.push(ReadConflict {
+                    key: ConflictKey::Group(format!("{:?}/{:?}", key, tag)),
+                    writer,
+                });
+            }
+        }
+
+        if let Some((global_module_cache, per_block_module_cache)) = module_caches {
+            for (key, read) in &self.module_reads {
+                let writer = match read {
+                    ModuleRead::GlobalCache(_) => {
+                        if global_module_cache.contains_not_overridden(key) {
+                            continue;
+                        }
+                        None
+                    },
+                    ModuleRead::PerBlockCache(previous) => {
+                        let current_version = per_block_module_cache.get_module_version(key);
+                        if current_version == previous.as_ref().map(|(_, version)| *version) {
+                            continue;
+                        }
+                        current_version.flatten()
+                    },
+                };
+                conflicts.push(ReadConflict {
+                    key: ConflictKey::Module(format!("{:?}", key)),
+                    writer,
+                });
+            }
+        }
+
+        conflicts
+    }
+
+    /// Returns the delayed field reads that do not validate anymore, with the transaction that
+    /// caused it when known. Like [CapturedReads::find_conflicts], only used for conflict
+    /// analytics.
+    pub(crate) fn find_delayed_field_conflicts(
+        &self,
+        delayed_fields: &dyn TVersionedDelayedFieldView<T::Identifier>,
+        idx_to_validate: TxnIndex,
+    ) -> Result<Vec<ReadConflict>, PanicError> {
+        use MVDelayedFieldsError::*;
+
+        let mut conflicts = vec![];
+        if self.delayed_field_speculative_failure {
+            return Ok(conflicts);
+        }
+
+        for (id, read_value) in &self.delayed_field_reads {
+            let writer = match delayed_fields.read_latest_predicted_value(
+                id,
+                idx_to_validate,
+                ReadPosition::BeforeCurrentTxn,
+            ) {
+                Ok(current_value) => {
+                    let valid = match read_value {
+                        DelayedFieldRead::Value { value, .. } => value == &current_value,
+                        DelayedFieldRead::HistoryBounded {
+                            restriction,
+                            max_value,
+                            ..
+                        } => restriction
+                            .validate_against_base_value(
+                                current_value.into_aggregator_value()?,
+                                *max_value,
+                            )
+                            .is_ok(),
+                    };
+                    if valid {
+                        continue;
+                    }
+                    None
+                },
+                Err(Dependency(idx)) => Some(idx),
+                Err(NotFound) | Err(DeltaApplicationFailure) => None,
+            };
+            conflicts.push(ReadConflict {
+                key: ConflictKey::DelayedField(format!("{:?}", id)),
+                writer,
+            });
+        }
+        Ok(conflicts)
+    }
+
     pub(crate) fn mark_failure(&mut self, delayed_field_failure: bool) {
         if delayed_field_failure {
             self.delayed_field_speculative_failure = true;
@@ -11,7 +11,9 @@ use aptos_mvhashmap::types::TxnIndex;
 use aptos_types::{
     executable::{Executable, ModulePath},
     state_store::{state_value::StateValueMetadata, TStateView},
-    transaction::BlockExecutableTransaction as Transaction,
+    transaction::{
+        resource_usage::record_resource_usage, BlockExecutableTransaction as Transaction,
+    },
     vm::modules::AptosModuleExtension,
 };
 use aptos_vm_types::module_and_script_storage::module_storage::AptosModuleStorage;
@@ -54,6 +56,10 @@ impl<'a, T: Transaction, S: TStateView<Key = T::Key>, X: Executable> ModuleCodeB
         self.get_raw_base_value(&key)
             .map_err(|err| err.finish(Location::Undefined))?
             .map(|state_value| {
+                record_resource_usage(|usage| {
+                    usage.module_reads += 1;
+                    usage.bytes_read += state_value.size() as u64;
+                });
                 let extension = Arc::new(AptosModuleExtension::new(state_value));
                 let compiled_module = self
                     .runtime_environment()
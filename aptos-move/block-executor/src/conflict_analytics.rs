@@ -0,0 +1,392 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Opt-in analytics explaining why a block is slow to execute in parallel: for every transaction,
+//! how many incarnations it took and how many of them were aborted, and for every abort, which of
+//! the keys the incarnation read were invalidated, and by which transaction.
+//!
+//! When enabled with [set_conflict_analytics_enabled], every block executed by Block-STM produces
+//! a [BlockConflictReport], which are buffered until taken with [take_conflict_reports]. At most
+//! [MAX_BUFFERED_CONFLICT_REPORTS] reports are buffered, older reports are dropped first. The
+//! invalidated keys are only looked up after a validation has already failed, so there is no cost
+//! on the validation path, but the attribution is best-effort: the multi-versioned data-structure
+//! may have changed between the failed validation and the lookup.
+
+use aptos_mvhashmap::types::{Incarnation, TxnIndex};
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::{
+    collections::{BTreeMap, HashMap, VecDeque},
+    fmt, fs,
+    path::Path,
+    sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering},
+};
+
+static CONFLICT_ANALYTICS_ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Maximum number of reports kept in memory, so that leaving analytics enabled for a long time
+/// does not grow memory usage without bound.
+pub const MAX_BUFFERED_CONFLICT_REPORTS: usize = 1_000;
+
+static CONFLICT_REPORTS: Lazy<Mutex<VecDeque<BlockConflictReport>>> =
+    Lazy::new(|| Mutex::new(VecDeque::new()));
+
+static NUM_DROPPED_CONFLICT_REPORTS: AtomicU64 = AtomicU64::new(0);
+
+/// Enables or disables conflict analytics for blocks executed from now on. While enabled, reports
+/// accumulate in memory until taken with [take_conflict_reports], or until the buffer is full.
+pub fn set_conflict_analytics_enabled(enabled: bool) {
+    CONFLICT_ANALYTICS_ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+pub fn is_conflict_analytics_enabled() -> bool {
+    CONFLICT_ANALYTICS_ENABLED.load(Ordering::Relaxed)
+}
+
+/// Returns the reports of blocks executed in parallel since the last call, in the order their
+/// execution finished. If more than [MAX_BUFFERED_CONFLICT_REPORTS] blocks were executed, only the
+/// latest reports are returned, see [take_num_dropped_conflict_reports].
+pub fn take_conflict_reports() -> Vec<BlockConflictReport> {
+    std::mem::take(&mut *CONFLICT_REPORTS.lock()).into()
+}
+
+/// Returns the number of reports dropped because the buffer was full since the last call.
+pub fn take_num_dropped_conflict_reports() -> u64 {
+    NUM_DROPPED_CONFLICT_REPORTS.swap(0, Ordering::Relaxed)
+}
+
+/// A read that caused an incarnation to be aborted.
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+#[serde(tag = "kind", content = "key", rename_all = "snake_case")]
+pub enum ConflictKey {
+    Resource(String),
+    /// A resource in a resource group (the group key and the tag), or the size of the group (the
+    /// group key only).
+    Group(String),
+    DelayedField(String),
+    Module(String),
+    /// The abort could not be attributed to a read: there was a speculative failure during
+    /// execution, or the invalidated reads became valid again before they were looked up.
+    Unattributed,
+}
+
+impl fmt::Display for ConflictKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConflictKey::Resource(key) => write!(f, "resource {}", key),
+            ConflictKey::Group(key) => write!(f, "group {}", key),
+            ConflictKey::DelayedField(key) => write!(f, "delayed field {}", key),
+            ConflictKey::Module(key) => write!(f, "module {}", key),
+            ConflictKey::Unattributed => write!(f, "unattributed"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Conflict {
+    /// Transaction whose incarnation was aborted.
+    pub txn_idx: TxnIndex,
+    pub incarnation: Incarnation,
+    pub key: ConflictKey,
+    /// Transaction that wrote the value observed instead of the one read, if any (None if, e.g.,
+    /// the value now comes from storage).
+    pub writer: Option<TxnIndex>,
+    /// True if the conflict was detected when committing the transaction, which is where delayed
+    /// field reads are validated.
+    pub at_commit: bool,
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct TxnConflictStats {
+    pub incarnations: u32,
+    pub validation_failures: u32,
+    pub commit_failures: u32,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct BlockConflictReport {
+    pub num_workers: usize,
+    /// Stats for every transaction in the block, by index.
+    pub txns: Vec<TxnConflictStats>,
+    pub conflicts: Vec<Conflict>,
+}
+
+impl BlockConflictReport {
+    pub fn num_txns(&self) -> usize {
+        self.txns.len()
+    }
+
+    pub fn num_aborts(&self) -> u64 {
+        self.txns
+            .iter()
+            .map(|txn| (txn.validation_failures + txn.commit_failures) as u64)
+            .sum()
+    }
+
+    /// Returns up to `limit` keys causing the most conflicts in the block, most conflicting first.
+    pub fn hot_keys(&self, limit: usize) -> Vec<(ConflictKey, usize)> {
+        hot_keys(std::iter::once(self), limit)
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Renders the conflict graph in DOT format. There is an edge from a writer to every
+    /// transaction it caused to abort, labelled with the conflicting keys, and every transaction
+    /// that was executed more than once is labelled with its number of incarnations.
+    pub fn to_dot(&self) -> String {
+        let mut edges: BTreeMap<(Option<TxnIndex>, TxnIndex), BTreeMap<&ConflictKey, usize>> =
+            BTreeMap::new();
+        for conflict in &self.conflicts {
+            *edges
+                .entry((conflict.writer, conflict.txn_idx))
+                .or_default()
+                .entry(&conflict.key)
+                .or_default() += 1;
+        }
+
+        let mut dot = String::from("digraph conflicts {\n    node [shape=box];\n");
+        for (idx, txn) in self.txns.iter().enumerate() {
+            if txn.incarnations > 1 {
+                dot.push_str(&format!(
+                    "    t{} [label=\"{} ({} incarnations)\"];\n",
+                    idx, idx, txn.incarnations
+                ));
+            }
+        }
+        if edges.keys().any(|(writer, _)| writer.is_none()) {
+            dot.push_str("    unknown [label=\"unknown writer\", shape=ellipse];\n");
+        }
+        for ((writer, reader), keys) in edges {
+            let writer = writer.map_or_else(|| "unknown".to_string(), |idx| format!("t{}", idx));
+            let label = keys
+                .into_iter()
+                .map(|(key, count)| format!("{} x{}", key, count))
+                .collect::<Vec<_>>()
+                .join("\\n");
+            dot.push_str(&format!(
+                "    {} -> t{} [label=\"{}\"];\n",
+                writer,
+                reader,
+                label.replace('"', "\\\"")
+            ));
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+/// Returns up to `limit` keys causing the most conflicts across all reports, most conflicting
+/// first.
+pub fn hot_keys<'a>(
+    reports: impl IntoIterator<Item = &'a BlockConflictReport>,
+    limit: usize,
+) -> Vec<(ConflictKey, usize)> {
+    let mut counts: HashMap<&ConflictKey, usize> = HashMap::new();
+    for report in reports {
+        for conflict in &report.conflicts {
+            *counts.entry(&conflict.key).or_default() += 1;
+        }
+    }
+
+    let mut hot_keys: Vec<_> = counts
+        .into_iter()
+        .map(|(key, count)| (key.clone(), count))
+        .collect();
+    hot_keys.sort_by(|(key1, count1), (key2, count2)| count2.cmp(count1).then(key1.cmp(key2)));
+    hot_keys.truncate(limit);
+    hot_keys
+}
+
+/// Writes `block_<i>.json` and `block_<i>.dot` for every report, and `hot_keys.json` with the
+/// `num_hot_keys` keys causing the most conflicts across all reports, to the given directory.
+pub fn export_conflict_reports(
+    reports: &[BlockConflictReport],
+    dir: &Path,
+    num_hot_keys: usize,
+) -> anyhow::Result<()> {
+    fs::create_dir_all(dir)?;
+    for (idx, report) in reports.iter().enumerate() {
+        fs::write(dir.join(format!("block_{}.json", idx)), report.to_json()?)?;
+        fs::write(dir.join(format!("block_{}.dot", idx)), report.to_dot())?;
+    }
+
+    #[derive(Serialize)]
+    struct HotKey {
+        key: ConflictKey,
+        conflicts: usize,
+    }
+    let hot_keys: Vec<_> = hot_keys(reports, num_hot_keys)
+        .into_iter()
+        .map(|(key, conflicts)| HotKey { key, conflicts })
+        .collect();
+    fs::write(
+        dir.join("hot_keys.json"),
+        serde_json::to_string_pretty(&hot_keys)?,
+    )?;
+    Ok(())
+}
+
+/// A read that failed validation, and the transaction whose write is observed now instead.
+pub(crate) struct ReadConflict {
+    pub(crate) key: ConflictKey,
+    pub(crate) writer: Option<TxnIndex>,
+}
+
+/// Collects the conflicts of a block during its parallel execution.
+pub(crate) struct ConflictRecorder {
+    txns: Vec<(AtomicU32, AtomicU32, AtomicU32)>,
+    conflicts: Mutex<Vec<Conflict>>,
+}
+
+impl ConflictRecorder {
+    fn new(num_txns: u32) -> Self {
+        Self {
+            txns: (0..num_txns)
+                .map(|_| (AtomicU32::new(0), AtomicU32::new(0), AtomicU32::new(0)))
+                .collect(),
+            conflicts: Mutex::new(vec![]),
+        }
+    }
+
+    /// Returns a recorder if conflict analytics are enabled.
+    pub(crate) fn new_if_enabled(num_txns: u32) -> Option<Self> {
+        is_conflict_analytics_enabled().then(|| Self::new(num_txns))
+    }
+
+    pub(crate) fn record_execution(&self, txn_idx: TxnIndex, incarnation: Incarnation) {
+        self.txns[txn_idx as usize]
+            .0
+            .fetch_max(incarnation + 1, Ordering::Relaxed);
+    }
+
+    /// Records that an incarnation was aborted, because of the given reads.
+    pub(crate) fn record_abort(
+        &self,
+        txn_idx: TxnIndex,
+        incarnation: Incarnation,
+        at_commit: bool,
+        read_conflicts: Vec<ReadConflict>,
+    ) {
+        let (_, validation_failures, commit_failures) = &self.txns[txn_idx as usize];
+        if at_commit {
+            commit_failures.fetch_add(1, Ordering::Relaxed);
+        } else {
+            validation_failures.fetch_add(1, Ordering::Relaxed);
+        }
+
+        let read_conflicts = if read_conflicts.is_empty() {
+            vec![ReadConflict {
+                key: ConflictKey::Unattributed,
+                writer: None,
+            }]
+        } else {
+            read_conflicts
+        };
+        self.conflicts
+            .lock()
+            .extend(read_conflicts.into_iter().map(|read_conflict| Conflict {
+                txn_idx,
+                incarnation,
+                key: read_conflict.key,
+                writer: read_conflict.writer,
+                at_commit,
+            }));
+    }
+
+    fn into_report(self, num_workers: usize) -> BlockConflictReport {
+        let txns = self
+            .txns
+            .into_iter()
+            .map(
+                |(incarnations, validation_failures, commit_failures)| TxnConflictStats {
+                    incarnations: incarnations.into_inner(),
+                    validation_failures: validation_failures.into_inner(),
+                    commit_failures: commit_failures.into_inner(),
+                },
+            )
+            .collect();
+        let mut conflicts = self.conflicts.into_inner();
+        conflicts.sort_by_key(|conflict| (conflict.txn_idx, conflict.incarnation));
+
+        BlockConflictReport {
+            num_workers,
+            txns,
+            conflicts,
+        }
+    }
+
+    /// Adds the report of the block to the ones to be taken, dropping the oldest report if the
+    /// buffer is full.
+    pub(crate) fn publish(self, num_workers: usize) {
+        let report = self.into_report(num_workers);
+        let mut reports = CONFLICT_REPORTS.lock();
+        if reports.len() >= MAX_BUFFERED_CONFLICT_REPORTS {
+            reports.pop_front();
+            NUM_DROPPED_CONFLICT_REPORTS.fetch_add(1, Ordering::Relaxed);
+        }
+        reports.push_back(report);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resource(key: &str) -> ConflictKey {
+        ConflictKey::Resource(key.to_string())
+    }
+
+    fn report() -> BlockConflictReport {
+        let recorder = ConflictRecorder::new(4);
+        for idx in 0..4 {
+            recorder.record_execution(idx, 0);
+        }
+        recorder.record_abort(2, 0, false, vec![
+            ReadConflict {
+                key: resource("A"),
+                writer: Some(0),
+            },
+            ReadConflict {
+                key: resource("B"),
+                writer: Some(1),
+            },
+        ]);
+        recorder.record_execution(2, 1);
+        recorder.record_abort(3, 0, true, vec![ReadConflict {
+            key: resource("A"),
+            writer: Some(0),
+        }]);
+        recorder.record_execution(3, 1);
+        recorder.record_abort(3, 1, false, vec![]);
+        recorder.record_execution(3, 2);
+
+        recorder.into_report(2)
+    }
+
+    #[test]
+    fn test_report_stats_and_hot_keys() {
+        let report = report();
+        let incarnations: Vec<_> = report.txns.iter().map(|txn| txn.incarnations).collect();
+        assert_eq!(incarnations, vec![1, 1, 2, 3]);
+        assert_eq!(report.num_aborts(), 3);
+        assert_eq!(report.txns[3].commit_failures, 1);
+        assert_eq!(report.hot_keys(2), vec![
+            (resource("A"), 2),
+            (resource("B"), 1)
+        ]);
+        assert_eq!(hot_keys([&report, &report], 1), vec![(resource("A"), 4)]);
+    }
+
+    #[test]
+    fn test_report_to_dot() {
+        let dot = report().to_dot();
+        assert!(dot.contains("t3 [label=\"3 (3 incarnations)\"];"));
+        assert!(dot.contains("t0 -> t2 [label=\"resource A x1\"];"));
+        assert!(dot.contains("t0 -> t3 [label=\"resource A x1\"];"));
+        assert!(dot.contains("unknown -> t3 [label=\"unattributed x1\"];"));
+        assert!(!dot.contains("t1 [label"));
+    }
+}
@@ -5,6 +5,7 @@
 use crate::{
     code_cache_global::GlobalModuleCache,
     code_cache_global_manager::AptosModuleCacheManagerGuard,
+    conflict_analytics::{ConflictRecorder, ReadConflict},
     counters::{
         self, BLOCK_EXECUTOR_INNER_EXECUTE_BLOCK, PARALLEL_EXECUTION_SECONDS,
         RAYON_EXECUTION_SECONDS, TASK_EXECUTE_SECONDS, TASK_VALIDATE_SECONDS, VM_INIT_SECONDS,
@@ -433,6 +434,33 @@ where
                     .validate_module_reads(global_module_cache, versioned_cache.module_cache()))
     }
 
+    /// Returns the reads of the transaction that made its validation fail, for conflict analytics.
+    /// Must be called before the transaction is re-executed, i.e. while its read-set is recorded.
+    fn find_read_conflicts(
+        txn_idx: TxnIndex,
+        last_input_output: &TxnLastInputOutput<T, E::Output, E::Error>,
+        global_module_cache: &GlobalModuleCache<
+            ModuleId,
+            CompiledModule,
+            Module,
+            AptosModuleExtension,
+        >,
+        versioned_cache: &MVHashMap<T::Key, T::Tag, T::Value, X, T::Identifier>,
+        scheduler: &Scheduler,
+    ) -> Vec<ReadConflict> {
+        let read_set = last_input_output
+            .read_set(txn_idx)
+            .expect("[BlockSTM]: Prior read-set must be recorded");
+        let module_caches = (!scheduler.skip_module_reads_validation())
+            .then(|| (global_module_cache, versioned_cache.module_cache()));
+        read_set.find_conflicts(
+            versioned_cache.data(),
+            versioned_cache.group_data(),
+            module_caches,
+            txn_idx,
+        )
+    }
+
     fn update_transaction_on_abort(
         txn_idx: TxnIndex,
         last_input_output: &TxnLastInputOutput<T, E::Output, E::Error>,
@@ -490,13 +518,30 @@ where
         valid: bool,
         validation_wave: Wave,
         last_input_output: &TxnLastInputOutput<T, E::Output, E::Error>,
+        global_module_cache: &GlobalModuleCache<
+            ModuleId,
+            CompiledModule,
+            Module,
+            AptosModuleExtension,
+        >,
         versioned_cache: &MVHashMap<T::Key, T::Tag, T::Value, X, T::Identifier>,
         scheduler: &Scheduler,
         runtime_environment: &RuntimeEnvironment,
+        conflict_recorder: Option<&ConflictRecorder>,
     ) -> Result<SchedulerTask, PanicError> {
         let aborted = !valid && scheduler.try_abort(txn_idx, incarnation);
 
         if aborted {
+            if let Some(conflict_recorder) = conflict_recorder {
+                let read_conflicts = Self::find_read_conflicts(
+                    txn_idx,
+                    last_input_output,
+                    global_module_cache,
+                    versioned_cache,
+                    scheduler,
+                );
+                conflict_recorder.record_abort(txn_idx, incarnation, false, read_conflicts);
+            }
             Self::update_transaction_on_abort(
                 txn_idx,
                 last_input_output,
@@ -580,6 +625,7 @@ where
         executor: &E,
         block: &TP,
         num_workers: usize,
+        conflict_recorder: Option<&ConflictRecorder>,
     ) -> Result<(), PanicOr<ParallelBlockExecutionError>> {
         let mut block_limit_processor = shared_commit_state.acquire();
 
@@ -591,6 +637,14 @@ where
                 last_input_output,
             )? {
                 // Transaction needs to be re-executed, one final time.
+                if let Some(conflict_recorder) = conflict_recorder {
+                    let read_conflicts = last_input_output
+                        .read_set(txn_idx)
+                        .expect("Read set must be recorded")
+                        .find_delayed_field_conflicts(versioned_cache.delayed_fields(), txn_idx)?;
+                    conflict_recorder.record_abort(txn_idx, incarnation, true, read_conflicts);
+                    conflict_recorder.record_execution(txn_idx, incarnation + 1);
+                }
 
                 Self::update_transaction_on_abort(
                     txn_idx,
@@ -968,6 +1022,7 @@ where
         shared_commit_state: &ExplicitSyncWrapper<BlockGasLimitProcessor<T>>,
         final_results: &ExplicitSyncWrapper<Vec<E::Output>>,
         num_workers: usize,
+        conflict_recorder: Option<&ConflictRecorder>,
     ) -> Result<(), PanicOr<ParallelBlockExecutionError>> {
         // Make executor for each task. TODO: fast concurrent executor.
         let num_txns = block.num_txns();
@@ -1026,6 +1081,7 @@ where
                     &executor,
                     block,
                     num_workers,
+                    conflict_recorder,
                 )?;
                 scheduler.queueing_commits_mark_done();
             }
@@ -1047,9 +1103,11 @@ where
                         valid,
                         wave,
                         last_input_output,
+                        global_module_cache,
                         versioned_cache,
                         scheduler,
                         runtime_environment,
+                        conflict_recorder,
                     )?
                 },
                 SchedulerTask::ExecutionTask(
@@ -1057,6 +1115,9 @@ where
                     incarnation,
                     ExecutionTaskType::Execution,
                 ) => {
+                    if let Some(conflict_recorder) = conflict_recorder {
+                        conflict_recorder.record_execution(txn_idx, incarnation);
+                    }
                     let needs_suffix_validation = Self::execute(
                         txn_idx,
                         incarnation,
@@ -1143,6 +1204,7 @@ where
 
         let last_input_output = TxnLastInputOutput::new(num_txns);
         let scheduler = Scheduler::new(num_txns);
+        let conflict_recorder = ConflictRecorder::new_if_enabled(num_txns);
 
         let timer = RAYON_EXECUTION_SECONDS.start_timer();
         self.executor_thread_pool.scope(|s| {
@@ -1161,6 +1223,7 @@ where
                         &shared_commit_state,
                         &final_results,
                         num_workers,
+                        conflict_recorder.as_ref(),
                     ) {
                         // If there are multiple errors, they all get logged:
                         // ModulePathReadWriteError and FatalVMError variant is logged at construction,
@@ -1187,6 +1250,13 @@ where
             shared_maybe_error.store(true, Ordering::Relaxed);
         }
 
+        // Blocks that fall back to sequential execution are not reported.
+        if let Some(conflict_recorder) = conflict_recorder {
+            if !shared_maybe_error.load(Ordering::SeqCst) {
+                conflict_recorder.publish(num_workers);
+            }
+        }
+
         counters::update_state_counters(versioned_cache.stats(), true);
         module_cache_manager_guard
             .module_cache_mut()
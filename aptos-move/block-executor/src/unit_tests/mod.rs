@@ -6,6 +6,7 @@ mod code_cache_tests;
 
 use crate::{
     code_cache_global_manager::AptosModuleCacheManagerGuard,
+    conflict_analytics::{set_conflict_analytics_enabled, take_conflict_reports, ConflictKey},
     errors::SequentialBlockExecutionError,
     executor::BlockExecutor,
     proptest_types::{
@@ -43,7 +44,7 @@ use std::{
     fmt::Debug,
     hash::Hash,
     marker::PhantomData,
-    sync::Arc,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
 };
 
 #[test]
@@ -491,6 +492,104 @@ fn cycle_transactions() {
     run_and_assert(transactions)
 }
 
+/// Serializes the tests enabling the process-wide conflict analytics.
+static CONFLICT_ANALYTICS_LOCK: Mutex<()> = Mutex::new(());
+
+/// Keeps the conflict analytics enabled until dropped (also when the test panics), while holding
+/// [CONFLICT_ANALYTICS_LOCK].
+struct ConflictAnalyticsGuard {
+    _lock: MutexGuard<'static, ()>,
+}
+
+impl ConflictAnalyticsGuard {
+    fn enable() -> Self {
+        let lock = CONFLICT_ANALYTICS_LOCK
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        set_conflict_analytics_enabled(true);
+        // Drop the reports left over by a previous test.
+        take_conflict_reports();
+        Self { _lock: lock }
+    }
+}
+
+impl Drop for ConflictAnalyticsGuard {
+    fn drop(&mut self) {
+        set_conflict_analytics_enabled(false);
+    }
+}
+
+#[test]
+fn conflict_analytics_records_conflicts() {
+    const NUM_TXNS: usize = 100;
+
+    // Every transaction reads and writes the same key, so that speculative reads of the key get
+    // invalidated. Other tests may run blocks concurrently, so only our blocks are considered.
+    let key = KeyType(random::<[u8; 32]>(), false);
+    let transactions: Vec<_> = (0..NUM_TXNS)
+        .map(|_| {
+            MockTransaction::from_behavior(MockIncarnation::<KeyType<[u8; 32]>, MockEvent>::new(
+                vec![key],                        // reads
+                vec![(key, random_value(false))], // writes
+                vec![],
+                vec![],
+                1, // gas
+            ))
+        })
+        .collect();
+    let txn_provider = DefaultTxnProvider::new(transactions);
+    let data_view = DeltaDataView::<KeyType<[u8; 32]>> {
+        phantom: PhantomData,
+    };
+    let executor_thread_pool = Arc::new(
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(4)
+            .build()
+            .unwrap(),
+    );
+    let block_executor = BlockExecutor::<
+        MockTransaction<KeyType<[u8; 32]>, MockEvent>,
+        MockTask<KeyType<[u8; 32]>, MockEvent>,
+        DeltaDataView<KeyType<[u8; 32]>>,
+        NoOpTransactionCommitHook<MockOutput<KeyType<[u8; 32]>, MockEvent>, usize>,
+        ExecutableTestType,
+        _,
+    >::new(
+        BlockExecutorConfig::new_no_block_limit(4),
+        executor_thread_pool,
+        None,
+    );
+
+    // Whether an incarnation is aborted depends on the interleaving, so re-execute the block until
+    // a conflict is recorded.
+    let analytics_guard = ConflictAnalyticsGuard::enable();
+    let expected_key = ConflictKey::Resource(format!("{:?}", key));
+    let mut conflict = None;
+    for _ in 0..100 {
+        let mut guard = AptosModuleCacheManagerGuard::none();
+        assert_ok!(block_executor.execute_transactions_parallel(
+            &txn_provider,
+            &data_view,
+            &mut guard
+        ));
+        conflict = take_conflict_reports()
+            .into_iter()
+            .filter(|report| report.num_txns() == NUM_TXNS)
+            .flat_map(|report| report.conflicts)
+            .find(|conflict| conflict.key == expected_key);
+        if conflict.is_some() {
+            break;
+        }
+    }
+    drop(analytics_guard);
+
+    let conflict = conflict.expect("Block should have a conflict on the shared key");
+    assert!(!conflict.at_commit);
+    if let Some(writer) = conflict.writer {
+        assert!(writer < conflict.txn_idx);
+    }
+}
+
 const NUM_BLOCKS: u64 = 10;
 const TXN_PER_BLOCK: u64 = 100;
 
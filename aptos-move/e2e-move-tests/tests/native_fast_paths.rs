@@ -0,0 +1,164 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Differential tests for native fast paths: every block is executed with the `NATIVE_FAST_PATHS`
+//! feature disabled and enabled, and the outputs must be identical, except for the gas charged.
+//!
+//! Fast paths are only compiled with the `native-fast-paths` feature of this crate, so the tests
+//! are run with `cargo test -p e2e-move-tests --features native-fast-paths`.
+
+use aptos_cached_packages::aptos_stdlib::aptos_account_transfer;
+use aptos_language_e2e_tests::{
+    account::Account,
+    executor::{ExecutorMode, FakeExecutor},
+};
+use aptos_types::{
+    account_address::AccountAddress,
+    account_config::{primary_apt_store, FungibleStoreResource, ObjectGroupResource},
+    contract_event::ContractEvent,
+    fee_statement::FeeStatement,
+    on_chain_config::FeatureFlag,
+    state_store::state_key::StateKey,
+    transaction::{SignedTransaction, TransactionOutput},
+};
+use aptos_vm::counters::NATIVE_FAST_PATH_TRANSACTIONS;
+use e2e_move_tests::MoveHarness;
+use move_core_types::{language_storage::TypeTag, move_resource::MoveStructType};
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+
+const NUM_ACCOUNTS: usize = 10;
+const NUM_BLOCKS: usize = 5;
+const BLOCK_SIZE: usize = 30;
+// Number of accounts whose primary stores are frozen in each block.
+const NUM_FROZEN_ACCOUNTS: usize = 2;
+
+fn new_harness(mode: ExecutorMode) -> MoveHarness {
+    let mut h =
+        MoveHarness::new_with_executor(FakeExecutor::from_head_genesis().set_executor_mode(mode));
+    h.enable_features(
+        vec![
+            FeatureFlag::NEW_ACCOUNTS_DEFAULT_TO_FA_APT_STORE,
+            FeatureFlag::OPERATIONS_DEFAULT_TO_FA_APT_STORE,
+        ],
+        vec![FeatureFlag::DEFAULT_TO_CONCURRENT_FUNGIBLE_BALANCE],
+    );
+    // Native executions charge different gas than Move, so transactions are free for balances to
+    // be the same.
+    h.set_default_gas_unit_price(0);
+    h
+}
+
+/// Generates transfers between the accounts, including transfers to themselves, to new accounts,
+/// of zero amounts and of amounts exceeding the balance, so that both the native and the Move
+/// implementations are exercised.
+fn generate_transfers(
+    h: &mut MoveHarness,
+    rng: &mut StdRng,
+    accounts: &[Account],
+) -> Vec<SignedTransaction> {
+    (0..BLOCK_SIZE)
+        .map(|_| {
+            let sender = &accounts[rng.gen_range(0, accounts.len())];
+            let recipient = match rng.gen_range(0, 10) {
+                0 => *sender.address(),
+                1 => AccountAddress::random(),
+                _ => *accounts[rng.gen_range(0, accounts.len())].address(),
+            };
+            let amount = match rng.gen_range(0, 10) {
+                0 => 0,
+                1 => u64::MAX,
+                _ => rng.gen_range(1, 1_000_000),
+            };
+            h.create_transaction_payload(sender, aptos_account_transfer(recipient, amount))
+        })
+        .collect()
+}
+
+/// Freezes or unfreezes the primary APT store of the account. Only the holder of the transfer ref
+/// of APT can do so in Move, so the store is modified directly.
+fn set_store_frozen(h: &mut MoveHarness, account: &Account, frozen: bool) {
+    let store = primary_apt_store(*account.address());
+    let mut group = h
+        .read_resource_group(&store, ObjectGroupResource::struct_tag())
+        .expect("Primary store must exist");
+    let resource = group
+        .get_mut(&FungibleStoreResource::struct_tag())
+        .expect("Primary store must exist");
+    let mut fungible_store: FungibleStoreResource = bcs::from_bytes(resource).unwrap();
+    fungible_store.frozen = frozen;
+    *resource = bcs::to_bytes(&fungible_store).unwrap();
+    h.executor.write_state_value(
+        StateKey::resource_group(&store, &ObjectGroupResource::struct_tag()),
+        bcs::to_bytes(&group).unwrap(),
+    );
+}
+
+/// Events emitted by the transaction, except for the fee statement.
+fn events_without_fee_statement(output: &TransactionOutput) -> Vec<&ContractEvent> {
+    let fee_statement = TypeTag::Struct(Box::new(FeeStatement::struct_tag()));
+    output
+        .events()
+        .iter()
+        .filter(|event| event.type_tag() != &fee_statement)
+        .collect()
+}
+
+fn native_transfers() -> u64 {
+    NATIVE_FAST_PATH_TRANSACTIONS
+        .with_label_values(&["aptos_account::transfer", "native"])
+        .get()
+}
+
+#[test]
+fn test_aptos_account_transfer_matches_move() {
+    for mode in [ExecutorMode::SequentialOnly, ExecutorMode::ParallelOnly] {
+        let mut h = new_harness(mode);
+        let mut rng = StdRng::seed_from_u64(50);
+        let accounts: Vec<_> = (0..NUM_ACCOUNTS)
+            .map(|_| h.new_account_with_balance_and_sequence_number(10_000_000_000, 0))
+            .collect();
+        // Create primary stores for all accounts, so that transfers between them can be executed
+        // natively.
+        let funding = accounts
+            .iter()
+            .map(|account| {
+                h.create_transaction_payload(
+                    account,
+                    aptos_account_transfer(*accounts[0].address(), 1),
+                )
+            })
+            .collect();
+        h.run_block_get_output(funding);
+
+        let native_transfers_before = native_transfers();
+        for _ in 0..NUM_BLOCKS {
+            // Transfers from and to frozen stores abort in Move, and are not executed natively.
+            let frozen_accounts: Vec<_> = accounts
+                .choose_multiple(&mut rng, NUM_FROZEN_ACCOUNTS)
+                .collect();
+            for account in &frozen_accounts {
+                set_store_frozen(&mut h, account, true);
+            }
+            let txns = generate_transfers(&mut h, &mut rng, &accounts);
+
+            let expected = h.executor.execute_block(txns.clone()).unwrap();
+            h.enable_features(vec![FeatureFlag::NATIVE_FAST_PATHS], vec![]);
+            let outputs = h.run_block_get_output(txns);
+            h.enable_features(vec![], vec![FeatureFlag::NATIVE_FAST_PATHS]);
+
+            assert_eq!(outputs.len(), expected.len());
+            for (output, expected) in outputs.iter().zip(&expected) {
+                assert_eq!(output.status(), expected.status());
+                assert_eq!(output.write_set(), expected.write_set());
+                assert_eq!(
+                    events_without_fee_statement(output),
+                    events_without_fee_statement(expected)
+                );
+            }
+            for account in &frozen_accounts {
+                set_store_frozen(&mut h, account, false);
+            }
+        }
+        assert!(native_transfers() > native_transfers_before);
+    }
+}
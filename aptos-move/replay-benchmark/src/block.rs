@@ -13,10 +13,14 @@ use aptos_types::{
         BlockExecutorModuleCacheLocalConfig,
     },
     state_store::{state_key::StateKey, state_value::StateValue, StateView},
-    transaction::{TransactionOutput, Version},
+    transaction::{
+        signature_verified_transaction::SignatureVerifiedTransaction, Transaction,
+        TransactionOutput, Version,
+    },
 };
 use aptos_vm::{aptos_vm::AptosVMBlockExecutor, VMBlockExecutor};
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, fs, path::Path};
 
 /// Block execution config used for replay benchmarking.
 fn block_execution_config(concurrency_level: usize) -> BlockExecutorConfig {
@@ -35,13 +39,19 @@ fn block_execution_config(concurrency_level: usize) -> BlockExecutorConfig {
 /// Represents a single benchmarking unit: a block of transactions with the input pre-block state.
 /// Also stores the comparison of outputs based on the input state to on-chain outputs (recall that
 /// input state may contain an override and differ from on-chain pre-block state).
+///
+/// Blocks are self-contained, and can be saved to a file to be benchmarked later without access
+/// to the network or the DB they were generated from.
+#[derive(Deserialize, Serialize)]
 pub struct Block {
     /// Stores all data needed to execute this block.
     inputs: ReadSet,
     /// Stores transactions to execute and benchmark.
     workload: Workload,
-    /// Stores diff results for each transaction output. The number of diffs is always equal to the
-    /// number of transactions, but they may or may not be empty.
+    /// Stores diff results for each transaction output. The number of diffs is equal to the number
+    /// of transactions, but they may or may not be empty. Diffs are not saved, so blocks loaded
+    /// from a file have none.
+    #[serde(skip)]
     diffs: Vec<TransactionDiff>,
 }
 
@@ -111,6 +121,34 @@ impl Block {
         }
     }
 
+    /// Creates a new block from transactions executed on top of the specified state, without any
+    /// overrides. Used to save blocks generated by other tools, e.g., blocks with transactions
+    /// that execute differently depending on the executor.
+    pub fn from_transactions(
+        begin: Version,
+        txns: Vec<Transaction>,
+        state_view: &(impl StateView + Sync),
+    ) -> Self {
+        Self::new(Workload::new(begin, txns), state_view, HashMap::new())
+    }
+
+    /// Returns the first version, the signature verified transactions and the input state of this
+    /// block, so that the block can be executed outside of this crate.
+    pub fn into_parts(
+        self,
+    ) -> (
+        Version,
+        Vec<SignatureVerifiedTransaction>,
+        impl StateView + Send + Sync + 'static,
+    ) {
+        let begin = self
+            .workload
+            .transaction_slice_metadata()
+            .begin_version()
+            .expect("Transaction metadata is a chunk");
+        (begin, self.workload.into_txns(), self.inputs)
+    }
+
     /// Prints the difference in transaction outputs when running with overrides.
     pub fn print_diffs(&self) {
         let begin = self
@@ -125,12 +163,27 @@ impl Block {
         }
     }
 
-    /// Executes the workload for benchmarking.
-    pub(crate) fn run(&self, executor: &AptosVMBlockExecutor, concurrency_level: usize) {
-        execute_workload(executor, &self.workload, &self.inputs, concurrency_level);
+    /// Executes the workload for benchmarking, returning the outputs.
+    pub(crate) fn run(
+        &self,
+        executor: &AptosVMBlockExecutor,
+        concurrency_level: usize,
+    ) -> Vec<TransactionOutput> {
+        execute_workload(executor, &self.workload, &self.inputs, concurrency_level)
     }
 }
 
+/// Saves blocks to a file, so that they can be loaded with [load_blocks].
+pub fn save_blocks(blocks: &[Block], path: &Path) -> anyhow::Result<()> {
+    fs::write(path, bcs::to_bytes(blocks)?)?;
+    Ok(())
+}
+
+/// Loads blocks saved with [save_blocks]. Any state overrides are part of the saved input states.
+pub fn load_blocks(path: &Path) -> anyhow::Result<Vec<Block>> {
+    Ok(bcs::from_bytes(&fs::read(path)?)?)
+}
+
 fn execute_workload(
     executor: &AptosVMBlockExecutor,
     workload: &Workload,
@@ -152,3 +205,66 @@ fn execute_workload(
         })
         .into_transaction_outputs_forced()
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use aptos_crypto::HashValue;
+    use aptos_temppath::TempPath;
+    use aptos_types::state_store::{MockStateView, TStateView};
+
+    #[test]
+    fn test_save_and_load_blocks() {
+        let state_key = StateKey::raw(b"key");
+        let state_value = StateValue::new_legacy(vec![1, 2, 3].into());
+        let inputs = ReadSetCapturingStateView::new(
+            &MockStateView::empty(),
+            HashMap::from([(state_key.clone(), state_value.clone())]),
+        )
+        .into_read_set();
+
+        let txns = vec![
+            Transaction::StateCheckpoint(HashValue::zero()),
+            Transaction::StateCheckpoint(HashValue::random()),
+        ];
+        let block = Block {
+            inputs,
+            workload: Workload::new(10, txns.clone()),
+            diffs: vec![],
+        };
+
+        let path = TempPath::new();
+        save_blocks(&[block], path.path()).unwrap();
+        let mut blocks = load_blocks(path.path()).unwrap();
+        assert_eq!(blocks.len(), 1);
+
+        let block = blocks.pop().unwrap();
+        assert!(block.diffs.is_empty());
+        let (begin, loaded_txns, loaded_inputs) = block.into_parts();
+        assert_eq!(begin, 10);
+        assert_eq!(
+            loaded_txns
+                .into_iter()
+                .map(|txn| txn.into_inner())
+                .collect::<Vec<_>>(),
+            txns
+        );
+        assert_eq!(
+            loaded_inputs.get_state_value(&state_key).unwrap(),
+            Some(state_value)
+        );
+        assert_eq!(
+            loaded_inputs
+                .get_state_value(&StateKey::raw(b"missing"))
+                .unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_load_empty_workload_fails() {
+        let bytes =
+            bcs::to_bytes(&(10 as Version, Vec::<SignatureVerifiedTransaction>::new())).unwrap();
+        assert!(bcs::from_bytes::<Workload>(&bytes).is_err());
+    }
+}
@@ -4,15 +4,15 @@
 use aptos_types::{
     contract_event::ContractEvent,
     state_store::state_key::StateKey,
-    transaction::{ExecutionStatus, TransactionOutput},
-    write_set::{WriteOp, WriteSet},
+    transaction::{TransactionOutput, TransactionStatus},
+    write_set::{TransactionWrite, WriteOp, WriteSet},
 };
 use claims::assert_ok;
 use std::collections::BTreeMap;
 
 /// Different parts of [TransactionOutput] that can be different:
 ///   1. gas used,
-///   2. status (must be kept since transactions are replayed),
+///   2. status (must be kept when transactions are replayed),
 ///   3. events,
 ///   4. writes.
 /// Note that fine-grained comparison allows for some differences to be okay, e.g., using more gas
@@ -24,9 +24,9 @@ enum Diff {
         left: u64,
         right: u64,
     },
-    ExecutionStatus {
-        left: ExecutionStatus,
-        right: ExecutionStatus,
+    TransactionStatus {
+        left: TransactionStatus,
+        right: TransactionStatus,
     },
     Event {
         left: Option<ContractEvent>,
@@ -39,16 +39,49 @@ enum Diff {
     },
 }
 
+/// Specifies which parts of transaction outputs are compared. Statuses, event types and written
+/// state keys are always compared.
+#[derive(Clone, Copy, Debug)]
+pub struct DiffConfig {
+    /// If false, gas used by transactions is not compared.
+    pub compare_gas_used: bool,
+    /// If false, only the structure of outputs is compared: events are compared by their types,
+    /// and writes by their state keys and kinds (creation, modification or deletion), but not by
+    /// their values.
+    pub compare_values: bool,
+}
+
+impl Default for DiffConfig {
+    fn default() -> Self {
+        Self {
+            compare_gas_used: true,
+            compare_values: true,
+        }
+    }
+}
+
 /// Holds all differences for a pair of transaction outputs.
-pub(crate) struct TransactionDiff {
+pub struct TransactionDiff {
     diffs: Vec<Diff>,
 }
 
 impl TransactionDiff {
     /// Given a pair of transaction outputs, computes its [TransactionDiff] that includes the gas
     /// used, execution status, events and write sets.
-    // TODO: Make comparison configurable, so we can skip gas differences, etc.
     pub(crate) fn from_outputs(left: TransactionOutput, right: TransactionOutput) -> Self {
+        // All statuses must be kept, since we are replaying transactions.
+        assert_ok!(left.status().as_kept_status());
+        assert_ok!(right.status().as_kept_status());
+        Self::from_outputs_with_config(left, right, DiffConfig::default())
+    }
+
+    /// Given a pair of transaction outputs, computes its [TransactionDiff] for the parts of the
+    /// outputs specified by the config. Unlike when replaying, outputs may be discarded or retried.
+    pub fn from_outputs_with_config(
+        left: TransactionOutput,
+        right: TransactionOutput,
+        config: DiffConfig,
+    ) -> Self {
         let (left_write_set, left_events, left_gas_used, left_transaction_status, _) =
             left.unpack();
         let (right_write_set, right_events, right_gas_used, right_transaction_status, _) =
@@ -56,36 +89,38 @@ impl TransactionDiff {
 
         let mut diffs = vec![];
 
-        // All statuses must be kept, since we are replaying transactions.
-        let left_execution_status = assert_ok!(left_transaction_status.as_kept_status());
-        let right_execution_status = assert_ok!(right_transaction_status.as_kept_status());
-        if left_execution_status != right_execution_status {
-            diffs.push(Diff::ExecutionStatus {
-                left: left_execution_status,
-                right: right_execution_status,
+        if left_transaction_status != right_transaction_status {
+            diffs.push(Diff::TransactionStatus {
+                left: left_transaction_status,
+                right: right_transaction_status,
             });
         }
 
-        if left_gas_used != right_gas_used {
+        if config.compare_gas_used && left_gas_used != right_gas_used {
             diffs.push(Diff::GasUsed {
                 left: left_gas_used,
                 right: right_gas_used,
             });
         }
 
-        Self::diff_events(&mut diffs, left_events, right_events);
-        Self::diff_write_sets(&mut diffs, left_write_set, right_write_set);
+        Self::diff_events(&mut diffs, left_events, right_events, config);
+        Self::diff_write_sets(&mut diffs, left_write_set, right_write_set, config);
 
         Self { diffs }
     }
 
     /// Returns true if the diff is empty, and transaction outputs match.
-    pub(crate) fn is_empty(&self) -> bool {
+    pub fn is_empty(&self) -> bool {
         self.diffs.is_empty()
     }
 
     /// Computes the differences between a pair of event vectors, and adds them to the diff.
-    fn diff_events(diffs: &mut Vec<Diff>, left: Vec<ContractEvent>, right: Vec<ContractEvent>) {
+    fn diff_events(
+        diffs: &mut Vec<Diff>,
+        left: Vec<ContractEvent>,
+        right: Vec<ContractEvent>,
+        config: DiffConfig,
+    ) {
         let event_vec_to_map = |events: Vec<ContractEvent>| {
             events
                 .into_iter()
@@ -98,10 +133,9 @@ impl TransactionDiff {
 
         for (left_ty_tag, left_event) in left {
             let maybe_right_event = right.remove(&left_ty_tag);
-            if maybe_right_event
-                .as_ref()
-                .is_some_and(|right_event| left_event.event_data() == right_event.event_data())
-            {
+            if maybe_right_event.as_ref().is_some_and(|right_event| {
+                !config.compare_values || left_event.event_data() == right_event.event_data()
+            }) {
                 continue;
             }
 
@@ -120,16 +154,19 @@ impl TransactionDiff {
     }
 
     /// Computes the differences between a pair of write sets, and adds them to the diff.
-    fn diff_write_sets(diffs: &mut Vec<Diff>, left: WriteSet, right: WriteSet) {
+    fn diff_write_sets(diffs: &mut Vec<Diff>, left: WriteSet, right: WriteSet, config: DiffConfig) {
         let left = left.into_mut().into_inner();
         let mut right = right.into_mut().into_inner();
 
         for (left_state_key, left_write_op) in left {
             let maybe_right_write_op = right.remove(&left_state_key);
-            if maybe_right_write_op
-                .as_ref()
-                .is_some_and(|right_write_op| right_write_op == &left_write_op)
-            {
+            if maybe_right_write_op.as_ref().is_some_and(|right_write_op| {
+                if config.compare_values {
+                    right_write_op == &left_write_op
+                } else {
+                    right_write_op.write_op_kind() == left_write_op.write_op_kind()
+                }
+            }) {
                 continue;
             }
 
@@ -158,12 +195,8 @@ impl std::fmt::Display for TransactionDiff {
                 Diff::GasUsed { left, right } => {
                     writeln!(f, "[gas used] before: {}, after: {}", left, right)?;
                 },
-                Diff::ExecutionStatus { left, right } => {
-                    writeln!(
-                        f,
-                        "[execution status] before: {:?}, after: {:?}",
-                        left, right
-                    )?;
+                Diff::TransactionStatus { left, right } => {
+                    writeln!(f, "[status] before: {:?}, after: {:?}", left, right)?;
                 },
                 Diff::Event { left, right } => {
                     let left = left.as_ref();
@@ -262,7 +295,7 @@ mod test {
             },
         ];
 
-        TransactionDiff::diff_events(&mut diffs, events_1, events_2);
+        TransactionDiff::diff_events(&mut diffs, events_1, events_2, DiffConfig::default());
 
         assert_eq!(diffs.len(), 3);
         assert!(diffs.iter().all(|diff| expected_diffs.contains(diff)));
@@ -368,9 +401,72 @@ mod test {
             },
         ];
 
-        TransactionDiff::diff_write_sets(&mut diffs, write_set_1, write_set_2);
+        TransactionDiff::diff_write_sets(
+            &mut diffs,
+            write_set_1,
+            write_set_2,
+            DiffConfig::default(),
+        );
 
         assert_eq!(diffs.len(), 5);
         assert!(diffs.iter().all(|diff| expected_diffs.contains(diff)));
     }
+
+    #[test]
+    fn test_diff_without_values() {
+        let config = DiffConfig {
+            compare_gas_used: false,
+            compare_values: false,
+        };
+
+        let mut diffs = vec![];
+        TransactionDiff::diff_events(
+            &mut diffs,
+            vec![ContractEvent::new_v2_with_type_tag_str(
+                "0x1::event::EventA",
+                vec![0, 1, 2],
+            )],
+            vec![ContractEvent::new_v2_with_type_tag_str(
+                "0x1::event::EventA",
+                vec![0, 1, 3],
+            )],
+            config,
+        );
+        assert!(diffs.is_empty());
+
+        let write_set_1 = WriteSetMut::new(vec![
+            (
+                StateKey::raw(b"key-1"),
+                WriteOp::legacy_modification(vec![0, 1, 2].into()),
+            ),
+            (
+                StateKey::raw(b"key-2"),
+                WriteOp::legacy_creation(vec![0, 1, 2].into()),
+            ),
+        ])
+        .freeze()
+        .unwrap();
+        let write_set_2 = WriteSetMut::new(vec![
+            // Only the value is different.
+            (
+                StateKey::raw(b"key-1"),
+                WriteOp::legacy_modification(vec![0, 1, 3].into()),
+            ),
+            // The kind of the write is different.
+            (
+                StateKey::raw(b"key-2"),
+                WriteOp::legacy_modification(vec![0, 1, 2].into()),
+            ),
+        ])
+        .freeze()
+        .unwrap();
+
+        TransactionDiff::diff_write_sets(&mut diffs, write_set_1, write_set_2, config);
+        assert_eq!(diffs.len(), 1);
+        assert!(diffs.contains(&Diff::WriteSet {
+            state_key: StateKey::raw(b"key-2"),
+            left: Some(WriteOp::legacy_creation(vec![0, 1, 2].into())),
+            right: Some(WriteOp::legacy_modification(vec![0, 1, 2].into())),
+        }));
+    }
 }
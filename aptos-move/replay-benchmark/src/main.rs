@@ -5,11 +5,15 @@ use aptos_logger::{Level, Logger};
 use aptos_move_debugger::aptos_debugger::AptosDebugger;
 use aptos_push_metrics::MetricsPusher;
 use aptos_replay_benchmark::{
-    generator::BenchmarkGenerator, overrides::OverrideConfig, runner::BenchmarkRunner,
+    block::{load_blocks, save_blocks, Block},
+    generator::BenchmarkGenerator,
+    overrides::OverrideConfig,
+    runner::BenchmarkRunner,
 };
 use aptos_rest_client::{AptosBaseUrl, Client};
 use aptos_types::{on_chain_config::FeatureFlag, transaction::Version};
-use clap::Parser;
+use clap::{ArgGroup, Parser};
+use std::path::PathBuf;
 use url::Url;
 
 /// Minimum number of times to execute blocks of transactions and measure the time taken.
@@ -17,6 +21,11 @@ const MIN_NUM_REPEATS: usize = 3;
 
 #[derive(Parser)]
 #[command(about)]
+#[clap(group(
+    ArgGroup::new("source")
+        .required(true)
+        .args(&["rest_endpoint", "db_path", "load_blocks"]),
+))]
 pub struct Command {
     #[clap(long, default_value_t = Level::Error)]
     log_level: Level,
@@ -26,19 +35,55 @@ pub struct Command {
         help = "Fullnode's REST API query endpoint, e.g., https://mainnet.aptoslabs.com/v1 for \
                 mainnet."
     )]
-    rest_endpoint: String,
+    rest_endpoint: Option<String>,
 
     #[clap(
         long,
+        requires = "rest_endpoint",
         help = "Optional API key to increase HTTP request rate limit quota."
     )]
     api_key: Option<String>,
 
-    #[clap(long, help = "First transaction to include for benchmarking.")]
-    begin_version: Version,
+    #[clap(
+        long,
+        help = "Path to a local AptosDB to generate blocks from, instead of the REST API."
+    )]
+    db_path: Option<PathBuf>,
+
+    #[clap(
+        long,
+        conflicts_with_all = [
+            "enable_features",
+            "disable_features",
+            "save_blocks",
+            "begin_version",
+            "end_version",
+        ],
+        help = "Path to a file with blocks saved by --save-blocks, to benchmark instead of \
+                generating blocks. Any overrides are already part of the saved blocks."
+    )]
+    load_blocks: Option<PathBuf>,
+
+    #[clap(
+        long,
+        help = "Path to a file to save generated blocks to, so that they can be benchmarked \
+                later with --load-blocks, without network or DB access."
+    )]
+    save_blocks: Option<PathBuf>,
 
-    #[clap(long, help = "Last transaction to include for benchmarking.")]
-    end_version: Version,
+    #[clap(
+        long,
+        required_unless_present = "load_blocks",
+        help = "First transaction to include for benchmarking."
+    )]
+    begin_version: Option<Version>,
+
+    #[clap(
+        long,
+        required_unless_present = "load_blocks",
+        help = "Last transaction to include for benchmarking."
+    )]
+    end_version: Option<Version>,
 
     #[clap(
         long,
@@ -90,6 +135,31 @@ pub struct Command {
                 flags, see aptos-core/types/src/on_chain_config/aptos_features.rs."
     )]
     disable_features: Vec<FeatureFlag>,
+
+    #[clap(
+        long,
+        help = "If set, blocks are first executed once with the highest concurrency level to \
+                record Block-STM conflicts. Per-block conflict graphs (JSON and DOT) and the most \
+                conflicting keys are written to this directory."
+    )]
+    conflict_analytics_dir: Option<PathBuf>,
+
+    #[clap(
+        long,
+        default_value_t = 20,
+        requires = "conflict_analytics_dir",
+        help = "Number of most conflicting keys to report with --conflict-analytics-dir."
+    )]
+    num_hot_keys: usize,
+
+    #[clap(
+        long,
+        help = "If set, blocks are first executed once with the highest concurrency level to \
+                account resources used by transactions (execution time, state reads, bytes read \
+                and written, instructions and native calls). Aggregated usage is reported for \
+                every block."
+    )]
+    resource_accounting: bool,
 }
 
 #[tokio::main]
@@ -103,12 +173,6 @@ async fn main() -> anyhow::Result<()> {
     let _mp = MetricsPusher::start(vec![]);
 
     // Sanity checks for provided commands.
-    assert!(
-        command.begin_version <= command.end_version,
-        "Transaction versions should be a valid closed interval. Instead got begin: {}, end: {}",
-        command.begin_version,
-        command.end_version,
-    );
     assert!(
         !command.concurrency_levels.is_empty(),
         "At least one concurrency level must be provided",
@@ -126,31 +190,10 @@ async fn main() -> anyhow::Result<()> {
         "Enable and disable feature flags cannot overlap",
     );
 
-    // TODO:
-    //   Right now we fetch transactions from debugger, but ideally we need a way to save them
-    //   locally (with corresponding read-sets) so we can use this for CI.
-    let builder = Client::builder(AptosBaseUrl::Custom(Url::parse(&command.rest_endpoint)?));
-    let client = if let Some(api_key) = command.api_key {
-        builder.api_key(&api_key)?.build()
-    } else {
-        builder.build()
+    let blocks = match &command.load_blocks {
+        Some(path) => load_blocks(path)?,
+        None => generate_blocks(&command).await?,
     };
-    let debugger = AptosDebugger::rest_client(client)?;
-
-    // TODO:
-    //  Right now, only features can be overridden. In general, this can be allowed for anything:
-    //      1. Framework code, e.g., to test performance of new natives or compiler,
-    //      2. Gas schedule, to track the costs of charging gas or tracking limits.
-    //  We probably should support at least these.
-    let override_config = OverrideConfig::new(command.enable_features, command.disable_features);
-
-    let blocks = BenchmarkGenerator::generate_blocks(
-        debugger,
-        command.begin_version,
-        command.end_version,
-        override_config,
-    )
-    .await?;
 
     // Ensure we have at least one block to benchmark.
     assert!(
@@ -163,18 +206,69 @@ async fn main() -> anyhow::Result<()> {
     for block in &blocks {
         block.print_diffs();
     }
+    if let Some(path) = &command.save_blocks {
+        save_blocks(&blocks, path)?;
+        println!("Saved {} blocks to {}", blocks.len(), path.display());
+    }
 
-    BenchmarkRunner::new(
+    let runner = BenchmarkRunner::new(
         command.concurrency_levels,
         command.num_repeats,
         command.measure_block_times,
         command.num_blocks_to_skip,
-    )
-    .measure_execution_time(&blocks);
+    );
+    if let Some(dir) = &command.conflict_analytics_dir {
+        runner.collect_conflict_analytics(&blocks, dir, command.num_hot_keys)?;
+    }
+    if command.resource_accounting {
+        runner.report_resource_usage(&blocks);
+    }
+    runner.measure_execution_time(&blocks);
 
     Ok(())
 }
 
+/// Generates blocks for benchmarking, fetching transactions and their inputs from the REST API or
+/// from a local DB.
+async fn generate_blocks(command: &Command) -> anyhow::Result<Vec<Block>> {
+    let begin_version = command.begin_version.expect("Begin version must be set");
+    let end_version = command.end_version.expect("End version must be set");
+    assert!(
+        begin_version <= end_version,
+        "Transaction versions should be a valid closed interval. Instead got begin: {}, end: {}",
+        begin_version,
+        end_version,
+    );
+
+    let debugger = if let Some(db_path) = &command.db_path {
+        AptosDebugger::db(db_path)?
+    } else {
+        let rest_endpoint = command
+            .rest_endpoint
+            .as_ref()
+            .expect("REST endpoint must be set without a DB path");
+        let builder = Client::builder(AptosBaseUrl::Custom(Url::parse(rest_endpoint)?));
+        let client = if let Some(api_key) = &command.api_key {
+            builder.api_key(api_key)?.build()
+        } else {
+            builder.build()
+        };
+        AptosDebugger::rest_client(client)?
+    };
+
+    // TODO:
+    //  Right now, only features can be overridden. In general, this can be allowed for anything:
+    //      1. Framework code, e.g., to test performance of new natives or compiler,
+    //      2. Gas schedule, to track the costs of charging gas or tracking limits.
+    //  We probably should support at least these.
+    let override_config = OverrideConfig::new(
+        command.enable_features.clone(),
+        command.disable_features.clone(),
+    );
+
+    BenchmarkGenerator::generate_blocks(debugger, begin_version, end_version, override_config).await
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
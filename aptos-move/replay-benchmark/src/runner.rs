@@ -2,8 +2,14 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::block::Block;
+use aptos_block_executor::conflict_analytics::{
+    export_conflict_reports, hot_keys, set_conflict_analytics_enabled, take_conflict_reports,
+};
+use aptos_types::transaction::resource_usage::{
+    set_resource_accounting_enabled, BlockResourceUsage,
+};
 use aptos_vm::{aptos_vm::AptosVMBlockExecutor, VMBlockExecutor};
-use std::time::Instant;
+use std::{path::Path, time::Instant};
 
 /// Holds configuration for running the benchmarks and measuring the time taken.
 pub struct BenchmarkRunner {
@@ -41,6 +47,83 @@ impl BenchmarkRunner {
         }
     }
 
+    /// Executes the blocks once with the highest concurrency level, recording which keys cause
+    /// Block-STM to abort transactions. Conflict graphs of measured blocks and the keys with the
+    /// most conflicts are exported to the given directory, and the hot keys are printed.
+    pub fn collect_conflict_analytics(
+        &self,
+        blocks: &[Block],
+        dir: &Path,
+        num_hot_keys: usize,
+    ) -> anyhow::Result<()> {
+        let concurrency_level = self.concurrency_levels.iter().max().copied().unwrap_or(1);
+        anyhow::ensure!(
+            concurrency_level > 1,
+            "Conflict analytics require parallel execution, but the concurrency level is {}",
+            concurrency_level,
+        );
+
+        set_conflict_analytics_enabled(true);
+        let executor = AptosVMBlockExecutor::new();
+        let mut reports = Vec::with_capacity(blocks.len());
+        for (idx, block) in blocks.iter().enumerate() {
+            block.run(&executor, concurrency_level);
+            let report = take_conflict_reports().pop();
+            if idx < self.num_blocks_to_skip {
+                continue;
+            }
+            match report {
+                Some(report) => reports.push(report),
+                None => println!(
+                    "Block {} was not executed in parallel, skipping its conflicts",
+                    idx + 1
+                ),
+            }
+        }
+        set_conflict_analytics_enabled(false);
+
+        export_conflict_reports(&reports, dir, num_hot_keys)?;
+        println!(
+            "Exported conflicts of {} blocks to {}, top conflicting keys:",
+            reports.len(),
+            dir.display()
+        );
+        for (key, num_conflicts) in hot_keys(&reports, num_hot_keys) {
+            println!("    {}: {} conflicts", key, num_conflicts);
+        }
+        println!();
+        Ok(())
+    }
+
+    /// Executes the blocks once with the highest concurrency level, accounting resources used by
+    /// every transaction. Aggregated usage is printed for every measured block and for all of
+    /// them.
+    pub fn report_resource_usage(&self, blocks: &[Block]) {
+        let concurrency_level = self.concurrency_levels.iter().max().copied().unwrap_or(1);
+
+        set_resource_accounting_enabled(true);
+        let executor = AptosVMBlockExecutor::new();
+        let mut overall_usage = BlockResourceUsage::default();
+        for (idx, block) in blocks.iter().enumerate() {
+            let outputs = block.run(&executor, concurrency_level);
+            if idx < self.num_blocks_to_skip {
+                continue;
+            }
+            if let Some(usage) = BlockResourceUsage::from_outputs(&outputs) {
+                println!("Block {} resource usage: {}", idx + 1, usage);
+                overall_usage.merge(&usage);
+            }
+        }
+        set_resource_accounting_enabled(false);
+
+        println!(
+            "Overall resource usage (blocks {}-{}): {}\n",
+            self.num_blocks_to_skip + 1,
+            blocks.len(),
+            overall_usage,
+        );
+    }
+
     /// Runs a sequence of blocks, measuring execution time for each block. The median is reported.
     fn measure_block_execution_times(&self, blocks: &[Block], concurrency_level: usize) {
         let mut times = (0..blocks.len())
@@ -11,6 +11,7 @@ use aptos_types::{
         Transaction, Version,
     },
 };
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 /// A workload to benchmark. Contains signature verified transactions, and metadata specifying the
 /// start and end versions of these transactions.
@@ -25,17 +26,18 @@ pub(crate) struct Workload {
 impl Workload {
     /// Returns a new workload to execute transactions at specified version.
     pub(crate) fn new(begin: Version, txns: Vec<Transaction>) -> Self {
+        Self::from_verified(begin, into_signature_verified_block(txns))
+    }
+
+    /// Returns a new workload to execute already signature verified transactions at specified
+    /// version.
+    fn from_verified(begin: Version, txns: Vec<SignatureVerifiedTransaction>) -> Self {
         assert!(!txns.is_empty());
 
         let end = begin + txns.len() as Version;
-        let transaction_slice_metadata = TransactionSliceMetadata::chunk(begin, end);
-
-        let signature_verified_txns = into_signature_verified_block(txns);
-        let txn_provider = DefaultTxnProvider::new(signature_verified_txns);
-
         Workload {
-            txn_provider,
-            transaction_slice_metadata,
+            txn_provider: DefaultTxnProvider::new(txns),
+            transaction_slice_metadata: TransactionSliceMetadata::chunk(begin, end),
         }
     }
 
@@ -44,8 +46,36 @@ impl Workload {
         &self.txn_provider
     }
 
+    /// Returns the signature verified transactions in the workload, consuming it.
+    pub(crate) fn into_txns(self) -> Vec<SignatureVerifiedTransaction> {
+        self.txn_provider.txns
+    }
+
     /// Returns transaction metadata corresponding to [begin, end) versions of the workload.
     pub(crate) fn transaction_slice_metadata(&self) -> TransactionSliceMetadata {
         self.transaction_slice_metadata
     }
 }
+
+/// Workloads are saved as the begin version and the signature verified transactions, so that
+/// signatures do not need to be verified again when loading them.
+impl Serialize for Workload {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let begin = self
+            .transaction_slice_metadata
+            .begin_version()
+            .expect("Transaction metadata must be a chunk");
+        (begin, self.txn_provider.get_txns()).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Workload {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let (begin, txns): (Version, Vec<SignatureVerifiedTransaction>) =
+            Deserialize::deserialize(deserializer)?;
+        if txns.is_empty() {
+            return Err(serde::de::Error::custom("Workload must not be empty"));
+        }
+        Ok(Self::from_verified(begin, txns))
+    }
+}
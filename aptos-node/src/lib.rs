@@ -26,6 +26,7 @@ use aptos_logger::{prelude::*, telemetry_log_writer::TelemetryLog, Level, Logger
 use aptos_state_sync_driver::driver_factory::StateSyncRuntimes;
 use aptos_types::{chain_id::ChainId, on_chain_config::OnChainJWKConsensusConfig};
 use clap::Parser;
+use either::Either;
 use futures::channel::{mpsc, oneshot};
 use hex::{FromHex, FromHexError};
 use rand::{rngs::StdRng, SeedableRng};
@@ -209,6 +210,13 @@ pub struct AptosHandle {
     _indexer_db_runtime: Option<Runtime>,
 }
 
+/// Runtime handle to ensure that the runtimes of a node in API only mode stay in scope
+pub struct AptosApiOnlyHandle {
+    _admin_service: AdminService,
+    _api_runtime: Runtime,
+    _db_catch_up_runtime: Runtime,
+}
+
 pub fn start(
     config: NodeConfig,
     log_file: Option<PathBuf>,
@@ -262,13 +270,20 @@ pub fn start_and_report_ports(
     }
 
     // Set up the node environment and start it
-    let _node_handle = setup_environment_and_start_node(
-        config,
-        remote_log_receiver,
-        Some(logger_filter_update),
-        api_port_tx,
-        indexer_grpc_port_tx,
-    )?;
+    let _node_handle = if config.storage.api_only_secondary.enabled {
+        Either::Right(setup_api_only_environment_and_start_node(
+            config,
+            api_port_tx,
+        )?)
+    } else {
+        Either::Left(setup_environment_and_start_node(
+            config,
+            remote_log_receiver,
+            Some(logger_filter_update),
+            api_port_tx,
+            indexer_grpc_port_tx,
+        )?)
+    };
     let term = Arc::new(AtomicBool::new(false));
     while !term.load(Ordering::Acquire) {
         thread::park();
@@ -792,6 +807,41 @@ pub fn setup_environment_and_start_node(
     })
 }
 
+/// Initializes the node environment and starts a node that only serves the REST API, from the DB
+/// of another node on the same disks (see `ApiOnlySecondaryConfig`)
+pub fn setup_api_only_environment_and_start_node(
+    node_config: NodeConfig,
+    api_port_tx: Option<oneshot::Sender<u16>>,
+) -> anyhow::Result<AptosApiOnlyHandle> {
+    // Log the node config at node startup
+    node_config.log_all_configs();
+
+    // Starts the admin service
+    let admin_service = services::start_admin_service(&node_config);
+
+    // Open the storage database as a secondary and start catching up with the primary
+    let (db_rw, db_catch_up_runtime) =
+        storage::initialize_api_only_secondary_database(&node_config)?;
+
+    // Set the Aptos VM configurations
+    utils::set_aptos_vm_configurations(&node_config);
+
+    // Obtain the chain_id from the DB
+    let chain_id = utils::fetch_chain_id(&db_rw)?;
+
+    // Set the chain_id in global AptosNodeIdentity
+    aptos_node_identity::set_chain_id(chain_id)?;
+
+    // Bootstrap the API
+    let api_runtime = services::bootstrap_api_only(&node_config, db_rw, chain_id, api_port_tx)?;
+
+    Ok(AptosApiOnlyHandle {
+        _admin_service: admin_service,
+        _api_runtime: api_runtime,
+        _db_catch_up_runtime: db_catch_up_runtime,
+    })
+}
+
 #[test]
 fn verify_tool() {
     use clap::CommandFactory;
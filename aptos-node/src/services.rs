@@ -12,6 +12,7 @@ use aptos_consensus::{
 };
 use aptos_consensus_notifications::ConsensusNotifier;
 use aptos_data_client::client::AptosDataClient;
+use aptos_db::partial_state::PartialStateDbReader;
 use aptos_db_indexer::{db_indexer::InternalIndexerDB, indexer_reader::IndexerReaders};
 use aptos_event_notifications::{DbBackedOnChainConfig, ReconfigNotificationListener};
 use aptos_indexer_grpc_fullnode::runtime::bootstrap as bootstrap_indexer_grpc;
@@ -96,10 +97,17 @@ pub fn bootstrap_api_and_indexer(
     });
 
     let api_runtime = if node_config.api.enabled {
+        // If the node is running in partial state mode, the API should return
+        // an error for any state that is not tracked (instead of reporting
+        // that the state does not exist).
+        let api_db_reader = PartialStateDbReader::wrap_if_enabled(
+            &node_config.storage.partial_state,
+            db_rw.reader.clone(),
+        );
         Some(bootstrap_api(
             node_config,
             chain_id,
-            db_rw.reader.clone(),
+            api_db_reader,
             mempool_client_sender.clone(),
             indexer_reader.clone(),
             api_port_tx,
@@ -137,6 +145,29 @@ pub fn bootstrap_api_and_indexer(
     ))
 }
 
+/// Bootstraps the API of a node in API only mode (see `ApiOnlySecondaryConfig`) and returns
+/// its runtime. There is no mempool, so the mempool client receiver is dropped and any
+/// transaction submission fails.
+pub fn bootstrap_api_only(
+    node_config: &NodeConfig,
+    db_rw: DbReaderWriter,
+    chain_id: ChainId,
+    api_port_tx: Option<oneshot::Sender<u16>>,
+) -> anyhow::Result<Runtime> {
+    let (mempool_client_sender, _mempool_client_receiver) =
+        mpsc::channel(AC_SMP_CHANNEL_BUFFER_SIZE);
+    let api_db_reader =
+        PartialStateDbReader::wrap_if_enabled(&node_config.storage.partial_state, db_rw.reader);
+    bootstrap_api(
+        node_config,
+        chain_id,
+        api_db_reader,
+        mempool_client_sender,
+        None,
+        api_port_tx,
+    )
+}
+
 /// Starts consensus and returns the runtime
 pub fn start_consensus_runtime(
     node_config: &NodeConfig,
@@ -183,11 +214,18 @@ pub fn start_mempool_runtime_and_get_consensus_sender(
     let (consensus_to_mempool_sender, consensus_to_mempool_receiver) =
         mpsc::channel(INTRA_NODE_CHANNEL_BUFFER_SIZE);
 
+    // If the node is running in partial state mode, transaction validation must
+    // fail for untracked state (instead of treating the state as non-existent).
+    let mempool_db_reader = PartialStateDbReader::wrap_if_enabled(
+        &node_config.storage.partial_state,
+        Arc::clone(&db_rw.reader),
+    );
+
     // Bootstrap and start mempool
     let instant = Instant::now();
     let mempool = aptos_mempool::bootstrap(
         node_config,
-        Arc::clone(&db_rw.reader),
+        mempool_db_reader,
         network_interfaces.network_client,
         network_interfaces.network_service_events,
         mempool_client_receiver,
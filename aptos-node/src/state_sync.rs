@@ -9,6 +9,7 @@ use aptos_data_streaming_service::{
     streaming_client::{new_streaming_service_client_listener_pair, StreamingServiceClient},
     streaming_service::DataStreamingService,
 };
+use aptos_db::partial_state::PartialStateDbReader;
 use aptos_event_notifications::{
     DbBackedOnChainConfig, EventNotificationListener, EventSubscriptionService,
     ReconfigNotificationListener,
@@ -171,12 +172,17 @@ pub fn start_state_sync_and_get_notification_handles(
     let (storage_service_notifier, storage_service_listener) =
         aptos_storage_service_notifications::new_storage_service_notifier_listener_pair();
 
-    // Start the state sync storage service
+    // Start the state sync storage service. If the node is running in partial
+    // state mode, the storage service must never serve untracked state.
+    let storage_service_db_reader = PartialStateDbReader::wrap_if_enabled(
+        &node_config.storage.partial_state,
+        db_rw.reader.clone(),
+    );
     let storage_service_runtime = setup_state_sync_storage_service(
         state_sync_config,
         peers_and_metadata,
         network_service_events,
-        &db_rw,
+        storage_service_db_reader,
         storage_service_listener,
     )?;
 
@@ -267,14 +273,14 @@ fn setup_state_sync_storage_service(
     config: StateSyncConfig,
     peers_and_metadata: Arc<PeersAndMetadata>,
     network_service_events: NetworkServiceEvents<StorageServiceMessage>,
-    db_rw: &DbReaderWriter,
+    db_reader: Arc<dyn DbReader>,
     storage_service_listener: StorageServiceNotificationListener,
 ) -> anyhow::Result<Runtime> {
     // Create a new state sync storage service runtime
     let storage_service_runtime = aptos_runtimes::spawn_named_runtime("stor-server".into(), None);
 
     // Spawn the state sync storage service servers on the runtime
-    let storage_reader = StorageReader::new(config.storage_service, Arc::clone(&db_rw.reader));
+    let storage_reader = StorageReader::new(config.storage_service, db_reader);
     let service = StorageServiceServer::new(
         config,
         storage_service_runtime.handle().clone(),
@@ -8,14 +8,19 @@ use aptos_db::{fast_sync_storage_wrapper::FastSyncStorageWrapper, AptosDB};
 use aptos_db_indexer::db_indexer::InternalIndexerDB;
 use aptos_executor::db_bootstrapper::maybe_bootstrap;
 use aptos_indexer_grpc_table_info::internal_indexer_db_service::InternalIndexerDBService;
-use aptos_logger::{debug, info};
+use aptos_logger::{debug, info, warn};
 use aptos_storage_interface::{DbReader, DbReaderWriter};
 use aptos_types::{
     ledger_info::LedgerInfoWithSignatures, transaction::Version, waypoint::Waypoint,
 };
 use aptos_vm::aptos_vm::AptosVMBlockExecutor;
 use either::Either;
-use std::{fs, path::Path, sync::Arc, time::Instant};
+use std::{
+    fs,
+    path::Path,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 use tokio::{
     runtime::Runtime,
     sync::watch::{channel, Receiver as WatchReceiver},
@@ -204,3 +209,46 @@ pub fn initialize_database_and_checkpoints(
         update_receiver,
     ))
 }
+
+/// Opens the DB of another node on the same disks as a read-only secondary instance (see
+/// `ApiOnlySecondaryConfig`), and returns it along with the runtime that periodically catches
+/// it up with the node writing the DB.
+pub fn initialize_api_only_secondary_database(
+    node_config: &NodeConfig,
+) -> Result<(DbReaderWriter, Runtime)> {
+    // Open the database
+    let instant = Instant::now();
+    let aptos_db = Arc::new(
+        AptosDB::open_as_secondary(
+            node_config.storage.get_dir_paths(),
+            node_config.storage.api_only_secondary_dir(),
+            node_config.storage.rocksdb_configs,
+            node_config.storage.max_num_nodes_per_lru_cache_shard,
+        )
+        .map_err(|err| anyhow!("DB failed to open as secondary {}", err))?,
+    );
+    debug!(
+        "Secondary storage opened in {} ms",
+        instant.elapsed().as_millis()
+    );
+
+    // Catch up with the node writing the DB at every interval
+    let catch_up_interval =
+        Duration::from_millis(node_config.storage.api_only_secondary.catch_up_interval_ms);
+    let catch_up_runtime = aptos_runtimes::spawn_named_runtime("db-catch-up".into(), Some(1));
+    let db = aptos_db.clone();
+    catch_up_runtime.spawn(async move {
+        let mut interval = tokio::time::interval(catch_up_interval);
+        loop {
+            interval.tick().await;
+            let db = db.clone();
+            match tokio::task::spawn_blocking(move || db.try_catch_up_with_primary()).await {
+                Ok(Ok(_)) => {},
+                Ok(Err(err)) => warn!("Failed to catch up with the primary DB: {}", err),
+                Err(err) => warn!("The catch up with the primary DB panicked: {}", err),
+            }
+        }
+    });
+
+    Ok((DbReaderWriter::from_arc(aptos_db), catch_up_runtime))
+}
@@ -49,6 +49,16 @@ pub struct ConsensusObserverConfig {
     /// Interval (in milliseconds) to refresh the subscription
     pub subscription_refresh_interval_ms: u64,
 
+    /// Whether to score publishers (based on message validity and lag) and
+    /// terminate subscriptions to publishers with low scores.
+    pub enable_publisher_scoring: bool,
+    /// Number of rounds a duplicate message can trail the last ordered
+    /// block before the publisher is considered to be lagging.
+    pub publisher_lag_threshold_rounds: u64,
+    /// Half-life (in milliseconds) of publisher score deviations, i.e., the
+    /// time it takes for a score to move halfway back to the starting score.
+    pub publisher_score_decay_half_life_ms: u64,
+
     /// Duration (in milliseconds) to require state sync to synchronize when in fallback mode
     pub observer_fallback_duration_ms: u64,
     /// Duration (in milliseconds) we'll wait on startup before considering fallback mode
@@ -76,6 +86,9 @@ impl Default for ConsensusObserverConfig {
             max_subscription_timeout_ms: 15_000,               // 15 seconds
             subscription_peer_change_interval_ms: 180_000,     // 3 minutes
             subscription_refresh_interval_ms: 600_000,         // 10 minutes
+            enable_publisher_scoring: false,
+            publisher_lag_threshold_rounds: 10,                // 10 rounds
+            publisher_score_decay_half_life_ms: 600_000,       // 10 minutes
             observer_fallback_duration_ms: 600_000,            // 10 minutes
             observer_fallback_startup_period_ms: 60_000,       // 60 seconds
             observer_fallback_progress_threshold_ms: 10_000,   // 10 seconds
@@ -15,6 +15,7 @@ pub struct InternalIndexerDBConfig {
     pub enable_event_v2_translation: bool,
     pub event_v2_translation_ignores_below_version: u64,
     pub enable_statekeys: bool,
+    pub enable_event_by_type: bool,
     pub batch_size: usize,
 }
 
@@ -25,6 +26,7 @@ impl InternalIndexerDBConfig {
         enable_event_v2_translation: bool,
         event_v2_translation_ignores_below_version: u64,
         enable_statekeys: bool,
+        enable_event_by_type: bool,
         batch_size: usize,
     ) -> Self {
         Self {
@@ -33,6 +35,7 @@ impl InternalIndexerDBConfig {
             enable_event_v2_translation,
             event_v2_translation_ignores_below_version,
             enable_statekeys,
+            enable_event_by_type,
             batch_size,
         }
     }
@@ -57,8 +60,15 @@ impl InternalIndexerDBConfig {
         self.enable_statekeys
     }
 
+    pub fn enable_event_by_type(&self) -> bool {
+        self.enable_event_by_type
+    }
+
     pub fn is_internal_indexer_db_enabled(&self) -> bool {
-        self.enable_transaction || self.enable_event || self.enable_statekeys
+        self.enable_transaction
+            || self.enable_event
+            || self.enable_statekeys
+            || self.enable_event_by_type
     }
 
     pub fn batch_size(&self) -> usize {
@@ -74,6 +84,7 @@ impl Default for InternalIndexerDBConfig {
             enable_event_v2_translation: false,
             event_v2_translation_ignores_below_version: 0,
             enable_statekeys: false,
+            enable_event_by_type: false,
             batch_size: 10_000,
         }
     }
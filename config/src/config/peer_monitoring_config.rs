@@ -6,13 +6,15 @@ use serde::{Deserialize, Serialize};
 #[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, Serialize)]
 #[serde(default, deny_unknown_fields)]
 pub struct PeerMonitoringServiceConfig {
+    pub bandwidth_monitoring: BandwidthMonitoringConfig,
     pub enable_peer_monitoring_client: bool, // Whether or not to spawn the monitoring client
     pub latency_monitoring: LatencyMonitoringConfig,
     pub max_concurrent_requests: u64, // Max num of concurrent server tasks
     pub max_network_channel_size: u64, // Max num of pending network messages
     pub max_num_response_bytes: u64,  // Max num of bytes in a (serialized) response
     pub max_request_jitter_ms: u64, // Max amount of jitter (ms) that a request will be delayed for
-    pub metadata_update_interval_ms: u64, // The interval (ms) between metadata updates
+    pub message_statistics_window_ms: u64, // The window (ms) over which message statistics are computed
+    pub metadata_update_interval_ms: u64,  // The interval (ms) between metadata updates
     pub network_monitoring: NetworkMonitoringConfig,
     pub node_monitoring: NodeMonitoringConfig,
     pub peer_monitor_interval_usec: u64, // The interval (usec) between peer monitor executions
@@ -21,13 +23,15 @@ pub struct PeerMonitoringServiceConfig {
 impl Default for PeerMonitoringServiceConfig {
     fn default() -> Self {
         Self {
+            bandwidth_monitoring: BandwidthMonitoringConfig::default(),
             enable_peer_monitoring_client: true,
             latency_monitoring: LatencyMonitoringConfig::default(),
             max_concurrent_requests: 1000,
             max_network_channel_size: 1000,
-            max_num_response_bytes: 100 * 1024, // 100 KB
-            max_request_jitter_ms: 1000,        // Monitoring requests are very infrequent
-            metadata_update_interval_ms: 5000,  // 5 seconds
+            max_num_response_bytes: 100 * 1024,    // 100 KB
+            max_request_jitter_ms: 1000,           // Monitoring requests are very infrequent
+            message_statistics_window_ms: 600_000, // 10 minutes
+            metadata_update_interval_ms: 5000,     // 5 seconds
             network_monitoring: NetworkMonitoringConfig::default(),
             node_monitoring: NodeMonitoringConfig::default(),
             peer_monitor_interval_usec: 1_000_000, // 1 second
@@ -35,6 +39,32 @@ impl Default for PeerMonitoringServiceConfig {
     }
 }
 
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BandwidthMonitoringConfig {
+    pub bandwidth_probe_interval_ms: u64, // The interval (ms) between bandwidth probes for each peer
+    pub bandwidth_probe_payload_bytes: u64, // The payload size (bytes) requested by each probe
+    pub bandwidth_probe_timeout_ms: u64,  // The timeout (ms) for each bandwidth probe
+    pub enable_bandwidth_probes: bool,    // Whether or not to send bandwidth probes to peers
+    pub max_bandwidth_probe_failures: u64, // Max probe failures before the peer is flagged
+    pub max_bandwidth_probe_payload_bytes: u64, // Max payload size (bytes) the server will send
+    pub max_num_bandwidth_probes_to_retain: usize, // The max bandwidth probes to retain per peer
+}
+
+impl Default for BandwidthMonitoringConfig {
+    fn default() -> Self {
+        Self {
+            bandwidth_probe_interval_ms: 300_000,     // 5 minutes
+            bandwidth_probe_payload_bytes: 64 * 1024, // 64 KB
+            bandwidth_probe_timeout_ms: 20_000,       // 20 seconds
+            enable_bandwidth_probes: false, // Probes consume bandwidth, so they are opt-in
+            max_bandwidth_probe_failures: 3,
+            max_bandwidth_probe_payload_bytes: 64 * 1024, // 64 KB
+            max_num_bandwidth_probes_to_retain: 10,
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, Serialize)]
 #[serde(default, deny_unknown_fields)]
 pub struct LatencyMonitoringConfig {
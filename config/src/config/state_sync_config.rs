@@ -483,6 +483,37 @@ impl ConfigSanitizer for StateSyncDriverConfig {
             ));
         }
 
+        // Verify that nodes running in partial state mode never execute
+        // transactions (execution requires the full state). Instead, they
+        // must verify and apply transaction outputs (or fast sync).
+        if node_config.storage.partial_state.enabled {
+            let bootstrapping_mode = state_sync_driver_config.bootstrapping_mode;
+            if !matches!(
+                bootstrapping_mode,
+                BootstrappingMode::ApplyTransactionOutputsFromGenesis
+                    | BootstrappingMode::DownloadLatestStates
+            ) {
+                return Err(Error::ConfigSanitizerFailed(
+                    sanitizer_name,
+                    format!(
+                        "The bootstrapping mode {:?} is not supported in partial state mode!",
+                        bootstrapping_mode
+                    ),
+                ));
+            }
+
+            let continuous_syncing_mode = state_sync_driver_config.continuous_syncing_mode;
+            if continuous_syncing_mode != ContinuousSyncingMode::ApplyTransactionOutputs {
+                return Err(Error::ConfigSanitizerFailed(
+                    sanitizer_name,
+                    format!(
+                        "The continuous syncing mode {:?} is not supported in partial state mode!",
+                        continuous_syncing_mode
+                    ),
+                ));
+            }
+        }
+
         Ok(())
     }
 }
@@ -788,6 +819,55 @@ mod tests {
         assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
     }
 
+    #[test]
+    fn test_sanitize_partial_state_syncing_modes() {
+        // Create a node config with execution mode and partial state enabled
+        let mut node_config = create_execution_mode_config();
+        node_config.storage.partial_state.enabled = true;
+
+        // Verify that sanitization fails
+        let error = StateSyncConfig::sanitize(
+            &node_config,
+            NodeType::PublicFullnode,
+            Some(ChainId::testnet()),
+        )
+        .unwrap_err();
+        assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
+
+        // Update the bootstrapping mode to output syncing and verify that sanitization still fails
+        node_config.state_sync.state_sync_driver.bootstrapping_mode =
+            BootstrappingMode::ApplyTransactionOutputsFromGenesis;
+        let error = StateSyncConfig::sanitize(
+            &node_config,
+            NodeType::PublicFullnode,
+            Some(ChainId::testnet()),
+        )
+        .unwrap_err();
+        assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
+
+        // Update the continuous syncing mode to output syncing and verify that sanitization passes
+        node_config
+            .state_sync
+            .state_sync_driver
+            .continuous_syncing_mode = ContinuousSyncingMode::ApplyTransactionOutputs;
+        StateSyncConfig::sanitize(
+            &node_config,
+            NodeType::PublicFullnode,
+            Some(ChainId::testnet()),
+        )
+        .unwrap();
+
+        // Verify that fast sync is also supported
+        node_config.state_sync.state_sync_driver.bootstrapping_mode =
+            BootstrappingMode::DownloadLatestStates;
+        StateSyncConfig::sanitize(
+            &node_config,
+            NodeType::PublicFullnode,
+            Some(ChainId::testnet()),
+        )
+        .unwrap();
+    }
+
     /// Creates and returns a node config with the syncing modes set to execution
     fn create_execution_mode_config() -> NodeConfig {
         NodeConfig {
@@ -8,8 +8,11 @@ use crate::{
 };
 use anyhow::{bail, ensure, Result};
 use aptos_logger::warn;
-use aptos_types::chain_id::ChainId;
+use aptos_types::{
+    account_address::AccountAddress, chain_id::ChainId, state_store::table::TableHandle,
+};
 use arr_macro::arr;
+use move_core_types::language_storage::TypeTag;
 use serde::{Deserialize, Serialize};
 use std::{
     collections::HashMap,
@@ -197,6 +200,118 @@ pub struct StorageConfig {
     /// If not specificed, will use `dir` as default.
     /// Only allowed when sharding is enabled.
     pub db_path_overrides: Option<DbPathConfig>,
+    /// Configuration for running the node in partial state mode (i.e., only
+    /// persisting state values for a set of tracked accounts).
+    pub partial_state: PartialStateConfig,
+    /// Rules for ledger data that is kept by the ledger pruner (i.e., archived) after
+    /// falling out of the prune window.
+    pub ledger_retention: LedgerRetentionConfig,
+    /// Background re-verification of the persisted data
+    pub consistency_scrubber: ConsistencyScrubberConfig,
+    /// Serve state values at versions whose state Merkle tree is already pruned from the state KV
+    /// DB, marked as unproven (i.e., without proofs), as long as the state values aren't pruned.
+    pub enable_unproven_historical_state_reads: bool,
+    /// Configuration for running the node as a read-only replica that only serves the REST API.
+    pub api_only_secondary: ApiOnlySecondaryConfig,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PartialStateConfig {
+    /// Boolean to enable/disable partial state mode. In this mode, the node still verifies
+    /// all ledger infos and transaction outputs (and persists the full state Merkle tree, so
+    /// that proofs can be served), but only persists state values for the tracked accounts.
+    pub enabled: bool,
+    /// The accounts whose state values (e.g., resources and modules) are persisted. Note: the
+    /// framework account (0x1) is always tracked, as the node requires the on-chain configs.
+    pub tracked_accounts: Vec<AccountAddress>,
+    /// The tables whose items are persisted. Note: table items are stored under the table
+    /// handles (and not the accounts that own the tables), so they must be tracked explicitly.
+    pub tracked_table_handles: Vec<TableHandle>,
+}
+
+/// Selective archival retention for the ledger pruner. A transaction is retained if it matches
+/// any of the rules below, in which case its transaction, events, write set, info, auxiliary
+/// data and accumulator proof are kept. Everything else is pruned as usual.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LedgerRetentionConfig {
+    /// Retain user transactions sent by these accounts.
+    pub senders: Vec<AccountAddress>,
+    /// Retain transactions emitting events of these types (e.g., "0x1::coin::CoinDeposit").
+    /// The type must match exactly, including its type arguments.
+    pub event_types: Vec<String>,
+    /// Retain transactions calling an entry function, or emitting an event, defined in a
+    /// module published under these addresses.
+    pub module_addresses: Vec<AccountAddress>,
+}
+
+impl LedgerRetentionConfig {
+    /// Returns true iff any retention rule is configured
+    pub fn is_enabled(&self) -> bool {
+        !self.senders.is_empty()
+            || !self.event_types.is_empty()
+            || !self.module_addresses.is_empty()
+    }
+}
+
+/// Config for running the node as a read-only replica that only serves the REST API, from the DB
+/// of another node sharing the same disks (i.e., at `dir` and `db_path_overrides`). The replica
+/// opens the DB as a RocksDB secondary instance and periodically catches up with the node writing
+/// it. No other component (e.g., state sync, mempool or consensus) is started, so transactions
+/// can't be submitted through the replica.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ApiOnlySecondaryConfig {
+    /// Boolean to enable/disable the API only mode.
+    pub enabled: bool,
+    /// Directory for the info logs and MANIFEST copies of the replica, which must not be used by
+    /// the DB it follows. A relative path is relative to the data directory.
+    pub secondary_dir: PathBuf,
+    /// The interval between two catch ups with the node writing the DB.
+    pub catch_up_interval_ms: u64,
+}
+
+impl Default for ApiOnlySecondaryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            secondary_dir: PathBuf::from("db_secondary"),
+            catch_up_interval_ms: 1_000,
+        }
+    }
+}
+
+/// Config for the consistency scrubber, which continuously re-verifies the persisted data in the
+/// background (transaction infos against the transaction accumulator, events against the event
+/// root hashes and state values against the leaves of the state Merkle tree), and reports any
+/// corruption via metrics, logs and the inspection service.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ConsistencyScrubberConfig {
+    /// Boolean to enable/disable the consistency scrubber.
+    pub enable: bool,
+    /// Number of transactions to verify in each ledger batch.
+    pub ledger_batch_size: usize,
+    /// Number of state values to verify in each state batch.
+    pub state_batch_size: usize,
+    /// The I/O budget of the scrubber: the approximate number of bytes read per second.
+    pub max_bytes_per_sec: u64,
+    /// The time to wait before starting a new pass once all data has been verified.
+    pub pass_interval_secs: u64,
+}
+
+impl Default for ConsistencyScrubberConfig {
+    fn default() -> Self {
+        Self {
+            enable: false,
+            ledger_batch_size: 1_000,
+            state_batch_size: 1_000,
+            // 4MB/s, which is negligible compared to the I/O of a node in sync.
+            max_bytes_per_sec: 4 * 1024 * 1024,
+            pass_interval_secs: 3_600,
+        }
+    }
 }
 
 pub const NO_OP_STORAGE_PRUNER_CONFIG: PrunerConfig = PrunerConfig {
@@ -216,6 +331,7 @@ pub const NO_OP_STORAGE_PRUNER_CONFIG: PrunerConfig = PrunerConfig {
         prune_window: 0,
         batch_size: 0,
     },
+    state_kv_pruner_config: None,
 };
 
 #[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
@@ -278,6 +394,17 @@ pub struct PrunerConfig {
     pub ledger_pruner_config: LedgerPrunerConfig,
     pub state_merkle_pruner_config: StateMerklePrunerConfig,
     pub epoch_snapshot_pruner_config: EpochSnapshotPrunerConfig,
+    /// Config of the state KV pruner (pruning state values), which follows the ledger pruner
+    /// config if not set. Archive nodes can keep the state values longer than the state Merkle
+    /// tree, to answer historical state queries (without proofs) cheaply.
+    pub state_kv_pruner_config: Option<LedgerPrunerConfig>,
+}
+
+impl PrunerConfig {
+    pub fn get_state_kv_pruner_config(&self) -> LedgerPrunerConfig {
+        self.state_kv_pruner_config
+            .unwrap_or(self.ledger_pruner_config)
+    }
 }
 
 impl Default for LedgerPrunerConfig {
@@ -344,6 +471,11 @@ impl Default for StorageConfig {
             rocksdb_configs: RocksdbConfigs::default(),
             enable_indexer: false,
             db_path_overrides: None,
+            partial_state: PartialStateConfig::default(),
+            ledger_retention: LedgerRetentionConfig::default(),
+            consistency_scrubber: ConsistencyScrubberConfig::default(),
+            enable_unproven_historical_state_reads: false,
+            api_only_secondary: ApiOnlySecondaryConfig::default(),
             buffered_state_target_items: BUFFERED_STATE_TARGET_ITEMS,
             max_num_nodes_per_lru_cache_shard: DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
         }
@@ -359,6 +491,15 @@ impl StorageConfig {
         }
     }
 
+    pub fn api_only_secondary_dir(&self) -> PathBuf {
+        let secondary_dir = &self.api_only_secondary.secondary_dir;
+        if secondary_dir.is_relative() {
+            self.data_dir.join(secondary_dir)
+        } else {
+            secondary_dir.clone()
+        }
+    }
+
     pub fn get_dir_paths(&self) -> StorageDirPaths {
         let default_dir = self.dir();
         let mut ledger_db_path = None;
@@ -496,7 +637,7 @@ impl ShardedDbPaths {
 impl ConfigSanitizer for StorageConfig {
     fn sanitize(
         node_config: &NodeConfig,
-        _node_type: NodeType,
+        node_type: NodeType,
         _chain_id: Option<ChainId>,
     ) -> Result<(), Error> {
         let sanitizer_name = Self::get_sanitizer_name();
@@ -528,6 +669,15 @@ impl ConfigSanitizer for StorageConfig {
         if epoch_snapshot_prune_window < 50_000_000 {
             warn!("Epoch snapshot prune_window is too small, harming network data availability.");
         }
+        if config.enable_unproven_historical_state_reads {
+            let state_kv_prune_window = config
+                .storage_pruner_config
+                .get_state_kv_pruner_config()
+                .prune_window;
+            if state_kv_prune_window <= state_merkle_prune_window {
+                warn!("State KV prune_window is not larger than the state Merkle prune_window, no unproven historical state reads can be served.");
+            }
+        }
         if user_pruning_window_offset > 1_000_000 {
             return Err(Error::ConfigSanitizerFailed(
                 sanitizer_name,
@@ -592,6 +742,71 @@ impl ConfigSanitizer for StorageConfig {
             }
         }
 
+        if config.partial_state.enabled {
+            if node_type.is_validator() {
+                return Err(Error::ConfigSanitizerFailed(
+                    sanitizer_name,
+                    "Partial state mode is not supported for validators!".to_string(),
+                ));
+            }
+            if config.enable_indexer {
+                return Err(Error::ConfigSanitizerFailed(
+                    sanitizer_name,
+                    "The indexer cannot be enabled in partial state mode!".to_string(),
+                ));
+            }
+        }
+
+        for event_type in &config.ledger_retention.event_types {
+            if let Err(e) = TypeTag::from_str(event_type) {
+                return Err(Error::ConfigSanitizerFailed(
+                    sanitizer_name,
+                    format!("Invalid event type {event_type:?} in ledger_retention: {e}"),
+                ));
+            }
+        }
+
+        if config.api_only_secondary.enabled {
+            if node_type.is_validator() {
+                return Err(Error::ConfigSanitizerFailed(
+                    sanitizer_name,
+                    "API only mode is not supported for validators!".to_string(),
+                ));
+            }
+            if !node_config.api.enabled {
+                return Err(Error::ConfigSanitizerFailed(
+                    sanitizer_name,
+                    "The API must be enabled in API only mode!".to_string(),
+                ));
+            }
+            if config.enable_indexer {
+                return Err(Error::ConfigSanitizerFailed(
+                    sanitizer_name,
+                    "The indexer cannot be enabled in API only mode!".to_string(),
+                ));
+            }
+            if config.api_only_secondary_dir() == config.dir() {
+                return Err(Error::ConfigSanitizerFailed(
+                    sanitizer_name,
+                    "The secondary_dir of the API only mode must differ from the DB dir!"
+                        .to_string(),
+                ));
+            }
+        }
+
+        let scrubber_config = &config.consistency_scrubber;
+        if scrubber_config.enable
+            && (scrubber_config.ledger_batch_size == 0
+                || scrubber_config.state_batch_size == 0
+                || scrubber_config.max_bytes_per_sec == 0)
+        {
+            return Err(Error::ConfigSanitizerFailed(
+                sanitizer_name,
+                "The batch sizes and I/O budget of the consistency scrubber must be positive!"
+                    .to_string(),
+            ));
+        }
+
         Ok(())
     }
 }
@@ -27,6 +27,9 @@ pub enum Error {
     #[error("Subscription progress stopped: {0}")]
     SubscriptionProgressStopped(String),
 
+    #[error("Subscription publisher score too low: {0}")]
+    SubscriptionPublisherScoreTooLow(String),
+
     #[error("Subscriptions reset: {0}")]
     SubscriptionsReset(String),
 
@@ -51,6 +54,7 @@ impl Error {
             Self::RpcError(_) => "rpc_error",
             Self::SubscriptionDisconnected(_) => "subscription_disconnected",
             Self::SubscriptionProgressStopped(_) => "subscription_progress_stopped",
+            Self::SubscriptionPublisherScoreTooLow(_) => "subscription_publisher_score_too_low",
             Self::SubscriptionsReset(_) => "subscriptions_reset",
             Self::SubscriptionSuboptimal(_) => "subscription_suboptimal",
             Self::SubscriptionTimeout(_) => "subscription_timeout",
@@ -19,6 +19,11 @@ pub const ORDERED_BLOCK_ENTRIES_LABEL: &str = "ordered_block_entries";
 pub const ORDERED_BLOCK_LABEL: &str = "ordered_block";
 pub const PENDING_BLOCK_ENTRIES_LABEL: &str = "pending_block_entries";
 pub const PENDING_BLOCKS_LABEL: &str = "pending_blocks";
+pub const PUBLISHER_IGNORED_LABEL: &str = "ignored";
+pub const PUBLISHER_INVALID_MESSAGE_LABEL: &str = "invalid_message";
+pub const PUBLISHER_LAGGING_MESSAGE_LABEL: &str = "lagging_message";
+pub const PUBLISHER_SCORED_LABEL: &str = "scored";
+pub const PUBLISHER_VALID_MESSAGE_LABEL: &str = "valid_message";
 pub const STORED_PAYLOADS_LABEL: &str = "stored_payloads";
 
 // Useful state sync metric labels
@@ -93,6 +98,26 @@ pub static OBSERVER_PROCESSED_BLOCK_ROUNDS: Lazy<IntGaugeVec> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Counter for tracking publisher scoring events observed by the consensus observer
+pub static OBSERVER_PUBLISHER_SCORE_EVENTS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "consensus_observer_publisher_score_events",
+        "Counters related to publisher scoring events observed by the consensus observer",
+        &["event_type", "network_id"]
+    )
+    .unwrap()
+});
+
+/// Gauge for tracking the number of scored and ignored publishers for the consensus observer
+pub static OBSERVER_PUBLISHER_COUNTS: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        "consensus_observer_publisher_counts",
+        "Gauge for tracking the number of scored and ignored publishers for the consensus observer",
+        &["status", "network_id"]
+    )
+    .unwrap()
+});
+
 /// Counter for tracking successful RPC responses received by the consensus observer
 pub static OBSERVER_RECEIVED_MESSAGE_RESPONSES: Lazy<IntCounterVec> = Lazy::new(|| {
     register_int_counter_vec!(
@@ -269,6 +294,18 @@ pub fn set_gauge(counter: &Lazy<IntGaugeVec>, network_id: &NetworkId, value: i64
     counter.with_label_values(&[network_id.as_str()]).set(value);
 }
 
+/// Sets the gauge with the specific label and network ID
+pub fn set_gauge_with_labels(
+    counter: &Lazy<IntGaugeVec>,
+    label: &str,
+    network_id: &NetworkId,
+    value: u64,
+) {
+    counter
+        .with_label_values(&[label, network_id.as_str()])
+        .set(value as i64);
+}
+
 /// Sets the gauge with the specific label and value
 pub fn set_gauge_with_label(counter: &Lazy<IntGaugeVec>, label: &str, value: u64) {
     counter.with_label_values(&[label]).set(value as i64);
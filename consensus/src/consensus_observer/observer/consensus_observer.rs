@@ -21,6 +21,7 @@ use crate::{
             ordered_blocks::OrderedBlockStore,
             payload_store::BlockPayloadStore,
             pending_blocks::{PendingBlockStore, PendingBlockWithMetadata},
+            publisher_scores::PublisherEvent,
             state_sync_manager::{StateSyncManager, StateSyncNotification},
             subscription_manager::SubscriptionManager,
         },
@@ -406,6 +407,14 @@ impl ConsensusObserver {
 
         // If the payload is out of date or already exists, ignore it
         if payload_out_of_date || payload_exists {
+            // Update the publisher score for the stale payload
+            self.subscription_manager
+                .update_publisher_score_for_stale_message(
+                    peer_network_id,
+                    (block_epoch, block_round),
+                    (last_ordered_block.epoch(), last_ordered_block.round()),
+                );
+
             // Update the metrics for the dropped block payload
             update_metrics_for_dropped_block_payload_message(peer_network_id, &block_payload);
             return;
@@ -423,6 +432,8 @@ impl ConsensusObserver {
                     error
                 ))
             );
+            self.subscription_manager
+                .update_publisher_score(peer_network_id, PublisherEvent::InvalidMessage);
             return;
         }
 
@@ -437,9 +448,15 @@ impl ConsensusObserver {
                         block_payload.block(), error
                     ))
                 );
+                self.subscription_manager
+                    .update_publisher_score(peer_network_id, PublisherEvent::InvalidMessage);
                 return;
             }
 
+            // The publisher sent a new and valid payload
+            self.subscription_manager
+                .update_publisher_score(peer_network_id, PublisherEvent::ValidMessage);
+
             true // We have successfully verified the signatures
         } else {
             false // We can't verify the signatures yet
@@ -478,7 +495,16 @@ impl ConsensusObserver {
         let commit_round = commit_decision.round();
 
         // If the commit message is behind our highest committed block, ignore it
-        if (commit_epoch, commit_round) <= self.get_highest_committed_epoch_round() {
+        let highest_committed_epoch_round = self.get_highest_committed_epoch_round();
+        if (commit_epoch, commit_round) <= highest_committed_epoch_round {
+            // Update the publisher score for the stale commit decision
+            self.subscription_manager
+                .update_publisher_score_for_stale_message(
+                    peer_network_id,
+                    (commit_epoch, commit_round),
+                    highest_committed_epoch_round,
+                );
+
             // Update the metrics for the dropped commit decision
             update_metrics_for_dropped_commit_decision_message(peer_network_id, &commit_decision);
             return;
@@ -499,9 +525,15 @@ impl ConsensusObserver {
                         error
                     ))
                 );
+                self.subscription_manager
+                    .update_publisher_score(peer_network_id, PublisherEvent::InvalidMessage);
                 return;
             }
 
+            // The publisher sent a new and valid commit decision
+            self.subscription_manager
+                .update_publisher_score(peer_network_id, PublisherEvent::ValidMessage);
+
             // Update the latency metrics for commit processing
             update_message_processing_latency_metrics(
                 message_received_time,
@@ -677,6 +709,8 @@ impl ConsensusObserver {
                     error
                 ))
             );
+            self.subscription_manager
+                .update_publisher_score(peer_network_id, PublisherEvent::InvalidMessage);
             return;
         };
 
@@ -695,6 +729,14 @@ impl ConsensusObserver {
 
         // If the block is out of date or already pending, ignore it
         if block_out_of_date || block_pending {
+            // Update the publisher score for the stale ordered block
+            self.subscription_manager
+                .update_publisher_score_for_stale_message(
+                    peer_network_id,
+                    first_block_epoch_round,
+                    (last_ordered_block.epoch(), last_ordered_block.round()),
+                );
+
             // Update the metrics for the dropped ordered block
             update_metrics_for_dropped_ordered_block_message(peer_network_id, &ordered_block);
             return;
@@ -740,6 +782,8 @@ impl ConsensusObserver {
                         error
                     ))
                 );
+                self.subscription_manager
+                    .update_publisher_score(peer_network_id, PublisherEvent::InvalidMessage);
                 return;
             }
         } else {
@@ -766,9 +810,15 @@ impl ConsensusObserver {
                     error
                 ))
             );
+            self.subscription_manager
+                .update_publisher_score(peer_network_id, PublisherEvent::InvalidMessage);
             return;
         }
 
+        // The publisher sent a new and valid ordered block
+        self.subscription_manager
+            .update_publisher_score(peer_network_id, PublisherEvent::ValidMessage);
+
         // The block was verified correctly. If the block is a child of our
         // last block, we can insert it into the ordered block store.
         if self.get_last_ordered_block().id() == ordered_block.first_block().parent_id() {
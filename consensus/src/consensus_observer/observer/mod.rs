@@ -7,6 +7,7 @@ pub mod fallback_manager;
 pub mod ordered_blocks;
 pub mod payload_store;
 pub mod pending_blocks;
+pub mod publisher_scores;
 pub mod state_sync_manager;
 pub mod subscription;
 pub mod subscription_manager;
@@ -0,0 +1,408 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::consensus_observer::common::{
+    logging::{LogEntry, LogSchema},
+    metrics,
+};
+use aptos_config::network_id::{NetworkId, PeerNetworkId};
+use aptos_logger::warn;
+use aptos_network::application::metadata::PeerMetadata;
+use aptos_time_service::{TimeService, TimeServiceTrait};
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+/// Scores for publisher rankings based on message behavior
+const MAX_SCORE: f64 = 100.0;
+const MIN_SCORE: f64 = 0.0;
+const STARTING_SCORE: f64 = 50.0;
+/// Add this score when a publisher sends a new and valid message
+const VALID_MESSAGE_DELTA: f64 = 1.0;
+/// Not necessarily malicious, but the publisher is lagging behind others
+const LAGGING_MESSAGE_MULTIPLIER: f64 = 0.95;
+/// Likely to be malicious (e.g., the message failed verification)
+const INVALID_MESSAGE_MULTIPLIER: f64 = 0.8;
+/// Ignore (and unsubscribe from) a publisher when their score dips below this threshold
+const IGNORE_PUBLISHER_THRESHOLD: f64 = 25.0;
+
+/// The types of publisher events that affect the publisher score
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PublisherEvent {
+    /// The publisher sent a message that failed verification
+    InvalidMessage,
+    /// The publisher sent a duplicate message that trails the observer's state
+    LaggingMessage,
+    /// The publisher sent a new message that was verified successfully
+    ValidMessage,
+}
+
+impl PublisherEvent {
+    /// Returns a summary label for the event
+    pub fn get_label(&self) -> &'static str {
+        match self {
+            Self::InvalidMessage => metrics::PUBLISHER_INVALID_MESSAGE_LABEL,
+            Self::LaggingMessage => metrics::PUBLISHER_LAGGING_MESSAGE_LABEL,
+            Self::ValidMessage => metrics::PUBLISHER_VALID_MESSAGE_LABEL,
+        }
+    }
+}
+
+/// The score and event counts for a single publisher. The score decays
+/// exponentially towards the starting score, so that past behavior
+/// (good or bad) is eventually forgotten.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PublisherScore {
+    score: f64,
+    last_update_time: Instant,
+    num_invalid_messages: u64,
+    num_lagging_messages: u64,
+    num_valid_messages: u64,
+}
+
+impl PublisherScore {
+    pub fn new(time_now: Instant) -> Self {
+        Self {
+            score: STARTING_SCORE,
+            last_update_time: time_now,
+            num_invalid_messages: 0,
+            num_lagging_messages: 0,
+            num_valid_messages: 0,
+        }
+    }
+
+    /// Returns the score of the publisher at the given time (after decay).
+    /// A zero half-life disables decay.
+    pub fn get_score(&self, time_now: Instant, decay_half_life: Duration) -> f64 {
+        if decay_half_life.is_zero() {
+            return self.score;
+        }
+        let elapsed = time_now.saturating_duration_since(self.last_update_time);
+        let decay_factor = 0.5_f64.powf(elapsed.as_secs_f64() / decay_half_life.as_secs_f64());
+        STARTING_SCORE + (self.score - STARTING_SCORE) * decay_factor
+    }
+
+    /// Returns true iff the publisher score (at the given time) is below the ignore threshold
+    pub fn is_ignored(&self, time_now: Instant, decay_half_life: Duration) -> bool {
+        self.get_score(time_now, decay_half_life) < IGNORE_PUBLISHER_THRESHOLD
+    }
+
+    /// Updates the score and event counts based on the given event
+    fn update_for_event(
+        &mut self,
+        publisher_event: PublisherEvent,
+        time_now: Instant,
+        decay_half_life: Duration,
+    ) {
+        // Apply the decay accumulated since the last update
+        self.score = self.get_score(time_now, decay_half_life);
+        self.last_update_time = time_now;
+
+        // Update the score based on the event
+        match publisher_event {
+            PublisherEvent::InvalidMessage => {
+                self.num_invalid_messages += 1;
+                self.score = f64::max(self.score * INVALID_MESSAGE_MULTIPLIER, MIN_SCORE);
+            },
+            PublisherEvent::LaggingMessage => {
+                self.num_lagging_messages += 1;
+                self.score = f64::max(self.score * LAGGING_MESSAGE_MULTIPLIER, MIN_SCORE);
+            },
+            PublisherEvent::ValidMessage => {
+                self.num_valid_messages += 1;
+                self.score = f64::min(self.score + VALID_MESSAGE_DELTA, MAX_SCORE);
+            },
+        }
+    }
+}
+
+/// Tracks the scores of the connected publishers the observer has received
+/// messages from. Scores are retained across subscriptions (so that a publisher
+/// that was recently misbehaving is not immediately re-selected), but are
+/// removed once the publisher disconnects.
+pub struct PublisherScores {
+    // The half-life of the score decay
+    decay_half_life: Duration,
+
+    // The scores of each publisher (indexed by peer)
+    publisher_scores: HashMap<PeerNetworkId, PublisherScore>,
+
+    // The time service (used to decay the scores)
+    time_service: TimeService,
+}
+
+impl PublisherScores {
+    pub fn new(decay_half_life: Duration, time_service: TimeService) -> Self {
+        Self {
+            decay_half_life,
+            publisher_scores: HashMap::new(),
+            time_service,
+        }
+    }
+
+    /// Returns the score for the given publisher (if one exists)
+    pub fn get_publisher_score(&self, peer_network_id: &PeerNetworkId) -> Option<PublisherScore> {
+        self.publisher_scores.get(peer_network_id).cloned()
+    }
+
+    /// Returns the current (decayed) score value for the given publisher (if one exists)
+    pub fn get_current_score(&self, peer_network_id: &PeerNetworkId) -> Option<f64> {
+        let time_now = self.time_service.now();
+        self.publisher_scores
+            .get(peer_network_id)
+            .map(|publisher_score| publisher_score.get_score(time_now, self.decay_half_life))
+    }
+
+    /// Returns the publishers that should currently be ignored (i.e., have low scores)
+    pub fn get_ignored_publishers(&self) -> Vec<PeerNetworkId> {
+        let time_now = self.time_service.now();
+        self.publisher_scores
+            .iter()
+            .filter(|(_, publisher_score)| {
+                publisher_score.is_ignored(time_now, self.decay_half_life)
+            })
+            .map(|(peer_network_id, _)| *peer_network_id)
+            .collect()
+    }
+
+    /// Returns true iff the given publisher should be ignored
+    pub fn is_ignored_publisher(&self, peer_network_id: &PeerNetworkId) -> bool {
+        let time_now = self.time_service.now();
+        self.publisher_scores
+            .get(peer_network_id)
+            .map(|publisher_score| publisher_score.is_ignored(time_now, self.decay_half_life))
+            .unwrap_or(false)
+    }
+
+    /// Removes the scores of all publishers that are no longer connected
+    pub fn remove_disconnected_publishers(
+        &mut self,
+        connected_peers_and_metadata: &HashMap<PeerNetworkId, PeerMetadata>,
+    ) {
+        self.publisher_scores.retain(|peer_network_id, _| {
+            connected_peers_and_metadata.contains_key(peer_network_id)
+        });
+        self.update_publisher_metrics();
+    }
+
+    /// Updates the score of the given publisher based on the event
+    pub fn update_publisher_score(
+        &mut self,
+        peer_network_id: PeerNetworkId,
+        publisher_event: PublisherEvent,
+    ) {
+        // Update the publisher score
+        let time_now = self.time_service.now();
+        let publisher_score = self
+            .publisher_scores
+            .entry(peer_network_id)
+            .or_insert_with(|| PublisherScore::new(time_now));
+        let was_ignored = publisher_score.is_ignored(time_now, self.decay_half_life);
+        publisher_score.update_for_event(publisher_event, time_now, self.decay_half_life);
+
+        // Log if the publisher has just dropped below the ignore threshold
+        if !was_ignored && publisher_score.is_ignored(time_now, self.decay_half_life) {
+            warn!(LogSchema::new(LogEntry::ConsensusObserver)
+                .peer(&peer_network_id)
+                .message(&format!(
+                    "Publisher score dropped below the ignore threshold! Score: {:?}, \
+                    last event: {:?}",
+                    publisher_score.get_score(time_now, self.decay_half_life),
+                    publisher_event
+                )));
+        }
+
+        // Update the publisher metrics
+        metrics::increment_counter(
+            &metrics::OBSERVER_PUBLISHER_SCORE_EVENTS,
+            publisher_event.get_label(),
+            &peer_network_id,
+        );
+        self.update_publisher_metrics();
+    }
+
+    /// Updates the aggregate publisher metrics (i.e., the number of
+    /// scored and ignored publishers for each network).
+    fn update_publisher_metrics(&self) {
+        let time_now = self.time_service.now();
+        for network_id in [NetworkId::Validator, NetworkId::Vfn, NetworkId::Public] {
+            let mut num_scored_publishers = 0;
+            let mut num_ignored_publishers = 0;
+            for (peer_network_id, publisher_score) in &self.publisher_scores {
+                if peer_network_id.network_id() == network_id {
+                    num_scored_publishers += 1;
+                    if publisher_score.is_ignored(time_now, self.decay_half_life) {
+                        num_ignored_publishers += 1;
+                    }
+                }
+            }
+            metrics::set_gauge_with_labels(
+                &metrics::OBSERVER_PUBLISHER_COUNTS,
+                metrics::PUBLISHER_SCORED_LABEL,
+                &network_id,
+                num_scored_publishers,
+            );
+            metrics::set_gauge_with_labels(
+                &metrics::OBSERVER_PUBLISHER_COUNTS,
+                metrics::PUBLISHER_IGNORED_LABEL,
+                &network_id,
+                num_ignored_publishers,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use aptos_network::transport::ConnectionMetadata;
+    use aptos_types::PeerId;
+
+    // The decay half-life used by the tests
+    const DECAY_HALF_LIFE: Duration = Duration::from_secs(600);
+
+    #[test]
+    fn test_publisher_score_bounds() {
+        // Create a new publisher score and verify the starting score
+        let time_now = TimeService::mock().now();
+        let mut publisher_score = PublisherScore::new(time_now);
+        assert_eq!(
+            publisher_score.get_score(time_now, DECAY_HALF_LIFE),
+            STARTING_SCORE
+        );
+
+        // Send many valid messages and verify the score is capped
+        for _ in 0..1000 {
+            publisher_score.update_for_event(
+                PublisherEvent::ValidMessage,
+                time_now,
+                DECAY_HALF_LIFE,
+            );
+        }
+        assert_eq!(
+            publisher_score.get_score(time_now, DECAY_HALF_LIFE),
+            MAX_SCORE
+        );
+        assert_eq!(publisher_score.num_valid_messages, 1000);
+
+        // Send many invalid messages and verify the score is bounded
+        for _ in 0..1000 {
+            publisher_score.update_for_event(
+                PublisherEvent::InvalidMessage,
+                time_now,
+                DECAY_HALF_LIFE,
+            );
+        }
+        assert!(publisher_score.get_score(time_now, DECAY_HALF_LIFE) >= MIN_SCORE);
+        assert!(publisher_score.is_ignored(time_now, DECAY_HALF_LIFE));
+        assert_eq!(publisher_score.num_invalid_messages, 1000);
+    }
+
+    #[test]
+    fn test_publisher_score_decay() {
+        // Create the publisher scores and a peer
+        let time_service = TimeService::mock();
+        let mut publisher_scores = PublisherScores::new(DECAY_HALF_LIFE, time_service.clone());
+        let peer = PeerNetworkId::new(NetworkId::Public, PeerId::random());
+
+        // Send invalid messages from the peer until it is ignored
+        while !publisher_scores.is_ignored_publisher(&peer) {
+            publisher_scores.update_publisher_score(peer, PublisherEvent::InvalidMessage);
+        }
+        let ignored_score = publisher_scores.get_current_score(&peer).unwrap();
+
+        // Elapse half of the half-life and verify the score recovered partially
+        let mock_time_service = time_service.into_mock();
+        mock_time_service.advance(DECAY_HALF_LIFE / 2);
+        let partially_decayed_score = publisher_scores.get_current_score(&peer).unwrap();
+        assert!(partially_decayed_score > ignored_score);
+        assert!(partially_decayed_score < STARTING_SCORE);
+
+        // Elapse more time and verify the publisher is no longer ignored
+        mock_time_service.advance(DECAY_HALF_LIFE * 2);
+        assert!(!publisher_scores.is_ignored_publisher(&peer));
+        assert!(publisher_scores.get_ignored_publishers().is_empty());
+
+        // Send valid messages, elapse a long time, and verify the score returns to the start
+        for _ in 0..100 {
+            publisher_scores.update_publisher_score(peer, PublisherEvent::ValidMessage);
+        }
+        mock_time_service.advance(DECAY_HALF_LIFE * 100);
+        let decayed_score = publisher_scores.get_current_score(&peer).unwrap();
+        assert!((decayed_score - STARTING_SCORE).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_ignored_publishers() {
+        // Create the publisher scores and several peers
+        let mut publisher_scores = PublisherScores::new(DECAY_HALF_LIFE, TimeService::mock());
+        let peer_1 = PeerNetworkId::new(NetworkId::Public, PeerId::random());
+        let peer_2 = PeerNetworkId::new(NetworkId::Public, PeerId::random());
+
+        // Send valid messages from both peers and verify neither is ignored
+        publisher_scores.update_publisher_score(peer_1, PublisherEvent::ValidMessage);
+        publisher_scores.update_publisher_score(peer_2, PublisherEvent::ValidMessage);
+        assert!(publisher_scores.get_ignored_publishers().is_empty());
+
+        // Send invalid messages from the first peer until it is ignored
+        let mut num_invalid_messages = 0;
+        while !publisher_scores.is_ignored_publisher(&peer_1) {
+            publisher_scores.update_publisher_score(peer_1, PublisherEvent::InvalidMessage);
+            num_invalid_messages += 1;
+        }
+        assert_eq!(publisher_scores.get_ignored_publishers(), vec![peer_1]);
+
+        // Verify a lagging publisher takes longer to be ignored than an invalid one
+        let mut num_lagging_messages = 0;
+        while !publisher_scores.is_ignored_publisher(&peer_2) {
+            publisher_scores.update_publisher_score(peer_2, PublisherEvent::LaggingMessage);
+            num_lagging_messages += 1;
+        }
+        assert!(num_lagging_messages > num_invalid_messages);
+
+        // Verify the counts for the second peer
+        let publisher_score = publisher_scores.get_publisher_score(&peer_2).unwrap();
+        assert_eq!(publisher_score.num_valid_messages, 1);
+        assert_eq!(publisher_score.num_lagging_messages, num_lagging_messages);
+        assert_eq!(publisher_score.num_invalid_messages, 0);
+    }
+
+    #[test]
+    fn test_remove_disconnected_publishers() {
+        // Create the publisher scores and several peers
+        let mut publisher_scores = PublisherScores::new(DECAY_HALF_LIFE, TimeService::mock());
+        let peer_1 = PeerNetworkId::new(NetworkId::Public, PeerId::random());
+        let peer_2 = PeerNetworkId::new(NetworkId::Vfn, PeerId::random());
+
+        // Send messages from both peers and verify both are scored
+        publisher_scores.update_publisher_score(peer_1, PublisherEvent::ValidMessage);
+        publisher_scores.update_publisher_score(peer_2, PublisherEvent::InvalidMessage);
+        assert!(publisher_scores.get_publisher_score(&peer_1).is_some());
+        assert!(publisher_scores.get_publisher_score(&peer_2).is_some());
+
+        // Remove the publishers that are disconnected (only the first peer is connected)
+        let connected_peers_and_metadata = HashMap::from([(
+            peer_1,
+            PeerMetadata::new(ConnectionMetadata::mock(peer_1.peer_id())),
+        )]);
+        publisher_scores.remove_disconnected_publishers(&connected_peers_and_metadata);
+
+        // Verify only the connected peer is still scored
+        assert!(publisher_scores.get_publisher_score(&peer_1).is_some());
+        assert!(publisher_scores.get_publisher_score(&peer_2).is_none());
+
+        // Remove all publishers and verify none are scored
+        publisher_scores.remove_disconnected_publishers(&HashMap::new());
+        assert!(publisher_scores.get_publisher_score(&peer_1).is_none());
+    }
+
+    #[test]
+    fn test_unknown_publisher_not_ignored() {
+        // Verify that an unknown publisher is not ignored
+        let publisher_scores = PublisherScores::new(DECAY_HALF_LIFE, TimeService::mock());
+        let peer = PeerNetworkId::new(NetworkId::Public, PeerId::random());
+        assert!(!publisher_scores.is_ignored_publisher(&peer));
+        assert!(publisher_scores.get_publisher_score(&peer).is_none());
+    }
+}
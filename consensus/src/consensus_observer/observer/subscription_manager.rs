@@ -13,7 +13,11 @@ use crate::consensus_observer::{
             ConsensusObserverMessage, ConsensusObserverRequest, ConsensusObserverResponse,
         },
     },
-    observer::{subscription::ConsensusObserverSubscription, subscription_utils},
+    observer::{
+        publisher_scores::{PublisherEvent, PublisherScores},
+        subscription::ConsensusObserverSubscription,
+        subscription_utils,
+    },
     publisher::consensus_publisher::ConsensusPublisher,
 };
 use aptos_config::{config::ConsensusObserverConfig, network_id::PeerNetworkId};
@@ -22,8 +26,9 @@ use aptos_logger::{info, warn};
 use aptos_network::application::{interface::NetworkClient, metadata::PeerMetadata};
 use aptos_storage_interface::DbReader;
 use aptos_time_service::TimeService;
+use aptos_types::block_info::Round;
 use itertools::Itertools;
-use std::{collections::HashMap, sync::Arc};
+use std::{collections::HashMap, sync::Arc, time::Duration};
 use tokio::task::JoinHandle;
 
 /// The manager for consensus observer subscriptions
@@ -48,6 +53,9 @@ pub struct SubscriptionManager {
     // A handle to storage (used to read the latest state and check progress)
     db_reader: Arc<dyn DbReader>,
 
+    // The scores of the publishers we've received messages from
+    publisher_scores: Arc<Mutex<PublisherScores>>,
+
     // The time service (used to check progress)
     time_service: TimeService,
 }
@@ -62,6 +70,12 @@ impl SubscriptionManager {
         db_reader: Arc<dyn DbReader>,
         time_service: TimeService,
     ) -> Self {
+        // Create the publisher scores
+        let publisher_score_decay_half_life =
+            Duration::from_millis(consensus_observer_config.publisher_score_decay_half_life_ms);
+        let publisher_scores =
+            PublisherScores::new(publisher_score_decay_half_life, time_service.clone());
+
         Self {
             active_observer_subscriptions: Arc::new(Mutex::new(HashMap::new())),
             active_subscription_creation_task: Arc::new(Mutex::new(None)),
@@ -69,6 +83,7 @@ impl SubscriptionManager {
             consensus_observer_config,
             consensus_publisher,
             db_reader,
+            publisher_scores: Arc::new(Mutex::new(publisher_scores)),
             time_service,
         }
     }
@@ -87,15 +102,43 @@ impl SubscriptionManager {
 
         // Check the health of the subscription
         match active_subscription {
-            Some(active_subscription) => active_subscription.check_subscription_health(
-                connected_peers_and_metadata,
-                skip_peer_optimality_check,
-            ),
-            None => Err(Error::UnexpectedError(format!(
-                "The subscription to peer: {:?} is not active!",
-                peer_network_id
-            ))),
+            Some(active_subscription) => {
+                active_subscription.check_subscription_health(
+                    connected_peers_and_metadata,
+                    skip_peer_optimality_check,
+                )?;
+            },
+            None => {
+                return Err(Error::UnexpectedError(format!(
+                    "The subscription to peer: {:?} is not active!",
+                    peer_network_id
+                )));
+            },
+        }
+
+        // Verify the publisher score is still acceptable (if scoring is enabled)
+        self.check_publisher_score(&peer_network_id)
+    }
+
+    /// Checks if the score of the given publisher is acceptable. If publisher
+    /// scoring is disabled, the score is always considered acceptable.
+    fn check_publisher_score(&self, peer_network_id: &PeerNetworkId) -> Result<(), Error> {
+        // If publisher scoring is disabled, there's nothing to check
+        if !self.consensus_observer_config.enable_publisher_scoring {
+            return Ok(());
+        }
+
+        // Verify the publisher is not ignored
+        let publisher_scores = self.publisher_scores.lock();
+        if publisher_scores.is_ignored_publisher(peer_network_id) {
+            let publisher_score = publisher_scores.get_current_score(peer_network_id);
+            return Err(Error::SubscriptionPublisherScoreTooLow(format!(
+                "The publisher: {:?} has a score that is too low: {:?}",
+                peer_network_id, publisher_score
+            )));
         }
+
+        Ok(())
     }
 
     /// Checks the health of the active subscriptions. If any subscription is
@@ -125,11 +168,19 @@ impl SubscriptionManager {
         // Update the total subscription metrics
         update_total_subscription_metrics(&remaining_subscription_peers);
 
+        // Remove the scores of disconnected publishers, and identify
+        // the publishers to ignore when creating new subscriptions.
+        self.publisher_scores
+            .lock()
+            .remove_disconnected_publishers(&connected_peers_and_metadata);
+        let ignored_publishers = self.get_ignored_publishers();
+
         // Spawn a task to create the new subscriptions (asynchronously)
         self.spawn_subscription_creation_task(
             num_subscriptions_to_create,
             remaining_subscription_peers,
             terminated_subscriptions,
+            ignored_publishers,
             connected_peers_and_metadata,
         )
         .await;
@@ -145,6 +196,16 @@ impl SubscriptionManager {
         }
     }
 
+    /// Returns the publishers that should be ignored when creating
+    /// new subscriptions (i.e., those with low scores).
+    fn get_ignored_publishers(&self) -> Vec<PeerNetworkId> {
+        if self.consensus_observer_config.enable_publisher_scoring {
+            self.publisher_scores.lock().get_ignored_publishers()
+        } else {
+            vec![]
+        }
+    }
+
     /// Returns the currently active subscription peers
     fn get_active_subscription_peers(&self) -> Vec<PeerNetworkId> {
         let active_observer_subscriptions = self.active_observer_subscriptions.lock();
@@ -178,6 +239,7 @@ impl SubscriptionManager {
         num_subscriptions_to_create: usize,
         active_subscription_peers: Vec<PeerNetworkId>,
         terminated_subscriptions: Vec<(PeerNetworkId, Error)>,
+        ignored_publishers: Vec<PeerNetworkId>,
         connected_peers_and_metadata: HashMap<PeerNetworkId, PeerMetadata>,
     ) {
         // If there are no new subscriptions to create, return early
@@ -202,10 +264,13 @@ impl SubscriptionManager {
 
         // Spawn a new subscription creation task
         let subscription_creation_task = tokio::spawn(async move {
-            // Identify the terminated subscription peers
-            let terminated_subscription_peers = terminated_subscriptions
+            // Identify the unhealthy subscription peers (i.e., the
+            // terminated subscriptions and the ignored publishers).
+            let unhealthy_subscription_peers = terminated_subscriptions
                 .iter()
                 .map(|(peer, _)| *peer)
+                .chain(ignored_publishers)
+                .unique()
                 .collect();
 
             // Create the new subscriptions
@@ -218,7 +283,7 @@ impl SubscriptionManager {
                 connected_peers_and_metadata,
                 num_subscriptions_to_create,
                 active_subscription_peers,
-                terminated_subscription_peers,
+                unhealthy_subscription_peers,
             )
             .await;
 
@@ -354,6 +419,45 @@ impl SubscriptionManager {
         });
     }
 
+    /// Updates the score of the given publisher based on the observed
+    /// event. If publisher scoring is disabled, this is a no-op.
+    pub fn update_publisher_score(
+        &self,
+        peer_network_id: PeerNetworkId,
+        publisher_event: PublisherEvent,
+    ) {
+        if self.consensus_observer_config.enable_publisher_scoring {
+            self.publisher_scores
+                .lock()
+                .update_publisher_score(peer_network_id, publisher_event);
+        }
+    }
+
+    /// Updates the score of the given publisher for a stale (i.e., out of date
+    /// or duplicate) message. The publisher is only penalized if the message
+    /// trails the given epoch and round by more than the configured threshold.
+    /// Otherwise, the message was likely delivered first by another publisher.
+    pub fn update_publisher_score_for_stale_message(
+        &self,
+        peer_network_id: PeerNetworkId,
+        message_epoch_round: (u64, Round),
+        latest_epoch_round: (u64, Round),
+    ) {
+        // Determine if the publisher is lagging behind
+        let (message_epoch, message_round) = message_epoch_round;
+        let (latest_epoch, latest_round) = latest_epoch_round;
+        let lag_threshold = self
+            .consensus_observer_config
+            .publisher_lag_threshold_rounds;
+        let publisher_lagging = message_epoch < latest_epoch
+            || latest_round.saturating_sub(message_round) > lag_threshold;
+
+        // Penalize the publisher if it is lagging
+        if publisher_lagging {
+            self.update_publisher_score(peer_network_id, PublisherEvent::LaggingMessage);
+        }
+    }
+
     /// Verifies that the message is from an active
     /// subscription. If not, an error is returned.
     pub fn verify_message_for_subscription(
@@ -705,6 +809,70 @@ mod test {
         verify_active_subscription_peers(&subscription_manager, vec![]);
     }
 
+    #[tokio::test]
+    async fn test_check_subscription_health_publisher_score() {
+        // Create a consensus observer client
+        let network_id = NetworkId::Public;
+        let (peers_and_metadata, consensus_observer_client) =
+            create_consensus_observer_client(&[network_id]);
+
+        // Create a new subscription manager (with publisher scoring enabled)
+        let consensus_observer_config = ConsensusObserverConfig {
+            enable_publisher_scoring: true,
+            ..ConsensusObserverConfig::default()
+        };
+        let db_reader = create_mock_db_reader();
+        let time_service = TimeService::mock();
+        let mut subscription_manager = SubscriptionManager::new(
+            consensus_observer_client,
+            consensus_observer_config,
+            None,
+            db_reader.clone(),
+            time_service.clone(),
+        );
+
+        // Add a new connected peer
+        let connected_peer =
+            create_peer_and_connection(network_id, peers_and_metadata.clone(), 1, None, true);
+
+        // Create a subscription to the new peer
+        create_observer_subscription(
+            &mut subscription_manager,
+            consensus_observer_config,
+            db_reader.clone(),
+            connected_peer,
+            time_service.clone(),
+        );
+
+        // Update the publisher score with valid messages and verify the subscription is healthy
+        for _ in 0..10 {
+            subscription_manager
+                .update_publisher_score(connected_peer, PublisherEvent::ValidMessage);
+        }
+        check_subscription_publisher_score(&mut subscription_manager, connected_peer, true);
+
+        // Terminate unhealthy subscriptions and verify none are removed
+        verify_terminated_unhealthy_subscriptions(&mut subscription_manager, vec![]);
+
+        // Update the publisher score with invalid messages until the publisher is ignored
+        for _ in 0..10 {
+            subscription_manager
+                .update_publisher_score(connected_peer, PublisherEvent::InvalidMessage);
+        }
+
+        // Check the active subscription and verify that it is unhealthy (the score is too low)
+        check_subscription_publisher_score(&mut subscription_manager, connected_peer, false);
+
+        // Terminate unhealthy subscriptions and verify the subscription was removed
+        verify_terminated_unhealthy_subscriptions(&mut subscription_manager, vec![connected_peer]);
+
+        // Verify the active subscription is no longer present, and the publisher is ignored
+        verify_active_subscription_peers(&subscription_manager, vec![]);
+        assert_eq!(subscription_manager.get_ignored_publishers(), vec![
+            connected_peer
+        ]);
+    }
+
     #[tokio::test]
     async fn test_check_subscription_health_suboptimal() {
         // Create a consensus observer config
@@ -799,7 +967,7 @@ mod test {
 
         // Spawn a subscription creation task with 0 subscriptions to create
         subscription_manager
-            .spawn_subscription_creation_task(0, vec![], vec![], hashmap![])
+            .spawn_subscription_creation_task(0, vec![], vec![], vec![], hashmap![])
             .await;
 
         // Verify that the active subscription creation task is still empty (no task was spawned)
@@ -807,7 +975,7 @@ mod test {
 
         // Spawn a subscription creation task with 1 subscription to create
         subscription_manager
-            .spawn_subscription_creation_task(1, vec![], vec![], hashmap![])
+            .spawn_subscription_creation_task(1, vec![], vec![], vec![], hashmap![])
             .await;
 
         // Verify that the active subscription creation task is now populated
@@ -836,7 +1004,7 @@ mod test {
 
         // Spawn a subscription creation task with 2 subscriptions to create
         subscription_manager
-            .spawn_subscription_creation_task(2, vec![], vec![], hashmap![])
+            .spawn_subscription_creation_task(2, vec![], vec![], vec![], hashmap![])
             .await;
 
         // Verify the new active subscription creation task is not finished
@@ -1021,6 +1189,62 @@ mod test {
         verify_active_subscription_peers(&subscription_manager, vec![subscription_peer_2]);
     }
 
+    #[tokio::test]
+    async fn test_update_publisher_score_for_stale_message() {
+        // Create a consensus observer client
+        let network_id = NetworkId::Public;
+        let (peers_and_metadata, consensus_observer_client) =
+            create_consensus_observer_client(&[network_id]);
+
+        // Create a new subscription manager (with publisher scoring enabled)
+        let consensus_observer_config = ConsensusObserverConfig {
+            enable_publisher_scoring: true,
+            ..ConsensusObserverConfig::default()
+        };
+        let subscription_manager = SubscriptionManager::new(
+            consensus_observer_client,
+            consensus_observer_config,
+            None,
+            create_mock_db_reader(),
+            TimeService::mock(),
+        );
+
+        // Add a new connected peer
+        let connected_peer =
+            create_peer_and_connection(network_id, peers_and_metadata.clone(), 1, None, true);
+
+        // Process many stale messages that are within the lag threshold
+        let lag_threshold_rounds = consensus_observer_config.publisher_lag_threshold_rounds;
+        for _ in 0..100 {
+            subscription_manager.update_publisher_score_for_stale_message(
+                connected_peer,
+                (10, 100),
+                (10, 100 + lag_threshold_rounds),
+            );
+        }
+
+        // Verify the publisher was not penalized (the messages were just duplicates)
+        assert!(subscription_manager
+            .publisher_scores
+            .lock()
+            .get_publisher_score(&connected_peer)
+            .is_none());
+
+        // Process many stale messages that are beyond the lag threshold
+        for _ in 0..100 {
+            subscription_manager.update_publisher_score_for_stale_message(
+                connected_peer,
+                (10, 100),
+                (10, 100 + lag_threshold_rounds + 1),
+            );
+        }
+
+        // Verify the publisher is now ignored
+        assert_eq!(subscription_manager.get_ignored_publishers(), vec![
+            connected_peer
+        ]);
+    }
+
     #[tokio::test]
     async fn test_verify_message_for_subscription() {
         // Create a consensus observer client
@@ -1166,6 +1390,28 @@ mod test {
         }
     }
 
+    /// Checks the health of a subscription and verifies the publisher score status
+    fn check_subscription_publisher_score(
+        subscription_manager: &mut SubscriptionManager,
+        subscription_peer: PeerNetworkId,
+        expect_acceptable_score: bool,
+    ) {
+        // Check the health of the subscription
+        let connected_peers_and_metadata = subscription_manager.get_connected_peers_and_metadata();
+        let result = subscription_manager.check_subscription_health(
+            &connected_peers_and_metadata,
+            subscription_peer,
+            false,
+        );
+
+        // Check the result based on the expected score status
+        if expect_acceptable_score {
+            assert!(result.is_ok());
+        } else {
+            assert_matches!(result, Err(Error::SubscriptionPublisherScoreTooLow(_)));
+        }
+    }
+
     /// Checks the health of a subscription and verifies the timeout status
     fn check_subscription_timeout(
         subscription_manager: &mut SubscriptionManager,
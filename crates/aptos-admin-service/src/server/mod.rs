@@ -28,12 +28,14 @@ use tokio::runtime::Runtime;
 
 mod consensus;
 mod mempool;
+mod storage;
 
 #[derive(Default)]
 pub struct Context {
     authentication_configs: Vec<AuthenticationConfig>,
 
     aptos_db: RwLock<Option<Arc<DbReaderWriter>>>,
+    db_checkpoint_status: Arc<RwLock<storage::DbCheckpointStatus>>,
     consensus_db: RwLock<Option<Arc<StorageWriteProxy>>>,
     quorum_store_db: RwLock<Option<Arc<QuorumStoreDB>>>,
     mempool_client_sender: RwLock<Option<MempoolClientSender>>,
@@ -222,6 +224,29 @@ impl AdminService {
                     ))
                 }
             },
+            (hyper::Method::POST, "/debug/storage/checkpoint") => {
+                let aptos_db = context.aptos_db.read().clone();
+                if let Some(aptos_db) = aptos_db {
+                    storage::handle_create_db_checkpoint_request(
+                        req,
+                        aptos_db,
+                        context.db_checkpoint_status.clone(),
+                    )
+                    .await
+                } else {
+                    Ok(reply_with_status(
+                        StatusCode::NOT_FOUND,
+                        "Aptos db is not available.",
+                    ))
+                }
+            },
+            (hyper::Method::GET, "/debug/storage/checkpoint") => {
+                storage::handle_db_checkpoint_status_request(
+                    req,
+                    context.db_checkpoint_status.clone(),
+                )
+                .await
+            },
             (hyper::Method::GET, "/debug/mempool/parking-lot/addresses") => {
                 let mempool_client_sender = context.mempool_client_sender.read().clone();
                 if mempool_client_sender.is_some() {
@@ -0,0 +1,185 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::Result;
+use aptos_infallible::{duration_since_epoch, RwLock};
+use aptos_logger::{error, info};
+use aptos_storage_interface::{DbCheckpointInfo, DbReaderWriter};
+use aptos_system_utils::utils::{reply_with, reply_with_status};
+use aptos_types::{transaction::Version, waypoint::Waypoint};
+use http::header::{HeaderValue, CONTENT_TYPE};
+use hyper::{Body, Request, Response, StatusCode};
+use serde::Serialize;
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Instant,
+};
+
+/// The file (in the checkpoint directory) describing a DB checkpoint.
+pub const CHECKPOINT_METADATA_FILE_NAME: &str = "checkpoint_metadata.json";
+
+/// The status of the latest DB checkpoint requested through the admin service.
+#[derive(Clone, Debug, Default, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum DbCheckpointStatus {
+    #[default]
+    NotStarted,
+    InProgress {
+        path: PathBuf,
+        started_at_usecs: u64,
+        /// The current stage of the checkpoint (e.g., the name of the DB being checkpointed).
+        stage: String,
+    },
+    Succeeded {
+        path: PathBuf,
+        metadata: DbCheckpointMetadata,
+    },
+    Failed {
+        path: PathBuf,
+        error: String,
+    },
+}
+
+/// The content of the checkpoint metadata file.
+#[derive(Clone, Debug, Serialize)]
+pub struct DbCheckpointMetadata {
+    /// The latest committed version in the checkpoint.
+    pub version: Version,
+    /// The epoch of the latest ledger info in the checkpoint.
+    pub epoch: Option<u64>,
+    /// The version of the latest ledger info in the checkpoint.
+    pub ledger_info_version: Option<Version>,
+    /// The timestamp of the latest ledger info in the checkpoint.
+    pub ledger_info_timestamp_usecs: Option<u64>,
+    /// The waypoint of the latest ledger info, which a node seeded from the checkpoint can trust.
+    pub waypoint: Option<String>,
+    pub created_at_usecs: u64,
+    pub duration_ms: u64,
+}
+
+pub async fn handle_create_db_checkpoint_request(
+    req: Request<Body>,
+    aptos_db: Arc<DbReaderWriter>,
+    checkpoint_status: Arc<RwLock<DbCheckpointStatus>>,
+) -> hyper::Result<Response<Body>> {
+    let query = req.uri().query().unwrap_or("");
+    let query_pairs: HashMap<_, _> = url::form_urlencoded::parse(query.as_bytes()).collect();
+    let path = match query_pairs.get("path") {
+        Some(path) => PathBuf::from(path.as_ref()),
+        None => {
+            return Ok(reply_with_status(
+                StatusCode::BAD_REQUEST,
+                "Missing `path` in query.",
+            ))
+        },
+    };
+
+    {
+        let mut checkpoint_status = checkpoint_status.write();
+        if let DbCheckpointStatus::InProgress { path, .. } = &*checkpoint_status {
+            return Ok(reply_with_status(
+                StatusCode::CONFLICT,
+                format!("DB checkpoint at {path:?} is already in progress."),
+            ));
+        }
+        *checkpoint_status = DbCheckpointStatus::InProgress {
+            path: path.clone(),
+            started_at_usecs: duration_since_epoch().as_micros() as u64,
+            stage: "starting".to_string(),
+        };
+    }
+
+    info!("Creating DB checkpoint at {path:?}.");
+    let message = format!("Started creating DB checkpoint at {path:?}.");
+    let checkpoint_path = path.clone();
+    let task_checkpoint_status = checkpoint_status.clone();
+    let checkpoint_task = tokio::task::spawn_blocking(move || {
+        let report_stage = |new_stage: &str| {
+            if let DbCheckpointStatus::InProgress { stage, .. } =
+                &mut *task_checkpoint_status.write()
+            {
+                *stage = new_stage.to_string();
+            }
+        };
+        create_db_checkpoint(aptos_db.as_ref(), &checkpoint_path, &report_stage)
+    });
+    // The checkpoint task is awaited separately, so that the status is updated even if the
+    // task panics or is cancelled.
+    tokio::spawn(async move {
+        let new_status = match checkpoint_task.await {
+            Ok(Ok(metadata)) => {
+                info!("Finished creating DB checkpoint at {path:?}: {metadata:?}");
+                DbCheckpointStatus::Succeeded { path, metadata }
+            },
+            Ok(Err(e)) => {
+                error!("Failed to create DB checkpoint at {path:?}: {e:?}");
+                DbCheckpointStatus::Failed {
+                    path,
+                    error: e.to_string(),
+                }
+            },
+            Err(e) => {
+                error!("DB checkpoint task at {path:?} panicked or was cancelled: {e:?}");
+                DbCheckpointStatus::Failed {
+                    path,
+                    error: format!("The checkpoint task failed: {e}"),
+                }
+            },
+        };
+        *checkpoint_status.write() = new_status;
+    });
+
+    Ok(reply_with_status(StatusCode::ACCEPTED, message))
+}
+
+pub async fn handle_db_checkpoint_status_request(
+    _req: Request<Body>,
+    checkpoint_status: Arc<RwLock<DbCheckpointStatus>>,
+) -> hyper::Result<Response<Body>> {
+    let checkpoint_status = checkpoint_status.read().clone();
+    match serde_json::to_string_pretty(&checkpoint_status) {
+        Ok(json) => Ok(reply_with(
+            vec![(CONTENT_TYPE, HeaderValue::from_static("application/json"))],
+            json,
+        )),
+        Err(e) => Ok(reply_with_status(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            e.to_string(),
+        )),
+    }
+}
+
+fn create_db_checkpoint(
+    aptos_db: &DbReaderWriter,
+    path: &Path,
+    report_stage: &dyn Fn(&str),
+) -> Result<DbCheckpointMetadata> {
+    let start = Instant::now();
+    let DbCheckpointInfo {
+        version,
+        ledger_info,
+    } = aptos_db
+        .writer
+        .create_online_checkpoint(path, report_stage)?;
+
+    let ledger_info = ledger_info.as_ref().map(|li| li.ledger_info());
+    let metadata = DbCheckpointMetadata {
+        version,
+        epoch: ledger_info.map(|li| li.epoch()),
+        ledger_info_version: ledger_info.map(|li| li.version()),
+        ledger_info_timestamp_usecs: ledger_info.map(|li| li.timestamp_usecs()),
+        waypoint: ledger_info.map(|li| Waypoint::new_any(li).to_string()),
+        created_at_usecs: duration_since_epoch().as_micros() as u64,
+        duration_ms: start.elapsed().as_millis() as u64,
+    };
+    report_stage("writing_metadata");
+    fs::write(
+        path.join(CHECKPOINT_METADATA_FILE_NAME),
+        serde_json::to_vec_pretty(&metadata)?,
+    )?;
+
+    Ok(metadata)
+}
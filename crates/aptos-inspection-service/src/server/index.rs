@@ -3,7 +3,8 @@
 
 use crate::{
     server::utils::CONTENT_TYPE_TEXT, CONFIGURATION_PATH, CONSENSUS_HEALTH_CHECK_PATH,
-    FORGE_METRICS_PATH, JSON_METRICS_PATH, METRICS_PATH, PEER_INFORMATION_PATH,
+    CONSENSUS_OBSERVER_PUBLISHERS_PATH, FORGE_METRICS_PATH, JSON_METRICS_PATH, METRICS_PATH,
+    PEER_INFORMATION_PATH, STATE_SYNC_STREAM_PROGRESS_PATH, STORAGE_SCRUBBER_PATH,
     SYSTEM_INFORMATION_PATH,
 };
 use hyper::{Body, StatusCode};
@@ -27,10 +28,13 @@ fn get_index_response() -> String {
     index_response.push("The following endpoints are available:".into());
     index_response.push(format!("\t- {}", CONFIGURATION_PATH));
     index_response.push(format!("\t- {}", CONSENSUS_HEALTH_CHECK_PATH));
+    index_response.push(format!("\t- {}", CONSENSUS_OBSERVER_PUBLISHERS_PATH));
     index_response.push(format!("\t- {}", FORGE_METRICS_PATH));
     index_response.push(format!("\t- {}", JSON_METRICS_PATH));
     index_response.push(format!("\t- {}", METRICS_PATH));
     index_response.push(format!("\t- {}", PEER_INFORMATION_PATH));
+    index_response.push(format!("\t- {}", STATE_SYNC_STREAM_PROGRESS_PATH));
+    index_response.push(format!("\t- {}", STORAGE_SCRUBBER_PATH));
     index_response.push(format!("\t- {}", SYSTEM_INFORMATION_PATH));
 
     index_response.join("\n") // Separate each entry with a newline
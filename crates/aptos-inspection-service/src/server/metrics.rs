@@ -9,10 +9,20 @@ use crate::server::{
 use aptos_config::config::NodeConfig;
 use hyper::{Body, StatusCode};
 use prometheus::TextEncoder;
+use std::collections::BTreeMap;
 
 // The metric key for the consensus execution gauge
 const CONSENSUS_EXECUTION_GAUGE: &str = "aptos_state_sync_consensus_executing_gauge{}";
 
+// The metric name prefix for the consensus observer publisher metrics
+const CONSENSUS_OBSERVER_PUBLISHER_METRICS: &str = "consensus_observer_publisher";
+
+// The metric name for the state sync stream progress
+const STATE_SYNC_STREAM_PROGRESS: &str = "aptos_state_sync_stream_progress";
+
+// The metric prefix for the storage consistency scrubber
+const STORAGE_SCRUBBER: &str = "aptos_storage_scrubber";
+
 /// Handles a consensus health check request. This method returns
 /// 200 if the node is currently participating in consensus.
 ///
@@ -47,6 +57,96 @@ pub async fn handle_consensus_health_check(node_config: &NodeConfig) -> (StatusC
     )
 }
 
+/// Handles a consensus observer publishers request. This method returns the
+/// aggregate publisher metrics (e.g., the number of scored and ignored publishers).
+pub fn handle_consensus_observer_publishers_request(
+    node_config: &NodeConfig,
+) -> (StatusCode, Body, String) {
+    // Verify the observer is enabled. If not, return an error.
+    let consensus_observer_config = &node_config.consensus_observer;
+    if !consensus_observer_config.observer_enabled {
+        return (
+            StatusCode::BAD_REQUEST,
+            Body::from("The consensus observer is not enabled!"),
+            CONTENT_TYPE_TEXT.into(),
+        );
+    }
+
+    // Gather the publisher metrics (sorted by metric key)
+    let publisher_metrics: BTreeMap<String, String> = utils::get_all_metrics()
+        .into_iter()
+        .filter(|(metric_key, _)| metric_key.starts_with(CONSENSUS_OBSERVER_PUBLISHER_METRICS))
+        .collect();
+
+    // Display the publisher metrics
+    let mut publisher_metrics_output = vec![format!(
+        "Publisher scoring enabled: {}",
+        consensus_observer_config.enable_publisher_scoring
+    )];
+    publisher_metrics_output.push("Publisher metrics:".into());
+    for (metric_key, value) in publisher_metrics {
+        publisher_metrics_output.push(format!("\t{} => {}", metric_key, value));
+    }
+
+    (
+        StatusCode::OK,
+        Body::from(publisher_metrics_output.join("\n")),
+        CONTENT_TYPE_TEXT.into(),
+    )
+}
+
+/// Handles a state sync stream progress request. This method returns the
+/// progress, throughput and ETA of the long-running state sync data streams.
+/// Note: an ETA of -1 indicates that the ETA is not yet known.
+pub fn handle_state_sync_stream_progress_request() -> (StatusCode, Body, String) {
+    // Gather the stream progress metrics (sorted by metric key)
+    let stream_progress: BTreeMap<String, String> = utils::get_all_metrics()
+        .into_iter()
+        .filter(|(metric_key, _)| metric_key.starts_with(STATE_SYNC_STREAM_PROGRESS))
+        .collect();
+
+    // Display the stream progress
+    let mut stream_progress_output = vec![format!(
+        "Number of stream progress metrics: {}",
+        stream_progress.len()
+    )];
+    for (metric_key, value) in stream_progress {
+        stream_progress_output.push(format!("\t{} => {}", metric_key, value));
+    }
+
+    (
+        StatusCode::OK,
+        Body::from(stream_progress_output.join("\n")),
+        CONTENT_TYPE_TEXT.into(),
+    )
+}
+
+/// Handles a storage scrubber request. This method returns the progress of the
+/// storage consistency scrubber, and the number of corruptions it found (per check).
+/// Note: the details of each corruption are logged by the scrubber.
+pub fn handle_storage_scrubber_request() -> (StatusCode, Body, String) {
+    // Gather the scrubber metrics (sorted by metric key)
+    let scrubber_metrics: BTreeMap<String, String> = utils::get_all_metrics()
+        .into_iter()
+        .filter(|(metric_key, _)| metric_key.starts_with(STORAGE_SCRUBBER))
+        .collect();
+
+    // Display the scrubber metrics
+    let mut scrubber_output = vec![format!(
+        "Number of storage scrubber metrics: {}",
+        scrubber_metrics.len()
+    )];
+    for (metric_key, value) in scrubber_metrics {
+        scrubber_output.push(format!("\t{} => {}", metric_key, value));
+    }
+
+    (
+        StatusCode::OK,
+        Body::from(scrubber_output.join("\n")),
+        CONTENT_TYPE_TEXT.into(),
+    )
+}
+
 /// Handles a new forge metrics request
 pub fn handle_forge_metrics() -> (StatusCode, Body, String) {
     // Get and encode the metrics
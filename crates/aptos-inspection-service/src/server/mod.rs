@@ -31,11 +31,14 @@ mod tests;
 // The list of endpoints offered by the inspection service
 pub const CONFIGURATION_PATH: &str = "/configuration";
 pub const CONSENSUS_HEALTH_CHECK_PATH: &str = "/consensus_health_check";
+pub const CONSENSUS_OBSERVER_PUBLISHERS_PATH: &str = "/consensus_observer_publishers";
 pub const FORGE_METRICS_PATH: &str = "/forge_metrics";
 pub const INDEX_PATH: &str = "/";
 pub const JSON_METRICS_PATH: &str = "/json_metrics";
 pub const METRICS_PATH: &str = "/metrics";
 pub const PEER_INFORMATION_PATH: &str = "/peer_information";
+pub const STATE_SYNC_STREAM_PROGRESS_PATH: &str = "/state_sync_stream_progress";
+pub const STORAGE_SCRUBBER_PATH: &str = "/storage_scrubber";
 pub const SYSTEM_INFORMATION_PATH: &str = "/system_information";
 
 // Useful string constants
@@ -117,6 +120,11 @@ async fn serve_requests(
             // Exposes the consensus health check
             metrics::handle_consensus_health_check(&node_config).await
         },
+        CONSENSUS_OBSERVER_PUBLISHERS_PATH => {
+            // /consensus_observer_publishers
+            // Exposes the consensus observer publisher scores
+            metrics::handle_consensus_observer_publishers_request(&node_config)
+        },
         FORGE_METRICS_PATH => {
             // /forge_metrics
             // Exposes forge encoded metrics
@@ -146,6 +154,16 @@ async fn serve_requests(
                 peers_and_metadata,
             )
         },
+        STATE_SYNC_STREAM_PROGRESS_PATH => {
+            // /state_sync_stream_progress
+            // Exposes the progress, throughput and ETA of state sync data streams
+            metrics::handle_state_sync_stream_progress_request()
+        },
+        STORAGE_SCRUBBER_PATH => {
+            // /storage_scrubber
+            // Exposes the progress and the corruptions found by the storage consistency scrubber
+            metrics::handle_storage_scrubber_request()
+        },
         SYSTEM_INFORMATION_PATH => {
             // /system_information
             // Exposes the system and build information
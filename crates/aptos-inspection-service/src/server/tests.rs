@@ -7,8 +7,9 @@ use crate::{
         peer_information::PEER_INFO_DISABLED_MESSAGE, serve_requests,
         system_information::SYS_INFO_DISABLED_MESSAGE, utils::get_all_metrics,
     },
-    CONFIGURATION_PATH, FORGE_METRICS_PATH, INDEX_PATH, JSON_METRICS_PATH, METRICS_PATH,
-    PEER_INFORMATION_PATH, SYSTEM_INFORMATION_PATH,
+    CONFIGURATION_PATH, CONSENSUS_OBSERVER_PUBLISHERS_PATH, FORGE_METRICS_PATH, INDEX_PATH,
+    JSON_METRICS_PATH, METRICS_PATH, PEER_INFORMATION_PATH, STATE_SYNC_STREAM_PROGRESS_PATH,
+    STORAGE_SCRUBBER_PATH, SYSTEM_INFORMATION_PATH,
 };
 use aptos_config::config::{AptosDataClientConfig, BaseConfig, NodeConfig};
 use aptos_data_client::client::AptosDataClient;
@@ -20,7 +21,10 @@ use assert_approx_eq::assert_approx_eq;
 use futures::executor::block_on;
 use hyper::{body, Body, Method, Request, Response, StatusCode};
 use once_cell::sync::Lazy;
-use prometheus::{proto::MetricFamily, register_int_counter, Counter, IntCounter, Opts, Registry};
+use prometheus::{
+    proto::MetricFamily, register_gauge_vec, register_int_counter, register_int_counter_vec,
+    Counter, IntCounter, Opts, Registry,
+};
 use rusty_fork::rusty_fork_test;
 use std::{collections::HashMap, io::read_to_string, string::String, sync::Arc};
 
@@ -86,13 +90,93 @@ async fn test_inspect_index() {
     // Verify that the response contains all the endpoints
     assert_eq!(response.status(), StatusCode::OK);
     assert!(response_body_string.contains(CONFIGURATION_PATH));
+    assert!(response_body_string.contains(CONSENSUS_OBSERVER_PUBLISHERS_PATH));
     assert!(response_body_string.contains(FORGE_METRICS_PATH));
     assert!(response_body_string.contains(JSON_METRICS_PATH));
     assert!(response_body_string.contains(METRICS_PATH));
     assert!(response_body_string.contains(PEER_INFORMATION_PATH));
+    assert!(response_body_string.contains(STATE_SYNC_STREAM_PROGRESS_PATH));
+    assert!(response_body_string.contains(STORAGE_SCRUBBER_PATH));
     assert!(response_body_string.contains(SYSTEM_INFORMATION_PATH));
 }
 
+#[tokio::test]
+async fn test_inspect_consensus_observer_publishers() {
+    // Create a PFN config
+    let mut config = NodeConfig::get_default_pfn_config();
+
+    // Disable the consensus observer and ping the endpoint
+    config.consensus_observer.observer_enabled = false;
+    let mut response = send_get_request_to_path(&config, CONSENSUS_OBSERVER_PUBLISHERS_PATH).await;
+    let response_body = body::to_bytes(response.body_mut()).await.unwrap();
+
+    // Verify that the response contains an error
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(response_body, "The consensus observer is not enabled!");
+
+    // Enable the consensus observer and ping the endpoint
+    config.consensus_observer.observer_enabled = true;
+    config.consensus_observer.enable_publisher_scoring = true;
+    let mut response = send_get_request_to_path(&config, CONSENSUS_OBSERVER_PUBLISHERS_PATH).await;
+    let response_body = body::to_bytes(response.body_mut()).await.unwrap();
+    let response_body_string = read_to_string(response_body.as_ref()).unwrap();
+
+    // Verify that the response contains the expected information
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(response_body_string.contains("Publisher scoring enabled: true"));
+    assert!(response_body_string.contains("Publisher metrics"));
+}
+
+#[tokio::test]
+async fn test_inspect_state_sync_stream_progress() {
+    // Register a stream progress gauge and update the ETA
+    let stream_progress = register_gauge_vec!(
+        "aptos_state_sync_stream_progress",
+        "Test stream progress gauge",
+        &["stream_type", "label"]
+    )
+    .unwrap();
+    stream_progress
+        .with_label_values(&["transactions_or_outputs", "eta_secs"])
+        .set(120.0);
+
+    // Ping the stream progress endpoint
+    let config = NodeConfig::get_default_pfn_config();
+    let mut response = send_get_request_to_path(&config, STATE_SYNC_STREAM_PROGRESS_PATH).await;
+    let response_body = body::to_bytes(response.body_mut()).await.unwrap();
+    let response_body_string = read_to_string(response_body.as_ref()).unwrap();
+
+    // Verify that the response contains the stream progress
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(response_body_string.contains("Number of stream progress metrics: 1"));
+    assert!(response_body_string.contains("transactions_or_outputs"));
+    assert!(response_body_string.contains("eta_secs"));
+}
+
+#[tokio::test]
+async fn test_inspect_storage_scrubber() {
+    // Register a scrubber corruption counter and report a corruption
+    let corruptions = register_int_counter_vec!(
+        "aptos_storage_scrubber_corruptions",
+        "Test storage scrubber corruption counter",
+        &["check"]
+    )
+    .unwrap();
+    corruptions.with_label_values(&["event"]).inc();
+
+    // Ping the storage scrubber endpoint
+    let config = NodeConfig::get_default_pfn_config();
+    let mut response = send_get_request_to_path(&config, STORAGE_SCRUBBER_PATH).await;
+    let response_body = body::to_bytes(response.body_mut()).await.unwrap();
+    let response_body_string = read_to_string(response_body.as_ref()).unwrap();
+
+    // Verify that the response contains the corruption counter
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(response_body_string.contains("Number of storage scrubber metrics: 1"));
+    assert!(response_body_string.contains("aptos_storage_scrubber_corruptions"));
+    assert!(response_body_string.contains("event"));
+}
+
 #[tokio::test]
 async fn test_inspect_json_metrics() {
     // Create a validator config
@@ -12,7 +12,7 @@ use std::{
     convert::TryFrom,
     fs::File,
     io::{BufRead, BufReader},
-    path::Path,
+    path::{Path, PathBuf},
 };
 use url::Url;
 
@@ -108,7 +108,7 @@ impl ClusterArgs {
 #[clap(group(
     ArgGroup::new("mode")
         .required(true)
-        .args(&["mempool_backlog", "target_tps"]),
+        .args(&["mempool_backlog", "target_tps", "replay_trace"]),
 ))]
 pub struct EmitArgs {
     #[clap(long)]
@@ -122,6 +122,17 @@ pub struct EmitArgs {
     #[clap(long)]
     pub target_tps: Option<usize>,
 
+    /// Replay a trace saved by extract-trace: its payloads, signed by generated accounts standing
+    /// in for the original senders, are submitted with the original inter-arrival timing.
+    /// --duration is then derived from the length of the trace.
+    #[clap(long)]
+    pub replay_trace: Option<PathBuf>,
+
+    /// Speed-up of the replay relative to the original traffic,
+    /// e.g. 2.0 replays twice as fast, 0.5 at half speed. Defaults to 1.0.
+    #[clap(long, requires = "replay_trace")]
+    pub replay_time_scale: Option<f64>,
+
     #[clap(long, default_value_t = 30)]
     pub txn_expiration_time_secs: u64,
 
@@ -138,6 +149,12 @@ pub struct EmitArgs {
     )]
     pub transaction_type: Vec<TransactionTypeArg>,
 
+    /// Workloads defined in YAML/JSON spec files, calling the entry functions of arbitrary Move
+    /// packages, to use instead of --transaction-type.
+    /// --transaction-weights and --transaction-phases then apply to them.
+    #[clap(long, num_args = 1.., conflicts_with = "transaction_type")]
+    pub workload_spec: Vec<PathBuf>,
+
     /// Number of copies of the modules that will be published,
     /// under separate accounts, creating independent contracts,
     /// removing contention.
@@ -260,6 +277,34 @@ pub struct CreateAccountsArgs {
     pub proof_file_path: Option<String>,
 }
 
+#[derive(Clone, Debug, Default, Deserialize, Parser, Serialize)]
+pub struct ExtractTraceArgs {
+    /// Node to extract the trace from, e.g. `http://node.mysite.com:8080`
+    #[clap(long, value_parser = parse_target, required_unless_present = "db_dir")]
+    pub target: Option<Url>,
+
+    /// Local DB to extract the trace from instead, e.g. the DB of a stopped node or a backup
+    /// restored with the db-tool.
+    #[clap(long, value_parser, conflicts_with = "target")]
+    pub db_dir: Option<PathBuf>,
+
+    /// Key to use for ratelimiting purposes with the node API.
+    #[clap(long, env)]
+    pub node_api_key: Option<String>,
+
+    /// First version of the range to extract.
+    #[clap(long)]
+    pub start_version: u64,
+
+    /// Number of versions to extract, including non-user transactions.
+    #[clap(long)]
+    pub num_versions: u64,
+
+    /// File to save the trace to, for use with emit-tx --replay-trace.
+    #[clap(long)]
+    pub output: PathBuf,
+}
+
 fn parse_target(target: &str) -> Result<Url> {
     let mut url = Url::try_from(target).map_err(|e| {
         format_err!(
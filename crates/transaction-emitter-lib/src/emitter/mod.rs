@@ -5,6 +5,7 @@ pub mod account_minter;
 pub mod local_account_generator;
 pub mod stats;
 pub mod submission_worker;
+pub mod trace_replay;
 pub mod transaction_executor;
 
 use crate::emitter::{
@@ -14,6 +15,7 @@ use crate::emitter::{
     },
     stats::{DynamicStatsTracking, TxnStats},
     submission_worker::SubmissionWorker,
+    trace_replay::{create_replay_workers, Trace, TraceReplayStats},
     transaction_executor::RestApiReliableTransactionSubmitter,
 };
 use again::RetryPolicy;
@@ -110,6 +112,11 @@ pub enum EmitJobMode {
         // number of waves within the wait_millis interval (which is txn_expiration_time + 180s)
         num_waves: usize,
     },
+    TraceReplay {
+        trace: Arc<Trace>,
+        // > 1.0 replays faster than the original traffic, < 1.0 slower
+        time_scale: f64,
+    },
 }
 
 impl EmitJobMode {
@@ -571,6 +578,45 @@ impl EmitJobRequest {
                     check_account_sequence_sleep: self.latency_polling_interval,
                 }
             },
+            EmitJobMode::TraceReplay {
+                ref trace,
+                time_scale,
+            } => {
+                assert!(time_scale > 0.0, "time_scale needs to be positive");
+                // Each replay account stands in for one original sender, unless capped, in which
+                // case original senders share accounts.
+                assert!(
+                    trace.num_senders() > 0,
+                    "Trace has no transactions to replay"
+                );
+                let num_accounts = match self.num_accounts_mode {
+                    NumAccountsMode::NumAccounts(num_accounts) => {
+                        min(num_accounts, trace.num_senders())
+                    },
+                    NumAccountsMode::TransactionsPerAccount(_) => trace.num_senders(),
+                };
+
+                info!(
+                    " Transaction emitter replaying {} transactions from {} senders, with {} accounts",
+                    trace.len(),
+                    trace.num_senders(),
+                    num_accounts
+                );
+
+                EmitModeParams {
+                    wait_millis: 0,
+                    txn_expiration_time_secs: self.txn_expiration_time_secs,
+                    num_accounts,
+                    // Accounts are funded for this many transactions, so it needs to cover the
+                    // account the most transactions of the trace are folded onto.
+                    transactions_per_account: trace.max_transactions_per_account(num_accounts),
+                    max_submit_batch_size: 1,
+                    worker_offset_mode: WorkerOffsetMode::NoOffset,
+                    endpoints: clients_count,
+                    check_account_sequence_only_once_fraction: 0.0,
+                    check_account_sequence_sleep: self.latency_polling_interval,
+                }
+            },
         }
     }
 }
@@ -645,6 +691,7 @@ pub struct EmitJob {
     stop: Arc<AtomicBool>,
     stats: Arc<DynamicStatsTracking>,
     phase_starts: Vec<Instant>,
+    trace_replay_stats: Option<Arc<TraceReplayStats>>,
 }
 
 impl EmitJob {
@@ -668,6 +715,9 @@ impl EmitJob {
                 .expect("TxnEmitter worker thread failed");
         }
 
+        if let Some(trace_replay_stats) = &self.trace_replay_stats {
+            trace_replay_stats.log(self.phase_starts[0].elapsed());
+        }
         self.stats.accumulate(&self.phase_starts)
     }
 
@@ -814,6 +864,35 @@ impl TxnEmitter {
         let stats = Arc::new(DynamicStatsTracking::new(stats_tracking_phases));
         let tokio_handle = Handle::current();
 
+        if let EmitJobMode::TraceReplay { trace, time_scale } = &req.mode {
+            // Payloads come from the trace, so there are no transaction generators to create.
+            let (replay_workers, trace_replay_stats) = create_replay_workers(
+                trace,
+                *time_scale,
+                all_accounts,
+                &req.rest_clients,
+                &txn_factory,
+                stop.clone(),
+                stats.clone(),
+            );
+            let phase_start = Instant::now();
+            let workers = replay_workers
+                .into_iter()
+                .map(|worker| Worker {
+                    join_handle: tokio_handle.spawn(worker.run(phase_start).boxed()),
+                })
+                .collect();
+            info!("Tx emitter trace replay workers started");
+
+            return Ok(EmitJob {
+                workers,
+                stop,
+                stats,
+                phase_starts: vec![phase_start],
+                trace_replay_stats: Some(trace_replay_stats),
+            });
+        }
+
         let txn_executor = RestApiReliableTransactionSubmitter::new(
             req.rest_clients.clone(),
             init_retries,
@@ -908,6 +987,7 @@ impl TxnEmitter {
             stop,
             stats,
             phase_starts: vec![phase_start],
+            trace_replay_stats: None,
         })
     }
 
@@ -0,0 +1,538 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Replay of the traffic shape of a range of committed transactions.
+//!
+//! A [`Trace`] records, for every user transaction in the range, when it arrived (relative to the
+//! start of the range), which of the original senders sent it and its payload. Replaying it
+//! re-signs the payloads with freshly created accounts (one per original sender), and submits
+//! them with the original inter-arrival timing, optionally sped up or slowed down.
+
+use crate::emitter::stats::{DynamicStatsTracking, StatsAccumulator, TxnStatsRate};
+use anyhow::{format_err, Context, Result};
+use aptos_logger::{info, sample, sample::SampleRate, warn};
+use aptos_rest_client::{aptos_api_types::TransactionData, Client as RestClient};
+use aptos_sdk::{
+    bcs,
+    move_types::account_address::AccountAddress,
+    transaction_builder::TransactionFactory,
+    types::{
+        transaction::{Transaction, TransactionPayload},
+        LocalAccount,
+    },
+};
+use aptos_storage_interface::DbReader;
+use futures::future::join_all;
+use serde::{Deserialize, Serialize};
+use std::{
+    cmp::min,
+    collections::{BTreeSet, HashMap},
+    path::Path,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+use tokio::time::sleep_until;
+
+/// Max number of transactions fetched per REST request when extracting a trace.
+const FETCH_PAGE_SIZE: u16 = 100;
+/// Longest a replay worker sleeps before checking whether the job was stopped.
+const STOP_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct TraceTransaction {
+    /// Arrival time relative to the start of the trace. Transactions carry no arrival time on
+    /// chain, so the timestamp of the block they were committed in is used instead.
+    pub offset_micros: u64,
+    /// Index of the original sender, among all senders of the trace.
+    pub sender_index: usize,
+    pub payload: TransactionPayload,
+}
+
+impl TraceTransaction {
+    /// Kind under which the transaction is reported: the entry function it calls, or the type of
+    /// its payload otherwise.
+    pub fn kind(&self) -> String {
+        match &self.payload {
+            TransactionPayload::EntryFunction(entry_function) => format!(
+                "0x{}::{}::{}",
+                entry_function.module().address().short_str_lossless(),
+                entry_function.module().name(),
+                entry_function.function()
+            ),
+            TransactionPayload::Script(_) => "script".to_string(),
+            TransactionPayload::ModuleBundle(_) => "module_bundle".to_string(),
+            TransactionPayload::Multisig(_) => "multisig".to_string(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct Trace {
+    transactions: Vec<TraceTransaction>,
+    num_senders: usize,
+}
+
+impl Trace {
+    /// Builds a trace from committed transactions, in version order.
+    pub fn from_transactions(transactions: impl IntoIterator<Item = Transaction>) -> Self {
+        let mut builder = TraceBuilder::default();
+        for txn in transactions {
+            builder.add(txn);
+        }
+        builder.build()
+    }
+
+    /// Extracts the trace of `num_versions` versions starting at `start_version` from a fullnode.
+    pub async fn fetch(client: &RestClient, start_version: u64, num_versions: u64) -> Result<Self> {
+        let end_version = start_version + num_versions;
+        let mut builder = TraceBuilder::default();
+        let mut version = start_version;
+        while version < end_version {
+            let limit = (end_version - version).min(FETCH_PAGE_SIZE as u64) as u16;
+            let transactions = client
+                .get_transactions_bcs(Some(version), Some(limit))
+                .await
+                .with_context(|| format!("Failed to fetch transactions at version {}", version))?
+                .into_inner();
+            if transactions.is_empty() {
+                warn!(
+                    "No transactions past version {}, trace ends there instead of at {}",
+                    version, end_version
+                );
+                break;
+            }
+            version += transactions.len() as u64;
+            for txn in transactions {
+                builder.add(txn.transaction);
+            }
+        }
+        Ok(builder.build())
+    }
+
+    /// Extracts the trace of `num_versions` versions starting at `start_version` from a local DB.
+    pub fn from_db(db: &dyn DbReader, start_version: u64, num_versions: u64) -> Result<Self> {
+        let synced_version = db
+            .get_synced_version()?
+            .ok_or_else(|| format_err!("The DB is empty"))?;
+        let end_version = start_version + num_versions;
+        let available_end_version = min(end_version, synced_version + 1);
+        if available_end_version < end_version {
+            warn!(
+                "No transactions past version {}, trace ends there instead of at {}",
+                available_end_version, end_version
+            );
+        }
+
+        let mut builder = TraceBuilder::default();
+        for txn in db.get_transaction_iterator(
+            start_version,
+            available_end_version.saturating_sub(start_version),
+        )? {
+            builder.add(txn?);
+        }
+        Ok(builder.build())
+    }
+
+    /// Number of transactions the busiest of `num_accounts` replay accounts submits, when
+    /// original senders are folded onto the accounts.
+    pub fn max_transactions_per_account(&self, num_accounts: usize) -> usize {
+        let mut num_transactions = vec![0; num_accounts];
+        for txn in &self.transactions {
+            num_transactions[txn.sender_index % num_accounts] += 1;
+        }
+        num_transactions.into_iter().max().unwrap_or(0)
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let bytes = std::fs::read(path)
+            .with_context(|| format!("Failed to read trace from {}", path.display()))?;
+        Ok(bcs::from_bytes(&bytes)?)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        std::fs::write(path, bcs::to_bytes(self)?)
+            .with_context(|| format!("Failed to write trace to {}", path.display()))
+    }
+
+    pub fn transactions(&self) -> &[TraceTransaction] {
+        &self.transactions
+    }
+
+    pub fn len(&self) -> usize {
+        self.transactions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.transactions.is_empty()
+    }
+
+    pub fn num_senders(&self) -> usize {
+        self.num_senders
+    }
+
+    /// Time between the first and the last transaction of the trace, at original speed.
+    pub fn duration(&self) -> Duration {
+        Duration::from_micros(self.transactions.last().map_or(0, |txn| txn.offset_micros))
+    }
+}
+
+#[derive(Default)]
+struct TraceBuilder {
+    start_timestamp_usecs: Option<u64>,
+    block_timestamp_usecs: Option<u64>,
+    senders: HashMap<AccountAddress, usize>,
+    transactions: Vec<TraceTransaction>,
+}
+
+impl TraceBuilder {
+    fn add(&mut self, txn: Transaction) {
+        match txn {
+            Transaction::BlockMetadata(block_metadata) => {
+                self.start_block(block_metadata.timestamp_usecs())
+            },
+            Transaction::BlockMetadataExt(block_metadata) => {
+                self.start_block(block_metadata.timestamp_usecs())
+            },
+            Transaction::UserTransaction(signed_txn) => {
+                // Transactions of a block the range starts in the middle of are treated as
+                // arriving at the start of the trace.
+                let offset_micros = match (self.block_timestamp_usecs, self.start_timestamp_usecs) {
+                    (Some(block), Some(start)) => block.saturating_sub(start),
+                    _ => 0,
+                };
+                let num_senders = self.senders.len();
+                let sender_index = *self
+                    .senders
+                    .entry(signed_txn.sender())
+                    .or_insert(num_senders);
+                self.transactions.push(TraceTransaction {
+                    offset_micros,
+                    sender_index,
+                    payload: signed_txn.payload().clone(),
+                });
+            },
+            Transaction::GenesisTransaction(_)
+            | Transaction::StateCheckpoint(_)
+            | Transaction::ValidatorTransaction(_)
+            | Transaction::BlockEpilogue(_) => (),
+        }
+    }
+
+    fn start_block(&mut self, timestamp_usecs: u64) {
+        self.start_timestamp_usecs.get_or_insert(timestamp_usecs);
+        self.block_timestamp_usecs = Some(timestamp_usecs);
+    }
+
+    fn build(self) -> Trace {
+        Trace {
+            transactions: self.transactions,
+            num_senders: self.senders.len(),
+        }
+    }
+}
+
+/// Stats of a trace replay, broken down by transaction kind.
+#[derive(Debug)]
+pub struct TraceReplayStats {
+    kinds: Vec<String>,
+    stats: Vec<StatsAccumulator>,
+    /// Transactions that were committed, but failed execution, e.g. aborted. Unlike in `stats`,
+    /// they aren't counted as committed.
+    failed_execution: Vec<AtomicU64>,
+}
+
+impl TraceReplayStats {
+    fn new(trace: &Trace) -> Self {
+        let kinds: Vec<_> = trace
+            .transactions
+            .iter()
+            .map(TraceTransaction::kind)
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+        let stats = kinds.iter().map(|_| StatsAccumulator::default()).collect();
+        let failed_execution = kinds.iter().map(|_| AtomicU64::new(0)).collect();
+        Self {
+            kinds,
+            stats,
+            failed_execution,
+        }
+    }
+
+    fn kind_index(&self, kind: &str) -> usize {
+        self.kinds
+            .binary_search_by(|k| k.as_str().cmp(kind))
+            .expect("Kind not in trace")
+    }
+
+    pub fn rates(&self, lasted: Duration) -> Vec<(String, TxnStatsRate)> {
+        self.kinds
+            .iter()
+            .zip(self.stats.iter())
+            .map(|(kind, stats)| (kind.clone(), stats.accumulate(lasted).rate()))
+            .collect()
+    }
+
+    pub fn failed_execution(&self) -> Vec<(String, u64)> {
+        self.kinds
+            .iter()
+            .zip(self.failed_execution.iter())
+            .map(|(kind, failed)| (kind.clone(), failed.load(Ordering::Relaxed)))
+            .collect()
+    }
+
+    pub fn log(&self, lasted: Duration) {
+        for ((kind, rate), (_, failed_execution)) in
+            self.rates(lasted).into_iter().zip(self.failed_execution())
+        {
+            info!(
+                "[trace replay] {}: {}, failed execution: {}",
+                kind, rate, failed_execution
+            );
+        }
+    }
+}
+
+struct ScheduledTransaction {
+    offset: Duration,
+    kind_index: usize,
+    payload: TransactionPayload,
+}
+
+/// Splits the trace into the transactions each of `num_accounts` replay accounts submits. When
+/// there are fewer accounts than original senders, senders are folded onto the same account.
+pub(crate) fn create_replay_workers(
+    trace: &Trace,
+    time_scale: f64,
+    accounts: Vec<LocalAccount>,
+    clients: &[RestClient],
+    txn_factory: &TransactionFactory,
+    stop: Arc<AtomicBool>,
+    stats: Arc<DynamicStatsTracking>,
+) -> (Vec<TraceReplayWorker>, Arc<TraceReplayStats>) {
+    let replay_stats = Arc::new(TraceReplayStats::new(trace));
+    let num_accounts = accounts.len();
+    let mut schedules: Vec<Vec<ScheduledTransaction>> =
+        (0..num_accounts).map(|_| Vec::new()).collect();
+    for txn in &trace.transactions {
+        schedules[txn.sender_index % num_accounts].push(ScheduledTransaction {
+            offset: Duration::from_micros(txn.offset_micros),
+            kind_index: replay_stats.kind_index(&txn.kind()),
+            payload: txn.payload.clone(),
+        });
+    }
+
+    let workers = accounts
+        .into_iter()
+        .zip(schedules)
+        .enumerate()
+        .map(|(index, (account, schedule))| TraceReplayWorker {
+            account,
+            client: clients[index % clients.len()].clone(),
+            txn_factory: txn_factory.clone(),
+            schedule,
+            time_scale,
+            stop: stop.clone(),
+            stats: stats.clone(),
+            replay_stats: replay_stats.clone(),
+        })
+        .collect();
+    (workers, replay_stats)
+}
+
+pub(crate) struct TraceReplayWorker {
+    account: LocalAccount,
+    client: RestClient,
+    txn_factory: TransactionFactory,
+    schedule: Vec<ScheduledTransaction>,
+    time_scale: f64,
+    stop: Arc<AtomicBool>,
+    stats: Arc<DynamicStatsTracking>,
+    replay_stats: Arc<TraceReplayStats>,
+}
+
+impl TraceReplayWorker {
+    pub(crate) async fn run(self, start_instant: Instant) -> Vec<LocalAccount> {
+        let mut pending = Vec::new();
+        for txn in self.schedule {
+            if !self
+                .sleep_until_or_stop(start_instant + txn.offset.div_f64(self.time_scale))
+                .await
+            {
+                break;
+            }
+
+            let signed_txn = self
+                .account
+                .sign_with_transaction_builder(self.txn_factory.payload(txn.payload));
+            let kind_index = txn.kind_index;
+            let kind_stats = &self.replay_stats.stats[kind_index];
+            self.stats
+                .get_cur()
+                .submitted
+                .fetch_add(1, Ordering::Relaxed);
+            kind_stats.submitted.fetch_add(1, Ordering::Relaxed);
+
+            let submitted_at = Instant::now();
+            if let Err(e) = self.client.submit_bcs(&signed_txn).await {
+                sample!(
+                    SampleRate::Duration(Duration::from_secs(60)),
+                    warn!(
+                        "[{:?}] Failed to submit replayed transaction: {:?}",
+                        self.client.path_prefix_string(),
+                        e
+                    )
+                );
+                self.stats
+                    .get_cur()
+                    .failed_submission
+                    .fetch_add(1, Ordering::Relaxed);
+                kind_stats.failed_submission.fetch_add(1, Ordering::Relaxed);
+                self.account.decrement_sequence_number();
+                continue;
+            }
+
+            let client = self.client.clone();
+            let stats = self.stats.clone();
+            let replay_stats = self.replay_stats.clone();
+            pending.push(tokio::spawn(async move {
+                let committed = client
+                    .wait_for_signed_transaction_bcs(&signed_txn)
+                    .await
+                    .is_ok();
+                let latency = submitted_at.elapsed().as_millis() as u64;
+                // Waiting fails for transactions that were committed but failed execution too,
+                // which consumed their sequence number, so they didn't expire.
+                let failed_execution = !committed
+                    && matches!(
+                        client
+                            .get_transaction_by_hash_bcs(signed_txn.committed_hash())
+                            .await
+                            .map(|response| response.into_inner()),
+                        Ok(TransactionData::OnChain(_))
+                    );
+
+                // As in the rest of the emitter, transactions that failed execution count as
+                // committed overall, and are only broken out per kind.
+                if committed || failed_execution {
+                    record_committed(stats.get_cur(), latency);
+                } else {
+                    stats.get_cur().expired.fetch_add(1, Ordering::Relaxed);
+                }
+                let kind_stats = &replay_stats.stats[kind_index];
+                if committed {
+                    record_committed(kind_stats, latency);
+                } else if failed_execution {
+                    replay_stats.failed_execution[kind_index].fetch_add(1, Ordering::Relaxed);
+                } else {
+                    kind_stats.expired.fetch_add(1, Ordering::Relaxed);
+                }
+            }));
+        }
+
+        join_all(pending).await;
+        vec![self.account]
+    }
+
+    /// Returns false if the job was stopped before `until`.
+    async fn sleep_until_or_stop(&self, until: Instant) -> bool {
+        loop {
+            if self.stop.load(Ordering::Relaxed) {
+                return false;
+            }
+            let now = Instant::now();
+            if now >= until {
+                return true;
+            }
+            sleep_until(min(until, now + STOP_POLL_INTERVAL).into()).await;
+        }
+    }
+}
+
+fn record_committed(stats: &StatsAccumulator, latency: u64) {
+    stats.committed.fetch_add(1, Ordering::Relaxed);
+    stats.latency.fetch_add(latency, Ordering::Relaxed);
+    stats.latency_samples.fetch_add(1, Ordering::Relaxed);
+    stats.latencies.record_data_point(latency, 1);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_crypto::HashValue;
+    use aptos_sdk::{
+        move_types::{identifier::Identifier, language_storage::ModuleId},
+        types::{
+            block_metadata::BlockMetadata,
+            chain_id::ChainId,
+            transaction::{EntryFunction, Script},
+        },
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+
+    fn block(timestamp_usecs: u64) -> Transaction {
+        Transaction::BlockMetadata(BlockMetadata::new(
+            HashValue::zero(),
+            0,
+            0,
+            AccountAddress::ONE,
+            vec![],
+            vec![],
+            timestamp_usecs,
+        ))
+    }
+
+    fn user_txn(sender: &LocalAccount, payload: TransactionPayload) -> Transaction {
+        let txn_factory = TransactionFactory::new(ChainId::test());
+        Transaction::UserTransaction(
+            sender.sign_with_transaction_builder(txn_factory.payload(payload)),
+        )
+    }
+
+    #[test]
+    fn test_trace_from_transactions() {
+        let mut rng = StdRng::from_seed([0; 32]);
+        let alice = LocalAccount::generate(&mut rng);
+        let bob = LocalAccount::generate(&mut rng);
+        let transfer = TransactionPayload::EntryFunction(EntryFunction::new(
+            ModuleId::new(AccountAddress::ONE, Identifier::new("coin").unwrap()),
+            Identifier::new("transfer").unwrap(),
+            vec![],
+            vec![],
+        ));
+        let script = TransactionPayload::Script(Script::new(vec![], vec![], vec![]));
+
+        let trace = Trace::from_transactions(vec![
+            // The range starts in the middle of a block.
+            user_txn(&alice, transfer.clone()),
+            block(1_000_000),
+            user_txn(&bob, script),
+            block(3_500_000),
+            user_txn(&alice, transfer),
+        ]);
+
+        assert_eq!(trace.len(), 3);
+        assert_eq!(trace.num_senders(), 2);
+        assert_eq!(trace.duration(), Duration::from_micros(2_500_000));
+        let summary: Vec<_> = trace
+            .transactions()
+            .iter()
+            .map(|txn| (txn.offset_micros, txn.sender_index, txn.kind()))
+            .collect();
+        assert_eq!(summary, vec![
+            (0, 0, "0x1::coin::transfer".to_string()),
+            (0, 1, "script".to_string()),
+            (2_500_000, 0, "0x1::coin::transfer".to_string()),
+        ]);
+
+        // Accounts are funded for the busiest one, rather than for an even split.
+        assert_eq!(trace.max_transactions_per_account(2), 2);
+        assert_eq!(trace.max_transactions_per_account(1), 3);
+
+        let stats = TraceReplayStats::new(&trace);
+        assert_eq!(stats.kinds, vec!["0x1::coin::transfer", "script"]);
+    }
+}
@@ -2,13 +2,14 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    args::{ClusterArgs, EmitArgs},
+    args::{ClusterArgs, EmitArgs, ExtractTraceArgs},
     cluster::Cluster,
     emitter::{
         account_minter::bulk_create_accounts,
         get_needed_balance_per_account_from_req,
         local_account_generator::{create_keyless_account_generator, PrivateKeyAccountGenerator},
         stats::TxnStats,
+        trace_replay::Trace,
         transaction_executor::RestApiReliableTransactionSubmitter,
         EmitJobMode, EmitJobRequest, NumAccountsMode, TxnEmitter,
     },
@@ -16,6 +17,11 @@ use crate::{
     CreateAccountsArgs,
 };
 use anyhow::{bail, Context, Result};
+use aptos_config::config::{
+    RocksdbConfigs, StorageDirPaths, BUFFERED_STATE_TARGET_ITEMS,
+    DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD, NO_OP_STORAGE_PRUNER_CONFIG,
+};
+use aptos_db::AptosDB;
 use aptos_logger::{error, info};
 use aptos_sdk::transaction_builder::TransactionFactory;
 use aptos_transaction_generator_lib::{args::TransactionTypeArg, AccountType, WorkflowProgress};
@@ -77,9 +83,29 @@ pub async fn emit_transactions_with_cluster(
     cluster: &Cluster,
     args: &EmitArgs,
 ) -> Result<TxnStats> {
-    let emitter_mode = EmitJobMode::create(args.mempool_backlog, args.target_tps);
+    let replay_trace = match &args.replay_trace {
+        Some(path) => Some(Arc::new(Trace::load(path)?)),
+        None => None,
+    };
+    let (emitter_mode, duration) = if let Some(trace) = &replay_trace {
+        let time_scale = args.replay_time_scale.unwrap_or(1.0);
+        // Leave time for the last replayed transactions to be committed or expire.
+        let duration = trace.duration().div_f64(time_scale)
+            + Duration::from_secs(args.txn_expiration_time_secs);
+        (
+            EmitJobMode::TraceReplay {
+                trace: trace.clone(),
+                time_scale,
+            },
+            duration,
+        )
+    } else {
+        (
+            EmitJobMode::create(args.mempool_backlog, args.target_tps),
+            Duration::from_secs(args.duration),
+        )
+    };
 
-    let duration = Duration::from_secs(args.duration);
     let client = cluster.random_instance().rest_client();
     let coin_source_account = cluster.load_coin_source_account(&client).await?;
     let emitter = TxnEmitter::new(
@@ -90,14 +116,22 @@ pub async fn emit_transactions_with_cluster(
         client,
     );
 
-    let transaction_mix_per_phase = TransactionTypeArg::args_to_transaction_mix_per_phase(
-        &args.transaction_type,
-        &args.transaction_weights,
-        &args.transaction_phases,
-        args.module_working_set_size.unwrap_or(1),
-        args.sender_use_account_pool.unwrap_or(false),
-        WorkflowProgress::when_done_default(),
-    );
+    let transaction_mix_per_phase = if args.workload_spec.is_empty() {
+        TransactionTypeArg::args_to_transaction_mix_per_phase(
+            &args.transaction_type,
+            &args.transaction_weights,
+            &args.transaction_phases,
+            args.module_working_set_size.unwrap_or(1),
+            args.sender_use_account_pool.unwrap_or(false),
+            WorkflowProgress::when_done_default(),
+        )
+    } else {
+        TransactionTypeArg::workload_specs_to_transaction_mix_per_phase(
+            &args.workload_spec,
+            &args.transaction_weights,
+            &args.transaction_phases,
+        )?
+    };
     let mut emit_job_request =
         EmitJobRequest::new(cluster.all_instances().map(Instance::rest_client).collect())
             .mode(emitter_mode)
@@ -153,6 +187,8 @@ pub async fn emit_transactions_with_cluster(
 
     if let Some(expected_max_txns) = args.expected_max_txns {
         emit_job_request = emit_job_request.expected_max_txns(expected_max_txns);
+    } else if let Some(trace) = &replay_trace {
+        emit_job_request = emit_job_request.expected_max_txns(trace.len() as u64);
     }
     if let Some(expected_gas_per_txn) = args.expected_gas_per_txn {
         emit_job_request = emit_job_request.expected_gas_per_txn(expected_gas_per_txn);
@@ -194,7 +230,7 @@ pub async fn emit_transactions_with_cluster(
             coin_source_account,
             emit_job_request,
             duration,
-            (args.duration / 10).clamp(1, 10),
+            (duration.as_secs() / 10).clamp(1, 10),
         )
         .await?;
     Ok(stats)
@@ -260,3 +296,51 @@ pub async fn create_accounts_command(
 
     Ok(())
 }
+
+pub async fn extract_trace_command(extract_trace_args: &ExtractTraceArgs) -> Result<()> {
+    let trace = if let Some(db_dir) = &extract_trace_args.db_dir {
+        let db = AptosDB::open(
+            StorageDirPaths::from_path(db_dir),
+            true, /* readonly */
+            NO_OP_STORAGE_PRUNER_CONFIG,
+            RocksdbConfigs::default(),
+            false, /* indexer */
+            BUFFERED_STATE_TARGET_ITEMS,
+            DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
+            None,
+            None,
+            None,
+        )
+        .with_context(|| format!("Failed to open DB at {}", db_dir.display()))?;
+        Trace::from_db(
+            &db,
+            extract_trace_args.start_version,
+            extract_trace_args.num_versions,
+        )?
+    } else {
+        let target = extract_trace_args
+            .target
+            .clone()
+            .context("Either --target or --db-dir is needed")?;
+        let client = Instance::new(
+            target.to_string(),
+            target,
+            None,
+            extract_trace_args.node_api_key.clone(),
+        )
+        .rest_client();
+        Trace::fetch(
+            &client,
+            extract_trace_args.start_version,
+            extract_trace_args.num_versions,
+        )
+        .await?
+    };
+    info!(
+        "Extracted {} transactions from {} senders, spanning {}s",
+        trace.len(),
+        trace.num_senders(),
+        trace.duration().as_secs_f64()
+    );
+    trace.save(&extract_trace_args.output)
+}
@@ -7,7 +7,8 @@ mod diag;
 use anyhow::{Context, Result};
 use aptos_logger::{Level, Logger};
 use aptos_transaction_emitter_lib::{
-    create_accounts_command, emit_transactions, Cluster, ClusterArgs, CreateAccountsArgs, EmitArgs,
+    create_accounts_command, emit_transactions, extract_trace_command, Cluster, ClusterArgs,
+    CreateAccountsArgs, EmitArgs, ExtractTraceArgs,
 };
 use clap::{Parser, Subcommand};
 use diag::diag;
@@ -28,6 +29,10 @@ enum TxnEmitterCommand {
     /// Create test accounts, for use with EmitTx
     CreateAccounts(CreateAccounts),
 
+    /// Extract the traffic shape of a range of versions from a node, for replay with
+    /// EmitTx --replay-trace
+    ExtractTrace(ExtractTraceArgs),
+
     /// This runs the transaction emitter in diag mode, where the focus is on
     /// FullNodes instead of ValidatorNodes. This performs a simple health check.
     Diag(Diag),
@@ -91,6 +96,12 @@ pub async fn main() -> Result<()> {
                 .unwrap();
             Ok(())
         },
+        TxnEmitterCommand::ExtractTrace(args) => {
+            extract_trace_command(&args)
+                .await
+                .context("Extract trace failed")?;
+            Ok(())
+        },
         TxnEmitterCommand::Diag(args) => {
             let cluster = Cluster::try_from_cluster_args(&args.cluster_args)
                 .await
@@ -2,11 +2,13 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    publishing::module_simple::LoopType, EntryPoints, TransactionType, WorkflowKind,
-    WorkflowProgress,
+    publishing::module_simple::LoopType, workload_spec::CustomWorkload, EntryPoints,
+    TransactionType, WorkflowKind, WorkflowProgress,
 };
+use anyhow::{ensure, Result};
 use clap::{Parser, ValueEnum};
 use serde::{Deserialize, Serialize};
+use std::{path::PathBuf, sync::Arc};
 
 /// Utility class for specifying transaction type with predefined configurations through CLI
 #[derive(Debug, Copy, Clone, ValueEnum, Default, Deserialize, Parser, Serialize)]
@@ -366,47 +368,90 @@ impl TransactionTypeArg {
             })
             .collect::<Vec<_>>();
 
-        let arg_transaction_weights = if transaction_weights.is_empty() {
-            vec![1; arg_transaction_types.len()]
-        } else {
-            assert_eq!(
-                transaction_weights.len(),
-                arg_transaction_types.len(),
-                "Transaction types and weights need to be the same length"
-            );
-            transaction_weights.to_vec()
-        };
-        let arg_transaction_phases = if transaction_phases.is_empty() {
-            vec![0; arg_transaction_types.len()]
-        } else {
-            assert_eq!(
-                transaction_phases.len(),
-                arg_transaction_types.len(),
-                "Transaction types and phases need to be the same length"
-            );
-            transaction_phases.to_vec()
-        };
+        to_transaction_mix_per_phase(
+            arg_transaction_types,
+            transaction_weights,
+            transaction_phases,
+        )
+    }
 
-        let mut transaction_mix_per_phase: Vec<Vec<(TransactionType, usize)>> = Vec::new();
-        for (transaction_type, (weight, phase)) in arg_transaction_types.into_iter().zip(
-            arg_transaction_weights
-                .into_iter()
-                .zip(arg_transaction_phases.into_iter()),
-        ) {
-            assert!(
-                phase <= transaction_mix_per_phase.len(),
-                "cannot skip phases ({})",
-                transaction_mix_per_phase.len()
-            );
-            if phase == transaction_mix_per_phase.len() {
-                transaction_mix_per_phase.push(Vec::new());
-            }
-            transaction_mix_per_phase
-                .get_mut(phase)
-                .unwrap()
-                .push((transaction_type, weight));
-        }
+    /// Like `args_to_transaction_mix_per_phase`, for workloads defined in spec files (see
+    /// [`crate::workload_spec`]) instead of transaction types.
+    pub fn workload_specs_to_transaction_mix_per_phase(
+        workload_specs: &[PathBuf],
+        transaction_weights: &[usize],
+        transaction_phases: &[usize],
+    ) -> Result<Vec<Vec<(TransactionType, usize)>>> {
+        ensure!(
+            transaction_weights.is_empty() || transaction_weights.len() == workload_specs.len(),
+            "Workload specs and weights need to be the same length"
+        );
+        ensure!(
+            transaction_phases.is_empty() || transaction_phases.len() == workload_specs.len(),
+            "Workload specs and phases need to be the same length"
+        );
+        let transaction_types = workload_specs
+            .iter()
+            .map(|path| {
+                Ok(TransactionType::CustomWorkload {
+                    workload: Arc::new(CustomWorkload::load(path)?),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(to_transaction_mix_per_phase(
+            transaction_types,
+            transaction_weights,
+            transaction_phases,
+        ))
+    }
+}
+
+fn to_transaction_mix_per_phase(
+    arg_transaction_types: Vec<TransactionType>,
+    transaction_weights: &[usize],
+    transaction_phases: &[usize],
+) -> Vec<Vec<(TransactionType, usize)>> {
+    let arg_transaction_weights = if transaction_weights.is_empty() {
+        vec![1; arg_transaction_types.len()]
+    } else {
+        assert_eq!(
+            transaction_weights.len(),
+            arg_transaction_types.len(),
+            "Transaction types and weights need to be the same length"
+        );
+        transaction_weights.to_vec()
+    };
+    let arg_transaction_phases = if transaction_phases.is_empty() {
+        vec![0; arg_transaction_types.len()]
+    } else {
+        assert_eq!(
+            transaction_phases.len(),
+            arg_transaction_types.len(),
+            "Transaction types and phases need to be the same length"
+        );
+        transaction_phases.to_vec()
+    };
 
+    let mut transaction_mix_per_phase: Vec<Vec<(TransactionType, usize)>> = Vec::new();
+    for (transaction_type, (weight, phase)) in arg_transaction_types.into_iter().zip(
+        arg_transaction_weights
+            .into_iter()
+            .zip(arg_transaction_phases.into_iter()),
+    ) {
+        assert!(
+            phase <= transaction_mix_per_phase.len(),
+            "cannot skip phases ({})",
+            transaction_mix_per_phase.len()
+        );
+        if phase == transaction_mix_per_phase.len() {
+            transaction_mix_per_phase.push(Vec::new());
+        }
         transaction_mix_per_phase
+            .get_mut(phase)
+            .unwrap()
+            .push((transaction_type, weight));
     }
+
+    transaction_mix_per_phase
 }
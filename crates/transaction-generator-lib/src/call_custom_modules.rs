@@ -133,11 +133,35 @@ impl CustomModulesDelegationGeneratorCreator {
         package_name: &str,
         workload: &mut dyn UserModuleTransactionGenerator,
     ) -> Self {
-        let mut packages = Self::publish_package(
+        Self::new_with_package_handler(
+            txn_factory,
+            init_txn_factory,
+            root_account,
+            txn_executor,
+            num_modules,
+            PackageHandler::new(package_name),
+            package_name,
+            workload,
+        )
+        .await
+    }
+
+    pub async fn new_with_package_handler(
+        txn_factory: TransactionFactory,
+        init_txn_factory: TransactionFactory,
+        root_account: &dyn RootAccountHandle,
+        txn_executor: &dyn ReliableTransactionSubmitter,
+        num_modules: usize,
+        package_handler: PackageHandler,
+        package_name: &str,
+        workload: &mut dyn UserModuleTransactionGenerator,
+    ) -> Self {
+        let mut packages = Self::publish_package_with_handler(
             init_txn_factory.clone(),
             root_account,
             txn_executor,
             num_modules,
+            package_handler,
             package_name,
             None,
         )
@@ -201,11 +225,31 @@ impl CustomModulesDelegationGeneratorCreator {
         num_modules: usize,
         package_name: &str,
         publisher_balance: Option<u64>,
+    ) -> Vec<(Package, LocalAccount)> {
+        Self::publish_package_with_handler(
+            init_txn_factory,
+            root_account,
+            txn_executor,
+            num_modules,
+            PackageHandler::new(package_name),
+            package_name,
+            publisher_balance,
+        )
+        .await
+    }
+
+    pub async fn publish_package_with_handler(
+        init_txn_factory: TransactionFactory,
+        root_account: &dyn RootAccountHandle,
+        txn_executor: &dyn ReliableTransactionSubmitter,
+        num_modules: usize,
+        mut package_handler: PackageHandler,
+        package_name: &str,
+        publisher_balance: Option<u64>,
     ) -> Vec<(Package, LocalAccount)> {
         let mut rng = StdRng::from_entropy();
         let mut requests_create = Vec::with_capacity(num_modules);
         let mut requests_publish = Vec::with_capacity(num_modules);
-        let mut package_handler = PackageHandler::new(package_name);
         let mut packages = Vec::new();
 
         let publisher_balance = publisher_balance.unwrap_or(
@@ -37,6 +37,7 @@ pub mod publish_modules;
 pub mod publishing;
 mod transaction_mix_generator;
 mod workflow_delegator;
+pub mod workload_spec;
 use self::{
     account_generator::AccountGeneratorCreator,
     call_custom_modules::CustomModulesDelegationGeneratorCreator,
@@ -47,8 +48,11 @@ use self::{
 use crate::{
     accounts_pool_wrapper::AccountsPoolWrapperCreator,
     batch_transfer::BatchTransferTransactionGeneratorCreator,
-    entry_points::EntryPointTransactionGenerator, p2p_transaction_generator::SamplingMode,
+    entry_points::EntryPointTransactionGenerator,
+    p2p_transaction_generator::SamplingMode,
+    publishing::publish_util::PackageHandler,
     workflow_delegator::WorkflowTxnGeneratorCreator,
+    workload_spec::{CustomWorkload, CustomWorkloadGenerator},
 };
 pub use publishing::module_simple::EntryPoints;
 
@@ -89,6 +93,10 @@ pub enum TransactionType {
         use_account_pool: bool,
         progress_type: WorkflowProgress,
     },
+    /// Calls the entry functions of a package, as defined in a workload spec file.
+    CustomWorkload {
+        workload: Arc<CustomWorkload>,
+    },
 }
 
 #[derive(Debug, Copy, Clone, ValueEnum, Default, Deserialize, Parser, Serialize)]
@@ -396,6 +404,23 @@ pub async fn create_txn_generator_creator(
                     )
                     .await,
                 ),
+                TransactionType::CustomWorkload { workload } => wrap_accounts_pool(
+                    Box::new(
+                        CustomModulesDelegationGeneratorCreator::new_with_package_handler(
+                            txn_factory.clone(),
+                            init_txn_factory.clone(),
+                            &root_account,
+                            txn_executor,
+                            workload.num_publishers(),
+                            PackageHandler::from_package(workload.package().clone()),
+                            workload.name(),
+                            &mut CustomWorkloadGenerator::new(workload.clone()),
+                        )
+                        .await,
+                    ),
+                    workload.sender_use_account_pool(),
+                    &accounts_pool,
+                ),
             };
             txn_generator_creator_mix.push((txn_generator_creator, *weight));
         }
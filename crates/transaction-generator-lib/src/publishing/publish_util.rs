@@ -2,8 +2,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::publishing::{module_simple, raw_module_data};
+use anyhow::Result;
 use aptos_framework::{
-    natives::code::PackageMetadata, KnownAttribute, APTOS_METADATA_KEY, APTOS_METADATA_KEY_V1,
+    natives::code::PackageMetadata, BuiltPackage, KnownAttribute, APTOS_METADATA_KEY,
+    APTOS_METADATA_KEY_V1,
 };
 use aptos_sdk::{
     bcs,
@@ -65,14 +67,22 @@ impl Default for PackageHandler {
 
 impl PackageHandler {
     pub fn new(name: &str) -> Self {
+        PackageHandler {
+            is_simple: name == "simple",
+            ..Self::from_package(Package::by_name(name))
+        }
+    }
+
+    // Handler for a package that isn't one of the known packages, e.g. built from sources.
+    pub fn from_package(package: Package) -> Self {
         let packages = vec![PackageTracker {
             publishers: vec![],
             suffix: 0,
-            package: Package::by_name(name),
+            package,
         }];
         PackageHandler {
             packages,
-            is_simple: name == "simple",
+            is_simple: false,
         }
     }
 
@@ -131,6 +141,16 @@ impl Package {
         Self::Simple(modules, metadata)
     }
 
+    // Given a package built with its publisher address set to some placeholder, loads its root
+    // modules, to be published under other addresses with `update`.
+    pub fn from_built_package(built_package: &BuiltPackage) -> Result<Self> {
+        let modules = built_package
+            .modules()
+            .map(|module| (module.self_id().name().to_string(), module.clone()))
+            .collect();
+        Ok(Self::Simple(modules, built_package.extract_metadata()?))
+    }
+
     pub fn script(publisher: AccountAddress) -> TransactionPayload {
         assert_ne!(publisher, AccountAddress::MAX_ADDRESS);
 
@@ -0,0 +1,826 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Workloads defined in a YAML (or JSON) file instead of in code, to load-test arbitrary Move
+//! packages. For example:
+//! ```yaml
+//! package:
+//!   path: ../my_package
+//!   address_name: my_addr
+//! num_publishers: 10
+//! initialize:
+//!   - module: counter
+//!     function: init
+//! entry_functions:
+//!   - module: counter
+//!     function: increment
+//!     args:
+//!       - type: u64_range
+//!         min: 1
+//!         max: 100
+//!     weight: 3
+//!   - module: counter
+//!     function: transfer
+//!     type_args: ["{publisher}::counter::Token"]
+//!     args:
+//!       - type: receiver
+//!       - type: u64
+//!         value: 1
+//! receivers:
+//!   size: 1000
+//!   conflict_ratio: 0.1
+//! ```
+//! The package is built once, with `address_name` set to a placeholder address, and a copy of
+//! it is published by each publisher (see [`Package::update`]).
+
+use crate::{
+    call_custom_modules::{TransactionGeneratorWorker, UserModuleTransactionGenerator},
+    publishing::publish_util::Package,
+    ReliableTransactionSubmitter, RootAccountHandle,
+};
+use anyhow::{bail, ensure, format_err, Context, Result};
+use aptos_framework::{BuildOptions, BuiltPackage};
+use aptos_sdk::{
+    bcs,
+    move_types::{
+        account_address::AccountAddress, identifier::Identifier, language_storage::TypeTag,
+    },
+    transaction_builder::TransactionFactory,
+    types::{
+        transaction::{EntryFunction, SignedTransaction, TransactionPayload},
+        LocalAccount,
+    },
+};
+use async_trait::async_trait;
+use move_binary_format::{access::ModuleAccess, file_format::SignatureToken};
+use rand::{distributions::Alphanumeric, rngs::StdRng, Rng};
+use rand_core::RngCore;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::Arc,
+};
+
+/// The address the package is built with, replaced by the address of each publisher.
+const PUBLISHER_PLACEHOLDER: AccountAddress = AccountAddress::new([0xAB; AccountAddress::LENGTH]);
+
+/// Stands for the publisher address in type arguments.
+const PUBLISHER_TOKEN: &str = "{publisher}";
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct WorkloadSpec {
+    pub package: PackageSpec,
+    /// Number of copies of the package that are published, under separate accounts.
+    #[serde(default = "default_num_publishers")]
+    pub num_publishers: usize,
+    /// Whether to use burner accounts for the sender.
+    #[serde(default)]
+    pub sender_use_account_pool: bool,
+    /// Entry functions called once by each publisher, after publishing its copy of the package.
+    #[serde(default)]
+    pub initialize: Vec<EntryFunctionSpec>,
+    /// Entry functions called by the workload, picked randomly according to their weights.
+    pub entry_functions: Vec<EntryFunctionSpec>,
+    #[serde(default)]
+    pub receivers: ReceiverPoolSpec,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct PackageSpec {
+    /// Path to the Move package, relative to the spec file.
+    pub path: PathBuf,
+    /// The named address the package is published at.
+    pub address_name: String,
+    /// Any other named addresses needed to build the package.
+    #[serde(default)]
+    pub named_addresses: BTreeMap<String, AccountAddress>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct EntryFunctionSpec {
+    pub module: String,
+    pub function: String,
+    /// Type arguments, in which `{publisher}` stands for the address of the package.
+    #[serde(default)]
+    pub type_args: Vec<String>,
+    /// Arguments, excluding the signers.
+    #[serde(default)]
+    pub args: Vec<ArgumentSpec>,
+    #[serde(default = "default_weight")]
+    pub weight: usize,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum ArgumentSpec {
+    U8 {
+        value: u8,
+    },
+    U64 {
+        value: u64,
+    },
+    /// A random u64 in `[min, max)`.
+    U64Range {
+        min: u64,
+        max: u64,
+    },
+    Bool {
+        value: bool,
+    },
+    RandomBool,
+    Address {
+        value: AccountAddress,
+    },
+    /// The address of the sender.
+    Sender,
+    /// The address the package is published at.
+    Publisher,
+    /// An address from the receiver pool.
+    Receiver,
+    String {
+        value: String,
+    },
+    /// A random alphanumeric string.
+    RandomString {
+        length: usize,
+    },
+    /// A random `vector<u8>`.
+    RandomBytes {
+        length: usize,
+    },
+}
+
+/// Random addresses used as `receiver` arguments. A `conflict_ratio` of them are picked among the
+/// first `hot_set_size` addresses of the pool, to make transactions conflict with each other.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ReceiverPoolSpec {
+    pub size: usize,
+    pub conflict_ratio: f64,
+    pub hot_set_size: usize,
+}
+
+impl Default for ReceiverPoolSpec {
+    fn default() -> Self {
+        Self {
+            size: 1000,
+            conflict_ratio: 0.0,
+            hot_set_size: 1,
+        }
+    }
+}
+
+fn default_num_publishers() -> usize {
+    1
+}
+
+fn default_weight() -> usize {
+    1
+}
+
+impl WorkloadSpec {
+    /// Parses a spec from YAML, which also accepts JSON.
+    pub fn parse(content: &str) -> Result<Self> {
+        Ok(serde_yaml::from_str(content)?)
+    }
+
+    /// Checks everything that doesn't require building the package.
+    pub fn validate(&self) -> Result<()> {
+        ensure!(self.num_publishers > 0, "num_publishers must be positive");
+        ensure!(
+            !self.entry_functions.is_empty(),
+            "entry_functions must not be empty"
+        );
+        ensure!(
+            self.entry_functions.iter().map(|f| f.weight).sum::<usize>() > 0,
+            "The total weight of entry_functions must be positive"
+        );
+        for function in self.initialize.iter().chain(&self.entry_functions) {
+            function
+                .validate()
+                .with_context(|| format!("Invalid {}::{}", function.module, function.function))?;
+        }
+
+        let receivers = &self.receivers;
+        ensure!(
+            (0.0..=1.0).contains(&receivers.conflict_ratio),
+            "receivers.conflict_ratio must be in [0, 1]"
+        );
+        let uses_receivers = self
+            .initialize
+            .iter()
+            .chain(&self.entry_functions)
+            .flat_map(|f| &f.args)
+            .any(|arg| matches!(arg, ArgumentSpec::Receiver));
+        if uses_receivers {
+            ensure!(receivers.size > 0, "receivers.size must be positive");
+            ensure!(
+                receivers.conflict_ratio == 0.0
+                    || (1..=receivers.size).contains(&receivers.hot_set_size),
+                "receivers.hot_set_size must be in [1, receivers.size]"
+            );
+        }
+        Ok(())
+    }
+}
+
+impl EntryFunctionSpec {
+    fn validate(&self) -> Result<()> {
+        Identifier::new(self.module.as_str())?;
+        Identifier::new(self.function.as_str())?;
+        self.parse_type_args()?;
+        for arg in &self.args {
+            if let ArgumentSpec::U64Range { min, max } = arg {
+                ensure!(min < max, "Empty u64_range [{}, {})", min, max);
+            }
+        }
+        Ok(())
+    }
+
+    fn parse_type_args(&self) -> Result<Vec<TypeTag>> {
+        self.type_args
+            .iter()
+            .map(|type_arg| {
+                TypeTag::from_str(
+                    &type_arg.replace(PUBLISHER_TOKEN, &PUBLISHER_PLACEHOLDER.to_hex_literal()),
+                )
+                .with_context(|| format!("Invalid type argument {}", type_arg))
+            })
+            .collect()
+    }
+}
+
+impl ArgumentSpec {
+    /// The type the Move function must take, or None if any type is accepted.
+    fn expected_type(&self) -> Option<SignatureToken> {
+        Some(match self {
+            ArgumentSpec::U8 { .. } => SignatureToken::U8,
+            ArgumentSpec::U64 { .. } | ArgumentSpec::U64Range { .. } => SignatureToken::U64,
+            ArgumentSpec::Bool { .. } | ArgumentSpec::RandomBool => SignatureToken::Bool,
+            ArgumentSpec::Address { .. }
+            | ArgumentSpec::Sender
+            | ArgumentSpec::Publisher
+            | ArgumentSpec::Receiver => SignatureToken::Address,
+            ArgumentSpec::RandomBytes { .. } => {
+                SignatureToken::Vector(Box::new(SignatureToken::U8))
+            },
+            // A struct (std::string::String), which isn't worth matching here.
+            ArgumentSpec::String { .. } | ArgumentSpec::RandomString { .. } => return None,
+        })
+    }
+
+    fn generate(&self, context: &ArgumentContext, rng: &mut StdRng) -> Vec<u8> {
+        match self {
+            ArgumentSpec::U8 { value } => bcs::to_bytes(value),
+            ArgumentSpec::U64 { value } => bcs::to_bytes(value),
+            ArgumentSpec::U64Range { min, max } => bcs::to_bytes(&rng.gen_range(*min, *max)),
+            ArgumentSpec::Bool { value } => bcs::to_bytes(value),
+            ArgumentSpec::RandomBool => bcs::to_bytes(&rng.gen::<bool>()),
+            ArgumentSpec::Address { value } => bcs::to_bytes(value),
+            ArgumentSpec::Sender => bcs::to_bytes(&context.sender),
+            ArgumentSpec::Publisher => bcs::to_bytes(&context.publisher),
+            ArgumentSpec::Receiver => bcs::to_bytes(&context.receivers.pick(rng)),
+            ArgumentSpec::String { value } => bcs::to_bytes(value),
+            ArgumentSpec::RandomString { length } => bcs::to_bytes(
+                &rng.sample_iter(&Alphanumeric)
+                    .take(*length)
+                    .map(char::from)
+                    .collect::<String>(),
+            ),
+            ArgumentSpec::RandomBytes { length } => {
+                let mut bytes = vec![0u8; *length];
+                rng.fill_bytes(&mut bytes);
+                bcs::to_bytes(&bytes)
+            },
+        }
+        .expect("Argument must serialize")
+    }
+}
+
+/// A workload loaded from a [`WorkloadSpec`], with its package built.
+#[derive(Debug)]
+pub struct CustomWorkload {
+    name: String,
+    num_publishers: usize,
+    sender_use_account_pool: bool,
+    package: Package,
+    initialize: Vec<EntryFunctionCall>,
+    entry_functions: Vec<EntryFunctionCall>,
+    receivers: ReceiverPoolSpec,
+}
+
+impl CustomWorkload {
+    /// Reads the spec at `path`, and builds its package.
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read workload spec {}", path.display()))?;
+        let spec = WorkloadSpec::parse(&content)
+            .with_context(|| format!("Failed to parse workload spec {}", path.display()))?;
+        let base_dir = path.parent().unwrap_or_else(|| Path::new("."));
+        Self::from_spec(spec, base_dir)
+            .with_context(|| format!("Invalid workload spec {}", path.display()))
+    }
+
+    /// Builds the package of `spec`, whose path is relative to `base_dir`.
+    pub fn from_spec(spec: WorkloadSpec, base_dir: &Path) -> Result<Self> {
+        spec.validate()?;
+
+        let mut named_addresses = spec.package.named_addresses.clone();
+        named_addresses.insert(spec.package.address_name.clone(), PUBLISHER_PLACEHOLDER);
+        let built_package = BuiltPackage::build(base_dir.join(&spec.package.path), BuildOptions {
+            named_addresses,
+            ..BuildOptions::default()
+        })?;
+        let package = Package::from_built_package(&built_package)?;
+
+        let resolve = |functions: &[EntryFunctionSpec]| {
+            functions
+                .iter()
+                .map(|function| EntryFunctionCall::new(function, &package))
+                .collect::<Result<Vec<_>>>()
+        };
+        Ok(Self {
+            name: built_package.name().to_string(),
+            num_publishers: spec.num_publishers,
+            sender_use_account_pool: spec.sender_use_account_pool,
+            initialize: resolve(&spec.initialize)?,
+            entry_functions: resolve(&spec.entry_functions)?,
+            package,
+            receivers: spec.receivers,
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn num_publishers(&self) -> usize {
+        self.num_publishers
+    }
+
+    pub fn sender_use_account_pool(&self) -> bool {
+        self.sender_use_account_pool
+    }
+
+    pub fn package(&self) -> &Package {
+        &self.package
+    }
+}
+
+/// An entry function of the package, checked against its signature.
+#[derive(Clone, Debug)]
+struct EntryFunctionCall {
+    module: String,
+    function: Identifier,
+    type_args: Vec<TypeTag>,
+    args: Vec<ArgumentSpec>,
+    weight: usize,
+}
+
+impl EntryFunctionCall {
+    fn new(spec: &EntryFunctionSpec, package: &Package) -> Result<Self> {
+        let Package::Simple(modules, _) = package;
+        let module = modules
+            .iter()
+            .find(|(name, _)| name == &spec.module)
+            .map(|(_, module)| module)
+            .ok_or_else(|| format_err!("Module {} not found in package", spec.module))?;
+        let handle = module
+            .function_defs()
+            .iter()
+            .filter(|def| def.is_entry)
+            .map(|def| module.function_handle_at(def.function))
+            .find(|handle| module.identifier_at(handle.name).as_str() == spec.function)
+            .ok_or_else(|| {
+                format_err!(
+                    "Entry function {} not found in module {}",
+                    spec.function,
+                    spec.module
+                )
+            })?;
+
+        let type_args = spec.parse_type_args()?;
+        ensure!(
+            type_args.len() == handle.type_parameters.len(),
+            "{}::{} takes {} type arguments, got {}",
+            spec.module,
+            spec.function,
+            handle.type_parameters.len(),
+            type_args.len(),
+        );
+        let params: Vec<_> = module
+            .signature_at(handle.parameters)
+            .0
+            .iter()
+            .skip_while(|token| match token {
+                SignatureToken::Signer => true,
+                SignatureToken::Reference(inner) => **inner == SignatureToken::Signer,
+                _ => false,
+            })
+            .collect();
+        ensure!(
+            params.len() == spec.args.len(),
+            "{}::{} takes {} arguments besides signers, got {}",
+            spec.module,
+            spec.function,
+            params.len(),
+            spec.args.len(),
+        );
+        for (index, (param, arg)) in params.into_iter().zip(&spec.args).enumerate() {
+            if let Some(expected) = arg.expected_type() {
+                if param != &expected {
+                    bail!(
+                        "Argument {} of {}::{} has type {:?}, which doesn't match {:?}",
+                        index,
+                        spec.module,
+                        spec.function,
+                        param,
+                        arg,
+                    );
+                }
+            }
+        }
+
+        Ok(Self {
+            module: spec.module.clone(),
+            function: Identifier::new(spec.function.as_str())?,
+            type_args,
+            args: spec.args.clone(),
+            weight: spec.weight,
+        })
+    }
+
+    fn create_payload(
+        &self,
+        package: &Package,
+        context: &ArgumentContext,
+        rng: &mut StdRng,
+    ) -> TransactionPayload {
+        TransactionPayload::EntryFunction(EntryFunction::new(
+            package.get_module_id(&self.module),
+            self.function.clone(),
+            self.type_args
+                .iter()
+                .map(|type_arg| replace_placeholder(type_arg, context.publisher))
+                .collect(),
+            self.args
+                .iter()
+                .map(|arg| arg.generate(context, rng))
+                .collect(),
+        ))
+    }
+}
+
+fn replace_placeholder(type_tag: &TypeTag, publisher: AccountAddress) -> TypeTag {
+    match type_tag {
+        TypeTag::Vector(inner) => TypeTag::Vector(Box::new(replace_placeholder(inner, publisher))),
+        TypeTag::Struct(struct_tag) => {
+            let mut struct_tag = struct_tag.clone();
+            if struct_tag.address == PUBLISHER_PLACEHOLDER {
+                struct_tag.address = publisher;
+            }
+            struct_tag.type_args = struct_tag
+                .type_args
+                .iter()
+                .map(|type_arg| replace_placeholder(type_arg, publisher))
+                .collect();
+            TypeTag::Struct(struct_tag)
+        },
+        _ => type_tag.clone(),
+    }
+}
+
+struct ArgumentContext<'a> {
+    sender: AccountAddress,
+    publisher: AccountAddress,
+    receivers: &'a ReceiverPool,
+}
+
+struct ReceiverPool {
+    addresses: Vec<AccountAddress>,
+    conflict_ratio: f64,
+    hot_set_size: usize,
+}
+
+impl ReceiverPool {
+    fn new(spec: &ReceiverPoolSpec, rng: &mut StdRng) -> Self {
+        Self {
+            addresses: (0..spec.size)
+                .map(|_| AccountAddress::new(rng.gen()))
+                .collect(),
+            conflict_ratio: spec.conflict_ratio,
+            hot_set_size: spec.hot_set_size,
+        }
+    }
+
+    fn pick(&self, rng: &mut StdRng) -> AccountAddress {
+        let candidates = if rng.gen_bool(self.conflict_ratio) {
+            self.hot_set_size
+        } else {
+            self.addresses.len()
+        };
+        self.addresses[rng.gen_range(0, candidates)]
+    }
+}
+
+/// Calls the entry functions of a [`CustomWorkload`].
+pub struct CustomWorkloadGenerator {
+    workload: Arc<CustomWorkload>,
+}
+
+impl CustomWorkloadGenerator {
+    pub fn new(workload: Arc<CustomWorkload>) -> Self {
+        Self { workload }
+    }
+
+    fn pick_random(entry_functions: &[EntryFunctionCall], rng: &mut StdRng) -> usize {
+        let total_weight: usize = entry_functions.iter().map(|f| f.weight).sum();
+        let mut picked = rng.gen_range(0, total_weight);
+        for (index, function) in entry_functions.iter().enumerate() {
+            if picked < function.weight {
+                return index;
+            }
+            picked -= function.weight;
+        }
+        unreachable!();
+    }
+}
+
+#[async_trait]
+impl UserModuleTransactionGenerator for CustomWorkloadGenerator {
+    fn initialize_package(
+        &mut self,
+        package: &Package,
+        publisher: &mut LocalAccount,
+        txn_factory: &TransactionFactory,
+        rng: &mut StdRng,
+    ) -> Vec<SignedTransaction> {
+        let receivers = ReceiverPool::new(&self.workload.receivers, rng);
+        let context = ArgumentContext {
+            sender: publisher.address(),
+            publisher: publisher.address(),
+            receivers: &receivers,
+        };
+        self.workload
+            .initialize
+            .iter()
+            .map(|function| {
+                let payload = function.create_payload(package, &context, rng);
+                publisher.sign_with_transaction_builder(txn_factory.payload(payload))
+            })
+            .collect()
+    }
+
+    async fn create_generator_fn(
+        &self,
+        _root_account: &dyn RootAccountHandle,
+        _txn_factory: &TransactionFactory,
+        _txn_executor: &dyn ReliableTransactionSubmitter,
+        rng: &mut StdRng,
+    ) -> Arc<TransactionGeneratorWorker> {
+        let workload = self.workload.clone();
+        let receivers = ReceiverPool::new(&workload.receivers, rng);
+
+        Arc::new(move |account, package, publisher, txn_factory, rng| {
+            let function =
+                &workload.entry_functions[Self::pick_random(&workload.entry_functions, rng)];
+            let context = ArgumentContext {
+                sender: account.address(),
+                publisher: publisher.address(),
+                receivers: &receivers,
+            };
+            let payload = function.create_payload(package, &context, rng);
+            Some(account.sign_with_transaction_builder(txn_factory.payload(payload)))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_sdk::types::chain_id::ChainId;
+    use aptos_temppath::TempPath;
+    use rand::SeedableRng;
+
+    const COUNTER_MOVE_TOML: &str = r#"
+[package]
+name = "Counter"
+version = "0.0.0"
+
+[addresses]
+my_addr = "_"
+"#;
+
+    const COUNTER_MODULE: &str = r#"
+module my_addr::counter {
+    struct Token {}
+
+    struct Counter has key {
+        value: u64,
+    }
+
+    public entry fun init(account: &signer) {
+        move_to(account, Counter { value: 0 });
+    }
+
+    public entry fun transfer<T>(_account: &signer, _receiver: address, _amount: u64) {}
+}
+"#;
+
+    /// Writes the counter package and a spec using it with the given entry functions, returning
+    /// the path of the spec.
+    fn write_workload(dir: &TempPath, entry_functions: &str) -> PathBuf {
+        let package_dir = dir.path().join("counter");
+        std::fs::create_dir_all(package_dir.join("sources")).unwrap();
+        std::fs::write(package_dir.join("Move.toml"), COUNTER_MOVE_TOML).unwrap();
+        std::fs::write(package_dir.join("sources/counter.move"), COUNTER_MODULE).unwrap();
+
+        let spec_path = dir.path().join("workload.yaml");
+        std::fs::write(
+            &spec_path,
+            format!(
+                "package: {{path: counter, address_name: my_addr}}\n\
+                 num_publishers: 2\n\
+                 initialize: [{{module: counter, function: init}}]\n\
+                 entry_functions: {}\n\
+                 receivers: {{size: 10}}\n",
+                entry_functions
+            ),
+        )
+        .unwrap();
+        spec_path
+    }
+
+    #[test]
+    fn test_parse_and_validate() {
+        let spec = WorkloadSpec::parse(
+            r#"
+package:
+  path: my_package
+  address_name: my_addr
+num_publishers: 2
+entry_functions:
+  - module: counter
+    function: transfer
+    type_args: ["{publisher}::counter::Token"]
+    args:
+      - type: receiver
+      - type: u64_range
+        min: 1
+        max: 100
+    weight: 3
+  - module: counter
+    function: reset
+receivers:
+  conflict_ratio: 0.5
+"#,
+        )
+        .unwrap();
+        spec.validate().unwrap();
+        assert_eq!(spec.entry_functions[0].args.len(), 2);
+        assert_eq!(spec.entry_functions[1].weight, 1);
+        assert_eq!(spec.receivers.size, 1000);
+
+        let type_args = spec.entry_functions[0].parse_type_args().unwrap();
+        let publisher = AccountAddress::random();
+        assert_eq!(
+            replace_placeholder(&type_args[0], publisher),
+            TypeTag::from_str(&format!("{}::counter::Token", publisher.to_hex_literal())).unwrap()
+        );
+
+        // JSON is accepted as well.
+        let json_spec = WorkloadSpec::parse(
+            r#"{"package": {"path": "p", "address_name": "a"},
+                "entry_functions": [{"module": "m", "function": "f",
+                                     "args": [{"type": "u64", "value": 5}]}]}"#,
+        )
+        .unwrap();
+        json_spec.validate().unwrap();
+    }
+
+    #[test]
+    fn test_invalid_specs() {
+        let parse = |entry_functions: &str, receivers: &str| {
+            WorkloadSpec::parse(&format!(
+                "package: {{path: p, address_name: a}}\nentry_functions: {}\nreceivers: {}",
+                entry_functions, receivers
+            ))
+        };
+
+        assert!(parse("[]", "{}").unwrap().validate().is_err());
+        assert!(parse("[{module: m, function: f, weight: 0}]", "{}")
+            .unwrap()
+            .validate()
+            .is_err());
+        assert!(parse(
+            "[{module: m, function: f, args: [{type: u64_range, min: 5, max: 5}]}]",
+            "{}"
+        )
+        .unwrap()
+        .validate()
+        .is_err());
+        assert!(parse(
+            "[{module: m, function: f, args: [{type: receiver}]}]",
+            "{conflict_ratio: 0.5, hot_set_size: 0}"
+        )
+        .unwrap()
+        .validate()
+        .is_err());
+        assert!(parse("[{module: m, function: f, unknown: 1}]", "{}").is_err());
+    }
+
+    #[test]
+    fn test_build_and_publish() {
+        let dir = TempPath::new();
+        dir.create_as_dir().unwrap();
+        let spec_path = write_workload(
+            &dir,
+            "[{module: counter, function: transfer, type_args: [\"{publisher}::counter::Token\"], \
+             args: [{type: receiver}, {type: u64_range, min: 1, max: 100}]}]",
+        );
+        let workload = Arc::new(CustomWorkload::load(&spec_path).unwrap());
+        assert_eq!(workload.name(), "Counter");
+        assert_eq!(workload.num_publishers(), 2);
+
+        // Each publisher publishes its own copy of the package.
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut publisher = LocalAccount::generate(&mut rng);
+        let package = workload.package().update(publisher.address(), 0);
+        assert_eq!(
+            package.get_module_id("counter").address(),
+            &publisher.address()
+        );
+        assert!(matches!(
+            package.publish_transaction_payload(),
+            TransactionPayload::EntryFunction(_)
+        ));
+
+        let txn_factory = TransactionFactory::new(ChainId::test());
+        let mut generator = CustomWorkloadGenerator::new(workload.clone());
+        let init_txns =
+            generator.initialize_package(&package, &mut publisher, &txn_factory, &mut rng);
+        assert_eq!(init_txns.len(), 1);
+        let TransactionPayload::EntryFunction(init) = init_txns[0].payload() else {
+            panic!("Expected an entry function");
+        };
+        assert_eq!(init.module(), &package.get_module_id("counter"));
+        assert_eq!(init.function().as_str(), "init");
+
+        // The calls target the copy of the publisher, with the generated arguments.
+        let receivers = ReceiverPool::new(&workload.receivers, &mut rng);
+        let sender = AccountAddress::random();
+        let context = ArgumentContext {
+            sender,
+            publisher: publisher.address(),
+            receivers: &receivers,
+        };
+        let TransactionPayload::EntryFunction(transfer) =
+            workload.entry_functions[0].create_payload(&package, &context, &mut rng)
+        else {
+            panic!("Expected an entry function");
+        };
+        assert_eq!(transfer.module(), &package.get_module_id("counter"));
+        let token = format!("{}::counter::Token", publisher.address().to_hex_literal());
+        assert_eq!(transfer.ty_args(), &[TypeTag::from_str(&token).unwrap()]);
+        let receiver: AccountAddress = bcs::from_bytes(&transfer.args()[0]).unwrap();
+        assert!(receivers.addresses.contains(&receiver));
+        let amount: u64 = bcs::from_bytes(&transfer.args()[1]).unwrap();
+        assert!((1..100).contains(&amount));
+    }
+
+    #[test]
+    fn test_build_rejects_mismatching_functions() {
+        let load = |entry_functions: &str| {
+            let dir = TempPath::new();
+            dir.create_as_dir().unwrap();
+            CustomWorkload::load(&write_workload(&dir, entry_functions))
+        };
+
+        // Unknown functions, and functions that aren't entry functions, are rejected.
+        assert!(load("[{module: counter, function: increment}]").is_err());
+        // So are missing type arguments, and arguments of the wrong count or type.
+        assert!(load(
+            "[{module: counter, function: transfer, args: [{type: receiver}, {type: u64, value: 1}]}]"
+        )
+        .is_err());
+        assert!(load(
+            "[{module: counter, function: transfer, type_args: [u8], args: [{type: receiver}]}]"
+        )
+        .is_err());
+        assert!(load(
+            "[{module: counter, function: transfer, type_args: [u8], \
+             args: [{type: receiver}, {type: u8, value: 1}]}]"
+        )
+        .is_err());
+        assert!(load(
+            "[{module: counter, function: transfer, type_args: [u8], \
+             args: [{type: receiver}, {type: u64, value: 1}]}]"
+        )
+        .is_ok());
+    }
+}
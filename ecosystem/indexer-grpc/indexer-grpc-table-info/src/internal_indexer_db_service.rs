@@ -52,7 +52,7 @@ impl InternalIndexerDBService {
         );
 
         let internal_indexer_db_config =
-            InternalIndexerDBConfig::new(true, true, true, 0, true, 10_000);
+            InternalIndexerDBConfig::new(true, true, true, 0, true, true, 10_000);
         Some(InternalIndexerDB::new(arc_db, internal_indexer_db_config))
     }
 
@@ -164,14 +164,67 @@ impl InternalIndexerDBService {
         Ok(start_version)
     }
 
+    /// Returns the next version to backfill in the event by type index, if it lags behind
+    /// the versions already processed by the indexer (e.g., if the index was enabled on an
+    /// existing database).
+    pub fn get_event_by_type_backfill_version(&self) -> Result<Option<Version>> {
+        if !self.db_indexer.event_by_type_backfill_pending() {
+            return Ok(None);
+        }
+
+        let lowest_viable_version = self.db_indexer.get_main_db_lowest_viable_version()?;
+        let backfill_version = self
+            .db_indexer
+            .indexer_db
+            .get_event_by_type_version()?
+            .map_or(0, |v| v + 1)
+            .max(lowest_viable_version);
+        info!(
+            backfill_version = backfill_version,
+            "Backfilling the event by type index."
+        );
+        Ok(Some(backfill_version))
+    }
+
+    /// Backfills a batch of the event by type index, up to the latest version processed by
+    /// the indexer (i.e., `next_version - 1`). The backfill is interleaved with the processed
+    /// batches, so it doesn't block them, and its progress is persisted with each batch.
+    /// Returns the next version to backfill, or None if the index has caught up.
+    pub fn backfill_events_by_type(
+        &self,
+        backfill_version: Version,
+        next_version: Version,
+    ) -> Result<Option<Version>> {
+        let backfill_version = self
+            .db_indexer
+            .backfill_events_by_type(backfill_version, next_version - 1)?;
+        if backfill_version < next_version {
+            return Ok(Some(backfill_version));
+        }
+
+        info!(
+            version = next_version - 1,
+            "Finished backfilling the event by type index."
+        );
+        Ok(None)
+    }
+
     pub async fn run(&mut self, node_config: &NodeConfig) -> Result<()> {
         let mut start_version = self.get_start_version(node_config).await?;
+        let mut backfill_version = self.get_event_by_type_backfill_version()?;
 
         loop {
             let start_time: std::time::Instant = std::time::Instant::now();
             let next_version = self.db_indexer.process_a_batch(start_version)?;
+            if let Some(version) = backfill_version {
+                backfill_version = self.backfill_events_by_type(version, next_version)?;
+            }
 
             if next_version == start_version {
+                if backfill_version.is_some() {
+                    continue;
+                }
+
                 if let Ok(recv_res) =
                     tokio::time::timeout(Duration::from_millis(100), self.update_receiver.changed())
                         .await
@@ -206,14 +259,21 @@ impl InternalIndexerDBService {
         end_version: Option<Version>,
     ) -> Result<()> {
         let mut start_version = self.get_start_version(node_config).await?;
+        let mut backfill_version = self.get_event_by_type_backfill_version()?;
         while start_version <= end_version.unwrap_or(std::u64::MAX) {
             let next_version = self.db_indexer.process_a_batch(start_version)?;
+            if let Some(version) = backfill_version {
+                backfill_version = self.backfill_events_by_type(version, next_version)?;
+            }
             if next_version == start_version {
                 tokio::time::sleep(std::time::Duration::from_millis(100)).await;
                 continue;
             }
             start_version = next_version;
         }
+        while let Some(version) = backfill_version {
+            backfill_version = self.backfill_events_by_type(version, start_version)?;
+        }
         // We should never stop the internal indexer
         tokio::time::sleep(std::time::Duration::from_secs(100)).await;
 
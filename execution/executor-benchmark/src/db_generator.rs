@@ -99,6 +99,8 @@ pub(crate) fn bootstrap_with_genesis(
             BUFFERED_STATE_TARGET_ITEMS,
             DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
             None,
+            None,
+            None,
         )
         .expect("DB should open."),
     );
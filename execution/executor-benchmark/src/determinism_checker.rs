@@ -0,0 +1,771 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Checks that blocks execute deterministically across executor configurations.
+//!
+//! Every block is executed sequentially, and by each of the configured executors: Block-STM with
+//! different concurrency levels, the sharded executor and the native or uncoordinated executors.
+//! Outputs of every configuration are compared to the outputs of sequential execution. If they
+//! diverge, the block is minimized with delta debugging to the smallest set of transactions that
+//! still diverges, which can be saved and replayed with `aptos-replay-benchmark`.
+
+use crate::{
+    default_benchmark_features, init_db,
+    native::{
+        aptos_vm_uncoordinated::AptosVMParallelUncoordinatedBlockExecutor,
+        native_transaction::NativeTransaction,
+        native_vm::NativeVMBlockExecutor,
+        parallel_uncoordinated_block_executor::{
+            NativeParallelUncoordinatedBlockExecutor, NativeRawTransactionExecutor,
+            NativeValueCacheRawTransactionExecutor,
+        },
+    },
+    state_generator::{GeneratedState, GeneratedStateWorkload},
+    transaction_generator::TransactionGenerator,
+    BenchmarkWorkload,
+};
+use aptos_block_executor::txn_provider::default::DefaultTxnProvider;
+use aptos_block_partitioner::{
+    v2::config::PartitionerV2Config, BlockPartitioner, PartitionerConfig,
+};
+use aptos_config::config::NO_OP_STORAGE_PRUNER_CONFIG;
+use aptos_logger::info;
+use aptos_replay_benchmark::{
+    block::{load_blocks, save_blocks, Block},
+    diff::{DiffConfig, TransactionDiff},
+};
+use aptos_storage_interface::state_store::state_view::db_state_view::{
+    DbStateView, LatestDbStateCheckpointView,
+};
+use aptos_types::{
+    block_executor::{
+        config::{
+            BlockExecutorConfig, BlockExecutorConfigFromOnchain, BlockExecutorLocalConfig,
+            BlockExecutorModuleCacheLocalConfig,
+        },
+        partitioner::PartitionedTransactions,
+        transaction_slice_metadata::TransactionSliceMetadata,
+    },
+    state_store::{
+        state_key::StateKey, state_storage_usage::StateStorageUsage, state_value::StateValue,
+        StateView, StateViewResult, TStateView,
+    },
+    transaction::{
+        analyzed_transaction::{try_get_read_write_hints, AnalyzedTransaction},
+        signature_verified_transaction::{
+            into_signature_verified_block, SignatureVerifiedTransaction,
+        },
+        BlockOutput, TransactionOutput, Version,
+    },
+    write_set::TransactionWrite,
+};
+use aptos_vm::{
+    aptos_vm::AptosVMBlockExecutor,
+    sharded_block_executor::{local_executor_shard::LocalExecutorClient, ShardedBlockExecutor},
+    VMBlockExecutor,
+};
+use clap::ValueEnum;
+use move_core_types::vm_status::VMStatus;
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    fmt,
+    path::{Path, PathBuf},
+    sync::{mpsc, Arc},
+};
+
+/// Executors other than Block-STM and the sharded executor, whose outputs can be checked against
+/// sequential execution.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, ValueEnum)]
+pub enum NativeExecutorType {
+    /// AptosVM executing all transactions on the state at the beginning of the block. Outputs
+    /// diverge from sequential execution if transactions in the block conflict.
+    AptosVMParallelUncoordinated,
+    /// NativeVM executing transactions via Block-STM.
+    NativeVMWithBlockSTM,
+    /// Native rust code executing all transactions on the state at the beginning of the block.
+    NativeParallelUncoordinated,
+    /// Same as [NativeExecutorType::NativeParallelUncoordinated], but with a value cache.
+    NativeValueCacheParallelUncoordinated,
+}
+
+/// An executor configuration whose outputs are compared against sequential execution.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ExecutorConfiguration {
+    BlockStm { concurrency_level: usize },
+    Sharded { num_shards: usize },
+    Native(NativeExecutorType),
+}
+
+impl ExecutorConfiguration {
+    /// Returns true if this configuration can execute all given transactions. The sharded and the
+    /// native executors only support transfer-like transactions, and panic on all others.
+    fn supports(&self, txns: &[SignatureVerifiedTransaction]) -> bool {
+        match self {
+            Self::BlockStm { .. }
+            | Self::Native(NativeExecutorType::AptosVMParallelUncoordinated) => true,
+            Self::Sharded { .. } => txns
+                .iter()
+                .all(|txn| txn.is_valid() && try_get_read_write_hints(txn).is_some()),
+            Self::Native(_) => txns
+                .iter()
+                .all(|txn| NativeTransaction::try_parse(txn).is_some()),
+        }
+    }
+
+    /// Returns which parts of the outputs are compared. Native executors do not run Move and do
+    /// not charge gas, so only the structure of their outputs can match sequential execution.
+    fn diff_config(&self) -> DiffConfig {
+        match self {
+            Self::BlockStm { .. }
+            | Self::Sharded { .. }
+            | Self::Native(NativeExecutorType::AptosVMParallelUncoordinated) => {
+                DiffConfig::default()
+            },
+            Self::Native(_) => DiffConfig {
+                compare_gas_used: false,
+                compare_values: false,
+            },
+        }
+    }
+}
+
+impl fmt::Display for ExecutorConfiguration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::BlockStm { concurrency_level } => {
+                write!(f, "Block-STM with concurrency level {}", concurrency_level)
+            },
+            Self::Sharded { num_shards } => {
+                write!(f, "sharded execution with {} shards", num_shards)
+            },
+            Self::Native(executor_type) => write!(f, "{:?}", executor_type),
+        }
+    }
+}
+
+/// Parameters of the determinism check.
+pub struct DeterminismCheckerConfig {
+    /// Configurations to compare against sequential execution.
+    pub configurations: Vec<ExecutorConfiguration>,
+    /// Partitioner used by sharded configurations.
+    pub partitioner_config: PartitionerV2Config,
+    /// Number of times a configuration executes a block before its outputs are considered to
+    /// match, since some non-determinism only shows up for specific interleavings.
+    pub num_attempts: usize,
+    /// If set, minimized divergent blocks are saved to this file, so that they can be replayed
+    /// with `aptos-replay-benchmark --load-blocks`.
+    pub divergent_blocks_file: Option<PathBuf>,
+}
+
+type ExecutionResult = Result<Vec<TransactionOutput>, VMStatus>;
+
+/// How the outputs of a configuration differ from the outputs of sequential execution.
+pub enum Mismatch {
+    /// One of the executions failed, or both failed with different errors.
+    Error {
+        sequential: Option<VMStatus>,
+        other: Option<VMStatus>,
+    },
+    /// Executions produced different numbers of outputs.
+    NumOutputs { sequential: usize, other: usize },
+    /// Non-empty differences of outputs, indexed by the position of the transaction in the
+    /// executed order.
+    Outputs(Vec<(usize, TransactionDiff)>),
+}
+
+impl Mismatch {
+    fn from_results(
+        sequential: ExecutionResult,
+        other: ExecutionResult,
+        diff_config: DiffConfig,
+    ) -> Option<Self> {
+        match (sequential, other) {
+            (Ok(sequential), Ok(other)) => {
+                if sequential.len() != other.len() {
+                    return Some(Self::NumOutputs {
+                        sequential: sequential.len(),
+                        other: other.len(),
+                    });
+                }
+                let diffs = sequential
+                    .into_iter()
+                    .zip(other)
+                    .map(|(left, right)| {
+                        TransactionDiff::from_outputs_with_config(left, right, diff_config)
+                    })
+                    .enumerate()
+                    .filter(|(_, diff)| !diff.is_empty())
+                    .collect::<Vec<_>>();
+                (!diffs.is_empty()).then_some(Self::Outputs(diffs))
+            },
+            (Err(sequential), Err(other)) if sequential == other => None,
+            (sequential, other) => Some(Self::Error {
+                sequential: sequential.err(),
+                other: other.err(),
+            }),
+        }
+    }
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Error { sequential, other } => writeln!(
+                f,
+                "[error] sequential: {:?}, other: {:?}",
+                sequential, other
+            ),
+            Self::NumOutputs { sequential, other } => writeln!(
+                f,
+                "[number of outputs] sequential: {}, other: {}",
+                sequential, other
+            ),
+            Self::Outputs(diffs) => {
+                for (idx, diff) in diffs {
+                    writeln!(f, "Output {} diff (before is sequential):\n{}", idx, diff)?;
+                }
+                Ok(())
+            },
+        }
+    }
+}
+
+/// Outputs of a configuration that diverged from sequential execution for some block.
+pub struct Divergence {
+    pub configuration: ExecutorConfiguration,
+    /// Indices of the transactions of the minimized block in the original block, in order.
+    pub txn_indices: Vec<usize>,
+    /// How the outputs of the minimized block diverge.
+    pub mismatch: Mismatch,
+}
+
+/// Executes blocks with different configurations, and compares their outputs to the outputs of
+/// sequential execution.
+pub struct DeterminismChecker<S: StateView + Sync + Send + 'static> {
+    configurations: Vec<ExecutorConfiguration>,
+    num_attempts: usize,
+    partitioner: Box<dyn BlockPartitioner>,
+    sharded_executors: HashMap<usize, ShardedBlockExecutor<S, LocalExecutorClient<S>>>,
+}
+
+impl<S: StateView + Sync + Send + 'static> DeterminismChecker<S> {
+    pub fn new(config: &DeterminismCheckerConfig) -> Self {
+        assert!(config.num_attempts > 0, "At least one attempt is needed.");
+        let sharded_executors = config
+            .configurations
+            .iter()
+            .filter_map(|configuration| match configuration {
+                ExecutorConfiguration::Sharded { num_shards } => Some((
+                    *num_shards,
+                    LocalExecutorClient::create_local_sharded_block_executor(*num_shards, None),
+                )),
+                _ => None,
+            })
+            .collect();
+        Self {
+            configurations: config.configurations.clone(),
+            num_attempts: config.num_attempts,
+            partitioner: config.partitioner_config.build(),
+            sharded_executors,
+        }
+    }
+
+    /// Executes the block with every configuration that supports its transactions, and returns
+    /// minimized divergences for configurations whose outputs differ from sequential execution.
+    pub fn check_block(
+        &self,
+        txns: &[SignatureVerifiedTransaction],
+        state_view: &Arc<S>,
+    ) -> Vec<Divergence> {
+        self.configurations
+            .iter()
+            .filter(|configuration| {
+                let supported = configuration.supports(txns);
+                if !supported {
+                    info!(
+                        "Skipping {}: block has unsupported transactions",
+                        configuration
+                    );
+                }
+                supported
+            })
+            .filter_map(|configuration| self.check_configuration(*configuration, txns, state_view))
+            .collect()
+    }
+
+    fn check_configuration(
+        &self,
+        configuration: ExecutorConfiguration,
+        txns: &[SignatureVerifiedTransaction],
+        state_view: &Arc<S>,
+    ) -> Option<Divergence> {
+        let find_mismatch = |txn_indices: &[usize]| {
+            let txns = txn_indices
+                .iter()
+                .map(|idx| txns[*idx].clone())
+                .collect::<Vec<_>>();
+            (0..self.num_attempts)
+                .find_map(|_| self.find_mismatch(configuration, &txns, state_view))
+        };
+
+        let all_txn_indices = (0..txns.len()).collect::<Vec<_>>();
+        let mismatch = find_mismatch(&all_txn_indices)?;
+
+        info!(
+            "Outputs of {} diverge for a block of {} transactions, minimizing",
+            configuration,
+            txns.len()
+        );
+        let txn_indices = minimize(all_txn_indices.clone(), |txn_indices| {
+            find_mismatch(txn_indices).is_some()
+        });
+
+        // Non-determinism may not be reproducible, in which case the whole block is reported.
+        let (txn_indices, mismatch) = match find_mismatch(&txn_indices) {
+            Some(minimized_mismatch) => (txn_indices, minimized_mismatch),
+            None => (all_txn_indices, mismatch),
+        };
+        Some(Divergence {
+            configuration,
+            txn_indices,
+            mismatch,
+        })
+    }
+
+    /// Executes transactions with the configuration once, and compares the outputs to the
+    /// outputs of sequential execution of transactions in the same order.
+    fn find_mismatch(
+        &self,
+        configuration: ExecutorConfiguration,
+        txns: &[SignatureVerifiedTransaction],
+        state_view: &Arc<S>,
+    ) -> Option<Mismatch> {
+        let (executed_txns, result) = match configuration {
+            ExecutorConfiguration::BlockStm { concurrency_level } => (
+                Cow::Borrowed(txns),
+                execute_with_block_stm(txns, state_view.as_ref(), concurrency_level),
+            ),
+            ExecutorConfiguration::Sharded { num_shards } => {
+                let (executed_txns, result) = self.execute_sharded(txns, state_view, num_shards);
+                (Cow::Owned(executed_txns), result)
+            },
+            ExecutorConfiguration::Native(executor_type) => (
+                Cow::Borrowed(txns),
+                execute_natively(executor_type, txns, state_view.as_ref()),
+            ),
+        };
+        let sequential_result = execute_sequentially(&executed_txns, state_view.as_ref());
+        Mismatch::from_results(sequential_result, result, configuration.diff_config())
+    }
+
+    /// Executes transactions with the sharded executor. Transactions may be reordered by the
+    /// partitioner, so returns them in the order of outputs.
+    fn execute_sharded(
+        &self,
+        txns: &[SignatureVerifiedTransaction],
+        state_view: &Arc<S>,
+        num_shards: usize,
+    ) -> (Vec<SignatureVerifiedTransaction>, ExecutionResult) {
+        let analyzed_txns = txns
+            .iter()
+            .cloned()
+            .map(AnalyzedTransaction::from)
+            .collect::<Vec<_>>();
+        let partitioned_txns = self.partitioner.partition(analyzed_txns, num_shards);
+        let executed_txns = PartitionedTransactions::flatten(partitioned_txns.clone())
+            .into_iter()
+            .map(AnalyzedTransaction::into_txn)
+            .collect();
+        let result = AptosVMBlockExecutor::execute_block_sharded(
+            &self.sharded_executors[&num_shards],
+            partitioned_txns,
+            state_view.clone(),
+            BlockExecutorConfigFromOnchain::new_no_block_limit(),
+        );
+        (executed_txns, result)
+    }
+}
+
+/// Executes transactions sequentially, i.e., with Block-STM with concurrency level of 1.
+fn execute_sequentially(
+    txns: &[SignatureVerifiedTransaction],
+    state_view: &(impl StateView + Sync),
+) -> ExecutionResult {
+    execute_with_block_stm(txns, state_view, 1)
+}
+
+fn execute_with_block_stm(
+    txns: &[SignatureVerifiedTransaction],
+    state_view: &(impl StateView + Sync),
+    concurrency_level: usize,
+) -> ExecutionResult {
+    let config = BlockExecutorConfig {
+        local: BlockExecutorLocalConfig {
+            concurrency_level,
+            // Falling back to sequential execution would hide Block-STM failures.
+            allow_fallback: false,
+            discard_failed_blocks: false,
+            module_cache_config: BlockExecutorModuleCacheLocalConfig::default(),
+        },
+        onchain: BlockExecutorConfigFromOnchain::new_no_block_limit(),
+    };
+    // Every execution uses a new executor, so that module caches are not shared.
+    AptosVMBlockExecutor::new()
+        .execute_block_with_config(
+            &DefaultTxnProvider::new(txns.to_vec()),
+            state_view,
+            config,
+            TransactionSliceMetadata::unknown(),
+        )
+        .map(BlockOutput::into_transaction_outputs_forced)
+}
+
+fn execute_natively(
+    executor_type: NativeExecutorType,
+    txns: &[SignatureVerifiedTransaction],
+    state_view: &(impl StateView + Sync),
+) -> ExecutionResult {
+    fn execute<E: VMBlockExecutor>(
+        txns: &[SignatureVerifiedTransaction],
+        state_view: &(impl StateView + Sync),
+    ) -> ExecutionResult {
+        E::new()
+            .execute_block(
+                &DefaultTxnProvider::new(txns.to_vec()),
+                state_view,
+                BlockExecutorConfigFromOnchain::new_no_block_limit(),
+                TransactionSliceMetadata::unknown(),
+            )
+            .map(BlockOutput::into_transaction_outputs_forced)
+    }
+
+    match executor_type {
+        NativeExecutorType::AptosVMParallelUncoordinated => {
+            execute::<AptosVMParallelUncoordinatedBlockExecutor>(txns, state_view)
+        },
+        NativeExecutorType::NativeVMWithBlockSTM => {
+            execute::<NativeVMBlockExecutor>(txns, state_view)
+        },
+        NativeExecutorType::NativeParallelUncoordinated => execute::<
+            NativeParallelUncoordinatedBlockExecutor<NativeRawTransactionExecutor>,
+        >(txns, state_view),
+        NativeExecutorType::NativeValueCacheParallelUncoordinated => execute::<
+            NativeParallelUncoordinatedBlockExecutor<NativeValueCacheRawTransactionExecutor>,
+        >(txns, state_view),
+    }
+}
+
+/// Returns a 1-minimal subsequence of items for which the predicate still holds, using delta
+/// debugging (ddmin): removing any single item from the result makes the predicate false. The
+/// predicate must hold for all items, and the relative order of items is preserved.
+fn minimize<T: Clone>(mut items: Vec<T>, mut predicate: impl FnMut(&[T]) -> bool) -> Vec<T> {
+    let mut num_chunks = 2;
+    while items.len() > 1 {
+        let chunk_size = items.len().div_ceil(num_chunks);
+        let chunks = items
+            .chunks(chunk_size)
+            .map(<[T]>::to_vec)
+            .collect::<Vec<_>>();
+
+        // First, check if any chunk alone is enough.
+        if let Some(chunk) = chunks.iter().find(|chunk| predicate(chunk)) {
+            items = chunk.clone();
+            num_chunks = 2;
+            continue;
+        }
+
+        // Otherwise, check if any chunk can be removed. With two chunks, complements are the
+        // chunks themselves and have been checked already.
+        let complement = if chunks.len() > 2 {
+            (0..chunks.len()).find_map(|removed_idx| {
+                let complement = chunks
+                    .iter()
+                    .enumerate()
+                    .filter(|(idx, _)| *idx != removed_idx)
+                    .flat_map(|(_, chunk)| chunk.iter().cloned())
+                    .collect::<Vec<_>>();
+                predicate(&complement).then_some(complement)
+            })
+        } else {
+            None
+        };
+        if let Some(complement) = complement {
+            items = complement;
+            num_chunks = (num_chunks - 1).max(2);
+            continue;
+        }
+
+        // Otherwise, split into smaller chunks, unless they already are single items.
+        if num_chunks >= items.len() {
+            break;
+        }
+        num_chunks = (num_chunks * 2).min(items.len());
+    }
+    items
+}
+
+/// State view with the writes of the previous blocks applied on top of the DB state, so that
+/// generated blocks can be checked one after another without committing them.
+struct OverlayStateView {
+    base: DbStateView,
+    writes: HashMap<StateKey, Option<StateValue>>,
+}
+
+impl OverlayStateView {
+    fn apply_outputs(&mut self, outputs: &[TransactionOutput]) {
+        for output in outputs {
+            if !output.status().is_discarded() {
+                for (state_key, write_op) in output.write_set() {
+                    self.writes
+                        .insert(state_key.clone(), write_op.as_state_value());
+                }
+            }
+        }
+    }
+}
+
+impl TStateView for OverlayStateView {
+    type Key = StateKey;
+
+    fn get_state_value(&self, state_key: &StateKey) -> StateViewResult<Option<StateValue>> {
+        match self.writes.get(state_key) {
+            Some(maybe_state_value) => Ok(maybe_state_value.clone()),
+            None => self.base.get_state_value(state_key),
+        }
+    }
+
+    fn get_usage(&self) -> StateViewResult<StateStorageUsage> {
+        // Only approximate, since writes of the previous blocks are not accounted.
+        self.base.get_usage()
+    }
+}
+
+/// Checks blocks saved with `aptos-replay-benchmark --save-blocks`. Every block is executed on
+/// its own input state. Returns the number of divergences found.
+pub fn check_saved_blocks(config: &DeterminismCheckerConfig, blocks_file: &Path) -> usize {
+    let blocks = load_blocks(blocks_file).expect("Blocks should be loaded");
+    info!(
+        "Loaded {} blocks from {}",
+        blocks.len(),
+        blocks_file.display()
+    );
+
+    let blocks = blocks
+        .into_iter()
+        .map(Block::into_parts)
+        .collect::<Vec<_>>();
+    let checker = DeterminismChecker::new(config);
+    let mut divergent_blocks = vec![];
+    let mut num_divergences = 0;
+    for (begin, txns, inputs) in blocks {
+        let inputs = Arc::new(inputs);
+        let divergences = checker.check_block(&txns, &inputs);
+        num_divergences += divergences.len();
+        report_divergences(
+            begin,
+            &txns,
+            inputs.as_ref(),
+            divergences,
+            &mut divergent_blocks,
+        );
+    }
+    save_divergent_blocks(config, &divergent_blocks);
+    num_divergences
+}
+
+/// Generates blocks of the workload over the DB created by `create-db`, and checks them one
+/// after another. Blocks are not committed: each block is executed on top of the DB state and
+/// the outputs of sequential execution of the previous blocks. Returns the number of divergences
+/// found.
+#[allow(clippy::too_many_arguments)]
+pub fn check_workload(
+    config: &DeterminismCheckerConfig,
+    workload: BenchmarkWorkload,
+    block_size: usize,
+    num_blocks: usize,
+    transactions_per_sender: usize,
+    num_main_signer_accounts: usize,
+    source_dir: impl AsRef<Path>,
+    enable_storage_sharding: bool,
+    num_generator_workers: usize,
+    is_keyless: bool,
+) -> usize {
+    let (mut node_config, genesis_key) =
+        aptos_genesis::test_utils::test_config_with_custom_features(default_benchmark_features());
+    node_config.storage.dir = source_dir.as_ref().to_path_buf();
+    node_config.storage.storage_pruner_config = NO_OP_STORAGE_PRUNER_CONFIG;
+    node_config.storage.rocksdb_configs.enable_storage_sharding = enable_storage_sharding;
+    let db = init_db(&node_config);
+    let root_account = TransactionGenerator::read_root_account(genesis_key, &db);
+
+    let generated_state = matches!(workload, BenchmarkWorkload::GeneratedState { .. })
+        .then(|| GeneratedState::read(&source_dir));
+    let num_accounts_to_load = generated_state
+        .as_ref()
+        .map_or(num_main_signer_accounts, |state| state.owners.len());
+
+    // Blocks are only checked once all of them are generated, so the channel must fit them all.
+    let (block_sender, block_receiver) = mpsc::sync_channel(num_blocks);
+    let mut generator = TransactionGenerator::new_with_existing_db(
+        db.clone(),
+        root_account,
+        block_sender,
+        &source_dir,
+        Some(num_accounts_to_load),
+        num_generator_workers,
+        is_keyless,
+    );
+    match workload {
+        BenchmarkWorkload::Transfer {
+            connected_tx_grps,
+            shuffle_connected_txns,
+            hotspot_probability,
+        } => {
+            generator.run_transfer(
+                block_size,
+                num_blocks,
+                transactions_per_sender,
+                connected_tx_grps,
+                shuffle_connected_txns,
+                hotspot_probability,
+            );
+        },
+        BenchmarkWorkload::GeneratedState {
+            workload_mix,
+            zipf_exponent,
+        } => {
+            let generated_state = generated_state.unwrap();
+            assert_eq!(generator.main_signer_addresses(), generated_state.owners);
+            let mut state_workload =
+                GeneratedStateWorkload::new(generated_state, workload_mix, zipf_exponent);
+            generator.run_payloads(
+                block_size,
+                std::iter::repeat_with(|| state_workload.gen_payload())
+                    .take(block_size * num_blocks),
+            );
+        },
+        BenchmarkWorkload::TransactionMix(_) => {
+            panic!("Transaction mix workloads need to commit their setup, and are not supported")
+        },
+    }
+    generator.drop_sender();
+
+    let mut state_view = OverlayStateView {
+        base: db
+            .reader
+            .latest_state_checkpoint_view()
+            .expect("State view should be created"),
+        writes: HashMap::new(),
+    };
+    let mut next_version = db.reader.expect_synced_version() + 1;
+
+    let checker = DeterminismChecker::new(config);
+    let mut divergent_blocks = vec![];
+    let mut num_divergences = 0;
+    for block in block_receiver {
+        let txns = into_signature_verified_block(block);
+        let num_txns = txns.len();
+
+        // The overlay is cloned because the sharded executor needs to own its state view.
+        let block_state_view = Arc::new(OverlayStateView {
+            base: state_view.base.clone(),
+            writes: state_view.writes.clone(),
+        });
+        let divergences = checker.check_block(&txns, &block_state_view);
+        num_divergences += divergences.len();
+        report_divergences(
+            next_version,
+            &txns,
+            block_state_view.as_ref(),
+            divergences,
+            &mut divergent_blocks,
+        );
+
+        let outputs = execute_sequentially(&txns, &state_view)
+            .unwrap_or_else(|err| panic!("Sequential execution failed: {:?}", err));
+        state_view.apply_outputs(&outputs);
+        next_version += num_txns as Version;
+    }
+    save_divergent_blocks(config, &divergent_blocks);
+    num_divergences
+}
+
+/// Prints divergences of a block, and creates self-contained blocks from the minimized
+/// transactions to save them later.
+fn report_divergences(
+    begin: Version,
+    txns: &[SignatureVerifiedTransaction],
+    state_view: &(impl StateView + Sync),
+    divergences: Vec<Divergence>,
+    divergent_blocks: &mut Vec<Block>,
+) {
+    if divergences.is_empty() {
+        info!(
+            "Block at version {} with {} transactions is deterministic",
+            begin,
+            txns.len()
+        );
+        return;
+    }
+
+    for divergence in divergences {
+        println!(
+            "Outputs of {} diverge from sequential execution for the block at version {}, \
+             minimized to transactions {:?}:\n{}",
+            divergence.configuration, begin, divergence.txn_indices, divergence.mismatch
+        );
+        let minimized_txns = divergence
+            .txn_indices
+            .iter()
+            .map(|idx| txns[*idx].clone().into_inner())
+            .collect();
+        divergent_blocks.push(Block::from_transactions(begin, minimized_txns, state_view));
+    }
+}
+
+fn save_divergent_blocks(config: &DeterminismCheckerConfig, divergent_blocks: &[Block]) {
+    if let Some(path) = &config.divergent_blocks_file {
+        save_blocks(divergent_blocks, path).expect("Divergent blocks should be saved");
+        println!(
+            "Saved {} minimized divergent blocks to {}",
+            divergent_blocks.len(),
+            path.display()
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::minimize;
+
+    #[test]
+    fn test_minimize_single_item() {
+        let minimized = minimize((0..100).collect(), |items| items.contains(&42));
+        assert_eq!(minimized, vec![42]);
+    }
+
+    #[test]
+    fn test_minimize_preserves_order() {
+        let minimized = minimize((0..100).collect(), |items| {
+            let position = |item| items.iter().position(|i| *i == item);
+            matches!((position(97), position(3)), (Some(i), Some(j)) if i < j)
+                || matches!((position(3), position(13)), (Some(i), Some(j)) if i < j)
+        });
+        assert_eq!(minimized, vec![3, 13]);
+    }
+
+    #[test]
+    fn test_minimize_is_one_minimal() {
+        // Holds if at least three of the items are even.
+        let predicate = |items: &[usize]| items.iter().filter(|i| *i % 2 == 0).count() >= 3;
+        let minimized = minimize((0..50).collect(), predicate);
+        assert_eq!(minimized.len(), 3);
+        assert!(predicate(&minimized));
+        for removed_idx in 0..minimized.len() {
+            let mut items = minimized.clone();
+            items.remove(removed_idx);
+            assert!(!predicate(&items));
+        }
+    }
+}
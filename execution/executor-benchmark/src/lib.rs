@@ -7,17 +7,23 @@ pub mod block_preparation;
 pub mod db_access;
 pub mod db_generator;
 mod db_reliable_submitter;
+pub mod determinism_checker;
 mod ledger_update_stage;
 mod metrics;
 pub mod native;
 pub mod pipeline;
+pub mod state_generator;
 pub mod transaction_committer;
 pub mod transaction_executor;
 pub mod transaction_generator;
 
 use crate::{
-    db_access::DbAccessUtil, pipeline::Pipeline, transaction_committer::TransactionCommitter,
-    transaction_executor::TransactionExecutor, transaction_generator::TransactionGenerator,
+    db_access::DbAccessUtil,
+    pipeline::Pipeline,
+    state_generator::{GeneratedState, GeneratedStateWorkload, StateWorkloadType},
+    transaction_committer::TransactionCommitter,
+    transaction_executor::TransactionExecutor,
+    transaction_generator::TransactionGenerator,
 };
 use aptos_block_executor::counters::{
     self as block_executor_counters, GasType, BLOCK_EXECUTOR_INNER_EXECUTE_BLOCK,
@@ -38,7 +44,9 @@ use aptos_logger::{info, warn};
 use aptos_metrics_core::Histogram;
 use aptos_sdk::types::LocalAccount;
 use aptos_storage_interface::{
-    state_store::state_view::db_state_view::LatestDbStateCheckpointView, DbReader, DbReaderWriter,
+    metrics::{STATE_CACHE_HITS, STATE_CACHE_MISSES},
+    state_store::state_view::db_state_view::LatestDbStateCheckpointView,
+    DbReader, DbReaderWriter,
 };
 use aptos_transaction_generator_lib::{
     create_txn_generator_creator, AlwaysApproveRootAccountHandle, TransactionGeneratorCreator,
@@ -73,6 +81,8 @@ pub fn init_db(config: &NodeConfig) -> DbReaderWriter {
             config.storage.buffered_state_target_items,
             config.storage.max_num_nodes_per_lru_cache_shard,
             None,
+            None,
+            None,
         )
         .expect("DB should open."),
     )
@@ -100,6 +110,11 @@ pub enum BenchmarkWorkload {
         shuffle_connected_txns: bool,
         hotspot_probability: Option<f32>,
     },
+    /// Accesses the state created by `state_generator::generate_state`.
+    GeneratedState {
+        workload_mix: Vec<(StateWorkloadType, usize)>,
+        zipf_exponent: f64,
+    },
 }
 
 /// Runs the benchmark with given parameters.
@@ -196,6 +211,15 @@ pub fn run_benchmark<V>(
 
     let mut num_accounts_to_load = num_main_signer_accounts;
 
+    let generated_state = if let BenchmarkWorkload::GeneratedState { .. } = &workload {
+        let generated_state = GeneratedState::read(&source_dir);
+        // Workloads over the generated state are signed by its owners.
+        num_accounts_to_load = generated_state.owners.len();
+        Some(generated_state)
+    } else {
+        None
+    };
+
     if let BenchmarkWorkload::TransactionMix(mix) = &workload {
         for (transaction_type, _) in mix {
             if matches!(transaction_type, CoinTransfer { non_conflicting, .. } if *non_conflicting)
@@ -253,6 +277,24 @@ pub fn run_benchmark<V>(
             );
             (num_blocks_created, "raw transfer".to_string())
         },
+        BenchmarkWorkload::GeneratedState {
+            workload_mix,
+            zipf_exponent,
+        } => {
+            let generated_state = generated_state.unwrap();
+            assert_eq!(generator.main_signer_addresses(), generated_state.owners);
+            let mut state_workload =
+                GeneratedStateWorkload::new(generated_state, workload_mix.clone(), zipf_exponent);
+            let num_blocks_created = generator.run_payloads(
+                block_size,
+                std::iter::repeat_with(|| state_workload.gen_payload())
+                    .take(block_size * num_blocks),
+            );
+            (
+                num_blocks_created,
+                format!("{:?} over generated state", workload_mix),
+            )
+        },
     };
     if pipeline_config.generate_then_execute {
         overall_measuring.start_time = Instant::now();
@@ -557,6 +599,7 @@ impl GasMeasurement {
 
 static OTHER_LABELS: &[(&str, bool, &str)] = &[
     ("1.", true, "verified_state_view"),
+    ("1.1.", false, "state_prefetch"),
     ("2.", true, "state_checkpoint"),
     ("2.1.", false, "sort_transactions"),
     ("2.2.", false, "calculate_for_transaction_block"),
@@ -578,6 +621,9 @@ struct ExecutionTimeMeasurement {
     by_other: HashMap<&'static str, f64>,
     ledger_update_total: f64,
     commit_total_time: f64,
+
+    state_cache_hits: u64,
+    state_cache_misses: u64,
 }
 
 impl ExecutionTimeMeasurement {
@@ -605,6 +651,8 @@ impl ExecutionTimeMeasurement {
             .collect::<HashMap<_, _>>();
         let ledger_update_total = UPDATE_LEDGER.get_sample_sum();
         let commit_total = COMMIT_BLOCKS.get_sample_sum();
+        let state_cache_hits = STATE_CACHE_HITS.get();
+        let state_cache_misses = STATE_CACHE_MISSES.get();
 
         Self {
             output_size,
@@ -616,6 +664,8 @@ impl ExecutionTimeMeasurement {
             by_other,
             ledger_update_total,
             commit_total_time: commit_total,
+            state_cache_hits,
+            state_cache_misses,
         }
     }
 
@@ -638,6 +688,8 @@ impl ExecutionTimeMeasurement {
                 .collect::<HashMap<_, _>>(),
             ledger_update_total: end.ledger_update_total - self.ledger_update_total,
             commit_total_time: end.commit_total_time - self.commit_total_time,
+            state_cache_hits: end.state_cache_hits - self.state_cache_hits,
+            state_cache_misses: end.state_cache_misses - self.state_cache_misses,
         }
     }
 }
@@ -757,6 +809,16 @@ impl OverallMeasuring {
             }
         }
 
+        info!(
+            "{} state cache hit rate: {:.4} ({} hits, {} misses)",
+            prefix,
+            delta_execution.state_cache_hits as f64
+                / ((delta_execution.state_cache_hits + delta_execution.state_cache_misses) as f64)
+                    .max(1.0),
+            delta_execution.state_cache_hits,
+            delta_execution.state_cache_misses
+        );
+
         info!(
             "{} fraction of total: {:.4} in ledger update (component TPS: {:.1})",
             prefix,
@@ -794,13 +856,19 @@ mod tests {
             },
         },
         pipeline::PipelineConfig,
+        state_generator::{
+            generate_state, GeneratedState, StateDistribution, StateWorkloadType,
+            MAX_OBJECT_GRAPH_DEPTH,
+        },
         transaction_executor::BENCHMARKS_BLOCK_EXECUTOR_ONCHAIN_CONFIG,
         transaction_generator::TransactionGenerator,
         BenchmarkWorkload,
     };
     use aptos_config::config::NO_OP_STORAGE_PRUNER_CONFIG;
     use aptos_crypto::HashValue;
-    use aptos_executor::block_executor::BlockExecutor;
+    use aptos_executor::block_executor::{
+        state_prefetcher::set_state_prefetching_enabled, BlockExecutor,
+    };
     use aptos_executor_types::BlockExecutorTrait;
     use aptos_sdk::{transaction_builder::aptos_stdlib, types::LocalAccount};
     use aptos_temppath::TempPath;
@@ -1091,11 +1159,118 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_benchmark_generated_state() {
+        aptos_logger::Logger::new().init();
+
+        let storage_dir = TempPath::new();
+        let checkpoint_dir = TempPath::new();
+
+        let mut features = default_benchmark_features();
+        features.enable(FeatureFlag::NEW_ACCOUNTS_DEFAULT_TO_FA_APT_STORE);
+        features.enable(FeatureFlag::OPERATIONS_DEFAULT_TO_FA_APT_STORE);
+
+        crate::db_generator::create_db_with_accounts::<AptosVMBlockExecutor>(
+            20,              /* num_accounts */
+            100_000_000_000, /* init_account_balance */
+            5,               /* block_size */
+            storage_dir.as_ref(),
+            NO_OP_STORAGE_PRUNER_CONFIG,
+            true,
+            false,
+            PipelineConfig::default(),
+            features.clone(),
+            false,
+        );
+
+        let distribution = StateDistribution {
+            state_owners: 4,
+            num_tables: 3,
+            table_items: 10,
+            table_item_bytes: 100,
+            num_object_graphs: 5,
+            object_graph_depth: MAX_OBJECT_GRAPH_DEPTH,
+            num_fa_stores: 10,
+            num_nft_collections: 3,
+            nfts_per_collection: 4,
+            target_state_size_gb: None,
+        };
+        generate_state::<AptosVMBlockExecutor>(
+            &distribution,
+            5, /* block_size */
+            storage_dir.as_ref(),
+            NO_OP_STORAGE_PRUNER_CONFIG,
+            false,
+            4, /* num_generator_workers */
+            features.clone(),
+            false,
+        );
+
+        let generated_state = GeneratedState::read(&storage_dir);
+        assert_eq!(generated_state.owners.len(), 4);
+        assert_eq!(generated_state.table_sizes, vec![10; 3]);
+        assert_eq!(generated_state.num_fa_stores, 10);
+        assert!(generated_state
+            .nft_collections
+            .iter()
+            .all(|tokens| tokens.len() == 4));
+        assert!(generated_state
+            .object_graphs
+            .iter()
+            .all(|objects| objects.len() == MAX_OBJECT_GRAPH_DEPTH));
+
+        super::run_benchmark::<AptosVMBlockExecutor>(
+            10, /* block_size */
+            30, /* num_blocks */
+            BenchmarkWorkload::GeneratedState {
+                workload_mix: vec![
+                    (StateWorkloadType::TableItemUpdate, 1),
+                    (StateWorkloadType::FungibleAssetTransfer, 1),
+                    (StateWorkloadType::NftMint, 1),
+                    (StateWorkloadType::NftUpdate, 1),
+                    (StateWorkloadType::ObjectGraphUpdate, 1),
+                ],
+                zipf_exponent: 1.0,
+            },
+            1, /* transactions per sender */
+            4, /* num_main_signer_accounts */
+            0, /* num_dst_pool_accounts */
+            storage_dir.as_ref(),
+            checkpoint_dir,
+            true,
+            NO_OP_STORAGE_PRUNER_CONFIG,
+            false,
+            PipelineConfig::default(),
+            features,
+            false,
+        );
+    }
+
     #[test]
     fn test_benchmark_default() {
         test_generic_benchmark::<AptosVMBlockExecutor>(None, true);
     }
 
+    #[test]
+    fn test_benchmark_with_state_prefetching() {
+        /// Disables prefetching when dropped, so that it is not left enabled for block executors
+        /// created by other tests, even if this one fails.
+        struct StatePrefetchingGuard;
+
+        impl Drop for StatePrefetchingGuard {
+            fn drop(&mut self) {
+                set_state_prefetching_enabled(false);
+            }
+        }
+
+        set_state_prefetching_enabled(true);
+        let _guard = StatePrefetchingGuard;
+        test_generic_benchmark::<AptosVMBlockExecutor>(
+            Some(TransactionTypeArg::AptFaTransfer),
+            true,
+        );
+    }
+
     #[test]
     fn test_publish_transaction() {
         AptosVM::set_num_shards_once(1);
@@ -2,6 +2,11 @@
 // Parts of the project are originally copyright © Meta Platforms, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use anyhow::{anyhow, Context, Result};
+use aptos_block_executor::conflict_analytics::{
+    export_conflict_reports, hot_keys, set_conflict_analytics_enabled, take_conflict_reports,
+    take_num_dropped_conflict_reports, MAX_BUFFERED_CONFLICT_REPORTS,
+};
 use aptos_block_partitioner::{
     pre_partition::{
         connected_component::config::ConnectedComponentPartitionerConfig,
@@ -13,8 +18,12 @@ use aptos_block_partitioner::{
 use aptos_config::config::{
     EpochSnapshotPrunerConfig, LedgerPrunerConfig, PrunerConfig, StateMerklePrunerConfig,
 };
+use aptos_executor::block_executor::state_prefetcher::set_state_prefetching_enabled;
 use aptos_executor_benchmark::{
     default_benchmark_features,
+    determinism_checker::{
+        self, DeterminismCheckerConfig, ExecutorConfiguration, NativeExecutorType,
+    },
     native::{
         aptos_vm_uncoordinated::AptosVMParallelUncoordinatedBlockExecutor,
         native_config::NativeConfig,
@@ -25,6 +34,7 @@ use aptos_executor_benchmark::{
         },
     },
     pipeline::PipelineConfig,
+    state_generator::{StateDistribution, StateWorkloadType},
     BenchmarkWorkload,
 };
 use aptos_executor_service::remote_executor_client;
@@ -35,7 +45,10 @@ use aptos_metrics_core::{register_int_gauge, IntGauge};
 use aptos_profiler::{ProfilerConfig, ProfilerHandler};
 use aptos_push_metrics::MetricsPusher;
 use aptos_transaction_generator_lib::{args::TransactionTypeArg, WorkflowProgress};
-use aptos_types::on_chain_config::{FeatureFlag, Features};
+use aptos_types::{
+    on_chain_config::{FeatureFlag, Features},
+    transaction::resource_usage::set_resource_accounting_enabled,
+};
 use aptos_vm::{aptos_vm::AptosVMBlockExecutor, AptosVM, VMBlockExecutor};
 use aptos_vm_environment::prod_configs::set_paranoid_type_checks;
 use clap::{Parser, Subcommand, ValueEnum};
@@ -104,6 +117,7 @@ impl PrunerOpt {
                 batch_size: self.ledger_pruning_batch_size,
                 user_pruning_window_offset: 0,
             },
+            state_kv_pruner_config: None,
         }
     }
 }
@@ -321,6 +335,24 @@ struct Opt {
 
     #[clap(long)]
     skip_paranoid_checks: bool,
+
+    /// Prefetch the state that blocks are predicted to access before executing them.
+    #[clap(long)]
+    prefetch_state: bool,
+
+    /// If set, records Block-STM conflicts of every block executed in parallel, and writes
+    /// per-block conflict graphs (JSON and DOT) and the most conflicting keys to this directory.
+    #[clap(long)]
+    conflict_analytics_dir: Option<PathBuf>,
+
+    /// Number of most conflicting keys to report with --conflict-analytics-dir.
+    #[clap(long, default_value_t = 20)]
+    num_hot_keys: usize,
+
+    /// If set, accounts resources used by every executed transaction. The executor logs the
+    /// aggregated usage of each block and exports it as metrics.
+    #[clap(long)]
+    resource_accounting: bool,
 }
 
 impl Opt {
@@ -348,6 +380,10 @@ enum Command {
         #[clap(long, default_value_t = 10000000000)]
         init_account_balance: u64,
 
+        /// Additional state to create after the accounts. No additional state by default.
+        #[clap(flatten)]
+        state_distribution: StateDistribution,
+
         #[clap(
             long,
             num_args=1..,
@@ -369,7 +405,13 @@ enum Command {
         #[clap(long, default_value_t = 1000)]
         blocks: usize,
 
-        #[clap(long, default_value_t = 1000000)]
+        /// Number of accounts signing the transactions. Workloads over the generated state are
+        /// signed by the state owners instead.
+        #[clap(
+            long,
+            default_value_t = 1000000,
+            conflicts_with = "generated_state_workload"
+        )]
         main_signer_accounts: usize,
 
         #[clap(long, default_value_t = 0)]
@@ -385,6 +427,26 @@ enum Command {
         )]
         transaction_type: Vec<TransactionTypeArg>,
 
+        /// Workloads defined in YAML/JSON spec files, to use instead of --transaction-type.
+        #[clap(long, num_args = 1.., conflicts_with = "transaction_type")]
+        workload_spec: Vec<PathBuf>,
+
+        /// Workloads over the state created with the state distribution options of create-db,
+        /// to use instead of --transaction-type.
+        #[clap(
+            long,
+            value_enum,
+            num_args = 1..,
+            ignore_case = true,
+            conflicts_with_all = &["transaction_type", "workload_spec"]
+        )]
+        generated_state_workload: Vec<StateWorkloadType>,
+
+        /// Exponent of the Zipfian distribution --generated-state-workload uses to choose the
+        /// state to access. 0 accesses it uniformly.
+        #[clap(long, default_value_t = 1.0)]
+        zipf_exponent: f64,
+
         #[clap(long, num_args = 0..)]
         transaction_weights: Vec<usize>,
 
@@ -416,6 +478,63 @@ enum Command {
             Sample usage: --enable-feature=V1 --disable-feature=V2 V3 where V1, V2, V3 are FeatureFlag enum variants.")]
         disable_feature: Vec<FeatureFlag>,
     },
+    /// Checks that blocks execute deterministically, by comparing the outputs of Block-STM, the
+    /// sharded and the native executors to the outputs of sequential execution. Divergent blocks
+    /// are minimized to the transactions that still diverge.
+    CheckDeterminism {
+        /// Blocks saved with `aptos-replay-benchmark --save-blocks` to check. If not set, blocks
+        /// of transfers, or of --generated-state-workload, are generated over --data-dir.
+        #[clap(long, value_parser, conflicts_with = "data_dir")]
+        blocks_file: Option<PathBuf>,
+
+        #[clap(long, value_parser, required_unless_present = "blocks_file")]
+        data_dir: Option<PathBuf>,
+
+        /// Number of blocks to generate.
+        #[clap(long, default_value_t = 10)]
+        blocks: usize,
+
+        /// Number of accounts signing the transactions. Workloads over the generated state are
+        /// signed by the state owners instead.
+        #[clap(
+            long,
+            default_value_t = 10000,
+            conflicts_with = "generated_state_workload"
+        )]
+        main_signer_accounts: usize,
+
+        /// Equally weighted workloads over the state created with the state distribution options
+        /// of create-db, to use instead of transfers.
+        #[clap(long, value_enum, num_args = 1.., ignore_case = true)]
+        generated_state_workload: Vec<StateWorkloadType>,
+
+        #[clap(long, default_value_t = 1.0)]
+        zipf_exponent: f64,
+
+        /// Block-STM concurrency levels to check.
+        #[clap(long, num_args = 0.., default_values_t = vec![2, 4, 8, 16])]
+        concurrency_levels: Vec<usize>,
+
+        /// Numbers of shards to check the sharded executor with. Blocks with transactions other
+        /// than transfers are not checked with the sharded executor.
+        #[clap(long, num_args = 0..)]
+        num_shards: Vec<usize>,
+
+        /// Native and uncoordinated executors to check. Uncoordinated executors diverge on blocks
+        /// with conflicts, and native executors only support transfers.
+        #[clap(long, value_enum, num_args = 0.., ignore_case = true)]
+        native_executors: Vec<NativeExecutorType>,
+
+        /// Number of times every configuration executes a block before it is considered to match
+        /// sequential execution.
+        #[clap(long, default_value_t = 3)]
+        num_attempts: usize,
+
+        /// If set, minimized divergent blocks are saved to this file, and can be loaded with
+        /// `aptos-replay-benchmark --load-blocks`.
+        #[clap(long, value_parser)]
+        divergent_blocks_file: Option<PathBuf>,
+    },
     AddAccounts {
         #[clap(long, value_parser)]
         data_dir: PathBuf,
@@ -451,7 +570,7 @@ fn get_init_features(
     init_features
 }
 
-fn run<E>(opt: Opt)
+fn run<E>(opt: Opt) -> Result<()>
 where
     E: VMBlockExecutor + 'static,
 {
@@ -460,27 +579,54 @@ where
             data_dir,
             num_accounts,
             init_account_balance,
+            state_distribution,
             enable_feature,
             disable_feature,
         } => {
+            if !state_distribution.is_empty() {
+                // Fail before spending time on creating the accounts.
+                state_distribution.validate();
+                assert!(
+                    state_distribution.state_owners <= num_accounts,
+                    "--state-owners cannot exceed --num-accounts."
+                );
+            }
+            let init_features = get_init_features(enable_feature, disable_feature);
+
             aptos_executor_benchmark::db_generator::create_db_with_accounts::<E>(
                 num_accounts,
                 init_account_balance,
                 opt.block_size,
-                data_dir,
+                &data_dir,
                 opt.pruner_opt.pruner_config(),
                 opt.verify_sequence_numbers,
                 opt.enable_storage_sharding,
                 opt.pipeline_opt.pipeline_config(),
-                get_init_features(enable_feature, disable_feature),
+                init_features.clone(),
                 opt.use_keyless_accounts,
             );
+
+            if !state_distribution.is_empty() {
+                aptos_executor_benchmark::state_generator::generate_state::<E>(
+                    &state_distribution,
+                    opt.block_size,
+                    &data_dir,
+                    opt.pruner_opt.pruner_config(),
+                    opt.enable_storage_sharding,
+                    opt.pipeline_opt.num_generator_workers,
+                    init_features,
+                    opt.use_keyless_accounts,
+                );
+            }
         },
         Command::RunExecutor {
             blocks,
             main_signer_accounts,
             additional_dst_pool_accounts,
             transaction_type,
+            workload_spec,
+            generated_state_workload,
+            zipf_exponent,
             transaction_weights,
             module_working_set_size,
             use_sender_account_pool,
@@ -494,7 +640,34 @@ where
             //     disable_feature,
             // );
 
-            let workload = if transaction_type.is_empty() {
+            let workload = if !workload_spec.is_empty() {
+                let mix_per_phase =
+                    TransactionTypeArg::workload_specs_to_transaction_mix_per_phase(
+                        &workload_spec,
+                        &transaction_weights,
+                        &[],
+                    )
+                    .context("Failed to load workload specs")?;
+                let [mix]: [_; 1] = mix_per_phase
+                    .try_into()
+                    .map_err(|_| anyhow!("Workload specs must form a single phase"))?;
+                BenchmarkWorkload::TransactionMix(mix)
+            } else if !generated_state_workload.is_empty() {
+                let weights = if transaction_weights.is_empty() {
+                    vec![1; generated_state_workload.len()]
+                } else {
+                    assert_eq!(
+                        transaction_weights.len(),
+                        generated_state_workload.len(),
+                        "Each --generated-state-workload needs a weight."
+                    );
+                    transaction_weights
+                };
+                BenchmarkWorkload::GeneratedState {
+                    workload_mix: generated_state_workload.into_iter().zip(weights).collect(),
+                    zipf_exponent,
+                }
+            } else if transaction_type.is_empty() {
                 BenchmarkWorkload::Transfer {
                     connected_tx_grps: opt.connected_tx_grps,
                     shuffle_connected_txns: opt.shuffle_connected_txns,
@@ -538,6 +711,76 @@ where
                 opt.use_keyless_accounts,
             );
         },
+        Command::CheckDeterminism {
+            blocks_file,
+            data_dir,
+            blocks,
+            main_signer_accounts,
+            generated_state_workload,
+            zipf_exponent,
+            concurrency_levels,
+            num_shards,
+            native_executors,
+            num_attempts,
+            divergent_blocks_file,
+        } => {
+            let configurations = concurrency_levels
+                .into_iter()
+                .map(|concurrency_level| ExecutorConfiguration::BlockStm { concurrency_level })
+                .chain(
+                    num_shards
+                        .into_iter()
+                        .map(|num_shards| ExecutorConfiguration::Sharded { num_shards }),
+                )
+                .chain(
+                    native_executors
+                        .into_iter()
+                        .map(ExecutorConfiguration::Native),
+                )
+                .collect();
+            let config = DeterminismCheckerConfig {
+                configurations,
+                partitioner_config: opt.pipeline_opt.sharding_opt.partitioner_config(),
+                num_attempts,
+                divergent_blocks_file,
+            };
+
+            let num_divergences = if let Some(blocks_file) = blocks_file {
+                determinism_checker::check_saved_blocks(&config, &blocks_file)
+            } else {
+                let workload = if generated_state_workload.is_empty() {
+                    BenchmarkWorkload::Transfer {
+                        connected_tx_grps: opt.connected_tx_grps,
+                        shuffle_connected_txns: opt.shuffle_connected_txns,
+                        hotspot_probability: opt.hotspot_probability,
+                    }
+                } else {
+                    BenchmarkWorkload::GeneratedState {
+                        workload_mix: generated_state_workload
+                            .into_iter()
+                            .map(|workload_type| (workload_type, 1))
+                            .collect(),
+                        zipf_exponent,
+                    }
+                };
+                determinism_checker::check_workload(
+                    &config,
+                    workload,
+                    opt.block_size,
+                    blocks,
+                    opt.transactions_per_sender,
+                    main_signer_accounts,
+                    data_dir.unwrap(),
+                    opt.enable_storage_sharding,
+                    opt.pipeline_opt.num_generator_workers,
+                    opt.use_keyless_accounts,
+                )
+            };
+            assert_eq!(
+                num_divergences, 0,
+                "Block execution is not deterministic across executor configurations."
+            );
+        },
         Command::AddAccounts {
             data_dir,
             checkpoint_dir,
@@ -559,9 +802,10 @@ where
             );
         },
     }
+    Ok(())
 }
 
-fn main() {
+fn main() -> Result<()> {
     let opt = Opt::parse();
     aptos_logger::Logger::new().init();
     START_TIME.set(
@@ -626,6 +870,17 @@ fn main() {
     AptosVM::set_concurrency_level_once(execution_threads_per_shard);
     NativeConfig::set_concurrency_level_once(execution_threads_per_shard);
     AptosVM::set_processed_transactions_detailed_counters();
+    let conflict_analytics = opt
+        .conflict_analytics_dir
+        .clone()
+        .map(|dir| (dir, opt.num_hot_keys));
+    if conflict_analytics.is_some() {
+        set_conflict_analytics_enabled(true);
+    }
+    set_resource_accounting_enabled(opt.resource_accounting);
+    if opt.prefetch_state {
+        set_state_prefetching_enabled(true);
+    }
 
     let config = ProfilerConfig::new_with_defaults();
     let handler = ProfilerHandler::new(config);
@@ -645,42 +900,64 @@ fn main() {
 
     match opt.block_executor_type {
         BlockExecutorTypeOpt::AptosVMWithBlockSTM => {
-            run::<AptosVMBlockExecutor>(opt);
+            run::<AptosVMBlockExecutor>(opt)?;
         },
         BlockExecutorTypeOpt::NativeVMWithBlockSTM => {
-            run::<NativeVMBlockExecutor>(opt);
+            run::<NativeVMBlockExecutor>(opt)?;
         },
         BlockExecutorTypeOpt::AptosVMParallelUncoordinated => {
-            run::<AptosVMParallelUncoordinatedBlockExecutor>(opt);
+            run::<AptosVMParallelUncoordinatedBlockExecutor>(opt)?;
         },
         BlockExecutorTypeOpt::NativeParallelUncoordinated => {
-            run::<NativeParallelUncoordinatedBlockExecutor<NativeRawTransactionExecutor>>(opt);
+            run::<NativeParallelUncoordinatedBlockExecutor<NativeRawTransactionExecutor>>(opt)?;
         },
         BlockExecutorTypeOpt::NativeValueCacheParallelUncoordinated => {
             run::<NativeParallelUncoordinatedBlockExecutor<NativeValueCacheRawTransactionExecutor>>(
                 opt,
-            );
+            )?;
         },
         BlockExecutorTypeOpt::NativeNoStorageParallelUncoordinated => {
             run::<NativeParallelUncoordinatedBlockExecutor<NativeNoStorageRawTransactionExecutor>>(
                 opt,
-            );
+            )?;
         },
         BlockExecutorTypeOpt::PtxExecutor => {
             #[cfg(target_os = "linux")]
             ThreadManagerBuilder::set_thread_config_strategy(
                 ThreadConfigStrategy::ThreadsPriority(48),
             );
-            run::<PtxBlockExecutor>(opt);
+            run::<PtxBlockExecutor>(opt)?;
         },
     }
 
+    if let Some((dir, num_hot_keys)) = conflict_analytics {
+        let reports = take_conflict_reports();
+        export_conflict_reports(&reports, &dir, num_hot_keys)
+            .expect("Failed to export conflict reports");
+        println!(
+            "Exported conflicts of {} blocks to {}, top conflicting keys:",
+            reports.len(),
+            dir.display()
+        );
+        let num_dropped = take_num_dropped_conflict_reports();
+        if num_dropped > 0 {
+            println!(
+                "Conflicts of the first {} blocks were dropped, only the latest {} are kept",
+                num_dropped, MAX_BUFFERED_CONFLICT_REPORTS
+            );
+        }
+        for (key, num_conflicts) in hot_keys(&reports, num_hot_keys) {
+            println!("    {}: {} conflicts", key, num_conflicts);
+        }
+    }
+
     if cpu_profiling {
         let _cpu_end = cpu_profiler.end_profiling("");
     }
     if memory_profiling {
         let _mem_end = memory_profiler.end_profiling("./target/release/aptos-executor-benchmark");
     }
+    Ok(())
 }
 
 #[test]
@@ -0,0 +1,1034 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Generates a large and realistic state on top of the accounts created by `db_generator`, and
+//! workloads accessing it with a Zipfian key distribution.
+//!
+//! All the state is created via framework entry functions, signed by the first `state_owners`
+//! accounts in the DB:
+//! - tables are token v1 collections, with each minted token adding a table item,
+//! - NFT collections are token v2 (object based) collections,
+//! - object graphs are chains of token v2 tokens, each owned by the previous one,
+//! - fungible asset stores are APT primary stores, at addresses without an account.
+//!
+//! Addresses of created objects cannot be predicted, so they are found by scanning the committed
+//! write sets, and are stored next to the DB, for the workloads to use.
+
+use crate::{
+    init_db,
+    pipeline::{Pipeline, PipelineConfig},
+    transaction_generator::TransactionGenerator,
+};
+use aptos_config::config::PrunerConfig;
+use aptos_crypto::{ed25519::Ed25519PrivateKey, HashValue};
+use aptos_executor::block_executor::BlockExecutor;
+use aptos_gas_schedule::{InitialGasSchedule, TransactionGasParameters};
+use aptos_logger::info;
+use aptos_sdk::transaction_builder::aptos_stdlib::{
+    self, aptos_token_objects_stdlib, aptos_token_stdlib,
+};
+use aptos_storage_interface::{DbReaderWriter, MAX_REQUEST_LIMIT};
+use aptos_types::{
+    access_path,
+    account_address::AccountAddress,
+    account_config::ObjectGroupResource,
+    on_chain_config::Features,
+    state_store::state_key::inner::StateKeyInner,
+    transaction::{EntryFunction, Transaction, TransactionPayload, Version},
+    write_set::TransactionWrite,
+    AptosCoinType, CoinType,
+};
+use aptos_vm::VMBlockExecutor;
+use clap::{Parser, ValueEnum};
+use move_core_types::{
+    ident_str,
+    language_storage::{ModuleId, StructTag, TypeTag},
+    move_resource::MoveStructType,
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, VecDeque},
+    fs,
+    ops::Range,
+    path::Path,
+};
+
+pub const GENERATED_STATE_FILENAME: &str = "generated_state.bcs";
+
+/// Transaction fees are paid by the state owners, so the root account funds them with this much
+/// per transaction they are going to send.
+const FUNDING_PER_TXN: u64 = 1_000_000;
+
+/// Objects cannot be nested deeper than this (`MAXIMUM_OBJECT_NESTING` in object.move).
+pub const MAX_OBJECT_GRAPH_DEPTH: usize = 8;
+
+const MAX_TABLE_ITEM_BYTES: usize = 32 * 1024;
+
+/// Rounds of adding table items to reach --target-state-size-gb, as the bytes per item are only
+/// estimated.
+const MAX_FILL_ROUNDS: usize = 10;
+
+const OBJECT_GRAPHS_COLLECTION: &str = "object_graphs";
+
+#[derive(Debug, Parser)]
+pub struct StateDistribution {
+    /// Number of accounts (the first ones in the DB) owning the generated state, and signing the
+    /// workloads over it.
+    #[clap(long, default_value_t = 100)]
+    pub state_owners: usize,
+
+    /// Number of tables (token v1 collections) to create.
+    #[clap(long, default_value_t = 0)]
+    pub num_tables: usize,
+
+    /// Number of items to create in each table.
+    #[clap(long, default_value_t = 0)]
+    pub table_items: u64,
+
+    /// Approximate size of each table item, in bytes.
+    #[clap(long, default_value_t = 500)]
+    pub table_item_bytes: usize,
+
+    /// Number of object graphs (chains of nested token v2 objects) to create.
+    #[clap(long, default_value_t = 0)]
+    pub num_object_graphs: usize,
+
+    /// Number of objects in each object graph.
+    #[clap(long, default_value_t = MAX_OBJECT_GRAPH_DEPTH)]
+    pub object_graph_depth: usize,
+
+    /// Number of APT fungible asset stores to create, owned by addresses without an account.
+    #[clap(long, default_value_t = 0)]
+    pub num_fa_stores: usize,
+
+    /// Number of NFT (token v2) collections to create.
+    #[clap(long, default_value_t = 0)]
+    pub num_nft_collections: usize,
+
+    /// Number of NFTs to mint in each collection.
+    #[clap(long, default_value_t = 0)]
+    pub nfts_per_collection: usize,
+
+    /// If set, keeps adding table items after the rest of the state is created, until the total
+    /// state size reaches this many GBs.
+    #[clap(long)]
+    pub target_state_size_gb: Option<f64>,
+}
+
+impl StateDistribution {
+    pub fn is_empty(&self) -> bool {
+        self.num_tables == 0
+            && self.num_object_graphs == 0
+            && self.num_fa_stores == 0
+            && self.num_nft_collections == 0
+            && self.target_state_size_gb.is_none()
+    }
+
+    pub fn validate(&self) {
+        assert!(self.state_owners > 0, "--state-owners must be positive.");
+        assert!(
+            (2..=MAX_OBJECT_GRAPH_DEPTH).contains(&self.object_graph_depth),
+            "--object-graph-depth must be in [2, {}].",
+            MAX_OBJECT_GRAPH_DEPTH,
+        );
+        assert!(
+            self.table_item_bytes <= MAX_TABLE_ITEM_BYTES,
+            "--table-item-bytes must be at most {}.",
+            MAX_TABLE_ITEM_BYTES,
+        );
+        if let Some(target_state_size_gb) = self.target_state_size_gb {
+            assert!(
+                target_state_size_gb > 0.0,
+                "--target-state-size-gb must be positive."
+            );
+            assert!(
+                self.num_tables > 0,
+                "--target-state-size-gb requires --num-tables, as it is reached by adding table items."
+            );
+        }
+    }
+}
+
+/// Describes the state created by [`generate_state`], stored in the DB directory.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GeneratedState {
+    /// Owners of the state, the i-th one being the i-th account in the DB.
+    pub owners: Vec<AccountAddress>,
+    /// Number of items in each table. Table `t` is owned by `owners[t % owners.len()]`.
+    pub table_sizes: Vec<u64>,
+    /// Tokens of each NFT collection. Collection `c` is owned by `owners[c % owners.len()]`.
+    pub nft_collections: Vec<Vec<AccountAddress>>,
+    /// Number of fungible asset stores, owned by [`fa_store_owner`] addresses.
+    pub num_fa_stores: usize,
+    /// Objects of each object graph, from the root to the leaf. Graph `g` is owned by
+    /// `owners[g % owners.len()]`.
+    pub object_graphs: Vec<Vec<AccountAddress>>,
+}
+
+impl GeneratedState {
+    pub fn read(db_dir: impl AsRef<Path>) -> Self {
+        let path = db_dir.as_ref().join(GENERATED_STATE_FILENAME);
+        let bytes =
+            fs::read(&path).unwrap_or_else(|e| panic!("Failed to read {}: {}", path.display(), e));
+        bcs::from_bytes(&bytes).expect("Generated state must deserialize.")
+    }
+
+    pub fn write(&self, db_dir: impl AsRef<Path>) {
+        fs::write(
+            db_dir.as_ref().join(GENERATED_STATE_FILENAME),
+            bcs::to_bytes(self).unwrap(),
+        )
+        .unwrap();
+    }
+
+    fn owner_of(&self, index: usize) -> usize {
+        index % self.owners.len()
+    }
+}
+
+fn table_name(table: usize) -> String {
+    format!("table_{}", table)
+}
+
+fn table_item_name(item: u64) -> String {
+    format!("item_{}", item)
+}
+
+fn nft_collection_name(collection: usize) -> String {
+    format!("nft_{}", collection)
+}
+
+/// Address owning the `index`-th fungible asset store. There is no account at it.
+pub fn fa_store_owner(index: usize) -> AccountAddress {
+    let mut seed = b"fa_store".to_vec();
+    seed.extend_from_slice(&(index as u64).to_le_bytes());
+    AccountAddress::new(*HashValue::sha3_256_of(&seed))
+}
+
+fn create_token_v2_collection(name: String) -> TransactionPayload {
+    aptos_token_objects_stdlib::aptos_token_create_collection(
+        vec![],
+        u64::MAX,
+        name.into_bytes(),
+        vec![],
+        false,
+        false,
+        false,
+        // Allows updating token descriptions in the workloads.
+        true,
+        false,
+        false,
+        false,
+        false,
+        false,
+        0,
+        1,
+    )
+}
+
+fn mint_token_v2(collection: String) -> TransactionPayload {
+    aptos_token_objects_stdlib::aptos_token_mint(
+        collection.into_bytes(),
+        vec![],
+        b"token".to_vec(),
+        vec![],
+        vec![],
+        vec![],
+        vec![],
+    )
+}
+
+/// `aptos_token::set_description<aptos_token_objects::token::Token>`, which has no builder, as
+/// it is generic over the object type.
+fn set_token_v2_description(token: AccountAddress, description: String) -> TransactionPayload {
+    TransactionPayload::EntryFunction(EntryFunction::new(
+        ModuleId::new(AccountAddress::FOUR, ident_str!("aptos_token").to_owned()),
+        ident_str!("set_description").to_owned(),
+        vec![TypeTag::Struct(Box::new(StructTag {
+            address: AccountAddress::FOUR,
+            module: ident_str!("token").to_owned(),
+            name: ident_str!("Token").to_owned(),
+            type_args: vec![],
+        }))],
+        vec![
+            bcs::to_bytes(&token).unwrap(),
+            bcs::to_bytes(&description).unwrap(),
+        ],
+    ))
+}
+
+/// `num_txns` transactions signed by `owner`, that need to execute in order.
+struct OwnerTask<T = TransactionPayload> {
+    owner: usize,
+    num_txns: u64,
+    payload: Box<dyn Fn(u64) -> T>,
+}
+
+impl<T> OwnerTask<T> {
+    fn new(owner: usize, num_txns: u64, payload: impl Fn(u64) -> T + 'static) -> Self {
+        Self {
+            owner,
+            num_txns,
+            payload: Box::new(payload),
+        }
+    }
+}
+
+/// Round-robins over the owners, so blocks have transactions from many senders, while tasks of
+/// the same owner are sent one after another, in the given order.
+fn interleave<T>(num_owners: usize, tasks: Vec<OwnerTask<T>>) -> impl Iterator<Item = (usize, T)> {
+    let mut tasks_per_owner: Vec<VecDeque<OwnerTask<T>>> =
+        (0..num_owners).map(|_| VecDeque::new()).collect();
+    for task in tasks {
+        tasks_per_owner[task.owner].push_back(task);
+    }
+    let mut next_txn_idx = vec![0; num_owners];
+    let mut next_owner = 0;
+    std::iter::from_fn(move || {
+        for _ in 0..num_owners {
+            let owner = next_owner;
+            next_owner = (next_owner + 1) % num_owners;
+            while let Some(task) = tasks_per_owner[owner].front() {
+                if next_txn_idx[owner] < task.num_txns {
+                    let payload = (task.payload)(next_txn_idx[owner]);
+                    next_txn_idx[owner] += 1;
+                    return Some((owner, payload));
+                }
+                tasks_per_owner[owner].pop_front();
+                next_txn_idx[owner] = 0;
+            }
+        }
+        None
+    })
+}
+
+/// Funding per transaction adding a table item with a description of `item_bytes`, whose storage
+/// fee can exceed `FUNDING_PER_TXN` on its own.
+fn funding_per_table_item(item_bytes: usize) -> u64 {
+    let fee_per_byte = u64::from(TransactionGasParameters::initial().storage_fee_per_state_byte);
+    // Twice the description, as the item also stores the rest of the token data.
+    FUNDING_PER_TXN + 2 * item_bytes as u64 * fee_per_byte
+}
+
+/// Panics unless all the transactions in the given versions succeeded, as the next phases and the
+/// workloads depend on the state they create.
+fn assert_succeeded(db: &DbReaderWriter, name: &str, versions: Range<Version>) {
+    let mut start = versions.start;
+    while start < versions.end {
+        let limit = std::cmp::min(versions.end - start, MAX_REQUEST_LIMIT);
+        let txn_infos = db
+            .reader
+            .get_transaction_info_iterator(start, limit)
+            .unwrap();
+        for (version, txn_info) in (start..).zip(txn_infos) {
+            let txn_info = txn_info.unwrap();
+            assert!(
+                txn_info.status().is_success(),
+                "State generation: {} transaction at version {} failed: {:?}",
+                name,
+                version,
+                txn_info.status(),
+            );
+        }
+        start += limit;
+    }
+}
+
+/// Returns the objects created by each sender of user transactions in the given versions, in the
+/// order of creation.
+fn created_objects(
+    db: &DbReaderWriter,
+    versions: Range<Version>,
+) -> HashMap<AccountAddress, Vec<AccountAddress>> {
+    let object_group = access_path::Path::ResourceGroup(ObjectGroupResource::struct_tag());
+    let mut created: HashMap<AccountAddress, Vec<AccountAddress>> = HashMap::new();
+    let mut start = versions.start;
+    while start < versions.end {
+        let limit = std::cmp::min(versions.end - start, MAX_REQUEST_LIMIT);
+        let txns = db.reader.get_transaction_iterator(start, limit).unwrap();
+        let write_sets = db.reader.get_write_set_iterator(start, limit).unwrap();
+        for (txn, write_set) in txns.zip(write_sets) {
+            let sender = match txn.unwrap() {
+                Transaction::UserTransaction(txn) => txn.sender(),
+                _ => continue,
+            };
+            for (key, op) in write_set.unwrap().iter() {
+                if let StateKeyInner::AccessPath(path) = key.inner() {
+                    if op.is_creation() && path.get_path() == object_group {
+                        created.entry(sender).or_default().push(path.address);
+                    }
+                }
+            }
+        }
+        start += limit;
+    }
+    created
+}
+
+struct StateGenerator<V> {
+    db: DbReaderWriter,
+    generator: TransactionGenerator,
+    /// Pipeline the generator currently sends blocks to, if not yet used by a phase.
+    pipeline: Option<Pipeline<V>>,
+    pipeline_config: PipelineConfig,
+    block_size: usize,
+    owners: Vec<AccountAddress>,
+}
+
+impl<V> StateGenerator<V>
+where
+    V: VMBlockExecutor + 'static,
+{
+    fn new(
+        db: DbReaderWriter,
+        genesis_key: Ed25519PrivateKey,
+        db_dir: impl AsRef<Path>,
+        num_owners: usize,
+        block_size: usize,
+        num_generator_workers: usize,
+        is_keyless: bool,
+    ) -> Self {
+        // Every phase is committed before the next one, to find the objects it created.
+        let pipeline_config = PipelineConfig {
+            num_generator_workers,
+            ..PipelineConfig::default()
+        };
+        let (pipeline, block_sender) = Self::create_pipeline(&db, &pipeline_config);
+        let generator = TransactionGenerator::new_with_existing_db(
+            db.clone(),
+            TransactionGenerator::read_root_account(genesis_key, &db),
+            block_sender,
+            db_dir,
+            Some(num_owners),
+            num_generator_workers,
+            is_keyless,
+        );
+        let owners = generator.main_signer_addresses();
+        assert_eq!(owners.len(), num_owners);
+
+        Self {
+            db,
+            generator,
+            pipeline: Some(pipeline),
+            pipeline_config,
+            block_size,
+            owners,
+        }
+    }
+
+    fn create_pipeline(
+        db: &DbReaderWriter,
+        pipeline_config: &PipelineConfig,
+    ) -> (Pipeline<V>, std::sync::mpsc::SyncSender<Vec<Transaction>>) {
+        let start_version = db.reader.get_latest_ledger_info_version().unwrap();
+        Pipeline::new(
+            BlockExecutor::<V>::new(db.clone()),
+            start_version,
+            pipeline_config,
+            None,
+        )
+    }
+
+    fn next_version(&self) -> Version {
+        self.db.reader.get_latest_ledger_info_version().unwrap() + 1
+    }
+
+    fn state_bytes(&self) -> usize {
+        let version = self.db.reader.get_latest_ledger_info_version().unwrap();
+        self.db
+            .reader
+            .get_state_storage_usage(Some(version))
+            .unwrap()
+            .bytes()
+    }
+
+    /// Funds the owners with `funding_per_txn` per transaction, and executes and commits all the
+    /// tasks, which must all succeed. Returns the versions of the committed transactions.
+    fn execute_phase(
+        &mut self,
+        name: &str,
+        tasks: Vec<OwnerTask>,
+        funding_per_txn: u64,
+    ) -> Range<Version> {
+        let mut txns_per_owner = vec![0; self.owners.len()];
+        for task in &tasks {
+            txns_per_owner[task.owner] += task.num_txns;
+        }
+        let num_txns: u64 = txns_per_owner.iter().sum();
+        let start_version = self.next_version();
+        if num_txns == 0 {
+            return start_version..start_version;
+        }
+        info!("State generation: {} ({} txns)", name, num_txns);
+
+        let pipeline = match self.pipeline.take() {
+            Some(pipeline) => pipeline,
+            None => {
+                let (pipeline, block_sender) =
+                    Self::create_pipeline(&self.db, &self.pipeline_config);
+                self.generator.set_block_sender(block_sender);
+                pipeline
+            },
+        };
+        let funding: Vec<_> = txns_per_owner
+            .iter()
+            .map(|num_txns| num_txns * funding_per_txn)
+            .collect();
+        self.generator
+            .fund_main_signer_accounts(&funding, self.block_size);
+        self.generator
+            .run_payloads(self.block_size, interleave(self.owners.len(), tasks));
+        self.generator.drop_sender();
+        pipeline.start_pipeline_processing();
+        pipeline.join();
+
+        let end_version = self.next_version();
+        assert_succeeded(&self.db, name, start_version..end_version);
+        info!(
+            "State generation: {} done, versions [{}, {}), state size {} bytes",
+            name,
+            start_version,
+            end_version,
+            self.state_bytes(),
+        );
+        start_version..end_version
+    }
+
+    /// Tasks adding items `items[t]` to each table `t`.
+    fn table_tasks(&self, items: Vec<Range<u64>>, item_bytes: usize) -> Vec<OwnerTask> {
+        items
+            .into_iter()
+            .enumerate()
+            .filter(|(_, items)| !items.is_empty())
+            .map(|(table, items)| {
+                let owner = table % self.owners.len();
+                let owner_address = self.owners[owner];
+                let description = "x".repeat(item_bytes);
+                OwnerTask::new(owner, items.end - items.start, move |i| {
+                    aptos_token_stdlib::token_create_token_script(
+                        table_name(table).into_bytes(),
+                        table_item_name(items.start + i).into_bytes(),
+                        description.clone().into_bytes(),
+                        1,
+                        // Unlimited, so the workloads can keep minting.
+                        0,
+                        vec![],
+                        owner_address,
+                        0,
+                        0,
+                        vec![false; 5],
+                        vec![],
+                        vec![],
+                        vec![],
+                    )
+                })
+            })
+            .collect()
+    }
+
+    /// Returns the tokens created by each owner in the given versions, expecting `expected[o]`
+    /// tokens from owner `o`.
+    fn discover_tokens(
+        &self,
+        versions: Range<Version>,
+        expected: &[usize],
+    ) -> Vec<VecDeque<AccountAddress>> {
+        let mut created = created_objects(&self.db, versions);
+        self.owners
+            .iter()
+            .zip(expected)
+            .map(|(owner, expected)| {
+                let tokens = created.remove(owner).unwrap_or_default();
+                assert_eq!(
+                    tokens.len(),
+                    *expected,
+                    "Unexpected number of tokens created by {}",
+                    owner
+                );
+                tokens.into()
+            })
+            .collect()
+    }
+
+    fn generate(&mut self, distribution: &StateDistribution) -> GeneratedState {
+        let num_owners = self.owners.len();
+        let num_graph_owners = std::cmp::min(num_owners, distribution.num_object_graphs);
+        let graphs_of_owner =
+            |owner: usize| (owner..distribution.num_object_graphs).step_by(num_owners);
+        let collections_of_owner =
+            |owner: usize| (owner..distribution.num_nft_collections).step_by(num_owners);
+
+        // Phase 1: collections, and APT primary stores for the owners funding the FA stores.
+        let mut tasks = vec![];
+        if distribution.num_fa_stores > 0 {
+            for owner in 0..num_owners {
+                tasks.push(OwnerTask::new(owner, 1, |_| {
+                    aptos_stdlib::coin_migrate_to_fungible_store(AptosCoinType::type_tag())
+                }));
+            }
+        }
+        for table in 0..distribution.num_tables {
+            tasks.push(OwnerTask::new(table % num_owners, 1, move |_| {
+                aptos_token_stdlib::token_create_collection_script(
+                    table_name(table).into_bytes(),
+                    vec![],
+                    vec![],
+                    0,
+                    vec![false; 3],
+                )
+            }));
+        }
+        for collection in 0..distribution.num_nft_collections {
+            tasks.push(OwnerTask::new(collection % num_owners, 1, move |_| {
+                create_token_v2_collection(nft_collection_name(collection))
+            }));
+        }
+        for owner in 0..num_graph_owners {
+            tasks.push(OwnerTask::new(owner, 1, |_| {
+                create_token_v2_collection(OBJECT_GRAPHS_COLLECTION.to_string())
+            }));
+        }
+        self.execute_phase("collections", tasks, FUNDING_PER_TXN);
+
+        // Phase 2: table items.
+        let table_item_funding = funding_per_table_item(distribution.table_item_bytes);
+        let mut table_sizes = vec![distribution.table_items; distribution.num_tables];
+        let bytes_before = self.state_bytes();
+        let tasks = self.table_tasks(
+            vec![0..distribution.table_items; distribution.num_tables],
+            distribution.table_item_bytes,
+        );
+        self.execute_phase("tables", tasks, table_item_funding);
+        let num_table_items: u64 = table_sizes.iter().sum();
+        // Each token adds a token data and a token store item.
+        let mut bytes_per_item = if num_table_items > 0 {
+            ((self.state_bytes() - bytes_before) as f64 / num_table_items as f64).max(1.0)
+        } else {
+            2.0 * distribution.table_item_bytes as f64
+        };
+
+        // Phase 3: fungible asset stores.
+        let tasks = (0..distribution.num_fa_stores)
+            .map(|store| {
+                OwnerTask::new(store % num_owners, 1, move |_| {
+                    aptos_stdlib::aptos_account_fungible_transfer_only(fa_store_owner(store), 1)
+                })
+            })
+            .collect();
+        self.execute_phase("fungible asset stores", tasks, FUNDING_PER_TXN);
+
+        // Phase 4: NFTs.
+        let tasks = (0..distribution.num_nft_collections)
+            .map(|collection| {
+                OwnerTask::new(
+                    collection % num_owners,
+                    distribution.nfts_per_collection as u64,
+                    move |_| mint_token_v2(nft_collection_name(collection)),
+                )
+            })
+            .collect();
+        let versions = self.execute_phase("NFTs", tasks, FUNDING_PER_TXN);
+        let expected: Vec<_> = (0..num_owners)
+            .map(|owner| collections_of_owner(owner).count() * distribution.nfts_per_collection)
+            .collect();
+        let mut tokens = self.discover_tokens(versions, &expected);
+        let mut nft_collections = vec![vec![]; distribution.num_nft_collections];
+        for (owner, tokens) in tokens.iter_mut().enumerate() {
+            for collection in collections_of_owner(owner) {
+                nft_collections[collection] =
+                    tokens.drain(..distribution.nfts_per_collection).collect();
+            }
+        }
+
+        // Phase 5: objects of the object graphs.
+        let depth = distribution.object_graph_depth;
+        let tasks = (0..num_graph_owners)
+            .map(|owner| {
+                OwnerTask::new(
+                    owner,
+                    (graphs_of_owner(owner).count() * depth) as u64,
+                    |_| mint_token_v2(OBJECT_GRAPHS_COLLECTION.to_string()),
+                )
+            })
+            .collect();
+        let versions = self.execute_phase("object graph objects", tasks, FUNDING_PER_TXN);
+        let expected: Vec<_> = (0..num_owners)
+            .map(|owner| graphs_of_owner(owner).count() * depth)
+            .collect();
+        let mut tokens = self.discover_tokens(versions, &expected);
+        let mut object_graphs = vec![vec![]; distribution.num_object_graphs];
+        for (owner, tokens) in tokens.iter_mut().enumerate() {
+            for graph in graphs_of_owner(owner) {
+                object_graphs[graph] = tokens.drain(..depth).collect();
+            }
+        }
+
+        // Phase 6: nest each object of a graph in the previous one.
+        let tasks = object_graphs
+            .iter()
+            .enumerate()
+            .map(|(graph, objects)| {
+                let objects = objects.clone();
+                OwnerTask::new(graph % num_owners, (depth - 1) as u64, move |i| {
+                    let i = i as usize + 1;
+                    aptos_stdlib::object_transfer_call(objects[i], objects[i - 1])
+                })
+            })
+            .collect();
+        self.execute_phase("object graph nesting", tasks, FUNDING_PER_TXN);
+
+        // Phase 7: grow the tables until the target state size is reached.
+        if let Some(target_state_size_gb) = distribution.target_state_size_gb {
+            let target_bytes = (target_state_size_gb * (1u64 << 30) as f64) as usize;
+            for round in 0..MAX_FILL_ROUNDS {
+                let bytes_before = self.state_bytes();
+                if bytes_before >= target_bytes {
+                    break;
+                }
+                let num_items =
+                    ((target_bytes - bytes_before) as f64 / bytes_per_item).ceil() as u64;
+                let per_table = num_items.div_ceil(table_sizes.len() as u64);
+                let items = table_sizes
+                    .iter()
+                    .map(|size| *size..*size + per_table)
+                    .collect();
+                let tasks = self.table_tasks(items, distribution.table_item_bytes);
+                self.execute_phase(
+                    &format!("state size fill round {}", round),
+                    tasks,
+                    table_item_funding,
+                );
+                table_sizes.iter_mut().for_each(|size| *size += per_table);
+                bytes_per_item = ((self.state_bytes() - bytes_before) as f64
+                    / (per_table * table_sizes.len() as u64) as f64)
+                    .max(1.0);
+            }
+        }
+
+        GeneratedState {
+            owners: self.owners.clone(),
+            table_sizes,
+            nft_collections,
+            num_fa_stores: distribution.num_fa_stores,
+            object_graphs,
+        }
+    }
+}
+
+/// Creates the state described by `distribution` in the DB at `db_dir`, which must already have
+/// at least `distribution.state_owners` accounts.
+pub fn generate_state<V>(
+    distribution: &StateDistribution,
+    block_size: usize,
+    db_dir: impl AsRef<Path>,
+    pruner_config: PrunerConfig,
+    enable_storage_sharding: bool,
+    num_generator_workers: usize,
+    init_features: Features,
+    is_keyless: bool,
+) where
+    V: VMBlockExecutor + 'static,
+{
+    distribution.validate();
+    let num_existing_accounts = TransactionGenerator::read_meta(&db_dir);
+    assert!(
+        distribution.state_owners <= num_existing_accounts,
+        "--state-owners ({}) cannot exceed the number of accounts ({}).",
+        distribution.state_owners,
+        num_existing_accounts,
+    );
+
+    let (mut config, genesis_key) =
+        aptos_genesis::test_utils::test_config_with_custom_features(init_features);
+    config.storage.dir = db_dir.as_ref().to_path_buf();
+    config.storage.storage_pruner_config = pruner_config;
+    config.storage.rocksdb_configs.enable_storage_sharding = enable_storage_sharding;
+    let db = init_db(&config);
+
+    let mut state_generator = StateGenerator::<V>::new(
+        db,
+        genesis_key,
+        &db_dir,
+        distribution.state_owners,
+        block_size,
+        num_generator_workers,
+        is_keyless,
+    );
+    let generated_state = state_generator.generate(distribution);
+    generated_state.write(&db_dir);
+
+    println!(
+        "Generated state: {} tables with {} items, {} NFT collections, {} object graphs, {} fungible asset stores. State size {} bytes.",
+        generated_state.table_sizes.len(),
+        generated_state.table_sizes.iter().sum::<u64>(),
+        generated_state.nft_collections.len(),
+        generated_state.object_graphs.len(),
+        generated_state.num_fa_stores,
+        state_generator.state_bytes(),
+    );
+
+    // Assert there were no error log lines in the run.
+    assert_eq!(0, aptos_logger::ERROR_LOG_COUNT.get());
+}
+
+/// Samples ranks in `[0, n)`, the probability of rank `k` being proportional to
+/// `1 / (k + 1)^exponent`. An exponent of 0 gives the uniform distribution.
+///
+/// Uses rejection-inversion sampling (Hörmann and Derflinger), same as `rand_distr::Zipf`.
+pub struct Zipf {
+    n: f64,
+    s: f64,
+    t: f64,
+    q: f64,
+}
+
+impl Zipf {
+    pub fn new(n: u64, exponent: f64) -> Self {
+        assert!(n > 0, "Cannot sample from an empty range.");
+        assert!(exponent >= 0.0, "Zipf exponent must be non-negative.");
+        let n = n as f64;
+        let q = if exponent != 1.0 {
+            1.0 / (1.0 - exponent)
+        } else {
+            0.0
+        };
+        let t = if exponent != 1.0 {
+            (n.powf(1.0 - exponent) - exponent) * q
+        } else {
+            1.0 + n.ln()
+        };
+        Self {
+            n,
+            s: exponent,
+            t,
+            q,
+        }
+    }
+
+    fn inv_cdf(&self, p: f64) -> f64 {
+        let pt = p * self.t;
+        if pt <= 1.0 {
+            pt
+        } else if self.s != 1.0 {
+            (pt * (1.0 - self.s) + self.s).powf(self.q)
+        } else {
+            (pt - 1.0).exp()
+        }
+    }
+
+    pub fn sample<R: Rng>(&self, rng: &mut R) -> u64 {
+        loop {
+            let inv_b = self.inv_cdf(rng.gen::<f64>());
+            let x = (inv_b + 1.0).floor();
+            let mut ratio = x.powf(-self.s);
+            if x > 1.0 {
+                ratio *= inv_b.powf(self.s);
+            }
+            if rng.gen::<f64>() < ratio {
+                return x.min(self.n) as u64 - 1;
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, ValueEnum)]
+pub enum StateWorkloadType {
+    /// Mints more of an existing table item (token v1), updating it.
+    TableItemUpdate,
+    /// Transfers APT to a fungible asset store.
+    FungibleAssetTransfer,
+    /// Mints a new NFT into an NFT collection.
+    NftMint,
+    /// Updates the description of an existing NFT.
+    NftUpdate,
+    /// Moves the leaf of an object graph under another object of the graph.
+    ObjectGraphUpdate,
+}
+
+/// Generates transactions of the given mix over a [`GeneratedState`], choosing the state they
+/// access (tables, table items, stores, collections, NFTs, graphs) with a Zipfian distribution.
+pub struct GeneratedStateWorkload {
+    state: GeneratedState,
+    mix: Vec<(StateWorkloadType, usize)>,
+    total_weight: usize,
+    zipf_exponent: f64,
+    rng: StdRng,
+}
+
+impl GeneratedStateWorkload {
+    pub fn new(
+        state: GeneratedState,
+        mix: Vec<(StateWorkloadType, usize)>,
+        zipf_exponent: f64,
+    ) -> Self {
+        for (workload_type, _) in &mix {
+            let has_state = match workload_type {
+                StateWorkloadType::TableItemUpdate => {
+                    !state.table_sizes.is_empty() && state.table_sizes.iter().all(|s| *s > 0)
+                },
+                StateWorkloadType::FungibleAssetTransfer => state.num_fa_stores > 0,
+                StateWorkloadType::NftMint => !state.nft_collections.is_empty(),
+                StateWorkloadType::NftUpdate => {
+                    !state.nft_collections.is_empty()
+                        && state.nft_collections.iter().all(|c| !c.is_empty())
+                },
+                StateWorkloadType::ObjectGraphUpdate => !state.object_graphs.is_empty(),
+            };
+            assert!(
+                has_state,
+                "Generated state has no state for the {:?} workload.",
+                workload_type
+            );
+        }
+        let total_weight = mix.iter().map(|(_, weight)| weight).sum();
+        assert!(
+            total_weight > 0,
+            "Workload mix must have a positive weight."
+        );
+
+        Self {
+            state,
+            mix,
+            total_weight,
+            zipf_exponent,
+            rng: StdRng::from_entropy(),
+        }
+    }
+
+    fn zipf(&mut self, n: usize) -> usize {
+        Zipf::new(n as u64, self.zipf_exponent).sample(&mut self.rng) as usize
+    }
+
+    fn pick_workload_type(&mut self) -> StateWorkloadType {
+        let mut pick = self.rng.gen_range(0, self.total_weight);
+        for (workload_type, weight) in &self.mix {
+            if pick < *weight {
+                return *workload_type;
+            }
+            pick -= weight;
+        }
+        unreachable!()
+    }
+
+    /// Returns the index of the owner that needs to sign the payload, and the payload.
+    pub fn gen_payload(&mut self) -> (usize, TransactionPayload) {
+        match self.pick_workload_type() {
+            StateWorkloadType::TableItemUpdate => {
+                let table = self.zipf(self.state.table_sizes.len());
+                let item = Zipf::new(self.state.table_sizes[table], self.zipf_exponent)
+                    .sample(&mut self.rng);
+                let owner = self.state.owner_of(table);
+                (
+                    owner,
+                    aptos_token_stdlib::token_mint_script(
+                        self.state.owners[owner],
+                        table_name(table).into_bytes(),
+                        table_item_name(item).into_bytes(),
+                        1,
+                    ),
+                )
+            },
+            StateWorkloadType::FungibleAssetTransfer => {
+                let store = self.zipf(self.state.num_fa_stores);
+                let owner = self.rng.gen_range(0, self.state.owners.len());
+                (
+                    owner,
+                    aptos_stdlib::aptos_account_fungible_transfer_only(fa_store_owner(store), 1),
+                )
+            },
+            StateWorkloadType::NftMint => {
+                let collection = self.zipf(self.state.nft_collections.len());
+                (
+                    self.state.owner_of(collection),
+                    mint_token_v2(nft_collection_name(collection)),
+                )
+            },
+            StateWorkloadType::NftUpdate => {
+                let collection = self.zipf(self.state.nft_collections.len());
+                let token = self.zipf(self.state.nft_collections[collection].len());
+                let description = format!("description {}", self.rng.gen::<u64>());
+                (
+                    self.state.owner_of(collection),
+                    set_token_v2_description(
+                        self.state.nft_collections[collection][token],
+                        description,
+                    ),
+                )
+            },
+            StateWorkloadType::ObjectGraphUpdate => {
+                let graph = self.zipf(self.state.object_graphs.len());
+                let objects = &self.state.object_graphs[graph];
+                // The leaf stays the leaf, so the graph never gets deeper than when created.
+                let new_parent = self.rng.gen_range(0, objects.len() - 1);
+                (
+                    self.state.owner_of(graph),
+                    aptos_stdlib::object_transfer_call(
+                        *objects.last().unwrap(),
+                        objects[new_parent],
+                    ),
+                )
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_zipf_range_and_skew() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let zipf = Zipf::new(100, 1.0);
+        let mut counts = vec![0; 100];
+        for _ in 0..100_000 {
+            counts[zipf.sample(&mut rng) as usize] += 1;
+        }
+        // P(0) = 1 / H(100) ~ 0.19, P(k) = P(0) / (k + 1).
+        assert!((17_000..21_000).contains(&counts[0]), "{:?}", counts);
+        assert!(counts[0] > 5 * counts[9]);
+        assert!(counts[9] > 5 * counts[99]);
+        assert!(counts[99] > 0);
+    }
+
+    #[test]
+    fn test_zipf_zero_exponent_is_uniform() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let zipf = Zipf::new(10, 0.0);
+        let mut counts = vec![0; 10];
+        for _ in 0..100_000 {
+            counts[zipf.sample(&mut rng) as usize] += 1;
+        }
+        for count in counts {
+            assert!((9_000..11_000).contains(&count), "{}", count);
+        }
+    }
+
+    #[test]
+    fn test_zipf_single_element() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let zipf = Zipf::new(1, 1.5);
+        for _ in 0..100 {
+            assert_eq!(zipf.sample(&mut rng), 0);
+        }
+    }
+
+    #[test]
+    fn test_interleave() {
+        let tasks = vec![
+            OwnerTask::new(0, 2, |i| ("a", i)),
+            OwnerTask::new(1, 3, |i| ("c", i)),
+            OwnerTask::new(0, 1, |i| ("b", i)),
+        ];
+        let order: Vec<_> = interleave(3, tasks).collect();
+        assert_eq!(order, vec![
+            (0, ("a", 0)),
+            (1, ("c", 0)),
+            (0, ("a", 1)),
+            (1, ("c", 1)),
+            (0, ("b", 0)),
+            (1, ("c", 2)),
+        ]);
+    }
+}
@@ -20,7 +20,7 @@ use aptos_types::{
     account_config::{aptos_test_root_address, AccountResource},
     chain_id::ChainId,
     state_store::MoveResourceExt,
-    transaction::Transaction,
+    transaction::{Transaction, TransactionPayload},
 };
 use chrono::Local;
 use indicatif::{ProgressBar, ProgressStyle};
@@ -260,6 +260,85 @@ impl TransactionGenerator {
         self.num_existing_accounts
     }
 
+    pub fn main_signer_addresses(&self) -> Vec<AccountAddress> {
+        self.main_signer_accounts
+            .as_ref()
+            .map_or_else(Vec::new, |cache| {
+                cache.accounts().iter().map(|a| a.address()).collect()
+            })
+    }
+
+    /// Replaces the channel blocks are sent to. Used to continue generating into a new pipeline,
+    /// after the previous sender was dropped to let the previous pipeline finish.
+    pub fn set_block_sender(&mut self, block_sender: mpsc::SyncSender<Vec<Transaction>>) {
+        assert!(self.block_sender.is_none());
+        self.block_sender = Some(block_sender);
+    }
+
+    /// Transfers `amounts[i]` from the root account to the i-th main signer account, skipping
+    /// zero amounts.
+    pub fn fund_main_signer_accounts(&mut self, amounts: &[u64], block_size: usize) {
+        assert!(self.block_sender.is_some());
+        let recipients: Vec<_> = self
+            .main_signer_accounts
+            .as_ref()
+            .unwrap()
+            .accounts()
+            .iter()
+            .zip(amounts)
+            .filter(|(_, amount)| **amount > 0)
+            .map(|(account, amount)| (account.address(), *amount))
+            .collect();
+        println!(
+            "[{}] Funding {} main signer accounts.",
+            now_fmt!(),
+            recipients.len()
+        );
+
+        for chunk in recipients.chunks(block_size) {
+            let transactions: Vec<_> = chunk
+                .iter()
+                .map(|(address, amount)| {
+                    let payload = aptos_stdlib::aptos_account_transfer(*address, *amount);
+                    let builder = self.transaction_factory.payload(payload);
+                    let txn = self.root_account.sign_with_transaction_builder(builder);
+                    Transaction::UserTransaction(txn)
+                })
+                .collect();
+            if let Some(sender) = &self.block_sender {
+                sender.send(transactions).unwrap();
+            }
+        }
+    }
+
+    /// Signs each payload with the main signer account at the given index, and sends them in
+    /// blocks of `block_size`. Transactions of the same sender keep their relative order.
+    /// Returns the number of blocks sent.
+    pub fn run_payloads(
+        &mut self,
+        block_size: usize,
+        payloads: impl Iterator<Item = (usize, TransactionPayload)>,
+    ) -> usize {
+        assert!(self.block_sender.is_some());
+        let mut num_blocks = 0;
+        for chunk in &payloads.chunks(block_size) {
+            self.generate_and_send_block(
+                self.main_signer_accounts.as_ref().unwrap(),
+                chunk.collect(),
+                Arc::new(AtomicUsize::new(0)),
+                Arc::new(AtomicUsize::new(0)),
+                |(sender_idx, payload), account_cache| {
+                    let txn = account_cache.accounts[sender_idx]
+                        .sign_with_transaction_builder(self.transaction_factory.payload(payload));
+                    Some(Transaction::UserTransaction(txn))
+                },
+                |(sender_idx, _)| *sender_idx,
+            );
+            num_blocks += 1;
+        }
+        num_blocks
+    }
+
     pub fn run_mint(
         &mut self,
         reader: Arc<dyn DbReader>,
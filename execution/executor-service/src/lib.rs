@@ -9,16 +9,19 @@ use aptos_types::{
     transaction::{analyzed_transaction::AnalyzedTransaction, TransactionOutput},
     vm_status::VMStatus,
 };
+use aptos_vm::sharded_block_executor::messages::CrossShardMsg;
 use serde::{Deserialize, Serialize};
 
 mod error;
 pub mod local_executor_helper;
+mod local_fallback;
 mod metrics;
 pub mod process_executor_service;
 mod remote_cordinator_client;
 mod remote_cross_shard_client;
 pub mod remote_executor_client;
 pub mod remote_executor_service;
+mod remote_health_check;
 mod remote_state_view;
 mod remote_state_view_service;
 #[cfg(test)]
@@ -30,12 +33,15 @@ mod thread_executor_service;
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct RemoteExecutionResult {
+    // The id of the block the result is for, so that late results of blocks the coordinator
+    // stopped waiting for can be told apart.
+    pub block_id: u64,
     pub inner: Result<Vec<Vec<TransactionOutput>>, VMStatus>,
 }
 
 impl RemoteExecutionResult {
-    pub fn new(inner: Result<Vec<Vec<TransactionOutput>>, VMStatus>) -> Self {
-        Self { inner }
+    pub fn new(block_id: u64, inner: Result<Vec<Vec<TransactionOutput>>, VMStatus>) -> Self {
+        Self { block_id, inner }
     }
 }
 
@@ -46,6 +52,7 @@ pub enum RemoteExecutionRequest {
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct ExecuteBlockCommand {
+    pub(crate) block_id: u64,
     pub(crate) sub_blocks: SubBlocksForShard<AnalyzedTransaction>,
     pub(crate) concurrency_level: usize,
     pub(crate) onchain_config: BlockExecutorConfigFromOnchain,
@@ -66,26 +73,67 @@ impl ExecuteBlockCommand {
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct RemoteKVRequest {
     pub(crate) shard_id: ShardId,
+    // The id of the block the keys are read by, so that requests of blocks the coordinator
+    // stopped executing are not answered with the state of another block.
+    pub(crate) block_id: u64,
     pub(crate) keys: Vec<StateKey>,
 }
 
 impl RemoteKVRequest {
-    pub fn new(shard_id: ShardId, keys: Vec<StateKey>) -> Self {
-        Self { shard_id, keys }
+    pub fn new(shard_id: ShardId, block_id: u64, keys: Vec<StateKey>) -> Self {
+        Self {
+            shard_id,
+            block_id,
+            keys,
+        }
     }
 
-    pub fn into(self) -> (ShardId, Vec<StateKey>) {
-        (self.shard_id, self.keys)
+    pub fn into(self) -> (ShardId, u64, Vec<StateKey>) {
+        (self.shard_id, self.block_id, self.keys)
     }
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct RemoteKVResponse {
-    pub(crate) inner: Vec<(StateKey, Option<StateValue>)>,
+    pub(crate) block_id: u64,
+    // None if the coordinator is not executing the block anymore, so the values will never be
+    // sent.
+    pub(crate) inner: Option<Vec<(StateKey, Option<StateValue>)>>,
 }
 
 impl RemoteKVResponse {
-    pub fn new(inner: Vec<(StateKey, Option<StateValue>)>) -> Self {
-        Self { inner }
+    pub fn new(block_id: u64, inner: Vec<(StateKey, Option<StateValue>)>) -> Self {
+        Self {
+            block_id,
+            inner: Some(inner),
+        }
     }
+
+    pub fn aborted(block_id: u64) -> Self {
+        Self {
+            block_id,
+            inner: None,
+        }
+    }
+}
+
+/// A cross-shard message, tagged with the block it belongs to, so that messages of aborted blocks
+/// are not received while executing another one.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct RemoteCrossShardMsg {
+    pub(crate) block_id: u64,
+    pub(crate) msg: CrossShardMsg,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct HealthCheckRequest {
+    pub(crate) nonce: u64,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct HealthCheckResponse {
+    pub(crate) shard_id: ShardId,
+    pub(crate) nonce: u64,
+    // The id of the block the shard is executing, if any.
+    pub(crate) executing_block_id: Option<u64>,
 }
@@ -0,0 +1,197 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Local execution of the sub-blocks of remote shards on the coordinator, used when shards fail
+//! and to verify the outputs of the shards.
+
+use crate::metrics::{REMOTE_EXECUTOR_FAULT_COUNT, REMOTE_EXECUTOR_TIMER};
+use aptos_block_executor::{
+    code_cache_global_manager::AptosModuleCacheManager, txn_commit_hook::NoOpTransactionCommitHook,
+    txn_provider::default::DefaultTxnProvider,
+};
+use aptos_logger::error;
+use aptos_types::{
+    block_executor::{
+        config::BlockExecutorConfig,
+        partitioner::{ShardId, SubBlock, SubBlocksForShard},
+        transaction_slice_metadata::TransactionSliceMetadata,
+    },
+    state_store::{
+        errors::StateViewError, state_key::StateKey, state_storage_usage::StateStorageUsage,
+        state_value::StateValue, StateView, TStateView,
+    },
+    transaction::{analyzed_transaction::AnalyzedTransaction, BlockOutput, TransactionOutput},
+    vm_status::{StatusCode, VMStatus},
+    write_set::TransactionWrite,
+};
+use aptos_vm::{
+    block_executor::{AptosTransactionOutput, AptosVMBlockExecutorWrapper},
+    sharded_block_executor::aggr_overridden_state_view::{
+        AggregatorOverriddenStateView, TOTAL_SUPPLY_AGGR_BASE_VAL,
+    },
+};
+use std::{collections::HashMap, sync::Arc};
+
+/// The base state of a block, with the writes of the rounds executed so far. Sub-blocks of the
+/// same round do not conflict across shards, so this is all a sub-block needs to see, wherever the
+/// earlier rounds were executed.
+struct RoundStateView<'a, S> {
+    base_view: &'a S,
+    writes: HashMap<StateKey, Option<StateValue>>,
+}
+
+impl<'a, S: StateView + Sync + Send> RoundStateView<'a, S> {
+    fn new(base_view: &'a S) -> Self {
+        Self {
+            base_view,
+            writes: HashMap::new(),
+        }
+    }
+
+    fn apply_outputs(&mut self, outputs: &[TransactionOutput]) {
+        for output in outputs {
+            for (state_key, write_op) in output.write_set().iter() {
+                self.writes
+                    .insert(state_key.clone(), write_op.as_state_value());
+            }
+        }
+    }
+}
+
+impl<'a, S: StateView + Sync + Send> TStateView for RoundStateView<'a, S> {
+    type Key = StateKey;
+
+    fn get_state_value(&self, state_key: &StateKey) -> Result<Option<StateValue>, StateViewError> {
+        match self.writes.get(state_key) {
+            Some(state_value) => Ok(state_value.clone()),
+            None => self.base_view.get_state_value(state_key),
+        }
+    }
+
+    fn get_usage(&self) -> Result<StateStorageUsage, StateViewError> {
+        self.base_view.get_usage()
+    }
+}
+
+/// Executes the sub-blocks of the shards that have no remote outputs locally, round by round,
+/// and returns the outputs of all the shards. If `verify_outputs` is set, the sub-blocks of the
+/// other shards are executed as well, and an error is returned if the outputs differ.
+pub fn execute_sub_blocks_locally<S: StateView + Sync + Send>(
+    thread_pool: &Arc<rayon::ThreadPool>,
+    state_view: &S,
+    sub_blocks: Vec<SubBlocksForShard<AnalyzedTransaction>>,
+    remote_outputs: Vec<Option<Vec<Vec<TransactionOutput>>>>,
+    config: BlockExecutorConfig,
+    verify_outputs: bool,
+) -> Result<Vec<Vec<Vec<TransactionOutput>>>, VMStatus> {
+    let _timer = REMOTE_EXECUTOR_TIMER
+        .with_label_values(&["coordinator", "local_execution"])
+        .start_timer();
+    let num_rounds = sub_blocks
+        .first()
+        .map_or(0, |sub_blocks| sub_blocks.num_sub_blocks());
+    let mut sub_blocks: Vec<_> = sub_blocks
+        .into_iter()
+        .map(|sub_blocks| sub_blocks.into_sub_blocks().into_iter())
+        .collect();
+    let mut remote_outputs: Vec<_> = remote_outputs
+        .into_iter()
+        .map(|outputs| outputs.map(Vec::into_iter))
+        .collect();
+
+    let mut state_view = RoundStateView::new(state_view);
+    let mut outputs = vec![Vec::with_capacity(num_rounds); sub_blocks.len()];
+    for round in 0..num_rounds {
+        for (shard_id, shard_sub_blocks) in sub_blocks.iter_mut().enumerate() {
+            let sub_block = shard_sub_blocks
+                .next()
+                .expect("All shards must have the same number of rounds");
+            let remote_output = remote_outputs[shard_id]
+                .as_mut()
+                .map(|outputs| outputs.next().expect("Missing output for round"));
+            let output = match remote_output {
+                Some(remote_output) => {
+                    if verify_outputs {
+                        let local_output =
+                            execute_sub_block(thread_pool, sub_block, &state_view, config.clone())?;
+                        verify_sub_block_outputs(shard_id, round, &remote_output, &local_output)?;
+                    }
+                    remote_output
+                },
+                None => execute_sub_block(thread_pool, sub_block, &state_view, config.clone())?,
+            };
+            outputs[shard_id].push(output);
+        }
+        // Make the writes of the round visible to the next rounds.
+        for shard_outputs in outputs.iter() {
+            state_view.apply_outputs(&shard_outputs[round]);
+        }
+    }
+    Ok(outputs)
+}
+
+fn execute_sub_block<S: StateView + Sync + Send>(
+    thread_pool: &Arc<rayon::ThreadPool>,
+    sub_block: SubBlock<AnalyzedTransaction>,
+    state_view: &S,
+    config: BlockExecutorConfig,
+) -> Result<Vec<TransactionOutput>, VMStatus> {
+    let txn_provider = DefaultTxnProvider::new(
+        sub_block
+            .into_txns()
+            .into_iter()
+            .map(AnalyzedTransaction::into_txn)
+            .collect(),
+    );
+    // The total supply is tracked the same way as on the remote shards, so that the outputs can be
+    // aggregated together.
+    let state_view = AggregatorOverriddenStateView::new(state_view, TOTAL_SUPPLY_AGGR_BASE_VAL);
+    AptosVMBlockExecutorWrapper::execute_block_on_thread_pool::<
+        _,
+        NoOpTransactionCommitHook<AptosTransactionOutput, VMStatus>,
+        _,
+    >(
+        thread_pool.clone(),
+        &txn_provider,
+        &state_view,
+        &AptosModuleCacheManager::new(),
+        config,
+        TransactionSliceMetadata::unknown(),
+        None,
+    )
+    .map(BlockOutput::into_transaction_outputs_forced)
+}
+
+fn verify_sub_block_outputs(
+    shard_id: ShardId,
+    round: usize,
+    remote_outputs: &[TransactionOutput],
+    local_outputs: &[TransactionOutput],
+) -> Result<(), VMStatus> {
+    if remote_outputs == local_outputs {
+        return Ok(());
+    }
+
+    REMOTE_EXECUTOR_FAULT_COUNT
+        .with_label_values(&[&shard_id.to_string(), "output_mismatch"])
+        .inc();
+    let first_mismatch = remote_outputs
+        .iter()
+        .zip(local_outputs)
+        .position(|(remote_output, local_output)| remote_output != local_output)
+        .unwrap_or(remote_outputs.len().min(local_outputs.len()));
+    let message = format!(
+        "Outputs of shard {} for round {} do not match local execution: {} remote and {} local \
+         outputs, first mismatch at index {}",
+        shard_id,
+        round,
+        remote_outputs.len(),
+        local_outputs.len(),
+        first_mismatch
+    );
+    error!("{}", message);
+    Err(VMStatus::error(
+        StatusCode::UNKNOWN_INVARIANT_VIOLATION_ERROR,
+        Some(message),
+    ))
+}
@@ -22,7 +22,10 @@ pub static REMOTE_EXECUTOR_TIMER: Lazy<HistogramVec> = Lazy::new(|| {
          7. non_prefetch_wait: waiting for the remote state values that were not prefetched; \
          8. kv_req_deser: deserializing the remote key value requests; \
          9. kv_requests: processing the remote key value requests; \
-         10. kv_resp_ser: serializing the remote key value responses;",
+         10. kv_resp_ser: serializing the remote key value responses; \
+         11. health_check: (on the coordinator) health checking the shards; \
+         12. local_execution: (on the coordinator) executing the sub-blocks of failed shards, \
+         or verifying the outputs of the shards, locally;",
         // metric labels (dimensions)
         &["shard_id", "name"],
         exponential_buckets(/*start=*/ 1e-3, /*factor=*/ 2.0, /*count=*/ 20).unwrap(),
@@ -44,3 +47,20 @@ pub static REMOTE_EXECUTOR_REMOTE_KV_COUNT: Lazy<IntCounterVec> = Lazy::new(|| {
     )
     .unwrap()
 });
+
+pub static REMOTE_EXECUTOR_FAULT_COUNT: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        // metric name
+        "remote_executor_fault_count",
+        // metric description
+        "Fault counts on the coordinator for each shard: \
+         1. unavailable: the shard was down or still busy when a block was dispatched, so its \
+         sub-blocks were executed locally; \
+         2. unresponsive: the shard stopped answering health checks while executing a block; \
+         3. timeout: the shard did not return its results before the execution timeout; \
+         4. output_mismatch: the outputs of the shard did not match local execution; ",
+        // metric labels (dimensions)
+        &["shard_id", "name"],
+    )
+    .unwrap()
+});
@@ -14,13 +14,18 @@ use aptos_vm::sharded_block_executor::{
 };
 use crossbeam_channel::{Receiver, Sender};
 use rayon::prelude::*;
-use std::{net::SocketAddr, sync::Arc};
+use std::{
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+};
 
 pub struct RemoteCoordinatorClient {
     state_view_client: Arc<RemoteStateViewClient>,
     command_rx: Receiver<Message>,
     result_tx: Sender<Message>,
     shard_id: ShardId,
+    // The id of the block being executed, shared with the health check service.
+    executing_block_id: Arc<Mutex<Option<u64>>>,
 }
 
 impl RemoteCoordinatorClient {
@@ -28,6 +33,7 @@ impl RemoteCoordinatorClient {
         shard_id: ShardId,
         controller: &mut NetworkController,
         coordinator_address: SocketAddr,
+        executing_block_id: Arc<Mutex<Option<u64>>>,
     ) -> Self {
         let execute_command_type = format!("execute_command_{}", shard_id);
         let execute_result_type = format!("execute_result_{}", shard_id);
@@ -43,6 +49,7 @@ impl RemoteCoordinatorClient {
             command_rx,
             result_tx,
             shard_id,
+            executing_block_id,
         }
     }
 
@@ -91,11 +98,13 @@ impl CoordinatorClient<RemoteStateViewClient> for RemoteCoordinatorClient {
 
                 match request {
                     RemoteExecutionRequest::ExecuteBlock(command) => {
+                        *self.executing_block_id.lock().unwrap() = Some(command.block_id);
                         let init_prefetch_timer = REMOTE_EXECUTOR_TIMER
                             .with_label_values(&[&self.shard_id.to_string(), "init_prefetch"])
                             .start_timer();
                         let state_keys = Self::extract_state_keys(&command);
-                        self.state_view_client.init_for_block(state_keys);
+                        self.state_view_client
+                            .init_for_block(command.block_id, state_keys);
                         drop(init_prefetch_timer);
 
                         let (sub_blocks, concurrency, onchain_config) = command.into();
@@ -113,7 +122,13 @@ impl CoordinatorClient<RemoteStateViewClient> for RemoteCoordinatorClient {
     }
 
     fn send_execution_result(&self, result: Result<Vec<Vec<TransactionOutput>>, VMStatus>) {
-        let remote_execution_result = RemoteExecutionResult::new(result);
+        let block_id = self
+            .executing_block_id
+            .lock()
+            .unwrap()
+            .take()
+            .expect("Result sent without an executing block");
+        let remote_execution_result = RemoteExecutionResult::new(block_id, result);
         let output_message = bcs::to_bytes(&remote_execution_result).unwrap();
         self.result_tx.send(Message::new(output_message)).unwrap();
     }
@@ -1,5 +1,7 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
+use crate::RemoteCrossShardMsg;
+use aptos_logger::trace;
 use aptos_secure_net::network_controller::{Message, NetworkController};
 use aptos_types::block_executor::partitioner::{RoundId, ShardId, MAX_ALLOWED_PARTITIONING_ROUNDS};
 use aptos_vm::sharded_block_executor::{
@@ -7,44 +9,83 @@ use aptos_vm::sharded_block_executor::{
 };
 use crossbeam_channel::{Receiver, Sender};
 use std::{
+    cmp::Ordering,
+    collections::VecDeque,
     net::SocketAddr,
     sync::{Arc, Mutex},
 };
 
+fn cross_shard_message_type(round: RoundId) -> String {
+    format!("cross_shard_{}", round)
+}
+
+/// Creates the channels to send cross-shard messages to each shard per round. The coordinator
+/// uses them as well, to abort blocks on the shards.
+pub fn create_cross_shard_message_txs(
+    controller: &mut NetworkController,
+    shard_addresses: &[SocketAddr],
+) -> Vec<Vec<Mutex<Sender<Message>>>> {
+    shard_addresses
+        .iter()
+        .map(|remote_address| {
+            (0..MAX_ALLOWED_PARTITIONING_ROUNDS)
+                .map(|round| {
+                    Mutex::new(
+                        controller.create_outbound_channel(
+                            *remote_address,
+                            cross_shard_message_type(round),
+                        ),
+                    )
+                })
+                .collect()
+        })
+        .collect()
+}
+
 pub struct RemoteCrossShardClient {
     // The senders of cross-shard messages to other shards per round.
     message_txs: Arc<Vec<Vec<Mutex<Sender<Message>>>>>,
     // The receivers of cross shard messages from other shards per round.
     message_rxs: Arc<Vec<Mutex<Receiver<Message>>>>,
+    // Messages received per round for blocks after the one being executed, e.g., from shards that
+    // finished the previous block earlier.
+    pending_messages: Vec<Mutex<VecDeque<RemoteCrossShardMsg>>>,
+    // The id of the block being executed, shared with the coordinator client.
+    executing_block_id: Arc<Mutex<Option<u64>>>,
 }
 
 impl RemoteCrossShardClient {
-    pub fn new(controller: &mut NetworkController, shard_addresses: Vec<SocketAddr>) -> Self {
-        let mut message_txs = vec![];
-        let mut message_rxs = vec![];
+    pub fn new(
+        controller: &mut NetworkController,
+        shard_addresses: Vec<SocketAddr>,
+        executing_block_id: Arc<Mutex<Option<u64>>>,
+    ) -> Self {
         // Create outbound channels for each shard per round.
-        for remote_address in shard_addresses.iter() {
-            let mut txs = vec![];
-            for round in 0..MAX_ALLOWED_PARTITIONING_ROUNDS {
-                let message_type = format!("cross_shard_{}", round);
-                let tx = controller.create_outbound_channel(*remote_address, message_type);
-                txs.push(Mutex::new(tx));
-            }
-            message_txs.push(txs);
-        }
+        let message_txs = create_cross_shard_message_txs(controller, &shard_addresses);
 
         // Create inbound channels for each round
+        let mut message_rxs = vec![];
+        let mut pending_messages = vec![];
         for round in 0..MAX_ALLOWED_PARTITIONING_ROUNDS {
-            let message_type = format!("cross_shard_{}", round);
-            let rx = controller.create_inbound_channel(message_type);
+            let rx = controller.create_inbound_channel(cross_shard_message_type(round));
             message_rxs.push(Mutex::new(rx));
+            pending_messages.push(Mutex::new(VecDeque::new()));
         }
 
         Self {
             message_txs: Arc::new(message_txs),
             message_rxs: Arc::new(message_rxs),
+            pending_messages,
+            executing_block_id,
         }
     }
+
+    fn executing_block_id(&self) -> u64 {
+        self.executing_block_id
+            .lock()
+            .unwrap()
+            .expect("Cross-shard messages are only exchanged while executing a block")
+    }
 }
 
 impl CrossShardClient for RemoteCrossShardClient {
@@ -53,15 +94,39 @@ impl CrossShardClient for RemoteCrossShardClient {
     }
 
     fn send_cross_shard_msg(&self, shard_id: ShardId, round: RoundId, msg: CrossShardMsg) {
+        let msg = RemoteCrossShardMsg {
+            block_id: self.executing_block_id(),
+            msg,
+        };
         let input_message = bcs::to_bytes(&msg).unwrap();
         let tx = self.message_txs[shard_id][round].lock().unwrap();
         tx.send(Message::new(input_message)).unwrap();
     }
 
     fn receive_cross_shard_msg(&self, current_round: RoundId) -> CrossShardMsg {
+        let block_id = self.executing_block_id();
         let rx = self.message_rxs[current_round].lock().unwrap();
-        let message = rx.recv().unwrap();
-        let msg: CrossShardMsg = bcs::from_bytes(&message.to_bytes()).unwrap();
-        msg
+        let mut pending_messages = self.pending_messages[current_round].lock().unwrap();
+        pending_messages.retain(|msg| msg.block_id >= block_id);
+        if let Some(idx) = pending_messages
+            .iter()
+            .position(|msg| msg.block_id == block_id)
+        {
+            return pending_messages.remove(idx).unwrap().msg;
+        }
+
+        loop {
+            let message = rx.recv().unwrap();
+            let msg: RemoteCrossShardMsg = bcs::from_bytes(&message.to_bytes()).unwrap();
+            match msg.block_id.cmp(&block_id) {
+                Ordering::Equal => return msg.msg,
+                Ordering::Greater => pending_messages.push_back(msg),
+                Ordering::Less => trace!(
+                    "Ignoring cross-shard message of block {} while executing block {}",
+                    msg.block_id,
+                    block_id
+                ),
+            }
+        }
     }
 }
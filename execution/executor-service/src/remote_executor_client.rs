@@ -2,36 +2,48 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 use crate::{
-    remote_state_view_service::RemoteStateViewService, ExecuteBlockCommand, RemoteExecutionRequest,
-    RemoteExecutionResult,
+    local_fallback::execute_sub_blocks_locally,
+    metrics::REMOTE_EXECUTOR_FAULT_COUNT,
+    remote_cross_shard_client::create_cross_shard_message_txs,
+    remote_health_check::{ShardHealth, ShardHealthChecker},
+    remote_state_view_service::RemoteStateViewService,
+    ExecuteBlockCommand, RemoteCrossShardMsg, RemoteExecutionRequest, RemoteExecutionResult,
 };
-use aptos_logger::{info, trace};
+use aptos_logger::{info, trace, warn};
 use aptos_secure_net::network_controller::{Message, NetworkController};
 use aptos_storage_interface::state_store::state_view::cached_state_view::CachedStateView;
 use aptos_types::{
     block_executor::{
-        config::BlockExecutorConfigFromOnchain, partitioner::PartitionedTransactions,
+        config::{BlockExecutorConfig, BlockExecutorConfigFromOnchain, BlockExecutorLocalConfig},
+        partitioner::{PartitionedTransactions, ShardId, SubBlocksForShard},
     },
     state_store::StateView,
-    transaction::TransactionOutput,
+    transaction::{analyzed_transaction::AnalyzedTransaction, TransactionOutput},
     vm_status::VMStatus,
 };
 use aptos_vm::sharded_block_executor::{
     executor_client::{ExecutorClient, ShardedExecutionOutput},
+    messages::CrossShardMsg,
     ShardedBlockExecutor,
 };
-use crossbeam_channel::{Receiver, Sender};
+use crossbeam_channel::{Receiver, Select, Sender};
 use once_cell::sync::{Lazy, OnceCell};
 use std::{
+    collections::BTreeSet,
     net::{IpAddr, Ipv4Addr, SocketAddr},
-    sync::{Arc, Mutex},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
     thread,
+    time::{Duration, Instant},
 };
 
 pub static COORDINATOR_PORT: u16 = 52200;
 
 static REMOTE_ADDRESSES: OnceCell<Vec<SocketAddr>> = OnceCell::new();
 static COORDINATOR_ADDRESS: OnceCell<SocketAddr> = OnceCell::new();
+static FAULT_TOLERANCE_CONFIG: OnceCell<FaultToleranceConfig> = OnceCell::new();
 
 pub fn set_remote_addresses(addresses: Vec<SocketAddr>) {
     REMOTE_ADDRESSES.set(addresses).ok();
@@ -55,6 +67,44 @@ pub fn get_coordinator_address() -> SocketAddr {
     }
 }
 
+pub fn set_fault_tolerance_config(config: FaultToleranceConfig) {
+    FAULT_TOLERANCE_CONFIG.set(config).ok();
+}
+
+pub fn get_fault_tolerance_config() -> FaultToleranceConfig {
+    match FAULT_TOLERANCE_CONFIG.get() {
+        Some(value) => value.clone(),
+        None => FaultToleranceConfig::default(),
+    }
+}
+
+/// How the coordinator deals with slow or crashed remote shards.
+#[derive(Clone, Debug)]
+pub struct FaultToleranceConfig {
+    /// Maximum time to wait for the results of a block from the shards. The sub-blocks of the
+    /// shards that did not return their results in time are executed locally.
+    pub execution_timeout: Duration,
+    /// How often the shards that have not returned their results yet are health checked, so that
+    /// crashed shards are detected before the execution timeout.
+    pub health_check_interval: Duration,
+    /// Maximum time to wait for a shard to answer a health check.
+    pub health_check_timeout: Duration,
+    /// Whether the sub-blocks executed by the remote shards are executed locally as well, to
+    /// verify that the outputs match. This is expensive, and meant for testing deployments.
+    pub verify_outputs: bool,
+}
+
+impl Default for FaultToleranceConfig {
+    fn default() -> Self {
+        Self {
+            execution_timeout: Duration::from_secs(60),
+            health_check_interval: Duration::from_secs(1),
+            health_check_timeout: Duration::from_secs(2),
+            verify_outputs: false,
+        }
+    }
+}
+
 pub static REMOTE_SHARDED_BLOCK_EXECUTOR: Lazy<
     Arc<
         aptos_infallible::Mutex<
@@ -83,8 +133,17 @@ pub struct RemoteExecutorClient<S: StateView + Sync + Send + 'static> {
     command_txs: Arc<Vec<Mutex<Sender<Message>>>>,
     // Channels to receive execution results from the executor shards.
     result_rxs: Vec<Receiver<Message>>,
+    // Channels to send cross-shard messages to the executor shards per round, used to abort blocks.
+    cross_shard_txs: Vec<Vec<Mutex<Sender<Message>>>>,
     // Thread pool used to pre-fetch the state values for the block in parallel and create an in-memory state view.
+    // It is also used to execute the sub-blocks of failed shards locally.
     thread_pool: Arc<rayon::ThreadPool>,
+    health_checker: ShardHealthChecker,
+    config: FaultToleranceConfig,
+    // Blocks are numbered, so that late results of blocks that were executed locally are ignored.
+    next_block_id: AtomicU64,
+    // Shards that failed, whose sub-blocks are executed locally until they are healthy again.
+    unavailable_shards: Mutex<BTreeSet<ShardId>>,
 
     phantom: std::marker::PhantomData<S>,
     _join_handle: Option<thread::JoinHandle<()>>,
@@ -93,9 +152,23 @@ pub struct RemoteExecutorClient<S: StateView + Sync + Send + 'static> {
 #[allow(dead_code)]
 impl<S: StateView + Sync + Send + 'static> RemoteExecutorClient<S> {
     pub fn new(
+        remote_shard_addresses: Vec<SocketAddr>,
+        controller: NetworkController,
+        num_threads: Option<usize>,
+    ) -> Self {
+        Self::new_with_config(
+            remote_shard_addresses,
+            controller,
+            num_threads,
+            get_fault_tolerance_config(),
+        )
+    }
+
+    pub fn new_with_config(
         remote_shard_addresses: Vec<SocketAddr>,
         mut controller: NetworkController,
         num_threads: Option<usize>,
+        config: FaultToleranceConfig,
     ) -> Self {
         let num_threads = num_threads.unwrap_or_else(num_cpus::get);
         let thread_pool = Arc::new(
@@ -119,6 +192,9 @@ impl<S: StateView + Sync + Send + 'static> RemoteExecutorClient<S> {
             })
             .unzip();
 
+        let cross_shard_txs =
+            create_cross_shard_message_txs(controller_mut_ref, &remote_shard_addresses);
+        let health_checker = ShardHealthChecker::new(controller_mut_ref, &remote_shard_addresses);
         let state_view_service = Arc::new(RemoteStateViewService::new(
             controller_mut_ref,
             remote_shard_addresses,
@@ -140,7 +216,12 @@ impl<S: StateView + Sync + Send + 'static> RemoteExecutorClient<S> {
             _join_handle: Some(join_handle),
             command_txs: Arc::new(command_txs),
             result_rxs,
+            cross_shard_txs,
             thread_pool,
+            health_checker,
+            config,
+            next_block_id: AtomicU64::new(0),
+            unavailable_shards: Mutex::new(BTreeSet::new()),
             phantom: std::marker::PhantomData,
         }
     }
@@ -161,18 +242,258 @@ impl<S: StateView + Sync + Send + 'static> RemoteExecutorClient<S> {
         ))
     }
 
-    fn get_output_from_shards(&self) -> Result<Vec<Vec<Vec<TransactionOutput>>>, VMStatus> {
+    /// Waits until all the shards answer health checks, e.g., after they were started. Returns
+    /// false if some of them did not answer before the timeout.
+    pub fn wait_for_shards(&self, timeout: Duration) -> bool {
+        let deadline = Instant::now() + timeout;
+        let shard_ids: Vec<_> = (0..self.command_txs.len()).collect();
+        loop {
+            let health = self
+                .health_checker
+                .check(&shard_ids, self.config.health_check_timeout);
+            if health
+                .values()
+                .all(|shard_health| *shard_health != ShardHealth::Unresponsive)
+            {
+                return true;
+            }
+            if Instant::now() >= deadline {
+                return false;
+            }
+        }
+    }
+
+    /// Returns the shards whose sub-blocks are currently executed locally, because they failed.
+    pub fn unavailable_shards(&self) -> Vec<ShardId> {
+        self.unavailable_shards
+            .lock()
+            .unwrap()
+            .iter()
+            .copied()
+            .collect()
+    }
+
+    /// Returns the shards whose sub-blocks have to be executed locally: the unavailable shards,
+    /// and the shards exchanging cross-shard messages with them, transitively. Otherwise, the
+    /// latter would wait for messages that are never sent, or send messages to shards that are
+    /// not executing the block.
+    fn shards_to_execute_locally(
+        &self,
+        sub_blocks: &[SubBlocksForShard<AnalyzedTransaction>],
+    ) -> BTreeSet<ShardId> {
+        let mut local_shards = {
+            let mut unavailable_shards = self.unavailable_shards.lock().unwrap();
+            if !unavailable_shards.is_empty() {
+                // Shards are available again once they answer, and are not stuck on an earlier
+                // block.
+                let shard_ids: Vec<_> = unavailable_shards.iter().copied().collect();
+                let health = self
+                    .health_checker
+                    .check(&shard_ids, self.config.health_check_timeout);
+                for (shard_id, shard_health) in health {
+                    if shard_health == ShardHealth::Idle {
+                        info!("Remote shard {} is available again", shard_id);
+                        unavailable_shards.remove(&shard_id);
+                    } else {
+                        REMOTE_EXECUTOR_FAULT_COUNT
+                            .with_label_values(&[&shard_id.to_string(), "unavailable"])
+                            .inc();
+                    }
+                }
+            }
+            unavailable_shards.clone()
+        };
+        if local_shards.is_empty() {
+            return local_shards;
+        }
+
+        let dependencies = cross_shard_dependencies(sub_blocks);
+        loop {
+            let num_local_shards = local_shards.len();
+            for (shard_id, required_shard_id) in dependencies.iter() {
+                if local_shards.contains(shard_id) || local_shards.contains(required_shard_id) {
+                    local_shards.insert(*shard_id);
+                    local_shards.insert(*required_shard_id);
+                }
+            }
+            if local_shards.len() == num_local_shards {
+                return local_shards;
+            }
+        }
+    }
+
+    /// Aborts the block on the given shards, so that they stop waiting for cross-shard messages
+    /// that will never be sent, and are available for the next blocks.
+    fn abort_block(&self, block_id: u64, num_rounds: usize, shard_ids: &BTreeSet<ShardId>) {
+        let message = bcs::to_bytes(&RemoteCrossShardMsg {
+            block_id,
+            msg: CrossShardMsg::AbortMsg,
+        })
+        .unwrap();
+        for shard_id in shard_ids {
+            for round in 0..num_rounds {
+                self.cross_shard_txs[*shard_id][round]
+                    .lock()
+                    .unwrap()
+                    .send(Message::new(message.clone()))
+                    .unwrap();
+            }
+        }
+    }
+
+    /// Waits for the results of the block from the shards that are not executed locally. Shards
+    /// that stop answering health checks, or do not return their results before the execution
+    /// timeout, are marked unavailable and have no results. Shards waiting for cross-shard
+    /// messages from unavailable shards are abandoned as soon as those are detected, and have no
+    /// results either. The block is aborted on the shards that have no results.
+    fn get_output_from_shards(
+        &self,
+        block_id: u64,
+        sub_blocks: &[SubBlocksForShard<AnalyzedTransaction>],
+        local_shards: &BTreeSet<ShardId>,
+    ) -> Result<Vec<Option<Vec<Vec<TransactionOutput>>>>, VMStatus> {
         trace!("RemoteExecutorClient Waiting for results");
-        let mut results = vec![];
-        for rx in self.result_rxs.iter() {
-            let received_bytes = rx.recv().unwrap().to_bytes();
+        let num_rounds = sub_blocks
+            .first()
+            .map_or(0, |sub_blocks| sub_blocks.num_sub_blocks());
+        let dependencies = cross_shard_dependencies(sub_blocks);
+        let deadline = Instant::now() + self.config.execution_timeout;
+        let mut next_health_check = Instant::now() + self.config.health_check_interval;
+        let mut results = vec![None; self.command_txs.len()];
+        let mut pending_shards: Vec<ShardId> = (0..self.command_txs.len())
+            .filter(|shard_id| !local_shards.contains(shard_id))
+            .collect();
+        while !pending_shards.is_empty() {
+            let mut select = Select::new();
+            for shard_id in pending_shards.iter() {
+                select.recv(&self.result_rxs[*shard_id]);
+            }
+            let Ok(operation) = select.select_deadline(deadline.min(next_health_check)) else {
+                if Instant::now() >= deadline {
+                    for shard_id in pending_shards.iter() {
+                        warn!(
+                            "Remote shard {} did not return results for block {} in time",
+                            shard_id, block_id
+                        );
+                        REMOTE_EXECUTOR_FAULT_COUNT
+                            .with_label_values(&[&shard_id.to_string(), "timeout"])
+                            .inc();
+                    }
+                    let timed_out_shards: BTreeSet<_> = pending_shards.iter().copied().collect();
+                    self.unavailable_shards
+                        .lock()
+                        .unwrap()
+                        .extend(timed_out_shards.iter().copied());
+                    self.abort_block(block_id, num_rounds, &timed_out_shards);
+                    break;
+                }
+
+                let health = self
+                    .health_checker
+                    .check(&pending_shards, self.config.health_check_timeout);
+                next_health_check = Instant::now() + self.config.health_check_interval;
+                let unresponsive_shards: BTreeSet<_> = pending_shards
+                    .iter()
+                    .copied()
+                    .filter(|shard_id| health[shard_id] == ShardHealth::Unresponsive)
+                    .collect();
+                if unresponsive_shards.is_empty() {
+                    continue;
+                }
+                for shard_id in unresponsive_shards.iter() {
+                    warn!(
+                        "Remote shard {} is unresponsive while executing block {}",
+                        shard_id, block_id
+                    );
+                    REMOTE_EXECUTOR_FAULT_COUNT
+                        .with_label_values(&[&shard_id.to_string(), "unresponsive"])
+                        .inc();
+                }
+                self.unavailable_shards
+                    .lock()
+                    .unwrap()
+                    .extend(unresponsive_shards.iter().copied());
+
+                // The shards waiting for cross-shard messages from the unresponsive shards would
+                // wait until the execution timeout, so their sub-blocks are executed locally too.
+                let mut aborted_shards = dependent_shards(&dependencies, &unresponsive_shards);
+                aborted_shards.retain(|shard_id| pending_shards.contains(shard_id));
+                for shard_id in aborted_shards.difference(&unresponsive_shards) {
+                    info!(
+                        "Abandoning block {} on remote shard {}, it depends on unresponsive shards",
+                        block_id, shard_id
+                    );
+                    REMOTE_EXECUTOR_FAULT_COUNT
+                        .with_label_values(&[&shard_id.to_string(), "abandoned"])
+                        .inc();
+                }
+                pending_shards.retain(|shard_id| !aborted_shards.contains(shard_id));
+                self.abort_block(block_id, num_rounds, &aborted_shards);
+                continue;
+            };
+
+            let index = operation.index();
+            let shard_id = pending_shards[index];
+            let received_bytes = operation
+                .recv(&self.result_rxs[shard_id])
+                .unwrap()
+                .to_bytes();
             let result: RemoteExecutionResult = bcs::from_bytes(&received_bytes).unwrap();
-            results.push(result.inner?);
+            if result.block_id != block_id {
+                trace!(
+                    "Ignoring late results of block {} from remote shard {}",
+                    result.block_id,
+                    shard_id
+                );
+                continue;
+            }
+            results[shard_id] = Some(result.inner?);
+            pending_shards.remove(index);
         }
         Ok(results)
     }
 }
 
+/// Returns the pairs of shards where the first one requires cross-shard messages from the second
+/// one to execute the block.
+fn cross_shard_dependencies(
+    sub_blocks: &[SubBlocksForShard<AnalyzedTransaction>],
+) -> BTreeSet<(ShardId, ShardId)> {
+    let mut dependencies = BTreeSet::new();
+    for (shard_id, shard_sub_blocks) in sub_blocks.iter().enumerate() {
+        for txn in shard_sub_blocks.iter() {
+            for (required_txn_idx, _) in txn.cross_shard_dependencies().required_edges_iter() {
+                if required_txn_idx.shard_id != shard_id
+                    && required_txn_idx.shard_id < sub_blocks.len()
+                {
+                    dependencies.insert((shard_id, required_txn_idx.shard_id));
+                }
+            }
+        }
+    }
+    dependencies
+}
+
+/// Returns the given shards, and the shards that require cross-shard messages from them,
+/// transitively.
+fn dependent_shards(
+    dependencies: &BTreeSet<(ShardId, ShardId)>,
+    shard_ids: &BTreeSet<ShardId>,
+) -> BTreeSet<ShardId> {
+    let mut dependent_shards = shard_ids.clone();
+    loop {
+        let num_dependent_shards = dependent_shards.len();
+        for (shard_id, required_shard_id) in dependencies.iter() {
+            if dependent_shards.contains(required_shard_id) {
+                dependent_shards.insert(*shard_id);
+            }
+        }
+        if dependent_shards.len() == num_dependent_shards {
+            return dependent_shards;
+        }
+    }
+}
+
 impl<S: StateView + Sync + Send + 'static> ExecutorClient<S> for RemoteExecutorClient<S> {
     fn num_shards(&self) -> usize {
         self.command_txs.len()
@@ -186,15 +507,23 @@ impl<S: StateView + Sync + Send + 'static> ExecutorClient<S> for RemoteExecutorC
         onchain_config: BlockExecutorConfigFromOnchain,
     ) -> Result<ShardedExecutionOutput, VMStatus> {
         trace!("RemoteExecutorClient Sending block to shards");
-        self.state_view_service.set_state_view(state_view);
+        let block_id = self.next_block_id.fetch_add(1, Ordering::Relaxed);
+        self.state_view_service
+            .set_state_view(block_id, state_view.clone());
         let (sub_blocks, global_txns) = transactions.into();
         if !global_txns.is_empty() {
             panic!("Global transactions are not supported yet");
         }
-        for (shard_id, sub_blocks) in sub_blocks.into_iter().enumerate() {
+        let local_shards = self.shards_to_execute_locally(&sub_blocks);
+        for (shard_id, shard_sub_blocks) in sub_blocks.iter().enumerate() {
+            if local_shards.contains(&shard_id) {
+                continue;
+            }
             let senders = self.command_txs.clone();
+            // The sub-blocks are kept, to execute them locally if the shard fails.
             let execution_request = RemoteExecutionRequest::ExecuteBlock(ExecuteBlockCommand {
-                sub_blocks,
+                block_id,
+                sub_blocks: shard_sub_blocks.clone(),
                 concurrency_level: concurrency_level_per_shard,
                 onchain_config: onchain_config.clone(),
             });
@@ -206,9 +535,28 @@ impl<S: StateView + Sync + Send + 'static> ExecutorClient<S> for RemoteExecutorC
                 .unwrap();
         }
 
-        let execution_results = self.get_output_from_shards()?;
+        let remote_outputs = self.get_output_from_shards(block_id, &sub_blocks, &local_shards);
 
         self.state_view_service.drop_state_view();
+        let remote_outputs = remote_outputs?;
+        if remote_outputs.iter().all(Option::is_some) && !self.config.verify_outputs {
+            let execution_results = remote_outputs.into_iter().flatten().collect();
+            return Ok(ShardedExecutionOutput::new(execution_results, vec![]));
+        }
+
+        let execution_results = execute_sub_blocks_locally(
+            &self.thread_pool,
+            state_view.as_ref(),
+            sub_blocks,
+            remote_outputs,
+            BlockExecutorConfig {
+                local: BlockExecutorLocalConfig::default_with_concurrency_level(
+                    concurrency_level_per_shard,
+                ),
+                onchain: onchain_config,
+            },
+            self.config.verify_outputs,
+        )?;
         Ok(ShardedExecutionOutput::new(execution_results, vec![]))
     }
 
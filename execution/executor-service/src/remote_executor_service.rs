@@ -3,12 +3,17 @@
 
 use crate::{
     remote_cordinator_client::RemoteCoordinatorClient,
-    remote_cross_shard_client::RemoteCrossShardClient, remote_state_view::RemoteStateViewClient,
+    remote_cross_shard_client::RemoteCrossShardClient, remote_health_check::HealthCheckService,
+    remote_state_view::RemoteStateViewClient,
 };
 use aptos_secure_net::network_controller::NetworkController;
 use aptos_types::block_executor::partitioner::ShardId;
 use aptos_vm::sharded_block_executor::sharded_executor_service::ShardedExecutorService;
-use std::{net::SocketAddr, sync::Arc, thread};
+use std::{
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+    thread,
+};
 
 /// A service that provides support for remote execution. Essentially, it reads a request from
 /// the remote executor client and executes the block locally and returns the result.
@@ -16,6 +21,7 @@ pub struct ExecutorService {
     shard_id: ShardId,
     controller: NetworkController,
     executor_service: Arc<ShardedExecutorService<RemoteStateViewClient>>,
+    health_check_service: Arc<HealthCheckService>,
 }
 
 impl ExecutorService {
@@ -29,14 +35,23 @@ impl ExecutorService {
     ) -> Self {
         let service_name = format!("executor_service-{}", shard_id);
         let mut controller = NetworkController::new(service_name, self_address, 5000);
+        let executing_block_id = Arc::new(Mutex::new(None));
         let coordinator_client = Arc::new(RemoteCoordinatorClient::new(
             shard_id,
             &mut controller,
             coordinator_address,
+            executing_block_id.clone(),
+        ));
+        let health_check_service = Arc::new(HealthCheckService::new(
+            shard_id,
+            &mut controller,
+            coordinator_address,
+            executing_block_id.clone(),
         ));
         let cross_shard_client = Arc::new(RemoteCrossShardClient::new(
             &mut controller,
             remote_shard_addresses,
+            executing_block_id,
         ));
 
         let executor_service = Arc::new(ShardedExecutorService::new(
@@ -51,6 +66,7 @@ impl ExecutorService {
             shard_id,
             controller,
             executor_service,
+            health_check_service,
         }
     }
 
@@ -64,6 +80,14 @@ impl ExecutorService {
                 executor_service_clone.start();
             })
             .expect("Failed to spawn thread");
+
+        let health_check_service_clone = self.health_check_service.clone();
+        thread::Builder::new()
+            .name(format!("HealthCheckService-{}", self.shard_id))
+            .spawn(move || {
+                health_check_service_clone.start();
+            })
+            .expect("Failed to spawn thread");
     }
 
     pub fn shutdown(&mut self) {
@@ -0,0 +1,148 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{metrics::REMOTE_EXECUTOR_TIMER, HealthCheckRequest, HealthCheckResponse};
+use aptos_logger::trace;
+use aptos_secure_net::network_controller::{Message, NetworkController};
+use aptos_types::block_executor::partitioner::ShardId;
+use crossbeam_channel::{Receiver, Sender};
+use std::{
+    collections::HashMap,
+    net::SocketAddr,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+fn health_check_request_type(shard_id: ShardId) -> String {
+    format!("health_check_request_{}", shard_id)
+}
+
+const HEALTH_CHECK_RESPONSE_TYPE: &str = "health_check_response";
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ShardHealth {
+    /// The shard answered, and is not executing any block.
+    Idle,
+    /// The shard answered while executing the block with the given id.
+    Executing(u64),
+    /// The shard did not answer in time.
+    Unresponsive,
+}
+
+/// Answers the health checks of the coordinator on a shard. It runs on its own thread, so that a
+/// shard busy executing a block still answers.
+pub struct HealthCheckService {
+    shard_id: ShardId,
+    request_rx: Receiver<Message>,
+    response_tx: Sender<Message>,
+    executing_block_id: Arc<Mutex<Option<u64>>>,
+}
+
+impl HealthCheckService {
+    pub fn new(
+        shard_id: ShardId,
+        controller: &mut NetworkController,
+        coordinator_address: SocketAddr,
+        executing_block_id: Arc<Mutex<Option<u64>>>,
+    ) -> Self {
+        let request_rx = controller.create_inbound_channel(health_check_request_type(shard_id));
+        let response_tx = controller
+            .create_outbound_channel(coordinator_address, HEALTH_CHECK_RESPONSE_TYPE.to_string());
+        Self {
+            shard_id,
+            request_rx,
+            response_tx,
+            executing_block_id,
+        }
+    }
+
+    pub fn start(&self) {
+        while let Ok(message) = self.request_rx.recv() {
+            let request: HealthCheckRequest = bcs::from_bytes(&message.data).unwrap();
+            let response = HealthCheckResponse {
+                shard_id: self.shard_id,
+                nonce: request.nonce,
+                executing_block_id: *self.executing_block_id.lock().unwrap(),
+            };
+            self.response_tx
+                .send(Message::new(bcs::to_bytes(&response).unwrap()))
+                .unwrap();
+        }
+    }
+}
+
+/// Health checks the remote shards from the coordinator.
+pub struct ShardHealthChecker {
+    request_txs: Vec<Sender<Message>>,
+    // Only one health check can wait for responses at a time, otherwise they would consume each
+    // other's responses.
+    response_rx: Mutex<Receiver<Message>>,
+    next_nonce: AtomicU64,
+}
+
+impl ShardHealthChecker {
+    pub fn new(controller: &mut NetworkController, remote_shard_addresses: &[SocketAddr]) -> Self {
+        let request_txs = remote_shard_addresses
+            .iter()
+            .enumerate()
+            .map(|(shard_id, address)| {
+                controller.create_outbound_channel(*address, health_check_request_type(shard_id))
+            })
+            .collect();
+        let response_rx = controller.create_inbound_channel(HEALTH_CHECK_RESPONSE_TYPE.to_string());
+        Self {
+            request_txs,
+            response_rx: Mutex::new(response_rx),
+            next_nonce: AtomicU64::new(0),
+        }
+    }
+
+    /// Health checks the given shards, waiting at most `timeout` for them to answer.
+    pub fn check(&self, shard_ids: &[ShardId], timeout: Duration) -> HashMap<ShardId, ShardHealth> {
+        let _timer = REMOTE_EXECUTOR_TIMER
+            .with_label_values(&["coordinator", "health_check"])
+            .start_timer();
+        let response_rx = self.response_rx.lock().unwrap();
+        let deadline = Instant::now() + timeout;
+        let nonce = self.next_nonce.fetch_add(1, Ordering::Relaxed);
+        let request = bcs::to_bytes(&HealthCheckRequest { nonce }).unwrap();
+        for shard_id in shard_ids {
+            self.request_txs[*shard_id]
+                .send(Message::new(request.clone()))
+                .unwrap();
+        }
+
+        let mut health: HashMap<_, _> = shard_ids
+            .iter()
+            .map(|shard_id| (*shard_id, ShardHealth::Unresponsive))
+            .collect();
+        let mut num_pending = health.len();
+        while num_pending > 0 {
+            let Ok(message) = response_rx.recv_deadline(deadline) else {
+                break;
+            };
+            let response: HealthCheckResponse = bcs::from_bytes(&message.data).unwrap();
+            // Answers to earlier health checks that arrived too late are ignored.
+            if response.nonce != nonce {
+                trace!(
+                    "Ignoring stale health check response from shard {}",
+                    response.shard_id
+                );
+                continue;
+            }
+            if let Some(shard_health @ ShardHealth::Unresponsive) =
+                health.get_mut(&response.shard_id)
+            {
+                *shard_health = match response.executing_block_id {
+                    Some(block_id) => ShardHealth::Executing(block_id),
+                    None => ShardHealth::Idle,
+                };
+                num_pending -= 1;
+            }
+        }
+        health
+    }
+}
@@ -13,7 +13,7 @@ use std::{
 
 extern crate itertools;
 use crate::metrics::{REMOTE_EXECUTOR_REMOTE_KV_COUNT, REMOTE_EXECUTOR_TIMER};
-use aptos_logger::trace;
+use aptos_logger::{trace, warn};
 use aptos_types::{
     block_executor::partitioner::ShardId,
     state_store::{
@@ -27,12 +27,15 @@ use rayon::ThreadPool;
 pub static REMOTE_STATE_KEY_BATCH_SIZE: usize = 200;
 
 pub struct RemoteStateView {
+    // The id of the block the state values are read by.
+    block_id: u64,
     state_values: DashMap<StateKey, RemoteStateValue>,
 }
 
 impl RemoteStateView {
-    pub fn new() -> Self {
+    pub fn new(block_id: u64) -> Self {
         Self {
+            block_id,
             state_values: DashMap::new(),
         }
     }
@@ -61,10 +64,18 @@ impl RemoteStateView {
             // case we explicitly drop the value to relinquish the read lock on the value. Cloning the
             // value should be in expensive as this is just cloning the underlying Arc.
             drop(value);
-            return Ok(value_clone.get_value());
+            return value_clone.get_value();
         }
         Ok(None)
     }
+
+    /// Stops waiting for the state values that have not been received yet, so that reads of them
+    /// fail instead of blocking forever.
+    pub fn abort(&self) {
+        for state_value in self.state_values.iter() {
+            state_value.abort();
+        }
+    }
 }
 
 pub struct RemoteStateViewClient {
@@ -93,7 +104,8 @@ impl RemoteStateViewClient {
         let result_rx = controller.create_inbound_channel(kv_response_type.to_string());
         let command_tx =
             controller.create_outbound_channel(coordinator_address, kv_request_type.to_string());
-        let state_view = Arc::new(RwLock::new(RemoteStateView::new()));
+        // Replaced with the state view of every block before it is executed.
+        let state_view = Arc::new(RwLock::new(RemoteStateView::new(0)));
         let state_value_receiver = RemoteStateValueReceiver::new(
             shard_id,
             state_view.clone(),
@@ -115,12 +127,12 @@ impl RemoteStateViewClient {
         }
     }
 
-    pub fn init_for_block(&self, state_keys: Vec<StateKey>) {
-        *self.state_view.write().unwrap() = RemoteStateView::new();
+    pub fn init_for_block(&self, block_id: u64, state_keys: Vec<StateKey>) {
+        *self.state_view.write().unwrap() = RemoteStateView::new(block_id);
         REMOTE_EXECUTOR_REMOTE_KV_COUNT
             .with_label_values(&[&self.shard_id.to_string(), "prefetch_kv"])
             .inc_by(state_keys.len() as u64);
-        self.pre_fetch_state_values(state_keys, false);
+        self.pre_fetch_state_values(block_id, state_keys, false);
     }
 
     fn insert_keys_and_fetch_values(
@@ -128,6 +140,7 @@ impl RemoteStateViewClient {
         thread_pool: Arc<ThreadPool>,
         kv_tx: Arc<Sender<Message>>,
         shard_id: ShardId,
+        block_id: u64,
         state_keys: Vec<StateKey>,
     ) {
         state_keys.clone().into_iter().for_each(|state_key| {
@@ -139,12 +152,17 @@ impl RemoteStateViewClient {
             .for_each(|state_keys| {
                 let sender = kv_tx.clone();
                 thread_pool.spawn(move || {
-                    Self::send_state_value_request(shard_id, sender, state_keys);
+                    Self::send_state_value_request(shard_id, block_id, sender, state_keys);
                 });
             });
     }
 
-    fn pre_fetch_state_values(&self, state_keys: Vec<StateKey>, sync_insert_keys: bool) {
+    fn pre_fetch_state_values(
+        &self,
+        block_id: u64,
+        state_keys: Vec<StateKey>,
+        sync_insert_keys: bool,
+    ) {
         let state_view_clone = self.state_view.clone();
         let thread_pool_clone = self.thread_pool.clone();
         let kv_tx_clone = self.kv_tx.clone();
@@ -156,6 +174,7 @@ impl RemoteStateViewClient {
                 thread_pool_clone,
                 kv_tx_clone,
                 shard_id,
+                block_id,
                 state_keys,
             );
         };
@@ -171,10 +190,11 @@ impl RemoteStateViewClient {
 
     fn send_state_value_request(
         shard_id: ShardId,
+        block_id: u64,
         sender: Arc<Sender<Message>>,
         state_keys: Vec<StateKey>,
     ) {
-        let request = RemoteKVRequest::new(shard_id, state_keys);
+        let request = RemoteKVRequest::new(shard_id, block_id, state_keys);
         let request_message = bcs::to_bytes(&request).unwrap();
         sender.send(Message::new(request_message)).unwrap();
     }
@@ -199,7 +219,7 @@ impl TStateView for RemoteStateViewClient {
         REMOTE_EXECUTOR_REMOTE_KV_COUNT
             .with_label_values(&[&self.shard_id.to_string(), "non_prefetch_kv"])
             .inc();
-        self.pre_fetch_state_values(vec![state_key.clone()], true);
+        self.pre_fetch_state_values(state_view_reader.block_id, vec![state_key.clone()], true);
         state_view_reader.get_state_value(state_key)
     }
 
@@ -258,13 +278,29 @@ impl RemoteStateValueReceiver {
             .with_label_values(&[&shard_id.to_string(), "kv_responses"])
             .inc();
         let state_view_lock = state_view.read().unwrap();
+        if response.block_id != state_view_lock.block_id {
+            trace!(
+                "Ignoring state values of block {} for shard {}, executing block {}",
+                response.block_id,
+                shard_id,
+                state_view_lock.block_id
+            );
+            return;
+        }
+        let Some(state_values) = response.inner else {
+            warn!(
+                "Coordinator is not executing block {} anymore, aborting it on shard {}",
+                response.block_id, shard_id
+            );
+            state_view_lock.abort();
+            return;
+        };
         trace!(
             "Received state values for shard {} with size {}",
             shard_id,
-            response.inner.len()
+            state_values.len()
         );
-        response
-            .inner
+        state_values
             .into_iter()
             .for_each(|(state_key, state_value)| {
                 state_view_lock.set_state_value(&state_key, state_value);
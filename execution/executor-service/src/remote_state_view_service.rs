@@ -10,7 +10,7 @@ use std::{
 
 extern crate itertools;
 use crate::metrics::REMOTE_EXECUTOR_TIMER;
-use aptos_logger::trace;
+use aptos_logger::{trace, warn};
 use aptos_types::state_store::{StateView, TStateView};
 use itertools::Itertools;
 
@@ -18,7 +18,8 @@ pub struct RemoteStateViewService<S: StateView + Sync + Send + 'static> {
     kv_rx: Receiver<Message>,
     kv_tx: Arc<Vec<Sender<Message>>>,
     thread_pool: Arc<rayon::ThreadPool>,
-    state_view: Arc<RwLock<Option<Arc<S>>>>,
+    // The state view of the block being executed, with its id.
+    state_view: Arc<RwLock<Option<(u64, Arc<S>)>>>,
 }
 
 impl<S: StateView + Sync + Send + 'static> RemoteStateViewService<S> {
@@ -51,9 +52,9 @@ impl<S: StateView + Sync + Send + 'static> RemoteStateViewService<S> {
         }
     }
 
-    pub fn set_state_view(&self, state_view: Arc<S>) {
+    pub fn set_state_view(&self, block_id: u64, state_view: Arc<S>) {
         let mut state_view_lock = self.state_view.write().unwrap();
-        *state_view_lock = Some(state_view);
+        *state_view_lock = Some((block_id, state_view));
     }
 
     pub fn drop_state_view(&self) {
@@ -73,7 +74,7 @@ impl<S: StateView + Sync + Send + 'static> RemoteStateViewService<S> {
 
     pub fn handle_message(
         message: Message,
-        state_view: Arc<RwLock<Option<Arc<S>>>>,
+        state_view: Arc<RwLock<Option<(u64, Arc<S>)>>>,
         kv_tx: Arc<Vec<Sender<Message>>>,
     ) {
         // we don't know the shard id until we deserialize the message, so lets default it to 0
@@ -86,27 +87,35 @@ impl<S: StateView + Sync + Send + 'static> RemoteStateViewService<S> {
         let req: RemoteKVRequest = bcs::from_bytes(&message.data).unwrap();
         drop(bcs_deser_timer);
 
-        let (shard_id, state_keys) = req.into();
+        let (shard_id, block_id, state_keys) = req.into();
         trace!(
             "remote state view service - received request for shard {} with {} keys",
             shard_id,
             state_keys.len()
         );
-        let resp = state_keys
-            .into_iter()
-            .map(|state_key| {
-                let state_value = state_view
-                    .read()
-                    .unwrap()
-                    .as_ref()
-                    .unwrap()
-                    .get_state_value(&state_key)
-                    .unwrap();
-                (state_key, state_value)
-            })
-            .collect_vec();
-        let len = resp.len();
-        let resp = RemoteKVResponse::new(resp);
+        let state_view = state_view.read().unwrap();
+        let resp = match state_view.as_ref() {
+            Some((executing_block_id, state_view)) if *executing_block_id == block_id => {
+                let resp = state_keys
+                    .into_iter()
+                    .map(|state_key| {
+                        let state_value = state_view.get_state_value(&state_key).unwrap();
+                        (state_key, state_value)
+                    })
+                    .collect_vec();
+                RemoteKVResponse::new(block_id, resp)
+            },
+            _ => {
+                // The coordinator is not executing the block anymore, e.g., this is a late request
+                // of a shard whose sub-blocks were executed locally. The shard aborts the block.
+                warn!(
+                    "remote state view service - aborting request for shard {}, block {} is not being executed",
+                    shard_id, block_id
+                );
+                RemoteKVResponse::aborted(block_id)
+            },
+        };
+        let len = resp.inner.as_ref().map_or(0, Vec::len);
         let bcs_ser_timer = REMOTE_EXECUTOR_TIMER
             .with_label_values(&["0", "kv_resp_ser"])
             .start_timer();
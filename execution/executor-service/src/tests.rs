@@ -2,14 +2,18 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    remote_executor_client::RemoteExecutorClient, test_utils,
+    remote_executor_client::{FaultToleranceConfig, RemoteExecutorClient},
+    test_utils,
     thread_executor_service::ThreadExecutorService,
 };
 use aptos_config::utils;
 use aptos_language_e2e_tests::data_store::FakeDataStore;
 use aptos_secure_net::network_controller::NetworkController;
 use aptos_vm::sharded_block_executor::ShardedBlockExecutor;
-use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::{
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    time::Duration,
+};
 
 pub fn create_thread_remote_executor_shards(
     num_shards: usize,
@@ -17,6 +21,21 @@ pub fn create_thread_remote_executor_shards(
 ) -> (
     RemoteExecutorClient<FakeDataStore>,
     Vec<ThreadExecutorService>,
+) {
+    create_thread_remote_executor_shards_with_config(
+        num_shards,
+        num_threads,
+        FaultToleranceConfig::default(),
+    )
+}
+
+pub fn create_thread_remote_executor_shards_with_config(
+    num_shards: usize,
+    num_threads: Option<usize>,
+    config: FaultToleranceConfig,
+) -> (
+    RemoteExecutorClient<FakeDataStore>,
+    Vec<ThreadExecutorService>,
 ) {
     // First create the coordinator.
     let listen_port = utils::get_available_port();
@@ -49,7 +68,7 @@ pub fn create_thread_remote_executor_shards(
         .collect::<Vec<_>>();
 
     let remote_executor_client =
-        RemoteExecutorClient::new(remote_shard_addresses, controller, None);
+        RemoteExecutorClient::new_with_config(remote_shard_addresses, controller, None, config);
     (remote_executor_client, remote_executor_services)
 }
 
@@ -92,3 +111,29 @@ fn test_sharded_block_executor_with_conflict() {
         executor_service.shutdown();
     });
 }
+
+#[test]
+fn test_sharded_block_executor_with_unresponsive_shard() {
+    let num_shards = 4;
+    let (executor_client, mut executor_services) = create_thread_remote_executor_shards_with_config(
+        num_shards,
+        Some(2),
+        FaultToleranceConfig {
+            execution_timeout: Duration::from_secs(60),
+            health_check_interval: Duration::from_millis(100),
+            health_check_timeout: Duration::from_millis(500),
+            verify_outputs: true,
+        },
+    );
+    assert!(executor_client.wait_for_shards(Duration::from_secs(10)));
+
+    // The sub-blocks of the shard are executed locally once it stops answering health checks, and
+    // the outputs of the other shards are verified against local execution.
+    executor_services[1].shutdown();
+    let sharded_block_executor = ShardedBlockExecutor::new(executor_client);
+    test_utils::test_sharded_block_executor_no_conflict(sharded_block_executor);
+
+    executor_services.iter_mut().for_each(|executor_service| {
+        executor_service.shutdown();
+    });
+}
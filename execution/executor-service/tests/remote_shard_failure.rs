@@ -0,0 +1,328 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Runs the shards in separate processes, and kills one of them while a block is executed.
+
+use aptos_block_executor::txn_provider::default::DefaultTxnProvider;
+use aptos_block_partitioner::{v2::config::PartitionerV2Config, PartitionerConfig};
+use aptos_config::utils;
+use aptos_executor_service::remote_executor_client::{FaultToleranceConfig, RemoteExecutorClient};
+use aptos_language_e2e_tests::{
+    common_transactions::peer_to_peer_txn, data_store::FakeDataStore, executor::FakeExecutor,
+};
+use aptos_secure_net::network_controller::NetworkController;
+use aptos_types::{
+    block_executor::{
+        config::BlockExecutorConfigFromOnchain, partitioner::PartitionedTransactions,
+    },
+    state_store::{
+        errors::StateViewError,
+        state_key::{inner::StateKeyInner, StateKey},
+        state_storage_usage::StateStorageUsage,
+        state_value::StateValue,
+        TStateView,
+    },
+    transaction::{
+        analyzed_transaction::AnalyzedTransaction,
+        signature_verified_transaction::SignatureVerifiedTransaction, Transaction,
+        TransactionOutput,
+    },
+};
+use aptos_vm::{
+    aptos_vm::AptosVMBlockExecutor, sharded_block_executor::executor_client::ExecutorClient,
+    VMBlockExecutor,
+};
+use std::{
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    process::{Child, Command},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+const NUM_SHARDS: usize = 4;
+const NUM_TXNS: usize = 400;
+// Number of accounts sending to each other in blocks with conflicts.
+const NUM_ACCOUNTS: usize = 40;
+const EXECUTION_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Shard processes, killed when dropped.
+struct ShardProcesses(Vec<Child>);
+
+impl Drop for ShardProcesses {
+    fn drop(&mut self) {
+        for process in self.0.iter_mut() {
+            process.kill().ok();
+            process.wait().ok();
+        }
+    }
+}
+
+/// Kills a shard process on the first read of the coordinator, i.e., once the shards received
+/// the block and started fetching its state.
+struct KillingStateView {
+    data_store: FakeDataStore,
+    process_to_kill: Mutex<Option<Child>>,
+}
+
+impl TStateView for KillingStateView {
+    type Key = StateKey;
+
+    fn get_state_value(&self, state_key: &StateKey) -> Result<Option<StateValue>, StateViewError> {
+        if let Some(mut process) = self.process_to_kill.lock().unwrap().take() {
+            process.kill().unwrap();
+            process.wait().unwrap();
+        }
+        self.data_store.get_state_value(state_key)
+    }
+
+    fn get_usage(&self) -> Result<StateStorageUsage, StateViewError> {
+        self.data_store.get_usage()
+    }
+}
+
+fn local_address() -> SocketAddr {
+    SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), utils::get_available_port())
+}
+
+fn spawn_shard(
+    shard_id: usize,
+    coordinator_address: SocketAddr,
+    shard_addresses: &[SocketAddr],
+) -> Child {
+    Command::new(env!("CARGO_BIN_EXE_aptos-executor-service"))
+        .arg("--shard-id")
+        .arg(shard_id.to_string())
+        .arg("--num-shards")
+        .arg(shard_addresses.len().to_string())
+        .arg("--num-executor-threads")
+        .arg("2")
+        .arg("--coordinator-address")
+        .arg(coordinator_address.to_string())
+        .arg("--remote-executor-addresses")
+        .args(shard_addresses.iter().map(SocketAddr::to_string))
+        .spawn()
+        .expect("Failed to start shard process")
+}
+
+fn generate_non_conflicting_p2p(executor: &mut FakeExecutor) -> AnalyzedTransaction {
+    let sender = executor.create_raw_account_data(3_000_000_000, 0);
+    let receiver = executor.create_raw_account_data(3_000_000_000, 0);
+    executor.add_account_data(&sender);
+    executor.add_account_data(&receiver);
+    Transaction::UserTransaction(peer_to_peer_txn(
+        sender.account(),
+        receiver.account(),
+        sender.sequence_number(),
+        1_000,
+        100,
+    ))
+    .into()
+}
+
+/// Generates transfers between a small set of accounts, so that the partitioner creates
+/// cross-shard dependencies.
+fn generate_conflicting_p2ps(executor: &mut FakeExecutor) -> Vec<AnalyzedTransaction> {
+    let accounts: Vec<_> = (0..NUM_ACCOUNTS)
+        .map(|_| {
+            let account = executor.create_raw_account_data(3_000_000_000, 0);
+            executor.add_account_data(&account);
+            account
+        })
+        .collect();
+    (0..NUM_TXNS)
+        .map(|idx| {
+            let sequence_number = idx / NUM_ACCOUNTS;
+            let sender = &accounts[idx % NUM_ACCOUNTS];
+            let receiver = &accounts[(idx + sequence_number + 1) % NUM_ACCOUNTS];
+            Transaction::UserTransaction(peer_to_peer_txn(
+                sender.account(),
+                receiver.account(),
+                sequence_number as u64,
+                1_000,
+                100,
+            ))
+            .into()
+        })
+        .collect()
+}
+
+fn generate_block(conflicting: bool) -> (FakeExecutor, PartitionedTransactions) {
+    let mut executor = FakeExecutor::from_head_genesis();
+    let transactions = if conflicting {
+        generate_conflicting_p2ps(&mut executor)
+    } else {
+        (0..NUM_TXNS)
+            .map(|_| generate_non_conflicting_p2p(&mut executor))
+            .collect()
+    };
+    let partitioned_txns = PartitionerV2Config::default()
+        .max_partitioning_rounds(2)
+        .cross_shard_dep_avoid_threshold(0.9)
+        .partition_last_round(true)
+        .build()
+        .partition(transactions, NUM_SHARDS);
+    (executor, partitioned_txns)
+}
+
+/// Returns the shards that other shards require cross-shard messages from.
+fn required_shards(partitioned_txns: &PartitionedTransactions) -> Vec<usize> {
+    let mut required_shards: Vec<_> = partitioned_txns
+        .sharded_txns()
+        .iter()
+        .enumerate()
+        .flat_map(|(shard_id, sub_blocks)| {
+            sub_blocks
+                .iter()
+                .flat_map(|txn| txn.cross_shard_dependencies().required_edges_iter())
+                .map(|(required_txn_idx, _)| required_txn_idx.shard_id)
+                .filter(move |required_shard_id| *required_shard_id != shard_id)
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    required_shards.sort();
+    required_shards.dedup();
+    required_shards
+}
+
+fn create_executor_client(
+    coordinator_address: SocketAddr,
+    shard_addresses: Vec<SocketAddr>,
+) -> RemoteExecutorClient<KillingStateView> {
+    let executor_client = RemoteExecutorClient::new_with_config(
+        shard_addresses,
+        NetworkController::new(
+            "remote-executor-coordinator".to_string(),
+            coordinator_address,
+            5000,
+        ),
+        Some(4),
+        FaultToleranceConfig {
+            execution_timeout: EXECUTION_TIMEOUT,
+            health_check_interval: Duration::from_millis(200),
+            health_check_timeout: Duration::from_secs(1),
+            verify_outputs: true,
+        },
+    );
+    assert!(executor_client.wait_for_shards(Duration::from_secs(60)));
+    executor_client
+}
+
+fn execute_and_compare(
+    executor_client: &RemoteExecutorClient<KillingStateView>,
+    executor: FakeExecutor,
+    partitioned_txns: PartitionedTransactions,
+    process_to_kill: Option<Child>,
+) {
+    let txns: Vec<SignatureVerifiedTransaction> =
+        PartitionedTransactions::flatten(partitioned_txns.clone())
+            .into_iter()
+            .map(|t| t.into_txn())
+            .collect();
+
+    let state_view = Arc::new(KillingStateView {
+        data_store: executor.data_store().clone(),
+        process_to_kill: Mutex::new(process_to_kill),
+    });
+    let (sharded_output, _) = executor_client
+        .execute_block(
+            state_view,
+            partitioned_txns,
+            2,
+            BlockExecutorConfigFromOnchain::new_no_block_limit(),
+        )
+        .unwrap()
+        .into_inner();
+    // The outputs of the shards are ordered by round, then by shard.
+    let num_rounds = sharded_output[0].len();
+    let sharded_txn_output: Vec<TransactionOutput> = (0..num_rounds)
+        .flat_map(|round| sharded_output.iter().map(move |outputs| &outputs[round]))
+        .flatten()
+        .cloned()
+        .collect();
+
+    let unsharded_txn_output = AptosVMBlockExecutor::new()
+        .execute_block_no_limit(&DefaultTxnProvider::new(txns), executor.data_store())
+        .unwrap();
+    assert_eq!(unsharded_txn_output.len(), sharded_txn_output.len());
+    for (unsharded, sharded) in unsharded_txn_output.iter().zip(sharded_txn_output.iter()) {
+        assert_eq!(unsharded.status(), sharded.status());
+        assert_eq!(unsharded.gas_used(), sharded.gas_used());
+        assert_eq!(unsharded.events(), sharded.events());
+        // The total supply is tracked differently in sharded execution, so table items are not
+        // compared.
+        let access_path_writes = |output: &TransactionOutput| {
+            output
+                .write_set()
+                .iter()
+                .filter(|(k, _)| matches!(k.inner(), StateKeyInner::AccessPath(_)))
+                .map(|(k, v)| (k.clone(), v.clone()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(access_path_writes(unsharded), access_path_writes(sharded));
+    }
+}
+
+fn spawn_shards(coordinator_address: SocketAddr, shard_addresses: &[SocketAddr]) -> ShardProcesses {
+    ShardProcesses(
+        (0..NUM_SHARDS)
+            .map(|shard_id| spawn_shard(shard_id, coordinator_address, shard_addresses))
+            .collect(),
+    )
+}
+
+#[test]
+fn test_kill_shard_process_mid_block() {
+    let coordinator_address = local_address();
+    let shard_addresses: Vec<_> = (0..NUM_SHARDS).map(|_| local_address()).collect();
+    let mut shard_processes = spawn_shards(coordinator_address, &shard_addresses);
+    let executor_client = create_executor_client(coordinator_address, shard_addresses);
+
+    // The killed shard stops answering health checks, so its sub-blocks are executed locally.
+    let shard_to_kill = 1;
+    let (executor, partitioned_txns) = generate_block(false);
+    execute_and_compare(
+        &executor_client,
+        executor,
+        partitioned_txns,
+        Some(shard_processes.0.remove(shard_to_kill)),
+    );
+    assert_eq!(executor_client.unavailable_shards(), vec![shard_to_kill]);
+
+    // In the next block, the shard is known to be unavailable and is not sent its sub-blocks.
+    let (executor, partitioned_txns) = generate_block(false);
+    execute_and_compare(&executor_client, executor, partitioned_txns, None);
+    assert_eq!(executor_client.unavailable_shards(), vec![shard_to_kill]);
+}
+
+#[test]
+fn test_kill_shard_process_with_cross_shard_dependencies() {
+    let coordinator_address = local_address();
+    let shard_addresses: Vec<_> = (0..NUM_SHARDS).map(|_| local_address()).collect();
+    let mut shard_processes = spawn_shards(coordinator_address, &shard_addresses);
+    let executor_client = create_executor_client(coordinator_address, shard_addresses);
+
+    // Kill a shard that other shards wait for cross-shard messages from. Those shards are
+    // abandoned as soon as the killed shard is detected, instead of waiting until the execution
+    // timeout, and the block is aborted on them.
+    let (executor, partitioned_txns) = generate_block(true);
+    let shard_to_kill = *required_shards(&partitioned_txns)
+        .first()
+        .expect("Block must have cross-shard dependencies");
+    let start_time = Instant::now();
+    execute_and_compare(
+        &executor_client,
+        executor,
+        partitioned_txns,
+        Some(shard_processes.0.remove(shard_to_kill)),
+    );
+    assert!(start_time.elapsed() < EXECUTION_TIMEOUT / 2);
+    assert_eq!(executor_client.unavailable_shards(), vec![shard_to_kill]);
+
+    // The abandoned shards stopped executing the aborted block, so they execute the next block
+    // without timing out.
+    let start_time = Instant::now();
+    let (executor, partitioned_txns) = generate_block(false);
+    execute_and_compare(&executor_client, executor, partitioned_txns, None);
+    assert!(start_time.elapsed() < EXECUTION_TIMEOUT / 2);
+    assert_eq!(executor_client.unavailable_shards(), vec![shard_to_kill]);
+}
@@ -43,9 +43,11 @@ use aptos_types::{
 use aptos_vm::VMBlockExecutor;
 use block_tree::BlockTree;
 use fail::fail_point;
+use state_prefetcher::StatePrefetcher;
 use std::sync::Arc;
 
 pub mod block_tree;
+pub mod state_prefetcher;
 
 pub struct BlockExecutor<V> {
     pub db: DbReaderWriter,
@@ -155,6 +157,7 @@ struct BlockExecutorInner<V> {
     db: DbReaderWriter,
     block_tree: BlockTree,
     block_executor: V,
+    state_prefetcher: Option<Arc<StatePrefetcher>>,
 }
 
 impl<V> BlockExecutorInner<V>
@@ -167,6 +170,7 @@ where
             db,
             block_tree,
             block_executor: V::new(),
+            state_prefetcher: StatePrefetcher::new_if_enabled().map(Arc::new),
         })
     }
 }
@@ -221,13 +225,17 @@ where
                 let state_view = {
                     let _timer = OTHER_TIMERS.timer_with(&["verified_state_view"]);
 
-                    CachedStateView::new(
+                    let state_view = CachedStateView::new(
                         StateViewId::BlockExecution { block_id },
                         Arc::clone(&self.db.reader),
                         parent_output.execution_output.next_version(),
                         parent_output.expect_result_state().current.clone(),
                         Arc::new(AsyncProofFetcher::new(self.db.reader.clone())),
-                    )?
+                    )?;
+                    if let Some(state_prefetcher) = &self.state_prefetcher {
+                        state_prefetcher.prefetch(&transactions, &state_view)?;
+                    }
+                    state_view
                 };
 
                 let execution_output = {
@@ -246,6 +254,13 @@ where
                         TransactionSliceMetadata::block(parent_block_id, block_id),
                     )?
                 };
+                if let Some(state_prefetcher) = &self.state_prefetcher {
+                    let state_prefetcher = state_prefetcher.clone();
+                    let execution_output = execution_output.clone();
+                    THREAD_MANAGER
+                        .get_background_pool()
+                        .spawn(move || state_prefetcher.learn(&execution_output));
+                }
 
                 let _timer = OTHER_TIMERS.timer_with(&["state_checkpoint"]);
 
@@ -0,0 +1,542 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Predicts the state keys a block is going to access and fetches them into the
+//! [CachedStateView] before the block is executed, so that Block-STM workers do not stall on
+//! cold storage reads.
+//!
+//! Keys are predicted from the read and write hints of transactions (the ones used by the block
+//! partitioner), and from key patterns learned per entry function: after a block is executed,
+//! the keys accessed by every successful entry function call are generalized with respect to the
+//! sender and the address arguments of the call, and to their primary fungible stores.
+//!
+//! Writes are known per transaction, but reads are only known for the whole block (the keys in
+//! the state cache). A read is attributed to every call in the block that accesses its address,
+//! and reads that cannot be generalized are not learned. The state cache also contains the keys
+//! prefetched for the block, so a learned read pattern keeps being observed while it is used.
+
+use crate::metrics::{OTHER_TIMERS, STATE_PREFETCH_KEYS};
+use aptos_executor_types::execution_output::ExecutionOutput;
+use aptos_infallible::RwLock;
+use aptos_metrics_core::TimerHelper;
+use aptos_storage_interface::state_store::{
+    state_view::cached_state_view::CachedStateView, NUM_STATE_SHARDS,
+};
+use aptos_types::{
+    access_path::Path,
+    account_address::create_derived_object_address,
+    block_executor::partitioner::ExecutableTransactions,
+    state_store::{
+        errors::StateViewError,
+        state_key::{inner::StateKeyInner, StateKey},
+    },
+    transaction::{
+        analyzed_transaction::{try_get_read_write_hints, StorageLocation},
+        signature_verified_transaction::SignatureVerifiedTransaction,
+        ExecutionStatus, Transaction, TransactionPayload, TransactionStatus,
+    },
+};
+use move_core_types::{
+    account_address::AccountAddress, identifier::Identifier, language_storage::ModuleId,
+};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+/// Maximum number of entry functions for which key patterns are learned.
+const MAX_LEARNED_FUNCTIONS: usize = 10_000;
+/// Maximum number of key patterns tracked per entry function, before rare ones are dropped.
+const MAX_PATTERNS_PER_FUNCTION: usize = 64;
+/// Learned patterns are only used for prediction if they were observed in at least this
+/// fraction of the calls of the entry function.
+const MIN_PATTERN_FREQUENCY: f64 = 0.5;
+
+static STATE_PREFETCHING_ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Enables or disables prefetching of predicted state before execution for block executors
+/// created afterwards.
+pub fn set_state_prefetching_enabled(enabled: bool) {
+    STATE_PREFETCHING_ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+pub fn is_state_prefetching_enabled() -> bool {
+    STATE_PREFETCHING_ENABLED.load(Ordering::Relaxed)
+}
+
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+struct EntryFunctionId {
+    module: ModuleId,
+    function: Identifier,
+}
+
+/// A call of an entry function: the function, the sender and the arguments that are addresses.
+struct EntryFunctionCall {
+    id: EntryFunctionId,
+    sender: AccountAddress,
+    address_args: Vec<Option<AccountAddress>>,
+}
+
+impl EntryFunctionCall {
+    fn from_txn(txn: &Transaction) -> Option<Self> {
+        let Transaction::UserTransaction(signed_txn) = txn else {
+            return None;
+        };
+        let TransactionPayload::EntryFunction(func) = signed_txn.payload() else {
+            return None;
+        };
+        Some(Self {
+            id: EntryFunctionId {
+                module: func.module().clone(),
+                function: func.function().to_owned(),
+            },
+            sender: signed_txn.sender(),
+            address_args: func
+                .args()
+                .iter()
+                .map(|arg| bcs::from_bytes(arg).ok())
+                .collect(),
+        })
+    }
+
+    /// Returns the addresses the call is expected to access, by how they are derived from the
+    /// call: the sender and the address arguments, and their primary stores of APT and of the
+    /// fungible assets passed as arguments.
+    fn addresses(&self) -> HashMap<AccountAddress, CallAddress> {
+        let owners: Vec<_> = std::iter::once(Owner::Sender)
+            .chain((0..self.address_args.len()).map(Owner::Argument))
+            .filter_map(|owner| Some((owner.resolve(self)?, owner)))
+            .collect();
+        let assets: Vec<_> = std::iter::once(FungibleAsset::Apt)
+            .chain((0..self.address_args.len()).map(FungibleAsset::Argument))
+            .filter_map(|asset| Some((asset.resolve(self)?, asset)))
+            .collect();
+
+        let mut addresses: HashMap<_, _> = owners
+            .iter()
+            .map(|(address, owner)| (*address, CallAddress::Account(*owner)))
+            .collect();
+        for (owner_address, owner) in &owners {
+            for (metadata_address, asset) in &assets {
+                addresses
+                    .entry(create_derived_object_address(
+                        *owner_address,
+                        *metadata_address,
+                    ))
+                    .or_insert(CallAddress::PrimaryStore(*owner, *asset));
+            }
+        }
+        addresses
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+enum Owner {
+    Sender,
+    Argument(usize),
+}
+
+impl Owner {
+    fn resolve(self, call: &EntryFunctionCall) -> Option<AccountAddress> {
+        match self {
+            Owner::Sender => Some(call.sender),
+            Owner::Argument(idx) => *call.address_args.get(idx)?,
+        }
+    }
+}
+
+/// A fungible asset, identified by the address of its metadata object.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+enum FungibleAsset {
+    Apt,
+    Argument(usize),
+}
+
+impl FungibleAsset {
+    fn resolve(self, call: &EntryFunctionCall) -> Option<AccountAddress> {
+        match self {
+            FungibleAsset::Apt => Some(AccountAddress::TEN),
+            FungibleAsset::Argument(idx) => *call.address_args.get(idx)?,
+        }
+    }
+}
+
+/// An address derived from a call.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+enum CallAddress {
+    Account(Owner),
+    /// Primary fungible stores are objects at addresses derived from the owner and the metadata
+    /// address of the asset, so they differ for every owner.
+    PrimaryStore(Owner, FungibleAsset),
+}
+
+impl CallAddress {
+    fn resolve(self, call: &EntryFunctionCall) -> Option<AccountAddress> {
+        match self {
+            CallAddress::Account(owner) => owner.resolve(call),
+            CallAddress::PrimaryStore(owner, asset) => Some(create_derived_object_address(
+                owner.resolve(call)?,
+                asset.resolve(call)?,
+            )),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+enum KeyPattern {
+    /// The same key for every call, e.g., an on-chain config or a table item.
+    Fixed(StateKey),
+    /// A resource or a resource group stored at an address derived from the call.
+    At(CallAddress, Path),
+}
+
+impl KeyPattern {
+    fn generalize(key: &StateKey, addresses: &HashMap<AccountAddress, CallAddress>) -> Self {
+        if let StateKeyInner::AccessPath(access_path) = key.inner() {
+            if !access_path.is_code() {
+                if let Some(address) = addresses.get(&access_path.address) {
+                    return KeyPattern::At(*address, access_path.get_path());
+                }
+            }
+        }
+        KeyPattern::Fixed(key.clone())
+    }
+
+    fn instantiate(&self, call: &EntryFunctionCall) -> Option<StateKey> {
+        let (address, path) = match self {
+            KeyPattern::Fixed(key) => return Some(key.clone()),
+            KeyPattern::At(address, path) => (address.resolve(call)?, path),
+        };
+        match path {
+            Path::Resource(struct_tag) => StateKey::resource(&address, struct_tag).ok(),
+            Path::ResourceGroup(struct_tag) => Some(StateKey::resource_group(&address, struct_tag)),
+            Path::Code(module_id) => Some(StateKey::module_id(module_id)),
+        }
+    }
+}
+
+#[derive(Default)]
+struct LearnedPatterns {
+    num_calls: u64,
+    pattern_counts: HashMap<KeyPattern, u64>,
+}
+
+impl LearnedPatterns {
+    /// Minimum number of times a pattern must have been observed to be used for prediction.
+    fn min_count(&self) -> f64 {
+        self.num_calls as f64 * MIN_PATTERN_FREQUENCY
+    }
+
+    fn observe(
+        &mut self,
+        addresses: &HashMap<AccountAddress, CallAddress>,
+        keys: impl Iterator<Item = StateKey>,
+    ) {
+        self.num_calls += 1;
+        let patterns: HashSet<_> = keys
+            .map(|key| KeyPattern::generalize(&key, addresses))
+            .collect();
+        for pattern in patterns {
+            *self.pattern_counts.entry(pattern).or_insert(0) += 1;
+        }
+
+        if self.pattern_counts.len() > MAX_PATTERNS_PER_FUNCTION {
+            let min_count = self.min_count();
+            self.pattern_counts
+                .retain(|_, count| *count as f64 >= min_count);
+        }
+    }
+
+    fn predict(&self, call: &EntryFunctionCall) -> impl Iterator<Item = StateKey> + '_ {
+        let min_count = self.min_count();
+        self.pattern_counts
+            .iter()
+            .filter(move |(_, count)| **count as f64 >= min_count)
+            .filter_map(move |(pattern, _)| pattern.instantiate(call))
+    }
+}
+
+/// Prefetches the state that blocks are predicted to access, and learns from executed blocks.
+#[derive(Default)]
+pub struct StatePrefetcher {
+    learned: RwLock<HashMap<EntryFunctionId, LearnedPatterns>>,
+}
+
+impl StatePrefetcher {
+    /// Returns a prefetcher if state prefetching is enabled.
+    pub fn new_if_enabled() -> Option<Self> {
+        is_state_prefetching_enabled().then(Self::default)
+    }
+
+    /// Predicts the keys the transactions are going to access, and fetches them into the state
+    /// view in parallel.
+    pub fn prefetch(
+        &self,
+        transactions: &ExecutableTransactions,
+        state_view: &CachedStateView,
+    ) -> Result<(), StateViewError> {
+        let _timer = OTHER_TIMERS.timer_with(&["state_prefetch"]);
+
+        let mut keys = HashSet::new();
+        let (mut num_hinted, mut num_learned) = (0, 0);
+        {
+            let learned = self.learned.read();
+            let mut add_prediction = |hints: [&[StorageLocation]; 2], txn: &Transaction| {
+                for hint in hints.into_iter().flatten() {
+                    if let StorageLocation::Specific(key) = hint {
+                        if keys.insert(key.clone()) {
+                            num_hinted += 1;
+                        }
+                    }
+                }
+                if let Some(call) = EntryFunctionCall::from_txn(txn) {
+                    if let Some(patterns) = learned.get(&call.id) {
+                        for key in patterns.predict(&call) {
+                            if keys.insert(key) {
+                                num_learned += 1;
+                            }
+                        }
+                    }
+                }
+            };
+
+            match transactions {
+                ExecutableTransactions::Unsharded(txns) => {
+                    for txn in txns {
+                        let SignatureVerifiedTransaction::Valid(valid_txn) = txn else {
+                            continue;
+                        };
+                        let (read_hints, write_hints) =
+                            try_get_read_write_hints(txn).unwrap_or_default();
+                        add_prediction([&read_hints, &write_hints], valid_txn);
+                    }
+                },
+                ExecutableTransactions::Sharded(partitioned_txns) => {
+                    let analyzed_txns = partitioned_txns
+                        .sharded_txns()
+                        .iter()
+                        .flat_map(|sub_blocks| sub_blocks.iter())
+                        .chain(partitioned_txns.global_txns.iter())
+                        .map(|txn_with_deps| txn_with_deps.txn());
+                    for analyzed_txn in analyzed_txns {
+                        let SignatureVerifiedTransaction::Valid(valid_txn) =
+                            analyzed_txn.transaction()
+                        else {
+                            continue;
+                        };
+                        add_prediction(
+                            [analyzed_txn.read_hints(), analyzed_txn.write_hints()],
+                            valid_txn,
+                        );
+                    }
+                },
+            }
+        }
+
+        STATE_PREFETCH_KEYS
+            .with_label_values(&["hint"])
+            .inc_by(num_hinted);
+        STATE_PREFETCH_KEYS
+            .with_label_values(&["learned"])
+            .inc_by(num_learned);
+        state_view.prime_cache_by_keys(&keys)
+    }
+
+    /// Learns the key patterns of entry functions from the writes of committed transactions, and
+    /// from the reads of the block at the addresses they access.
+    pub fn learn(&self, execution_output: &ExecutionOutput) {
+        let _timer = OTHER_TIMERS.timer_with(&["state_prefetch_learn"]);
+
+        let state_cache = &execution_output.state_cache.sharded_state_cache;
+        let mut reads_by_address: HashMap<AccountAddress, Vec<StateKey>> = HashMap::new();
+        for shard_id in 0..NUM_STATE_SHARDS {
+            for entry in state_cache.shard(shard_id as u8).iter() {
+                if let StateKeyInner::AccessPath(access_path) = entry.key().inner() {
+                    if !access_path.is_code() {
+                        reads_by_address
+                            .entry(access_path.address)
+                            .or_default()
+                            .push(entry.key().clone());
+                    }
+                }
+            }
+        }
+
+        let mut learned = self.learned.write();
+        for (txn, output) in execution_output.to_commit.iter() {
+            if output.status() != &TransactionStatus::Keep(ExecutionStatus::Success) {
+                continue;
+            }
+            let Some(call) = EntryFunctionCall::from_txn(txn) else {
+                continue;
+            };
+            if !learned.contains_key(&call.id) && learned.len() >= MAX_LEARNED_FUNCTIONS {
+                continue;
+            }
+
+            let addresses = call.addresses();
+            let reads = addresses
+                .keys()
+                .filter_map(|address| reads_by_address.get(address))
+                .flatten()
+                .cloned();
+            let writes = output.write_set().iter().map(|(key, _)| key.clone());
+            learned
+                .entry(call.id.clone())
+                .or_default()
+                .observe(&addresses, writes.chain(reads));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_crypto::{ed25519::Ed25519PrivateKey, PrivateKey, Uniform};
+    use aptos_scratchpad::SparseMerkleTree;
+    use aptos_storage_interface::{
+        mock::MockDbReaderWriter, state_store::state_view::async_proof_fetcher::AsyncProofFetcher,
+    };
+    use aptos_types::{
+        account_config::primary_apt_store,
+        chain_id::ChainId,
+        state_store::{state_value::StateValue, StateViewId},
+        transaction::{EntryFunction, RawTransaction},
+    };
+    use move_core_types::{ident_str, language_storage::StructTag};
+    use std::sync::Arc;
+
+    fn address(byte: u8) -> AccountAddress {
+        AccountAddress::from_hex_literal(&format!("0x{:x}", byte)).unwrap()
+    }
+
+    fn function_id() -> EntryFunctionId {
+        EntryFunctionId {
+            module: ModuleId::new(AccountAddress::ONE, ident_str!("m").to_owned()),
+            function: ident_str!("transfer").to_owned(),
+        }
+    }
+
+    fn call(sender: u8, receiver: u8) -> EntryFunctionCall {
+        EntryFunctionCall {
+            id: function_id(),
+            sender: address(sender),
+            address_args: vec![Some(address(receiver)), None],
+        }
+    }
+
+    fn struct_tag(name: &str) -> StructTag {
+        StructTag {
+            address: AccountAddress::ONE,
+            module: Identifier::new("m").unwrap(),
+            name: Identifier::new(name).unwrap(),
+            type_args: vec![],
+        }
+    }
+
+    fn resource(address: AccountAddress, name: &str) -> StateKey {
+        StateKey::resource(&address, &struct_tag(name)).unwrap()
+    }
+
+    fn resource_group(address: AccountAddress, name: &str) -> StateKey {
+        StateKey::resource_group(&address, &struct_tag(name))
+    }
+
+    /// Keys accessed by a transfer: the account of the sender, the primary APT stores of both
+    /// parties, and a config.
+    fn transfer_keys(call: &EntryFunctionCall) -> Vec<StateKey> {
+        let receiver = call.address_args[0].unwrap();
+        vec![
+            resource(call.sender, "Account"),
+            resource_group(primary_apt_store(call.sender), "ObjectGroup"),
+            resource_group(primary_apt_store(receiver), "ObjectGroup"),
+            resource(AccountAddress::ONE, "Config"),
+        ]
+    }
+
+    #[test]
+    fn test_learn_and_predict() {
+        let mut patterns = LearnedPatterns::default();
+        for (idx, (sender, receiver)) in [(0x12, 0x13), (0x14, 0x15), (0x16, 0x17)]
+            .into_iter()
+            .enumerate()
+        {
+            let call = call(sender, receiver);
+            let mut keys = transfer_keys(&call);
+            // Only accessed by one of the calls, so not frequent enough to be predicted.
+            if idx == 0 {
+                keys.push(resource(AccountAddress::TWO, "Rare"));
+            }
+            patterns.observe(&call.addresses(), keys.into_iter());
+        }
+        assert!(patterns.pattern_counts.contains_key(&KeyPattern::At(
+            CallAddress::PrimaryStore(Owner::Argument(0), FungibleAsset::Apt),
+            Path::ResourceGroup(struct_tag("ObjectGroup")),
+        )));
+
+        let new_call = call(0x18, 0x19);
+        let predicted: HashSet<_> = patterns.predict(&new_call).collect();
+        let expected: HashSet<_> = transfer_keys(&new_call).into_iter().collect();
+        assert_eq!(predicted, expected);
+    }
+
+    #[test]
+    fn test_prefetch_fills_cache() {
+        let prefetcher = StatePrefetcher::default();
+        let learned_call = call(0x12, 0x13);
+        prefetcher
+            .learned
+            .write()
+            .entry(function_id())
+            .or_default()
+            .observe(
+                &learned_call.addresses(),
+                transfer_keys(&learned_call).into_iter(),
+            );
+
+        let (sender, receiver) = (address(0x14), address(0x15));
+        let private_key = Ed25519PrivateKey::generate_for_testing();
+        let entry_function =
+            EntryFunction::new(function_id().module, function_id().function, vec![], vec![
+                bcs::to_bytes(&receiver).unwrap(),
+                bcs::to_bytes(&100u64).unwrap(),
+            ]);
+        let txn = Transaction::UserTransaction(
+            RawTransaction::new_entry_function(
+                sender,
+                0,
+                entry_function,
+                1_000,
+                0,
+                0,
+                ChainId::test(),
+            )
+            .sign(&private_key, private_key.public_key())
+            .unwrap()
+            .into_inner(),
+        );
+
+        let smt = SparseMerkleTree::<StateValue>::new_empty();
+        let state_view = CachedStateView::new_impl(
+            StateViewId::Miscellaneous,
+            0,
+            None,
+            smt.freeze(&smt),
+            Arc::new(AsyncProofFetcher::new(Arc::new(MockDbReaderWriter))),
+        );
+        prefetcher
+            .prefetch(
+                &ExecutableTransactions::Unsharded(vec![txn.clone().into()]),
+                &state_view,
+            )
+            .unwrap();
+
+        let cache = state_view.into_state_cache().sharded_state_cache;
+        let is_cached = |key: &StateKey| cache.shard(key.get_shard_id()).contains_key(key);
+        let call = EntryFunctionCall::from_txn(&txn).unwrap();
+        for key in transfer_keys(&call) {
+            assert!(is_cached(&key), "{:?} should be prefetched", key);
+        }
+        assert!(!is_cached(&resource(receiver, "Account")));
+    }
+}
@@ -11,8 +11,9 @@ use aptos_metrics_core::{
 use aptos_types::{
     contract_event::ContractEvent,
     transaction::{
-        authenticator::AccountAuthenticator, signature_verified_transaction::TransactionProvider,
-        ExecutionStatus, Transaction, TransactionOutput, TransactionStatus,
+        authenticator::AccountAuthenticator, resource_usage::BlockResourceUsage,
+        signature_verified_transaction::TransactionProvider, ExecutionStatus, Transaction,
+        TransactionOutput, TransactionStatus,
     },
 };
 use aptos_vm::AptosVM;
@@ -80,6 +81,15 @@ pub static EXECUTOR_ERRORS: Lazy<IntCounter> = Lazy::new(|| {
     register_int_counter!("aptos_executor_error_total", "Cumulative number of errors").unwrap()
 });
 
+pub static STATE_PREFETCH_KEYS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_executor_state_prefetch_keys",
+        "Number of state keys prefetched before block execution, by how they were predicted",
+        &["source"]
+    )
+    .unwrap()
+});
+
 pub static BLOCK_EXECUTION_WORKFLOW_WHOLE: Lazy<Histogram> = Lazy::new(|| {
     register_histogram!(
         // metric name
@@ -251,6 +261,18 @@ pub static PROCESSED_TXNS_AUTHENTICATOR: Lazy<IntCounterVec> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Resources used by the committed transactions of a block, only recorded when resource
+/// accounting is enabled.
+pub static BLOCK_RESOURCE_USAGE: Lazy<HistogramVec> = Lazy::new(|| {
+    register_histogram_vec!(
+        "aptos_executor_block_resource_usage",
+        "Histogram of resources used by the committed transactions of a block",
+        &["resource"],
+        exponential_buckets(/*start=*/ 1.0, /*factor=*/ 2.0, /*count=*/ 32).unwrap()
+    )
+    .unwrap()
+});
+
 pub static CONCURRENCY_GAUGE: Lazy<IntGaugeVec> = Lazy::new(|| {
     register_int_gauge_vec!(
         "aptos_executor_call_concurrency",
@@ -501,3 +523,28 @@ pub fn update_counters_for_processed_chunk<T>(
         }
     }
 }
+
+pub fn update_counters_for_block_resource_usage(usage: &BlockResourceUsage) {
+    let total = &usage.total;
+    for (resource, value) in [
+        ("num_transactions", usage.num_transactions as u64),
+        ("execution_time_us", total.execution_time.as_micros() as u64),
+        (
+            "max_execution_time_us",
+            usage.max_execution_time.as_micros() as u64,
+        ),
+        ("resource_reads", total.resource_reads),
+        ("resource_group_reads", total.resource_group_reads),
+        ("table_item_reads", total.table_item_reads),
+        ("module_reads", total.module_reads),
+        ("aggregator_reads", total.aggregator_reads),
+        ("bytes_read", total.bytes_read),
+        ("bytes_written", total.bytes_written),
+        ("num_instructions", total.num_instructions),
+        ("num_native_calls", total.num_native_calls),
+    ] {
+        BLOCK_RESOURCE_USAGE
+            .with_label_values(&[resource])
+            .observe(value as f64);
+    }
+}
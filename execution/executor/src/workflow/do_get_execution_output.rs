@@ -42,6 +42,7 @@ use aptos_types::{
         TStateView,
     },
     transaction::{
+        resource_usage::BlockResourceUsage,
         signature_verified_transaction::SignatureVerifiedTransaction, BlockEndInfo, BlockOutput,
         Transaction, TransactionOutput, TransactionStatus, Version,
     },
@@ -89,6 +90,15 @@ impl DoGetExecutionOutput {
                     "execution",
                 )
             }
+            if let Some(usage) =
+                BlockResourceUsage::from_outputs(&out.to_commit.transaction_outputs)
+            {
+                info!(
+                    first_version = out.first_version,
+                    "Block resource usage: {}", usage
+                );
+                metrics::update_counters_for_block_resource_usage(&usage);
+            }
         });
 
         Ok(ret)
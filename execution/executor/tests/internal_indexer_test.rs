@@ -20,6 +20,7 @@ use aptos_types::{
     account_config::aptos_test_root_address,
     block_metadata::BlockMetadata,
     chain_id::ChainId,
+    contract_event::{ContractEvent, EventWithVersion},
     state_store::state_key::{prefix::StateKeyPrefix, StateKey},
     test_helpers::transaction_test_helpers::TEST_BLOCK_EXECUTOR_ONCHAIN_CONFIG,
     transaction::{
@@ -28,9 +29,12 @@ use aptos_types::{
         WriteSetPayload,
     },
 };
-use move_core_types::{ident_str, language_storage::StructTag};
+use move_core_types::{
+    ident_str,
+    language_storage::{StructTag, TypeTag},
+};
 use rand::SeedableRng;
-use std::{fmt::Debug, str::FromStr, sync::Arc};
+use std::{collections::HashSet, fmt::Debug, str::FromStr, sync::Arc};
 
 const B: u64 = 1_000_000_000;
 
@@ -393,6 +397,104 @@ fn test_db_indexer_data() {
     assert_vec_eq(&resources, &expected_resources);
 }
 
+#[test]
+fn test_db_indexer_events_by_type() {
+    use std::{thread, time::Duration};
+    // create test db
+    let (aptos_db, _core_account) = create_test_db();
+    let total_version = aptos_db.expect_synced_version();
+    let temp_path = TempPath::new();
+    let mut node_config = aptos_config::config::NodeConfig::default();
+    node_config.storage.dir = temp_path.path().to_path_buf();
+    node_config.indexer_db_config.enable_event = true;
+    node_config.indexer_db_config.batch_size = 5;
+
+    // index the first batch without the event by type index
+    let internal_indexer_db = InternalIndexerDBService::get_indexer_db(&node_config).unwrap();
+    let db_indexer = DBIndexer::new(internal_indexer_db, aptos_db.clone());
+    let mut start_version = db_indexer.process_a_batch(0).unwrap();
+    drop(db_indexer);
+
+    // enable the event by type index, backfill it and index the remaining transactions
+    node_config.indexer_db_config.enable_event_by_type = true;
+    let internal_indexer_db = InternalIndexerDBService::get_indexer_db(&node_config).unwrap();
+    assert_eq!(
+        internal_indexer_db.get_event_by_type_version().unwrap(),
+        None
+    );
+    let db_indexer = DBIndexer::new(internal_indexer_db.clone(), aptos_db.clone());
+    // the processed versions can't be queried by type until they are backfilled
+    let event_type = TypeTag::from_str("0x1::transaction_fee::FeeStatement").unwrap();
+    assert!(db_indexer
+        .get_events_by_type(&event_type, 0, 1000, start_version - 1)
+        .is_err());
+    // batches processed during the backfill don't update the index
+    assert!(db_indexer.event_by_type_backfill_pending());
+    start_version = db_indexer.process_a_batch(start_version).unwrap();
+    thread::sleep(Duration::from_millis(100));
+    assert_eq!(
+        internal_indexer_db.get_event_by_type_version().unwrap(),
+        None
+    );
+    let mut backfill_version = 0;
+    while backfill_version < start_version {
+        backfill_version = db_indexer
+            .backfill_events_by_type(backfill_version, start_version - 1)
+            .unwrap();
+    }
+    assert!(!db_indexer.event_by_type_backfill_pending());
+    while start_version <= total_version {
+        start_version = db_indexer.process_a_batch(start_version).unwrap();
+    }
+    // wait for the commit to finish
+    thread::sleep(Duration::from_millis(100));
+    assert_eq!(
+        internal_indexer_db.get_event_by_type_version().unwrap(),
+        Some(total_version)
+    );
+
+    // collect the expected module events from the main db
+    let module_events: Vec<_> = aptos_db
+        .get_events_iterator(0, total_version + 1)
+        .unwrap()
+        .enumerate()
+        .flat_map(|(version, events)| {
+            events
+                .unwrap()
+                .into_iter()
+                .filter(|event| matches!(event, ContractEvent::V2(_)))
+                .map(move |event| (version as u64, event))
+        })
+        .collect();
+    assert!(!module_events.is_empty());
+
+    // every module event type should be found in the index
+    let type_tags: HashSet<_> = module_events
+        .iter()
+        .map(|(_, event)| event.type_tag().clone())
+        .collect();
+    for type_tag in type_tags {
+        let expected_events: Vec<_> = module_events
+            .iter()
+            .filter(|(_, event)| event.type_tag() == &type_tag)
+            .map(|(version, event)| EventWithVersion::new(*version, event.clone()))
+            .collect();
+        let events = db_indexer
+            .get_events_by_type(&type_tag, 0, 1000, total_version)
+            .unwrap();
+        assert_vec_eq(&events, &expected_events);
+
+        // events below the start version are skipped
+        let start_version = expected_events.last().unwrap().transaction_version;
+        let events = db_indexer
+            .get_events_by_type(&type_tag, start_version, 1000, total_version)
+            .unwrap();
+        assert!(events
+            .iter()
+            .all(|event| event.transaction_version == start_version));
+    }
+}
+
 fn assert_vec_eq<T: Eq + Debug>(left: &[T], right: &[T]) {
     for i in 0..left.len().min(right.len()) {
         assert_eq!(left[i], right[i], "difference at position {}", i);
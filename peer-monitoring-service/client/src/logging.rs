@@ -40,6 +40,7 @@ impl<'a> LogSchema<'a> {
 #[derive(Clone, Copy, Serialize)]
 #[serde(rename_all = "snake_case")]
 pub enum LogEntry {
+    BandwidthProbe,
     LatencyPing,
     MetadataUpdateLoop,
     NetworkInfoRequest,
@@ -54,11 +55,13 @@ pub enum LogEvent {
     InvalidResponse,
     LogAllPeerStates,
     PeerPingError,
+    PeerProbeError,
     ResponseError,
     ResponseSuccess,
     SendRequest,
     StartedMetadataUpdaterLoop,
     StartedPeerMonitorLoop,
     TooManyPingFailures,
+    TooManyProbeFailures,
     UnexpectedErrorEncountered,
 }
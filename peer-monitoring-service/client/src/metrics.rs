@@ -21,6 +21,30 @@ pub static AVERAGE_PING_LATENCIES: Lazy<HistogramVec> = Lazy::new(|| {
     register_histogram_vec!(histogram_opts, &["network_id"]).unwrap()
 });
 
+// Histogram buckets for tracking the average bandwidth (bytes per second)
+const BANDWIDTH_BUCKETS: &[f64] = &[
+    1024.0,       // 1 KB/s
+    10240.0,      // 10 KB/s
+    102400.0,     // 100 KB/s
+    512000.0,     // 500 KB/s
+    1048576.0,    // 1 MB/s
+    5242880.0,    // 5 MB/s
+    10485760.0,   // 10 MB/s
+    52428800.0,   // 50 MB/s
+    104857600.0,  // 100 MB/s
+    1073741824.0, // 1 GB/s
+];
+
+/// Counter for tracking the average bandwidths
+pub static AVERAGE_BANDWIDTHS: Lazy<HistogramVec> = Lazy::new(|| {
+    let histogram_opts = histogram_opts!(
+        "peer_monitoring_client_average_bandwidths",
+        "Counters related to average bandwidths (bytes per second)",
+        BANDWIDTH_BUCKETS.to_vec()
+    );
+    register_histogram_vec!(histogram_opts, &["network_id"]).unwrap()
+});
+
 // Histogram buckets for tracking the distance from the validators
 const DISTANCE_FROM_VALIDATORS_BUCKETS: &[f64] = &[
     0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 10.0, 15.0, 20.0, 30.0, 40.0, 50.0,
@@ -0,0 +1,653 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    metrics,
+    peer_states::{key_value::StateValueInterface, request_tracker::RequestTracker},
+    Error, LogEntry, LogEvent, LogSchema,
+};
+use aptos_config::{config::BandwidthMonitoringConfig, network_id::PeerNetworkId};
+use aptos_infallible::RwLock;
+use aptos_logger::{error, info, warn};
+use aptos_network::application::metadata::PeerMetadata;
+use aptos_peer_monitoring_service_types::{
+    request::{BandwidthProbeRequest, PeerMonitoringServiceRequest},
+    response::PeerMonitoringServiceResponse,
+    MIN_BANDWIDTH_PROBE_SERVER_VERSION,
+};
+use aptos_time_service::TimeService;
+use std::{
+    collections::BTreeMap,
+    fmt,
+    fmt::{Display, Formatter},
+    sync::Arc,
+};
+
+/// A simple container that holds a peer's bandwidth info
+#[derive(Clone, Debug)]
+pub struct BandwidthInfoState {
+    bandwidth_monitoring_config: BandwidthMonitoringConfig, // The config for bandwidth monitoring
+    bandwidth_probe_counter: u64, // The monotonically increasing counter for each probe
+    recorded_bandwidth_bytes_per_sec: BTreeMap<u64, f64>, // Successful probe throughputs by counter (bytes/sec)
+    request_tracker: Arc<RwLock<RequestTracker>>, // The request tracker for bandwidth probe requests
+    server_protocol_version: Option<u64>, // The peer monitoring server version run by the peer (if known)
+}
+
+impl BandwidthInfoState {
+    pub fn new(
+        bandwidth_monitoring_config: BandwidthMonitoringConfig,
+        time_service: TimeService,
+    ) -> Self {
+        let request_tracker = RequestTracker::new(
+            bandwidth_monitoring_config.bandwidth_probe_interval_ms,
+            time_service,
+        );
+
+        Self {
+            bandwidth_monitoring_config,
+            bandwidth_probe_counter: 0,
+            recorded_bandwidth_bytes_per_sec: BTreeMap::new(),
+            request_tracker: Arc::new(RwLock::new(request_tracker)),
+            server_protocol_version: None,
+        }
+    }
+
+    /// Returns the current bandwidth probe counter and increments it internally
+    pub fn get_and_increment_bandwidth_probe_counter(&mut self) -> u64 {
+        let bandwidth_probe_counter = self.bandwidth_probe_counter;
+        self.bandwidth_probe_counter += 1;
+        bandwidth_probe_counter
+    }
+
+    /// Handles a probe failure for the specified peer
+    fn handle_request_failure(&self, peer_network_id: &PeerNetworkId) {
+        // Update the number of probe failures for the request tracker
+        self.request_tracker.write().record_response_failure();
+
+        // Log if the number of probe failures is too high
+        let num_consecutive_failures = self.request_tracker.read().get_num_consecutive_failures();
+        let max_bandwidth_probe_failures = self
+            .bandwidth_monitoring_config
+            .max_bandwidth_probe_failures;
+        if num_consecutive_failures >= max_bandwidth_probe_failures {
+            warn!(LogSchema::new(LogEntry::BandwidthProbe)
+                .event(LogEvent::TooManyProbeFailures)
+                .peer(peer_network_id)
+                .message("Too many bandwidth probe failures occurred for the peer!"));
+        }
+    }
+
+    /// Records the new bandwidth measurement for the peer and resets the
+    /// consecutive failure counter.
+    pub fn record_new_bandwidth_and_reset_failures(
+        &mut self,
+        bandwidth_probe_counter: u64,
+        bandwidth_bytes_per_sec: f64,
+    ) {
+        // Update the request tracker with a successful response
+        self.request_tracker.write().record_response_success();
+
+        // Save the bandwidth measurement
+        self.recorded_bandwidth_bytes_per_sec
+            .insert(bandwidth_probe_counter, bandwidth_bytes_per_sec);
+
+        // Perform garbage collection on the recorded bandwidth measurements
+        let max_num_bandwidth_probes_to_retain = self
+            .bandwidth_monitoring_config
+            .max_num_bandwidth_probes_to_retain;
+        if self.recorded_bandwidth_bytes_per_sec.len() > max_num_bandwidth_probes_to_retain {
+            // We only need to pop a single element because insertion only happens in this method.
+            // Thus, the size can only ever grow to be 1 greater than the max.
+            let _ = self.recorded_bandwidth_bytes_per_sec.pop_first();
+        }
+    }
+
+    /// Returns the average bandwidth in bytes per second. If no
+    /// bandwidth probes have been recorded, None is returned.
+    pub fn get_average_bandwidth_bytes_per_sec(&self) -> Option<f64> {
+        let num_bandwidth_probes = self.recorded_bandwidth_bytes_per_sec.len();
+        if num_bandwidth_probes > 0 {
+            let bandwidth_sum: f64 = self.recorded_bandwidth_bytes_per_sec.values().sum();
+            Some(bandwidth_sum / num_bandwidth_probes as f64)
+        } else {
+            None
+        }
+    }
+
+    /// Handles a server protocol version response from the peer (this
+    /// is used to determine if the peer supports bandwidth probes).
+    fn handle_server_protocol_version_response(
+        &mut self,
+        peer_network_id: &PeerNetworkId,
+        monitoring_service_response: PeerMonitoringServiceResponse,
+    ) {
+        // Verify the response type is valid
+        let server_protocol_version = match monitoring_service_response {
+            PeerMonitoringServiceResponse::ServerProtocolVersion(response) => response.version,
+            _ => {
+                warn!(LogSchema::new(LogEntry::BandwidthProbe)
+                    .event(LogEvent::ResponseError)
+                    .peer(peer_network_id)
+                    .message(
+                        "An unexpected response was received instead of a server protocol version!"
+                    ));
+                self.handle_request_failure(peer_network_id);
+                return;
+            },
+        };
+
+        // Log if the peer doesn't support bandwidth probes
+        if server_protocol_version < MIN_BANDWIDTH_PROBE_SERVER_VERSION {
+            info!(LogSchema::new(LogEntry::BandwidthProbe)
+                .peer(peer_network_id)
+                .message(&format!(
+                    "The peer does not support bandwidth probes! Server protocol version: {:?}",
+                    server_protocol_version
+                )));
+        }
+
+        // Store the server protocol version
+        self.server_protocol_version = Some(server_protocol_version);
+        self.request_tracker.write().record_response_success();
+    }
+
+    /// Handles a bandwidth probe response from the peer
+    fn handle_bandwidth_probe_response(
+        &mut self,
+        peer_network_id: &PeerNetworkId,
+        peer_metadata: PeerMetadata,
+        bandwidth_probe_request: BandwidthProbeRequest,
+        monitoring_service_response: PeerMonitoringServiceResponse,
+        response_time_secs: f64,
+    ) {
+        // Verify the response type is valid
+        let bandwidth_probe_response = match monitoring_service_response {
+            PeerMonitoringServiceResponse::BandwidthProbe(bandwidth_probe_response) => {
+                bandwidth_probe_response
+            },
+            _ => {
+                warn!(LogSchema::new(LogEntry::BandwidthProbe)
+                    .event(LogEvent::ResponseError)
+                    .peer(peer_network_id)
+                    .message("An unexpected response was received instead of a bandwidth probe!"));
+                self.handle_request_failure(peer_network_id);
+                return;
+            },
+        };
+
+        // Verify the bandwidth probe response contains the correct counter
+        let request_probe_counter = bandwidth_probe_request.probe_counter;
+        let response_probe_counter = bandwidth_probe_response.probe_counter;
+        if request_probe_counter != response_probe_counter {
+            warn!(LogSchema::new(LogEntry::BandwidthProbe)
+                .event(LogEvent::PeerProbeError)
+                .peer(peer_network_id)
+                .message(&format!(
+                    "Peer responded with the incorrect probe counter! Expected: {:?}, found: {:?}",
+                    request_probe_counter, response_probe_counter
+                )));
+            self.handle_request_failure(peer_network_id);
+            return;
+        }
+
+        // Verify the bandwidth probe response contains the requested payload size
+        let num_requested_bytes = bandwidth_probe_request.num_payload_bytes;
+        let num_received_bytes = bandwidth_probe_response.payload.len() as u64;
+        if num_requested_bytes != num_received_bytes {
+            warn!(LogSchema::new(LogEntry::BandwidthProbe)
+                .event(LogEvent::PeerProbeError)
+                .peer(peer_network_id)
+                .message(&format!(
+                    "Peer responded with the incorrect payload size! Expected: {:?}, found: {:?}",
+                    num_requested_bytes, num_received_bytes
+                )));
+            self.handle_request_failure(peer_network_id);
+            return;
+        }
+
+        // The response time includes the round trip latency to the peer, which is
+        // unrelated to throughput. Thus, we subtract the average ping latency to
+        // estimate the transfer time. If the latency is unknown, we can't do this.
+        let average_ping_latency_secs = peer_metadata
+            .get_peer_monitoring_metadata()
+            .average_ping_latency_secs;
+        let transfer_time_secs = average_ping_latency_secs
+            .map(|average_ping_latency_secs| response_time_secs - average_ping_latency_secs);
+
+        // Calculate and store the new bandwidth measurement
+        match transfer_time_secs {
+            Some(transfer_time_secs) if transfer_time_secs > 0.0 => {
+                let bandwidth_bytes_per_sec = num_received_bytes as f64 / transfer_time_secs;
+                self.record_new_bandwidth_and_reset_failures(
+                    request_probe_counter,
+                    bandwidth_bytes_per_sec,
+                );
+            },
+            _ => {
+                // The transfer time can't be measured. Simply mark the request as successful.
+                self.request_tracker.write().record_response_success();
+            },
+        }
+    }
+
+    /// Returns a copy of the recorded bandwidth measurements for test purposes
+    #[cfg(test)]
+    pub fn get_recorded_bandwidths(&self) -> BTreeMap<u64, f64> {
+        self.recorded_bandwidth_bytes_per_sec.clone()
+    }
+}
+
+impl StateValueInterface for BandwidthInfoState {
+    fn create_monitoring_service_request(&mut self) -> PeerMonitoringServiceRequest {
+        // If the server protocol version is unknown, fetch it first
+        if self.server_protocol_version.is_none() {
+            return PeerMonitoringServiceRequest::GetServerProtocolVersion;
+        }
+
+        // Otherwise, create a bandwidth probe
+        let probe_counter = self.get_and_increment_bandwidth_probe_counter();
+        let num_payload_bytes = self
+            .bandwidth_monitoring_config
+            .bandwidth_probe_payload_bytes;
+        PeerMonitoringServiceRequest::BandwidthProbe(BandwidthProbeRequest {
+            probe_counter,
+            num_payload_bytes,
+        })
+    }
+
+    fn get_request_timeout_ms(&self) -> u64 {
+        self.bandwidth_monitoring_config.bandwidth_probe_timeout_ms
+    }
+
+    fn get_request_tracker(&self) -> Arc<RwLock<RequestTracker>> {
+        self.request_tracker.clone()
+    }
+
+    fn handle_monitoring_service_response(
+        &mut self,
+        peer_network_id: &PeerNetworkId,
+        peer_metadata: PeerMetadata,
+        monitoring_service_request: PeerMonitoringServiceRequest,
+        monitoring_service_response: PeerMonitoringServiceResponse,
+        response_time_secs: f64,
+    ) {
+        match monitoring_service_request {
+            PeerMonitoringServiceRequest::GetServerProtocolVersion => self
+                .handle_server_protocol_version_response(
+                    peer_network_id,
+                    monitoring_service_response,
+                ),
+            PeerMonitoringServiceRequest::BandwidthProbe(bandwidth_probe_request) => self
+                .handle_bandwidth_probe_response(
+                    peer_network_id,
+                    peer_metadata,
+                    bandwidth_probe_request,
+                    monitoring_service_response,
+                    response_time_secs,
+                ),
+            request => {
+                error!(LogSchema::new(LogEntry::BandwidthProbe)
+                    .event(LogEvent::UnexpectedErrorEncountered)
+                    .peer(peer_network_id)
+                    .request(&request)
+                    .message("An unexpected request was sent instead of a bandwidth probe!"));
+                self.handle_request_failure(peer_network_id);
+            },
+        }
+    }
+
+    fn handle_monitoring_service_response_error(
+        &mut self,
+        peer_network_id: &PeerNetworkId,
+        error: Error,
+    ) {
+        // Handle the failure
+        self.handle_request_failure(peer_network_id);
+
+        // Log the error
+        warn!(LogSchema::new(LogEntry::BandwidthProbe)
+            .event(LogEvent::ResponseError)
+            .message("Error encountered when probing peer bandwidth!")
+            .peer(peer_network_id)
+            .error(&error));
+    }
+
+    fn is_supported_by_peer(&self) -> bool {
+        // If the server protocol version is unknown, we must fetch it first
+        self.server_protocol_version
+            .map(|version| version >= MIN_BANDWIDTH_PROBE_SERVER_VERSION)
+            .unwrap_or(true)
+    }
+
+    fn update_peer_state_metrics(&self, peer_network_id: &PeerNetworkId) {
+        if let Some(average_bandwidth_bytes_per_sec) = self.get_average_bandwidth_bytes_per_sec() {
+            // Update the average bandwidth metric
+            metrics::observe_value(
+                &metrics::AVERAGE_BANDWIDTHS,
+                peer_network_id,
+                average_bandwidth_bytes_per_sec,
+            );
+        }
+    }
+}
+
+impl Display for BandwidthInfoState {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "BandwidthInfoState {{ bandwidth_probe_counter: {:?}, recorded_bandwidth_bytes_per_sec: {:?}, server_protocol_version: {:?} }}",
+            self.bandwidth_probe_counter, self.recorded_bandwidth_bytes_per_sec, self.server_protocol_version,
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::peer_states::{bandwidth_info::BandwidthInfoState, key_value::StateValueInterface};
+    use aptos_config::{
+        config::{BandwidthMonitoringConfig, PeerRole},
+        network_id::{NetworkId, PeerNetworkId},
+    };
+    use aptos_netcore::transport::ConnectionOrigin;
+    use aptos_network::{
+        application::metadata::PeerMetadata,
+        protocols::wire::handshake::v1::{MessagingProtocolVersion, ProtocolIdSet},
+        transport::{ConnectionId, ConnectionMetadata},
+    };
+    use aptos_peer_monitoring_service_types::{
+        request::{BandwidthProbeRequest, PeerMonitoringServiceRequest},
+        response::{
+            BandwidthProbeResponse, PeerMonitoringServiceResponse, ServerProtocolVersionResponse,
+        },
+        PeerMonitoringMetadata, MIN_BANDWIDTH_PROBE_SERVER_VERSION,
+    };
+    use aptos_time_service::TimeService;
+    use aptos_types::{network_address::NetworkAddress, PeerId};
+    use std::{cmp::min, str::FromStr};
+
+    // Useful test constants
+    const TEST_NETWORK_ADDRESS: &str = "/ip4/127.0.0.1/tcp/8081";
+    const TEST_PING_LATENCY_SECS: f64 = 0.25;
+
+    #[test]
+    fn test_verify_bandwidth_info_state() {
+        // Create the bandwidth info state
+        let bandwidth_monitoring_config = BandwidthMonitoringConfig::default();
+        let time_service = TimeService::mock();
+        let mut bandwidth_info_state =
+            BandwidthInfoState::new(bandwidth_monitoring_config, time_service);
+
+        // Verify the initial bandwidth info state
+        assert_eq!(bandwidth_info_state.bandwidth_probe_counter, 0);
+        assert!(bandwidth_info_state
+            .get_average_bandwidth_bytes_per_sec()
+            .is_none());
+
+        // Attempt to handle an invalid probe response with mismatched probe counters
+        let num_payload_bytes = 1000;
+        let probe_counter = bandwidth_info_state.get_and_increment_bandwidth_probe_counter();
+        handle_monitoring_service_response(
+            &mut bandwidth_info_state,
+            probe_counter,
+            probe_counter + 1,
+            num_payload_bytes,
+            num_payload_bytes,
+            1.0,
+        );
+        assert!(bandwidth_info_state.get_recorded_bandwidths().is_empty());
+
+        // Attempt to handle an invalid probe response with a mismatched payload size
+        let probe_counter = bandwidth_info_state.get_and_increment_bandwidth_probe_counter();
+        handle_monitoring_service_response(
+            &mut bandwidth_info_state,
+            probe_counter,
+            probe_counter,
+            num_payload_bytes,
+            num_payload_bytes - 1,
+            1.0,
+        );
+        assert!(bandwidth_info_state.get_recorded_bandwidths().is_empty());
+
+        // Verify the consecutive failures were recorded
+        assert_eq!(
+            bandwidth_info_state
+                .get_request_tracker()
+                .read()
+                .get_num_consecutive_failures(),
+            2
+        );
+
+        // Handle several valid probe responses (with different transfer times)
+        for transfer_time_secs in [0.5, 1.0, 2.0] {
+            let probe_counter = bandwidth_info_state.get_and_increment_bandwidth_probe_counter();
+            handle_monitoring_service_response(
+                &mut bandwidth_info_state,
+                probe_counter,
+                probe_counter,
+                num_payload_bytes,
+                num_payload_bytes,
+                TEST_PING_LATENCY_SECS + transfer_time_secs,
+            );
+        }
+
+        // Verify the failures were reset and the average bandwidth is correct
+        // (i.e., the ping latency was excluded from the transfer times).
+        assert_eq!(
+            bandwidth_info_state
+                .get_request_tracker()
+                .read()
+                .get_num_consecutive_failures(),
+            0
+        );
+        let expected_average_bandwidth = (2000.0 + 1000.0 + 500.0) / 3.0;
+        assert_eq!(
+            bandwidth_info_state
+                .get_average_bandwidth_bytes_per_sec()
+                .unwrap(),
+            expected_average_bandwidth
+        );
+    }
+
+    #[test]
+    fn test_verify_bandwidth_info_unmeasurable_transfer_time() {
+        // Create the bandwidth info state
+        let bandwidth_monitoring_config = BandwidthMonitoringConfig::default();
+        let mut bandwidth_info_state =
+            BandwidthInfoState::new(bandwidth_monitoring_config, TimeService::mock());
+
+        // Handle a probe response that is faster than the ping latency
+        let num_payload_bytes = 1000;
+        let probe_counter = bandwidth_info_state.get_and_increment_bandwidth_probe_counter();
+        handle_monitoring_service_response(
+            &mut bandwidth_info_state,
+            probe_counter,
+            probe_counter,
+            num_payload_bytes,
+            num_payload_bytes,
+            TEST_PING_LATENCY_SECS / 2.0,
+        );
+
+        // Handle a probe response for a peer with an unknown ping latency
+        let probe_counter = bandwidth_info_state.get_and_increment_bandwidth_probe_counter();
+        handle_monitoring_service_response_with_latency(
+            &mut bandwidth_info_state,
+            PeerMonitoringServiceRequest::BandwidthProbe(BandwidthProbeRequest {
+                probe_counter,
+                num_payload_bytes,
+            }),
+            PeerMonitoringServiceResponse::BandwidthProbe(BandwidthProbeResponse {
+                probe_counter,
+                payload: vec![0; num_payload_bytes as usize],
+            }),
+            None,
+            1.0,
+        );
+
+        // Verify no bandwidth was recorded, but the responses were not treated as failures
+        assert!(bandwidth_info_state.get_recorded_bandwidths().is_empty());
+        assert_eq!(
+            bandwidth_info_state
+                .get_request_tracker()
+                .read()
+                .get_num_consecutive_failures(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_verify_bandwidth_info_server_protocol_version() {
+        // Create the bandwidth info state
+        let bandwidth_monitoring_config = BandwidthMonitoringConfig::default();
+        let mut bandwidth_info_state =
+            BandwidthInfoState::new(bandwidth_monitoring_config, TimeService::mock());
+
+        // Verify the server protocol version is requested first
+        assert!(bandwidth_info_state.is_supported_by_peer());
+        let request = bandwidth_info_state.create_monitoring_service_request();
+        assert_eq!(
+            request,
+            PeerMonitoringServiceRequest::GetServerProtocolVersion
+        );
+
+        // Handle a response from a peer that does not support bandwidth probes
+        handle_monitoring_service_response_with_latency(
+            &mut bandwidth_info_state,
+            request.clone(),
+            PeerMonitoringServiceResponse::ServerProtocolVersion(ServerProtocolVersionResponse {
+                version: MIN_BANDWIDTH_PROBE_SERVER_VERSION - 1,
+            }),
+            Some(TEST_PING_LATENCY_SECS),
+            1.0,
+        );
+
+        // Verify the peer is no longer probed
+        assert!(!bandwidth_info_state.is_supported_by_peer());
+
+        // Create a new bandwidth info state and handle a response from an upgraded peer
+        let mut bandwidth_info_state =
+            BandwidthInfoState::new(bandwidth_monitoring_config, TimeService::mock());
+        handle_monitoring_service_response_with_latency(
+            &mut bandwidth_info_state,
+            request,
+            PeerMonitoringServiceResponse::ServerProtocolVersion(ServerProtocolVersionResponse {
+                version: MIN_BANDWIDTH_PROBE_SERVER_VERSION,
+            }),
+            Some(TEST_PING_LATENCY_SECS),
+            1.0,
+        );
+
+        // Verify the peer is now probed
+        assert!(bandwidth_info_state.is_supported_by_peer());
+        let request = bandwidth_info_state.create_monitoring_service_request();
+        assert!(matches!(
+            request,
+            PeerMonitoringServiceRequest::BandwidthProbe(_)
+        ));
+    }
+
+    #[test]
+    fn test_verify_bandwidth_info_garbage_collection() {
+        // Create the bandwidth info state
+        let bandwidth_monitoring_config = BandwidthMonitoringConfig::default();
+        let time_service = TimeService::mock();
+        let mut bandwidth_info_state =
+            BandwidthInfoState::new(bandwidth_monitoring_config, time_service);
+
+        // Handle several valid probe responses and verify the number of stored entries
+        let max_num_bandwidth_probes_to_retain =
+            bandwidth_monitoring_config.max_num_bandwidth_probes_to_retain as u64;
+        let num_bandwidth_probes = max_num_bandwidth_probes_to_retain * 10;
+        for i in 0..num_bandwidth_probes {
+            // Handle the probe response
+            let probe_counter = bandwidth_info_state.get_and_increment_bandwidth_probe_counter();
+            handle_monitoring_service_response(
+                &mut bandwidth_info_state,
+                probe_counter,
+                probe_counter,
+                probe_counter,
+                probe_counter,
+                TEST_PING_LATENCY_SECS + 1.0,
+            );
+
+            // Verify the number of recorded bandwidths
+            let recorded_bandwidths = bandwidth_info_state.get_recorded_bandwidths();
+            let expected_num_bandwidths = min(max_num_bandwidth_probes_to_retain, i + 1);
+            assert_eq!(recorded_bandwidths.len() as u64, expected_num_bandwidths);
+
+            // Verify the average bandwidth
+            assert_eq!(
+                bandwidth_info_state
+                    .get_average_bandwidth_bytes_per_sec()
+                    .unwrap(),
+                recorded_bandwidths.values().sum::<f64>() / recorded_bandwidths.len() as f64,
+            );
+        }
+    }
+
+    /// Handles a bandwidth probe response from a peer (with the test ping latency)
+    fn handle_monitoring_service_response(
+        bandwidth_info_state: &mut BandwidthInfoState,
+        request_probe_counter: u64,
+        response_probe_counter: u64,
+        num_requested_bytes: u64,
+        num_response_bytes: u64,
+        response_time_secs: f64,
+    ) {
+        // Create the service request
+        let peer_monitoring_service_request =
+            PeerMonitoringServiceRequest::BandwidthProbe(BandwidthProbeRequest {
+                probe_counter: request_probe_counter,
+                num_payload_bytes: num_requested_bytes,
+            });
+
+        // Create the service response
+        let peer_monitoring_service_response =
+            PeerMonitoringServiceResponse::BandwidthProbe(BandwidthProbeResponse {
+                probe_counter: response_probe_counter,
+                payload: vec![0; num_response_bytes as usize],
+            });
+
+        // Handle the response
+        handle_monitoring_service_response_with_latency(
+            bandwidth_info_state,
+            peer_monitoring_service_request,
+            peer_monitoring_service_response,
+            Some(TEST_PING_LATENCY_SECS),
+            response_time_secs,
+        );
+    }
+
+    /// Handles a monitoring service response from a peer with the given ping latency
+    fn handle_monitoring_service_response_with_latency(
+        bandwidth_info_state: &mut BandwidthInfoState,
+        peer_monitoring_service_request: PeerMonitoringServiceRequest,
+        peer_monitoring_service_response: PeerMonitoringServiceResponse,
+        average_ping_latency_secs: Option<f64>,
+        response_time_secs: f64,
+    ) {
+        // Create a new peer metadata entry
+        let peer_network_id = PeerNetworkId::new(NetworkId::Validator, PeerId::random());
+        let connection_metadata = ConnectionMetadata::new(
+            peer_network_id.peer_id(),
+            ConnectionId::default(),
+            NetworkAddress::from_str(TEST_NETWORK_ADDRESS).unwrap(),
+            ConnectionOrigin::Outbound,
+            MessagingProtocolVersion::V1,
+            ProtocolIdSet::empty(),
+            PeerRole::Validator,
+        );
+        let peer_monitoring_metadata =
+            PeerMonitoringMetadata::new(average_ping_latency_secs, None, None, None, None);
+        let peer_metadata =
+            PeerMetadata::new_for_test(connection_metadata, peer_monitoring_metadata);
+
+        // Handle the response
+        bandwidth_info_state.handle_monitoring_service_response(
+            &peer_network_id,
+            peer_metadata,
+            peer_monitoring_service_request,
+            peer_monitoring_service_response,
+            response_time_secs,
+        );
+    }
+}
@@ -3,16 +3,19 @@
 
 use crate::{
     peer_states::{
-        latency_info::LatencyInfoState, network_info::NetworkInfoState, node_info::NodeInfoState,
-        request_tracker::RequestTracker,
+        bandwidth_info::BandwidthInfoState, latency_info::LatencyInfoState,
+        network_info::NetworkInfoState, node_info::NodeInfoState, request_tracker::RequestTracker,
     },
     Error,
 };
-use aptos_config::{config::NodeConfig, network_id::PeerNetworkId};
+use aptos_config::{
+    config::{NodeConfig, PeerMonitoringServiceConfig},
+    network_id::PeerNetworkId,
+};
 use aptos_infallible::RwLock;
 use aptos_network::application::metadata::PeerMetadata;
 use aptos_peer_monitoring_service_types::{
-    request::{LatencyPingRequest, PeerMonitoringServiceRequest},
+    request::{BandwidthProbeRequest, LatencyPingRequest, PeerMonitoringServiceRequest},
     response::PeerMonitoringServiceResponse,
 };
 use aptos_time_service::TimeService;
@@ -23,6 +26,7 @@ use std::{fmt::Display, sync::Arc};
 /// states held for each peer.
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub enum PeerStateKey {
+    BandwidthInfo,
     LatencyInfo,
     NetworkInfo,
     NodeInfo,
@@ -32,15 +36,40 @@ impl PeerStateKey {
     /// A utility function for getting all peer state keys
     pub fn get_all_keys() -> Vec<PeerStateKey> {
         vec![
+            PeerStateKey::BandwidthInfo,
             PeerStateKey::LatencyInfo,
             PeerStateKey::NetworkInfo,
             PeerStateKey::NodeInfo,
         ]
     }
 
+    /// A utility function for getting all peer state keys that
+    /// are enabled (i.e., should be refreshed) by the given config.
+    pub fn get_enabled_keys(
+        monitoring_service_config: &PeerMonitoringServiceConfig,
+    ) -> Vec<PeerStateKey> {
+        PeerStateKey::get_all_keys()
+            .into_iter()
+            .filter(|peer_state_key| peer_state_key.is_enabled(monitoring_service_config))
+            .collect()
+    }
+
+    /// Returns true iff the peer state key is enabled by the given config
+    pub fn is_enabled(&self, monitoring_service_config: &PeerMonitoringServiceConfig) -> bool {
+        match self {
+            PeerStateKey::BandwidthInfo => {
+                monitoring_service_config
+                    .bandwidth_monitoring
+                    .enable_bandwidth_probes
+            },
+            PeerStateKey::LatencyInfo | PeerStateKey::NetworkInfo | PeerStateKey::NodeInfo => true,
+        }
+    }
+
     /// Returns the label for the peer state key
     pub fn get_label(&self) -> &str {
         match self {
+            PeerStateKey::BandwidthInfo => "bandwidth_info",
             PeerStateKey::LatencyInfo => "latency_info",
             PeerStateKey::NetworkInfo => "network_info",
             PeerStateKey::NodeInfo => "node_info",
@@ -51,6 +80,13 @@ impl PeerStateKey {
     /// Returns the metric label for the requests sent by the peer state key
     pub fn get_metrics_request_label(&self) -> &str {
         match self {
+            PeerStateKey::BandwidthInfo => {
+                PeerMonitoringServiceRequest::BandwidthProbe(BandwidthProbeRequest {
+                    probe_counter: 0,
+                    num_payload_bytes: 0,
+                })
+                .get_label()
+            },
             PeerStateKey::LatencyInfo => {
                 PeerMonitoringServiceRequest::LatencyPing(LatencyPingRequest { ping_counter: 0 })
                     .get_label()
@@ -92,6 +128,12 @@ pub trait StateValueInterface {
         error: Error,
     );
 
+    /// Returns true iff the peer supports the requests sent for this
+    /// state (e.g., the peer runs a server version that handles them).
+    fn is_supported_by_peer(&self) -> bool {
+        true
+    }
+
     /// Updates the peer state metrics for the given peer
     fn update_peer_state_metrics(&self, peer_network_id: &PeerNetworkId);
 }
@@ -101,6 +143,7 @@ pub trait StateValueInterface {
 #[enum_dispatch(StateValueInterface)]
 #[derive(Clone, Debug)]
 pub enum PeerStateValue {
+    BandwidthInfoState,
     LatencyInfoState,
     NetworkInfoState,
     NodeInfoState,
@@ -113,6 +156,11 @@ impl PeerStateValue {
         peer_state_key: &PeerStateKey,
     ) -> Self {
         match peer_state_key {
+            PeerStateKey::BandwidthInfo => {
+                let bandwidth_monitoring_config =
+                    node_config.peer_monitoring_service.bandwidth_monitoring;
+                BandwidthInfoState::new(bandwidth_monitoring_config, time_service).into()
+            },
             PeerStateKey::LatencyInfo => {
                 let latency_monitoring_config =
                     node_config.peer_monitoring_service.latency_monitoring;
@@ -131,6 +179,9 @@ impl PeerStateValue {
 impl Display for PeerStateValue {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
+            PeerStateValue::BandwidthInfoState(state) => {
+                write!(f, "BandwidthInfoState: {}", state)
+            },
             PeerStateValue::LatencyInfoState(state) => write!(f, "LatencyInfoState: {}", state),
             PeerStateValue::NetworkInfoState(state) => write!(f, "NetworkInfoState: {}", state),
             PeerStateValue::NodeInfoState(state) => write!(f, "NodeInfoState: {}", state),
@@ -0,0 +1,195 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_peer_monitoring_service_types::PeerMessageStatistics;
+use aptos_time_service::{TimeService, TimeServiceTrait};
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+/// The types of message events tracked for each peer
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum MessageEvent {
+    RequestDropped,
+    RequestSent,
+    ResponseReceived,
+}
+
+/// A simple container that tracks the message rate and drop statistics
+/// for the requests sent to a peer, over a sliding time window.
+#[derive(Clone, Debug)]
+pub struct MessageStatisticsTracker {
+    first_request_time: Option<Instant>, // The time the first request was sent to the peer
+    message_events: VecDeque<(Instant, MessageEvent)>, // The message events in the window (oldest first)
+    time_service: TimeService, // The time service to use for rate calculation
+    window_duration: Duration, // The duration of the sliding window
+}
+
+impl MessageStatisticsTracker {
+    pub fn new(window_duration: Duration, time_service: TimeService) -> Self {
+        Self {
+            first_request_time: None,
+            message_events: VecDeque::new(),
+            time_service,
+            window_duration,
+        }
+    }
+
+    /// Records that a request was sent to the peer
+    pub fn record_request_sent(&mut self) {
+        if self.first_request_time.is_none() {
+            self.first_request_time = Some(self.time_service.now());
+        }
+        self.record_message_event(MessageEvent::RequestSent);
+    }
+
+    /// Records that a request to the peer was dropped (e.g., it failed or timed out)
+    pub fn record_request_dropped(&mut self) {
+        self.record_message_event(MessageEvent::RequestDropped);
+    }
+
+    /// Records that a valid response was received from the peer
+    pub fn record_response_received(&mut self) {
+        self.record_message_event(MessageEvent::ResponseReceived);
+    }
+
+    /// Records the message event and garbage collects the events outside the window
+    fn record_message_event(&mut self, message_event: MessageEvent) {
+        let time_now = self.time_service.now();
+        self.message_events.push_back((time_now, message_event));
+
+        while let Some((event_time, _)) = self.message_events.front() {
+            if self.is_outside_window(*event_time, time_now) {
+                self.message_events.pop_front();
+            } else {
+                break;
+            }
+        }
+    }
+
+    /// Returns true iff the event time is outside the window ending at the given time
+    fn is_outside_window(&self, event_time: Instant, time_now: Instant) -> bool {
+        time_now.saturating_duration_since(event_time) > self.window_duration
+    }
+
+    /// Returns a snapshot of the message statistics for the peer (over the window)
+    pub fn get_message_statistics(&self) -> PeerMessageStatistics {
+        // Count the message events in the window
+        let time_now = self.time_service.now();
+        let mut message_statistics = PeerMessageStatistics::default();
+        for (event_time, message_event) in &self.message_events {
+            if self.is_outside_window(*event_time, time_now) {
+                continue;
+            }
+            match message_event {
+                MessageEvent::RequestDropped => message_statistics.num_requests_dropped += 1,
+                MessageEvent::RequestSent => message_statistics.num_requests_sent += 1,
+                MessageEvent::ResponseReceived => message_statistics.num_responses_received += 1,
+            }
+        }
+
+        // Calculate the rate of responses received over the window (or
+        // since the first request, if that was sent more recently).
+        if let Some(first_request_time) = self.first_request_time {
+            let elapsed_secs = time_now
+                .saturating_duration_since(first_request_time)
+                .min(self.window_duration)
+                .as_secs_f64();
+            if elapsed_secs > 0.0 {
+                message_statistics.messages_per_sec =
+                    message_statistics.num_responses_received as f64 / elapsed_secs;
+            }
+        }
+
+        message_statistics
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::peer_states::message_statistics::MessageStatisticsTracker;
+    use aptos_time_service::TimeService;
+    use std::time::Duration;
+
+    // The window duration used by the tests
+    const WINDOW_DURATION: Duration = Duration::from_secs(60);
+
+    #[test]
+    fn test_message_statistics() {
+        // Create the message statistics tracker
+        let time_service = TimeService::mock();
+        let mut message_statistics_tracker =
+            MessageStatisticsTracker::new(WINDOW_DURATION, time_service.clone());
+
+        // Verify the initial statistics
+        let message_statistics = message_statistics_tracker.get_message_statistics();
+        assert_eq!(message_statistics.num_requests_sent, 0);
+        assert_eq!(message_statistics.messages_per_sec, 0.0);
+        assert!(message_statistics.get_drop_rate().is_none());
+
+        // Send several requests and record responses and drops
+        for i in 0..10 {
+            message_statistics_tracker.record_request_sent();
+            if i % 5 == 0 {
+                message_statistics_tracker.record_request_dropped();
+            } else {
+                message_statistics_tracker.record_response_received();
+            }
+        }
+
+        // Elapse some time
+        time_service.into_mock().advance(Duration::from_secs(4));
+
+        // Verify the statistics
+        let message_statistics = message_statistics_tracker.get_message_statistics();
+        assert_eq!(message_statistics.num_requests_sent, 10);
+        assert_eq!(message_statistics.num_responses_received, 8);
+        assert_eq!(message_statistics.num_requests_dropped, 2);
+        assert_eq!(message_statistics.messages_per_sec, 2.0);
+        assert_eq!(message_statistics.get_drop_rate(), Some(0.2));
+    }
+
+    #[test]
+    fn test_message_statistics_window() {
+        // Create the message statistics tracker
+        let time_service = TimeService::mock();
+        let mock_time_service = time_service.clone().into_mock();
+        let mut message_statistics_tracker =
+            MessageStatisticsTracker::new(WINDOW_DURATION, time_service);
+
+        // Send several requests that are all dropped
+        for _ in 0..10 {
+            message_statistics_tracker.record_request_sent();
+            message_statistics_tracker.record_request_dropped();
+        }
+        let message_statistics = message_statistics_tracker.get_message_statistics();
+        assert_eq!(message_statistics.get_drop_rate(), Some(1.0));
+
+        // Elapse more than the window and verify the drops are forgotten
+        mock_time_service.advance(WINDOW_DURATION + Duration::from_secs(1));
+        let message_statistics = message_statistics_tracker.get_message_statistics();
+        assert_eq!(message_statistics.num_requests_sent, 0);
+        assert_eq!(message_statistics.num_requests_dropped, 0);
+        assert!(message_statistics.get_drop_rate().is_none());
+
+        // Send several requests that all succeed and verify only they are counted
+        for _ in 0..6 {
+            message_statistics_tracker.record_request_sent();
+            message_statistics_tracker.record_response_received();
+        }
+        let message_statistics = message_statistics_tracker.get_message_statistics();
+        assert_eq!(message_statistics.num_requests_sent, 6);
+        assert_eq!(message_statistics.num_responses_received, 6);
+        assert_eq!(message_statistics.get_drop_rate(), Some(0.0));
+
+        // Verify the rate is computed over the window duration
+        assert_eq!(
+            message_statistics.messages_per_sec,
+            6.0 / WINDOW_DURATION.as_secs_f64()
+        );
+
+        // Verify the old events were garbage collected
+        assert_eq!(message_statistics_tracker.message_events.len(), 12);
+    }
+}
@@ -14,8 +14,10 @@ use key_value::PeerStateKey;
 use std::{collections::HashMap, time::Duration};
 use tokio::runtime::Handle;
 
+pub mod bandwidth_info;
 pub mod key_value;
 pub mod latency_info;
+mod message_statistics;
 pub mod network_info;
 pub mod node_info;
 pub mod peer_state;
@@ -36,9 +38,9 @@ pub fn refresh_peer_states(
     time_service: TimeService,
     runtime: Option<Handle>,
 ) -> Result<(), Error> {
-    // Process all state entries (in order) and update the ones that
-    // need to be refreshed for each peer.
-    for peer_state_key in PeerStateKey::get_all_keys() {
+    // Process all enabled state entries (in order) and update the
+    // ones that need to be refreshed for each peer.
+    for peer_state_key in PeerStateKey::get_enabled_keys(monitoring_service_config) {
         let mut num_in_flight_requests = 0;
 
         // Go through all connected peers and see if we should refresh the state
@@ -52,8 +54,9 @@ pub fn refresh_peer_states(
                 num_in_flight_requests += 1;
             }
 
-            // Update the state if it needs to be refreshed
-            let should_refresh_peer_state_key = request_tracker.read().new_request_required();
+            // Update the state if it needs to be refreshed (and the peer supports it)
+            let should_refresh_peer_state_key = request_tracker.read().new_request_required()
+                && peer_state.is_supported_by_peer(&peer_state_key)?;
             if should_refresh_peer_state_key {
                 peer_state.refresh_peer_state_key(
                     monitoring_service_config,
@@ -4,8 +4,10 @@
 use crate::{
     metrics, network,
     peer_states::{
+        bandwidth_info::BandwidthInfoState,
         key_value::{PeerStateKey, PeerStateValue, StateValueInterface},
         latency_info::LatencyInfoState,
+        message_statistics::MessageStatisticsTracker,
         network_info::NetworkInfoState,
         node_info::NodeInfoState,
         request_tracker::RequestTracker,
@@ -36,6 +38,7 @@ use tokio::{runtime::Handle, task::JoinHandle, time::sleep};
 
 #[derive(Clone, Debug)]
 pub struct PeerState {
+    message_statistics: Arc<RwLock<MessageStatisticsTracker>>, // The message rate and drop statistics for the peer
     state_entries: Arc<RwLock<HashMap<PeerStateKey, Arc<RwLock<PeerStateValue>>>>>, // The state entries for the peer
 }
 
@@ -51,7 +54,21 @@ impl PeerState {
                 .insert(peer_state_key, Arc::new(RwLock::new(peer_state_value)));
         }
 
-        Self { state_entries }
+        // Create the message statistics tracker
+        let message_statistics_window = Duration::from_millis(
+            node_config
+                .peer_monitoring_service
+                .message_statistics_window_ms,
+        );
+        let message_statistics = Arc::new(RwLock::new(MessageStatisticsTracker::new(
+            message_statistics_window,
+            time_service,
+        )));
+
+        Self {
+            message_statistics,
+            state_entries,
+        }
     }
 
     /// Returns the request tracker for the given peer state key
@@ -63,6 +80,12 @@ impl PeerState {
             .map(|peer_state_value| peer_state_value.read().get_request_tracker())
     }
 
+    /// Returns true iff the peer supports the requests for the given peer state key
+    pub fn is_supported_by_peer(&self, peer_state_key: &PeerStateKey) -> Result<bool, Error> {
+        self.get_peer_state_value(peer_state_key)
+            .map(|peer_state_value| peer_state_value.read().is_supported_by_peer())
+    }
+
     /// Refreshes the peer state key by sending a request to the peer
     pub fn refresh_peer_state_key(
         &self,
@@ -94,6 +117,9 @@ impl PeerState {
         // Get the max message size for the response
         let max_num_response_bytes = monitoring_service_config.max_num_response_bytes;
 
+        // Get the message statistics tracker for the peer
+        let message_statistics = self.message_statistics.clone();
+
         // Create the request task
         let request_task = async move {
             // Add some amount of jitter before sending the request.
@@ -104,6 +130,7 @@ impl PeerState {
             let start_time = time_service.now();
 
             // Send the request to the peer and wait for a response
+            message_statistics.write().record_request_sent();
             let request_id = request_id_generator.next();
             let monitoring_service_response = network::send_request_to_peer(
                 peer_monitoring_client,
@@ -124,6 +151,7 @@ impl PeerState {
             let monitoring_service_response = match monitoring_service_response {
                 Ok(monitoring_service_response) => monitoring_service_response,
                 Err(error) => {
+                    message_statistics.write().record_request_dropped();
                     peer_state_value
                         .write()
                         .handle_monitoring_service_response_error(&peer_network_id, error);
@@ -135,11 +163,13 @@ impl PeerState {
             if let Err(error) =
                 sanity_check_response_size(max_num_response_bytes, &monitoring_service_response)
             {
+                message_statistics.write().record_request_dropped();
                 peer_state_value
                     .write()
                     .handle_monitoring_service_response_error(&peer_network_id, error);
                 return;
             }
+            message_statistics.write().record_response_received();
 
             // Handle the monitoring service response
             peer_state_value.write().handle_monitoring_service_response(
@@ -210,6 +240,16 @@ impl PeerState {
         let node_info_response = node_info_state.get_latest_node_info_response();
         peer_monitoring_metadata.latest_node_info_response = node_info_response;
 
+        // Get and store the average bandwidth
+        let bandwidth_info_state = self.get_bandwidth_info_state()?;
+        let average_bandwidth_bytes_per_sec =
+            bandwidth_info_state.get_average_bandwidth_bytes_per_sec();
+        peer_monitoring_metadata.average_bandwidth_bytes_per_sec = average_bandwidth_bytes_per_sec;
+
+        // Get and store the message statistics
+        let message_statistics = self.message_statistics.read().get_message_statistics();
+        peer_monitoring_metadata.message_statistics = Some(message_statistics);
+
         Ok(peer_monitoring_metadata)
     }
 
@@ -227,6 +267,21 @@ impl PeerState {
         })
     }
 
+    /// Returns a copy of the bandwidth info state
+    pub(crate) fn get_bandwidth_info_state(&self) -> Result<BandwidthInfoState, Error> {
+        let peer_state_value = self
+            .get_peer_state_value(&PeerStateKey::BandwidthInfo)?
+            .read()
+            .clone();
+        match peer_state_value {
+            PeerStateValue::BandwidthInfoState(bandwidth_info_state) => Ok(bandwidth_info_state),
+            peer_state_value => Err(Error::UnexpectedError(format!(
+                "Invalid peer state value found! Expected bandwidth_info_state but got: {:?}",
+                peer_state_value
+            ))),
+        }
+    }
+
     /// Returns a copy of the latency ping state
     pub(crate) fn get_latency_info_state(&self) -> Result<LatencyInfoState, Error> {
         let peer_state_value = self
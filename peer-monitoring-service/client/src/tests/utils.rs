@@ -18,10 +18,11 @@ use aptos_network::application::{interface::NetworkClient, storage::PeersAndMeta
 use aptos_peer_monitoring_service_types::{
     request::{LatencyPingRequest, PeerMonitoringServiceRequest},
     response::{
-        ConnectionMetadata, LatencyPingResponse, NetworkInformationResponse,
-        NodeInformationResponse, PeerMonitoringServiceResponse, ServerProtocolVersionResponse,
+        BandwidthProbeResponse, ConnectionMetadata, LatencyPingResponse,
+        NetworkInformationResponse, NodeInformationResponse, PeerMonitoringServiceResponse,
+        ServerProtocolVersionResponse,
     },
-    PeerMonitoringServiceMessage,
+    PeerMonitoringServiceMessage, MIN_BANDWIDTH_PROBE_SERVER_VERSION,
 };
 use aptos_time_service::{MockTimeService, TimeService, TimeServiceTrait};
 use aptos_types::{network_address::NetworkAddress, PeerId};
@@ -277,7 +278,9 @@ pub async fn initialize_and_verify_peer_states(
     elapse_peer_monitor_interval(node_config.clone(), mock_time.clone()).await;
 
     // Verify the initial client requests and send responses
-    let num_expected_requests = PeerStateKey::get_all_keys().len() as u64;
+    let enabled_peer_state_keys =
+        PeerStateKey::get_enabled_keys(&node_config.peer_monitoring_service);
+    let num_expected_requests = enabled_peer_state_keys.len() as u64;
     verify_all_requests_and_respond(
         network_id,
         mock_monitoring_server,
@@ -292,7 +295,7 @@ pub async fn initialize_and_verify_peer_states(
         time_before_update,
         peer_monitor_state,
         peer_network_id,
-        PeerStateKey::get_all_keys(),
+        enabled_peer_state_keys,
     )
     .await;
 
@@ -626,6 +629,12 @@ pub async fn verify_all_requests_and_respond(
 
             // Process the peer monitoring request
             let response = match network_request.peer_monitoring_service_request {
+                PeerMonitoringServiceRequest::BandwidthProbe(bandwidth_probe) => {
+                    PeerMonitoringServiceResponse::BandwidthProbe(BandwidthProbeResponse {
+                        probe_counter: bandwidth_probe.probe_counter,
+                        payload: vec![0; bandwidth_probe.num_payload_bytes as usize],
+                    })
+                },
                 PeerMonitoringServiceRequest::GetNetworkInformation => {
                     PeerMonitoringServiceResponse::NetworkInformation(
                         network_information_response.clone().unwrap(),
@@ -636,6 +645,13 @@ pub async fn verify_all_requests_and_respond(
                         node_information_response.clone().unwrap(),
                     )
                 },
+                PeerMonitoringServiceRequest::GetServerProtocolVersion => {
+                    PeerMonitoringServiceResponse::ServerProtocolVersion(
+                        ServerProtocolVersionResponse {
+                            version: MIN_BANDWIDTH_PROBE_SERVER_VERSION,
+                        },
+                    )
+                },
                 PeerMonitoringServiceRequest::LatencyPing(latency_ping) => {
                     PeerMonitoringServiceResponse::LatencyPing(LatencyPingResponse {
                         ping_counter: latency_ping.ping_counter,
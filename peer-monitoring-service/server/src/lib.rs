@@ -11,16 +11,17 @@ use crate::{
 };
 use aptos_bounded_executor::BoundedExecutor;
 use aptos_config::{
-    config::{BaseConfig, NodeConfig},
+    config::{BandwidthMonitoringConfig, BaseConfig, NodeConfig},
     network_id::NetworkId,
 };
 use aptos_logger::prelude::*;
 use aptos_network::application::storage::PeersAndMetadata;
 use aptos_peer_monitoring_service_types::{
-    request::{LatencyPingRequest, PeerMonitoringServiceRequest},
+    request::{BandwidthProbeRequest, LatencyPingRequest, PeerMonitoringServiceRequest},
     response::{
-        ConnectionMetadata, LatencyPingResponse, NetworkInformationResponse,
-        NodeInformationResponse, PeerMonitoringServiceResponse, ServerProtocolVersionResponse,
+        BandwidthProbeResponse, ConnectionMetadata, LatencyPingResponse,
+        NetworkInformationResponse, NodeInformationResponse, PeerMonitoringServiceResponse,
+        ServerProtocolVersionResponse,
     },
     PeerMonitoringServiceError, Result, MAX_DISTANCE_FROM_VALIDATORS,
 };
@@ -40,10 +41,11 @@ pub mod storage;
 mod tests;
 
 /// Peer monitoring server constants
-pub const PEER_MONITORING_SERVER_VERSION: u64 = 1;
+pub const PEER_MONITORING_SERVER_VERSION: u64 = 2; // Version 2 added bandwidth probes
 
 /// The server-side actor for the peer monitoring service
 pub struct PeerMonitoringServiceServer<T> {
+    bandwidth_monitoring_config: BandwidthMonitoringConfig,
     base_config: BaseConfig,
     bounded_executor: BoundedExecutor,
     network_requests: PeerMonitoringServiceNetworkEvents,
@@ -62,6 +64,7 @@ impl<T: StorageReaderInterface> PeerMonitoringServiceServer<T> {
         storage: T,
         time_service: TimeService,
     ) -> Self {
+        let bandwidth_monitoring_config = node_config.peer_monitoring_service.bandwidth_monitoring;
         let base_config = node_config.base;
         let bounded_executor = BoundedExecutor::new(
             node_config.peer_monitoring_service.max_concurrent_requests as usize,
@@ -70,6 +73,7 @@ impl<T: StorageReaderInterface> PeerMonitoringServiceServer<T> {
         let start_time = time_service.now();
 
         Self {
+            bandwidth_monitoring_config,
             base_config,
             bounded_executor,
             network_requests,
@@ -97,6 +101,7 @@ impl<T: StorageReaderInterface> PeerMonitoringServiceServer<T> {
 
             // All handler methods are currently CPU-bound so we want
             // to spawn on the blocking thread pool.
+            let bandwidth_monitoring_config = self.bandwidth_monitoring_config;
             let base_config = self.base_config.clone();
             let peers_and_metadata = self.peers_and_metadata.clone();
             let start_time = self.start_time;
@@ -105,6 +110,7 @@ impl<T: StorageReaderInterface> PeerMonitoringServiceServer<T> {
             self.bounded_executor
                 .spawn_blocking(move || {
                     let response = Handler::new(
+                        bandwidth_monitoring_config,
                         base_config,
                         peers_and_metadata,
                         start_time,
@@ -128,6 +134,7 @@ impl<T: StorageReaderInterface> PeerMonitoringServiceServer<T> {
 /// request. We usually clone/create a new handler for every request.
 #[derive(Clone)]
 pub struct Handler<T> {
+    bandwidth_monitoring_config: BandwidthMonitoringConfig,
     base_config: BaseConfig,
     peers_and_metadata: Arc<PeersAndMetadata>,
     start_time: Instant,
@@ -137,6 +144,7 @@ pub struct Handler<T> {
 
 impl<T: StorageReaderInterface> Handler<T> {
     pub fn new(
+        bandwidth_monitoring_config: BandwidthMonitoringConfig,
         base_config: BaseConfig,
         peers_and_metadata: Arc<PeersAndMetadata>,
         start_time: Instant,
@@ -144,6 +152,7 @@ impl<T: StorageReaderInterface> Handler<T> {
         time_service: TimeService,
     ) -> Self {
         Self {
+            bandwidth_monitoring_config,
             base_config,
             peers_and_metadata,
             start_time,
@@ -173,6 +182,9 @@ impl<T: StorageReaderInterface> Handler<T> {
 
         // Process the request
         let response = match &request {
+            PeerMonitoringServiceRequest::BandwidthProbe(request) => {
+                self.handle_bandwidth_probe(request)
+            },
             PeerMonitoringServiceRequest::GetNetworkInformation => self.get_network_information(),
             PeerMonitoringServiceRequest::GetServerProtocolVersion => {
                 self.get_server_protocol_version()
@@ -280,6 +292,33 @@ impl<T: StorageReaderInterface> Handler<T> {
         ))
     }
 
+    fn handle_bandwidth_probe(
+        &self,
+        bandwidth_probe_request: &BandwidthProbeRequest,
+    ) -> Result<PeerMonitoringServiceResponse, Error> {
+        // Verify the requested payload size is within the allowed limit
+        let num_payload_bytes = bandwidth_probe_request.num_payload_bytes;
+        let max_payload_bytes = self
+            .bandwidth_monitoring_config
+            .max_bandwidth_probe_payload_bytes;
+        if num_payload_bytes > max_payload_bytes {
+            return Err(Error::InvalidRequest(format!(
+                "The requested bandwidth probe payload is too large: {:?}. Maximum allowed: {:?}",
+                num_payload_bytes, max_payload_bytes
+            )));
+        }
+
+        // Create and return the response. The payload contents are
+        // irrelevant, only the size is used to measure throughput.
+        let bandwidth_probe_response = BandwidthProbeResponse {
+            probe_counter: bandwidth_probe_request.probe_counter,
+            payload: vec![0; num_payload_bytes as usize],
+        };
+        Ok(PeerMonitoringServiceResponse::BandwidthProbe(
+            bandwidth_probe_response,
+        ))
+    }
+
     fn handle_latency_ping(
         &self,
         latency_ping_request: &LatencyPingRequest,
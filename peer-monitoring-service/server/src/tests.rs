@@ -10,7 +10,10 @@ use crate::{
 };
 use aptos_channels::{aptos_channel, message_queues::QueueStyle};
 use aptos_config::{
-    config::{BaseConfig, NodeConfig, PeerMonitoringServiceConfig, PeerRole, RoleType},
+    config::{
+        BandwidthMonitoringConfig, BaseConfig, NodeConfig, PeerMonitoringServiceConfig, PeerRole,
+        RoleType,
+    },
     network_id::{NetworkId, PeerNetworkId},
 };
 use aptos_crypto::HashValue;
@@ -30,7 +33,7 @@ use aptos_network::{
     transport::{ConnectionId, ConnectionMetadata},
 };
 use aptos_peer_monitoring_service_types::{
-    request::{LatencyPingRequest, PeerMonitoringServiceRequest},
+    request::{BandwidthProbeRequest, LatencyPingRequest, PeerMonitoringServiceRequest},
     response::{
         NetworkInformationResponse, NodeInformationResponse, PeerMonitoringServiceResponse,
         ServerProtocolVersionResponse,
@@ -74,6 +77,62 @@ use std::{
 // Useful test constants
 const LOCAL_HOST_NET_ADDR: &str = "/ip4/127.0.0.1/tcp/8081";
 
+#[tokio::test]
+async fn test_bandwidth_probe_request() {
+    // Create the peer monitoring client and server
+    let (mut mock_client, service, _, _) = MockClient::new(None, None, None);
+    tokio::spawn(service.start());
+
+    // Process several requests to perform bandwidth probes (of different sizes)
+    let max_payload_bytes = BandwidthMonitoringConfig::default().max_bandwidth_probe_payload_bytes;
+    for (i, num_payload_bytes) in [0, 1, 1024, max_payload_bytes].into_iter().enumerate() {
+        let request = PeerMonitoringServiceRequest::BandwidthProbe(BandwidthProbeRequest {
+            probe_counter: i as u64,
+            num_payload_bytes,
+        });
+        let response = mock_client.send_request(request).await.unwrap();
+        match response {
+            PeerMonitoringServiceResponse::BandwidthProbe(bandwidth_probe_response) => {
+                assert_eq!(bandwidth_probe_response.probe_counter, i as u64);
+                assert_eq!(
+                    bandwidth_probe_response.payload.len() as u64,
+                    num_payload_bytes
+                );
+            },
+            _ => panic!("Expected bandwidth probe response but got: {:?}", response),
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_bandwidth_probe_request_too_large() {
+    // Create a peer monitoring config with a small max probe size
+    let max_bandwidth_probe_payload_bytes = 100;
+    let peer_monitoring_config = PeerMonitoringServiceConfig {
+        bandwidth_monitoring: BandwidthMonitoringConfig {
+            max_bandwidth_probe_payload_bytes,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    // Create the peer monitoring client and server
+    let (mut mock_client, service, _, _) =
+        MockClient::new(None, Some(peer_monitoring_config), None);
+    tokio::spawn(service.start());
+
+    // Send a bandwidth probe that is too large and verify an error is returned
+    let request = PeerMonitoringServiceRequest::BandwidthProbe(BandwidthProbeRequest {
+        probe_counter: 0,
+        num_payload_bytes: max_bandwidth_probe_payload_bytes + 1,
+    });
+    let response = mock_client.send_request(request).await.unwrap_err();
+    assert!(matches!(
+        response,
+        PeerMonitoringServiceError::InvalidRequest(_)
+    ));
+}
+
 #[tokio::test]
 async fn test_get_server_protocol_version() {
     // Create the peer monitoring client and server
@@ -20,6 +20,7 @@ pub type Result<T, E = PeerMonitoringServiceError> = ::std::result::Result<T, E>
 
 /// Useful global constants
 pub const MAX_DISTANCE_FROM_VALIDATORS: u64 = 100; // Nodes that aren't connected to the network
+pub const MIN_BANDWIDTH_PROBE_SERVER_VERSION: u64 = 2; // The first server version that supports bandwidth probes
 
 /// An error that can be returned to the client on a failure to
 /// process a request.
@@ -48,6 +49,8 @@ pub struct PeerMonitoringMetadata {
     pub latest_network_info_response: Option<NetworkInformationResponse>, // The latest network info response
     pub latest_node_info_response: Option<NodeInformationResponse>, // The latest node info response
     pub internal_client_state: Option<String>, // A detailed client state string for debugging and logging
+    pub average_bandwidth_bytes_per_sec: Option<f64>, // The average measured throughput for the peer
+    pub message_statistics: Option<PeerMessageStatistics>, // The message rate and drop statistics for the peer
 }
 
 /// We must manually define this because f64 doesn't implement Eq. Instead,
@@ -68,20 +71,65 @@ impl PeerMonitoringMetadata {
             latest_network_info_response,
             latest_node_info_response,
             internal_client_state,
+            average_bandwidth_bytes_per_sec: None,
+            message_statistics: None,
         }
     }
 }
 
+/// The message statistics for a peer, as observed by the peer monitoring client
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct PeerMessageStatistics {
+    pub num_requests_sent: u64,      // The number of requests sent to the peer
+    pub num_responses_received: u64, // The number of (successful) responses received from the peer
+    pub num_requests_dropped: u64,   // The number of requests that failed or timed out
+    pub messages_per_sec: f64,       // The rate of responses received from the peer (per second)
+}
+
+impl PeerMessageStatistics {
+    /// Returns the ratio of dropped requests to completed requests.
+    /// If no requests have completed, None is returned.
+    pub fn get_drop_rate(&self) -> Option<f64> {
+        let num_completed_requests = self.get_num_completed_requests();
+        if num_completed_requests > 0 {
+            Some(self.num_requests_dropped as f64 / num_completed_requests as f64)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the number of completed (i.e., successful and dropped) requests
+    pub fn get_num_completed_requests(&self) -> u64 {
+        self.num_responses_received + self.num_requests_dropped
+    }
+}
+
+// Display formatting provides a high-level summary of the statistics
+impl Display for PeerMessageStatistics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{{ num_requests_sent: {:?}, num_responses_received: {:?}, num_requests_dropped: {:?}, messages_per_sec: {:?} }}",
+            self.num_requests_sent,
+            self.num_responses_received,
+            self.num_requests_dropped,
+            self.messages_per_sec,
+        )
+    }
+}
+
 // Display formatting includes basic monitoring metadata
 impl Display for PeerMonitoringMetadata {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
             f,
-            "{{ average_ping_latency_secs: {}, latest_ping_latency_secs: {}, latest_network_info_response: {}, latest_node_info_response: {} }}",
+            "{{ average_ping_latency_secs: {}, latest_ping_latency_secs: {}, latest_network_info_response: {}, latest_node_info_response: {}, average_bandwidth_bytes_per_sec: {}, message_statistics: {} }}",
             display_format_option(&self.average_ping_latency_secs),
             display_format_option(&self.latest_ping_latency_secs),
             display_format_option(&self.latest_network_info_response),
             display_format_option(&self.latest_node_info_response),
+            display_format_option(&self.average_bandwidth_bytes_per_sec),
+            display_format_option(&self.message_statistics),
         )
     }
 }
@@ -92,11 +140,13 @@ impl Debug for PeerMonitoringMetadata {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
             f,
-            "{{ average_ping_latency_secs: {}, latest_ping_latency_secs: {}, latest_network_info_response: {}, latest_node_info_response: {} }}",
+            "{{ average_ping_latency_secs: {}, latest_ping_latency_secs: {}, latest_network_info_response: {}, latest_node_info_response: {}, average_bandwidth_bytes_per_sec: {}, message_statistics: {} }}",
             debug_format_option(&self.average_ping_latency_secs),
             debug_format_option(&self.latest_ping_latency_secs),
             debug_format_option(&self.latest_network_info_response),
             debug_format_option(&self.latest_node_info_response),
+            debug_format_option(&self.average_bandwidth_bytes_per_sec),
+            debug_format_option(&self.message_statistics),
         )
     }
 }
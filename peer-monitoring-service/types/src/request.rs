@@ -10,12 +10,14 @@ pub enum PeerMonitoringServiceRequest {
     GetNodeInformation,       // Returns relevant node information about the peer
     GetServerProtocolVersion, // Fetches the protocol version run by the server
     LatencyPing(LatencyPingRequest), // A simple message used by the client to ensure liveness and measure latency
+    BandwidthProbe(BandwidthProbeRequest), // A request for a sized payload (used to measure throughput)
 }
 
 impl PeerMonitoringServiceRequest {
     /// Returns a summary label for the request
     pub fn get_label(&self) -> &'static str {
         match self {
+            Self::BandwidthProbe(_) => "bandwidth_probe",
             Self::GetNetworkInformation => "get_network_information",
             Self::GetNodeInformation => "get_node_information",
             Self::GetServerProtocolVersion => "get_server_protocol_version",
@@ -24,6 +26,13 @@ impl PeerMonitoringServiceRequest {
     }
 }
 
+/// The bandwidth probe request
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct BandwidthProbeRequest {
+    pub probe_counter: u64, // A monotonically increasing counter to verify probe responses
+    pub num_payload_bytes: u64, // The number of payload bytes the server should respond with
+}
+
 /// The latency ping request
 #[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
 pub struct LatencyPingRequest {
@@ -15,12 +15,14 @@ pub enum PeerMonitoringServiceResponse {
     NetworkInformation(NetworkInformationResponse), // Holds the response for network information
     NodeInformation(NodeInformationResponse), // Holds the response for node information
     ServerProtocolVersion(ServerProtocolVersionResponse), // Returns the current server protocol version
+    BandwidthProbe(BandwidthProbeResponse), // Holds the sized payload for bandwidth probes
 }
 
 impl PeerMonitoringServiceResponse {
     /// Returns a summary label for the response
     pub fn get_label(&self) -> &'static str {
         match self {
+            Self::BandwidthProbe(_) => "bandwidth_probe",
             Self::LatencyPing(_) => "latency_ping",
             Self::NetworkInformation(_) => "network_information",
             Self::NodeInformation(_) => "node_information",
@@ -41,6 +43,13 @@ impl PeerMonitoringServiceResponse {
     }
 }
 
+/// A response for the bandwidth probe request
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct BandwidthProbeResponse {
+    pub probe_counter: u64, // A monotonically increasing counter to verify probe responses
+    pub payload: Vec<u8>,   // The payload (of the requested size) used to measure throughput
+}
+
 /// A response for the latency ping request
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub struct LatencyPingResponse {
@@ -121,6 +130,20 @@ impl Display for NodeInformationResponse {
 #[error("Unexpected response variant: {0}")]
 pub struct UnexpectedResponseError(pub String);
 
+impl TryFrom<PeerMonitoringServiceResponse> for BandwidthProbeResponse {
+    type Error = UnexpectedResponseError;
+
+    fn try_from(response: PeerMonitoringServiceResponse) -> crate::Result<Self, Self::Error> {
+        match response {
+            PeerMonitoringServiceResponse::BandwidthProbe(inner) => Ok(inner),
+            _ => Err(UnexpectedResponseError(format!(
+                "expected bandwidth_probe_response, found {}",
+                response.get_label()
+            ))),
+        }
+    }
+}
+
 impl TryFrom<PeerMonitoringServiceResponse> for LatencyPingResponse {
     type Error = UnexpectedResponseError;
 
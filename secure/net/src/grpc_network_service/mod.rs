@@ -148,11 +148,14 @@ impl GRPCNetworkMessageServiceClientWrapper {
             message_type: mt.get_type(),
         });
         // TODO: Retry with exponential backoff on failures
+        // The message is dropped if the remote node is unreachable, instead of panicking, so that a
+        // crashed node does not take down the outbound handler (and all the other connections).
+        // Callers are expected to detect unresponsive nodes themselves, e.g. with timeouts.
         match self.remote_channel.simple_msg_exchange(request).await {
             Ok(_) => {},
             Err(e) => {
-                panic!(
-                    "Error '{}' sending message to {} on node {:?}",
+                error!(
+                    "Error '{}' sending message to {} on node {:?}, dropping the message",
                     e, self.remote_addr, sender_addr
                 );
             },
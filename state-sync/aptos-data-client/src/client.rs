@@ -36,13 +36,16 @@ use aptos_storage_service_types::{
     requests::{
         DataRequest, EpochEndingLedgerInfoRequest, NewTransactionOutputsWithProofRequest,
         NewTransactionsOrOutputsWithProofRequest, NewTransactionsWithProofRequest,
-        StateValuesWithProofRequest, StorageServiceRequest,
-        SubscribeTransactionOutputsWithProofRequest,
+        StateKeysSelector, StateValuesByKeysWithProofRequest, StateValuesWithProofRequest,
+        StorageServiceRequest, SubscribeTransactionOutputsWithProofRequest,
         SubscribeTransactionsOrOutputsWithProofRequest, SubscribeTransactionsWithProofRequest,
         SubscriptionStreamMetadata, TransactionOutputsWithProofRequest,
         TransactionsOrOutputsWithProofRequest, TransactionsWithProofRequest,
     },
-    responses::{StorageServerSummary, StorageServiceResponse, TransactionOrOutputListWithProof},
+    responses::{
+        StateValuesByKeysWithProof, StorageServerSummary, StorageServiceResponse,
+        TransactionOrOutputListWithProof,
+    },
     Epoch, StorageServiceMessage,
 };
 use aptos_time_service::TimeService;
@@ -1013,6 +1016,61 @@ impl AptosDataClientInterface for AptosDataClient {
             .await
     }
 
+    async fn get_state_values_by_keys_with_proof(
+        &self,
+        version: u64,
+        proof_ledger_info: LedgerInfoWithSignatures,
+        state_keys_selector: StateKeysSelector,
+        request_timeout_ms: u64,
+    ) -> crate::error::Result<Response<StateValuesByKeysWithProof>> {
+        let data_request =
+            DataRequest::GetStateValuesByKeysWithProof(StateValuesByKeysWithProofRequest {
+                version,
+                proof_version: proof_ledger_info.ledger_info().version(),
+                state_keys_selector: state_keys_selector.clone(),
+            });
+        let response: Response<StateValuesByKeysWithProof> = self
+            .create_and_send_storage_request(request_timeout_ms, data_request)
+            .await?;
+
+        // Verify the state values are for the requested version and state keys
+        let state_keys_result = if response.payload.version != version {
+            Err(format!(
+                "Unexpected state values version! Expected: {}, found: {}",
+                version, response.payload.version
+            ))
+        } else {
+            response
+                .payload
+                .verify_state_keys(&state_keys_selector)
+                .map_err(|error| format!("{:?}", error))
+        };
+        if let Err(error) = state_keys_result {
+            response
+                .context
+                .response_callback
+                .notify_bad_response(ResponseError::InvalidData);
+            return Err(Error::InvalidResponse(format!(
+                "Unexpected state values by keys! Error: {}",
+                error
+            )));
+        }
+
+        // Verify the state values against the trusted ledger info
+        if let Err(error) = response.payload.verify(proof_ledger_info.ledger_info()) {
+            response
+                .context
+                .response_callback
+                .notify_bad_response(ResponseError::ProofVerificationError);
+            return Err(Error::InvalidResponse(format!(
+                "Failed to verify the state values by keys! Error: {:?}",
+                error
+            )));
+        }
+
+        Ok(response)
+    }
+
     async fn get_transaction_outputs_with_proof(
         &self,
         proof_version: Version,
@@ -2,7 +2,11 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{error, error::Error, global_summary::GlobalDataSummary};
-use aptos_storage_service_types::{responses::TransactionOrOutputListWithProof, Epoch};
+use aptos_storage_service_types::{
+    requests::StateKeysSelector,
+    responses::{StateValuesByKeysWithProof, TransactionOrOutputListWithProof},
+    Epoch,
+};
 use aptos_types::{
     ledger_info::LedgerInfoWithSignatures,
     state_store::state_value::StateValueChunkWithProof,
@@ -87,6 +91,24 @@ pub trait AptosDataClientInterface {
         request_timeout_ms: u64,
     ) -> error::Result<Response<StateValueChunkWithProof>>;
 
+    /// Fetches a set of state values (with a proof) at the specified version.
+    /// The version must be a state checkpoint. The state keys are identified
+    /// by the given selector (e.g., an explicit set of keys, or the keys of a
+    /// single account). The response is verified against the given (trusted)
+    /// ledger info, which must be at or after the version, and checked to only
+    /// contain the selected keys. Note: for an account, the proofs cannot show
+    /// that all of the account's state keys were returned. In some cases, fewer
+    /// state values may be returned (e.g., to tolerate network or chunk limits),
+    /// and the response will contain the next state key to fetch. If the data
+    /// cannot be fetched or verified, an error is returned.
+    async fn get_state_values_by_keys_with_proof(
+        &self,
+        version: u64,
+        proof_ledger_info: LedgerInfoWithSignatures,
+        state_keys_selector: StateKeysSelector,
+        request_timeout_ms: u64,
+    ) -> error::Result<Response<StateValuesByKeysWithProof>>;
+
     /// Fetches a transaction output list with proof, with transaction
     /// outputs from start to end versions (inclusive). The proof is relative
     /// to the specified `proof_version`. In some cases, fewer outputs may be
@@ -10,6 +10,13 @@ use aptos_network::application::storage::PeersAndMetadata;
 use itertools::Itertools;
 use std::sync::Arc;
 
+// Peers that drop more than this ratio of monitoring requests are demoted
+const MAX_MESSAGE_DROP_RATE: f64 = 0.25;
+// The min number of completed monitoring requests before the drop rate is considered
+const MIN_COMPLETED_REQUESTS_FOR_DROP_RATE: u64 = 10;
+// Peers with a measured bandwidth below this value (bytes per second) are demoted
+const MIN_BANDWIDTH_BYTES_PER_SEC: f64 = 100.0 * 1024.0; // 100 KB/s
+
 /// A simple enum containing the different categories for peer prioritization.
 ///
 /// Note: If another priority is added to this enum, it should also be added
@@ -46,14 +53,40 @@ impl PeerPriority {
     pub fn is_high_priority(&self) -> bool {
         matches!(self, Self::HighPriority)
     }
+
+    /// Returns the priority one level below this priority
+    /// (the lowest priority cannot be demoted further).
+    pub fn demote(&self) -> PeerPriority {
+        match self {
+            Self::HighPriority => Self::MediumPriority,
+            Self::MediumPriority | Self::LowPriority => Self::LowPriority,
+        }
+    }
 }
 
 /// Returns the priority for the specified peer, according
-/// to the node's config and the peer metadata.
+/// to the node's config and the peer metadata. Peers that
+/// are unreliable (i.e., frequently drop requests or have
+/// low measured bandwidth) are demoted by a single level.
 pub fn get_peer_priority(
     base_config: Arc<BaseConfig>,
     peers_and_metadata: Arc<PeersAndMetadata>,
     peer: &PeerNetworkId,
+) -> PeerPriority {
+    let peer_priority = get_base_peer_priority(base_config, peers_and_metadata.clone(), peer);
+    if is_unreliable_peer(&peers_and_metadata, peer) {
+        peer_priority.demote()
+    } else {
+        peer_priority
+    }
+}
+
+/// Returns the base priority for the specified peer, according to
+/// the node's config and the peer's network and connection metadata.
+fn get_base_peer_priority(
+    base_config: Arc<BaseConfig>,
+    peers_and_metadata: Arc<PeersAndMetadata>,
+    peer: &PeerNetworkId,
 ) -> PeerPriority {
     // Handle the case that this node is a validator
     let peer_network_id = peer.network_id();
@@ -121,6 +154,38 @@ pub fn get_peer_priority(
     }
 }
 
+/// Returns true iff the given peer is unreliable, according to the
+/// peer monitoring metadata (i.e., the peer drops too many messages,
+/// or the peer's measured bandwidth is too low).
+fn is_unreliable_peer(peers_and_metadata: &Arc<PeersAndMetadata>, peer: &PeerNetworkId) -> bool {
+    // Get the peer monitoring metadata (if no metadata exists, we can't tell)
+    let peer_metadata = match peers_and_metadata.get_metadata_for_peer(*peer) {
+        Ok(peer_metadata) => peer_metadata,
+        Err(_) => return false,
+    };
+    let peer_monitoring_metadata = peer_metadata.get_peer_monitoring_metadata();
+
+    // Check if the peer drops too many messages
+    if let Some(message_statistics) = &peer_monitoring_metadata.message_statistics {
+        if message_statistics.get_num_completed_requests() >= MIN_COMPLETED_REQUESTS_FOR_DROP_RATE {
+            if let Some(drop_rate) = message_statistics.get_drop_rate() {
+                if drop_rate > MAX_MESSAGE_DROP_RATE {
+                    return true;
+                }
+            }
+        }
+    }
+
+    // Check if the peer bandwidth is too low
+    if let Some(bandwidth) = peer_monitoring_metadata.average_bandwidth_bytes_per_sec {
+        if bandwidth < MIN_BANDWIDTH_BYTES_PER_SEC {
+            return true;
+        }
+    }
+
+    false
+}
+
 /// Returns true iff the given peer is a trusted peer
 fn is_trusted_peer(peers_and_metadata: Arc<PeersAndMetadata>, peer: &PeerNetworkId) -> bool {
     peers_and_metadata
@@ -140,13 +205,17 @@ pub fn is_high_priority_peer(
 
 #[cfg(test)]
 mod tests {
-    use crate::priority::{get_peer_priority, is_high_priority_peer, PeerPriority};
+    use crate::priority::{
+        get_peer_priority, is_high_priority_peer, PeerPriority, MAX_MESSAGE_DROP_RATE,
+        MIN_BANDWIDTH_BYTES_PER_SEC, MIN_COMPLETED_REQUESTS_FOR_DROP_RATE,
+    };
     use aptos_config::{
         config::{BaseConfig, Peer, PeerRole, RoleType},
         network_id::{NetworkId, PeerNetworkId},
     };
     use aptos_netcore::transport::ConnectionOrigin;
     use aptos_network::{application::storage::PeersAndMetadata, transport::ConnectionMetadata};
+    use aptos_peer_monitoring_service_types::{PeerMessageStatistics, PeerMonitoringMetadata};
     use aptos_types::PeerId;
     use maplit::hashmap;
     use std::{assert_eq, sync::Arc};
@@ -465,6 +534,87 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_unreliable_peer_priorities() {
+        // Create a base config for a PFN
+        let base_config = Arc::new(BaseConfig {
+            role: RoleType::FullNode,
+            ..Default::default()
+        });
+
+        // Create a peers and metadata struct with the public networks registered
+        let peers_and_metadata = PeersAndMetadata::new(&[NetworkId::Public]);
+
+        // Create a PFN peer (with an outbound connection) and verify it is highly prioritized
+        let pfn_peer = PeerNetworkId::new(NetworkId::Public, PeerId::random());
+        create_connection_metadata(&peers_and_metadata, pfn_peer, ConnectionOrigin::Outbound);
+        assert_eq!(
+            get_peer_priority(base_config.clone(), peers_and_metadata.clone(), &pfn_peer),
+            PeerPriority::HighPriority
+        );
+
+        // Update the peer with a high drop rate (but too few requests) and verify it isn't demoted
+        let num_requests_dropped = MIN_COMPLETED_REQUESTS_FOR_DROP_RATE - 1;
+        update_message_statistics(&peers_and_metadata, pfn_peer, 0, num_requests_dropped);
+        assert_eq!(
+            get_peer_priority(base_config.clone(), peers_and_metadata.clone(), &pfn_peer),
+            PeerPriority::HighPriority
+        );
+
+        // Update the peer with a high drop rate and verify it is demoted
+        let num_completed_requests = MIN_COMPLETED_REQUESTS_FOR_DROP_RATE * 10;
+        let num_requests_dropped =
+            (num_completed_requests as f64 * (MAX_MESSAGE_DROP_RATE + 0.1)) as u64;
+        update_message_statistics(
+            &peers_and_metadata,
+            pfn_peer,
+            num_completed_requests - num_requests_dropped,
+            num_requests_dropped,
+        );
+        assert_eq!(
+            get_peer_priority(base_config.clone(), peers_and_metadata.clone(), &pfn_peer),
+            PeerPriority::MediumPriority
+        );
+
+        // Update the peer with a low drop rate and verify it is highly prioritized again
+        update_message_statistics(&peers_and_metadata, pfn_peer, num_completed_requests, 0);
+        assert_eq!(
+            get_peer_priority(base_config.clone(), peers_and_metadata.clone(), &pfn_peer),
+            PeerPriority::HighPriority
+        );
+
+        // Update the peer with a low bandwidth and verify it is demoted
+        update_average_bandwidth(
+            &peers_and_metadata,
+            pfn_peer,
+            MIN_BANDWIDTH_BYTES_PER_SEC / 2.0,
+        );
+        assert_eq!(
+            get_peer_priority(base_config.clone(), peers_and_metadata.clone(), &pfn_peer),
+            PeerPriority::MediumPriority
+        );
+
+        // Update the peer with a high bandwidth and verify it is highly prioritized again
+        update_average_bandwidth(
+            &peers_and_metadata,
+            pfn_peer,
+            MIN_BANDWIDTH_BYTES_PER_SEC * 2.0,
+        );
+        assert_eq!(
+            get_peer_priority(base_config.clone(), peers_and_metadata.clone(), &pfn_peer),
+            PeerPriority::HighPriority
+        );
+
+        // Create an inbound PFN peer with low bandwidth and verify it remains low prioritized
+        let pfn_peer = PeerNetworkId::new(NetworkId::Public, PeerId::random());
+        create_connection_metadata(&peers_and_metadata, pfn_peer, ConnectionOrigin::Inbound);
+        update_average_bandwidth(&peers_and_metadata, pfn_peer, 0.0);
+        assert_eq!(
+            get_peer_priority(base_config.clone(), peers_and_metadata.clone(), &pfn_peer),
+            PeerPriority::LowPriority
+        );
+    }
+
     /// Adds the given peer to the trusted peers set
     fn add_to_trusted_peers(peers_and_metadata: &Arc<PeersAndMetadata>, peer: PeerNetworkId) {
         peers_and_metadata
@@ -496,4 +646,41 @@ mod tests {
             )
             .unwrap();
     }
+
+    /// Updates the peer monitoring metadata for the specified peer with the given bandwidth
+    fn update_average_bandwidth(
+        peers_and_metadata: &Arc<PeersAndMetadata>,
+        peer: PeerNetworkId,
+        average_bandwidth_bytes_per_sec: f64,
+    ) {
+        let peer_monitoring_metadata = PeerMonitoringMetadata {
+            average_bandwidth_bytes_per_sec: Some(average_bandwidth_bytes_per_sec),
+            ..Default::default()
+        };
+        peers_and_metadata
+            .update_peer_monitoring_metadata(peer, peer_monitoring_metadata)
+            .unwrap();
+    }
+
+    /// Updates the peer monitoring metadata for the specified peer with the given message statistics
+    fn update_message_statistics(
+        peers_and_metadata: &Arc<PeersAndMetadata>,
+        peer: PeerNetworkId,
+        num_responses_received: u64,
+        num_requests_dropped: u64,
+    ) {
+        let message_statistics = PeerMessageStatistics {
+            num_requests_sent: num_responses_received + num_requests_dropped,
+            num_responses_received,
+            num_requests_dropped,
+            messages_per_sec: 1.0,
+        };
+        let peer_monitoring_metadata = PeerMonitoringMetadata {
+            message_statistics: Some(message_statistics),
+            ..Default::default()
+        };
+        peers_and_metadata
+            .update_peer_monitoring_metadata(peer, peer_monitoring_metadata)
+            .unwrap();
+    }
 }
@@ -0,0 +1,179 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    error::Error,
+    interface::AptosDataClientInterface,
+    poller,
+    priority::PeerPriority,
+    tests::{mock::MockNetwork, utils},
+};
+use aptos_config::config::AptosDataClientConfig;
+use aptos_crypto::{hash::CryptoHash, HashValue};
+use aptos_storage_service_types::{
+    requests::{DataRequest, StateKeysSelector},
+    responses::{
+        CompleteDataRange, DataResponse, StateValuesByKeysWithProof, StorageServiceResponse,
+    },
+};
+use aptos_types::{
+    aggregate_signature::AggregateSignature,
+    block_info::BlockInfo,
+    ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
+    proof::{
+        SparseMerkleLeafNode, SparseMerkleMultiProof, SparseMerkleMultiProofLeaf,
+        TransactionAccumulatorProof, TransactionInfoWithProof,
+    },
+    state_store::{state_key::StateKey, state_value::StateValue},
+    transaction::{ExecutionStatus, TransactionInfo},
+};
+use claims::assert_matches;
+
+#[tokio::test]
+async fn state_values_by_keys_verification() {
+    // Create a base config for a validator
+    let base_config = utils::create_validator_base_config();
+    let data_client_config = AptosDataClientConfig::default();
+
+    // Ensure the properties hold for valid and invalid responses (i.e., with
+    // an invalid state value, version or state key)
+    for invalid_field in [
+        None,
+        Some("state_value"),
+        Some("version"),
+        Some("state_key"),
+    ] {
+        // Create the mock network, mock time, client and poller
+        let (mut mock_network, mut mock_time, client, poller) =
+            MockNetwork::new(Some(base_config.clone()), Some(data_client_config), None);
+
+        // Start the poller
+        tokio::spawn(poller::start_poller(poller));
+
+        // Add a connected peer
+        let (_, network_id) =
+            utils::add_peer_to_network(PeerPriority::HighPriority, &mut mock_network);
+
+        // Advance time so the poller sends a data summary request
+        utils::advance_polling_timer(&mut mock_time, &data_client_config).await;
+
+        // Receive their request and respond with a summary that advertises the states
+        let highest_synced_version = 0;
+        let network_request = utils::get_network_request(&mut mock_network, network_id).await;
+        let mut storage_summary = utils::create_storage_summary(highest_synced_version);
+        storage_summary.data_summary.states =
+            Some(CompleteDataRange::new(0, highest_synced_version).unwrap());
+        utils::handle_storage_summary_request(network_request, storage_summary);
+
+        // Wait for the poller to process the response
+        let transaction_range = CompleteDataRange::new(0, highest_synced_version).unwrap();
+        utils::wait_for_transaction_advertisement(
+            &client,
+            &mut mock_time,
+            &data_client_config,
+            transaction_range,
+        )
+        .await;
+
+        // Create the state values response (and the ledger info that proves it)
+        let (state_values_by_keys, ledger_info) = create_state_values_by_keys_response();
+        let state_keys: Vec<_> = state_values_by_keys
+            .state_values
+            .iter()
+            .map(|(state_key, _)| state_key.clone())
+            .collect();
+
+        // Tamper with the response if it should be invalid
+        let mut response_state_values_by_keys = state_values_by_keys.clone();
+        match invalid_field {
+            Some("state_value") => response_state_values_by_keys.state_values[0].1 = None,
+            Some("version") => response_state_values_by_keys.version = 1,
+            Some("state_key") => {
+                response_state_values_by_keys.state_values[0].0 =
+                    StateKey::raw(b"unrequested_state_key")
+            },
+            _ => {},
+        }
+
+        // Handle the client's state values request
+        tokio::spawn(async move {
+            loop {
+                // Fulfill the request if it is for the state values
+                let network_request =
+                    utils::get_network_request(&mut mock_network, network_id).await;
+                if let DataRequest::GetStateValuesByKeysWithProof(request) =
+                    &network_request.storage_service_request.data_request
+                {
+                    assert_eq!(request.proof_version, highest_synced_version);
+                    let data_response = DataResponse::StateValuesByKeysWithProof(
+                        response_state_values_by_keys.clone(),
+                    );
+                    network_request
+                        .response_sender
+                        .send(Ok(StorageServiceResponse::new(
+                            data_response,
+                            network_request.storage_service_request.use_compression,
+                        )
+                        .unwrap()));
+                }
+            }
+        });
+
+        // Send the request and verify the response
+        let request_timeout = data_client_config.response_timeout_ms;
+        let response = client
+            .get_state_values_by_keys_with_proof(
+                highest_synced_version,
+                ledger_info,
+                StateKeysSelector::StateKeys(state_keys),
+                request_timeout,
+            )
+            .await;
+        if invalid_field.is_none() {
+            assert_eq!(response.unwrap().payload, state_values_by_keys);
+        } else {
+            assert_matches!(response.unwrap_err(), Error::InvalidResponse(_));
+        }
+    }
+}
+
+/// Creates a response with a single state value (at version 0) and the
+/// ledger info that proves it.
+fn create_state_values_by_keys_response() -> (StateValuesByKeysWithProof, LedgerInfoWithSignatures)
+{
+    // Create a state tree with a single state value (the leaf is the root)
+    let state_key = StateKey::raw(b"state_key");
+    let state_value = StateValue::from(vec![0; 10]);
+    let leaf = SparseMerkleLeafNode::new(state_key.hash(), state_value.hash());
+    let proof =
+        SparseMerkleMultiProof::new(vec![SparseMerkleMultiProofLeaf::new(0, Some(leaf))], vec![]);
+
+    // Create the transaction info at version 0 (the root of the accumulator)
+    let transaction_info = TransactionInfo::new(
+        HashValue::random(),
+        HashValue::random(),
+        HashValue::random(),
+        Some(leaf.hash()),
+        0,
+        ExecutionStatus::Success,
+    );
+    let ledger_info = LedgerInfoWithSignatures::new(
+        LedgerInfo::new(
+            BlockInfo::new(0, 0, HashValue::zero(), transaction_info.hash(), 0, 0, None),
+            HashValue::zero(),
+        ),
+        AggregateSignature::empty(),
+    );
+    let transaction_info_with_proof =
+        TransactionInfoWithProof::new(TransactionAccumulatorProof::new(vec![]), transaction_info);
+
+    // Create the response
+    let state_values_by_keys = StateValuesByKeysWithProof::new(
+        0,
+        vec![(state_key, Some(state_value))],
+        proof,
+        transaction_info_with_proof,
+        None,
+    );
+    (state_values_by_keys, ledger_info)
+}
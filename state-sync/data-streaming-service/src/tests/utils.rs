@@ -18,12 +18,13 @@ use aptos_storage_service_types::{
     requests::{
         DataRequest, EpochEndingLedgerInfoRequest, NewTransactionOutputsWithProofRequest,
         NewTransactionsOrOutputsWithProofRequest, NewTransactionsWithProofRequest,
-        StateValuesWithProofRequest, SubscribeTransactionOutputsWithProofRequest,
+        StateKeysSelector, StateValuesByKeysWithProofRequest, StateValuesWithProofRequest,
+        SubscribeTransactionOutputsWithProofRequest,
         SubscribeTransactionsOrOutputsWithProofRequest, SubscribeTransactionsWithProofRequest,
         SubscriptionStreamMetadata, TransactionOutputsWithProofRequest,
         TransactionsOrOutputsWithProofRequest, TransactionsWithProofRequest,
     },
-    responses::{CompleteDataRange, TransactionOrOutputListWithProof},
+    responses::{CompleteDataRange, StateValuesByKeysWithProof, TransactionOrOutputListWithProof},
     Epoch,
 };
 use aptos_types::{
@@ -33,15 +34,18 @@ use aptos_types::{
     chain_id::ChainId,
     epoch_state::EpochState,
     ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
-    proof::SparseMerkleRangeProof,
+    proof::{
+        SparseMerkleMultiProof, SparseMerkleRangeProof, TransactionAccumulatorProof,
+        TransactionInfoWithProof,
+    },
     state_store::{
         state_key::StateKey,
         state_value::{StateValue, StateValueChunkWithProof},
     },
     transaction::{
-        RawTransaction, Script, SignedTransaction, Transaction, TransactionAuxiliaryData,
-        TransactionListWithProof, TransactionOutput, TransactionOutputListWithProof,
-        TransactionPayload, TransactionStatus, Version,
+        ExecutionStatus, RawTransaction, Script, SignedTransaction, Transaction,
+        TransactionAuxiliaryData, TransactionInfo, TransactionListWithProof, TransactionOutput,
+        TransactionOutputListWithProof, TransactionPayload, TransactionStatus, Version,
     },
     write_set::WriteSet,
 };
@@ -327,6 +331,48 @@ impl AptosDataClientInterface for MockAptosDataClient {
         Ok(create_data_client_response(state_value_chunk_with_proof))
     }
 
+    async fn get_state_values_by_keys_with_proof(
+        &self,
+        version: Version,
+        proof_ledger_info: LedgerInfoWithSignatures,
+        state_keys_selector: StateKeysSelector,
+        request_timeout_ms: u64,
+    ) -> Result<Response<StateValuesByKeysWithProof>, aptos_data_client::error::Error> {
+        // Verify the request timeout
+        let data_request =
+            DataRequest::GetStateValuesByKeysWithProof(StateValuesByKeysWithProofRequest {
+                version,
+                proof_version: proof_ledger_info.ledger_info().version(),
+                state_keys_selector,
+            });
+        self.verify_request_timeout_value(request_timeout_ms, false, false, data_request);
+
+        // Emulate network latencies
+        self.emulate_network_latencies().await;
+
+        // Create and send a data client response (the streaming
+        // service does not use this request, so no data is returned).
+        let transaction_info = TransactionInfo::new(
+            HashValue::zero(),
+            HashValue::zero(),
+            HashValue::zero(),
+            Some(HashValue::zero()),
+            0,
+            ExecutionStatus::Success,
+        );
+        let state_values_by_keys_with_proof = StateValuesByKeysWithProof::new(
+            version,
+            vec![],
+            SparseMerkleMultiProof::default(),
+            TransactionInfoWithProof::new(
+                TransactionAccumulatorProof::new(vec![]),
+                transaction_info,
+            ),
+            None,
+        );
+        Ok(create_data_client_response(state_values_by_keys_with_proof))
+    }
+
     async fn get_epoch_ending_ledger_infos(
         &self,
         start_epoch: Epoch,
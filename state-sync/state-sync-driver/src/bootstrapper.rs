@@ -6,10 +6,11 @@ use crate::{
     driver::DriverConfiguration,
     error::Error,
     logging::{LogEntry, LogSchema},
-    metadata_storage::MetadataStorageInterface,
+    metadata_storage::{DataStreamType, MetadataStorageInterface},
     metrics,
     metrics::ExecutingComponent,
     storage_synchronizer::{NotificationMetadata, StorageSynchronizerInterface},
+    stream_progress_tracker::StreamProgressTracker,
     utils,
     utils::{OutputFallbackHandler, SpeculativeStreamState, PENDING_DATA_LOG_FREQ_SECS},
 };
@@ -22,6 +23,7 @@ use aptos_data_streaming_service::{
 };
 use aptos_logger::{prelude::*, sample::SampleRate};
 use aptos_storage_interface::DbReader;
+use aptos_time_service::TimeService;
 use aptos_types::{
     epoch_change::Verifier,
     epoch_state::EpochState,
@@ -129,6 +131,31 @@ impl VerifiedEpochStates {
         self.verify_waypoint(epoch_ending_ledger_info, waypoint)
     }
 
+    /// Restores the given epoch ending ledger infos (e.g., that were verified and
+    /// persisted before a reboot). Each ledger info is re-verified against the latest
+    /// epoch state, and restoration stops at the first ledger info that fails.
+    /// Returns the number of restored ledger infos.
+    pub fn restore_epoch_ending_ledger_infos(
+        &mut self,
+        epoch_ending_ledger_infos: Vec<LedgerInfoWithSignatures>,
+        waypoint: &Waypoint,
+    ) -> usize {
+        let mut num_restored_ledger_infos = 0;
+        for epoch_ending_ledger_info in epoch_ending_ledger_infos {
+            if let Err(error) =
+                self.update_verified_epoch_states(&epoch_ending_ledger_info, waypoint)
+            {
+                warn!(LogSchema::new(LogEntry::Bootstrapper).message(&format!(
+                    "Failed to restore a persisted epoch ending ledger info! Ledger info: {:?}, error: {:?}",
+                    epoch_ending_ledger_info, error
+                )));
+                break;
+            }
+            num_restored_ledger_infos += 1;
+        }
+        num_restored_ledger_infos
+    }
+
     /// Attempts to verify the waypoint using the new epoch ending ledger info
     fn verify_waypoint(
         &mut self,
@@ -321,6 +348,9 @@ pub struct Bootstrapper<MetadataStorage, StorageSyncer, StreamingClient> {
     // The storage synchronizer used to update local storage
     storage_synchronizer: StorageSyncer,
 
+    // The tracker for the progress of long-running data streams
+    stream_progress_tracker: StreamProgressTracker<MetadataStorage>,
+
     // The epoch states verified by this node (held in memory)
     verified_epoch_states: VerifiedEpochStates,
 }
@@ -338,11 +368,41 @@ impl<
         streaming_client: StreamingClient,
         storage: Arc<dyn DbReader>,
         storage_synchronizer: StorageSyncer,
+        time_service: TimeService,
     ) -> Self {
         // Load the latest epoch state from storage
         let latest_epoch_state = utils::fetch_latest_epoch_state(storage.clone())
             .expect("Unable to fetch latest epoch state!");
-        let verified_epoch_states = VerifiedEpochStates::new(latest_epoch_state);
+        let latest_epoch = latest_epoch_state.epoch;
+        let mut verified_epoch_states = VerifiedEpochStates::new(latest_epoch_state);
+
+        // Restore any epoch ending ledger infos verified before a reboot
+        let stream_progress_tracker =
+            StreamProgressTracker::new(metadata_storage.clone(), time_service);
+        let epoch_ending_ledger_infos = stream_progress_tracker
+            .get_verified_epoch_ending_ledger_infos(latest_epoch)
+            .expect("Unable to fetch the persisted epoch ending ledger infos!");
+        if !epoch_ending_ledger_infos.is_empty() {
+            let num_restored_ledger_infos = verified_epoch_states
+                .restore_epoch_ending_ledger_infos(
+                    epoch_ending_ledger_infos,
+                    &driver_configuration.waypoint,
+                );
+            info!(LogSchema::new(LogEntry::Bootstrapper).message(&format!(
+                "Restored {:?} persisted epoch ending ledger infos (starting at epoch: {:?})",
+                num_restored_ledger_infos, latest_epoch
+            )));
+        }
+
+        // Prune any persisted epoch ending ledger infos that have already been committed
+        if let Err(error) =
+            stream_progress_tracker.prune_committed_epoch_ending_ledger_infos(latest_epoch)
+        {
+            warn!(LogSchema::new(LogEntry::Bootstrapper).message(&format!(
+                "Failed to prune the persisted epoch ending ledger infos! Error: {:?}",
+                error
+            )));
+        }
 
         Self {
             state_value_syncer: StateValueSyncer::new(),
@@ -356,6 +416,7 @@ impl<
             streaming_client,
             storage,
             storage_synchronizer,
+            stream_progress_tracker,
             verified_epoch_states,
         }
     }
@@ -375,6 +436,13 @@ impl<
         info!(LogSchema::new(LogEntry::Bootstrapper)
             .message("The node has successfully bootstrapped!"));
         self.bootstrapped = true;
+        self.stream_progress_tracker.stop_stream();
+
+        // All verified epoch ending ledger infos have now been committed, so prune them
+        let latest_epoch = utils::fetch_latest_epoch_state(self.storage.clone())?.epoch;
+        self.stream_progress_tracker
+            .prune_committed_epoch_ending_ledger_infos(latest_epoch)?;
+
         self.notify_listeners_if_bootstrapped().await
     }
 
@@ -425,6 +493,7 @@ impl<
         if self.active_data_stream.is_some() {
             // We have an active data stream. Process any notifications!
             self.process_active_stream_notifications().await?;
+            self.update_transaction_stream_progress()?;
         } else if self.storage_synchronizer.pending_storage_data() {
             // Wait for any pending data to be processed
             sample!(
@@ -441,6 +510,23 @@ impl<
         self.notify_listeners_if_bootstrapped().await
     }
 
+    /// Updates the progress of any active transaction (or output) stream
+    /// using the latest ledger info committed to storage.
+    fn update_transaction_stream_progress(&mut self) -> Result<(), Error> {
+        let is_transaction_stream = self
+            .stream_progress_tracker
+            .get_active_stream_progress()
+            .map(|progress| progress.data_stream_type == DataStreamType::TransactionsOrOutputs)
+            .unwrap_or(false);
+        if is_transaction_stream {
+            let latest_synced_ledger_info =
+                utils::fetch_latest_synced_ledger_info(self.storage.clone())?;
+            self.stream_progress_tracker
+                .record_committed_transactions(latest_synced_ledger_info.ledger_info().version())?;
+        }
+        Ok(())
+    }
+
     /// Returns true iff the bootstrapper should continue to fetch epoch ending
     /// ledger infos (in order to make progress).
     fn should_fetch_epoch_ending_ledger_infos(&self) -> bool {
@@ -806,6 +892,13 @@ impl<
         ));
         self.active_data_stream = Some(data_stream);
 
+        // Track the progress of the stream (resuming any persisted progress)
+        self.stream_progress_tracker.start_stream(
+            DataStreamType::TransactionsOrOutputs,
+            next_version,
+            highest_known_ledger_version,
+        )?;
+
         Ok(())
     }
 
@@ -858,6 +951,13 @@ impl<
                 .get_all_epoch_ending_ledger_infos(next_epoch_end)
                 .await?;
             self.active_data_stream = Some(epoch_ending_stream);
+
+            // Track the progress of the stream (resuming any persisted progress)
+            self.stream_progress_tracker.start_stream(
+                DataStreamType::EpochEndingLedgerInfos,
+                next_epoch_end,
+                highest_advertised_epoch_end,
+            )?;
         } else if self.verified_epoch_states.verified_waypoint() {
             info!(LogSchema::new(LogEntry::Bootstrapper).message(
                 "No new epoch ending ledger infos to fetch! All peers are in the same epoch!"
@@ -1090,9 +1190,9 @@ impl<
 
         // Verify the epoch change proofs, update our latest epoch state and
         // verify our waypoint.
-        for epoch_ending_ledger_info in epoch_ending_ledger_infos {
+        for epoch_ending_ledger_info in &epoch_ending_ledger_infos {
             if let Err(error) = self.verified_epoch_states.update_verified_epoch_states(
-                &epoch_ending_ledger_info,
+                epoch_ending_ledger_info,
                 &self.driver_configuration.waypoint,
             ) {
                 self.reset_active_stream(Some(NotificationAndFeedback::new(
@@ -1104,6 +1204,10 @@ impl<
             }
         }
 
+        // Persist the verified chunk (so that it can be reused after a reboot)
+        self.stream_progress_tracker
+            .record_verified_epoch_ending_ledger_infos(&epoch_ending_ledger_infos)?;
+
         // TODO(joshlind): do we want to preemptively notify certain components
         // of the new reconfigurations?
 
@@ -181,6 +181,7 @@ impl<
             streaming_client.clone(),
             storage.clone(),
             storage_synchronizer.clone(),
+            time_service.clone(),
         );
         let continuous_syncer = ContinuousSyncer::new(
             driver_configuration.clone(),
@@ -15,6 +15,7 @@ pub mod metadata_storage;
 pub mod metrics;
 mod notification_handlers;
 mod storage_synchronizer;
+mod stream_progress_tracker;
 mod utils;
 
 #[cfg(test)]
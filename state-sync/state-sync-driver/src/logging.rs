@@ -34,5 +34,6 @@ pub enum LogEntry {
     Driver,
     NotificationHandler,
     StorageSynchronizer,
+    StreamProgressTracker,
     SynchronizerNotification,
 }
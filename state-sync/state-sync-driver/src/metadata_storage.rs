@@ -47,6 +47,40 @@ pub trait MetadataStorageInterface {
         last_persisted_state_value_index: u64,
         snapshot_sync_completed: bool,
     ) -> Result<(), Error>;
+
+    /// Returns the last persisted progress for the data stream of the
+    /// specified type. If no progress is found, None is returned.
+    fn get_data_stream_progress(
+        &self,
+        data_stream_type: DataStreamType,
+    ) -> Result<Option<DataStreamProgress>, Error>;
+
+    /// Updates the persisted progress for the data stream (of the same type)
+    fn update_data_stream_progress(
+        &self,
+        data_stream_progress: &DataStreamProgress,
+    ) -> Result<(), Error>;
+
+    /// Returns all verified epoch ending ledger infos that were previously persisted,
+    /// starting at the specified epoch (inclusive). The ledger infos are returned in
+    /// epoch order, and the first missing epoch terminates the sequence.
+    fn get_verified_epoch_ending_ledger_infos(
+        &self,
+        start_epoch: u64,
+    ) -> Result<Vec<LedgerInfoWithSignatures>, Error>;
+
+    /// Atomically persists a verified chunk of epoch ending ledger infos
+    /// together with the updated progress of the epoch ending data stream.
+    fn commit_verified_epoch_ending_ledger_infos(
+        &self,
+        epoch_ending_ledger_infos: &[LedgerInfoWithSignatures],
+        data_stream_progress: &DataStreamProgress,
+    ) -> Result<(), Error>;
+
+    /// Removes all persisted epoch ending ledger infos for epochs lower than the
+    /// specified epoch (i.e., ledger infos that have already been committed to
+    /// the database and are no longer required to resume syncing).
+    fn prune_epoch_ending_ledger_infos(&self, end_epoch: u64) -> Result<(), Error>;
 }
 
 /// The name of the state sync db file
@@ -96,20 +130,11 @@ impl PersistentMetadataStorage {
     /// Returns the existing snapshot sync progress. Returns None if no progress is found.
     fn get_snapshot_progress(&self) -> Result<Option<StateSnapshotProgress>, Error> {
         let metadata_key = MetadataKey::StateSnapshotSync;
-        let maybe_metadata_value =
-            self.database
-                .get::<MetadataSchema>(&metadata_key)
-                .map_err(|error| {
-                    Error::StorageError(format!(
-                        "Failed to read metadata value for key: {:?}. Error: {:?}",
-                        metadata_key, error
-                    ))
-                })?;
-        match maybe_metadata_value {
-            Some(metadata_value) => {
-                let MetadataValue::StateSnapshotSync(snapshot_progress) = metadata_value;
+        match self.get_metadata_value(&metadata_key)? {
+            Some(MetadataValue::StateSnapshotSync(snapshot_progress)) => {
                 Ok(Some(snapshot_progress))
             },
+            Some(metadata_value) => Err(unexpected_metadata_value(metadata_key, metadata_value)),
             None => Ok(None),
         }
     }
@@ -137,21 +162,46 @@ impl PersistentMetadataStorage {
         }
     }
 
+    /// Reads the value for the given key from the database
+    fn get_metadata_value(
+        &self,
+        metadata_key: &MetadataKey,
+    ) -> Result<Option<MetadataValue>, Error> {
+        self.database
+            .get::<MetadataSchema>(metadata_key)
+            .map_err(|error| {
+                Error::StorageError(format!(
+                    "Failed to read metadata value for key: {:?}. Error: {:?}",
+                    metadata_key, error
+                ))
+            })
+    }
+
     /// Write the key value pair to the database
     fn commit_key_value(
         &self,
         metadata_key: MetadataKey,
         metadata_value: MetadataValue,
+    ) -> Result<(), Error> {
+        self.commit_key_values(vec![(metadata_key, metadata_value)])
+    }
+
+    /// Write all key value pairs to the database (atomically)
+    fn commit_key_values(
+        &self,
+        metadata_key_values: Vec<(MetadataKey, MetadataValue)>,
     ) -> Result<(), Error> {
         // Create the schema batch
         let batch = SchemaBatch::new();
-        batch
-            .put::<MetadataSchema>(&metadata_key, &metadata_value)
-            .map_err(|error| {
-                Error::StorageError(format!(
-                    "Failed to batch put the metadata key and value. Key: {:?}, Value: {:?}. Error: {:?}", metadata_key, metadata_value, error
-                ))
-            })?;
+        for (metadata_key, metadata_value) in metadata_key_values {
+            batch
+                .put::<MetadataSchema>(&metadata_key, &metadata_value)
+                .map_err(|error| {
+                    Error::StorageError(format!(
+                        "Failed to batch put the metadata key and value. Key: {:?}, Value: {:?}. Error: {:?}", metadata_key, metadata_value, error
+                    ))
+                })?;
+        }
 
         // Write the schema batch to the database
         self.database.write_schemas(batch).map_err(|error| {
@@ -224,6 +274,129 @@ impl MetadataStorageInterface for PersistentMetadataStorage {
         // Insert the new key/value pair
         self.commit_key_value(metadata_key, metadata_value)
     }
+
+    fn get_data_stream_progress(
+        &self,
+        data_stream_type: DataStreamType,
+    ) -> Result<Option<DataStreamProgress>, Error> {
+        let metadata_key = MetadataKey::DataStreamProgress(data_stream_type);
+        match self.get_metadata_value(&metadata_key)? {
+            Some(MetadataValue::DataStreamProgress(data_stream_progress)) => {
+                Ok(Some(data_stream_progress))
+            },
+            Some(metadata_value) => Err(unexpected_metadata_value(metadata_key, metadata_value)),
+            None => Ok(None),
+        }
+    }
+
+    fn update_data_stream_progress(
+        &self,
+        data_stream_progress: &DataStreamProgress,
+    ) -> Result<(), Error> {
+        let metadata_key = MetadataKey::DataStreamProgress(data_stream_progress.data_stream_type);
+        let metadata_value = MetadataValue::DataStreamProgress(data_stream_progress.clone());
+        self.commit_key_value(metadata_key, metadata_value)
+    }
+
+    fn get_verified_epoch_ending_ledger_infos(
+        &self,
+        start_epoch: u64,
+    ) -> Result<Vec<LedgerInfoWithSignatures>, Error> {
+        let mut epoch_ending_ledger_infos = vec![];
+        let mut epoch = start_epoch;
+        loop {
+            let metadata_key = MetadataKey::EpochEndingLedgerInfo(epoch);
+            match self.get_metadata_value(&metadata_key)? {
+                Some(MetadataValue::EpochEndingLedgerInfo(epoch_ending_ledger_info)) => {
+                    epoch_ending_ledger_infos.push(epoch_ending_ledger_info);
+                },
+                Some(metadata_value) => {
+                    return Err(unexpected_metadata_value(metadata_key, metadata_value))
+                },
+                None => return Ok(epoch_ending_ledger_infos),
+            }
+            epoch = epoch.checked_add(1).ok_or_else(|| {
+                Error::IntegerOverflow("The next epoch to read has overflown!".into())
+            })?;
+        }
+    }
+
+    fn commit_verified_epoch_ending_ledger_infos(
+        &self,
+        epoch_ending_ledger_infos: &[LedgerInfoWithSignatures],
+        data_stream_progress: &DataStreamProgress,
+    ) -> Result<(), Error> {
+        // Create the key/value pairs for the ledger infos
+        let mut metadata_key_values: Vec<_> = epoch_ending_ledger_infos
+            .iter()
+            .map(|epoch_ending_ledger_info| {
+                (
+                    MetadataKey::EpochEndingLedgerInfo(
+                        epoch_ending_ledger_info.ledger_info().epoch(),
+                    ),
+                    MetadataValue::EpochEndingLedgerInfo(epoch_ending_ledger_info.clone()),
+                )
+            })
+            .collect();
+
+        // Add the key/value pair for the stream progress
+        metadata_key_values.push((
+            MetadataKey::DataStreamProgress(data_stream_progress.data_stream_type),
+            MetadataValue::DataStreamProgress(data_stream_progress.clone()),
+        ));
+
+        // Insert all key/value pairs atomically
+        self.commit_key_values(metadata_key_values)
+    }
+
+    fn prune_epoch_ending_ledger_infos(&self, end_epoch: u64) -> Result<(), Error> {
+        // Identify the ledger infos to prune. Note: the keys are not ordered
+        // by epoch, so we iterate over the entire (small) metadata table.
+        let mut metadata_iterator = self.database.iter::<MetadataSchema>().map_err(|error| {
+            Error::StorageError(format!(
+                "Failed to create the metadata iterator. Error: {:?}",
+                error
+            ))
+        })?;
+        metadata_iterator.seek_to_first();
+        let batch = SchemaBatch::new();
+        for entry in metadata_iterator {
+            let (metadata_key, _) = entry.map_err(|error| {
+                Error::StorageError(format!(
+                    "Failed to read the metadata entry. Error: {:?}",
+                    error
+                ))
+            })?;
+            if let MetadataKey::EpochEndingLedgerInfo(epoch) = metadata_key {
+                if epoch < end_epoch {
+                    batch
+                        .delete::<MetadataSchema>(&metadata_key)
+                        .map_err(|error| {
+                            Error::StorageError(format!(
+                                "Failed to batch delete the metadata key: {:?}. Error: {:?}",
+                                metadata_key, error
+                            ))
+                        })?;
+                }
+            }
+        }
+
+        // Write the schema batch to the database
+        self.database.write_schemas(batch).map_err(|error| {
+            Error::StorageError(format!(
+                "Failed to prune the epoch ending ledger infos. Error: {:?}",
+                error
+            ))
+        })
+    }
+}
+
+/// Returns an error for a metadata value that doesn't match the metadata key
+fn unexpected_metadata_value(metadata_key: MetadataKey, metadata_value: MetadataValue) -> Error {
+    Error::StorageError(format!(
+        "Found an unexpected metadata value for key: {:?}. Value: {:?}",
+        metadata_key, metadata_value
+    ))
 }
 
 /// A simple struct for recording the progress of a state snapshot sync
@@ -234,6 +407,87 @@ pub struct StateSnapshotProgress {
     pub snapshot_sync_completed: bool,
 }
 
+/// The types of long-running data streams whose progress is persisted
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub enum DataStreamType {
+    EpochEndingLedgerInfos, // A stream of epoch ending ledger infos
+    TransactionsOrOutputs,  // A stream of transactions and/or transaction outputs
+}
+
+impl DataStreamType {
+    /// Returns the label of the data stream type (e.g., for metrics)
+    pub fn get_label(&self) -> &'static str {
+        match self {
+            DataStreamType::EpochEndingLedgerInfos => "epoch_ending_ledger_infos",
+            DataStreamType::TransactionsOrOutputs => "transactions_or_outputs",
+        }
+    }
+}
+
+/// A simple struct for recording the progress of a long-running data stream.
+/// Indices are epochs for epoch ending ledger infos, and versions otherwise.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct DataStreamProgress {
+    pub data_stream_type: DataStreamType, // The type of the data stream
+    pub start_index: u64, // The first index processed by the stream (in the current run)
+    pub start_time_usecs: u64, // The time (unix usecs) the stream started (in the current run)
+    pub next_index: u64,  // The next index to commit (all previous indices are committed)
+    pub target_index: u64, // The index the stream is syncing to (inclusive)
+    pub num_committed_chunks: u64, // The number of chunks committed (across all runs)
+    pub last_update_time_usecs: u64, // The time (unix usecs) of the last progress update
+}
+
+impl DataStreamProgress {
+    pub fn new(
+        data_stream_type: DataStreamType,
+        start_index: u64,
+        target_index: u64,
+        start_time_usecs: u64,
+    ) -> Self {
+        Self {
+            data_stream_type,
+            start_index,
+            start_time_usecs,
+            next_index: start_index,
+            target_index,
+            num_committed_chunks: 0,
+            last_update_time_usecs: start_time_usecs,
+        }
+    }
+
+    /// Returns the number of indices that remain to be committed
+    pub fn num_remaining(&self) -> u64 {
+        self.target_index
+            .saturating_add(1)
+            .saturating_sub(self.next_index)
+    }
+
+    /// Returns the number of indices committed per second (in the current run)
+    pub fn get_throughput_per_sec(&self) -> f64 {
+        let num_committed = self.next_index.saturating_sub(self.start_index);
+        let elapsed_usecs = self
+            .last_update_time_usecs
+            .saturating_sub(self.start_time_usecs);
+        if elapsed_usecs == 0 {
+            return 0.0;
+        }
+        num_committed as f64 / (elapsed_usecs as f64 / 1_000_000.0)
+    }
+
+    /// Returns the estimated number of seconds until the stream completes.
+    /// If the throughput is still unknown, None is returned.
+    pub fn get_eta_secs(&self) -> Option<f64> {
+        let throughput_per_sec = self.get_throughput_per_sec();
+        if throughput_per_sec > 0.0 {
+            Some(self.num_remaining() as f64 / throughput_per_sec)
+        } else if self.num_remaining() == 0 {
+            Some(0.0)
+        } else {
+            None
+        }
+    }
+}
+
 /// The raw schema format used by the database
 pub mod database_schema {
     use super::*;
@@ -251,7 +505,9 @@ pub mod database_schema {
     #[derive(Debug, Deserialize, Eq, PartialEq, Serialize)]
     #[repr(u8)]
     pub enum MetadataKey {
-        StateSnapshotSync, // A state snapshot sync that was started
+        StateSnapshotSync,                  // A state snapshot sync that was started
+        DataStreamProgress(DataStreamType), // The progress of a long-running data stream
+        EpochEndingLedgerInfo(u64), // A verified epoch ending ledger info (for the given epoch)
     }
 
     /// A metadata value that can be inserted into the database
@@ -259,6 +515,8 @@ pub mod database_schema {
     #[repr(u8)]
     pub enum MetadataValue {
         StateSnapshotSync(StateSnapshotProgress), // A state snapshot sync progress marker
+        DataStreamProgress(DataStreamProgress),   // A data stream progress marker
+        EpochEndingLedgerInfo(LedgerInfoWithSignatures), // A verified epoch ending ledger info
     }
 
     impl KeyCodec<MetadataSchema> for MetadataKey {
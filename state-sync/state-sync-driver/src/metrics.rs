@@ -2,9 +2,9 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use aptos_metrics_core::{
-    exponential_buckets, histogram_opts, register_histogram_vec, register_int_counter_vec,
-    register_int_gauge, register_int_gauge_vec, HistogramTimer, HistogramVec, IntCounterVec,
-    IntGauge, IntGaugeVec,
+    exponential_buckets, histogram_opts, register_gauge_vec, register_histogram_vec,
+    register_int_counter_vec, register_int_gauge, register_int_gauge_vec, GaugeVec, HistogramTimer,
+    HistogramVec, IntCounterVec, IntGauge, IntGaugeVec,
 };
 use once_cell::sync::Lazy;
 use std::time::Instant;
@@ -42,6 +42,13 @@ pub const STORAGE_SYNCHRONIZER_COMMITTER: &str = "committer";
 pub const STORAGE_SYNCHRONIZER_COMMIT_POST_PROCESSOR: &str = "commit_post_processor";
 pub const STORAGE_SYNCHRONIZER_STATE_SNAPSHOT_RECEIVER: &str = "state_snapshot_receiver";
 
+/// Stream progress metric labels
+pub const STREAM_PROGRESS_ETA_SECS: &str = "eta_secs";
+pub const STREAM_PROGRESS_NEXT_INDEX: &str = "next_index";
+pub const STREAM_PROGRESS_NUM_COMMITTED_CHUNKS: &str = "num_committed_chunks";
+pub const STREAM_PROGRESS_TARGET_INDEX: &str = "target_index";
+pub const STREAM_PROGRESS_THROUGHPUT_PER_SEC: &str = "throughput_per_sec";
+
 /// An enum representing the component currently executing
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum ExecutingComponent {
@@ -229,6 +236,16 @@ pub static STORAGE_SYNCHRONIZER_PIPELINE_CHANNEL_BACKPRESSURE: Lazy<IntGaugeVec>
         .unwrap()
     });
 
+/// Gauges for tracking the progress of long-running data streams
+pub static STREAM_PROGRESS: Lazy<GaugeVec> = Lazy::new(|| {
+    register_gauge_vec!(
+        "aptos_state_sync_stream_progress",
+        "Gauges for tracking the progress of long-running data streams",
+        &["stream_type", "label"]
+    )
+    .unwrap()
+});
+
 /// Increments the given counter with the provided label values.
 pub fn increment_counter(counter: &Lazy<IntCounterVec>, label: &str) {
     counter.with_label_values(&[label]).inc();
@@ -266,6 +283,13 @@ pub fn set_gauge(gauge: &Lazy<IntGaugeVec>, label: &str, value: u64) {
     gauge.with_label_values(&[label]).set(value as i64);
 }
 
+/// Sets the stream progress gauge with the specific stream type and label
+pub fn set_stream_progress(stream_type: &str, label: &str, value: f64) {
+    STREAM_PROGRESS
+        .with_label_values(&[stream_type, label])
+        .set(value);
+}
+
 /// Starts the timer for the provided histogram and label
 pub fn start_timer(histogram: &Lazy<HistogramVec>, label: &str) -> HistogramTimer {
     histogram.with_label_values(&[label]).start_timer()
@@ -0,0 +1,264 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    error::Error,
+    logging::{LogEntry, LogSchema},
+    metadata_storage::{DataStreamProgress, DataStreamType, MetadataStorageInterface},
+    metrics,
+};
+use aptos_logger::prelude::*;
+use aptos_time_service::{TimeService, TimeServiceTrait};
+use aptos_types::ledger_info::LedgerInfoWithSignatures;
+
+/// The minimum interval between progress writes to the metadata storage. Note:
+/// the progress is always persisted when a stream is started or completed.
+const PROGRESS_PERSISTENCE_INTERVAL_SECS: u64 = 10;
+
+/// A simple component that tracks the progress of long-running data streams
+/// (e.g., epoch ending ledger infos and transaction outputs). The progress is
+/// persisted to the metadata storage (so that streams can be resumed from the
+/// last committed chunk after a crash), and the throughput and ETA of the
+/// active stream are exposed via metrics (e.g., to the inspection service).
+///
+/// To avoid a storage write per chunk, the progress is only persisted every
+/// `PROGRESS_PERSISTENCE_INTERVAL_SECS` (so a crash may lose recent progress,
+/// which is simply re-synced).
+pub struct StreamProgressTracker<MetadataStorage> {
+    // The progress of the currently active data stream (if any)
+    active_stream_progress: Option<DataStreamProgress>,
+
+    // The time (in microseconds) at which the active progress was last persisted
+    last_persistence_time_usecs: u64,
+
+    // The verified epoch ending ledger infos that are yet to be persisted
+    pending_epoch_ending_ledger_infos: Vec<LedgerInfoWithSignatures>,
+
+    // The storage used to persist the stream progress
+    metadata_storage: MetadataStorage,
+
+    // The time service used to calculate throughput and ETA
+    time_service: TimeService,
+}
+
+impl<MetadataStorage: MetadataStorageInterface> StreamProgressTracker<MetadataStorage> {
+    pub fn new(metadata_storage: MetadataStorage, time_service: TimeService) -> Self {
+        Self {
+            active_stream_progress: None,
+            last_persistence_time_usecs: 0,
+            pending_epoch_ending_ledger_infos: vec![],
+            metadata_storage,
+            time_service,
+        }
+    }
+
+    /// Returns the progress of the currently active data stream (if any)
+    pub fn get_active_stream_progress(&self) -> Option<&DataStreamProgress> {
+        self.active_stream_progress.as_ref()
+    }
+
+    /// Returns all verified epoch ending ledger infos that were persisted by
+    /// a previous epoch ending stream, starting at the specified epoch.
+    pub fn get_verified_epoch_ending_ledger_infos(
+        &self,
+        start_epoch: u64,
+    ) -> Result<Vec<LedgerInfoWithSignatures>, Error> {
+        self.metadata_storage
+            .get_verified_epoch_ending_ledger_infos(start_epoch)
+    }
+
+    /// Prunes all persisted epoch ending ledger infos that have already been
+    /// committed to the database (i.e., for epochs lower than the latest epoch).
+    pub fn prune_committed_epoch_ending_ledger_infos(
+        &self,
+        latest_committed_epoch: u64,
+    ) -> Result<(), Error> {
+        self.metadata_storage
+            .prune_epoch_ending_ledger_infos(latest_committed_epoch)
+    }
+
+    /// Starts tracking the progress of a new data stream. If the stream
+    /// continues the active stream (i.e., it has the same type and target),
+    /// the existing progress is maintained. Otherwise, any persisted progress
+    /// for the same target is resumed (e.g., after a crash).
+    pub fn start_stream(
+        &mut self,
+        data_stream_type: DataStreamType,
+        start_index: u64,
+        target_index: u64,
+    ) -> Result<(), Error> {
+        // If the stream continues the active stream, there's nothing to do
+        if let Some(active_stream_progress) = self.active_stream_progress.as_ref() {
+            if active_stream_progress.data_stream_type == data_stream_type
+                && active_stream_progress.target_index == target_index
+            {
+                return Ok(());
+            }
+        }
+
+        // Create the progress for the new stream
+        let current_time_usecs = self.get_current_time_usecs();
+        let mut data_stream_progress = DataStreamProgress::new(
+            data_stream_type,
+            start_index,
+            target_index,
+            current_time_usecs,
+        );
+
+        // Resume any persisted progress for the same target
+        if let Some(persisted_progress) = self
+            .metadata_storage
+            .get_data_stream_progress(data_stream_type)?
+        {
+            if persisted_progress.target_index == target_index {
+                info!(LogSchema::new(LogEntry::StreamProgressTracker).message(&format!(
+                    "Resuming the {:?} stream from the last committed chunk! Persisted next index: {:?}, \
+                    resumed start index: {:?}, target index: {:?}",
+                    data_stream_type, persisted_progress.next_index, start_index, target_index
+                )));
+                data_stream_progress.num_committed_chunks = persisted_progress.num_committed_chunks;
+            }
+        }
+
+        // Persist the progress and update the metrics
+        self.metadata_storage
+            .update_data_stream_progress(&data_stream_progress)?;
+        update_progress_metrics(&data_stream_progress);
+        self.last_persistence_time_usecs = current_time_usecs;
+        self.pending_epoch_ending_ledger_infos.clear();
+        self.active_stream_progress = Some(data_stream_progress);
+
+        Ok(())
+    }
+
+    /// Records that a chunk of transactions or outputs has been committed (i.e.,
+    /// all versions up to and including the given synced version are committed).
+    pub fn record_committed_transactions(&mut self, synced_version: u64) -> Result<(), Error> {
+        let next_index = synced_version.checked_add(1).ok_or_else(|| {
+            Error::IntegerOverflow("The next version to commit has overflown!".into())
+        })?;
+        if let Some(data_stream_progress) =
+            self.update_active_progress(DataStreamType::TransactionsOrOutputs, next_index)
+        {
+            if self.should_persist_progress(&data_stream_progress) {
+                self.metadata_storage
+                    .update_data_stream_progress(&data_stream_progress)?;
+                self.last_persistence_time_usecs = data_stream_progress.last_update_time_usecs;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Records that a chunk of epoch ending ledger infos has been verified, and
+    /// persists the ledger infos (so they can be reused after a crash). Note: the
+    /// ledger infos are buffered until the progress is next persisted.
+    pub fn record_verified_epoch_ending_ledger_infos(
+        &mut self,
+        epoch_ending_ledger_infos: &[LedgerInfoWithSignatures],
+    ) -> Result<(), Error> {
+        // Identify the next epoch to verify
+        let last_epoch = match epoch_ending_ledger_infos.last() {
+            Some(epoch_ending_ledger_info) => epoch_ending_ledger_info.ledger_info().epoch(),
+            None => return Ok(()), // Nothing was verified
+        };
+        let next_index = last_epoch.checked_add(1).ok_or_else(|| {
+            Error::IntegerOverflow("The next epoch to verify has overflown!".into())
+        })?;
+
+        // Atomically persist the buffered ledger infos and the stream progress
+        if let Some(data_stream_progress) =
+            self.update_active_progress(DataStreamType::EpochEndingLedgerInfos, next_index)
+        {
+            self.pending_epoch_ending_ledger_infos
+                .extend_from_slice(epoch_ending_ledger_infos);
+            if self.should_persist_progress(&data_stream_progress) {
+                self.metadata_storage
+                    .commit_verified_epoch_ending_ledger_infos(
+                        &self.pending_epoch_ending_ledger_infos,
+                        &data_stream_progress,
+                    )?;
+                self.pending_epoch_ending_ledger_infos.clear();
+                self.last_persistence_time_usecs = data_stream_progress.last_update_time_usecs;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Stops tracking the active data stream (the persisted progress is maintained,
+    /// but any progress that is yet to be persisted is dropped).
+    pub fn stop_stream(&mut self) {
+        self.active_stream_progress = None;
+        self.pending_epoch_ending_ledger_infos.clear();
+    }
+
+    /// Returns true iff the given progress should be persisted, i.e., the stream
+    /// has completed, or enough time has elapsed since the last persisted progress.
+    fn should_persist_progress(&self, data_stream_progress: &DataStreamProgress) -> bool {
+        let persistence_interval_usecs = PROGRESS_PERSISTENCE_INTERVAL_SECS * 1_000_000;
+        data_stream_progress.num_remaining() == 0
+            || data_stream_progress
+                .last_update_time_usecs
+                .saturating_sub(self.last_persistence_time_usecs)
+                >= persistence_interval_usecs
+    }
+
+    /// Updates the progress of the active stream (if it has the given type and
+    /// the next index has advanced). Returns the updated progress.
+    fn update_active_progress(
+        &mut self,
+        data_stream_type: DataStreamType,
+        next_index: u64,
+    ) -> Option<DataStreamProgress> {
+        let current_time_usecs = self.get_current_time_usecs();
+        let data_stream_progress = self.active_stream_progress.as_mut()?;
+        if data_stream_progress.data_stream_type != data_stream_type
+            || next_index <= data_stream_progress.next_index
+        {
+            return None;
+        }
+
+        // Update the progress and metrics
+        data_stream_progress.next_index = next_index;
+        data_stream_progress.num_committed_chunks += 1;
+        data_stream_progress.last_update_time_usecs = current_time_usecs;
+        update_progress_metrics(data_stream_progress);
+
+        Some(data_stream_progress.clone())
+    }
+
+    /// Returns the current unix time (in microseconds)
+    fn get_current_time_usecs(&self) -> u64 {
+        self.time_service.now_unix_time().as_micros() as u64
+    }
+}
+
+/// Updates the progress metrics for the given data stream
+fn update_progress_metrics(data_stream_progress: &DataStreamProgress) {
+    let stream_type = data_stream_progress.data_stream_type.get_label();
+    metrics::set_stream_progress(
+        stream_type,
+        metrics::STREAM_PROGRESS_NEXT_INDEX,
+        data_stream_progress.next_index as f64,
+    );
+    metrics::set_stream_progress(
+        stream_type,
+        metrics::STREAM_PROGRESS_TARGET_INDEX,
+        data_stream_progress.target_index as f64,
+    );
+    metrics::set_stream_progress(
+        stream_type,
+        metrics::STREAM_PROGRESS_NUM_COMMITTED_CHUNKS,
+        data_stream_progress.num_committed_chunks as f64,
+    );
+    metrics::set_stream_progress(
+        stream_type,
+        metrics::STREAM_PROGRESS_THROUGHPUT_PER_SEC,
+        data_stream_progress.get_throughput_per_sec(),
+    );
+
+    // If the ETA is unknown, we set it to -1
+    let eta_secs = data_stream_progress.get_eta_secs().unwrap_or(-1.0);
+    metrics::set_stream_progress(stream_type, metrics::STREAM_PROGRESS_ETA_SECS, eta_secs);
+}
@@ -1604,6 +1604,9 @@ fn create_bootstrapper(
     metadata_storage
         .expect_previous_snapshot_sync_target()
         .returning(|| Ok(None));
+    metadata_storage
+        .expect_clone()
+        .returning(create_stream_progress_metadata_storage);
 
     // Create the mock db reader with only genesis loaded
     let mut mock_database_reader = create_mock_db_reader();
@@ -1623,7 +1626,7 @@ fn create_bootstrapper(
     // Create the output fallback handler
     let time_service = time_service.unwrap_or_else(TimeService::mock);
     let output_fallback_handler =
-        OutputFallbackHandler::new(driver_configuration.clone(), time_service);
+        OutputFallbackHandler::new(driver_configuration.clone(), time_service.clone());
 
     // Create the bootstrapper
     let bootstrapper = Bootstrapper::new(
@@ -1633,6 +1636,7 @@ fn create_bootstrapper(
         mock_streaming_client,
         Arc::new(mock_database_reader),
         mock_storage_synchronizer,
+        time_service,
     );
 
     (bootstrapper, output_fallback_handler)
@@ -1642,7 +1646,7 @@ fn create_bootstrapper(
 fn create_bootstrapper_with_storage(
     driver_configuration: DriverConfiguration,
     mock_streaming_client: MockStreamingClient,
-    mock_metadata_storage: MockMetadataStorage,
+    mut mock_metadata_storage: MockMetadataStorage,
     latest_synced_epoch: Option<u64>,
     latest_synced_version: Version,
     expect_reset_executor: bool,
@@ -1680,9 +1684,15 @@ fn create_bootstrapper_with_storage(
         .expect_get_pre_committed_version()
         .returning(move || Ok(Some(latest_synced_version)));
 
+    // Set the expectations for the stream progress tracker
+    mock_metadata_storage
+        .expect_clone()
+        .returning(create_stream_progress_metadata_storage);
+
     // Create the output fallback handler
+    let time_service = TimeService::mock();
     let output_fallback_handler =
-        OutputFallbackHandler::new(driver_configuration.clone(), TimeService::mock());
+        OutputFallbackHandler::new(driver_configuration.clone(), time_service.clone());
 
     Bootstrapper::new(
         driver_configuration,
@@ -1691,9 +1701,32 @@ fn create_bootstrapper_with_storage(
         mock_streaming_client,
         Arc::new(mock_database_reader),
         mock_storage_synchronizer,
+        time_service,
     )
 }
 
+/// Creates a mock metadata storage for the stream progress tracker
+/// (i.e., with no previously persisted stream progress).
+fn create_stream_progress_metadata_storage() -> MockMetadataStorage {
+    let mut metadata_storage = MockMetadataStorage::new();
+    metadata_storage
+        .expect_get_verified_epoch_ending_ledger_infos()
+        .returning(|_| Ok(vec![]));
+    metadata_storage
+        .expect_get_data_stream_progress()
+        .returning(|_| Ok(None));
+    metadata_storage
+        .expect_update_data_stream_progress()
+        .returning(|_| Ok(()));
+    metadata_storage
+        .expect_commit_verified_epoch_ending_ledger_infos()
+        .returning(|_, _| Ok(()));
+    metadata_storage
+        .expect_prune_epoch_ending_ledger_infos()
+        .returning(|_| Ok(()));
+    metadata_storage
+}
+
 /// Drives progress for the given bootstrapper. If `until_bootstrapped`
 /// is true this method will continue to drive the bootstrapper until
 /// bootstrapping is complete.
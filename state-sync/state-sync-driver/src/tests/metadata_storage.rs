@@ -4,9 +4,13 @@
 use crate::{
     metadata_storage::{
         database_schema::{MetadataKey, MetadataSchema, MetadataValue},
-        MetadataStorageInterface, PersistentMetadataStorage, StateSnapshotProgress,
+        DataStreamProgress, DataStreamType, MetadataStorageInterface, PersistentMetadataStorage,
+        StateSnapshotProgress,
+    },
+    tests::utils::{
+        create_epoch_ending_ledger_info, create_epoch_ending_ledger_info_for_epoch,
+        create_ledger_info_at_version,
     },
-    tests::utils::{create_epoch_ending_ledger_info, create_ledger_info_at_version},
 };
 use aptos_schemadb::schema::fuzzing::assert_encode_decode;
 use aptos_temppath::TempPath;
@@ -89,6 +93,121 @@ fn test_create_then_open() {
     );
 }
 
+#[test]
+fn test_data_stream_progress() {
+    // Create a new metadata storage
+    let tmp_dir = TempPath::new();
+    let metadata_storage = PersistentMetadataStorage::new(tmp_dir.path());
+
+    // Verify the storage is empty
+    for data_stream_type in [
+        DataStreamType::EpochEndingLedgerInfos,
+        DataStreamType::TransactionsOrOutputs,
+    ] {
+        assert_none!(metadata_storage
+            .get_data_stream_progress(data_stream_type)
+            .unwrap());
+    }
+
+    // Update the progress of a transaction stream
+    let mut data_stream_progress =
+        DataStreamProgress::new(DataStreamType::TransactionsOrOutputs, 100, 1000, 0);
+    data_stream_progress.next_index = 500;
+    metadata_storage
+        .update_data_stream_progress(&data_stream_progress)
+        .unwrap();
+
+    // Drop the handle to the storage (mimic a reboot)
+    drop(metadata_storage);
+
+    // Create another storage (it should reopen the existing file) and verify the progress
+    let metadata_storage = PersistentMetadataStorage::new(tmp_dir.path());
+    assert_eq!(
+        metadata_storage
+            .get_data_stream_progress(DataStreamType::TransactionsOrOutputs)
+            .unwrap(),
+        Some(data_stream_progress)
+    );
+    assert_none!(metadata_storage
+        .get_data_stream_progress(DataStreamType::EpochEndingLedgerInfos)
+        .unwrap());
+}
+
+#[test]
+fn test_verified_epoch_ending_ledger_infos() {
+    // Create a new metadata storage
+    let tmp_dir = TempPath::new();
+    let metadata_storage = PersistentMetadataStorage::new(tmp_dir.path());
+
+    // Verify the storage is empty
+    assert!(metadata_storage
+        .get_verified_epoch_ending_ledger_infos(0)
+        .unwrap()
+        .is_empty());
+
+    // Commit several chunks of epoch ending ledger infos
+    let epoch_ending_ledger_infos: Vec<_> = (0..10)
+        .map(|epoch| create_epoch_ending_ledger_info_for_epoch(epoch, epoch * 100))
+        .collect();
+    let mut data_stream_progress =
+        DataStreamProgress::new(DataStreamType::EpochEndingLedgerInfos, 0, 20, 0);
+    for chunk in epoch_ending_ledger_infos.chunks(3) {
+        data_stream_progress.next_index = chunk.last().unwrap().ledger_info().epoch() + 1;
+        metadata_storage
+            .commit_verified_epoch_ending_ledger_infos(chunk, &data_stream_progress)
+            .unwrap();
+    }
+
+    // Drop the handle to the storage (mimic a reboot)
+    drop(metadata_storage);
+
+    // Create another storage and verify the ledger infos and progress
+    let metadata_storage = PersistentMetadataStorage::new(tmp_dir.path());
+    assert_eq!(
+        metadata_storage
+            .get_verified_epoch_ending_ledger_infos(0)
+            .unwrap(),
+        epoch_ending_ledger_infos
+    );
+    assert_eq!(
+        metadata_storage
+            .get_verified_epoch_ending_ledger_infos(4)
+            .unwrap(),
+        epoch_ending_ledger_infos[4..].to_vec()
+    );
+    assert!(metadata_storage
+        .get_verified_epoch_ending_ledger_infos(10)
+        .unwrap()
+        .is_empty());
+    assert_eq!(
+        metadata_storage
+            .get_data_stream_progress(DataStreamType::EpochEndingLedgerInfos)
+            .unwrap(),
+        Some(data_stream_progress.clone())
+    );
+
+    // Prune the ledger infos below epoch 6 and verify only the later ones remain
+    metadata_storage.prune_epoch_ending_ledger_infos(6).unwrap();
+    assert!(metadata_storage
+        .get_verified_epoch_ending_ledger_infos(0)
+        .unwrap()
+        .is_empty());
+    assert_eq!(
+        metadata_storage
+            .get_verified_epoch_ending_ledger_infos(6)
+            .unwrap(),
+        epoch_ending_ledger_infos[6..].to_vec()
+    );
+
+    // Verify that the stream progress is unaffected by pruning
+    assert_eq!(
+        metadata_storage
+            .get_data_stream_progress(DataStreamType::EpochEndingLedgerInfos)
+            .unwrap(),
+        Some(data_stream_progress)
+    );
+}
+
 #[test]
 fn test_metadata_schema_encode_decode() {
     assert_encode_decode::<MetadataSchema>(
@@ -99,6 +218,19 @@ fn test_metadata_schema_encode_decode() {
             snapshot_sync_completed: false,
         }),
     );
+    assert_encode_decode::<MetadataSchema>(
+        &MetadataKey::DataStreamProgress(DataStreamType::TransactionsOrOutputs),
+        &MetadataValue::DataStreamProgress(DataStreamProgress::new(
+            DataStreamType::TransactionsOrOutputs,
+            10,
+            100,
+            12345,
+        )),
+    );
+    assert_encode_decode::<MetadataSchema>(
+        &MetadataKey::EpochEndingLedgerInfo(5),
+        &MetadataValue::EpochEndingLedgerInfo(create_epoch_ending_ledger_info_for_epoch(5, 500)),
+    );
 }
 
 #[test]
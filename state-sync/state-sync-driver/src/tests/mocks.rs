@@ -3,7 +3,7 @@
 
 use crate::{
     error::Error,
-    metadata_storage::MetadataStorageInterface,
+    metadata_storage::{DataStreamProgress, DataStreamType, MetadataStorageInterface},
     storage_synchronizer::{NotificationMetadata, StorageSynchronizerInterface},
     tests::utils::{create_empty_epoch_state, create_epoch_ending_ledger_info},
 };
@@ -354,6 +354,29 @@ mock! {
             last_persisted_state_value_index: u64,
             snapshot_sync_completed: bool,
         ) -> Result<(), Error>;
+
+        fn get_data_stream_progress(
+            &self,
+            data_stream_type: DataStreamType,
+        ) -> Result<Option<DataStreamProgress>, Error>;
+
+        fn update_data_stream_progress(
+            &self,
+            data_stream_progress: &DataStreamProgress,
+        ) -> Result<(), Error>;
+
+        fn get_verified_epoch_ending_ledger_infos(
+            &self,
+            start_epoch: u64,
+        ) -> Result<Vec<LedgerInfoWithSignatures>, Error>;
+
+        fn commit_verified_epoch_ending_ledger_infos(
+            &self,
+            epoch_ending_ledger_infos: &[LedgerInfoWithSignatures],
+            data_stream_progress: &DataStreamProgress,
+        ) -> Result<(), Error>;
+
+        fn prune_epoch_ending_ledger_infos(&self, end_epoch: u64) -> Result<(), Error>;
     }
 
     impl Clone for MetadataStorage {
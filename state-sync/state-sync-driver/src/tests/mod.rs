@@ -9,4 +9,5 @@ mod driver_factory;
 mod metadata_storage;
 mod mocks;
 mod storage_synchronizer;
+mod stream_progress_tracker;
 mod utils;
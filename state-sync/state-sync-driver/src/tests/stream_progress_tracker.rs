@@ -0,0 +1,290 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    metadata_storage::{DataStreamType, MetadataStorageInterface, PersistentMetadataStorage},
+    stream_progress_tracker::StreamProgressTracker,
+    tests::utils::create_epoch_ending_ledger_info_for_epoch,
+};
+use aptos_temppath::TempPath;
+use aptos_time_service::TimeService;
+use claims::assert_none;
+use std::time::Duration;
+
+// The interval at which the stream progress is persisted (to avoid frequent writes)
+const PERSISTENCE_INTERVAL_SECS: u64 = 10;
+
+#[test]
+fn test_stream_progress_throughput_and_eta() {
+    // Create a stream progress tracker
+    let tmp_dir = TempPath::new();
+    let time_service = TimeService::mock();
+    let mut stream_progress_tracker = StreamProgressTracker::new(
+        PersistentMetadataStorage::new(tmp_dir.path()),
+        time_service.clone(),
+    );
+
+    // Start a new transaction stream and verify the throughput and ETA are unknown
+    stream_progress_tracker
+        .start_stream(DataStreamType::TransactionsOrOutputs, 101, 1100)
+        .unwrap();
+    let data_stream_progress = stream_progress_tracker
+        .get_active_stream_progress()
+        .unwrap();
+    assert_eq!(data_stream_progress.get_throughput_per_sec(), 0.0);
+    assert_none!(data_stream_progress.get_eta_secs());
+
+    // Elapse some time and commit a chunk of transactions
+    time_service
+        .clone()
+        .into_mock()
+        .advance(Duration::from_secs(10));
+    stream_progress_tracker
+        .record_committed_transactions(600)
+        .unwrap();
+
+    // Verify the throughput and ETA
+    let data_stream_progress = stream_progress_tracker
+        .get_active_stream_progress()
+        .unwrap();
+    assert_eq!(data_stream_progress.next_index, 601);
+    assert_eq!(data_stream_progress.num_committed_chunks, 1);
+    assert_eq!(data_stream_progress.get_throughput_per_sec(), 50.0);
+    assert_eq!(data_stream_progress.get_eta_secs(), Some(10.0));
+
+    // Commit the remaining transactions and verify the ETA is zero
+    time_service.into_mock().advance(Duration::from_secs(10));
+    stream_progress_tracker
+        .record_committed_transactions(1100)
+        .unwrap();
+    let data_stream_progress = stream_progress_tracker
+        .get_active_stream_progress()
+        .unwrap();
+    assert_eq!(data_stream_progress.num_remaining(), 0);
+    assert_eq!(data_stream_progress.get_eta_secs(), Some(0.0));
+}
+
+#[test]
+fn test_stream_progress_resumption() {
+    // Create a stream progress tracker
+    let tmp_dir = TempPath::new();
+    let time_service = TimeService::mock();
+    let mut stream_progress_tracker = StreamProgressTracker::new(
+        PersistentMetadataStorage::new(tmp_dir.path()),
+        time_service.clone(),
+    );
+
+    // Start a new transaction stream and commit several chunks (elapsing
+    // enough time between chunks for the progress to be persisted).
+    let target_version = 10_000;
+    stream_progress_tracker
+        .start_stream(DataStreamType::TransactionsOrOutputs, 1, target_version)
+        .unwrap();
+    for synced_version in [100, 200, 300] {
+        time_service
+            .clone()
+            .into_mock()
+            .advance(Duration::from_secs(PERSISTENCE_INTERVAL_SECS));
+        stream_progress_tracker
+            .record_committed_transactions(synced_version)
+            .unwrap();
+    }
+
+    // Verify that stale commits are ignored
+    stream_progress_tracker
+        .record_committed_transactions(250)
+        .unwrap();
+    let data_stream_progress = stream_progress_tracker
+        .get_active_stream_progress()
+        .unwrap();
+    assert_eq!(data_stream_progress.next_index, 301);
+    assert_eq!(data_stream_progress.num_committed_chunks, 3);
+
+    // Drop the tracker and storage (mimic a reboot)
+    drop(stream_progress_tracker);
+
+    // Create another tracker and resume the stream for the same target
+    let metadata_storage = PersistentMetadataStorage::new(tmp_dir.path());
+    let mut stream_progress_tracker =
+        StreamProgressTracker::new(metadata_storage.clone(), TimeService::mock());
+    stream_progress_tracker
+        .start_stream(DataStreamType::TransactionsOrOutputs, 301, target_version)
+        .unwrap();
+
+    // Verify the progress was resumed
+    let data_stream_progress = stream_progress_tracker
+        .get_active_stream_progress()
+        .unwrap();
+    assert_eq!(data_stream_progress.start_index, 301);
+    assert_eq!(data_stream_progress.next_index, 301);
+    assert_eq!(data_stream_progress.num_committed_chunks, 3);
+
+    // Start a stream for a different target and verify the progress was reset
+    stream_progress_tracker
+        .start_stream(
+            DataStreamType::TransactionsOrOutputs,
+            301,
+            target_version * 2,
+        )
+        .unwrap();
+    let data_stream_progress = stream_progress_tracker
+        .get_active_stream_progress()
+        .unwrap()
+        .clone();
+    assert_eq!(data_stream_progress.num_committed_chunks, 0);
+    assert_eq!(
+        metadata_storage
+            .get_data_stream_progress(DataStreamType::TransactionsOrOutputs)
+            .unwrap(),
+        Some(data_stream_progress)
+    );
+}
+
+#[test]
+fn test_stream_progress_epoch_ending_ledger_infos() {
+    // Create a stream progress tracker
+    let tmp_dir = TempPath::new();
+    let metadata_storage = PersistentMetadataStorage::new(tmp_dir.path());
+    let mut stream_progress_tracker =
+        StreamProgressTracker::new(metadata_storage.clone(), TimeService::mock());
+
+    // Verify that epoch ending ledger infos aren't persisted without an active stream
+    let epoch_ending_ledger_infos: Vec<_> = (0..10)
+        .map(|epoch| create_epoch_ending_ledger_info_for_epoch(epoch, epoch * 10))
+        .collect();
+    stream_progress_tracker
+        .record_verified_epoch_ending_ledger_infos(&epoch_ending_ledger_infos[0..5])
+        .unwrap();
+    assert!(stream_progress_tracker
+        .get_verified_epoch_ending_ledger_infos(0)
+        .unwrap()
+        .is_empty());
+
+    // Start an epoch ending stream and record several verified chunks
+    stream_progress_tracker
+        .start_stream(DataStreamType::EpochEndingLedgerInfos, 0, 9)
+        .unwrap();
+    for chunk in epoch_ending_ledger_infos.chunks(4) {
+        stream_progress_tracker
+            .record_verified_epoch_ending_ledger_infos(chunk)
+            .unwrap();
+    }
+
+    // Verify the ledger infos and progress were persisted
+    assert_eq!(
+        stream_progress_tracker
+            .get_verified_epoch_ending_ledger_infos(0)
+            .unwrap(),
+        epoch_ending_ledger_infos
+    );
+    let data_stream_progress = metadata_storage
+        .get_data_stream_progress(DataStreamType::EpochEndingLedgerInfos)
+        .unwrap()
+        .unwrap();
+    assert_eq!(data_stream_progress.next_index, 10);
+    assert_eq!(data_stream_progress.num_committed_chunks, 3);
+    assert_eq!(data_stream_progress.num_remaining(), 0);
+
+    // Stop the stream and verify that the persisted progress is maintained
+    stream_progress_tracker.stop_stream();
+    assert_none!(stream_progress_tracker.get_active_stream_progress());
+    assert_eq!(
+        metadata_storage
+            .get_data_stream_progress(DataStreamType::EpochEndingLedgerInfos)
+            .unwrap(),
+        Some(data_stream_progress)
+    );
+}
+
+#[test]
+fn test_stream_progress_persistence_throttling() {
+    // Create a stream progress tracker
+    let tmp_dir = TempPath::new();
+    let metadata_storage = PersistentMetadataStorage::new(tmp_dir.path());
+    let time_service = TimeService::mock();
+    let mut stream_progress_tracker =
+        StreamProgressTracker::new(metadata_storage.clone(), time_service.clone());
+
+    // Start a new transaction stream and commit several chunks (without elapsing time)
+    let target_version = 1000;
+    stream_progress_tracker
+        .start_stream(DataStreamType::TransactionsOrOutputs, 0, target_version)
+        .unwrap();
+    for synced_version in [100, 200, 300] {
+        stream_progress_tracker
+            .record_committed_transactions(synced_version)
+            .unwrap();
+    }
+
+    // Verify that the in-memory progress was updated, but not persisted
+    let data_stream_progress = stream_progress_tracker
+        .get_active_stream_progress()
+        .unwrap();
+    assert_eq!(data_stream_progress.next_index, 301);
+    let persisted_progress = metadata_storage
+        .get_data_stream_progress(DataStreamType::TransactionsOrOutputs)
+        .unwrap()
+        .unwrap();
+    assert_eq!(persisted_progress.next_index, 0);
+
+    // Elapse the persistence interval, commit another chunk and verify it was persisted
+    time_service
+        .clone()
+        .into_mock()
+        .advance(Duration::from_secs(PERSISTENCE_INTERVAL_SECS));
+    stream_progress_tracker
+        .record_committed_transactions(400)
+        .unwrap();
+    let persisted_progress = metadata_storage
+        .get_data_stream_progress(DataStreamType::TransactionsOrOutputs)
+        .unwrap()
+        .unwrap();
+    assert_eq!(persisted_progress.next_index, 401);
+    assert_eq!(persisted_progress.num_committed_chunks, 4);
+
+    // Commit the final chunk (without elapsing time) and verify it was persisted
+    stream_progress_tracker
+        .record_committed_transactions(target_version)
+        .unwrap();
+    let persisted_progress = metadata_storage
+        .get_data_stream_progress(DataStreamType::TransactionsOrOutputs)
+        .unwrap()
+        .unwrap();
+    assert_eq!(persisted_progress.num_remaining(), 0);
+}
+
+#[test]
+fn test_stream_progress_prune_epoch_ending_ledger_infos() {
+    // Create a stream progress tracker
+    let tmp_dir = TempPath::new();
+    let mut stream_progress_tracker = StreamProgressTracker::new(
+        PersistentMetadataStorage::new(tmp_dir.path()),
+        TimeService::mock(),
+    );
+
+    // Start an epoch ending stream and verify all ledger infos
+    let epoch_ending_ledger_infos: Vec<_> = (0..10)
+        .map(|epoch| create_epoch_ending_ledger_info_for_epoch(epoch, epoch * 10))
+        .collect();
+    stream_progress_tracker
+        .start_stream(DataStreamType::EpochEndingLedgerInfos, 0, 9)
+        .unwrap();
+    stream_progress_tracker
+        .record_verified_epoch_ending_ledger_infos(&epoch_ending_ledger_infos)
+        .unwrap();
+
+    // Prune the committed ledger infos and verify only the uncommitted ones remain
+    stream_progress_tracker
+        .prune_committed_epoch_ending_ledger_infos(7)
+        .unwrap();
+    assert!(stream_progress_tracker
+        .get_verified_epoch_ending_ledger_infos(0)
+        .unwrap()
+        .is_empty());
+    assert_eq!(
+        stream_progress_tracker
+            .get_verified_epoch_ending_ledger_infos(7)
+            .unwrap(),
+        epoch_ending_ledger_infos[7..].to_vec()
+    );
+}
@@ -3,19 +3,30 @@
 
 use crate::{error::Error, metrics::increment_network_frame_overflow};
 use aptos_config::config::StorageServiceConfig;
+use aptos_crypto::hash::CryptoHash;
 use aptos_logger::debug;
 use aptos_storage_interface::{AptosDbError, DbReader, Result as StorageResult};
-use aptos_storage_service_types::responses::{
-    CompleteDataRange, DataResponse, DataSummary, TransactionOrOutputListWithProof,
+use aptos_storage_service_types::{
+    requests::StateKeysSelector,
+    responses::{
+        CompleteDataRange, DataResponse, DataSummary, StateValuesByKeysWithProof,
+        TransactionOrOutputListWithProof,
+    },
 };
 use aptos_types::{
     epoch_change::EpochChangeProof,
     ledger_info::LedgerInfoWithSignatures,
-    state_store::state_value::StateValueChunkWithProof,
-    transaction::{TransactionListWithProof, TransactionOutputListWithProof, Version},
+    proof::SparseMerkleMultiProof,
+    state_store::{
+        state_key::{prefix::StateKeyPrefix, StateKey},
+        state_value::{StateValue, StateValueChunkWithProof},
+    },
+    transaction::{
+        TransactionListWithProof, TransactionOutputListWithProof, TransactionWithProof, Version,
+    },
 };
 use serde::Serialize;
-use std::{cmp::min, sync::Arc};
+use std::{cmp::min, collections::HashSet, sync::Arc};
 
 /// The interface into local storage (e.g., the Aptos DB) used by the storage
 /// server to handle client requests and responses.
@@ -89,6 +100,20 @@ pub trait StorageReaderInterface: Clone + Send + 'static {
         start_index: u64,
         end_index: u64,
     ) -> aptos_storage_service_types::Result<StateValueChunkWithProof, Error>;
+
+    /// Returns a set of state values (with a single sparse Merkle multi-proof)
+    /// at the specified version. The state root is proven by the transaction
+    /// info at the version, relative to the ledger info at `proof_version`.
+    /// The version must be a state checkpoint. The state keys are identified
+    /// by the given `state_keys_selector`. In some cases, less state values
+    /// may be returned (e.g., due to network or chunk limits), in which case
+    /// the next state key to fetch is also returned.
+    fn get_state_values_by_keys_with_proof(
+        &self,
+        version: u64,
+        proof_version: u64,
+        state_keys_selector: &StateKeysSelector,
+    ) -> aptos_storage_service_types::Result<StateValuesByKeysWithProof, Error>;
 }
 
 /// The underlying implementation of the StorageReaderInterface, used by the
@@ -109,12 +134,17 @@ impl StorageReader {
 
     /// Returns the state values range held in the database (lowest to highest).
     /// Note: it is currently assumed that if a node contains a transaction at a
-    /// version, V, the node also contains all state values at V.
+    /// version, V, the node also contains all state values at V (unless the node
+    /// is running in partial state mode, in which case no states are advertised).
     fn fetch_state_values_range(
         &self,
         latest_version: Version,
         transactions_range: &Option<CompleteDataRange<Version>>,
     ) -> aptos_storage_service_types::Result<Option<CompleteDataRange<Version>>, Error> {
+        if self.storage.partial_state_enabled() {
+            return Ok(None);
+        }
+
         let pruner_enabled = self
             .storage
             .is_state_merkle_pruner_enabled()
@@ -224,6 +254,7 @@ impl StorageReaderInterface for StorageReader {
             transactions,
             transaction_outputs,
             states,
+            state_values_by_account: !self.storage.state_kv_sharding_enabled(),
         };
 
         Ok(data_summary)
@@ -498,6 +529,117 @@ impl StorageReaderInterface for StorageReader {
             version, start_index, end_index
         )))
     }
+
+    fn get_state_values_by_keys_with_proof(
+        &self,
+        version: u64,
+        proof_version: u64,
+        state_keys_selector: &StateKeysSelector,
+    ) -> aptos_storage_service_types::Result<StateValuesByKeysWithProof, Error> {
+        // Identify the state keys to fetch. We fetch one more key than the
+        // max chunk size to determine if the response must be truncated.
+        let max_num_state_values = self.config.max_state_chunk_size as usize;
+        let mut state_keys = match state_keys_selector {
+            StateKeysSelector::Account { address, cursor } => {
+                if self.storage.state_kv_sharding_enabled() {
+                    return Err(Error::InvalidRequest(
+                        "The account selector is not supported by servers with a sharded state DB!"
+                            .into(),
+                    ));
+                }
+                let key_prefix = StateKeyPrefix::from(*address);
+                self.storage
+                    .get_prefixed_state_value_iterator(&key_prefix, cursor.as_ref(), version)
+                    .map_err(|error| Error::StorageErrorEncountered(error.to_string()))?
+                    .take(max_num_state_values + 1)
+                    .map(|result| result.map(|(state_key, _)| state_key))
+                    .collect::<StorageResult<Vec<_>>>()
+                    .map_err(|error| Error::StorageErrorEncountered(error.to_string()))?
+            },
+            StateKeysSelector::StateKeys(state_keys) => {
+                if state_keys.is_empty() {
+                    return Err(Error::InvalidRequest(
+                        "The set of requested state keys cannot be empty!".into(),
+                    ));
+                }
+                let mut state_key_hashes = HashSet::new();
+                state_keys
+                    .iter()
+                    .filter(|state_key| state_key_hashes.insert(state_key.hash()))
+                    .take(max_num_state_values + 1)
+                    .cloned()
+                    .collect()
+            },
+        };
+        let next_state_key = if state_keys.len() > max_num_state_values {
+            state_keys.pop()
+        } else {
+            None
+        };
+
+        // Fetch the transaction info (and proof) at the version, and
+        // verify that the version is a state checkpoint.
+        let transaction_info_with_proof = self
+            .storage
+            .get_transaction_by_version(version, proof_version, false)
+            .map_err(|error| Error::StorageErrorEncountered(error.to_string()))?
+            .proof;
+        if transaction_info_with_proof
+            .transaction_info()
+            .state_checkpoint_hash()
+            .is_none()
+        {
+            return Err(Error::InvalidRequest(format!(
+                "The requested version is not a state checkpoint: {:?}",
+                version
+            )));
+        }
+
+        // Attempt to serve the request
+        let mut num_state_values_to_return = state_keys.len();
+        loop {
+            // Fetch the state values (and multi-proof) for the state keys.
+            // The multi-proof requires the keys to be sorted by hash.
+            let response_next_state_key = state_keys
+                .get(num_state_values_to_return)
+                .cloned()
+                .or_else(|| next_state_key.clone());
+            let mut response_state_keys = state_keys[..num_state_values_to_return].to_vec();
+            response_state_keys.sort_by_cached_key(|state_key| state_key.hash());
+            let (state_values, proof) = self
+                .storage
+                .get_state_values_with_multi_proof_by_version(&response_state_keys, version)
+                .map_err(|error| Error::StorageErrorEncountered(error.to_string()))?;
+            let state_values_by_keys_with_proof = StateValuesByKeysWithProof::new(
+                version,
+                response_state_keys.into_iter().zip(state_values).collect(),
+                proof,
+                transaction_info_with_proof.clone(),
+                response_next_state_key,
+            );
+            if num_state_values_to_return <= 1 {
+                return Ok(state_values_by_keys_with_proof); // We cannot return less than a single item
+            }
+
+            // Attempt to divide up the response if it overflows the message size
+            let (overflow_frame, num_bytes) = check_overflow_network_frame(
+                &state_values_by_keys_with_proof,
+                self.config.max_network_chunk_bytes,
+            )?;
+            if !overflow_frame {
+                return Ok(state_values_by_keys_with_proof);
+            } else {
+                increment_network_frame_overflow(
+                    DataResponse::StateValuesByKeysWithProof(state_values_by_keys_with_proof)
+                        .get_label(),
+                );
+                let new_num_state_values_to_return = num_state_values_to_return / 2;
+                debug!("The response with {:?} state values (by keys) was too large (num bytes: {:?}). Retrying with {:?}.",
+                    num_state_values_to_return, num_bytes, new_num_state_values_to_return);
+                num_state_values_to_return = new_num_state_values_to_return; // Try again with half the amount of data
+            }
+        }
+    }
 }
 
 // A simple macro that wraps each storage read call with a timer
@@ -578,7 +720,35 @@ impl DbReader for TimedStorageReader {
             start_idx: usize,
             chunk_size: usize,
         ) -> StorageResult<StateValueChunkWithProof>;
+
+        fn get_prefixed_state_value_iterator(
+            &self,
+            key_prefix: &StateKeyPrefix,
+            cursor: Option<&StateKey>,
+            version: Version,
+        ) -> StorageResult<Box<dyn Iterator<Item = StorageResult<(StateKey, StateValue)>> + '_>>;
+
+        fn get_state_values_with_multi_proof_by_version(
+            &self,
+            state_keys: &[StateKey],
+            version: Version,
+        ) -> StorageResult<(Vec<Option<StateValue>>, SparseMerkleMultiProof)>;
+
+        fn get_transaction_by_version(
+            &self,
+            version: Version,
+            ledger_version: Version,
+            fetch_events: bool,
+        ) -> StorageResult<TransactionWithProof>;
     );
+
+    fn state_kv_sharding_enabled(&self) -> bool {
+        self.storage.state_kv_sharding_enabled()
+    }
+
+    fn partial_state_enabled(&self) -> bool {
+        self.storage.partial_state_enabled()
+    }
 }
 
 /// Calculate `(start..=end).len()`. Returns an error if `end < start` or
@@ -35,7 +35,10 @@ use aptos_types::{
     epoch_change::EpochChangeProof,
     event::EventKey,
     ledger_info::LedgerInfoWithSignatures,
-    proof::{AccumulatorConsistencyProof, SparseMerkleProof, TransactionAccumulatorSummary},
+    proof::{
+        AccumulatorConsistencyProof, SparseMerkleMultiProof, SparseMerkleProof,
+        TransactionAccumulatorSummary,
+    },
     state_proof::StateProof,
     state_store::{
         state_key::StateKey,
@@ -325,6 +328,12 @@ mock! {
             version: Version,
         ) -> aptos_storage_interface::Result<(Option<StateValue>, SparseMerkleProof)>;
 
+        fn get_state_values_with_multi_proof_by_version(
+            &self,
+            state_keys: &[StateKey],
+            version: Version,
+        ) -> aptos_storage_interface::Result<(Vec<Option<StateValue>>, SparseMerkleMultiProof)>;
+
         fn get_pre_committed_ledger_summary(&self) -> aptos_storage_interface::Result<LedgerSummary>;
 
         fn get_epoch_ending_ledger_info(&self, known_version: u64) ->aptos_storage_interface::Result<LedgerInfoWithSignatures>;
@@ -354,6 +363,10 @@ mock! {
         fn get_epoch_snapshot_prune_window(&self) -> aptos_storage_interface::Result<usize>;
 
         fn is_state_merkle_pruner_enabled(&self) -> aptos_storage_interface::Result<bool>;
+
+        fn state_kv_sharding_enabled(&self) -> bool;
+
+        fn partial_state_enabled(&self) -> bool;
     }
 }
 
@@ -382,6 +395,12 @@ pub fn create_mock_db_with_summary_updates(
     db_reader
         .expect_is_state_merkle_pruner_enabled()
         .returning(move || Ok(true));
+    db_reader
+        .expect_partial_state_enabled()
+        .returning(move || false);
+    db_reader
+        .expect_state_kv_sharding_enabled()
+        .returning(move || false);
 
     db_reader
 }
@@ -0,0 +1,341 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::tests::{
+    mock,
+    mock::{MockClient, MockDatabaseReader},
+    utils,
+};
+use aptos_config::config::StorageServiceConfig;
+use aptos_crypto::hash::{CryptoHash, HashValue};
+use aptos_storage_service_types::{
+    requests::{DataRequest, StateKeysSelector, StateValuesByKeysWithProofRequest},
+    responses::{DataResponse, StateValuesByKeysWithProof, StorageServiceResponse},
+    StorageServiceError,
+};
+use aptos_types::{
+    account_address::AccountAddress,
+    proof::{SparseMerkleMultiProof, TransactionAccumulatorProof, TransactionInfoWithProof},
+    state_store::{state_key::StateKey, state_value::StateValue},
+    transaction::{ExecutionStatus, Transaction, TransactionInfo, TransactionWithProof},
+};
+use claims::assert_matches;
+use mockall::predicate::eq;
+
+#[tokio::test]
+async fn test_get_state_values_by_keys_with_proof() {
+    // Test small and large key sets
+    for num_state_keys in [1, 10, 50] {
+        // Create test data (including a duplicate state key)
+        let version = 101;
+        let proof_version = 105;
+        let state_keys = create_state_keys(num_state_keys);
+        let mut requested_state_keys = state_keys.clone();
+        requested_state_keys.push(state_keys[0].clone());
+
+        // Create the mock db reader
+        let mut db_reader = mock::create_mock_db_reader();
+        let transaction_info_with_proof =
+            expect_get_transaction_info_with_proof(&mut db_reader, version, proof_version, true);
+        let (expected_state_values, expected_proof) =
+            expect_get_state_values_with_multi_proof_by_version(
+                &mut db_reader,
+                version,
+                &state_keys,
+            );
+
+        // Create the storage client and server
+        let (mut mock_client, mut service, _, _, _) = MockClient::new(Some(db_reader), None);
+        utils::update_storage_server_summary(&mut service, proof_version, 10);
+        tokio::spawn(service.start());
+
+        // Process a request to fetch the state values by keys
+        let response = get_state_values_by_keys_with_proof(
+            &mut mock_client,
+            version,
+            proof_version,
+            StateKeysSelector::StateKeys(requested_state_keys),
+            false,
+        )
+        .await
+        .unwrap();
+
+        // Verify the response is correct
+        assert_matches!(response, StorageServiceResponse::RawResponse(_));
+        assert_eq!(
+            response.get_data_response().unwrap(),
+            DataResponse::StateValuesByKeysWithProof(StateValuesByKeysWithProof::new(
+                version,
+                expected_state_values,
+                expected_proof,
+                transaction_info_with_proof,
+                None
+            ))
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_get_state_values_by_keys_with_proof_chunk_limit() {
+    // Create test data
+    let max_state_chunk_size = 20;
+    let version = 101;
+    let state_keys = create_state_keys(max_state_chunk_size * 2); // Request more keys than the max
+
+    // Create the mock db reader (only the first chunk of keys should be read)
+    let mut db_reader = mock::create_mock_db_reader();
+    let transaction_info_with_proof =
+        expect_get_transaction_info_with_proof(&mut db_reader, version, version, true);
+    let (expected_state_values, expected_proof) =
+        expect_get_state_values_with_multi_proof_by_version(
+            &mut db_reader,
+            version,
+            &state_keys[..max_state_chunk_size as usize],
+        );
+
+    // Create a storage config with the specified max chunk size
+    let storage_config = StorageServiceConfig {
+        max_state_chunk_size,
+        ..Default::default()
+    };
+
+    // Create the storage client and server
+    let (mut mock_client, mut service, _, _, _) =
+        MockClient::new(Some(db_reader), Some(storage_config));
+    utils::update_storage_server_summary(&mut service, version, 10);
+    tokio::spawn(service.start());
+
+    // Process a request to fetch the state values by keys
+    let response = get_state_values_by_keys_with_proof(
+        &mut mock_client,
+        version,
+        version,
+        StateKeysSelector::StateKeys(state_keys.clone()),
+        true,
+    )
+    .await
+    .unwrap();
+
+    // Verify the response is truncated and identifies the next state key
+    assert_matches!(response, StorageServiceResponse::CompressedResponse(_, _));
+    assert_eq!(
+        response.get_data_response().unwrap(),
+        DataResponse::StateValuesByKeysWithProof(StateValuesByKeysWithProof::new(
+            version,
+            expected_state_values,
+            expected_proof,
+            transaction_info_with_proof,
+            Some(state_keys[max_state_chunk_size as usize].clone()),
+        ))
+    );
+}
+
+#[tokio::test]
+async fn test_get_state_values_by_keys_with_proof_invalid() {
+    // Create the storage client and server
+    let version = 101;
+    let (mut mock_client, mut service, _, _, _) = MockClient::new(None, None);
+    utils::update_storage_server_summary(&mut service, version, 10);
+    tokio::spawn(service.start());
+
+    // Process a request to fetch an empty set of state keys
+    let response = get_state_values_by_keys_with_proof(
+        &mut mock_client,
+        version,
+        version,
+        StateKeysSelector::StateKeys(vec![]),
+        false,
+    )
+    .await
+    .unwrap_err();
+
+    // Verify the request is invalid
+    assert_matches!(response, StorageServiceError::InvalidRequest(_));
+}
+
+#[tokio::test]
+async fn test_get_state_values_by_keys_with_proof_not_checkpoint() {
+    // Create the mock db reader (the version is not a state checkpoint)
+    let version = 101;
+    let mut db_reader = mock::create_mock_db_reader();
+    expect_get_transaction_info_with_proof(&mut db_reader, version, version, false);
+
+    // Create the storage client and server
+    let (mut mock_client, mut service, _, _, _) = MockClient::new(Some(db_reader), None);
+    utils::update_storage_server_summary(&mut service, version, 10);
+    tokio::spawn(service.start());
+
+    // Process a request to fetch the state values by keys
+    let response = get_state_values_by_keys_with_proof(
+        &mut mock_client,
+        version,
+        version,
+        StateKeysSelector::StateKeys(create_state_keys(10)),
+        false,
+    )
+    .await
+    .unwrap_err();
+
+    // Verify the request is invalid
+    assert_matches!(response, StorageServiceError::InvalidRequest(_));
+}
+
+#[tokio::test]
+async fn test_get_state_values_by_keys_with_proof_sharded_account() {
+    // Create the mock db reader (with a sharded state DB)
+    let version = 101;
+    let mut db_reader = mock::create_mock_db_reader();
+    db_reader
+        .expect_state_kv_sharding_enabled()
+        .times(1)
+        .return_const(true);
+
+    // Create the storage client and server
+    let (mut mock_client, mut service, _, _, _) = MockClient::new(Some(db_reader), None);
+    utils::update_storage_server_summary(&mut service, version, 10);
+    tokio::spawn(service.start());
+
+    // Process a request to fetch the state values of an account
+    let response = get_state_values_by_keys_with_proof(
+        &mut mock_client,
+        version,
+        version,
+        StateKeysSelector::Account {
+            address: AccountAddress::random(),
+            cursor: None,
+        },
+        false,
+    )
+    .await
+    .unwrap_err();
+
+    // Verify the request is invalid (prefix iteration is unsupported by sharded DBs)
+    assert_matches!(response, StorageServiceError::InvalidRequest(_));
+}
+
+#[tokio::test]
+async fn test_get_state_values_by_keys_with_proof_not_serviceable() {
+    // Create test data
+    let version = 101;
+    let state_keys = create_state_keys(10);
+
+    // Create the storage client and server (that cannot service the request)
+    let (mut mock_client, mut service, _, _, _) = MockClient::new(None, None);
+    utils::update_storage_server_summary(&mut service, version - 1, 10);
+    tokio::spawn(service.start());
+
+    // Process a request to fetch the state values by keys
+    let response = get_state_values_by_keys_with_proof(
+        &mut mock_client,
+        version,
+        version,
+        StateKeysSelector::StateKeys(state_keys),
+        false,
+    )
+    .await
+    .unwrap_err();
+
+    // Verify the request is not serviceable
+    assert_matches!(response, StorageServiceError::InvalidRequest(_));
+}
+
+/// Creates a set of unique state keys
+fn create_state_keys(num_state_keys: u64) -> Vec<StateKey> {
+    (0..num_state_keys)
+        .map(|index| StateKey::raw(&index.to_le_bytes()))
+        .collect()
+}
+
+/// Sets an expectation on the given mock db for a call to fetch the transaction
+/// info (with proof) at the given version. Returns the transaction info with proof.
+fn expect_get_transaction_info_with_proof(
+    mock_db: &mut MockDatabaseReader,
+    version: u64,
+    proof_version: u64,
+    state_checkpoint: bool,
+) -> TransactionInfoWithProof {
+    // Create the transaction info with proof
+    let state_checkpoint_hash = state_checkpoint.then(HashValue::random);
+    let transaction_info = TransactionInfo::new(
+        HashValue::random(),
+        HashValue::random(),
+        HashValue::random(),
+        state_checkpoint_hash,
+        0,
+        ExecutionStatus::Success,
+    );
+    let transaction_info_with_proof = TransactionInfoWithProof::new(
+        TransactionAccumulatorProof::new(vec![HashValue::random()]),
+        transaction_info,
+    );
+
+    // Set the expectation
+    let transaction_with_proof = TransactionWithProof::new(
+        version,
+        Transaction::StateCheckpoint(HashValue::random()),
+        None,
+        transaction_info_with_proof.clone(),
+    );
+    mock_db
+        .expect_get_transaction_by_version()
+        .times(1)
+        .with(eq(version), eq(proof_version), eq(false))
+        .returning(move |_, _, _| Ok(transaction_with_proof.clone()));
+
+    transaction_info_with_proof
+}
+
+/// Sets an expectation on the given mock db for a call to fetch the state values
+/// (with a multi-proof) for the state keys. Returns the expected state values
+/// (sorted by state key hash) and the multi-proof.
+fn expect_get_state_values_with_multi_proof_by_version(
+    mock_db: &mut MockDatabaseReader,
+    version: u64,
+    state_keys: &[StateKey],
+) -> (Vec<(StateKey, Option<StateValue>)>, SparseMerkleMultiProof) {
+    // Sort the state keys by hash (as required by the multi-proof)
+    let mut state_keys = state_keys.to_vec();
+    state_keys.sort_by_cached_key(|state_key| state_key.hash());
+
+    // Create the state values (every other key is missing)
+    let state_values: Vec<_> = (0..state_keys.len())
+        .map(|index| {
+            if index % 2 == 0 {
+                Some(StateValue::new_legacy(vec![index as u8].into()))
+            } else {
+                None
+            }
+        })
+        .collect();
+    let proof = SparseMerkleMultiProof::new(vec![], vec![HashValue::random()]);
+
+    // Set the expectation
+    let response = (state_values.clone(), proof.clone());
+    let expected_state_keys = state_keys.clone();
+    mock_db
+        .expect_get_state_values_with_multi_proof_by_version()
+        .times(1)
+        .withf(move |state_keys, request_version| {
+            state_keys.to_vec() == expected_state_keys && *request_version == version
+        })
+        .returning(move |_, _| Ok(response.clone()));
+
+    (state_keys.into_iter().zip(state_values).collect(), proof)
+}
+
+/// Sends a state values by keys with proof request and processes the response
+async fn get_state_values_by_keys_with_proof(
+    mock_client: &mut MockClient,
+    version: u64,
+    proof_version: u64,
+    state_keys_selector: StateKeysSelector,
+    use_compression: bool,
+) -> Result<StorageServiceResponse, StorageServiceError> {
+    let data_request =
+        DataRequest::GetStateValuesByKeysWithProof(StateValuesByKeysWithProofRequest {
+            version,
+            proof_version,
+            state_keys_selector,
+        });
+    utils::send_storage_request(mock_client, use_compression, data_request).await
+}
@@ -3,7 +3,7 @@
 
 use crate::{
     refresh_cached_storage_summary,
-    storage::StorageReader,
+    storage::{StorageReader, StorageReaderInterface},
     tests::{
         mock,
         mock::{MockClient, MockDatabaseReader},
@@ -46,6 +46,7 @@ async fn test_refresh_cached_storage_summary() {
         lowest_version,
         state_prune_window,
         highest_ledger_info.clone(),
+        false,
     );
     let storage_reader = StorageReader::new(storage_service_config, Arc::new(db_reader));
 
@@ -160,6 +161,7 @@ async fn test_get_storage_server_summary_advance_time() {
         lowest_version,
         state_prune_window,
         highest_ledger_info.clone(),
+        false,
     );
 
     // Create the storage client and server
@@ -218,6 +220,7 @@ async fn test_get_storage_server_summary_notification() {
         lowest_version,
         state_prune_window,
         highest_ledger_info.clone(),
+        false,
     );
 
     // Create the storage client and server
@@ -262,12 +265,41 @@ async fn test_get_storage_server_summary_notification() {
     }
 }
 
+#[test]
+fn test_get_data_summary_partial_state() {
+    // Create test data
+    let highest_version = 1000;
+    let highest_epoch = 430;
+    let lowest_version = 11;
+    let state_prune_window = 200;
+    let highest_ledger_info =
+        utils::create_test_ledger_info_with_sigs(highest_epoch, highest_version);
+
+    // Create the storage reader for a DB running in partial state mode
+    let db_reader = create_db_reader_with_expectations(
+        lowest_version,
+        state_prune_window,
+        highest_ledger_info.clone(),
+        true,
+    );
+    let storage_reader = StorageReader::new(StorageServiceConfig::default(), Arc::new(db_reader));
+
+    // Verify that the transactions are advertised, but the states are not
+    let data_summary = storage_reader.get_data_summary().unwrap();
+    assert_eq!(
+        data_summary.transactions,
+        Some(CompleteDataRange::new(lowest_version, highest_version).unwrap())
+    );
+    assert_eq!(data_summary.states, None);
+}
+
 /// Creates a mock database reader with the necessary
 /// expectations to satisfy the storage server summary request.
 fn create_db_reader_with_expectations(
     lowest_version: Version,
     state_prune_window: usize,
     highest_ledger_info: LedgerInfoWithSignatures,
+    partial_state_enabled: bool,
 ) -> MockDatabaseReader {
     // Create the mock reader
     let mut db_reader = mock::create_mock_db_reader();
@@ -289,6 +321,12 @@ fn create_db_reader_with_expectations(
         .expect_is_state_merkle_pruner_enabled()
         .returning(move || Ok(true));
     db_reader
+        .expect_partial_state_enabled()
+        .returning(move || partial_state_enabled);
+    db_reader
+        .expect_state_kv_sharding_enabled()
+        .returning(move || false);
+    db_reader
 }
 
 /// Sends a storage summary request and processes the response
@@ -333,6 +371,7 @@ fn verify_server_summary_response(
                 )
                 .unwrap(),
             ),
+            state_values_by_account: true,
         },
     };
 
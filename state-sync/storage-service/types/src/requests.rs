@@ -2,7 +2,9 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::COMPRESSION_SUFFIX_LABEL;
-use aptos_types::transaction::Version;
+use aptos_types::{
+    account_address::AccountAddress, state_store::state_key::StateKey, transaction::Version,
+};
 use serde::{Deserialize, Serialize};
 
 /// A storage service request.
@@ -47,6 +49,7 @@ pub enum DataRequest {
     SubscribeTransactionOutputsWithProof(SubscribeTransactionOutputsWithProofRequest), // Subscribes to transaction outputs with a proof
     SubscribeTransactionsOrOutputsWithProof(SubscribeTransactionsOrOutputsWithProofRequest), // Subscribes to transactions or outputs with a proof
     SubscribeTransactionsWithProof(SubscribeTransactionsWithProofRequest), // Subscribes to transactions with a proof
+    GetStateValuesByKeysWithProof(StateValuesByKeysWithProofRequest), // Fetches a set of states (by key) with proofs
 }
 
 impl DataRequest {
@@ -73,6 +76,7 @@ impl DataRequest {
                 "subscribe_transactions_or_outputs_with_proof"
             },
             Self::SubscribeTransactionsWithProof(_) => "subscribe_transactions_with_proof",
+            Self::GetStateValuesByKeysWithProof(_) => "get_state_values_by_keys_with_proof",
         }
     }
 
@@ -130,6 +134,38 @@ pub struct StateValuesWithProofRequest {
     pub end_index: u64,   // The index to stop fetching state values (inclusive)
 }
 
+/// A storage service request for fetching a set of state values
+/// (selected by key) at a specified version. The state values are
+/// returned with a sparse Merkle multi-proof against the state root,
+/// which is itself anchored to the ledger info at the proof version.
+/// The version must be a state checkpoint.
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct StateValuesByKeysWithProofRequest {
+    pub version: u64, // The version to fetch the state values at (must be a state checkpoint)
+    pub proof_version: u64, // The version the state root proof should be relative to
+    pub state_keys_selector: StateKeysSelector, // The selector for the state keys to fetch
+}
+
+/// The selector for the state keys to fetch in a
+/// `StateValuesByKeysWithProofRequest`.
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub enum StateKeysSelector {
+    /// The state keys belonging to the given account. If the response
+    /// is truncated, the request can be resumed by setting the cursor
+    /// to the next state key returned by the server (inclusive). Note:
+    /// the proofs only show that the returned keys belong to the account,
+    /// not that all of its keys were returned. This also requires the
+    /// server to iterate over state keys by prefix, which is not supported
+    /// by servers with a sharded state DB.
+    Account {
+        address: AccountAddress,
+        cursor: Option<StateKey>,
+    },
+    /// An explicit set of state keys. State keys that do not
+    /// exist are returned with a proof of non-inclusion.
+    StateKeys(Vec<StateKey>),
+}
+
 /// A storage service request for fetching a transaction output list with a
 /// corresponding proof.
 #[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
@@ -5,11 +5,13 @@ use crate::{
     requests::DataRequest::{
         GetEpochEndingLedgerInfos, GetNewTransactionOutputsWithProof,
         GetNewTransactionsOrOutputsWithProof, GetNewTransactionsWithProof,
-        GetNumberOfStatesAtVersion, GetServerProtocolVersion, GetStateValuesWithProof,
-        GetStorageServerSummary, GetTransactionOutputsWithProof, GetTransactionsOrOutputsWithProof,
-        GetTransactionsWithProof, SubscribeTransactionOutputsWithProof,
-        SubscribeTransactionsOrOutputsWithProof, SubscribeTransactionsWithProof,
+        GetNumberOfStatesAtVersion, GetServerProtocolVersion, GetStateValuesByKeysWithProof,
+        GetStateValuesWithProof, GetStorageServerSummary, GetTransactionOutputsWithProof,
+        GetTransactionsOrOutputsWithProof, GetTransactionsWithProof,
+        SubscribeTransactionOutputsWithProof, SubscribeTransactionsOrOutputsWithProof,
+        SubscribeTransactionsWithProof,
     },
+    requests::StateKeysSelector,
     responses::Error::DegenerateRangeError,
     Epoch, StorageServiceRequest, COMPRESSION_SUFFIX_LABEL,
 };
@@ -17,11 +19,16 @@ use aptos_compression::{client::CompressionClient, CompressedData};
 use aptos_config::config::{
     AptosDataClientConfig, StorageServiceConfig, MAX_APPLICATION_MESSAGE_SIZE,
 };
+use aptos_crypto::hash::CryptoHash;
 use aptos_time_service::{TimeService, TimeServiceTrait};
 use aptos_types::{
     epoch_change::EpochChangeProof,
-    ledger_info::LedgerInfoWithSignatures,
-    state_store::state_value::StateValueChunkWithProof,
+    ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
+    proof::{SparseMerkleMultiProof, TransactionInfoWithProof},
+    state_store::{
+        state_key::{prefix::StateKeyPrefix, StateKey},
+        state_value::{StateValue, StateValueChunkWithProof},
+    },
     transaction::{TransactionListWithProof, TransactionOutputListWithProof, Version},
 };
 use num_traits::{PrimInt, Zero};
@@ -29,6 +36,7 @@ use num_traits::{PrimInt, Zero};
 use proptest::prelude::{any, Arbitrary, BoxedStrategy, Strategy};
 use serde::{Deserialize, Serialize};
 use std::{
+    collections::HashSet,
     convert::TryFrom,
     fmt::{Display, Formatter},
 };
@@ -139,6 +147,7 @@ pub enum DataResponse {
     TransactionsWithProof(TransactionListWithProof),
     NewTransactionsOrOutputsWithProof((TransactionOrOutputListWithProof, LedgerInfoWithSignatures)),
     TransactionsOrOutputsWithProof(TransactionOrOutputListWithProof),
+    StateValuesByKeysWithProof(StateValuesByKeysWithProof),
 }
 
 impl DataResponse {
@@ -156,6 +165,7 @@ impl DataResponse {
             Self::TransactionsWithProof(_) => "transactions_with_proof",
             Self::NewTransactionsOrOutputsWithProof(_) => "new_transactions_or_outputs_with_proof",
             Self::TransactionsOrOutputsWithProof(_) => "transactions_or_outputs_with_proof",
+            Self::StateValuesByKeysWithProof(_) => "state_values_by_keys_with_proof",
         }
     }
 }
@@ -347,6 +357,168 @@ impl TryFrom<StorageServiceResponse> for TransactionOrOutputListWithProof {
     }
 }
 
+impl TryFrom<StorageServiceResponse> for StateValuesByKeysWithProof {
+    type Error = crate::responses::Error;
+
+    fn try_from(response: StorageServiceResponse) -> crate::Result<Self, Self::Error> {
+        let data_response = response.get_data_response()?;
+        match data_response {
+            DataResponse::StateValuesByKeysWithProof(inner) => Ok(inner),
+            _ => Err(Error::UnexpectedResponseError(format!(
+                "expected state_values_by_keys_with_proof, found {}",
+                data_response.get_label()
+            ))),
+        }
+    }
+}
+
+/// A set of state values (selected by key) at a specific version, sorted
+/// by state key hash. The state values are authenticated by a single
+/// sparse Merkle multi-proof against the state checkpoint hash at the
+/// version, which is in turn authenticated by the transaction info proof
+/// (relative to the ledger info at the proof version). Missing state
+/// values (i.e., `None`) are proven by non-inclusion.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct StateValuesByKeysWithProof {
+    pub version: Version, // The version of the state values
+    pub state_values: Vec<(StateKey, Option<StateValue>)>, // The state values (sorted by state key hash)
+    pub proof: SparseMerkleMultiProof, // The multi-proof of the state values against the state root
+    pub transaction_info_with_proof: TransactionInfoWithProof, // The proof of the state root at the version
+    pub next_state_key: Option<StateKey>, // The next state key to fetch (if the response was truncated)
+}
+
+impl StateValuesByKeysWithProof {
+    pub fn new(
+        version: Version,
+        state_values: Vec<(StateKey, Option<StateValue>)>,
+        proof: SparseMerkleMultiProof,
+        transaction_info_with_proof: TransactionInfoWithProof,
+        next_state_key: Option<StateKey>,
+    ) -> Self {
+        Self {
+            version,
+            state_values,
+            proof,
+            transaction_info_with_proof,
+            next_state_key,
+        }
+    }
+
+    /// Returns true iff the response was truncated and more
+    /// state values remain to be fetched.
+    pub fn is_truncated(&self) -> bool {
+        self.next_state_key.is_some()
+    }
+
+    /// Verifies the state values against the given (trusted) ledger info.
+    /// This verifies that: (i) the transaction info at the version is
+    /// proven by the ledger info; (ii) the version is a state checkpoint;
+    /// and (iii) the state values are proven by the state checkpoint hash.
+    pub fn verify(&self, ledger_info: &LedgerInfo) -> crate::Result<(), Error> {
+        // Verify the transaction info at the version
+        self.transaction_info_with_proof
+            .verify(ledger_info, self.version)
+            .map_err(|error| {
+                Error::UnexpectedResponseError(format!(
+                    "failed to verify the transaction info at version {}: {:?}",
+                    self.version, error
+                ))
+            })?;
+
+        // Verify the version is a state checkpoint
+        let state_checkpoint_hash = self
+            .transaction_info_with_proof
+            .transaction_info()
+            .state_checkpoint_hash()
+            .ok_or_else(|| {
+                Error::UnexpectedResponseError(format!(
+                    "the version is not a state checkpoint: {}",
+                    self.version
+                ))
+            })?;
+
+        // Verify the state values against the state checkpoint hash
+        let elements: Vec<_> = self
+            .state_values
+            .iter()
+            .map(|(state_key, state_value)| (state_key.hash(), state_value.as_ref()))
+            .collect();
+        self.proof
+            .verify(state_checkpoint_hash, &elements)
+            .map_err(|error| {
+                Error::UnexpectedResponseError(format!(
+                    "failed to verify the state values at version {}: {:?}",
+                    self.version, error
+                ))
+            })
+    }
+
+    /// Verifies that the state values are for the state keys selected by
+    /// the given selector. For an explicit set of state keys, the response
+    /// must contain exactly the (deduplicated) requested keys, or a prefix
+    /// of them if the response was truncated, and the next state key must
+    /// be the first key not returned. For an account, all state keys must
+    /// belong to the account. Note: the proofs cannot show that all state
+    /// keys of the account were returned.
+    pub fn verify_state_keys(
+        &self,
+        state_keys_selector: &StateKeysSelector,
+    ) -> crate::Result<(), Error> {
+        match state_keys_selector {
+            StateKeysSelector::Account { address, .. } => {
+                let key_prefix = StateKeyPrefix::from(*address);
+                let state_keys = self
+                    .state_values
+                    .iter()
+                    .map(|(state_key, _)| state_key)
+                    .chain(self.next_state_key.as_ref());
+                for state_key in state_keys {
+                    if !key_prefix.is_prefix(state_key).unwrap_or(false) {
+                        return Err(Error::UnexpectedResponseError(format!(
+                            "the state key does not belong to account {}: {:?}",
+                            address, state_key
+                        )));
+                    }
+                }
+            },
+            StateKeysSelector::StateKeys(requested_state_keys) => {
+                let mut requested_state_key_hashes = HashSet::new();
+                let requested_state_keys: Vec<_> = requested_state_keys
+                    .iter()
+                    .filter(|state_key| requested_state_key_hashes.insert(state_key.hash()))
+                    .collect();
+                let num_state_values = self.state_values.len();
+                if num_state_values == 0 || num_state_values > requested_state_keys.len() {
+                    return Err(Error::UnexpectedResponseError(format!(
+                        "unexpected number of state values: {}, requested state keys: {}",
+                        num_state_values,
+                        requested_state_keys.len()
+                    )));
+                }
+
+                let returned_state_keys: HashSet<_> = self
+                    .state_values
+                    .iter()
+                    .map(|(state_key, _)| state_key)
+                    .collect();
+                let expected_state_keys: HashSet<_> = requested_state_keys[..num_state_values]
+                    .iter()
+                    .copied()
+                    .collect();
+                let expected_next_state_key = requested_state_keys.get(num_state_values).copied();
+                if returned_state_keys != expected_state_keys
+                    || self.next_state_key.as_ref() != expected_next_state_key
+                {
+                    return Err(Error::UnexpectedResponseError(
+                        "the state keys do not match the requested state keys".into(),
+                    ));
+                }
+            },
+        }
+        Ok(())
+    }
+}
+
 /// The protocol version run by this server. Clients request this first to
 /// identify what API calls and data requests the server supports.
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
@@ -431,6 +603,9 @@ pub struct DataSummary {
     /// is [(X,Y)], it means all transaction outputs for versions X->Y
     /// (inclusive) are held.
     pub transaction_outputs: Option<CompleteDataRange<Version>>,
+    /// True iff state values can be fetched by account, which is not
+    /// supported by servers with a sharded state DB.
+    pub state_values_by_account: bool,
 }
 
 impl DataSummary {
@@ -488,6 +663,31 @@ impl DataSummary {
 
                 can_serve_states && can_create_proof
             },
+            GetStateValuesByKeysWithProof(request) => {
+                if request.version > request.proof_version {
+                    return false; // The state root cannot be proven by an older ledger info
+                }
+                if matches!(
+                    request.state_keys_selector,
+                    StateKeysSelector::Account { .. }
+                ) && !self.state_values_by_account
+                {
+                    return false; // The server cannot iterate over the state keys of an account
+                }
+
+                let can_serve_states = self
+                    .states
+                    .map(|range| range.contains(request.version))
+                    .unwrap_or(false);
+
+                let can_create_proof = self
+                    .synced_ledger_info
+                    .as_ref()
+                    .map(|li| li.ledger_info().version() >= request.proof_version)
+                    .unwrap_or(false);
+
+                can_serve_states && can_create_proof
+            },
             GetTransactionOutputsWithProof(request) => {
                 let desired_range =
                     match CompleteDataRange::new(request.start_version, request.end_version) {
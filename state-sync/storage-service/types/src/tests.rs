@@ -5,22 +5,30 @@ use crate::{
     requests::{
         DataRequest, EpochEndingLedgerInfoRequest, NewTransactionOutputsWithProofRequest,
         NewTransactionsOrOutputsWithProofRequest, NewTransactionsWithProofRequest,
-        StateValuesWithProofRequest, SubscribeTransactionOutputsWithProofRequest,
+        StateKeysSelector, StateValuesByKeysWithProofRequest, StateValuesWithProofRequest,
+        SubscribeTransactionOutputsWithProofRequest,
         SubscribeTransactionsOrOutputsWithProofRequest, SubscribeTransactionsWithProofRequest,
         SubscriptionStreamMetadata, TransactionOutputsWithProofRequest,
         TransactionsOrOutputsWithProofRequest, TransactionsWithProofRequest,
     },
-    responses::{CompleteDataRange, DataSummary, ProtocolMetadata},
+    responses::{CompleteDataRange, DataSummary, ProtocolMetadata, StateValuesByKeysWithProof},
     Epoch, StorageServiceRequest,
 };
 use aptos_config::config::AptosDataClientConfig;
-use aptos_crypto::hash::HashValue;
+use aptos_crypto::hash::{CryptoHash, HashValue};
 use aptos_time_service::{TimeService, TimeServiceTrait};
 use aptos_types::{
+    account_address::AccountAddress,
+    account_config::AccountResource,
     aggregate_signature::AggregateSignature,
     block_info::BlockInfo,
     ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
-    transaction::Version,
+    proof::{
+        SparseMerkleLeafNode, SparseMerkleMultiProof, SparseMerkleMultiProofLeaf,
+        TransactionAccumulatorProof, TransactionInfoWithProof,
+    },
+    state_store::{state_key::StateKey, state_value::StateValue},
+    transaction::{ExecutionStatus, TransactionInfo, Version},
 };
 use claims::{assert_err, assert_ok};
 use proptest::{arbitrary::any, prelude::*};
@@ -466,6 +474,136 @@ fn test_data_summary_can_service_state_chunk_request() {
     }
 }
 
+#[test]
+fn test_data_summary_can_service_state_values_by_keys_request() {
+    // Create a data client config and data summary
+    let data_client_config = AptosDataClientConfig::default();
+    let data_summary = DataSummary {
+        synced_ledger_info: Some(create_ledger_info_at_version(250)),
+        states: Some(create_data_range(100, 300)),
+        state_values_by_account: true,
+        ..Default::default()
+    };
+
+    // Verify the different requests that can be serviced
+    for compression in [true, false] {
+        // Test the valid request versions
+        for (version, proof_version) in [(100, 100), (100, 250), (200, 250), (250, 250)] {
+            let request = create_state_values_by_keys_request(version, proof_version, compression);
+            verify_serviceability(&data_client_config, &data_summary, None, request, true);
+        }
+
+        // Test invalid request versions
+        for (version, proof_version) in [(50, 250), (99, 250), (200, 100), (250, 251), (300, 300)] {
+            let request = create_state_values_by_keys_request(version, proof_version, compression);
+            verify_serviceability(&data_client_config, &data_summary, None, request, false);
+        }
+    }
+
+    // Verify that account requests cannot be serviced if the server does not support them
+    let data_summary = DataSummary {
+        state_values_by_account: false,
+        ..data_summary
+    };
+    for compression in [true, false] {
+        let request = create_state_values_by_keys_request(200, 250, compression);
+        verify_serviceability(&data_client_config, &data_summary, None, request, false);
+    }
+}
+
+#[test]
+fn test_state_values_by_keys_verify() {
+    // Create a response and the ledger info that proves it
+    let (state_values_by_keys, ledger_info) = create_state_values_by_keys_response(true);
+
+    // Verify the response against the ledger info
+    assert_ok!(state_values_by_keys.verify(&ledger_info));
+
+    // Verify that a response with a modified state value fails verification
+    let mut invalid_response = state_values_by_keys.clone();
+    invalid_response.state_values[0].1 = Some(StateValue::from(vec![1, 2, 3]));
+    assert_err!(invalid_response.verify(&ledger_info));
+
+    // Verify that a response with a missing state value fails verification
+    let mut invalid_response = state_values_by_keys.clone();
+    invalid_response.state_values[0].1 = None;
+    assert_err!(invalid_response.verify(&ledger_info));
+
+    // Verify that a response at a different version fails verification
+    let mut invalid_response = state_values_by_keys;
+    invalid_response.version = 1;
+    assert_err!(invalid_response.verify(&ledger_info));
+
+    // Verify that a response at a version that is not a state checkpoint fails verification
+    let (state_values_by_keys, ledger_info) = create_state_values_by_keys_response(false);
+    assert_err!(state_values_by_keys.verify(&ledger_info));
+}
+
+#[test]
+fn test_state_values_by_keys_verify_state_keys() {
+    // Create a response for the first two of three (deduplicated) requested keys
+    let state_keys: Vec<_> = (0..3)
+        .map(|index| StateKey::raw(format!("state_key_{}", index).as_bytes()))
+        .collect();
+    let (mut state_values_by_keys, _) = create_state_values_by_keys_response(true);
+    state_values_by_keys.state_values =
+        vec![(state_keys[1].clone(), None), (state_keys[0].clone(), None)];
+    state_values_by_keys.next_state_key = Some(state_keys[2].clone());
+    let selector = StateKeysSelector::StateKeys(vec![
+        state_keys[0].clone(),
+        state_keys[1].clone(),
+        state_keys[0].clone(),
+        state_keys[2].clone(),
+    ]);
+
+    // Verify the response (in any order) matches the requested keys
+    assert_ok!(state_values_by_keys.verify_state_keys(&selector));
+
+    // Verify that a response with a wrong next state key fails verification
+    let mut invalid_response = state_values_by_keys.clone();
+    invalid_response.next_state_key = None;
+    assert_err!(invalid_response.verify_state_keys(&selector));
+
+    // Verify that a response with an unrequested key fails verification
+    let mut invalid_response = state_values_by_keys.clone();
+    invalid_response.state_values[0].0 = StateKey::raw(b"unrequested_state_key");
+    assert_err!(invalid_response.verify_state_keys(&selector));
+
+    // Verify that a response with a skipped key fails verification
+    let mut invalid_response = state_values_by_keys.clone();
+    invalid_response.state_values[0].0 = state_keys[2].clone();
+    assert_err!(invalid_response.verify_state_keys(&selector));
+
+    // Verify that an empty response fails verification
+    let mut invalid_response = state_values_by_keys;
+    invalid_response.state_values = vec![];
+    assert_err!(invalid_response.verify_state_keys(&selector));
+
+    // Verify the response for an account only contains keys of the account
+    let address = AccountAddress::random();
+    let selector = StateKeysSelector::Account {
+        address,
+        cursor: None,
+    };
+    let (mut state_values_by_keys, _) = create_state_values_by_keys_response(true);
+    state_values_by_keys.state_values = vec![(
+        StateKey::resource_typed::<AccountResource>(&address).unwrap(),
+        None,
+    )];
+    assert_ok!(state_values_by_keys.verify_state_keys(&selector));
+
+    // Verify that a response with a key of another account fails verification
+    let mut invalid_response = state_values_by_keys.clone();
+    invalid_response.next_state_key =
+        Some(StateKey::resource_typed::<AccountResource>(&AccountAddress::random()).unwrap());
+    assert_err!(invalid_response.verify_state_keys(&selector));
+
+    // Verify that a response with a key that is not under any account fails verification
+    let mut invalid_response = state_values_by_keys;
+    invalid_response.state_values[0].0 = StateKey::raw(b"state_key");
+    assert_err!(invalid_response.verify_state_keys(&selector));
+}
+
 #[test]
 fn test_protocol_metadata_service() {
     // Create the protocol metadata
@@ -683,6 +821,65 @@ fn create_state_values_request(
     StorageServiceRequest::new(data_request, use_compression)
 }
 
+/// Creates a request for state values (by account) at a given version
+fn create_state_values_by_keys_request(
+    version: Version,
+    proof_version: Version,
+    use_compression: bool,
+) -> StorageServiceRequest {
+    let data_request =
+        DataRequest::GetStateValuesByKeysWithProof(StateValuesByKeysWithProofRequest {
+            version,
+            proof_version,
+            state_keys_selector: StateKeysSelector::Account {
+                address: AccountAddress::random(),
+                cursor: None,
+            },
+        });
+    StorageServiceRequest::new(data_request, use_compression)
+}
+
+/// Creates a response with a single state value (at version 0) and the
+/// ledger info that proves it. If `state_checkpoint` is false, the version
+/// of the response is not a state checkpoint.
+fn create_state_values_by_keys_response(
+    state_checkpoint: bool,
+) -> (StateValuesByKeysWithProof, LedgerInfo) {
+    // Create a state tree with a single state value (the leaf is the root)
+    let state_key = StateKey::raw(b"state_key");
+    let state_value = StateValue::from(vec![0; 10]);
+    let leaf = SparseMerkleLeafNode::new(state_key.hash(), state_value.hash());
+    let proof =
+        SparseMerkleMultiProof::new(vec![SparseMerkleMultiProofLeaf::new(0, Some(leaf))], vec![]);
+
+    // Create the transaction info at version 0 (the root of the accumulator)
+    let state_checkpoint_hash = state_checkpoint.then(|| leaf.hash());
+    let transaction_info = TransactionInfo::new(
+        HashValue::random(),
+        HashValue::random(),
+        HashValue::random(),
+        state_checkpoint_hash,
+        0,
+        ExecutionStatus::Success,
+    );
+    let ledger_info = LedgerInfo::new(
+        BlockInfo::new(0, 0, HashValue::zero(), transaction_info.hash(), 0, 0, None),
+        HashValue::zero(),
+    );
+    let transaction_info_with_proof =
+        TransactionInfoWithProof::new(TransactionAccumulatorProof::new(vec![]), transaction_info);
+
+    // Create the response
+    let state_values_by_keys = StateValuesByKeysWithProof::new(
+        0,
+        vec![(state_key, Some(state_value))],
+        proof,
+        transaction_info_with_proof,
+        None,
+    );
+    (state_values_by_keys, ledger_info)
+}
+
 /// Creates a request for state values at a given version
 fn create_state_values_request_at_version(
     version: Version,
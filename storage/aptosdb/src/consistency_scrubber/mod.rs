@@ -0,0 +1,737 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! The consistency scrubber continuously re-verifies the persisted data in the background, so
+//! that silent corruption (e.g., bit rot or a faulty disk) is detected before the data is served
+//! or relied upon. For every version in the ledger, it verifies:
+//!   * the transaction info against the transaction accumulator (both the stored leaf and a
+//!     range proof towards the root hash in the latest ledger info),
+//!   * the transaction against the transaction hash in the transaction info, and
+//!   * the events against the event root hash in the transaction info.
+//!
+//! For every leaf of a recent state snapshot, it verifies the state value in the state KV DB
+//! against the leaf of the state Merkle tree (with a proof towards the snapshot root hash, which
+//! is itself verified against the state checkpoint hash in the transaction info).
+//!
+//! The reads are throttled by an I/O budget. Data pruned while being verified is skipped, while
+//! any corruption found is reported via metrics (`aptos_storage_scrubber_*`, which are also
+//! exposed by the inspection service) and logs.
+
+use crate::{
+    ledger_db::LedgerDb,
+    metrics::{
+        SCRUBBER_CORRUPTIONS, SCRUBBER_LAST_CORRUPTED_VERSION, SCRUBBER_PROGRESS,
+        SCRUBBER_READ_BYTES, SCRUBBER_VERIFIED_ITEMS,
+    },
+    pruner::{pruner_utils::get_ledger_pruner_progress, PrunerManager},
+    schema::transaction_accumulator::TransactionAccumulatorSchema,
+    state_merkle_db::StateMerkleDb,
+    state_store::StateStore,
+};
+use aptos_config::config::ConsistencyScrubberConfig;
+use aptos_crypto::{
+    hash::{CryptoHash, SPARSE_MERKLE_PLACEHOLDER_HASH},
+    HashValue,
+};
+use aptos_jellyfish_merkle::{
+    iterator::JellyfishMerkleIterator,
+    node_type::{LeafNode, Node, NodeKey},
+    JellyfishMerkleTree, TreeReader,
+};
+use aptos_logger::{
+    error, info,
+    prelude::{sample, SampleRate},
+};
+use aptos_storage_interface::Result;
+use aptos_types::{
+    proof::{accumulator::InMemoryEventAccumulator, position::Position},
+    state_store::state_key::StateKey,
+    transaction::Version,
+};
+use std::{
+    fmt::Display,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc,
+    },
+    thread::{sleep, JoinHandle},
+    time::{Duration, Instant},
+};
+
+#[cfg(test)]
+mod test;
+
+/// The names of the checks, used as metric labels.
+pub(crate) const TRANSACTION_ACCUMULATOR_CHECK: &str = "transaction_accumulator";
+pub(crate) const TRANSACTION_CHECK: &str = "transaction";
+pub(crate) const EVENT_CHECK: &str = "event";
+pub(crate) const STATE_ROOT_CHECK: &str = "state_root";
+pub(crate) const STATE_VALUE_CHECK: &str = "state_value";
+/// Data that should exist (i.e., that is not pruned) but can't be read.
+pub(crate) const READ_ERROR_CHECK: &str = "read_error";
+
+/// The worker sleeps for this period of time when there is an error, or nothing to verify.
+const IDLE_INTERVAL: Duration = Duration::from_secs(1);
+/// The worker checks whether it should quit at least this often while sleeping.
+const MAX_SLEEP_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Runs the consistency scrubber in a background thread, which is stopped on drop.
+pub(crate) struct ConsistencyScrubber {
+    worker_thread: Option<JoinHandle<()>>,
+    quit_worker: Arc<AtomicBool>,
+}
+
+impl ConsistencyScrubber {
+    pub(crate) fn new(
+        config: ConsistencyScrubberConfig,
+        ledger_db: Arc<LedgerDb>,
+        state_store: Arc<StateStore>,
+    ) -> Self {
+        let quit_worker = Arc::new(AtomicBool::new(false));
+        let mut worker =
+            ScrubberWorker::new(config, ledger_db, state_store, Arc::clone(&quit_worker));
+
+        let worker_thread = std::thread::Builder::new()
+            .name("db_scrubber".into())
+            .spawn(move || worker.work())
+            .expect("Creating consistency scrubber thread should succeed.");
+
+        Self {
+            worker_thread: Some(worker_thread),
+            quit_worker,
+        }
+    }
+}
+
+impl Drop for ConsistencyScrubber {
+    fn drop(&mut self) {
+        self.quit_worker.store(true, Ordering::SeqCst);
+        self.worker_thread
+            .take()
+            .expect("Consistency scrubber thread must exist.")
+            .join()
+            .unwrap_or_else(|e| {
+                panic!("Consistency scrubber thread should join peacefully: {e:?}")
+            });
+    }
+}
+
+/// The position of the scrubber in the state snapshot being verified.
+#[derive(Clone, Copy, Debug)]
+struct StateCursor {
+    snapshot_version: Version,
+    root_hash: HashValue,
+    /// The key hash of the last verified leaf, None if no leaf has been verified yet.
+    last_key_hash: Option<HashValue>,
+}
+
+/// The result of verifying a batch.
+#[derive(Debug, Default)]
+pub(crate) struct BatchOutcome {
+    /// Whether the current pass is done, i.e. there is nothing left to verify.
+    pub done: bool,
+    pub num_bytes_read: u64,
+    pub num_corruptions: u64,
+}
+
+impl BatchOutcome {
+    fn done() -> Self {
+        Self {
+            done: true,
+            ..Default::default()
+        }
+    }
+}
+
+pub(crate) struct ScrubberWorker {
+    config: ConsistencyScrubberConfig,
+    ledger_db: Arc<LedgerDb>,
+    state_store: Arc<StateStore>,
+    quit_worker: Arc<AtomicBool>,
+    /// The next version to verify in the ledger, None if a new pass is to be started.
+    next_ledger_version: Option<Version>,
+    /// None if a new pass over a new state snapshot is to be started.
+    state_cursor: Option<StateCursor>,
+}
+
+impl ScrubberWorker {
+    pub(crate) fn new(
+        config: ConsistencyScrubberConfig,
+        ledger_db: Arc<LedgerDb>,
+        state_store: Arc<StateStore>,
+        quit_worker: Arc<AtomicBool>,
+    ) -> Self {
+        Self {
+            config,
+            ledger_db,
+            state_store,
+            quit_worker,
+            next_ledger_version: None,
+            state_cursor: None,
+        }
+    }
+
+    fn should_quit(&self) -> bool {
+        self.quit_worker.load(Ordering::SeqCst)
+    }
+
+    // Loop that does the real scrubbing job.
+    fn work(&mut self) {
+        info!(config = ?self.config, "Consistency scrubber started.");
+        let mut ledger_pass_done = false;
+        let mut state_pass_done = false;
+        while !self.should_quit() {
+            let batch_start = Instant::now();
+            let num_bytes_read =
+                match self.scrub_next_batches(&mut ledger_pass_done, &mut state_pass_done) {
+                    Ok(num_bytes_read) => num_bytes_read,
+                    Err(err) => {
+                        sample!(
+                            SampleRate::Duration(Duration::from_secs(60)),
+                            error!(error = ?err, "Consistency scrubber has error.")
+                        );
+                        self.sleep(IDLE_INTERVAL);
+                        continue;
+                    },
+                };
+            SCRUBBER_READ_BYTES.inc_by(num_bytes_read);
+
+            if ledger_pass_done && state_pass_done {
+                info!("Consistency scrubber finished a pass.");
+                SCRUBBER_PROGRESS.with_label_values(&["passes"]).inc();
+                self.sleep(Duration::from_secs(self.config.pass_interval_secs));
+                ledger_pass_done = false;
+                state_pass_done = false;
+            } else {
+                self.throttle(batch_start, num_bytes_read);
+            }
+        }
+    }
+
+    /// Verifies the next ledger and state batches of the current pass, returning the number of
+    /// bytes read.
+    fn scrub_next_batches(
+        &mut self,
+        ledger_pass_done: &mut bool,
+        state_pass_done: &mut bool,
+    ) -> Result<u64> {
+        let mut num_bytes_read = 0;
+        if !*ledger_pass_done {
+            let outcome = self.scrub_ledger_batch()?;
+            *ledger_pass_done = outcome.done;
+            num_bytes_read += outcome.num_bytes_read;
+        }
+        if !*state_pass_done {
+            let outcome = self.scrub_state_batch()?;
+            *state_pass_done = outcome.done;
+            num_bytes_read += outcome.num_bytes_read;
+        }
+        Ok(num_bytes_read)
+    }
+
+    /// Sleeps long enough for the bytes read since `batch_start` to be within the I/O budget.
+    fn throttle(&self, batch_start: Instant, num_bytes_read: u64) {
+        let budgeted_duration =
+            Duration::from_secs_f64(num_bytes_read as f64 / self.config.max_bytes_per_sec as f64);
+        if let Some(duration) = budgeted_duration.checked_sub(batch_start.elapsed()) {
+            self.sleep(duration);
+        }
+    }
+
+    fn sleep(&self, duration: Duration) {
+        let deadline = Instant::now() + duration;
+        while !self.should_quit() {
+            match deadline.checked_duration_since(Instant::now()) {
+                Some(remaining) if !remaining.is_zero() => {
+                    sleep(std::cmp::min(remaining, MAX_SLEEP_INTERVAL))
+                },
+                _ => break,
+            }
+        }
+    }
+
+    /// Runs a full pass over the ledger and the latest state snapshot synchronously, without
+    /// throttling. Returns the number of corruptions found.
+    #[cfg(test)]
+    pub(crate) fn scrub_all(&mut self) -> Result<u64> {
+        let mut num_corruptions = 0;
+        loop {
+            let outcome = self.scrub_ledger_batch()?;
+            num_corruptions += outcome.num_corruptions;
+            if outcome.done {
+                break;
+            }
+        }
+        loop {
+            let outcome = self.scrub_state_batch()?;
+            num_corruptions += outcome.num_corruptions;
+            if outcome.done {
+                break;
+            }
+        }
+        Ok(num_corruptions)
+    }
+
+    fn report_corruption(
+        &self,
+        check: &str,
+        version: Version,
+        details: impl Display,
+        outcome: &mut BatchOutcome,
+    ) {
+        error!(
+            check = check,
+            version = version,
+            "Consistency scrubber found corruption: {}",
+            details
+        );
+        SCRUBBER_CORRUPTIONS.with_label_values(&[check]).inc();
+        SCRUBBER_LAST_CORRUPTED_VERSION
+            .with_label_values(&[check])
+            .set(version as i64);
+        outcome.num_corruptions += 1;
+    }
+
+    /// Verifies the next batch of versions in the ledger, up to the version of the latest ledger
+    /// info.
+    pub(crate) fn scrub_ledger_batch(&mut self) -> Result<BatchOutcome> {
+        let ledger_info_with_sigs =
+            match self.ledger_db.metadata_db().get_latest_ledger_info_option() {
+                Some(ledger_info_with_sigs) => ledger_info_with_sigs,
+                None => return Ok(BatchOutcome::done()),
+            };
+        let ledger_info = ledger_info_with_sigs.ledger_info();
+        let ledger_version = ledger_info.version();
+
+        let min_readable_version = get_ledger_pruner_progress(&self.ledger_db)?;
+        let start_version =
+            std::cmp::max(self.next_ledger_version.unwrap_or(0), min_readable_version);
+        if start_version > ledger_version {
+            self.next_ledger_version = None;
+            return Ok(BatchOutcome::done());
+        }
+        let num_versions = std::cmp::min(
+            self.config.ledger_batch_size as u64,
+            ledger_version - start_version + 1,
+        );
+
+        let mut outcome = BatchOutcome::default();
+        if let Err(err) = self.verify_ledger_range(
+            start_version,
+            num_versions,
+            ledger_version,
+            ledger_info.transaction_accumulator_hash(),
+            &mut outcome,
+        ) {
+            // The data might have been pruned while being verified.
+            if start_version >= get_ledger_pruner_progress(&self.ledger_db)? {
+                self.report_corruption(
+                    READ_ERROR_CHECK,
+                    start_version,
+                    format!(
+                        "Failed to read ledger data at versions [{start_version}, {}): {err}",
+                        start_version + num_versions
+                    ),
+                    &mut outcome,
+                );
+            }
+        }
+
+        let next_version = start_version + num_versions;
+        self.next_ledger_version = Some(next_version);
+        SCRUBBER_PROGRESS
+            .with_label_values(&["ledger_version"])
+            .set(next_version as i64);
+        Ok(outcome)
+    }
+
+    fn verify_ledger_range(
+        &self,
+        start_version: Version,
+        num_versions: u64,
+        ledger_version: Version,
+        expected_root_hash: HashValue,
+        outcome: &mut BatchOutcome,
+    ) -> Result<()> {
+        let txn_infos = self
+            .ledger_db
+            .transaction_info_db()
+            .get_transaction_info_iter(start_version, num_versions as usize)?
+            .collect::<Result<Vec<_>>>()?;
+        let txns = self
+            .ledger_db
+            .transaction_db()
+            .get_transaction_iter(start_version, num_versions as usize)?
+            .collect::<Result<Vec<_>>>()?;
+        let events = self
+            .ledger_db
+            .event_db()
+            .get_events_by_version_iter(start_version, num_versions as usize)?
+            .collect::<Result<Vec<_>>>()?;
+
+        let txn_info_hashes: Vec<_> = txn_infos.iter().map(CryptoHash::hash).collect();
+        let range_proof = self
+            .ledger_db
+            .transaction_accumulator_db()
+            .get_transaction_range_proof(Some(start_version), num_versions, ledger_version)?;
+        outcome.num_bytes_read += (range_proof.left_siblings().len()
+            + range_proof.right_siblings().len()) as u64
+            * HashValue::LENGTH as u64;
+        if let Err(err) =
+            range_proof.verify(expected_root_hash, Some(start_version), &txn_info_hashes)
+        {
+            self.report_corruption(
+                TRANSACTION_ACCUMULATOR_CHECK,
+                start_version,
+                format!("Transaction infos at versions [{start_version}, {}) don't match the accumulator root hash: {err}", start_version + num_versions),
+                outcome,
+            );
+        }
+
+        for (idx, ((txn_info, txn), events)) in txn_infos.iter().zip(&txns).zip(&events).enumerate()
+        {
+            let version = start_version + idx as u64;
+
+            let leaf_hash =
+                self.ledger_db
+                    .transaction_accumulator_db_raw()
+                    .get::<TransactionAccumulatorSchema>(&Position::from_leaf_index(version))?;
+            if leaf_hash != Some(txn_info_hashes[idx]) {
+                self.report_corruption(
+                    TRANSACTION_ACCUMULATOR_CHECK,
+                    version,
+                    format!(
+                        "Transaction info hash {} doesn't match the accumulator leaf hash {:?}.",
+                        txn_info_hashes[idx], leaf_hash,
+                    ),
+                    outcome,
+                );
+            }
+
+            let txn_hash = txn.hash();
+            if txn_hash != txn_info.transaction_hash() {
+                self.report_corruption(
+                    TRANSACTION_CHECK,
+                    version,
+                    format!(
+                        "Transaction hash {} doesn't match the one in the transaction info {}.",
+                        txn_hash,
+                        txn_info.transaction_hash(),
+                    ),
+                    outcome,
+                );
+            }
+
+            let event_hashes: Vec<_> = events.iter().map(CryptoHash::hash).collect();
+            let event_root_hash = InMemoryEventAccumulator::from_leaves(&event_hashes).root_hash();
+            if event_root_hash != txn_info.event_root_hash() {
+                self.report_corruption(
+                    EVENT_CHECK,
+                    version,
+                    format!(
+                        "Event root hash {} doesn't match the one in the transaction info {}.",
+                        event_root_hash,
+                        txn_info.event_root_hash(),
+                    ),
+                    outcome,
+                );
+            }
+
+            outcome.num_bytes_read += bcs::serialized_size(txn_info)? as u64
+                + bcs::serialized_size(txn)? as u64
+                + events.iter().map(|event| event.size() as u64).sum::<u64>()
+                + HashValue::LENGTH as u64;
+        }
+
+        // The iterators error out on gaps, but not if the data ends early. The data might also
+        // have been pruned while being read, which isn't a corruption.
+        if (txn_infos.len() as u64 != num_versions
+            || txns.len() as u64 != num_versions
+            || events.len() as u64 != num_versions)
+            && start_version >= get_ledger_pruner_progress(&self.ledger_db)?
+        {
+            self.report_corruption(
+                READ_ERROR_CHECK,
+                start_version,
+                format!(
+                    "Expected {num_versions} versions, got {} transaction infos, {} transactions and {} event lists.",
+                    txn_infos.len(),
+                    txns.len(),
+                    events.len(),
+                ),
+                outcome,
+            );
+        }
+
+        SCRUBBER_VERIFIED_ITEMS
+            .with_label_values(&[TRANSACTION_CHECK])
+            .inc_by(txns.len() as u64);
+        Ok(())
+    }
+
+    fn is_state_snapshot_pruned(&self, snapshot_version: Version) -> bool {
+        let state_db = &self.state_store.state_db;
+        snapshot_version < state_db.state_merkle_pruner.get_min_readable_version()
+            || snapshot_version < state_db.state_kv_pruner.get_min_readable_version()
+    }
+
+    /// Returns a cursor at the latest state snapshot, starting after `last_key_hash`.
+    fn new_state_cursor(
+        &self,
+        last_key_hash: Option<HashValue>,
+        outcome: &mut BatchOutcome,
+    ) -> Result<Option<StateCursor>> {
+        let next_version = self
+            .ledger_db
+            .metadata_db()
+            .get_synced_version()?
+            .map_or(0, |v| v + 1);
+        let snapshot_version = match self
+            .state_store
+            .state_merkle_db
+            .get_state_snapshot_version_before(next_version)?
+        {
+            Some(snapshot_version) => snapshot_version,
+            None => return Ok(None),
+        };
+        let root_hash = self
+            .state_store
+            .state_merkle_db
+            .get_root_hash(snapshot_version)?;
+
+        let state_checkpoint_hash = self
+            .ledger_db
+            .transaction_info_db()
+            .get_transaction_info(snapshot_version)
+            .ok()
+            .and_then(|txn_info| txn_info.state_checkpoint_hash());
+        if let Some(state_checkpoint_hash) = state_checkpoint_hash {
+            if state_checkpoint_hash != root_hash {
+                self.report_corruption(
+                    STATE_ROOT_CHECK,
+                    snapshot_version,
+                    format!(
+                        "State root hash {root_hash} doesn't match the state checkpoint hash {state_checkpoint_hash}."
+                    ),
+                    outcome,
+                );
+            }
+        }
+
+        SCRUBBER_PROGRESS
+            .with_label_values(&["state_snapshot_version"])
+            .set(snapshot_version as i64);
+        Ok(Some(StateCursor {
+            snapshot_version,
+            root_hash,
+            last_key_hash,
+        }))
+    }
+
+    /// Verifies the next batch of leaves in the state snapshot being verified.
+    pub(crate) fn scrub_state_batch(&mut self) -> Result<BatchOutcome> {
+        let mut outcome = BatchOutcome::default();
+        let cursor = match self.state_cursor {
+            Some(cursor) if !self.is_state_snapshot_pruned(cursor.snapshot_version) => Some(cursor),
+            // Carry on with the latest snapshot if the one being verified is pruned.
+            Some(cursor) => self.new_state_cursor(cursor.last_key_hash, &mut outcome)?,
+            None => self.new_state_cursor(None, &mut outcome)?,
+        };
+        let mut cursor = match cursor {
+            Some(cursor) if cursor.root_hash != *SPARSE_MERKLE_PLACEHOLDER_HASH => cursor,
+            _ => {
+                self.state_cursor = None;
+                outcome.done = true;
+                return Ok(outcome);
+            },
+        };
+
+        match self.verify_state_leaves(&mut cursor, &mut outcome) {
+            Ok(done) => outcome.done = done,
+            Err(err) => {
+                // The snapshot might have been pruned while being verified, in which case the
+                // next batch carries on with the latest snapshot.
+                if !self.is_state_snapshot_pruned(cursor.snapshot_version) {
+                    self.report_corruption(
+                        READ_ERROR_CHECK,
+                        cursor.snapshot_version,
+                        format!(
+                            "Failed to read the state snapshot after key hash {:?}: {err}",
+                            cursor.last_key_hash,
+                        ),
+                        &mut outcome,
+                    );
+                    // Give up on this snapshot, since the position of the next leaf is unknown.
+                    outcome.done = true;
+                }
+            },
+        }
+
+        self.state_cursor = (!outcome.done).then_some(cursor);
+        Ok(outcome)
+    }
+
+    /// Verifies up to a batch of leaves after the cursor, advancing the cursor. Returns true if
+    /// all leaves of the snapshot have been verified.
+    fn verify_state_leaves(
+        &self,
+        cursor: &mut StateCursor,
+        outcome: &mut BatchOutcome,
+    ) -> Result<bool> {
+        let tree_reader = Arc::new(ByteCountingTreeReader::new(Arc::clone(
+            &self.state_store.state_merkle_db,
+        )));
+        let result = self.verify_state_leaves_with_reader(&tree_reader, cursor, outcome);
+        outcome.num_bytes_read += tree_reader.num_bytes_read();
+        result
+    }
+
+    fn verify_state_leaves_with_reader(
+        &self,
+        tree_reader: &Arc<ByteCountingTreeReader>,
+        cursor: &mut StateCursor,
+        outcome: &mut BatchOutcome,
+    ) -> Result<bool> {
+        let iter: JellyfishMerkleIterator<_, StateKey> = JellyfishMerkleIterator::new(
+            Arc::clone(tree_reader),
+            cursor.snapshot_version,
+            cursor.last_key_hash.unwrap_or(HashValue::zero()),
+        )?;
+
+        let mut num_leaves = 0;
+        let mut done = true;
+        for leaf in iter {
+            let (key_hash, (state_key, _leaf_version)) = leaf?;
+            // The iterator starts at the last verified leaf (if any).
+            if Some(key_hash) == cursor.last_key_hash {
+                continue;
+            }
+            if num_leaves == self.config.state_batch_size {
+                done = false;
+                break;
+            }
+            self.verify_state_leaf(tree_reader, cursor, key_hash, &state_key, outcome)?;
+            cursor.last_key_hash = Some(key_hash);
+            num_leaves += 1;
+        }
+        SCRUBBER_VERIFIED_ITEMS
+            .with_label_values(&[STATE_VALUE_CHECK])
+            .inc_by(num_leaves as u64);
+        Ok(done)
+    }
+
+    fn verify_state_leaf(
+        &self,
+        tree_reader: &ByteCountingTreeReader,
+        cursor: &StateCursor,
+        key_hash: HashValue,
+        state_key: &StateKey,
+        outcome: &mut BatchOutcome,
+    ) -> Result<()> {
+        let version = cursor.snapshot_version;
+        if state_key.hash() != key_hash {
+            self.report_corruption(
+                STATE_VALUE_CHECK,
+                version,
+                format!("State key {state_key:?} doesn't match its leaf key hash {key_hash}."),
+                outcome,
+            );
+            return Ok(());
+        }
+
+        let (leaf, proof) =
+            JellyfishMerkleTree::new(tree_reader).get_with_proof_ext(key_hash, version, 0)?;
+        let value_hash = match leaf {
+            Some((value_hash, _)) => value_hash,
+            None => {
+                self.report_corruption(
+                    STATE_VALUE_CHECK,
+                    version,
+                    format!("State key {state_key:?} has no leaf in the state Merkle tree."),
+                    outcome,
+                );
+                return Ok(());
+            },
+        };
+        if let Err(err) = proof.verify_by_hash(cursor.root_hash, key_hash, Some(value_hash)) {
+            self.report_corruption(
+                STATE_VALUE_CHECK,
+                version,
+                format!("Proof of state key {state_key:?} doesn't match the root hash: {err}"),
+                outcome,
+            );
+        }
+
+        // In partial state mode, values of untracked state keys are not persisted.
+        if !self.state_store.should_persist_state_value(state_key) {
+            return Ok(());
+        }
+        match self
+            .state_store
+            .state_kv_db
+            .get_state_value_with_version_by_version(state_key, version)?
+        {
+            Some((_, value)) => {
+                outcome.num_bytes_read += (state_key.size() + value.size()) as u64;
+                if value.hash() != value_hash {
+                    self.report_corruption(
+                        STATE_VALUE_CHECK,
+                        version,
+                        format!(
+                            "Value of state key {state_key:?} doesn't match the leaf value hash {value_hash}."
+                        ),
+                        outcome,
+                    );
+                }
+            },
+            None => self.report_corruption(
+                STATE_VALUE_CHECK,
+                version,
+                format!("Value of state key {state_key:?} is missing."),
+                outcome,
+            ),
+        }
+        Ok(())
+    }
+}
+
+/// Reads the state Merkle tree, counting the (encoded) bytes of all nodes read, so that the
+/// scrubber is throttled by the amount of data it actually goes through. Nodes are read from the
+/// DB directly, so that the scrubber doesn't evict the nodes cached for the hot path.
+struct ByteCountingTreeReader {
+    state_merkle_db: Arc<StateMerkleDb>,
+    num_bytes_read: AtomicU64,
+}
+
+impl ByteCountingTreeReader {
+    fn new(state_merkle_db: Arc<StateMerkleDb>) -> Self {
+        Self {
+            state_merkle_db,
+            num_bytes_read: AtomicU64::new(0),
+        }
+    }
+
+    fn num_bytes_read(&self) -> u64 {
+        self.num_bytes_read.load(Ordering::Relaxed)
+    }
+}
+
+impl TreeReader<StateKey> for ByteCountingTreeReader {
+    fn get_node_option(&self, node_key: &NodeKey, _tag: &str) -> Result<Option<Node<StateKey>>> {
+        let node_opt = self.state_merkle_db.get_node_option_uncached(node_key)?;
+        if let Some(node) = &node_opt {
+            self.num_bytes_read.fetch_add(
+                (node_key.encode()?.len() + node.encode()?.len()) as u64,
+                Ordering::Relaxed,
+            );
+        }
+        Ok(node_opt)
+    }
+
+    fn get_rightmost_leaf(
+        &self,
+        version: Version,
+    ) -> Result<Option<(NodeKey, LeafNode<StateKey>)>> {
+        self.state_merkle_db.get_rightmost_leaf(version)
+    }
+}
@@ -0,0 +1,154 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    consistency_scrubber::ScrubberWorker,
+    db::{
+        test_helper::{arb_blocks_to_commit, update_in_memory_state},
+        AptosDB,
+    },
+    schema::{
+        state_value::StateValueSchema, transaction_accumulator::TransactionAccumulatorSchema,
+    },
+};
+use aptos_config::config::ConsistencyScrubberConfig;
+use aptos_crypto::HashValue;
+use aptos_jellyfish_merkle::iterator::JellyfishMerkleIterator;
+use aptos_temppath::TempPath;
+use aptos_types::{
+    ledger_info::LedgerInfoWithSignatures,
+    proof::position::Position,
+    state_store::{state_key::StateKey, state_value::StateValue},
+    transaction::{TransactionToCommit, Version},
+};
+use proptest::prelude::*;
+use std::sync::{atomic::AtomicBool, Arc};
+
+fn new_scrubber_worker(db: &AptosDB) -> ScrubberWorker {
+    ScrubberWorker::new(
+        ConsistencyScrubberConfig {
+            enable: true,
+            ledger_batch_size: 3,
+            state_batch_size: 3,
+            ..Default::default()
+        },
+        Arc::clone(&db.ledger_db),
+        Arc::clone(&db.state_store),
+        Arc::new(AtomicBool::new(false)),
+    )
+}
+
+fn test_consistency_scrubber_impl(
+    input: Vec<(Vec<TransactionToCommit>, LedgerInfoWithSignatures)>,
+) {
+    let tmp_dir = TempPath::new();
+    let db = AptosDB::new_for_test(&tmp_dir);
+
+    let mut in_memory_state = db.state_store.current_state_cloned();
+    let mut cur_ver: Version = 0;
+    for (txns_to_commit, ledger_info_with_sigs) in &input {
+        update_in_memory_state(&mut in_memory_state, txns_to_commit.as_slice());
+        db.save_transactions_for_test(
+            txns_to_commit,
+            cur_ver,                /* first_version */
+            cur_ver.checked_sub(1), /* base_state_version */
+            Some(ledger_info_with_sigs),
+            true, /* sync_commit */
+            &in_memory_state,
+        )
+        .unwrap();
+        cur_ver += txns_to_commit.len() as u64;
+    }
+
+    // Nothing is corrupted.
+    assert_eq!(new_scrubber_worker(&db).scrub_all().unwrap(), 0);
+
+    // Reading the proof nodes is charged on top of the state keys and values.
+    let outcome = new_scrubber_worker(&db).scrub_state_batch().unwrap();
+    if let Some(snapshot_version) = db
+        .state_store
+        .state_merkle_db
+        .get_state_snapshot_version_before(cur_ver)
+        .unwrap()
+    {
+        let num_kv_bytes: usize = JellyfishMerkleIterator::<_, StateKey>::new(
+            Arc::clone(&db.state_store.state_merkle_db),
+            snapshot_version,
+            HashValue::zero(),
+        )
+        .unwrap()
+        .take(3)
+        .map(|leaf| {
+            let (_key_hash, (state_key, _leaf_version)) = leaf.unwrap();
+            let (_, value) = db
+                .state_kv_db
+                .get_state_value_with_version_by_version(&state_key, snapshot_version)
+                .unwrap()
+                .unwrap();
+            state_key.size() + value.size()
+        })
+        .sum();
+        if num_kv_bytes > 0 {
+            assert!(outcome.num_bytes_read > num_kv_bytes as u64);
+        }
+    }
+
+    // Corrupt an accumulator leaf.
+    db.ledger_db
+        .transaction_accumulator_db_raw()
+        .put::<TransactionAccumulatorSchema>(&Position::from_leaf_index(0), &HashValue::random())
+        .unwrap();
+    let mut num_corruptions = 1;
+
+    // Corrupt a state value in the latest snapshot.
+    if let Some(snapshot_version) = db
+        .state_store
+        .state_merkle_db
+        .get_state_snapshot_version_before(cur_ver)
+        .unwrap()
+    {
+        let first_leaf = JellyfishMerkleIterator::<_, StateKey>::new(
+            Arc::clone(&db.state_store.state_merkle_db),
+            snapshot_version,
+            HashValue::zero(),
+        )
+        .unwrap()
+        .next();
+        if let Some(leaf) = first_leaf {
+            let (_key_hash, (state_key, leaf_version)) = leaf.unwrap();
+            db.state_kv_db
+                .db_shard(state_key.get_shard_id())
+                .put::<StateValueSchema>(
+                    &(state_key, leaf_version),
+                    &Some(StateValue::from(b"corrupted".to_vec())),
+                )
+                .unwrap();
+            num_corruptions += 1;
+        }
+    }
+
+    assert_eq!(
+        new_scrubber_worker(&db).scrub_all().unwrap(),
+        num_corruptions
+    );
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(5))]
+
+    #[test]
+    fn test_consistency_scrubber(input in arb_blocks_to_commit()) {
+        test_consistency_scrubber_impl(input);
+    }
+}
+
+#[test]
+fn test_consistency_scrubber_stops_on_drop() {
+    let tmp_dir = TempPath::new();
+    let mut db = AptosDB::new_for_test(&tmp_dir);
+    db.start_consistency_scrubber(ConsistencyScrubberConfig {
+        enable: true,
+        ..Default::default()
+    });
+    drop(db);
+}
@@ -6,10 +6,11 @@ use crate::{
         get_first_seq_num_and_limit, test_helper,
         test_helper::{
             arb_blocks_to_commit, put_as_state_root, put_transaction_auxiliary_data,
-            put_transaction_infos,
+            put_transaction_infos, update_in_memory_state,
         },
         AptosDB,
     },
+    ledger_retention::LedgerRetentionFilter,
     pruner::{LedgerPrunerManager, PrunerManager, StateMerklePrunerManager},
     schema::stale_node_index::StaleNodeIndexSchema,
 };
@@ -19,13 +20,18 @@ use aptos_config::config::{
     DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
 };
 use aptos_crypto::{hash::CryptoHash, HashValue};
-use aptos_storage_interface::{DbReader, LedgerSummary, Order};
+use aptos_storage_interface::{
+    state_store::state_view::db_state_view::OptionalProofDbStateView, DbReader, DbWriter,
+    LedgerSummary, Order, StateValueWithOptionalProof, MAX_REQUEST_LIMIT,
+};
 use aptos_temppath::TempPath;
 use aptos_types::{
+    account_address::AccountAddress,
     ledger_info::LedgerInfoWithSignatures,
     proof::SparseMerkleLeafNode,
     state_store::{
         state_key::StateKey, state_storage_usage::StateStorageUsage, state_value::StateValue,
+        TStateView,
     },
     transaction::{
         ExecutionStatus, TransactionAuxiliaryData, TransactionAuxiliaryDataV1, TransactionInfo,
@@ -118,6 +124,7 @@ fn test_pruner_config() {
                 user_pruning_window_offset: 0,
             },
             None,
+            None,
         );
         assert_eq!(ledger_pruner.is_pruner_enabled(), enable);
         assert_eq!(ledger_pruner.get_prune_window(), 100);
@@ -235,12 +242,15 @@ pub fn test_state_merkle_pruning_impl(
                 prune_window: 10,
                 batch_size: 1,
             },
+            state_kv_pruner_config: None,
         },
         RocksdbConfigs::default(),
         false, /* enable_indexer */
         BUFFERED_STATE_TARGET_ITEMS_FOR_TEST,
         DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
         None,
+        None,
+        None,
     )
     .unwrap();
 
@@ -322,3 +332,366 @@ proptest! {
         test_state_merkle_pruning_impl(input);
     }
 }
+
+fn test_create_online_checkpoint_impl(
+    input: Vec<(Vec<TransactionToCommit>, LedgerInfoWithSignatures)>,
+) {
+    let tmp_dir = TempPath::new();
+    let db =
+        AptosDB::new_for_test_with_sharding(&tmp_dir, DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD);
+
+    let mut in_memory_state = db.state_store.current_state_cloned();
+    let mut cur_ver: Version = 0;
+    for (txns_to_commit, ledger_info_with_sigs) in &input {
+        update_in_memory_state(&mut in_memory_state, txns_to_commit.as_slice());
+        db.save_transactions_for_test(
+            txns_to_commit,
+            cur_ver,                /* first_version */
+            cur_ver.checked_sub(1), /* base_state_version */
+            Some(ledger_info_with_sigs),
+            false, /* sync_commit */
+            &in_memory_state,
+        )
+        .unwrap();
+        cur_ver += txns_to_commit.len() as u64;
+    }
+    let latest_ledger_info = &input.last().unwrap().1;
+
+    let checkpoint_dir = TempPath::new();
+    let stages = std::sync::Mutex::new(vec![]);
+    let report_stage = |stage: &str| stages.lock().unwrap().push(stage.to_string());
+    let checkpoint_info = db
+        .create_online_checkpoint(checkpoint_dir.path(), &report_stage)
+        .unwrap();
+    assert_eq!(checkpoint_info.version, cur_ver - 1);
+    assert_eq!(
+        checkpoint_info.ledger_info.as_ref(),
+        Some(latest_ledger_info)
+    );
+    assert_eq!(*stages.lock().unwrap(), vec![
+        "waiting_for_commits",
+        "ledger_db",
+        "state_kv_db",
+        "state_merkle_db"
+    ]);
+    // The checkpoint path must not be reused.
+    assert!(db
+        .create_online_checkpoint(checkpoint_dir.path(), &|_| {})
+        .is_err());
+
+    let checkpoint_db = AptosDB::new_for_test_with_sharding(
+        checkpoint_dir.path(),
+        DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
+    );
+    assert_eq!(
+        checkpoint_db.get_synced_version().unwrap(),
+        Some(cur_ver - 1)
+    );
+    assert_eq!(
+        checkpoint_db.get_latest_ledger_info().unwrap(),
+        *latest_ledger_info
+    );
+    assert_eq!(
+        checkpoint_db
+            .get_transaction_by_version(cur_ver - 1, cur_ver - 1, false)
+            .unwrap(),
+        db.get_transaction_by_version(cur_ver - 1, cur_ver - 1, false)
+            .unwrap()
+    );
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(5))]
+
+    #[test]
+    fn test_create_online_checkpoint(input in arb_blocks_to_commit()) {
+        test_create_online_checkpoint_impl(input);
+    }
+}
+
+fn test_ledger_pruner_retention_impl(
+    input: Vec<(Vec<TransactionToCommit>, LedgerInfoWithSignatures)>,
+) {
+    let tmp_dir = TempPath::new();
+    let db = AptosDB::new_for_test(&tmp_dir);
+
+    let mut in_memory_state = db.state_store.current_state_cloned();
+    let mut cur_ver: Version = 0;
+    for (txns_to_commit, ledger_info_with_sigs) in &input {
+        update_in_memory_state(&mut in_memory_state, txns_to_commit.as_slice());
+        db.save_transactions_for_test(
+            txns_to_commit,
+            cur_ver,                /* first_version */
+            cur_ver.checked_sub(1), /* base_state_version */
+            Some(ledger_info_with_sigs),
+            false, /* sync_commit */
+            &in_memory_state,
+        )
+        .unwrap();
+        cur_ver += txns_to_commit.len() as u64;
+    }
+    let ledger_info = input.last().unwrap().1.ledger_info();
+    let ledger_version = ledger_info.version();
+
+    // Retain the transactions sent by the sender of the first user transaction.
+    let txns: Vec<_> = input
+        .iter()
+        .flat_map(|(txns_to_commit, _)| txns_to_commit.iter())
+        .map(|txn_to_commit| txn_to_commit.transaction().try_as_signed_user_txn())
+        .collect();
+    let retained_sender = txns
+        .iter()
+        .find_map(|txn| txn.map(|txn| txn.sender()))
+        .unwrap_or(AccountAddress::ZERO);
+
+    let ledger_pruner = LedgerPrunerManager::new(
+        Arc::clone(&db.ledger_db),
+        LedgerPrunerConfig {
+            enable: true,
+            prune_window: 0,
+            batch_size: 1,
+            user_pruning_window_offset: 0,
+        },
+        None,
+        Some(LedgerRetentionFilter::new([retained_sender], [], [])),
+    );
+    ledger_pruner.wake_and_wait_pruner(ledger_version).unwrap();
+    let min_readable_version = ledger_pruner.get_min_readable_version();
+    db.ledger_pruner
+        .save_min_readable_version(min_readable_version)
+        .unwrap();
+
+    let retained_versions: Vec<Version> = (0..min_readable_version)
+        .filter(|version| {
+            txns[*version as usize].map_or(false, |txn| txn.sender() == retained_sender)
+        })
+        .collect();
+    assert_eq!(
+        db.get_retained_versions(0, MAX_REQUEST_LIMIT).unwrap(),
+        retained_versions
+    );
+    for version in 0..min_readable_version {
+        let txn_with_proof = db.get_transaction_by_version(version, ledger_version, true);
+        if retained_versions.contains(&version) {
+            txn_with_proof.unwrap().verify(ledger_info).unwrap();
+        } else {
+            assert!(txn_with_proof.is_err());
+        }
+    }
+
+    // Range reads are only served if they don't span pruned versions
+    for start_version in 0..min_readable_version {
+        let num_versions = ledger_version - start_version + 1;
+        let txns = db.get_transactions(start_version, num_versions, ledger_version, false);
+        let range_retained = (start_version..min_readable_version)
+            .all(|version| retained_versions.contains(&version));
+        if range_retained {
+            txns.unwrap()
+                .verify(ledger_info, Some(start_version))
+                .unwrap();
+        } else {
+            assert!(format!("{}", txns.unwrap_err()).contains("is pruned at version"));
+        }
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(5))]
+
+    #[test]
+    fn test_ledger_pruner_retention(input in arb_blocks_to_commit()) {
+        test_ledger_pruner_retention_impl(input);
+    }
+}
+
+fn test_secondary_catch_up_with_primary_impl(
+    input: Vec<(Vec<TransactionToCommit>, LedgerInfoWithSignatures)>,
+) {
+    let tmp_dir = TempPath::new();
+    let db =
+        AptosDB::new_for_test_with_sharding(&tmp_dir, DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD);
+    let secondary_dir = TempPath::new();
+    let mut secondary_db = None;
+
+    let mut in_memory_state = db.state_store.current_state_cloned();
+    let mut cur_ver: Version = 0;
+    for (txns_to_commit, ledger_info_with_sigs) in &input {
+        update_in_memory_state(&mut in_memory_state, txns_to_commit.as_slice());
+        db.save_transactions_for_test(
+            txns_to_commit,
+            cur_ver,                /* first_version */
+            cur_ver.checked_sub(1), /* base_state_version */
+            Some(ledger_info_with_sigs),
+            true, /* sync_commit */
+            &in_memory_state,
+        )
+        .unwrap();
+        let first_version = cur_ver;
+        cur_ver += txns_to_commit.len() as u64;
+
+        // The secondary is opened after the first block, and must not see any newer block until
+        // it catches up.
+        match &secondary_db {
+            Some(secondary_db) => assert_eq!(
+                secondary_db.get_synced_version().unwrap(),
+                first_version.checked_sub(1)
+            ),
+            None => {
+                secondary_db = Some(
+                    AptosDB::open_as_secondary(
+                        StorageDirPaths::from_path(&tmp_dir),
+                        secondary_dir.path(),
+                        RocksdbConfigs {
+                            enable_storage_sharding: true,
+                            ..Default::default()
+                        },
+                        DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
+                    )
+                    .unwrap(),
+                )
+            },
+        }
+        let secondary_db = secondary_db.as_ref().unwrap();
+        assert_eq!(
+            secondary_db.try_catch_up_with_primary().unwrap(),
+            Some(cur_ver - 1)
+        );
+        assert_eq!(
+            secondary_db.get_latest_ledger_info().unwrap(),
+            *ledger_info_with_sigs
+        );
+        assert_eq!(
+            secondary_db
+                .get_transaction_by_version(cur_ver - 1, cur_ver - 1, false)
+                .unwrap(),
+            db.get_transaction_by_version(cur_ver - 1, cur_ver - 1, false)
+                .unwrap()
+        );
+        assert_eq!(
+            secondary_db.get_state_snapshot_before(cur_ver).unwrap(),
+            db.get_state_snapshot_before(cur_ver).unwrap()
+        );
+        // The write sets after the snapshot are replayed up to the exposed ledger info.
+        let secondary_state = secondary_db.state_store.current_state_cloned();
+        assert_eq!(secondary_state.current_version, Some(cur_ver - 1));
+        assert_eq!(
+            secondary_state.current.root_hash(),
+            in_memory_state.current.root_hash()
+        );
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(5))]
+
+    #[test]
+    fn test_secondary_catch_up_with_primary(input in arb_blocks_to_commit()) {
+        test_secondary_catch_up_with_primary_impl(input);
+    }
+}
+
+fn test_unproven_historical_state_reads_impl(
+    input: Vec<(Vec<TransactionToCommit>, LedgerInfoWithSignatures)>,
+) {
+    let tmp_dir = TempPath::new();
+    let mut db = AptosDB::new_for_test(&tmp_dir);
+
+    let mut in_memory_state = db.state_store.current_state_cloned();
+    let mut cur_ver: Version = 0;
+    for (txns_to_commit, ledger_info_with_sigs) in &input {
+        update_in_memory_state(&mut in_memory_state, txns_to_commit.as_slice());
+        db.save_transactions_for_test(
+            txns_to_commit,
+            cur_ver,                /* first_version */
+            cur_ver.checked_sub(1), /* base_state_version */
+            Some(ledger_info_with_sigs),
+            true, /* sync_commit */
+            &in_memory_state,
+        )
+        .unwrap();
+        cur_ver += txns_to_commit.len() as u64;
+    }
+    let (first_block, _) = &input[0];
+    let historical_version = first_block.len() as Version - 1;
+    let Some(state_key) = first_block
+        .iter()
+        .flat_map(|txn_to_commit| txn_to_commit.write_set().iter())
+        .map(|(state_key, _)| state_key.clone())
+        .next()
+    else {
+        return;
+    };
+    let Some((snapshot_version, _)) = db.get_state_snapshot_before(cur_ver).unwrap() else {
+        return;
+    };
+    if historical_version >= snapshot_version {
+        return;
+    }
+
+    // Prune the state Merkle trees before the latest snapshot, but not the state values.
+    db.state_store
+        .state_db
+        .state_merkle_pruner
+        .save_min_readable_version(snapshot_version)
+        .unwrap();
+    db.state_store
+        .state_db
+        .epoch_snapshot_pruner
+        .save_min_readable_version(snapshot_version)
+        .unwrap();
+    assert!(db
+        .get_state_value_with_optional_proof_by_version(&state_key, historical_version)
+        .is_err());
+
+    db.set_enable_unproven_historical_state_reads(true);
+    assert_eq!(
+        db.get_state_value_with_optional_proof_by_version(&state_key, historical_version)
+            .unwrap(),
+        StateValueWithOptionalProof::Unproven(
+            db.get_state_value_by_version(&state_key, historical_version)
+                .unwrap()
+        )
+    );
+    assert!(db
+        .get_state_value_with_optional_proof_by_version(&state_key, snapshot_version)
+        .unwrap()
+        .is_proven());
+    assert!(db.is_state_merkle_pruned(historical_version).unwrap());
+    assert!(!db.is_state_merkle_pruned(snapshot_version).unwrap());
+
+    // The API state view keeps reading the state KV DB, and only marks the pruned versions as
+    // unproven if asked to.
+    let db = Arc::new(db);
+    for mark_unproven in [false, true] {
+        let historical_view =
+            OptionalProofDbStateView::new(db.clone(), historical_version, mark_unproven).unwrap();
+        assert_eq!(
+            historical_view.get_state_value(&state_key).unwrap(),
+            db.get_state_value_by_version(&state_key, historical_version)
+                .unwrap()
+        );
+        assert_eq!(historical_view.is_unproven(), mark_unproven);
+        let latest_view =
+            OptionalProofDbStateView::new(db.clone(), cur_ver - 1, mark_unproven).unwrap();
+        latest_view.get_state_value(&state_key).unwrap();
+        assert!(!latest_view.is_unproven());
+    }
+
+    // Values beyond the state KV prune window can't be read either.
+    db.state_store
+        .state_kv_pruner
+        .save_min_readable_version(snapshot_version)
+        .unwrap();
+    assert!(db
+        .get_state_value_with_optional_proof_by_version(&state_key, historical_version)
+        .is_err());
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(5))]
+
+    #[test]
+    fn test_unproven_historical_state_reads(input in arb_blocks_to_commit()) {
+        test_unproven_historical_state_reads_impl(input);
+    }
+}
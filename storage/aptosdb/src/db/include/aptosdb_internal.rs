@@ -16,6 +16,8 @@ impl AptosDB {
         empty_buffered_state_for_restore: bool,
         skip_index_and_usage: bool,
         internal_indexer_db: Option<InternalIndexerDB>,
+        partial_state_filter: Option<PartialStateFilter>,
+        ledger_retention_filter: Option<LedgerRetentionFilter>,
     ) -> Self {
         let ledger_db = Arc::new(ledger_db);
         let state_merkle_db = Arc::new(state_merkle_db);
@@ -28,8 +30,10 @@ impl AptosDB {
             Arc::clone(&state_merkle_db),
             pruner_config.epoch_snapshot_pruner_config.into(),
         );
-        let state_kv_pruner =
-            StateKvPrunerManager::new(Arc::clone(&state_kv_db), pruner_config.ledger_pruner_config);
+        let state_kv_pruner = StateKvPrunerManager::new(
+            Arc::clone(&state_kv_db),
+            pruner_config.get_state_kv_pruner_config(),
+        );
         let state_store = Arc::new(StateStore::new(
             Arc::clone(&ledger_db),
             Arc::clone(&state_merkle_db),
@@ -42,12 +46,14 @@ impl AptosDB {
             empty_buffered_state_for_restore,
             skip_index_and_usage,
             internal_indexer_db.clone(),
+            partial_state_filter,
         ));
 
         let ledger_pruner = LedgerPrunerManager::new(
             Arc::clone(&ledger_db),
             pruner_config.ledger_pruner_config,
             internal_indexer_db,
+            ledger_retention_filter,
         );
 
         AptosDB {
@@ -64,9 +70,12 @@ impl AptosDB {
             ),
             pre_commit_lock: std::sync::Mutex::new(()),
             commit_lock: std::sync::Mutex::new(()),
+            checkpoint_lock: aptos_infallible::RwLock::new(()),
             indexer: None,
             skip_index_and_usage,
             update_subscriber: None,
+            consistency_scrubber: None,
+            enable_unproven_historical_state_reads: false,
         }
     }
 
@@ -80,6 +89,8 @@ impl AptosDB {
         max_num_nodes_per_lru_cache_shard: usize,
         empty_buffered_state_for_restore: bool,
         internal_indexer_db: Option<InternalIndexerDB>,
+        partial_state_filter: Option<PartialStateFilter>,
+        ledger_retention_filter: Option<LedgerRetentionFilter>,
     ) -> Result<Self> {
         ensure!(
             pruner_config.eq(&NO_OP_STORAGE_PRUNER_CONFIG) || !readonly,
@@ -103,6 +114,8 @@ impl AptosDB {
             empty_buffered_state_for_restore,
             rocksdb_configs.enable_storage_sharding,
             internal_indexer_db,
+            partial_state_filter,
+            ledger_retention_filter,
         );
 
         if !readonly && enable_indexer {
@@ -177,12 +190,19 @@ impl AptosDB {
             buffered_state_target_items,
             max_num_nodes_per_lru_cache_shard,
             None,
+            None,
+            None,
         )
         .expect("Unable to open AptosDB")
     }
 
     fn error_if_ledger_pruned(&self, data_type: &str, version: Version) -> Result<()> {
         let min_readable_version = self.ledger_pruner.get_min_readable_version();
+        if version < min_readable_version
+            && self.ledger_db.metadata_db().is_retained_version(version)?
+        {
+            return Ok(());
+        }
         ensure!(
             version >= min_readable_version,
             "{} at version {} is pruned, min available version is {}.",
@@ -193,6 +213,51 @@ impl AptosDB {
         Ok(())
     }
 
+    /// Like `error_if_ledger_pruned`, but for reading `num_versions` versions starting from
+    /// `start_version`. Below the min readable version only the retained versions are kept, so
+    /// such a range is only readable if all versions in it are retained.
+    fn error_if_ledger_range_pruned(
+        &self,
+        data_type: &str,
+        start_version: Version,
+        num_versions: u64,
+    ) -> Result<()> {
+        self.error_if_ledger_pruned(data_type, start_version)?;
+
+        let min_readable_version = self.ledger_pruner.get_min_readable_version();
+        let end_version = start_version
+            .saturating_add(num_versions)
+            .min(min_readable_version);
+        if start_version >= end_version {
+            return Ok(());
+        }
+        let retained_versions = self
+            .ledger_db
+            .metadata_db()
+            .get_retained_versions_in_range(start_version..end_version)?;
+        if retained_versions.len() as u64 != end_version - start_version {
+            // The retained versions are sorted, so the first gap is where they stop being
+            // consecutive.
+            let pruned_version = (start_version..)
+                .zip(&retained_versions)
+                .find(|(version, retained_version)| version != *retained_version)
+                .map_or(
+                    start_version + retained_versions.len() as u64,
+                    |(version, _)| version,
+                );
+            bail!(
+                "{} in range [{}, {}) is pruned at version {}, only retained versions are \
+                 available below the min available version {}.",
+                data_type,
+                start_version,
+                start_version.saturating_add(num_versions),
+                pruned_version,
+                min_readable_version
+            );
+        }
+        Ok(())
+    }
+
     fn error_if_state_merkle_pruned(&self, data_type: &str, version: Version) -> Result<()> {
         let min_readable_version = self
             .state_store
@@ -170,9 +170,8 @@ impl DbReader for AptosDB {
             if start_version > ledger_version || limit == 0 {
                 return Ok(TransactionListWithProof::new_empty());
             }
-            self.error_if_ledger_pruned("Transaction", start_version)?;
-
             let limit = std::cmp::min(limit, ledger_version - start_version + 1);
+            self.error_if_ledger_range_pruned("Transaction", start_version, limit)?;
 
             let txns = (start_version..start_version + limit)
                 .map(|version| self.ledger_db.transaction_db().get_transaction(version))
@@ -249,6 +248,17 @@ impl DbReader for AptosDB {
         })
     }
 
+    /// Returns up to `limit` versions retained by the ledger retention rules, starting from
+    /// `start_version`. The ledger data at these versions is kept by the ledger pruner.
+    fn get_retained_versions(&self, start_version: Version, limit: u64) -> Result<Vec<Version>> {
+        gauged_api("get_retained_versions", || {
+            error_if_too_many_requested(limit, MAX_REQUEST_LIMIT)?;
+            self.ledger_db
+                .metadata_db()
+                .get_retained_versions(start_version, limit as usize)
+        })
+    }
+
     /// Returns a batch of transactions for the purpose of synchronizing state to another node.
     ///
     /// If any version beyond ledger_version is requested, it is ignored.
@@ -268,9 +278,8 @@ impl DbReader for AptosDB {
                 return Ok(TransactionOutputListWithProof::new_empty());
             }
 
-            self.error_if_ledger_pruned("Transaction", start_version)?;
-
             let limit = std::cmp::min(limit, ledger_version - start_version + 1);
+            self.error_if_ledger_range_pruned("Transaction", start_version, limit)?;
 
             let (txn_infos, txns_and_outputs) = (start_version..start_version + limit)
                 .map(|version| {
@@ -334,7 +343,7 @@ impl DbReader for AptosDB {
     ) -> Result<Box<dyn Iterator<Item = Result<Transaction>> + '_>> {
         gauged_api("get_transaction_iterator", || {
             error_if_too_many_requested(limit, MAX_REQUEST_LIMIT)?;
-            self.error_if_ledger_pruned("Transaction", start_version)?;
+            self.error_if_ledger_range_pruned("Transaction", start_version, limit)?;
 
             let iter = self
                 .ledger_db
@@ -351,7 +360,7 @@ impl DbReader for AptosDB {
     ) -> Result<Box<dyn Iterator<Item = Result<TransactionInfo>> + '_>> {
         gauged_api("get_transaction_info_iterator", || {
             error_if_too_many_requested(limit, MAX_REQUEST_LIMIT)?;
-            self.error_if_ledger_pruned("Transaction", start_version)?;
+            self.error_if_ledger_range_pruned("Transaction", start_version, limit)?;
 
             let iter = self
                 .ledger_db
@@ -368,7 +377,7 @@ impl DbReader for AptosDB {
     ) -> Result<Box<dyn Iterator<Item = Result<Vec<ContractEvent>>> + '_>> {
         gauged_api("get_events_iterator", || {
             error_if_too_many_requested(limit, MAX_REQUEST_LIMIT)?;
-            self.error_if_ledger_pruned("Transaction", start_version)?;
+            self.error_if_ledger_range_pruned("Transaction", start_version, limit)?;
 
             let iter = self
                 .ledger_db
@@ -388,7 +397,7 @@ impl DbReader for AptosDB {
     ) -> Result<Box<dyn Iterator<Item = Result<WriteSet>> + '_>> {
         gauged_api("get_write_set_iterator", || {
             error_if_too_many_requested(limit, MAX_REQUEST_LIMIT)?;
-            self.error_if_ledger_pruned("Transaction", start_version)?;
+            self.error_if_ledger_range_pruned("Transaction", start_version, limit)?;
 
             let iter = self
                 .ledger_db
@@ -405,7 +414,7 @@ impl DbReader for AptosDB {
         ledger_version: Version,
     ) -> Result<TransactionAccumulatorRangeProof> {
         gauged_api("get_transaction_accumulator_range_proof", || {
-            self.error_if_ledger_pruned("Transaction", first_version)?;
+            self.error_if_ledger_range_pruned("Transaction", first_version, limit)?;
 
             self.ledger_db
                 .transaction_accumulator_db()
@@ -514,6 +523,46 @@ impl DbReader for AptosDB {
         })
     }
 
+    fn get_state_values_with_multi_proof_by_version(
+        &self,
+        state_keys: &[StateKey],
+        version: Version,
+    ) -> Result<(Vec<Option<StateValue>>, SparseMerkleMultiProof)> {
+        gauged_api("get_state_values_with_multi_proof_by_version", || {
+            self.error_if_state_merkle_pruned("State merkle", version)?;
+
+            self.state_store
+                .get_state_values_with_multi_proof_by_version(state_keys, version)
+        })
+    }
+
+    fn get_state_value_with_optional_proof_by_version(
+        &self,
+        state_key: &StateKey,
+        version: Version,
+    ) -> Result<StateValueWithOptionalProof> {
+        gauged_api(
+            "get_state_value_with_optional_proof_by_version",
+            || match self.error_if_state_merkle_pruned("State merkle", version) {
+                Ok(()) => {
+                    let (value, proof) = self
+                        .state_store
+                        .get_state_value_with_proof_by_version_ext(state_key, version, 0)?;
+                    Ok(StateValueWithOptionalProof::Proven(value, proof.into()))
+                },
+                Err(_) if self.enable_unproven_historical_state_reads => {
+                    self.error_if_state_kv_pruned("StateValue", version)?;
+                    UNPROVEN_STATE_READS.inc();
+                    Ok(StateValueWithOptionalProof::Unproven(
+                        self.state_store
+                            .get_state_value_by_version(state_key, version)?,
+                    ))
+                },
+                Err(err) => Err(err),
+            },
+        )
+    }
+
     fn get_latest_epoch_state(&self) -> Result<EpochState> {
         gauged_api("get_latest_epoch_state", || {
             let latest_ledger_info = self.ledger_db.metadata_db().get_latest_ledger_info()?;
@@ -727,6 +776,14 @@ impl DbReader for AptosDB {
         })
     }
 
+    fn is_state_merkle_pruned(&self, version: Version) -> Result<bool> {
+        gauged_api("is_state_merkle_pruned", || {
+            Ok(self
+                .error_if_state_merkle_pruned("State merkle", version)
+                .is_err())
+        })
+    }
+
     fn get_epoch_snapshot_prune_window(&self) -> Result<usize> {
         gauged_api("get_state_prune_window", || {
             Ok(self
@@ -761,6 +818,14 @@ impl DbReader for AptosDB {
         self.indexer.is_some()
     }
 
+    fn state_kv_sharding_enabled(&self) -> bool {
+        self.state_kv_db.enabled_sharding()
+    }
+
+    fn partial_state_enabled(&self) -> bool {
+        self.state_store.partial_state_enabled()
+    }
+
     fn get_state_storage_usage(&self, version: Option<Version>) -> Result<StateStorageUsage> {
         gauged_api("get_state_storage_usage", || {
             if let Some(v) = version {
@@ -40,6 +40,8 @@ impl AptosDB {
             BUFFERED_STATE_TARGET_ITEMS_FOR_TEST,
             max_node_cache,
             None,
+            None,
+            None,
         )
         .expect("Unable to open AptosDB")
     }
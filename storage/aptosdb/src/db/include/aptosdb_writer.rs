@@ -19,6 +19,7 @@ impl DbWriter for AptosDB {
                 .pre_commit_lock
                 .try_lock()
                 .expect("Concurrent committing detected.");
+            let _checkpoint_guard = self.checkpoint_lock.read();
             let _timer = OTHER_TIMERS_SECONDS.timer_with(&["pre_commit_ledger"]);
 
             chunk.latest_in_memory_state.current.log_generation("db_save");
@@ -62,6 +63,7 @@ impl DbWriter for AptosDB {
                 .commit_lock
                 .try_lock()
                 .expect("Concurrent committing detected.");
+            let _checkpoint_guard = self.checkpoint_lock.read();
             let _timer = OTHER_TIMERS_SECONDS.timer_with(&["commit_ledger"]);
 
             let old_committed_ver = self.get_and_check_commit_range(version)?;
@@ -89,6 +91,56 @@ impl DbWriter for AptosDB {
     }
 
 
+    fn create_online_checkpoint(
+        &self,
+        checkpoint_path: &Path,
+        report_stage: &dyn Fn(&str),
+    ) -> Result<DbCheckpointInfo> {
+        gauged_api("create_online_checkpoint", || {
+            let start = Instant::now();
+            ensure!(
+                !checkpoint_path.exists(),
+                "Checkpoint path {:?} already exists.",
+                checkpoint_path
+            );
+            std::fs::create_dir_all(checkpoint_path)?;
+
+            // Pause commits, so that the ledger and state kv DBs are checkpointed at the same
+            // version. The state merkle DB is committed asynchronously and can be ahead of or
+            // behind that version, which is reconciled when the checkpoint is opened (as it is
+            // after any restart).
+            report_stage("waiting_for_commits");
+            let _checkpoint_guard = self.checkpoint_lock.write();
+            let ledger_metadata_db = self.ledger_db.metadata_db();
+            let version = ledger_metadata_db
+                .get_synced_version()?
+                .ok_or_else(|| AptosDbError::NotFound("Synced version not found.".to_string()))?;
+            let ledger_info = ledger_metadata_db.get_latest_ledger_info_option();
+
+            report_stage("ledger_db");
+            self.ledger_db.checkpoint_to(checkpoint_path)?;
+            if self.state_kv_db.enabled_sharding() {
+                report_stage("state_kv_db");
+                self.state_kv_db.checkpoint_to(checkpoint_path)?;
+            }
+            report_stage("state_merkle_db");
+            self.state_store
+                .state_merkle_db
+                .checkpoint_to(checkpoint_path)?;
+
+            info!(
+                version = version,
+                checkpoint_path = checkpoint_path,
+                time_ms = %start.elapsed().as_millis(),
+                "Made online AptosDB checkpoint."
+            );
+            Ok(DbCheckpointInfo {
+                version,
+                ledger_info,
+            })
+        })
+    }
+
     fn get_state_snapshot_receiver(
         &self,
         version: Version,
@@ -5,16 +5,19 @@
 use crate::{
     backup::{backup_handler::BackupHandler, restore_utils},
     common::MAX_NUM_EPOCH_ENDING_LEDGER_INFO,
+    consistency_scrubber::ConsistencyScrubber,
     event_store::EventStore,
     ledger_db::{
         ledger_metadata_db::LedgerMetadataDb,
         transaction_auxiliary_data_db::TransactionAuxiliaryDataDb,
         transaction_info_db::TransactionInfoDb, LedgerDb, LedgerDbSchemaBatches,
     },
+    ledger_retention::LedgerRetentionFilter,
     metrics::{
         API_LATENCY_SECONDS, COMMITTED_TXNS, LATEST_TXN_VERSION, LEDGER_VERSION, NEXT_BLOCK_EPOCH,
-        OTHER_TIMERS_SECONDS,
+        OTHER_TIMERS_SECONDS, UNPROVEN_STATE_READS,
     },
+    partial_state::PartialStateFilter,
     pruner::{LedgerPrunerManager, PrunerManager, StateKvPrunerManager, StateMerklePrunerManager},
     rocksdb_property_reporter::RocksdbPropertyReporter,
     schema::{
@@ -26,10 +29,11 @@ use crate::{
     state_merkle_db::StateMerkleDb,
     state_store::StateStore,
     transaction_store::TransactionStore,
-    utils::new_sharded_kv_schema_batch,
+    utils::{new_sharded_kv_schema_batch, DbOpenMode},
 };
 use aptos_config::config::{
-    PrunerConfig, RocksdbConfig, RocksdbConfigs, StorageDirPaths, NO_OP_STORAGE_PRUNER_CONFIG,
+    ConsistencyScrubberConfig, PrunerConfig, RocksdbConfig, RocksdbConfigs, StorageDirPaths,
+    BUFFERED_STATE_TARGET_ITEMS, NO_OP_STORAGE_PRUNER_CONFIG,
 };
 use aptos_crypto::HashValue;
 use aptos_db_indexer::{db_indexer::InternalIndexerDB, Indexer};
@@ -41,8 +45,9 @@ use aptos_schemadb::SchemaBatch;
 use aptos_scratchpad::SparseMerkleTree;
 use aptos_storage_interface::{
     db_ensure as ensure, db_other_bail as bail,
-    state_store::sharded_state_updates::ShardedStateUpdates, AptosDbError, DbReader, DbWriter,
-    LedgerSummary, Order, Result, StateSnapshotReceiver, MAX_REQUEST_LIMIT,
+    state_store::sharded_state_updates::ShardedStateUpdates, AptosDbError, DbCheckpointInfo,
+    DbReader, DbWriter, LedgerSummary, Order, Result, StateSnapshotReceiver,
+    StateValueWithOptionalProof, MAX_REQUEST_LIMIT,
 };
 use aptos_types::{
     account_address::AccountAddress,
@@ -53,8 +58,8 @@ use aptos_types::{
     event::EventKey,
     ledger_info::LedgerInfoWithSignatures,
     proof::{
-        accumulator::InMemoryAccumulator, AccumulatorConsistencyProof, SparseMerkleProofExt,
-        TransactionAccumulatorRangeProof, TransactionAccumulatorSummary,
+        accumulator::InMemoryAccumulator, AccumulatorConsistencyProof, SparseMerkleMultiProof,
+        SparseMerkleProofExt, TransactionAccumulatorRangeProof, TransactionAccumulatorSummary,
         TransactionInfoListWithProof,
     },
     state_proof::StateProof,
@@ -101,9 +106,15 @@ pub struct AptosDB {
     pre_commit_lock: std::sync::Mutex<()>,
     /// This is just to detect concurrent calls to `commit_ledger()`
     commit_lock: std::sync::Mutex<()>,
+    /// Held for read by commits and for write by `create_online_checkpoint()`, so that commits
+    /// are paused while a checkpoint is being created.
+    checkpoint_lock: aptos_infallible::RwLock<()>,
     indexer: Option<Indexer>,
     skip_index_and_usage: bool,
     update_subscriber: Option<Sender<Version>>,
+    consistency_scrubber: Option<ConsistencyScrubber>,
+    /// Whether state values at versions whose state Merkle tree is pruned are served unproven.
+    enable_unproven_historical_state_reads: bool,
 }
 
 // DbReader implementations and private functions used by them.
@@ -129,6 +140,8 @@ impl AptosDB {
         buffered_state_target_items: usize,
         max_num_nodes_per_lru_cache_shard: usize,
         internal_indexer_db: Option<InternalIndexerDB>,
+        partial_state_filter: Option<PartialStateFilter>,
+        ledger_retention_filter: Option<LedgerRetentionFilter>,
     ) -> Result<Self> {
         Self::open_internal(
             &db_paths,
@@ -140,6 +153,8 @@ impl AptosDB {
             max_num_nodes_per_lru_cache_shard,
             false,
             internal_indexer_db,
+            partial_state_filter,
+            ledger_retention_filter,
         )
     }
 
@@ -152,6 +167,8 @@ impl AptosDB {
         buffered_state_target_items: usize,
         max_num_nodes_per_lru_cache_shard: usize,
         internal_indexer_db: Option<InternalIndexerDB>,
+        partial_state_filter: Option<PartialStateFilter>,
+        ledger_retention_filter: Option<LedgerRetentionFilter>,
     ) -> Result<Self> {
         Self::open_internal(
             &db_paths,
@@ -163,26 +180,130 @@ impl AptosDB {
             max_num_nodes_per_lru_cache_shard,
             true,
             internal_indexer_db,
+            partial_state_filter,
+            ledger_retention_filter,
         )
     }
 
+    /// Opens a read-only replica following the AptosDB at `primary_db_paths`, which is written by
+    /// another process. The replica keeps its own (small) info logs and MANIFEST copies under
+    /// `secondary_db_root_path`, and only sees new data after `try_catch_up_with_primary`.
+    pub fn open_as_secondary(
+        primary_db_paths: StorageDirPaths,
+        secondary_db_root_path: impl AsRef<Path>,
+        rocksdb_configs: RocksdbConfigs,
+        max_num_nodes_per_lru_cache_shard: usize,
+    ) -> Result<Self> {
+        let (ledger_db, state_merkle_db, state_kv_db) = Self::open_dbs_with_mode(
+            &primary_db_paths,
+            rocksdb_configs,
+            DbOpenMode::Secondary(secondary_db_root_path.as_ref()),
+            max_num_nodes_per_lru_cache_shard,
+        )?;
+
+        // Syncing the commit progress and committing the replayed write sets after the latest
+        // snapshot (as done when opening a primary) would write to the DB, so the buffered state
+        // starts empty and is built by the initial catch up, which replays in memory only.
+        let db = Self::new_with_dbs(
+            ledger_db,
+            state_merkle_db,
+            state_kv_db,
+            NO_OP_STORAGE_PRUNER_CONFIG,
+            BUFFERED_STATE_TARGET_ITEMS,
+            /*hack_for_tests=*/ false,
+            /*empty_buffered_state_for_restore=*/ true,
+            rocksdb_configs.enable_storage_sharding,
+            /*internal_indexer_db=*/ None,
+            /*partial_state_filter=*/ None,
+            /*ledger_retention_filter=*/ None,
+        );
+        db.try_catch_up_with_primary()?;
+        Ok(db)
+    }
+
+    /// Makes the latest data written by the primary visible to a DB opened by
+    /// `open_as_secondary`, returning the new latest ledger version.
+    ///
+    /// The ledger metadata DB is caught up first, and reads are capped at its latest ledger info:
+    /// the primary commits a ledger info after all the data up to it, so every version up to it
+    /// is fully readable once the other DBs are caught up afterwards. The ledger info is only
+    /// exposed after that.
+    pub fn try_catch_up_with_primary(&self) -> Result<Option<Version>> {
+        let _timer = OTHER_TIMERS_SECONDS.timer_with(&["try_catch_up_with_primary"]);
+
+        let old_version = self.ledger_db.metadata_db().get_committed_version();
+
+        let latest_ledger_info = self.ledger_db.try_catch_up_with_primary()?;
+        self.state_kv_db.try_catch_up_with_primary()?;
+        self.state_store
+            .state_merkle_db
+            .try_catch_up_with_primary()?;
+
+        self.ledger_pruner.refresh_min_readable_version()?;
+        self.state_store
+            .state_merkle_pruner
+            .refresh_min_readable_version()?;
+        self.state_store
+            .epoch_snapshot_pruner
+            .refresh_min_readable_version()?;
+        self.state_store
+            .state_kv_pruner
+            .refresh_min_readable_version()?;
+
+        let latest_ledger_info = match latest_ledger_info {
+            Some(latest_ledger_info) => latest_ledger_info,
+            None => return Ok(None),
+        };
+        let version = latest_ledger_info.ledger_info().version();
+        self.state_store.refresh_buffered_state(version)?;
+        LEDGER_VERSION.set(version as i64);
+        NEXT_BLOCK_EPOCH.set(latest_ledger_info.ledger_info().next_block_epoch() as i64);
+        self.ledger_db
+            .metadata_db()
+            .set_latest_ledger_info(latest_ledger_info);
+
+        if let Some(update_sender) = &self.update_subscriber {
+            if old_version.map_or(true, |v| v < version) {
+                update_sender.send(version).map_err(|err| {
+                    AptosDbError::Other(format!("Failed to send update to subscriber: {}", err))
+                })?;
+            }
+        }
+
+        Ok(Some(version))
+    }
+
     pub fn open_dbs(
         db_paths: &StorageDirPaths,
         rocksdb_configs: RocksdbConfigs,
         readonly: bool,
         max_num_nodes_per_lru_cache_shard: usize,
     ) -> Result<(LedgerDb, StateMerkleDb, StateKvDb)> {
-        let ledger_db = LedgerDb::new(db_paths.ledger_db_root_path(), rocksdb_configs, readonly)?;
+        Self::open_dbs_with_mode(
+            db_paths,
+            rocksdb_configs,
+            DbOpenMode::from_readonly(readonly),
+            max_num_nodes_per_lru_cache_shard,
+        )
+    }
+
+    fn open_dbs_with_mode(
+        db_paths: &StorageDirPaths,
+        rocksdb_configs: RocksdbConfigs,
+        open_mode: DbOpenMode,
+        max_num_nodes_per_lru_cache_shard: usize,
+    ) -> Result<(LedgerDb, StateMerkleDb, StateKvDb)> {
+        let ledger_db = LedgerDb::new(db_paths.ledger_db_root_path(), rocksdb_configs, open_mode)?;
         let state_kv_db = StateKvDb::new(
             db_paths,
             rocksdb_configs,
-            readonly,
+            open_mode,
             ledger_db.metadata_db_arc(),
         )?;
         let state_merkle_db = StateMerkleDb::new(
             db_paths,
             rocksdb_configs,
-            readonly,
+            open_mode,
             max_num_nodes_per_lru_cache_shard,
         )?;
 
@@ -194,6 +315,22 @@ impl AptosDB {
         Ok(())
     }
 
+    /// Serves state values at versions whose state Merkle tree is already pruned from the state KV
+    /// DB, without proofs. See `DbReader::get_state_value_with_optional_proof_by_version()`.
+    pub fn set_enable_unproven_historical_state_reads(&mut self, enable: bool) {
+        self.enable_unproven_historical_state_reads = enable;
+    }
+
+    /// Starts the consistency scrubber, which continuously re-verifies the persisted data in the
+    /// background and reports any corruption found.
+    pub fn start_consistency_scrubber(&mut self, config: ConsistencyScrubberConfig) {
+        self.consistency_scrubber = Some(ConsistencyScrubber::new(
+            config,
+            Arc::clone(&self.ledger_db),
+            Arc::clone(&self.state_store),
+        ));
+    }
+
     /// Gets an instance of `BackupHandler` for data backup purpose.
     pub fn get_backup_handler(&self) -> BackupHandler {
         BackupHandler::new(Arc::clone(&self.state_store), Arc::clone(&self.ledger_db))
@@ -3,7 +3,7 @@
 
 use crate::{
     db_debugger::ShardingConfig, ledger_db::LedgerDb, state_kv_db::StateKvDb,
-    state_merkle_db::StateMerkleDb,
+    state_merkle_db::StateMerkleDb, utils::DbOpenMode,
 };
 use aptos_config::config::{RocksdbConfigs, StorageDirPaths};
 use aptos_storage_interface::Result;
@@ -32,7 +32,7 @@ impl DbDir {
                 enable_storage_sharding: self.sharding_config.enable_storage_sharding,
                 ..Default::default()
             },
-            false,
+            DbOpenMode::ReadWrite,
             0,
         )
     }
@@ -45,7 +45,7 @@ impl DbDir {
                 enable_storage_sharding: self.sharding_config.enable_storage_sharding,
                 ..Default::default()
             },
-            true,
+            DbOpenMode::ReadOnly,
             leger_db.metadata_db_arc(),
         )
     }
@@ -57,7 +57,7 @@ impl DbDir {
                 enable_storage_sharding: self.sharding_config.enable_storage_sharding,
                 ..Default::default()
             },
-            true,
+            DbOpenMode::ReadOnly,
         )
     }
 }
@@ -0,0 +1,649 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{db_debugger::common::DbDir, state_kv_db::StateKvDb, state_merkle_db::StateMerkleDb};
+use aptos_jellyfish_merkle::iterator::JellyfishMerkleIterator;
+use aptos_storage_interface::{AptosDbError, Result};
+use aptos_types::{
+    access_path::Path,
+    state_store::state_key::{inner::StateKeyInner, StateKey},
+    transaction::Version,
+};
+use clap::Parser;
+use indicatif::{ProgressBar, ProgressStyle};
+use move_core_types::{account_address::AccountAddress, language_storage::StructTag};
+use owo_colors::OwoColorize;
+use rayon::prelude::*;
+use serde::Serialize;
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    fmt::Display,
+    hash::Hash,
+    sync::Arc,
+};
+
+const BATCH_SIZE: usize = 100_000;
+const NUM_HISTOGRAM_BUCKETS: usize = 65;
+
+#[derive(Parser)]
+#[clap(about = "Analyze state storage usage by account, struct type and table handle.")]
+pub struct Cmd {
+    #[clap(flatten)]
+    db_dir: DbDir,
+
+    #[clap(long)]
+    version: Version,
+
+    #[clap(
+        long,
+        help = "If set, also analyzes the snapshot at this version and reports the usage changes \
+        from it to `--version`."
+    )]
+    compare_version: Option<Version>,
+
+    #[clap(long, default_value = "20")]
+    top_k: usize,
+
+    #[clap(long, default_value = "32")]
+    concurrency: usize,
+
+    #[clap(long, help = "Print the report as JSON.")]
+    json: bool,
+}
+
+impl Cmd {
+    pub fn run(self) -> Result<()> {
+        let state_kv_db = Arc::new(self.db_dir.open_state_kv_db()?);
+        let state_merkle_db = Arc::new(self.db_dir.open_state_merkle_db()?);
+        let thread_pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(self.concurrency)
+            .build()
+            .map_err(|err| AptosDbError::Other(err.to_string()))?;
+
+        let usage = thread_pool.install(|| {
+            StateUsage::collect(&state_kv_db, &state_merkle_db, self.version, !self.json)
+        })?;
+        match self.compare_version {
+            None => {
+                let report = usage.report(self.version, self.top_k);
+                if self.json {
+                    print_json(&report)?;
+                } else {
+                    report.print();
+                }
+            },
+            Some(compare_version) => {
+                let base_usage = thread_pool.install(|| {
+                    StateUsage::collect(&state_kv_db, &state_merkle_db, compare_version, !self.json)
+                })?;
+                let report =
+                    usage.diff_report(&base_usage, compare_version, self.version, self.top_k);
+                if self.json {
+                    print_json(&report)?;
+                } else {
+                    report.print();
+                }
+            },
+        }
+
+        Ok(())
+    }
+}
+
+fn print_json<T: Serialize>(report: &T) -> Result<()> {
+    let json =
+        serde_json::to_string_pretty(report).map_err(|err| AptosDbError::Other(err.to_string()))?;
+    println!("{}", json);
+    Ok(())
+}
+
+/// Number of items and bytes (key plus value) used by a group of state values.
+#[derive(Clone, Copy, Debug, Default, Serialize)]
+pub struct Usage {
+    pub items: u64,
+    pub bytes: u64,
+}
+
+impl Usage {
+    fn add(&mut self, bytes: u64) {
+        self.items += 1;
+        self.bytes += bytes;
+    }
+
+    fn merge(&mut self, other: &Usage) {
+        self.items += other.items;
+        self.bytes += other.bytes;
+    }
+
+    fn delta(&self, base: &Usage) -> UsageDelta {
+        UsageDelta {
+            items: self.items as i64 - base.items as i64,
+            bytes: self.bytes as i64 - base.bytes as i64,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Serialize)]
+pub struct UsageDelta {
+    pub items: i64,
+    pub bytes: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UsageEntry {
+    pub id: String,
+    #[serde(flatten)]
+    pub usage: Usage,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UsageDeltaEntry {
+    pub id: String,
+    #[serde(flatten)]
+    pub delta: UsageDelta,
+}
+
+#[derive(Debug, Serialize)]
+pub struct HistogramBucket {
+    /// Inclusive upper bound of the value sizes in the bucket.
+    pub max_bytes: u64,
+    pub items: u64,
+}
+
+/// Aggregated usage of all state values in a snapshot.
+#[derive(Default)]
+struct StateUsage {
+    total: Usage,
+    by_kind: HashMap<&'static str, Usage>,
+    by_account: HashMap<AccountAddress, Usage>,
+    by_struct_type: HashMap<String, Usage>,
+    by_table_handle: HashMap<AccountAddress, Usage>,
+    // Bucket `i` counts the values whose size needs exactly `i` bits.
+    value_size_histogram: Vec<u64>,
+}
+
+impl StateUsage {
+    fn new() -> Self {
+        Self {
+            value_size_histogram: vec![0; NUM_HISTOGRAM_BUCKETS],
+            ..Default::default()
+        }
+    }
+
+    /// Walks the snapshot at `version` in parallel (on the current rayon pool), reading every
+    /// value from the state kv db.
+    fn collect(
+        state_kv_db: &Arc<StateKvDb>,
+        state_merkle_db: &Arc<StateMerkleDb>,
+        version: Version,
+        show_progress: bool,
+    ) -> Result<Self> {
+        let total_leaves = state_merkle_db.get_leaf_count(version)?;
+        let bar = if show_progress {
+            println!(
+                "{}",
+                format!(
+                    "* Analyzing {} state values in snapshot at version {}.\n",
+                    total_leaves, version
+                )
+                .yellow()
+            );
+            let bar = ProgressBar::new(total_leaves as u64);
+            bar.set_style(ProgressStyle::default_bar().template(
+                "[{elapsed_precise} {per_sec}] {bar:100.cyan/blue} {pos} / {len} {percent}% ETA {eta_precise}",
+            ));
+            bar
+        } else {
+            ProgressBar::hidden()
+        };
+
+        let usage = (0..total_leaves)
+            .step_by(BATCH_SIZE)
+            .collect::<Vec<_>>()
+            .into_par_iter()
+            .map(|start| -> Result<StateUsage> {
+                let len = BATCH_SIZE.min(total_leaves - start);
+                let mut usage = StateUsage::new();
+                let leaves =
+                    JellyfishMerkleIterator::new_by_index(state_merkle_db.clone(), version, start)?
+                        .take(len);
+                for leaf in leaves {
+                    let (_key_hash, (key, _key_version)) = leaf?;
+                    let (_value_version, value) = state_kv_db
+                        .get_state_value_with_version_by_version(&key, version)?
+                        .ok_or_else(|| {
+                            AptosDbError::NotFound(format!(
+                                "State value for {:?} at version {}",
+                                key, version
+                            ))
+                        })?;
+                    usage.add(&key, value.size());
+                }
+                bar.inc(len as u64);
+                Ok(usage)
+            })
+            .try_reduce(StateUsage::new, |mut left, right| {
+                left.merge(right);
+                Ok(left)
+            })?;
+        bar.finish();
+
+        Ok(usage)
+    }
+
+    fn add(&mut self, key: &StateKey, value_size: usize) {
+        let bytes = (key.size() + value_size) as u64;
+        self.total.add(bytes);
+        self.value_size_histogram[(u64::BITS - (value_size as u64).leading_zeros()) as usize] += 1;
+
+        let kind = match key.inner() {
+            StateKeyInner::AccessPath(access_path) => {
+                self.by_account
+                    .entry(access_path.address)
+                    .or_default()
+                    .add(bytes);
+                match access_path.get_path() {
+                    Path::Code(_) => "code",
+                    Path::Resource(struct_tag) => {
+                        self.add_struct_type(struct_type_id(&struct_tag), bytes);
+                        "resource"
+                    },
+                    Path::ResourceGroup(struct_tag) => {
+                        self.add_struct_type(struct_type_id(&struct_tag), bytes);
+                        "resource_group"
+                    },
+                }
+            },
+            StateKeyInner::TableItem { handle, .. } => {
+                self.by_table_handle.entry(handle.0).or_default().add(bytes);
+                "table_item"
+            },
+            StateKeyInner::Raw(_) => "raw",
+        };
+        self.by_kind.entry(kind).or_default().add(bytes);
+    }
+
+    fn add_struct_type(&mut self, struct_type: String, bytes: u64) {
+        self.by_struct_type
+            .entry(struct_type)
+            .or_default()
+            .add(bytes);
+    }
+
+    fn merge(&mut self, other: StateUsage) {
+        self.total.merge(&other.total);
+        merge_usage_map(&mut self.by_kind, other.by_kind);
+        merge_usage_map(&mut self.by_account, other.by_account);
+        merge_usage_map(&mut self.by_struct_type, other.by_struct_type);
+        merge_usage_map(&mut self.by_table_handle, other.by_table_handle);
+        for (count, other_count) in self
+            .value_size_histogram
+            .iter_mut()
+            .zip(other.value_size_histogram)
+        {
+            *count += other_count;
+        }
+    }
+
+    fn report(&self, version: Version, top_k: usize) -> UsageReport {
+        UsageReport {
+            version,
+            total: self.total,
+            by_kind: self
+                .by_kind
+                .iter()
+                .map(|(kind, usage)| (*kind, *usage))
+                .collect(),
+            top_accounts: top_usages(&self.by_account, top_k),
+            top_struct_types: top_usages(&self.by_struct_type, top_k),
+            top_table_handles: top_usages(&self.by_table_handle, top_k),
+            value_size_histogram: self
+                .value_size_histogram
+                .iter()
+                .enumerate()
+                .filter(|(_, items)| **items > 0)
+                .map(|(num_bits, items)| HistogramBucket {
+                    max_bytes: if num_bits == 0 {
+                        0
+                    } else {
+                        u64::MAX >> (u64::BITS as usize - num_bits)
+                    },
+                    items: *items,
+                })
+                .collect(),
+        }
+    }
+
+    fn diff_report(
+        &self,
+        base: &StateUsage,
+        base_version: Version,
+        version: Version,
+        top_k: usize,
+    ) -> UsageDiffReport {
+        UsageDiffReport {
+            base_version,
+            version,
+            total: self.total.delta(&base.total),
+            by_kind: self
+                .by_kind
+                .keys()
+                .chain(base.by_kind.keys())
+                .map(|kind| {
+                    let usage = self.by_kind.get(kind).copied().unwrap_or_default();
+                    let base_usage = base.by_kind.get(kind).copied().unwrap_or_default();
+                    (*kind, usage.delta(&base_usage))
+                })
+                .collect(),
+            top_account_changes: top_usage_deltas(&self.by_account, &base.by_account, top_k),
+            top_struct_type_changes: top_usage_deltas(
+                &self.by_struct_type,
+                &base.by_struct_type,
+                top_k,
+            ),
+            top_table_handle_changes: top_usage_deltas(
+                &self.by_table_handle,
+                &base.by_table_handle,
+                top_k,
+            ),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct UsageReport {
+    version: Version,
+    total: Usage,
+    by_kind: BTreeMap<&'static str, Usage>,
+    top_accounts: Vec<UsageEntry>,
+    top_struct_types: Vec<UsageEntry>,
+    top_table_handles: Vec<UsageEntry>,
+    value_size_histogram: Vec<HistogramBucket>,
+}
+
+impl UsageReport {
+    fn print(&self) {
+        println!(
+            "{}",
+            format!("* Usage at version {}:", self.version).yellow()
+        );
+        println!(
+            "  total: {} items, {} bytes",
+            self.total.items, self.total.bytes
+        );
+        for (kind, usage) in &self.by_kind {
+            println!("  {}: {} items, {} bytes", kind, usage.items, usage.bytes);
+        }
+        for (title, entries) in [
+            ("Top accounts", &self.top_accounts),
+            ("Top struct types", &self.top_struct_types),
+            ("Top table handles", &self.top_table_handles),
+        ] {
+            println!("{}", format!("* {}:", title).yellow());
+            for entry in entries {
+                println!(
+                    "  {}: {} items, {} bytes",
+                    entry.id, entry.usage.items, entry.usage.bytes
+                );
+            }
+        }
+        println!("{}", "* Value size histogram:".yellow());
+        for bucket in &self.value_size_histogram {
+            println!("  <= {} bytes: {} items", bucket.max_bytes, bucket.items);
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct UsageDiffReport {
+    base_version: Version,
+    version: Version,
+    total: UsageDelta,
+    by_kind: BTreeMap<&'static str, UsageDelta>,
+    top_account_changes: Vec<UsageDeltaEntry>,
+    top_struct_type_changes: Vec<UsageDeltaEntry>,
+    top_table_handle_changes: Vec<UsageDeltaEntry>,
+}
+
+impl UsageDiffReport {
+    fn print(&self) {
+        println!(
+            "{}",
+            format!(
+                "* Usage changes from version {} to version {}:",
+                self.base_version, self.version
+            )
+            .yellow()
+        );
+        println!(
+            "  total: {:+} items, {:+} bytes",
+            self.total.items, self.total.bytes
+        );
+        for (kind, delta) in &self.by_kind {
+            println!(
+                "  {}: {:+} items, {:+} bytes",
+                kind, delta.items, delta.bytes
+            );
+        }
+        for (title, entries) in [
+            ("Top account changes", &self.top_account_changes),
+            ("Top struct type changes", &self.top_struct_type_changes),
+            ("Top table handle changes", &self.top_table_handle_changes),
+        ] {
+            println!("{}", format!("* {}:", title).yellow());
+            for entry in entries {
+                println!(
+                    "  {}: {:+} items, {:+} bytes",
+                    entry.id, entry.delta.items, entry.delta.bytes
+                );
+            }
+        }
+    }
+}
+
+/// Identifies a struct type regardless of its type arguments, e.g. `0x1::coin::CoinStore`.
+fn struct_type_id(struct_tag: &StructTag) -> String {
+    format!(
+        "{}::{}::{}",
+        struct_tag.address.short_str_lossless(),
+        struct_tag.module,
+        struct_tag.name
+    )
+}
+
+fn merge_usage_map<K: Eq + Hash>(map: &mut HashMap<K, Usage>, other: HashMap<K, Usage>) {
+    for (key, usage) in other {
+        map.entry(key).or_default().merge(&usage);
+    }
+}
+
+fn top_usages<K: Display>(map: &HashMap<K, Usage>, top_k: usize) -> Vec<UsageEntry> {
+    let mut usages: Vec<_> = map.iter().collect();
+    usages.sort_by(|(_, left), (_, right)| right.bytes.cmp(&left.bytes));
+    usages
+        .into_iter()
+        .take(top_k)
+        .map(|(key, usage)| UsageEntry {
+            id: key.to_string(),
+            usage: *usage,
+        })
+        .collect()
+}
+
+fn top_usage_deltas<K: Display + Eq + Hash>(
+    map: &HashMap<K, Usage>,
+    base: &HashMap<K, Usage>,
+    top_k: usize,
+) -> Vec<UsageDeltaEntry> {
+    let keys: HashSet<&K> = map.keys().chain(base.keys()).collect();
+    let mut deltas: Vec<_> = keys
+        .into_iter()
+        .map(|key| {
+            let usage = map.get(key).copied().unwrap_or_default();
+            let base_usage = base.get(key).copied().unwrap_or_default();
+            (key, usage.delta(&base_usage))
+        })
+        .filter(|(_, delta)| delta.items != 0 || delta.bytes != 0)
+        .collect();
+    deltas.sort_by(|(_, left), (_, right)| right.bytes.abs().cmp(&left.bytes.abs()));
+    deltas
+        .into_iter()
+        .take(top_k)
+        .map(|(key, delta)| UsageDeltaEntry {
+            id: key.to_string(),
+            delta,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use aptos_types::state_store::table::TableHandle;
+    use move_core_types::ident_str;
+    use std::str::FromStr;
+
+    fn resource_key(address: AccountAddress, struct_tag: &str) -> StateKey {
+        StateKey::resource(&address, &StructTag::from_str(struct_tag).unwrap()).unwrap()
+    }
+
+    fn usage_of(keys_and_sizes: &[(StateKey, usize)]) -> StateUsage {
+        let mut usage = StateUsage::new();
+        for (key, value_size) in keys_and_sizes {
+            usage.add(key, *value_size);
+        }
+        usage
+    }
+
+    #[test]
+    fn test_aggregation() {
+        let account = AccountAddress::from_hex_literal("0xa").unwrap();
+        let other_account = AccountAddress::from_hex_literal("0xb").unwrap();
+        let coin_store = resource_key(account, "0x1::coin::CoinStore<0x1::aptos_coin::AptosCoin>");
+        let other_coin_store = resource_key(other_account, "0x1::coin::CoinStore<0xb::foo::Foo>");
+        let module = StateKey::module(&account, ident_str!("foo"));
+        let table_item = StateKey::table_item(&TableHandle(other_account), b"key");
+        let keys_and_sizes = vec![
+            (coin_store.clone(), 10),
+            (other_coin_store.clone(), 20),
+            (module.clone(), 100),
+            (table_item.clone(), 5),
+        ];
+        let bytes = |key: &StateKey, value_size: usize| (key.size() + value_size) as u64;
+
+        let usage = usage_of(&keys_and_sizes);
+        assert_eq!(usage.total.items, 4);
+        assert_eq!(
+            usage.total.bytes,
+            keys_and_sizes
+                .iter()
+                .map(|(key, value_size)| bytes(key, *value_size))
+                .sum::<u64>()
+        );
+
+        // Resources of the same struct are grouped regardless of their type arguments
+        let coin_store_usage = usage.by_struct_type["0x1::coin::CoinStore"];
+        assert_eq!(coin_store_usage.items, 2);
+        assert_eq!(
+            coin_store_usage.bytes,
+            bytes(&coin_store, 10) + bytes(&other_coin_store, 20)
+        );
+
+        // Table items are attributed to their handle, not to an account
+        assert_eq!(usage.by_account[&account].items, 2);
+        assert_eq!(
+            usage.by_account[&account].bytes,
+            bytes(&coin_store, 10) + bytes(&module, 100)
+        );
+        assert_eq!(usage.by_account[&other_account].items, 1);
+        assert_eq!(usage.by_table_handle[&other_account].items, 1);
+        assert_eq!(
+            usage.by_table_handle[&other_account].bytes,
+            bytes(&table_item, 5)
+        );
+        for (kind, items) in [("resource", 2), ("code", 1), ("table_item", 1)] {
+            assert_eq!(usage.by_kind[kind].items, items);
+        }
+
+        // Merging the usages of a split snapshot gives the usage of the whole snapshot
+        let mut merged = usage_of(&keys_and_sizes[..1]);
+        merged.merge(usage_of(&keys_and_sizes[1..]));
+        let report = usage.report(0, 10);
+        let merged_report = merged.report(0, 10);
+        assert_eq!(
+            serde_json::to_value(&merged_report).unwrap(),
+            serde_json::to_value(&report).unwrap()
+        );
+
+        // Top usages are sorted by bytes and limited to `top_k`
+        let top_accounts = report.top_accounts;
+        assert_eq!(top_accounts.len(), 2);
+        assert_eq!(top_accounts[0].id, account.to_string());
+        let top_accounts = usage.report(0, 1).top_accounts;
+        assert_eq!(top_accounts.len(), 1);
+        assert_eq!(top_accounts[0].id, account.to_string());
+    }
+
+    #[test]
+    fn test_value_size_histogram() {
+        let key = StateKey::raw(b"key");
+        let usage = usage_of(
+            &[0, 1, 2, 3, 4, 255, 256]
+                .into_iter()
+                .map(|value_size| (key.clone(), value_size))
+                .collect::<Vec<_>>(),
+        );
+
+        // Only non-empty buckets are reported, each bounded by the largest size of its bit length
+        let buckets: Vec<_> = usage
+            .report(0, 10)
+            .value_size_histogram
+            .iter()
+            .map(|bucket| (bucket.max_bytes, bucket.items))
+            .collect();
+        assert_eq!(buckets, vec![
+            (0, 1),
+            (1, 1),
+            (3, 2),
+            (7, 1),
+            (255, 1),
+            (511, 1)
+        ]);
+    }
+
+    #[test]
+    fn test_diff_report() {
+        let accounts: Vec<_> = (1..=4)
+            .map(|i| AccountAddress::from_hex_literal(&format!("0x{}", i)).unwrap())
+            .collect();
+        let key = |i: usize| resource_key(accounts[i], "0x1::account::Account");
+        let base = usage_of(&[(key(0), 10), (key(1), 10), (key(2), 10)]);
+        // Account 0 is unchanged, account 1 grows, account 2 is deleted and account 3 is new
+        let usage = usage_of(&[(key(0), 10), (key(1), 15), (key(3), 100)]);
+
+        let report = usage.diff_report(&base, 1, 2, 10);
+        assert_eq!(report.total.items, 0);
+        assert_eq!(
+            report.total.bytes,
+            (key(3).size() + 100 + 5) as i64 - (key(2).size() + 10) as i64
+        );
+        assert_eq!(report.by_kind["resource"].items, 0);
+
+        // Unchanged entries are skipped, and the rest is sorted by the absolute change in bytes
+        let changes: Vec<_> = report
+            .top_account_changes
+            .iter()
+            .map(|entry| (entry.id.clone(), entry.delta.items, entry.delta.bytes))
+            .collect();
+        assert_eq!(changes, vec![
+            (accounts[3].to_string(), 1, (key(3).size() + 100) as i64),
+            (accounts[2].to_string(), -1, -((key(2).size() + 10) as i64)),
+            (accounts[1].to_string(), 0, 5),
+        ]);
+        assert_eq!(report.top_struct_type_changes.len(), 1);
+        assert_eq!(
+            usage.diff_report(&base, 1, 2, 1).top_account_changes.len(),
+            1
+        );
+    }
+}
@@ -132,6 +132,7 @@ impl Cmd {
             Arc::clone(&state_kv_db),
             Arc::clone(&state_merkle_db),
             /*crash_if_difference_is_too_large=*/ false,
+            /*internal_indexer_db=*/ None,
         );
         println!("Done!");
 
@@ -2,7 +2,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    schema::state_value_by_key_hash::StateValueByKeyHashSchema, state_kv_db::StateKvDb, AptosDB,
+    schema::state_value_by_key_hash::StateValueByKeyHashSchema, state_kv_db::StateKvDb,
+    utils::DbOpenMode, AptosDB,
 };
 use aptos_config::config::{RocksdbConfig, StorageDirPaths};
 use aptos_crypto::{hash::CryptoHash, HashValue};
@@ -118,7 +119,11 @@ pub fn verify_state_kvs(
 ) -> Result<()> {
     println!("Validating db statekeys");
     let storage_dir = StorageDirPaths::from_path(db_root_path);
-    let state_kv_db = StateKvDb::open_sharded(&storage_dir, RocksdbConfig::default(), false)?;
+    let state_kv_db = StateKvDb::open_sharded(
+        &storage_dir,
+        RocksdbConfig::default(),
+        DbOpenMode::ReadWrite,
+    )?;
 
     //read all statekeys from internal db and store them in mem
     let mut all_internal_keys = HashSet::new();
@@ -1,7 +1,7 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::AptosDB;
+use crate::{ledger_retention::LedgerRetentionFilter, partial_state::PartialStateFilter, AptosDB};
 use anyhow::anyhow;
 use aptos_config::config::{NodeConfig, StorageDirPaths};
 use aptos_crypto::HashValue;
@@ -46,6 +46,9 @@ impl FastSyncStorageWrapper {
         internal_indexer_db: Option<InternalIndexerDB>,
         update_sender: Option<Sender<Version>>,
     ) -> Result<Either<AptosDB, Self>> {
+        let partial_state_filter = PartialStateFilter::from_config(&config.storage.partial_state);
+        let ledger_retention_filter =
+            LedgerRetentionFilter::from_config(&config.storage.ledger_retention)?;
         let mut db_main = AptosDB::open(
             config.storage.get_dir_paths(),
             /*readonly=*/ false,
@@ -55,11 +58,19 @@ impl FastSyncStorageWrapper {
             config.storage.buffered_state_target_items,
             config.storage.max_num_nodes_per_lru_cache_shard,
             internal_indexer_db,
+            partial_state_filter.clone(),
+            ledger_retention_filter.clone(),
         )
         .map_err(|err| anyhow!("fast sync DB failed to open {}", err))?;
         if let Some(sender) = update_sender {
             db_main.add_version_update_subscriber(sender)?;
         }
+        if config.storage.consistency_scrubber.enable {
+            db_main.start_consistency_scrubber(config.storage.consistency_scrubber);
+        }
+        db_main.set_enable_unproven_historical_state_reads(
+            config.storage.enable_unproven_historical_state_reads,
+        );
 
         let mut db_dir = config.storage.dir();
         // when the db is empty and configured to do fast sync, we will create a second DB
@@ -76,7 +87,7 @@ impl FastSyncStorageWrapper {
                 == 0)
         {
             db_dir.push(SECONDARY_DB_DIR);
-            let secondary_db = AptosDB::open(
+            let mut secondary_db = AptosDB::open(
                 StorageDirPaths::from_path(db_dir.as_path()),
                 /*readonly=*/ false,
                 config.storage.storage_pruner_config,
@@ -85,8 +96,13 @@ impl FastSyncStorageWrapper {
                 config.storage.buffered_state_target_items,
                 config.storage.max_num_nodes_per_lru_cache_shard,
                 None,
+                partial_state_filter,
+                ledger_retention_filter,
             )
             .map_err(|err| anyhow!("Secondary DB failed to open {}", err))?;
+            secondary_db.set_enable_unproven_historical_state_reads(
+                config.storage.enable_unproven_historical_state_reads,
+            );
 
             Ok(Either::Right(FastSyncStorageWrapper {
                 temporary_db_with_genesis: Arc::new(secondary_db),
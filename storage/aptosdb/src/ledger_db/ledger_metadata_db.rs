@@ -8,6 +8,7 @@ use crate::{
         db_metadata::{DbMetadataKey, DbMetadataSchema, DbMetadataValue},
         epoch_by_version::EpochByVersionSchema,
         ledger_info::LedgerInfoSchema,
+        retained_version::RetainedVersionSchema,
         version_data::VersionDataSchema,
     },
     utils::{get_progress, iterators::EpochEndingLedgerInfoIter},
@@ -21,7 +22,11 @@ use aptos_types::{
     state_store::state_storage_usage::StateStorageUsage, transaction::Version,
 };
 use arc_swap::ArcSwap;
-use std::{ops::Deref, path::Path, sync::Arc};
+use std::{
+    ops::{Deref, Range},
+    path::Path,
+    sync::Arc,
+};
 
 fn get_latest_ledger_info_in_db_impl(db: &DB) -> Result<Option<LedgerInfoWithSignatures>> {
     let mut iter = db.iter::<LedgerInfoSchema>()?;
@@ -182,6 +187,13 @@ impl LedgerMetadataDb {
             .store(Arc::new(Some(ledger_info_with_sigs)));
     }
 
+    /// Catches up with the primary instance when opened as a secondary, returning the latest
+    /// ledger info in the DB. The ledger info is not exposed until `set_latest_ledger_info`.
+    pub(crate) fn try_catch_up_with_primary(&self) -> Result<Option<LedgerInfoWithSignatures>> {
+        self.db.try_catch_up_with_primary()?;
+        get_latest_ledger_info_in_db_impl(&self.db)
+    }
+
     /// Writes `ledger_info_with_sigs` to `batch`.
     pub(crate) fn put_ledger_info(
         &self,
@@ -340,3 +352,57 @@ impl LedgerMetadataDb {
         }
     }
 }
+
+/// Ledger retention APIs.
+impl LedgerMetadataDb {
+    /// Returns true iff the ledger data at the given version is retained by the ledger pruner.
+    pub(crate) fn is_retained_version(&self, version: Version) -> Result<bool> {
+        Ok(self.db.get::<RetainedVersionSchema>(&version)?.is_some())
+    }
+
+    /// Returns up to `limit` retained versions, starting from `start_version`.
+    pub(crate) fn get_retained_versions(
+        &self,
+        start_version: Version,
+        limit: usize,
+    ) -> Result<Vec<Version>> {
+        let mut iter = self.db.iter::<RetainedVersionSchema>()?;
+        iter.seek(&start_version)?;
+        iter.take(limit)
+            .map(|res| res.map(|(version, _)| version))
+            .collect()
+    }
+
+    /// Returns the retained versions in the given version range.
+    pub(crate) fn get_retained_versions_in_range(
+        &self,
+        range: Range<Version>,
+    ) -> Result<Vec<Version>> {
+        let mut iter = self.db.iter::<RetainedVersionSchema>()?;
+        iter.seek(&range.start)?;
+        let mut versions = Vec::new();
+        for res in iter {
+            let (version, _) = res?;
+            if version >= range.end {
+                break;
+            }
+            versions.push(version);
+        }
+        Ok(versions)
+    }
+
+    /// Returns true iff there is any retained version in the given version range.
+    pub(crate) fn has_retained_version_in_range(&self, range: Range<Version>) -> Result<bool> {
+        let mut iter = self.db.iter::<RetainedVersionSchema>()?;
+        iter.seek(&range.start)?;
+        Ok(iter
+            .next()
+            .transpose()?
+            .map_or(false, |(version, _)| version < range.end))
+    }
+
+    /// Puts a retained version into the batch.
+    pub(crate) fn put_retained_version(version: Version, batch: &SchemaBatch) -> Result<()> {
+        batch.put::<RetainedVersionSchema>(&version, &())
+    }
+}
@@ -22,14 +22,14 @@ use crate::{
         transaction_info_db::TransactionInfoDb, write_set_db::WriteSetDb,
     },
     schema::db_metadata::{DbMetadataKey, DbMetadataSchema},
+    utils::{open_rocksdb, DbOpenMode},
 };
 use aptos_config::config::{RocksdbConfig, RocksdbConfigs};
 use aptos_experimental_runtimes::thread_manager::THREAD_MANAGER;
 use aptos_logger::prelude::info;
-use aptos_rocksdb_options::gen_rocksdb_options;
 use aptos_schemadb::{ColumnFamilyDescriptor, ColumnFamilyName, SchemaBatch, DB};
 use aptos_storage_interface::Result;
-use aptos_types::transaction::Version;
+use aptos_types::{ledger_info::LedgerInfoWithSignatures, transaction::Version};
 use std::{
     path::{Path, PathBuf},
     sync::Arc,
@@ -112,7 +112,7 @@ impl LedgerDb {
     pub(crate) fn new<P: AsRef<Path>>(
         db_root_path: P,
         rocksdb_configs: RocksdbConfigs,
-        readonly: bool,
+        open_mode: DbOpenMode,
     ) -> Result<Self> {
         let sharding = rocksdb_configs.enable_storage_sharding;
         let ledger_metadata_db_path = Self::metadata_db_path(db_root_path.as_ref(), sharding);
@@ -124,7 +124,7 @@ impl LedgerDb {
                 LEDGER_DB_NAME
             },
             &rocksdb_configs.ledger_db_config,
-            readonly,
+            open_mode,
         )?);
 
         info!(
@@ -169,7 +169,7 @@ impl LedgerDb {
                         ledger_db_folder.join(EVENT_DB_NAME),
                         EVENT_DB_NAME,
                         &rocksdb_configs.ledger_db_config,
-                        readonly,
+                        open_mode,
                     )
                     .unwrap(),
                 );
@@ -184,7 +184,7 @@ impl LedgerDb {
                         ledger_db_folder.join(TRANSACTION_ACCUMULATOR_DB_NAME),
                         TRANSACTION_ACCUMULATOR_DB_NAME,
                         &rocksdb_configs.ledger_db_config,
-                        readonly,
+                        open_mode,
                     )
                     .unwrap(),
                 )));
@@ -195,7 +195,7 @@ impl LedgerDb {
                         ledger_db_folder.join(TRANSACTION_AUXILIARY_DATA_DB_NAME),
                         TRANSACTION_AUXILIARY_DATA_DB_NAME,
                         &rocksdb_configs.ledger_db_config,
-                        readonly,
+                        open_mode,
                     )
                     .unwrap(),
                 )))
@@ -206,7 +206,7 @@ impl LedgerDb {
                         ledger_db_folder.join(TRANSACTION_DB_NAME),
                         TRANSACTION_DB_NAME,
                         &rocksdb_configs.ledger_db_config,
-                        readonly,
+                        open_mode,
                     )
                     .unwrap(),
                 )));
@@ -217,7 +217,7 @@ impl LedgerDb {
                         ledger_db_folder.join(TRANSACTION_INFO_DB_NAME),
                         TRANSACTION_INFO_DB_NAME,
                         &rocksdb_configs.ledger_db_config,
-                        readonly,
+                        open_mode,
                     )
                     .unwrap(),
                 )));
@@ -228,7 +228,7 @@ impl LedgerDb {
                         ledger_db_folder.join(WRITE_SET_DB_NAME),
                         WRITE_SET_DB_NAME,
                         &rocksdb_configs.ledger_db_config,
-                        readonly,
+                        open_mode,
                     )
                     .unwrap(),
                 )));
@@ -273,7 +273,13 @@ impl LedgerDb {
             enable_storage_sharding: sharding,
             ..Default::default()
         };
-        let ledger_db = Self::new(db_root_path, rocksdb_configs, /*readonly=*/ false)?;
+        let ledger_db = Self::new(db_root_path, rocksdb_configs, DbOpenMode::ReadWrite)?;
+        ledger_db.checkpoint_to(cp_root_path)
+    }
+
+    /// Creates a checkpoint of all the ledger DBs under `cp_root_path`, can be used on a live DB.
+    pub(crate) fn checkpoint_to(&self, cp_root_path: impl AsRef<Path>) -> Result<()> {
+        let sharding = self.enable_storage_sharding;
         let cp_ledger_db_folder = cp_root_path.as_ref().join(LEDGER_DB_FOLDER_NAME);
 
         info!(
@@ -286,28 +292,21 @@ impl LedgerDb {
             std::fs::create_dir_all(&cp_ledger_db_folder).unwrap_or(());
         }
 
-        ledger_db
-            .metadata_db()
+        self.metadata_db()
             .create_checkpoint(Self::metadata_db_path(cp_root_path.as_ref(), sharding))?;
 
         if sharding {
-            ledger_db
-                .event_db()
+            self.event_db()
                 .create_checkpoint(cp_ledger_db_folder.join(EVENT_DB_NAME))?;
-            ledger_db
-                .transaction_accumulator_db()
+            self.transaction_accumulator_db()
                 .create_checkpoint(cp_ledger_db_folder.join(TRANSACTION_ACCUMULATOR_DB_NAME))?;
-            ledger_db
-                .transaction_auxiliary_data_db()
+            self.transaction_auxiliary_data_db()
                 .create_checkpoint(cp_ledger_db_folder.join(TRANSACTION_AUXILIARY_DATA_DB_NAME))?;
-            ledger_db
-                .transaction_db()
+            self.transaction_db()
                 .create_checkpoint(cp_ledger_db_folder.join(TRANSACTION_DB_NAME))?;
-            ledger_db
-                .transaction_info_db()
+            self.transaction_info_db()
                 .create_checkpoint(cp_ledger_db_folder.join(TRANSACTION_INFO_DB_NAME))?;
-            ledger_db
-                .write_set_db()
+            self.write_set_db()
                 .create_checkpoint(cp_ledger_db_folder.join(WRITE_SET_DB_NAME))?;
         }
 
@@ -330,6 +329,25 @@ impl LedgerDb {
         Ok(())
     }
 
+    /// Catches up with the primary instance when opened as a secondary, returning the latest
+    /// ledger info. The metadata DB is caught up first: the primary commits a ledger info after
+    /// all the data up to it, so that data is visible in the other DBs caught up afterwards.
+    pub(crate) fn try_catch_up_with_primary(&self) -> Result<Option<LedgerInfoWithSignatures>> {
+        let latest_ledger_info = self.ledger_metadata_db.try_catch_up_with_primary()?;
+        if self.enable_storage_sharding {
+            self.event_db_raw().try_catch_up_with_primary()?;
+            self.transaction_accumulator_db_raw()
+                .try_catch_up_with_primary()?;
+            self.transaction_auxiliary_data_db_raw()
+                .try_catch_up_with_primary()?;
+            self.transaction_db_raw().try_catch_up_with_primary()?;
+            self.transaction_info_db_raw().try_catch_up_with_primary()?;
+            self.write_set_db_raw().try_catch_up_with_primary()?;
+        }
+
+        Ok(latest_ledger_info)
+    }
+
     pub(crate) fn metadata_db(&self) -> &LedgerMetadataDb {
         &self.ledger_metadata_db
     }
@@ -393,23 +411,15 @@ impl LedgerDb {
         path: PathBuf,
         name: &str,
         db_config: &RocksdbConfig,
-        readonly: bool,
+        open_mode: DbOpenMode,
     ) -> Result<DB> {
-        let db = if readonly {
-            DB::open_cf_readonly(
-                &gen_rocksdb_options(db_config, true),
-                path.clone(),
-                name,
-                Self::gen_cfds_by_name(db_config, name),
-            )?
-        } else {
-            DB::open_cf(
-                &gen_rocksdb_options(db_config, false),
-                path.clone(),
-                name,
-                Self::gen_cfds_by_name(db_config, name),
-            )?
-        };
+        let db = open_rocksdb(
+            path.clone(),
+            name,
+            db_config,
+            Self::gen_cfds_by_name(db_config, name),
+            open_mode,
+        )?;
 
         info!("Opened {name} at {path:?}!");
 
@@ -1,10 +1,13 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::schema::{
-    db_metadata::{DbMetadataKey, DbMetadataSchema, DbMetadataValue},
-    transaction_accumulator::TransactionAccumulatorSchema,
-    transaction_accumulator_root_hash::TransactionAccumulatorRootHashSchema,
+use crate::{
+    ledger_db::ledger_metadata_db::LedgerMetadataDb,
+    schema::{
+        db_metadata::{DbMetadataKey, DbMetadataSchema, DbMetadataValue},
+        transaction_accumulator::TransactionAccumulatorSchema,
+        transaction_accumulator_root_hash::TransactionAccumulatorRootHashSchema,
+    },
 };
 use anyhow::anyhow;
 use aptos_accumulator::{HashReader, MerkleAccumulator};
@@ -21,7 +24,7 @@ use aptos_types::{
     },
     transaction::{TransactionInfo, Version},
 };
-use std::{borrow::Borrow, path::Path, sync::Arc};
+use std::{borrow::Borrow, collections::HashSet, ops::Range, path::Path, sync::Arc};
 
 pub(crate) type Accumulator =
     MerkleAccumulator<TransactionAccumulatorDb, TransactionAccumulatorHasher>;
@@ -146,7 +149,65 @@ impl TransactionAccumulatorDb {
     /// 2. From the node found from the previous step, delete both its children non-useful, and go
     /// to the right child to repeat the process until we reach a leaf node.
     /// More details are in this issue https://github.com/aptos-labs/aptos-core/issues/1288.
-    pub(crate) fn prune(begin: Version, end: Version, db_batch: &SchemaBatch) -> Result<()> {
+    ///
+    /// Versions retained by the ledger retention rules keep their root hash, as well as the nodes
+    /// in their proofs, i.e. a child is not deleted if its sibling's subtree contains a retained
+    /// version. The retained versions are looked up in `ledger_metadata_db`, which is None if no
+    /// retention rule is configured.
+    pub(crate) fn prune(
+        begin: Version,
+        end: Version,
+        db_batch: &SchemaBatch,
+        ledger_metadata_db: Option<&LedgerMetadataDb>,
+    ) -> Result<()> {
+        let Some(ledger_metadata_db) = ledger_metadata_db else {
+            return Self::prune_all(begin, end, db_batch);
+        };
+
+        let retained_versions: HashSet<_> = ledger_metadata_db
+            .get_retained_versions_in_range(begin..end)?
+            .into_iter()
+            .collect();
+        for version_to_delete in begin..end {
+            if !retained_versions.contains(&version_to_delete) {
+                db_batch.delete::<TransactionAccumulatorRootHashSchema>(&version_to_delete)?;
+            }
+            // The even version will be pruned in the iteration of version + 1.
+            if version_to_delete % 2 == 0 {
+                continue;
+            }
+
+            let first_ancestor_that_is_a_left_child =
+                Self::find_first_ancestor_that_is_a_left_child(version_to_delete);
+
+            // This assertion is true because we skip the leaf nodes with address which is a
+            // a multiple of 2.
+            assert!(!first_ancestor_that_is_a_left_child.is_leaf());
+
+            // All the nodes to delete below are in the subtree of the first ancestor, so there is
+            // nothing to keep unless a version in the subtree is retained.
+            let has_retained_version = ledger_metadata_db.has_retained_version_in_range(
+                Self::leaf_range(first_ancestor_that_is_a_left_child),
+            )?;
+
+            let mut current = first_ancestor_that_is_a_left_child;
+            while !current.is_leaf() {
+                for child in [current.left_child(), current.right_child()] {
+                    if !has_retained_version
+                        || !ledger_metadata_db
+                            .has_retained_version_in_range(Self::leaf_range(child.sibling()))?
+                    {
+                        db_batch.delete::<TransactionAccumulatorSchema>(&child)?;
+                    }
+                }
+                current = current.right_child();
+            }
+        }
+        Ok(())
+    }
+
+    /// Prunes the range [begin, end) without keeping any retained version.
+    fn prune_all(begin: Version, end: Version, db_batch: &SchemaBatch) -> Result<()> {
         for version_to_delete in begin..end {
             db_batch.delete::<TransactionAccumulatorRootHashSchema>(&version_to_delete)?;
             // The even version will be pruned in the iteration of version + 1.
@@ -171,6 +232,13 @@ impl TransactionAccumulatorDb {
         Ok(())
     }
 
+    /// Returns the versions of the leaves in the subtree rooted at `position`.
+    fn leaf_range(position: Position) -> Range<Version> {
+        let first_leaf = position.left_most_child().to_inorder_index() / 2;
+        let last_leaf = position.right_most_child().to_inorder_index() / 2;
+        first_leaf..last_leaf + 1
+    }
+
     /// Returns the first ancestor that is a child of its parent.
     fn find_first_ancestor_that_is_a_left_child(version: Version) -> Position {
         // We can get the first ancestor's position based on the two observations:
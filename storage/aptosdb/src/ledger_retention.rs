@@ -0,0 +1,110 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Support for selective archival retention in the ledger pruner. Transactions matching any of
+//! the configured retention rules are kept by the ledger pruner, together with their events,
+//! write set, transaction info, auxiliary data and transaction accumulator proof, while all
+//! other ledger data falling out of the prune window is pruned as usual.
+//!
+//! Retained versions are recorded in the ledger metadata DB (see `RetainedVersionSchema`) before
+//! anything is pruned, so the sub pruners (including ones catching up on startup) skip exactly
+//! the same versions, and the retained versions can be queried afterwards.
+
+use aptos_config::config::LedgerRetentionConfig;
+use aptos_storage_interface::{AptosDbError, Result};
+use aptos_types::{
+    account_address::AccountAddress,
+    contract_event::ContractEvent,
+    transaction::{MultisigTransactionPayload, Transaction, TransactionPayload},
+};
+use move_core_types::language_storage::TypeTag;
+use std::{collections::HashSet, str::FromStr};
+
+/// Identifies the transactions that are retained by the ledger pruner.
+#[derive(Clone, Debug)]
+pub struct LedgerRetentionFilter {
+    senders: HashSet<AccountAddress>,
+    event_types: HashSet<TypeTag>,
+    module_addresses: HashSet<AccountAddress>,
+}
+
+impl LedgerRetentionFilter {
+    pub fn new(
+        senders: impl IntoIterator<Item = AccountAddress>,
+        event_types: impl IntoIterator<Item = TypeTag>,
+        module_addresses: impl IntoIterator<Item = AccountAddress>,
+    ) -> Self {
+        Self {
+            senders: senders.into_iter().collect(),
+            event_types: event_types.into_iter().collect(),
+            module_addresses: module_addresses.into_iter().collect(),
+        }
+    }
+
+    /// Returns a filter for the given config, or None if no retention rule is configured
+    pub fn from_config(config: &LedgerRetentionConfig) -> Result<Option<Self>> {
+        if !config.is_enabled() {
+            return Ok(None);
+        }
+
+        let event_types = config
+            .event_types
+            .iter()
+            .map(|event_type| {
+                TypeTag::from_str(event_type).map_err(|e| {
+                    AptosDbError::Other(format!("Invalid retained event type {event_type}: {e}"))
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Some(Self::new(
+            config.senders.iter().cloned(),
+            event_types,
+            config.module_addresses.iter().cloned(),
+        )))
+    }
+
+    /// Returns true iff the transaction (with the events it emitted) matches any retention rule
+    pub fn is_retained(&self, txn: &Transaction, events: &[ContractEvent]) -> bool {
+        if let Transaction::UserTransaction(signed_txn) = txn {
+            if self.senders.contains(&signed_txn.sender()) {
+                return true;
+            }
+            if entry_function_module_address(signed_txn.payload())
+                .map_or(false, |address| self.module_addresses.contains(&address))
+            {
+                return true;
+            }
+        }
+
+        events
+            .iter()
+            .any(|event| self.is_retained_event_type(event.type_tag()))
+    }
+
+    fn is_retained_event_type(&self, type_tag: &TypeTag) -> bool {
+        if self.event_types.contains(type_tag) {
+            return true;
+        }
+        match type_tag {
+            TypeTag::Struct(struct_tag) => self.module_addresses.contains(&struct_tag.address),
+            _ => false,
+        }
+    }
+}
+
+/// Returns the address of the module defining the entry function called by the payload, if any.
+fn entry_function_module_address(payload: &TransactionPayload) -> Option<AccountAddress> {
+    match payload {
+        TransactionPayload::EntryFunction(entry_function) => {
+            Some(*entry_function.module().address())
+        },
+        TransactionPayload::Multisig(multisig) => match &multisig.transaction_payload {
+            Some(MultisigTransactionPayload::EntryFunction(entry_function)) => {
+                Some(*entry_function.module().address())
+            },
+            None => None,
+        },
+        TransactionPayload::Script(_) | TransactionPayload::ModuleBundle(_) => None,
+    }
+}
@@ -27,7 +27,10 @@ pub mod utils;
 #[cfg(feature = "db-debugger")]
 pub mod db_debugger;
 pub mod fast_sync_storage_wrapper;
+pub mod ledger_retention;
+pub mod partial_state;
 
+mod consistency_scrubber;
 mod db_options;
 mod event_store;
 mod ledger_db;
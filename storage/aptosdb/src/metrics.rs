@@ -3,8 +3,9 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use aptos_metrics_core::{
-    exponential_buckets, register_histogram_vec, register_int_counter, register_int_gauge,
-    register_int_gauge_vec, HistogramVec, IntCounter, IntGauge, IntGaugeVec,
+    exponential_buckets, register_histogram_vec, register_int_counter, register_int_counter_vec,
+    register_int_gauge, register_int_gauge_vec, HistogramVec, IntCounter, IntCounterVec, IntGauge,
+    IntGaugeVec,
 };
 use once_cell::sync::Lazy;
 
@@ -263,3 +264,56 @@ pub static CONCURRENCY_GAUGE: Lazy<IntGaugeVec> = Lazy::new(|| {
     )
     .unwrap()
 });
+
+/// Consistency scrubber progress, e.g. the next ledger version to verify.
+pub static SCRUBBER_PROGRESS: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        "aptos_storage_scrubber_progress",
+        "Progress of the consistency scrubber.",
+        &["type"]
+    )
+    .unwrap()
+});
+
+pub static SCRUBBER_VERIFIED_ITEMS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_storage_scrubber_verified_items",
+        "Number of items verified by the consistency scrubber, by check.",
+        &["check"]
+    )
+    .unwrap()
+});
+
+pub static SCRUBBER_CORRUPTIONS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_storage_scrubber_corruptions",
+        "Number of corruptions detected by the consistency scrubber, by check.",
+        &["check"]
+    )
+    .unwrap()
+});
+
+pub static SCRUBBER_LAST_CORRUPTED_VERSION: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        "aptos_storage_scrubber_last_corrupted_version",
+        "The version of the latest corruption detected by the consistency scrubber, by check.",
+        &["check"]
+    )
+    .unwrap()
+});
+
+pub static SCRUBBER_READ_BYTES: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "aptos_storage_scrubber_read_bytes",
+        "Approximate number of bytes read by the consistency scrubber."
+    )
+    .unwrap()
+});
+
+pub static UNPROVEN_STATE_READS: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "aptos_storage_unproven_state_reads",
+        "Number of state values served without proofs, because the state Merkle tree at the requested version is pruned."
+    )
+    .unwrap()
+});
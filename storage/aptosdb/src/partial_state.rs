@@ -0,0 +1,281 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Support for running a node in partial state mode. In this mode, the node verifies all
+//! ledger infos and transaction outputs and persists the full state Merkle tree, but only
+//! persists state values for a configured set of tracked accounts (and table handles).
+//!
+//! Note: the state values that are persisted are incomplete, so a node in partial state mode
+//! never serves state to its peers (e.g., via the storage service), and only hands out
+//! readers (see [PartialStateDbReader]) that fail for untracked state.
+
+use aptos_config::config::PartialStateConfig;
+use aptos_storage_interface::{db_other_bail as bail, AptosDbError, DbReader, Result};
+use aptos_types::{
+    account_address::AccountAddress,
+    account_config::{primary_apt_store, CORE_CODE_ADDRESS},
+    proof::{SparseMerkleMultiProof, SparseMerkleProofExt},
+    state_store::{
+        state_key::{inner::StateKeyInner, prefix::StateKeyPrefix, StateKey},
+        state_value::{StateValue, StateValueChunkWithProof},
+        table::TableHandle,
+    },
+    transaction::Version,
+};
+use std::{collections::HashSet, sync::Arc};
+
+/// Identifies the state keys whose values are persisted by a node running in partial state
+/// mode. Only access paths (i.e., resources and modules) under tracked accounts (including
+/// the primary APT stores of the tracked accounts) and items of tracked tables are persisted.
+#[derive(Clone, Debug)]
+pub struct PartialStateFilter {
+    tracked_accounts: HashSet<AccountAddress>,
+    tracked_prefixes: Vec<Vec<u8>>,
+    tracked_table_handles: HashSet<TableHandle>,
+}
+
+impl PartialStateFilter {
+    pub fn new(
+        tracked_accounts: impl IntoIterator<Item = AccountAddress>,
+        tracked_table_handles: impl IntoIterator<Item = TableHandle>,
+    ) -> Self {
+        // The framework account is always tracked, as the node requires the on-chain configs
+        let mut tracked_accounts: HashSet<_> = tracked_accounts.into_iter().collect();
+        tracked_accounts.insert(CORE_CODE_ADDRESS);
+
+        // The APT balances of the tracked accounts live in primary fungible stores, which
+        // are objects at addresses derived from the account addresses. So, track those too.
+        let primary_stores: Vec<_> = tracked_accounts
+            .iter()
+            .map(|address| primary_apt_store(*address))
+            .collect();
+        tracked_accounts.extend(primary_stores);
+
+        let tracked_prefixes = tracked_accounts
+            .iter()
+            .map(|address| {
+                StateKeyPrefix::from(*address)
+                    .encode()
+                    .expect("Encoding an account prefix should never fail!")
+            })
+            .collect();
+
+        Self {
+            tracked_accounts,
+            tracked_prefixes,
+            tracked_table_handles: tracked_table_handles.into_iter().collect(),
+        }
+    }
+
+    /// Returns a filter for the given config, or None if partial state mode is disabled
+    pub fn from_config(config: &PartialStateConfig) -> Option<Self> {
+        config.enabled.then(|| {
+            Self::new(
+                config.tracked_accounts.iter().cloned(),
+                config.tracked_table_handles.iter().cloned(),
+            )
+        })
+    }
+
+    /// Returns true iff the value of the given state key is persisted
+    pub fn is_tracked(&self, state_key: &StateKey) -> bool {
+        match state_key.inner() {
+            StateKeyInner::AccessPath(access_path) => {
+                self.tracked_accounts.contains(&access_path.address)
+            },
+            StateKeyInner::TableItem { handle, .. } => self.tracked_table_handles.contains(handle),
+            StateKeyInner::Raw(_) => false,
+        }
+    }
+
+    /// Returns true iff all state values with the given prefix are persisted
+    pub fn is_tracked_prefix(&self, key_prefix: &StateKeyPrefix) -> Result<bool> {
+        let encoded_prefix = key_prefix.encode()?;
+        Ok(self
+            .tracked_prefixes
+            .iter()
+            .any(|tracked_prefix| encoded_prefix.starts_with(tracked_prefix)))
+    }
+
+    /// Returns an error if the value of the given state key is not persisted
+    fn ensure_tracked(&self, state_key: &StateKey) -> Result<()> {
+        if self.is_tracked(state_key) {
+            Ok(())
+        } else {
+            Err(AptosDbError::NotFound(format!(
+                "State value for untracked state key {:?} (the node is running in partial state mode)",
+                state_key
+            )))
+        }
+    }
+}
+
+/// A wrapper around a [DbReader] for nodes running in partial state mode. Internally, the
+/// values of untracked state keys are simply absent from the DB. This wrapper is handed
+/// to external readers (e.g., the REST API) and returns a clear error for untracked state,
+/// instead of (incorrectly) reporting that the state value does not exist.
+pub struct PartialStateDbReader {
+    db: Arc<dyn DbReader>,
+    partial_state_filter: PartialStateFilter,
+}
+
+impl PartialStateDbReader {
+    pub fn new(db: Arc<dyn DbReader>, partial_state_filter: PartialStateFilter) -> Self {
+        Self {
+            db,
+            partial_state_filter,
+        }
+    }
+
+    /// Wraps the given reader if partial state mode is enabled in the config. Otherwise,
+    /// the reader is returned as is.
+    pub fn wrap_if_enabled(
+        config: &PartialStateConfig,
+        db: Arc<dyn DbReader>,
+    ) -> Arc<dyn DbReader> {
+        match PartialStateFilter::from_config(config) {
+            Some(partial_state_filter) => Arc::new(Self::new(db, partial_state_filter)),
+            None => db,
+        }
+    }
+}
+
+impl DbReader for PartialStateDbReader {
+    fn get_read_delegatee(&self) -> &dyn DbReader {
+        self.db.as_ref()
+    }
+
+    fn get_prefixed_state_value_iterator(
+        &self,
+        key_prefix: &StateKeyPrefix,
+        cursor: Option<&StateKey>,
+        version: Version,
+    ) -> Result<Box<dyn Iterator<Item = Result<(StateKey, StateValue)>> + '_>> {
+        if !self.partial_state_filter.is_tracked_prefix(key_prefix)? {
+            return Err(AptosDbError::NotFound(format!(
+                "State values for untracked key prefix {:?} (the node is running in partial state mode)",
+                key_prefix
+            )));
+        }
+        self.db
+            .get_prefixed_state_value_iterator(key_prefix, cursor, version)
+    }
+
+    fn get_state_value_by_version(
+        &self,
+        state_key: &StateKey,
+        version: Version,
+    ) -> Result<Option<StateValue>> {
+        self.partial_state_filter.ensure_tracked(state_key)?;
+        self.db.get_state_value_by_version(state_key, version)
+    }
+
+    fn get_state_value_with_version_by_version(
+        &self,
+        state_key: &StateKey,
+        version: Version,
+    ) -> Result<Option<(Version, StateValue)>> {
+        self.partial_state_filter.ensure_tracked(state_key)?;
+        self.db
+            .get_state_value_with_version_by_version(state_key, version)
+    }
+
+    fn get_state_value_with_proof_by_version_ext(
+        &self,
+        state_key: &StateKey,
+        version: Version,
+        root_depth: usize,
+    ) -> Result<(Option<StateValue>, SparseMerkleProofExt)> {
+        self.partial_state_filter.ensure_tracked(state_key)?;
+        self.db
+            .get_state_value_with_proof_by_version_ext(state_key, version, root_depth)
+    }
+
+    fn get_state_values_with_multi_proof_by_version(
+        &self,
+        state_keys: &[StateKey],
+        version: Version,
+    ) -> Result<(Vec<Option<StateValue>>, SparseMerkleMultiProof)> {
+        for state_key in state_keys {
+            self.partial_state_filter.ensure_tracked(state_key)?;
+        }
+        self.db
+            .get_state_values_with_multi_proof_by_version(state_keys, version)
+    }
+
+    fn get_state_value_chunk_with_proof(
+        &self,
+        version: Version,
+        _start_idx: usize,
+        _chunk_size: usize,
+    ) -> Result<StateValueChunkWithProof> {
+        bail!(
+            "State value chunks cannot be served at version {} (the node is running in partial state mode)",
+            version
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::partial_state::PartialStateFilter;
+    use aptos_types::{
+        account_address::AccountAddress,
+        account_config::{
+            primary_apt_store, AccountResource, ObjectGroupResource, CORE_CODE_ADDRESS,
+        },
+        state_store::{
+            state_key::{prefix::StateKeyPrefix, StateKey},
+            table::TableHandle,
+        },
+    };
+    use move_core_types::{ident_str, move_resource::MoveStructType};
+
+    #[test]
+    fn test_partial_state_filter() {
+        // Create a filter with a single tracked account and table
+        let tracked_account = AccountAddress::random();
+        let tracked_table_handle = TableHandle(AccountAddress::random());
+        let partial_state_filter =
+            PartialStateFilter::new(vec![tracked_account], vec![tracked_table_handle]);
+
+        // Verify that resources and modules of the tracked account are tracked
+        let resource_key = StateKey::resource_typed::<AccountResource>(&tracked_account).unwrap();
+        assert!(partial_state_filter.is_tracked(&resource_key));
+        let module_key = StateKey::module(&tracked_account, ident_str!("module"));
+        assert!(partial_state_filter.is_tracked(&module_key));
+        assert!(partial_state_filter
+            .is_tracked_prefix(&StateKeyPrefix::from(tracked_account))
+            .unwrap());
+
+        // Verify that the primary APT store of the tracked account is tracked
+        let primary_store_key = StateKey::resource_group(
+            &primary_apt_store(tracked_account),
+            &ObjectGroupResource::struct_tag(),
+        );
+        assert!(partial_state_filter.is_tracked(&primary_store_key));
+
+        // Verify that the framework account is always tracked
+        let framework_key =
+            StateKey::resource_typed::<AccountResource>(&CORE_CODE_ADDRESS).unwrap();
+        assert!(partial_state_filter.is_tracked(&framework_key));
+
+        // Verify that other accounts are not tracked
+        let untracked_account = AccountAddress::random();
+        let untracked_key =
+            StateKey::resource_typed::<AccountResource>(&untracked_account).unwrap();
+        assert!(!partial_state_filter.is_tracked(&untracked_key));
+        assert!(!partial_state_filter
+            .is_tracked_prefix(&StateKeyPrefix::from(untracked_account))
+            .unwrap());
+
+        // Verify that only the items of the tracked table are tracked
+        let table_item_key = StateKey::table_item(&tracked_table_handle, &[0, 1, 2]);
+        assert!(partial_state_filter.is_tracked(&table_item_key));
+        let table_item_key = StateKey::table_item(&TableHandle(tracked_account), &[0, 1, 2]);
+        assert!(!partial_state_filter.is_tracked(&table_item_key));
+
+        // Verify that raw keys are not tracked
+        assert!(!partial_state_filter.is_tracked(&StateKey::raw(&[0, 1, 2])));
+    }
+}
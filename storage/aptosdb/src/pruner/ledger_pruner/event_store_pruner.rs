@@ -3,7 +3,10 @@
 
 use crate::{
     ledger_db::LedgerDb,
-    pruner::{db_sub_pruner::DBSubPruner, pruner_utils::get_or_initialize_subpruner_progress},
+    pruner::{
+        db_sub_pruner::DBSubPruner,
+        pruner_utils::{get_ledger_ranges_to_prune, get_or_initialize_subpruner_progress},
+    },
     schema::db_metadata::{DbMetadataKey, DbMetadataSchema, DbMetadataValue},
 };
 use aptos_db_indexer::db_indexer::InternalIndexerDB;
@@ -21,6 +24,8 @@ use std::sync::Arc;
 pub struct EventStorePruner {
     ledger_db: Arc<LedgerDb>,
     internal_indexer_db: Option<InternalIndexerDB>,
+    /// True iff ledger retention rules are configured, i.e., retained versions must be kept.
+    retention_enabled: bool,
 }
 
 impl EventStorePruner {
@@ -44,20 +49,35 @@ impl DBSubPruner for EventStorePruner {
         let batch = SchemaBatch::new();
         let mut indexer_batch = None;
 
-        let indices_batch = if let Some(indexer_db) = self.indexer_db() {
-            if indexer_db.event_enabled() {
+        let (indices_batch, type_indices_batch) = if let Some(indexer_db) = self.indexer_db() {
+            if indexer_db.event_enabled() || indexer_db.event_by_type_enabled() {
                 indexer_batch = Some(SchemaBatch::new());
             }
-            indexer_batch.as_ref()
+            (
+                indexer_batch
+                    .as_ref()
+                    .filter(|_| indexer_db.event_enabled()),
+                indexer_batch
+                    .as_ref()
+                    .filter(|_| indexer_db.event_by_type_enabled()),
+            )
         } else {
-            Some(&batch)
+            (Some(&batch), None)
         };
-        self.ledger_db.event_db().prune_events(
+        for range in get_ledger_ranges_to_prune(
+            &self.ledger_db,
             current_progress,
             target_version,
-            &batch,
-            indices_batch,
-        )?;
+            self.retention_enabled,
+        )? {
+            self.ledger_db.event_db().prune_events(
+                range.start,
+                range.end,
+                &batch,
+                indices_batch,
+                type_indices_batch,
+            )?;
+        }
         batch.put::<DbMetadataSchema>(
             &DbMetadataKey::EventPrunerProgress,
             &DbMetadataValue::Version(target_version),
@@ -81,6 +101,7 @@ impl EventStorePruner {
         ledger_db: Arc<LedgerDb>,
         metadata_progress: Version,
         internal_indexer_db: Option<InternalIndexerDB>,
+        retention_enabled: bool,
     ) -> Result<Self> {
         let progress = get_or_initialize_subpruner_progress(
             ledger_db.event_db_raw(),
@@ -91,6 +112,7 @@ impl EventStorePruner {
         let myself = EventStorePruner {
             ledger_db,
             internal_indexer_db,
+            retention_enabled,
         };
 
         info!(
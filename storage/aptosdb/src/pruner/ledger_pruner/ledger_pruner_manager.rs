@@ -3,6 +3,7 @@
 
 use crate::{
     ledger_db::LedgerDb,
+    ledger_retention::LedgerRetentionFilter,
     metrics::{PRUNER_BATCH_SIZE, PRUNER_VERSIONS, PRUNER_WINDOW},
     pruner::{
         ledger_pruner::LedgerPruner, pruner_manager::PrunerManager, pruner_utils,
@@ -88,6 +89,18 @@ impl PrunerManager for LedgerPrunerManager {
         self.ledger_db.write_pruner_progress(min_readable_version)
     }
 
+    fn refresh_min_readable_version(&self) -> Result<()> {
+        let min_readable_version = pruner_utils::get_ledger_pruner_progress(&self.ledger_db)?;
+        self.min_readable_version
+            .store(min_readable_version, Ordering::SeqCst);
+
+        PRUNER_VERSIONS
+            .with_label_values(&["ledger_pruner", "min_readable"])
+            .set(min_readable_version as i64);
+
+        Ok(())
+    }
+
     fn is_pruning_pending(&self) -> bool {
         self.pruner_worker
             .as_ref()
@@ -109,12 +122,14 @@ impl LedgerPrunerManager {
         ledger_db: Arc<LedgerDb>,
         ledger_pruner_config: LedgerPrunerConfig,
         internal_indexer_db: Option<InternalIndexerDB>,
+        ledger_retention_filter: Option<LedgerRetentionFilter>,
     ) -> Self {
         let pruner_worker = if ledger_pruner_config.enable {
             Some(Self::init_pruner(
                 Arc::clone(&ledger_db),
                 ledger_pruner_config,
                 internal_indexer_db,
+                ledger_retention_filter,
             ))
         } else {
             None
@@ -142,9 +157,10 @@ impl LedgerPrunerManager {
         ledger_db: Arc<LedgerDb>,
         ledger_pruner_config: LedgerPrunerConfig,
         internal_indexer_db: Option<InternalIndexerDB>,
+        ledger_retention_filter: Option<LedgerRetentionFilter>,
     ) -> PrunerWorker {
         let pruner = Arc::new(
-            LedgerPruner::new(ledger_db, internal_indexer_db)
+            LedgerPruner::new(ledger_db, internal_indexer_db, ledger_retention_filter)
                 .expect("Failed to create ledger pruner."),
         );
 
@@ -11,7 +11,8 @@ mod transaction_pruner;
 mod write_set_pruner;
 
 use crate::{
-    ledger_db::LedgerDb,
+    ledger_db::{ledger_metadata_db::LedgerMetadataDb, LedgerDb},
+    ledger_retention::LedgerRetentionFilter,
     metrics::PRUNER_VERSIONS,
     pruner::{
         db_pruner::DBPruner,
@@ -30,6 +31,7 @@ use anyhow::anyhow;
 use aptos_db_indexer::db_indexer::InternalIndexerDB;
 use aptos_experimental_runtimes::thread_manager::THREAD_MANAGER;
 use aptos_logger::info;
+use aptos_schemadb::SchemaBatch;
 use aptos_storage_interface::Result;
 use aptos_types::transaction::{AtomicVersion, Version};
 use rayon::prelude::*;
@@ -47,6 +49,11 @@ pub(crate) struct LedgerPruner {
 
     progress: AtomicVersion,
 
+    ledger_db: Arc<LedgerDb>,
+
+    /// Identifies the transactions kept by the pruner, None if no retention rule is configured.
+    ledger_retention_filter: Option<LedgerRetentionFilter>,
+
     ledger_metadata_pruner: Box<LedgerMetadataPruner>,
 
     sub_pruners: Vec<Box<dyn DBSubPruner + Send + Sync>>,
@@ -70,6 +77,15 @@ impl DBPruner for LedgerPruner {
                 target_version = current_batch_target_version,
                 "Pruning ledger data."
             );
+            // The retained versions are persisted before anything is pruned, so that all sub
+            // pruners (including the ones catching up on startup) keep the same versions.
+            if let Some(ledger_retention_filter) = &self.ledger_retention_filter {
+                self.save_retained_versions(
+                    ledger_retention_filter,
+                    progress,
+                    current_batch_target_version,
+                )?;
+            }
             self.ledger_metadata_pruner
                 .prune(progress, current_batch_target_version)?;
 
@@ -116,6 +132,7 @@ impl LedgerPruner {
     pub fn new(
         ledger_db: Arc<LedgerDb>,
         internal_indexer_db: Option<InternalIndexerDB>,
+        ledger_retention_filter: Option<LedgerRetentionFilter>,
     ) -> Result<Self> {
         info!(name = LEDGER_PRUNER_NAME, "Initializing...");
 
@@ -132,40 +149,49 @@ impl LedgerPruner {
         );
 
         let transaction_store = Arc::new(TransactionStore::new(Arc::clone(&ledger_db)));
+        let retention_enabled = ledger_retention_filter.is_some();
 
         let event_store_pruner = Box::new(EventStorePruner::new(
             Arc::clone(&ledger_db),
             metadata_progress,
             internal_indexer_db.clone(),
+            retention_enabled,
         )?);
         let transaction_accumulator_pruner = Box::new(TransactionAccumulatorPruner::new(
             Arc::clone(&ledger_db),
             metadata_progress,
+            retention_enabled,
         )?);
 
         let transaction_auxiliary_data_pruner = Box::new(TransactionAuxiliaryDataPruner::new(
             Arc::clone(&ledger_db),
             metadata_progress,
+            retention_enabled,
         )?);
 
         let transaction_info_pruner = Box::new(TransactionInfoPruner::new(
             Arc::clone(&ledger_db),
             metadata_progress,
+            retention_enabled,
         )?);
         let transaction_pruner = Box::new(TransactionPruner::new(
             Arc::clone(&transaction_store),
             Arc::clone(&ledger_db),
             metadata_progress,
             internal_indexer_db,
+            retention_enabled,
         )?);
         let write_set_pruner = Box::new(WriteSetPruner::new(
             Arc::clone(&ledger_db),
             metadata_progress,
+            retention_enabled,
         )?);
 
         let pruner = LedgerPruner {
             target_version: AtomicVersion::new(metadata_progress),
             progress: AtomicVersion::new(metadata_progress),
+            ledger_db,
+            ledger_retention_filter,
             ledger_metadata_pruner,
             sub_pruners: vec![
                 event_store_pruner,
@@ -185,4 +211,25 @@ impl LedgerPruner {
 
         Ok(pruner)
     }
+
+    /// Persists the versions in [begin, end) that are retained by the ledger retention rules.
+    fn save_retained_versions(
+        &self,
+        ledger_retention_filter: &LedgerRetentionFilter,
+        begin: Version,
+        end: Version,
+    ) -> Result<()> {
+        let batch = SchemaBatch::new();
+        let txn_iter = self
+            .ledger_db
+            .transaction_db()
+            .get_transaction_iter(begin, (end - begin) as usize)?;
+        for (version, txn) in (begin..end).zip(txn_iter) {
+            let events = self.ledger_db.event_db().get_events_by_version(version)?;
+            if ledger_retention_filter.is_retained(&txn?, &events) {
+                LedgerMetadataDb::put_retained_version(version, &batch)?;
+            }
+        }
+        self.ledger_db.metadata_db().write_schemas(batch)
+    }
 }
@@ -15,6 +15,8 @@ use std::sync::Arc;
 #[derive(Debug)]
 pub struct TransactionAccumulatorPruner {
     ledger_db: Arc<LedgerDb>,
+    /// True iff ledger retention rules are configured, i.e., retained versions must be kept.
+    retention_enabled: bool,
 }
 
 impl DBSubPruner for TransactionAccumulatorPruner {
@@ -24,7 +26,12 @@ impl DBSubPruner for TransactionAccumulatorPruner {
 
     fn prune(&self, current_progress: Version, target_version: Version) -> Result<()> {
         let batch = SchemaBatch::new();
-        TransactionAccumulatorDb::prune(current_progress, target_version, &batch)?;
+        TransactionAccumulatorDb::prune(
+            current_progress,
+            target_version,
+            &batch,
+            self.retention_enabled.then(|| self.ledger_db.metadata_db()),
+        )?;
         batch.put::<DbMetadataSchema>(
             &DbMetadataKey::TransactionAccumulatorPrunerProgress,
             &DbMetadataValue::Version(target_version),
@@ -39,6 +46,7 @@ impl TransactionAccumulatorPruner {
     pub(in crate::pruner) fn new(
         ledger_db: Arc<LedgerDb>,
         metadata_progress: Version,
+        retention_enabled: bool,
     ) -> Result<Self> {
         let progress = get_or_initialize_subpruner_progress(
             ledger_db.transaction_accumulator_db_raw(),
@@ -46,7 +54,10 @@ impl TransactionAccumulatorPruner {
             metadata_progress,
         )?;
 
-        let myself = TransactionAccumulatorPruner { ledger_db };
+        let myself = TransactionAccumulatorPruner {
+            ledger_db,
+            retention_enabled,
+        };
 
         info!(
             progress = progress,
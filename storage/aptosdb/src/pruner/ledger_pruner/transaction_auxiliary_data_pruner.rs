@@ -3,7 +3,10 @@
 
 use crate::{
     ledger_db::{transaction_auxiliary_data_db::TransactionAuxiliaryDataDb, LedgerDb},
-    pruner::{db_sub_pruner::DBSubPruner, pruner_utils::get_or_initialize_subpruner_progress},
+    pruner::{
+        db_sub_pruner::DBSubPruner,
+        pruner_utils::{get_ledger_ranges_to_prune, get_or_initialize_subpruner_progress},
+    },
     schema::db_metadata::{DbMetadataKey, DbMetadataSchema, DbMetadataValue},
 };
 use aptos_logger::info;
@@ -15,6 +18,8 @@ use std::sync::Arc;
 #[derive(Debug)]
 pub struct TransactionAuxiliaryDataPruner {
     ledger_db: Arc<LedgerDb>,
+    /// True iff ledger retention rules are configured, i.e., retained versions must be kept.
+    retention_enabled: bool,
 }
 
 impl DBSubPruner for TransactionAuxiliaryDataPruner {
@@ -24,7 +29,14 @@ impl DBSubPruner for TransactionAuxiliaryDataPruner {
 
     fn prune(&self, current_progress: Version, target_version: Version) -> Result<()> {
         let batch = SchemaBatch::new();
-        TransactionAuxiliaryDataDb::prune(current_progress, target_version, &batch)?;
+        for range in get_ledger_ranges_to_prune(
+            &self.ledger_db,
+            current_progress,
+            target_version,
+            self.retention_enabled,
+        )? {
+            TransactionAuxiliaryDataDb::prune(range.start, range.end, &batch)?;
+        }
         batch.put::<DbMetadataSchema>(
             &DbMetadataKey::TransactionAuxiliaryDataPrunerProgress,
             &DbMetadataValue::Version(target_version),
@@ -39,6 +51,7 @@ impl TransactionAuxiliaryDataPruner {
     pub(in crate::pruner) fn new(
         ledger_db: Arc<LedgerDb>,
         metadata_progress: Version,
+        retention_enabled: bool,
     ) -> Result<Self> {
         let progress = get_or_initialize_subpruner_progress(
             ledger_db.transaction_auxiliary_data_db_raw(),
@@ -46,7 +59,10 @@ impl TransactionAuxiliaryDataPruner {
             metadata_progress,
         )?;
 
-        let myself = TransactionAuxiliaryDataPruner { ledger_db };
+        let myself = TransactionAuxiliaryDataPruner {
+            ledger_db,
+            retention_enabled,
+        };
 
         info!(
             progress = progress,
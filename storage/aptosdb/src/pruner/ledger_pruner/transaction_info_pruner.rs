@@ -3,7 +3,10 @@
 
 use crate::{
     ledger_db::{transaction_info_db::TransactionInfoDb, LedgerDb},
-    pruner::{db_sub_pruner::DBSubPruner, pruner_utils::get_or_initialize_subpruner_progress},
+    pruner::{
+        db_sub_pruner::DBSubPruner,
+        pruner_utils::{get_ledger_ranges_to_prune, get_or_initialize_subpruner_progress},
+    },
     schema::db_metadata::{DbMetadataKey, DbMetadataSchema, DbMetadataValue},
 };
 use aptos_logger::info;
@@ -15,6 +18,8 @@ use std::sync::Arc;
 #[derive(Debug)]
 pub struct TransactionInfoPruner {
     ledger_db: Arc<LedgerDb>,
+    /// True iff ledger retention rules are configured, i.e., retained versions must be kept.
+    retention_enabled: bool,
 }
 
 impl DBSubPruner for TransactionInfoPruner {
@@ -24,7 +29,14 @@ impl DBSubPruner for TransactionInfoPruner {
 
     fn prune(&self, current_progress: Version, target_version: Version) -> Result<()> {
         let batch = SchemaBatch::new();
-        TransactionInfoDb::prune(current_progress, target_version, &batch)?;
+        for range in get_ledger_ranges_to_prune(
+            &self.ledger_db,
+            current_progress,
+            target_version,
+            self.retention_enabled,
+        )? {
+            TransactionInfoDb::prune(range.start, range.end, &batch)?;
+        }
         batch.put::<DbMetadataSchema>(
             &DbMetadataKey::TransactionInfoPrunerProgress,
             &DbMetadataValue::Version(target_version),
@@ -37,6 +49,7 @@ impl TransactionInfoPruner {
     pub(in crate::pruner) fn new(
         ledger_db: Arc<LedgerDb>,
         metadata_progress: Version,
+        retention_enabled: bool,
     ) -> Result<Self> {
         let progress = get_or_initialize_subpruner_progress(
             ledger_db.transaction_info_db_raw(),
@@ -44,7 +57,10 @@ impl TransactionInfoPruner {
             metadata_progress,
         )?;
 
-        let myself = TransactionInfoPruner { ledger_db };
+        let myself = TransactionInfoPruner {
+            ledger_db,
+            retention_enabled,
+        };
 
         info!(
             progress = progress,
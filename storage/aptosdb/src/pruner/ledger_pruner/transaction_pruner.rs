@@ -3,7 +3,10 @@
 
 use crate::{
     ledger_db::LedgerDb,
-    pruner::{db_sub_pruner::DBSubPruner, pruner_utils::get_or_initialize_subpruner_progress},
+    pruner::{
+        db_sub_pruner::DBSubPruner,
+        pruner_utils::{get_ledger_ranges_to_prune, get_or_initialize_subpruner_progress},
+    },
     schema::{
         db_metadata::{DbMetadataKey, DbMetadataSchema, DbMetadataValue},
         transaction::TransactionSchema,
@@ -26,6 +29,8 @@ pub struct TransactionPruner {
     transaction_store: Arc<TransactionStore>,
     ledger_db: Arc<LedgerDb>,
     internal_indexer_db: Option<InternalIndexerDB>,
+    /// True iff ledger retention rules are configured, i.e., retained versions must be kept.
+    retention_enabled: bool,
 }
 
 impl DBSubPruner for TransactionPruner {
@@ -35,16 +40,22 @@ impl DBSubPruner for TransactionPruner {
 
     fn prune(&self, current_progress: Version, target_version: Version) -> Result<()> {
         let batch = SchemaBatch::new();
-        let candidate_transactions =
-            self.get_pruning_candidate_transactions(current_progress, target_version)?;
+        let mut candidate_transactions = Vec::new();
+        for range in get_ledger_ranges_to_prune(
+            &self.ledger_db,
+            current_progress,
+            target_version,
+            self.retention_enabled,
+        )? {
+            candidate_transactions
+                .extend(self.get_pruning_candidate_transactions(range.start, range.end)?);
+            self.ledger_db
+                .transaction_db()
+                .prune_transactions(range.start, range.end, &batch)?;
+        }
         self.ledger_db
             .transaction_db()
             .prune_transaction_by_hash_indices(&candidate_transactions, &batch)?;
-        self.ledger_db.transaction_db().prune_transactions(
-            current_progress,
-            target_version,
-            &batch,
-        )?;
         batch.put::<DbMetadataSchema>(
             &DbMetadataKey::TransactionPrunerProgress,
             &DbMetadataValue::Version(target_version),
@@ -74,6 +85,7 @@ impl TransactionPruner {
         ledger_db: Arc<LedgerDb>,
         metadata_progress: Version,
         internal_indexer_db: Option<InternalIndexerDB>,
+        retention_enabled: bool,
     ) -> Result<Self> {
         let progress = get_or_initialize_subpruner_progress(
             ledger_db.transaction_db_raw(),
@@ -85,6 +97,7 @@ impl TransactionPruner {
             transaction_store,
             ledger_db,
             internal_indexer_db,
+            retention_enabled,
         };
 
         info!(
@@ -3,7 +3,10 @@
 
 use crate::{
     ledger_db::{write_set_db::WriteSetDb, LedgerDb},
-    pruner::{db_sub_pruner::DBSubPruner, pruner_utils::get_or_initialize_subpruner_progress},
+    pruner::{
+        db_sub_pruner::DBSubPruner,
+        pruner_utils::{get_ledger_ranges_to_prune, get_or_initialize_subpruner_progress},
+    },
     schema::db_metadata::{DbMetadataKey, DbMetadataSchema, DbMetadataValue},
 };
 use aptos_logger::info;
@@ -15,6 +18,8 @@ use std::sync::Arc;
 #[derive(Debug)]
 pub struct WriteSetPruner {
     ledger_db: Arc<LedgerDb>,
+    /// True iff ledger retention rules are configured, i.e., retained versions must be kept.
+    retention_enabled: bool,
 }
 
 impl DBSubPruner for WriteSetPruner {
@@ -24,7 +29,14 @@ impl DBSubPruner for WriteSetPruner {
 
     fn prune(&self, current_progress: Version, target_version: Version) -> Result<()> {
         let batch = SchemaBatch::new();
-        WriteSetDb::prune(current_progress, target_version, &batch)?;
+        for range in get_ledger_ranges_to_prune(
+            &self.ledger_db,
+            current_progress,
+            target_version,
+            self.retention_enabled,
+        )? {
+            WriteSetDb::prune(range.start, range.end, &batch)?;
+        }
         batch.put::<DbMetadataSchema>(
             &DbMetadataKey::WriteSetPrunerProgress,
             &DbMetadataValue::Version(target_version),
@@ -37,6 +49,7 @@ impl WriteSetPruner {
     pub(in crate::pruner) fn new(
         ledger_db: Arc<LedgerDb>,
         metadata_progress: Version,
+        retention_enabled: bool,
     ) -> Result<Self> {
         let progress = get_or_initialize_subpruner_progress(
             ledger_db.write_set_db_raw(),
@@ -44,7 +57,10 @@ impl WriteSetPruner {
             metadata_progress,
         )?;
 
-        let myself = WriteSetPruner { ledger_db };
+        let myself = WriteSetPruner {
+            ledger_db,
+            retention_enabled,
+        };
 
         info!(
             progress = progress,
@@ -6,7 +6,7 @@ mod db_pruner;
 mod db_sub_pruner;
 mod ledger_pruner;
 mod pruner_manager;
-mod pruner_utils;
+pub(crate) mod pruner_utils;
 mod pruner_worker;
 mod state_kv_pruner;
 mod state_merkle_pruner;
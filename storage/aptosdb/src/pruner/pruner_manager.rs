@@ -34,6 +34,10 @@ pub trait PrunerManager: Sync {
     // in memory progress.
     fn save_min_readable_version(&self, min_readable_version: Version) -> Result<()>;
 
+    /// Reloads the in memory progress from db, used when the db is pruned by another instance
+    /// (i.e. when following a primary instance as a secondary).
+    fn refresh_min_readable_version(&self) -> Result<()>;
+
     #[allow(unused)]
     fn is_pruning_pending(&self) -> bool;
 
@@ -15,11 +15,46 @@ use anyhow::Result;
 use aptos_jellyfish_merkle::StaleNodeIndex;
 use aptos_schemadb::{schema::KeyCodec, DB};
 use aptos_types::transaction::Version;
+use std::ops::Range;
 
 pub(crate) fn get_ledger_pruner_progress(ledger_db: &LedgerDb) -> Result<Version> {
     Ok(ledger_db.metadata_db().get_pruner_progress().unwrap_or(0))
 }
 
+/// Splits the version range [begin, end) into the ranges to be pruned by the ledger sub pruners,
+/// i.e. around the versions retained by the ledger retention rules. If no retention rule is
+/// configured, the whole range is pruned without looking up the retained versions.
+pub(crate) fn get_ledger_ranges_to_prune(
+    ledger_db: &LedgerDb,
+    begin: Version,
+    end: Version,
+    retention_enabled: bool,
+) -> Result<Vec<Range<Version>>> {
+    if !retention_enabled {
+        return Ok(if begin < end {
+            vec![begin..end]
+        } else {
+            vec![]
+        });
+    }
+
+    let mut ranges = Vec::new();
+    let mut range_begin = begin;
+    for retained_version in ledger_db
+        .metadata_db()
+        .get_retained_versions_in_range(begin..end)?
+    {
+        if range_begin < retained_version {
+            ranges.push(range_begin..retained_version);
+        }
+        range_begin = retained_version + 1;
+    }
+    if range_begin < end {
+        ranges.push(range_begin..end);
+    }
+    Ok(ranges)
+}
+
 pub(crate) fn get_state_kv_pruner_progress(state_kv_db: &StateKvDb) -> Result<Version> {
     Ok(get_progress(
         state_kv_db.metadata_db(),
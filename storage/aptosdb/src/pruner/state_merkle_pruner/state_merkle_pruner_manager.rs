@@ -83,6 +83,19 @@ where
             .write_pruner_progress(min_readable_version)
     }
 
+    fn refresh_min_readable_version(&self) -> Result<()> {
+        let min_readable_version =
+            pruner_utils::get_state_merkle_pruner_progress::<S>(&self.state_merkle_db)?;
+        self.min_readable_version
+            .store(min_readable_version, Ordering::SeqCst);
+
+        PRUNER_VERSIONS
+            .with_label_values(&[S::name(), "min_readable"])
+            .set(min_readable_version as i64);
+
+        Ok(())
+    }
+
     fn is_pruning_pending(&self) -> bool {
         self.pruner_worker
             .as_ref()
@@ -11,13 +11,16 @@ use crate::{
         state_value::StateValueSchema,
         state_value_by_key_hash::StateValueByKeyHashSchema,
     },
-    utils::truncation_helper::{get_state_kv_commit_progress, truncate_state_kv_db_shards},
+    utils::{
+        open_rocksdb,
+        truncation_helper::{get_state_kv_commit_progress, truncate_state_kv_db_shards},
+        DbOpenMode,
+    },
 };
 use aptos_config::config::{RocksdbConfig, RocksdbConfigs, StorageDirPaths};
 use aptos_crypto::hash::CryptoHash;
 use aptos_experimental_runtimes::thread_manager::THREAD_MANAGER;
 use aptos_logger::prelude::info;
-use aptos_rocksdb_options::gen_rocksdb_options;
 use aptos_schemadb::{ReadOptions, SchemaBatch, DB};
 use aptos_storage_interface::{state_store::NUM_STATE_SHARDS, Result};
 use aptos_types::{
@@ -44,7 +47,7 @@ impl StateKvDb {
     pub(crate) fn new(
         db_paths: &StorageDirPaths,
         rocksdb_configs: RocksdbConfigs,
-        readonly: bool,
+        open_mode: DbOpenMode,
         ledger_db: Arc<DB>,
     ) -> Result<Self> {
         let sharding = rocksdb_configs.enable_storage_sharding;
@@ -57,13 +60,13 @@ impl StateKvDb {
             });
         }
 
-        Self::open_sharded(db_paths, rocksdb_configs.state_kv_db_config, readonly)
+        Self::open_sharded(db_paths, rocksdb_configs.state_kv_db_config, open_mode)
     }
 
     pub(crate) fn open_sharded(
         db_paths: &StorageDirPaths,
         state_kv_db_config: RocksdbConfig,
-        readonly: bool,
+        open_mode: DbOpenMode,
     ) -> Result<Self> {
         let state_kv_metadata_db_path =
             Self::metadata_db_path(db_paths.state_kv_db_metadata_root_path());
@@ -72,7 +75,7 @@ impl StateKvDb {
             state_kv_metadata_db_path.clone(),
             STATE_KV_METADATA_DB_NAME,
             &state_kv_db_config,
-            readonly,
+            open_mode,
         )?);
 
         info!(
@@ -88,7 +91,7 @@ impl StateKvDb {
                     shard_root_path,
                     shard_id as u8,
                     &state_kv_db_config,
-                    readonly,
+                    open_mode,
                 )
                 .unwrap_or_else(|e| panic!("Failed to open state kv db shard {shard_id}: {e:?}."));
                 Arc::new(db)
@@ -103,7 +106,7 @@ impl StateKvDb {
             enabled_sharding: true,
         };
 
-        if !readonly {
+        if open_mode.is_read_write() {
             if let Some(overall_kv_commit_progress) = get_state_kv_commit_progress(&state_kv_db)? {
                 truncate_state_kv_db_shards(&state_kv_db, overall_kv_commit_progress)?;
             }
@@ -175,8 +178,14 @@ impl StateKvDb {
         let state_kv_db = Self::open_sharded(
             &StorageDirPaths::from_path(db_root_path),
             RocksdbConfig::default(),
-            false,
+            DbOpenMode::ReadWrite,
         )?;
+        state_kv_db.checkpoint_to(cp_root_path)
+    }
+
+    /// Creates a checkpoint of the (sharded) state kv DBs under `cp_root_path`, can be used on a
+    /// live DB.
+    pub(crate) fn checkpoint_to(&self, cp_root_path: impl AsRef<Path>) -> Result<()> {
         let cp_state_kv_db_path = cp_root_path.as_ref().join(STATE_KV_DB_FOLDER_NAME);
 
         info!("Creating state_kv_db checkpoint at: {cp_state_kv_db_path:?}");
@@ -184,19 +193,33 @@ impl StateKvDb {
         std::fs::remove_dir_all(&cp_state_kv_db_path).unwrap_or(());
         std::fs::create_dir_all(&cp_state_kv_db_path).unwrap_or(());
 
-        state_kv_db
-            .metadata_db()
+        self.metadata_db()
             .create_checkpoint(Self::metadata_db_path(cp_root_path.as_ref()))?;
 
         for shard_id in 0..NUM_STATE_SHARDS {
-            state_kv_db
-                .db_shard(shard_id as u8)
+            self.db_shard(shard_id as u8)
                 .create_checkpoint(Self::db_shard_path(cp_root_path.as_ref(), shard_id as u8))?;
         }
 
         Ok(())
     }
 
+    /// Catches up with the primary instance when opened as a secondary. The shards are caught up
+    /// before the metadata DB, so the commit progress never runs ahead of the data.
+    pub(crate) fn try_catch_up_with_primary(&self) -> Result<()> {
+        if !self.enabled_sharding {
+            // Shares the DB with the ledger metadata DB, which is caught up by the `LedgerDb`.
+            return Ok(());
+        }
+
+        for shard_id in 0..NUM_STATE_SHARDS {
+            self.db_shard(shard_id as u8).try_catch_up_with_primary()?;
+        }
+        self.metadata_db().try_catch_up_with_primary()?;
+
+        Ok(())
+    }
+
     pub(crate) fn metadata_db(&self) -> &DB {
         &self.state_kv_metadata_db
     }
@@ -246,14 +269,14 @@ impl StateKvDb {
         db_root_path: P,
         shard_id: u8,
         state_kv_db_config: &RocksdbConfig,
-        readonly: bool,
+        open_mode: DbOpenMode,
     ) -> Result<DB> {
         let db_name = format!("state_kv_db_shard_{}", shard_id);
         Self::open_db(
             Self::db_shard_path(db_root_path, shard_id),
             &db_name,
             state_kv_db_config,
-            readonly,
+            open_mode,
         )
     }
 
@@ -261,23 +284,15 @@ impl StateKvDb {
         path: PathBuf,
         name: &str,
         state_kv_db_config: &RocksdbConfig,
-        readonly: bool,
+        open_mode: DbOpenMode,
     ) -> Result<DB> {
-        Ok(if readonly {
-            DB::open_cf_readonly(
-                &gen_rocksdb_options(state_kv_db_config, true),
-                path,
-                name,
-                gen_state_kv_shard_cfds(state_kv_db_config),
-            )?
-        } else {
-            DB::open_cf(
-                &gen_rocksdb_options(state_kv_db_config, false),
-                path,
-                name,
-                gen_state_kv_shard_cfds(state_kv_db_config),
-            )?
-        })
+        open_rocksdb(
+            path,
+            name,
+            state_kv_db_config,
+            gen_state_kv_shard_cfds(state_kv_db_config),
+            open_mode,
+        )
     }
 
     fn db_shard_path<P: AsRef<Path>>(db_root_path: P, shard_id: u8) -> PathBuf {
@@ -11,7 +11,11 @@ use crate::{
         stale_node_index::StaleNodeIndexSchema,
         stale_node_index_cross_epoch::StaleNodeIndexCrossEpochSchema,
     },
-    utils::truncation_helper::{get_state_merkle_commit_progress, truncate_state_merkle_db_shards},
+    utils::{
+        open_rocksdb,
+        truncation_helper::{get_state_merkle_commit_progress, truncate_state_merkle_db_shards},
+        DbOpenMode,
+    },
     versioned_node_cache::VersionedNodeCache,
 };
 use aptos_config::config::{RocksdbConfig, RocksdbConfigs, StorageDirPaths};
@@ -21,7 +25,6 @@ use aptos_jellyfish_merkle::{
     node_type::NodeKey, JellyfishMerkleTree, TreeReader, TreeUpdateBatch, TreeWriter,
 };
 use aptos_logger::prelude::*;
-use aptos_rocksdb_options::gen_rocksdb_options;
 use aptos_schemadb::{SchemaBatch, DB};
 #[cfg(test)]
 use aptos_scratchpad::get_state_shard_id;
@@ -30,7 +33,7 @@ use aptos_storage_interface::{
 };
 use aptos_types::{
     nibble::{nibble_path::NibblePath, ROOT_NIBBLE_HEIGHT},
-    proof::{SparseMerkleProofExt, SparseMerkleRangeProof},
+    proof::{SparseMerkleMultiProof, SparseMerkleProofExt, SparseMerkleRangeProof},
     state_store::state_key::StateKey,
     transaction::Version,
 };
@@ -68,7 +71,7 @@ impl StateMerkleDb {
     pub(crate) fn new(
         db_paths: &StorageDirPaths,
         rocksdb_configs: RocksdbConfigs,
-        readonly: bool,
+        open_mode: DbOpenMode,
         max_nodes_per_lru_cache_shard: usize,
     ) -> Result<Self> {
         let sharding = rocksdb_configs.enable_storage_sharding;
@@ -89,7 +92,7 @@ impl StateMerkleDb {
                 state_merkle_db_path,
                 STATE_MERKLE_DB_NAME,
                 &state_merkle_db_config,
-                readonly,
+                open_mode,
             )?);
             return Ok(Self {
                 state_merkle_metadata_db: Arc::clone(&db),
@@ -104,7 +107,7 @@ impl StateMerkleDb {
         Self::open(
             db_paths,
             state_merkle_db_config,
-            readonly,
+            open_mode,
             enable_cache,
             version_caches,
             lru_cache,
@@ -169,9 +172,17 @@ impl StateMerkleDb {
         let state_merkle_db = Self::new(
             &StorageDirPaths::from_path(db_root_path),
             rocksdb_configs,
-            /*readonly=*/ false,
+            DbOpenMode::ReadWrite,
             /*max_nodes_per_lru_cache_shard=*/ 0,
         )?;
+        state_merkle_db.checkpoint_to(cp_root_path)
+    }
+
+    /// Creates a checkpoint of the state merkle DBs under `cp_root_path`, can be used on a live
+    /// DB. The metadata DB (holding the top levels of the trees) is checkpointed before the
+    /// shards, so every root in the checkpoint has all its nodes available.
+    pub(crate) fn checkpoint_to(&self, cp_root_path: impl AsRef<Path>) -> Result<()> {
+        let sharding = self.enable_sharding;
         let cp_state_merkle_db_path = cp_root_path.as_ref().join(STATE_MERKLE_DB_FOLDER_NAME);
 
         info!("Creating state_merkle_db checkpoint at: {cp_state_merkle_db_path:?}");
@@ -181,14 +192,12 @@ impl StateMerkleDb {
             std::fs::create_dir_all(&cp_state_merkle_db_path).unwrap_or(());
         }
 
-        state_merkle_db
-            .metadata_db()
+        self.metadata_db()
             .create_checkpoint(Self::metadata_db_path(cp_root_path.as_ref(), sharding))?;
 
         if sharding {
             for shard_id in 0..NUM_STATE_SHARDS {
-                state_merkle_db
-                    .db_shard(shard_id as u8)
+                self.db_shard(shard_id as u8)
                     .create_checkpoint(Self::db_shard_path(
                         cp_root_path.as_ref(),
                         shard_id as u8,
@@ -199,6 +208,20 @@ impl StateMerkleDb {
         Ok(())
     }
 
+    /// Catches up with the primary instance when opened as a secondary. The shards are caught up
+    /// before the metadata DB (holding the top levels of the trees), so every visible root has all
+    /// its nodes available.
+    pub(crate) fn try_catch_up_with_primary(&self) -> Result<()> {
+        if self.enable_sharding {
+            for shard_id in 0..NUM_STATE_SHARDS {
+                self.db_shard(shard_id as u8).try_catch_up_with_primary()?;
+            }
+        }
+        self.metadata_db().try_catch_up_with_primary()?;
+
+        Ok(())
+    }
+
     pub(crate) fn metadata_db(&self) -> &DB {
         &self.state_merkle_metadata_db
     }
@@ -252,6 +275,19 @@ impl StateMerkleDb {
             .map_err(Into::into)
     }
 
+    pub fn get_with_multi_proof(
+        &self,
+        keys: &[HashValue],
+        version: Version,
+    ) -> Result<(
+        Vec<Option<(HashValue, (StateKey, Version))>>,
+        SparseMerkleMultiProof,
+    )> {
+        JellyfishMerkleTree::new(self)
+            .get_with_multi_proof(keys, version)
+            .map_err(Into::into)
+    }
+
     pub fn get_range_proof(
         &self,
         rightmost_key: HashValue,
@@ -550,6 +586,13 @@ impl StateMerkleDb {
         }
     }
 
+    /// Reads a node directly from the DB, neither using nor filling the node caches (e.g., for
+    /// background scans that shouldn't evict the nodes read by the hot path).
+    pub(crate) fn get_node_option_uncached(&self, node_key: &NodeKey) -> Result<Option<Node>> {
+        self.db_by_key(node_key)
+            .get::<JellyfishMerkleNodeSchema>(node_key)
+    }
+
     fn db_by_key(&self, node_key: &NodeKey) -> &DB {
         if let Some(shard_id) = node_key.get_shard_id() {
             self.db_shard(shard_id)
@@ -561,7 +604,7 @@ impl StateMerkleDb {
     fn open(
         db_paths: &StorageDirPaths,
         state_merkle_db_config: RocksdbConfig,
-        readonly: bool,
+        open_mode: DbOpenMode,
         enable_cache: bool,
         version_caches: HashMap<Option<u8>, VersionedNodeCache>,
         lru_cache: LruNodeCache,
@@ -575,7 +618,7 @@ impl StateMerkleDb {
             state_merkle_metadata_db_path.clone(),
             STATE_MERKLE_METADATA_DB_NAME,
             &state_merkle_db_config,
-            readonly,
+            open_mode,
         )?);
 
         info!(
@@ -591,7 +634,7 @@ impl StateMerkleDb {
                     shard_root_path,
                     shard_id as u8,
                     &state_merkle_db_config,
-                    readonly,
+                    open_mode,
                 )
                 .unwrap_or_else(|e| {
                     panic!("Failed to open state merkle db shard {shard_id}: {e:?}.")
@@ -611,7 +654,7 @@ impl StateMerkleDb {
             lru_cache,
         };
 
-        if !readonly {
+        if open_mode.is_read_write() {
             if let Some(overall_state_merkle_commit_progress) =
                 get_state_merkle_commit_progress(&state_merkle_db)?
             {
@@ -629,14 +672,14 @@ impl StateMerkleDb {
         db_root_path: P,
         shard_id: u8,
         state_merkle_db_config: &RocksdbConfig,
-        readonly: bool,
+        open_mode: DbOpenMode,
     ) -> Result<DB> {
         let db_name = format!("state_merkle_db_shard_{}", shard_id);
         Self::open_db(
             Self::db_shard_path(db_root_path, shard_id),
             &db_name,
             state_merkle_db_config,
-            readonly,
+            open_mode,
         )
     }
 
@@ -644,23 +687,15 @@ impl StateMerkleDb {
         path: PathBuf,
         name: &str,
         state_merkle_db_config: &RocksdbConfig,
-        readonly: bool,
+        open_mode: DbOpenMode,
     ) -> Result<DB> {
-        Ok(if readonly {
-            DB::open_cf_readonly(
-                &gen_rocksdb_options(state_merkle_db_config, true),
-                path,
-                name,
-                gen_state_merkle_cfds(state_merkle_db_config),
-            )?
-        } else {
-            DB::open_cf(
-                &gen_rocksdb_options(state_merkle_db_config, false),
-                path,
-                name,
-                gen_state_merkle_cfds(state_merkle_db_config),
-            )?
-        })
+        open_rocksdb(
+            path,
+            name,
+            state_merkle_db_config,
+            gen_state_merkle_cfds(state_merkle_db_config),
+            open_mode,
+        )
     }
 
     fn db_shard_path<P: AsRef<Path>>(db_root_path: P, shard_id: u8) -> PathBuf {
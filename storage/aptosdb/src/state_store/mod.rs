@@ -7,6 +7,7 @@
 use crate::{
     ledger_db::LedgerDb,
     metrics::{OTHER_TIMERS_SECONDS, STATE_ITEMS, TOTAL_STATE_BYTES},
+    partial_state::PartialStateFilter,
     pruner::{StateKvPrunerManager, StateMerklePrunerManager},
     schema::{
         db_metadata::{DbMetadataKey, DbMetadataSchema, DbMetadataValue},
@@ -45,6 +46,7 @@ use aptos_db_indexer_schemas::{
     schema::indexer_metadata::InternalIndexerMetadataSchema,
 };
 use aptos_executor::types::in_memory_state_calculator_v2::InMemoryStateCalculatorV2;
+use aptos_executor_types::state_checkpoint_output::StateCheckpointOutput;
 use aptos_experimental_runtimes::thread_manager::THREAD_MANAGER;
 use aptos_infallible::Mutex;
 use aptos_jellyfish_merkle::iterator::JellyfishMerkleIterator;
@@ -66,7 +68,9 @@ use aptos_storage_interface::{
     AptosDbError, DbReader, Result, StateSnapshotReceiver,
 };
 use aptos_types::{
-    proof::{definition::LeafCount, SparseMerkleProofExt, SparseMerkleRangeProof},
+    proof::{
+        definition::LeafCount, SparseMerkleMultiProof, SparseMerkleProofExt, SparseMerkleRangeProof,
+    },
     state_store::{
         state_key::{prefix::StateKeyPrefix, StateKey},
         state_storage_usage::StateStorageUsage,
@@ -129,6 +133,8 @@ pub(crate) struct StateStore {
     persisted_state: Arc<Mutex<PersistedState>>,
     buffered_state_target_items: usize,
     internal_indexer_db: Option<InternalIndexerDB>,
+    /// If set, only the state values of tracked state keys are persisted (i.e., partial state mode)
+    partial_state_filter: Option<PartialStateFilter>,
 }
 
 impl Deref for StateStore {
@@ -283,6 +289,27 @@ impl DbReader for StateStore {
 }
 
 impl StateDb {
+    /// Get the state values with a single multi-proof given the state keys (sorted by
+    /// their hashes) and version
+    pub fn get_state_values_with_multi_proof_by_version(
+        &self,
+        state_keys: &[StateKey],
+        version: Version,
+    ) -> Result<(Vec<Option<StateValue>>, SparseMerkleMultiProof)> {
+        let key_hashes: Vec<_> = state_keys.iter().map(CryptoHash::hash).collect();
+        let (leaf_data, proof) = self
+            .state_merkle_db
+            .get_with_multi_proof(&key_hashes, version)?;
+        let state_values = leaf_data
+            .into_iter()
+            .map(|leaf_data| match leaf_data {
+                Some((_, (key, version))) => Ok(Some(self.expect_value_by_version(&key, version)?)),
+                None => Ok(None),
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok((state_values, proof))
+    }
+
     fn expect_value_by_version(
         &self,
         state_key: &StateKey,
@@ -313,6 +340,7 @@ impl StateStore {
         empty_buffered_state_for_restore: bool,
         skip_usage: bool,
         internal_indexer_db: Option<InternalIndexerDB>,
+        partial_state_filter: Option<PartialStateFilter>,
     ) -> Self {
         if !hack_for_tests && !empty_buffered_state_for_restore {
             Self::sync_commit_progress(
@@ -320,6 +348,7 @@ impl StateStore {
                 Arc::clone(&state_kv_db),
                 Arc::clone(&state_merkle_db),
                 /*crash_if_difference_is_too_large=*/ true,
+                internal_indexer_db.as_ref(),
             );
         }
         let state_db = Arc::new(StateDb {
@@ -360,6 +389,7 @@ impl StateStore {
             current_state,
             persisted_state,
             internal_indexer_db,
+            partial_state_filter,
         }
     }
 
@@ -370,6 +400,7 @@ impl StateStore {
         state_kv_db: Arc<StateKvDb>,
         state_merkle_db: Arc<StateMerkleDb>,
         crash_if_difference_is_too_large: bool,
+        internal_indexer_db: Option<&InternalIndexerDB>,
     ) {
         let ledger_metadata_db = ledger_db.metadata_db();
         if let Some(overall_commit_progress) = ledger_metadata_db
@@ -403,7 +434,7 @@ impl StateStore {
             if crash_if_difference_is_too_large {
                 assert_le!(difference, MAX_COMMIT_PROGRESS_DIFFERENCE);
             }
-            truncate_ledger_db(ledger_db, overall_commit_progress)
+            truncate_ledger_db(ledger_db, overall_commit_progress, internal_indexer_db)
                 .expect("Failed to truncate ledger db.");
 
             // State K/V commit progress isn't (can't be) written atomically with the data,
@@ -500,6 +531,29 @@ impl StateStore {
         Ok(base_version)
     }
 
+    /// Creates the buffered state at the given snapshot, without anything on top of it.
+    fn create_buffered_state_at_snapshot(
+        state_db: &Arc<StateDb>,
+        buffered_state_target_items: usize,
+        snapshot_version: Option<Version>,
+        current_state: Arc<Mutex<CurrentState>>,
+        persisted_state: Arc<Mutex<PersistedState>>,
+    ) -> Result<BufferedState> {
+        let snapshot_root_hash = if let Some(version) = snapshot_version {
+            state_db.state_merkle_db.get_root_hash(version)?
+        } else {
+            *SPARSE_MERKLE_PLACEHOLDER_HASH
+        };
+        let usage = state_db.get_state_storage_usage(snapshot_version)?;
+        Ok(BufferedState::new(
+            state_db,
+            StateDelta::new_at_checkpoint(snapshot_root_hash, usage, snapshot_version),
+            buffered_state_target_items,
+            current_state,
+            persisted_state,
+        ))
+    }
+
     fn create_buffered_state_from_latest_snapshot(
         state_db: &Arc<StateDb>,
         buffered_state_target_items: usize,
@@ -524,26 +578,13 @@ impl StateStore {
             latest_snapshot_version = latest_snapshot_version,
             "Initializing BufferedState."
         );
-        let latest_snapshot_root_hash = if let Some(version) = latest_snapshot_version {
-            state_db
-                .state_merkle_db
-                .get_root_hash(version)
-                .expect("Failed to query latest checkpoint root hash on initialization.")
-        } else {
-            *SPARSE_MERKLE_PLACEHOLDER_HASH
-        };
-        let usage = state_db.get_state_storage_usage(latest_snapshot_version)?;
-        let mut buffered_state = BufferedState::new(
+        let mut buffered_state = Self::create_buffered_state_at_snapshot(
             state_db,
-            StateDelta::new_at_checkpoint(
-                latest_snapshot_root_hash,
-                usage,
-                latest_snapshot_version,
-            ),
             buffered_state_target_items,
+            latest_snapshot_version,
             current_state.clone(),
             persisted_state,
-        );
+        )?;
 
         // In some backup-restore tests we hope to open the db without consistency check.
         if hack_for_tests {
@@ -572,43 +613,15 @@ impl StateStore {
                     num_transactions,
                 );
             }
-            let snapshot = state_db.get_state_snapshot_before(num_transactions)?;
             let current_state_cloned = current_state.lock().get().clone();
-            let speculative_state = current_state_cloned
-                .current
-                .freeze(&current_state_cloned.base);
-            let latest_snapshot_state_view = CachedStateView::new_impl(
-                StateViewId::Miscellaneous,
+            let persisted_smt = current_state_cloned.base.clone();
+            let state_checkpoint_output = Self::calculate_state_after_committed_write_sets(
+                state_db,
+                current_state_cloned,
+                &persisted_smt,
+                snapshot_next_version,
                 num_transactions,
-                snapshot,
-                speculative_state,
-                Arc::new(AsyncProofFetcher::new(state_db.clone())),
-            );
-            let write_sets = state_db
-                .ledger_db
-                .write_set_db()
-                .get_write_sets(snapshot_next_version, num_transactions)?;
-            let txn_info_iter = state_db
-                .ledger_db
-                .transaction_info_db()
-                .get_transaction_info_iter(snapshot_next_version, write_sets.len())?;
-            let last_checkpoint_index = txn_info_iter
-                .into_iter()
-                .collect::<Result<Vec<_>>>()?
-                .into_iter()
-                .enumerate()
-                .filter(|(_idx, txn_info)| txn_info.has_state_checkpoint_hash())
-                .last()
-                .map(|(idx, _)| idx);
-            latest_snapshot_state_view.prime_cache_by_write_set(&write_sets)?;
-
-            let state_checkpoint_output =
-                InMemoryStateCalculatorV2::calculate_for_write_sets_after_snapshot(
-                    &Arc::new(current_state_cloned),
-                    &latest_snapshot_state_view.into_state_cache(),
-                    last_checkpoint_index,
-                    &write_sets,
-                )?;
+            )?;
 
             // synchronously commit the snapshot at the last checkpoint here if not committed to disk yet.
             buffered_state.update(
@@ -646,6 +659,104 @@ impl StateStore {
         .expect("buffered state creation failed.");
     }
 
+    /// Calculates the state after the committed write sets from `first_version` until
+    /// `end_version` (exclusive) on top of `parent_state`, which is at `first_version - 1`. Values
+    /// not in memory are read from the latest snapshot before `end_version`, whose in-memory tree
+    /// is `persisted_smt`.
+    fn calculate_state_after_committed_write_sets(
+        state_db: &Arc<StateDb>,
+        parent_state: StateDelta,
+        persisted_smt: &SparseMerkleTree<StateValue>,
+        first_version: Version,
+        end_version: Version,
+    ) -> Result<StateCheckpointOutput> {
+        let snapshot = state_db.get_state_snapshot_before(end_version)?;
+        let speculative_state = parent_state.current.freeze(persisted_smt);
+        let state_view = CachedStateView::new_impl(
+            StateViewId::Miscellaneous,
+            end_version,
+            snapshot,
+            speculative_state,
+            Arc::new(AsyncProofFetcher::new(state_db.clone())),
+        );
+        let write_sets = state_db
+            .ledger_db
+            .write_set_db()
+            .get_write_sets(first_version, end_version)?;
+        let txn_info_iter = state_db
+            .ledger_db
+            .transaction_info_db()
+            .get_transaction_info_iter(first_version, write_sets.len())?;
+        let last_checkpoint_index = txn_info_iter
+            .into_iter()
+            .collect::<Result<Vec<_>>>()?
+            .into_iter()
+            .enumerate()
+            .filter(|(_idx, txn_info)| txn_info.has_state_checkpoint_hash())
+            .last()
+            .map(|(idx, _)| idx);
+        state_view.prime_cache_by_write_set(&write_sets)?;
+
+        InMemoryStateCalculatorV2::calculate_for_write_sets_after_snapshot(
+            &Arc::new(parent_state),
+            &state_view.into_state_cache(),
+            last_checkpoint_index,
+            &write_sets,
+        )
+    }
+
+    /// Brings the current state to `max_version`, only used when the DB is written by another
+    /// instance (i.e. following a primary instance as a secondary). The buffered state is
+    /// re-created at the latest snapshot at or before `max_version` if it has moved, and the write
+    /// sets after the current state are replayed in memory only: the primary persists the state
+    /// snapshots, so nothing is committed to the DB here.
+    pub(crate) fn refresh_buffered_state(&self, max_version: Version) -> Result<()> {
+        let current_version = self.current_state().current_version;
+        if current_version == Some(max_version) {
+            return Ok(());
+        }
+        let next_version = current_version.map_or(0, |v| v + 1);
+        let snapshot_version = self
+            .state_merkle_db
+            .get_state_snapshot_version_before(max_version + 1)?;
+
+        let mut buffered_state = self.buffered_state.lock();
+        let first_version = if next_version > max_version
+            || snapshot_version
+                != self
+                    .state_merkle_db
+                    .get_state_snapshot_version_before(next_version)?
+        {
+            buffered_state.drain();
+            *buffered_state = Self::create_buffered_state_at_snapshot(
+                &self.state_db,
+                self.buffered_state_target_items,
+                snapshot_version,
+                self.current_state.clone(),
+                self.persisted_state.clone(),
+            )?;
+            snapshot_version.map_or(0, |v| v + 1)
+        } else {
+            next_version
+        };
+
+        if first_version <= max_version {
+            let persisted_smt = self.persisted_state.lock().get().clone();
+            let state_checkpoint_output = Self::calculate_state_after_committed_write_sets(
+                &self.state_db,
+                self.current_state_cloned(),
+                &persisted_smt,
+                first_version,
+                max_version + 1,
+            )?;
+            // Bypasses `BufferedState::update`, which would commit the state at the checkpoints.
+            self.current_state
+                .lock()
+                .set(state_checkpoint_output.result_state.as_ref().clone());
+        }
+        Ok(())
+    }
+
     pub fn buffered_state(&self) -> &Mutex<BufferedState> {
         &self.buffered_state
     }
@@ -759,6 +870,9 @@ impl StateStore {
             .zip_eq(state_update_refs.shards.par_iter())
             .try_for_each(|(batch, updates)| {
                 updates.iter().try_for_each(|(idx, key, val)| {
+                    if !self.should_persist_state_value(key) {
+                        return Ok(());
+                    }
                     let ver = first_version + *idx as Version;
                     if enable_sharding {
                         batch.put::<StateValueByKeyHashSchema>(
@@ -772,6 +886,20 @@ impl StateStore {
             })
     }
 
+    /// Returns true iff the value of the given state key should be persisted. In partial
+    /// state mode, values of untracked state keys are skipped (but the keys are still
+    /// merklized, so the state root hashes remain verifiable).
+    pub(crate) fn should_persist_state_value(&self, state_key: &StateKey) -> bool {
+        self.partial_state_filter
+            .as_ref()
+            .map_or(true, |filter| filter.is_tracked(state_key))
+    }
+
+    /// Returns true iff the store is running in partial state mode
+    pub(crate) fn partial_state_enabled(&self) -> bool {
+        self.partial_state_filter.is_some()
+    }
+
     pub fn get_usage(&self, version: Option<Version>) -> Result<StateStorageUsage> {
         let _timer = OTHER_TIMERS_SECONDS
             .with_label_values(&["get_usage"])
@@ -859,6 +987,22 @@ impl StateStore {
             expected_usage.is_untracked() || base_version.is_none(), // ignore_state_cache_miss
         );
 
+        // In partial state mode, the old values of untracked state keys are never persisted,
+        // so the usage can't be calculated. Instead, the usage is recorded as untracked.
+        if self.partial_state_enabled() {
+            let num_versions = state_update_refs.num_versions;
+            for i in 0..num_versions {
+                if (i == num_versions - 1) || Some(i) == last_checkpoint_index {
+                    let version = first_version + i as u64;
+                    batch.put::<VersionDataSchema>(
+                        &version,
+                        &StateStorageUsage::new_untracked().into(),
+                    )?
+                }
+            }
+            return Ok(());
+        }
+
         {
             let _timer = OTHER_TIMERS_SECONDS.timer_with(&["put_stats_and_indices__put_usage"]);
             let num_versions = state_update_refs.num_versions;
@@ -1020,23 +1164,26 @@ impl StateStore {
         values: &StateValueBatch,
         enable_sharding: bool,
     ) -> Result<()> {
-        values.iter().for_each(|((key, version), value)| {
-            let shard_id = key.get_shard_id() as usize;
-            assert!(
-                shard_id < NUM_STATE_SHARDS,
-                "Invalid shard id: {}",
-                shard_id
-            );
-            if enable_sharding {
-                sharded_batch[shard_id]
-                    .put::<StateValueByKeyHashSchema>(&(key.hash(), *version), value)
-                    .expect("Inserting into sharded schema batch should never fail");
-            } else {
-                sharded_batch[shard_id]
-                    .put::<StateValueSchema>(&(key.clone(), *version), value)
-                    .expect("Inserting into sharded schema batch should never fail");
-            }
-        });
+        values
+            .iter()
+            .filter(|((key, _), _)| self.should_persist_state_value(key))
+            .for_each(|((key, version), value)| {
+                let shard_id = key.get_shard_id() as usize;
+                assert!(
+                    shard_id < NUM_STATE_SHARDS,
+                    "Invalid shard id: {}",
+                    shard_id
+                );
+                if enable_sharding {
+                    sharded_batch[shard_id]
+                        .put::<StateValueByKeyHashSchema>(&(key.hash(), *version), value)
+                        .expect("Inserting into sharded schema batch should never fail");
+                } else {
+                    sharded_batch[shard_id]
+                        .put::<StateValueSchema>(&(key.clone(), *version), value)
+                        .expect("Inserting into sharded schema batch should never fail");
+                }
+            });
         Ok(())
     }
 
@@ -1267,6 +1414,13 @@ impl StateValueWriter<StateKey, StateValue> for StateStore {
     }
 
     fn kv_finish(&self, version: Version, usage: StateStorageUsage) -> Result<()> {
+        // In partial state mode, the usage can't be maintained by later commits (see
+        // `put_stats_and_indices`), so it's recorded as untracked from the start.
+        let usage = if self.partial_state_enabled() {
+            StateStorageUsage::new_untracked()
+        } else {
+            usage
+        };
         self.ledger_db.metadata_db().put_usage(version, usage)?;
         if let Some(internal_indexer_db) = self.internal_indexer_db.as_ref() {
             if version > 0 {
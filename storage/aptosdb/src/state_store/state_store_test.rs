@@ -5,11 +5,16 @@
 use super::*;
 use crate::{
     db::test_helper::{arb_state_kv_sets, update_store},
+    partial_state::PartialStateFilter,
     schema::jellyfish_merkle_node::JellyfishMerkleNodeSchema,
     state_restore::StateSnapshotRestore,
     utils::new_sharded_kv_schema_batch,
     AptosDB,
 };
+use aptos_config::config::{
+    RocksdbConfigs, StorageDirPaths, BUFFERED_STATE_TARGET_ITEMS_FOR_TEST,
+    DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD, NO_OP_STORAGE_PRUNER_CONFIG,
+};
 use aptos_jellyfish_merkle::{
     node_type::{Node, NodeKey},
     TreeReader,
@@ -166,6 +171,109 @@ fn test_state_store_reader_writer() {
     verify_value_and_proof(store, key3, Some(&value3), 1, root);
 }
 
+fn open_partial_state_db(tmp_dir: &TempPath, tracked_account: AccountAddress) -> AptosDB {
+    AptosDB::open(
+        StorageDirPaths::from_path(tmp_dir),
+        false, /* readonly */
+        NO_OP_STORAGE_PRUNER_CONFIG,
+        RocksdbConfigs::default(),
+        false, /* indexer */
+        BUFFERED_STATE_TARGET_ITEMS_FOR_TEST,
+        DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
+        None, /* internal_indexer_db */
+        Some(PartialStateFilter::new(vec![tracked_account], vec![])),
+        None, /* ledger_retention_filter */
+    )
+    .expect("Unable to open AptosDB")
+}
+
+fn create_partial_state_value_set(
+    tracked_account: AccountAddress,
+) -> (StateKey, StateKey, Vec<(StateKey, StateValue)>) {
+    let tracked_key = StateKey::resource_typed::<AccountResource>(&tracked_account).unwrap();
+    let untracked_key =
+        StateKey::resource_typed::<AccountResource>(&AccountAddress::random()).unwrap();
+    let value_set = vec![
+        (
+            tracked_key.clone(),
+            StateValue::from(String::from("tracked_val").into_bytes()),
+        ),
+        (
+            untracked_key.clone(),
+            StateValue::from(String::from("untracked_val").into_bytes()),
+        ),
+    ];
+    (tracked_key, untracked_key, value_set)
+}
+
+#[test]
+fn test_partial_state_commit() {
+    let tracked_account = AccountAddress::random();
+    let (tracked_key, untracked_key, value_set) = create_partial_state_value_set(tracked_account);
+
+    // Commit the values to a full DB and a DB in partial state mode
+    let tmp_dir1 = TempPath::new();
+    let db1 = AptosDB::new_for_test(&tmp_dir1);
+    let root1 = put_value_set(&db1.state_store, value_set.clone(), 0, None);
+    let tmp_dir2 = TempPath::new();
+    let db2 = open_partial_state_db(&tmp_dir2, tracked_account);
+    let store2 = &db2.state_store;
+    let root2 = put_value_set(store2, value_set.clone(), 0, None);
+
+    // Verify that both values are merklized, but only the tracked value is persisted
+    assert_eq!(root1, root2);
+    verify_value_and_proof(store2, tracked_key, Some(&value_set[0].1), 0, root2);
+    assert_eq!(
+        store2
+            .get_state_value_by_version(&untracked_key, 0)
+            .unwrap(),
+        None
+    );
+
+    // Verify that the usage is untracked (it can't be calculated from partial state)
+    assert!(store2.get_usage(Some(0)).unwrap().is_untracked());
+}
+
+#[test]
+fn test_partial_state_restore() {
+    let tracked_account = AccountAddress::random();
+    let (tracked_key, untracked_key, value_set) = create_partial_state_value_set(tracked_account);
+
+    // Commit the values to a full DB
+    let tmp_dir1 = TempPath::new();
+    let db1 = AptosDB::new_for_test(&tmp_dir1);
+    let store1 = &db1.state_store;
+    let expected_root_hash = put_value_set(store1, value_set.clone(), 0, None);
+
+    // Restore the snapshot into a DB in partial state mode
+    let tmp_dir2 = TempPath::new();
+    let db2 = open_partial_state_db(&tmp_dir2, tracked_account);
+    let store2 = &db2.state_store;
+    let mut restore = store2.get_snapshot_receiver(0, expected_root_hash).unwrap();
+    let chunk = store1
+        .get_value_chunk_with_proof(0, 0, value_set.len())
+        .unwrap();
+    restore.add_chunk(chunk.raw_values, chunk.proof).unwrap();
+    restore.finish_box().unwrap();
+
+    // Verify that the whole tree is restored, but only the tracked value is persisted
+    assert_eq!(store2.get_root_hash(0).unwrap(), expected_root_hash);
+    verify_value_and_proof(
+        store2,
+        tracked_key,
+        Some(&value_set[0].1),
+        0,
+        expected_root_hash,
+    );
+    assert_eq!(
+        store2
+            .get_state_value_by_version(&untracked_key, 0)
+            .unwrap(),
+        None
+    );
+    assert!(store2.get_usage(Some(0)).unwrap().is_untracked());
+}
+
 fn traverse_values(
     store: &StateStore,
     prefix: &StateKeyPrefix,
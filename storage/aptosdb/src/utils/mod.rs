@@ -5,13 +5,66 @@ pub mod iterators;
 pub(crate) mod truncation_helper;
 
 use crate::schema::db_metadata::{DbMetadataKey, DbMetadataSchema};
-use aptos_schemadb::{SchemaBatch, DB};
+use aptos_config::config::RocksdbConfig;
+use aptos_rocksdb_options::gen_rocksdb_options;
+use aptos_schemadb::{ColumnFamilyDescriptor, SchemaBatch, DB};
 use aptos_storage_interface::{state_store::NUM_STATE_SHARDS, Result};
 use aptos_types::transaction::Version;
 use arr_macro::arr;
+use std::path::{Path, PathBuf};
 
 pub(crate) type ShardedStateKvSchemaBatch = [SchemaBatch; NUM_STATE_SHARDS];
 
+/// How the RocksDB instances backing AptosDB are opened.
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum DbOpenMode<'a> {
+    ReadWrite,
+    ReadOnly,
+    /// Follows the primary instance writing to the DB (see `AptosDB::open_as_secondary`). Each
+    /// secondary instance keeps its own files in a sub directory (named after the DB) of the
+    /// given path.
+    Secondary(&'a Path),
+}
+
+impl DbOpenMode<'_> {
+    pub(crate) fn from_readonly(readonly: bool) -> Self {
+        if readonly {
+            Self::ReadOnly
+        } else {
+            Self::ReadWrite
+        }
+    }
+
+    pub(crate) fn is_read_write(&self) -> bool {
+        matches!(self, Self::ReadWrite)
+    }
+}
+
+pub(crate) fn open_rocksdb(
+    path: PathBuf,
+    name: &str,
+    db_config: &RocksdbConfig,
+    cfds: Vec<ColumnFamilyDescriptor>,
+    open_mode: DbOpenMode,
+) -> Result<DB> {
+    Ok(match open_mode {
+        DbOpenMode::ReadWrite => {
+            DB::open_cf(&gen_rocksdb_options(db_config, false), path, name, cfds)?
+        },
+        DbOpenMode::ReadOnly => {
+            DB::open_cf_readonly(&gen_rocksdb_options(db_config, true), path, name, cfds)?
+        },
+        DbOpenMode::Secondary(secondary_root_path) => {
+            let mut db_opts = gen_rocksdb_options(db_config, true);
+            // Required by secondary instances, which can't tell which files are still in use.
+            db_opts.set_max_open_files(-1);
+            let secondary_path = secondary_root_path.join(name);
+            std::fs::create_dir_all(&secondary_path)?;
+            DB::open_cf_as_secondary(&db_opts, path, secondary_path, name, cfds)?
+        },
+    })
+}
+
 pub(crate) fn get_progress(db: &DB, progress_key: &DbMetadataKey) -> Result<Option<Version>> {
     Ok(db
         .get::<DbMetadataSchema>(progress_key)?
@@ -29,6 +29,11 @@ use crate::{
     transaction_store::TransactionStore,
     utils::get_progress,
 };
+use aptos_db_indexer::db_indexer::InternalIndexerDB;
+use aptos_db_indexer_schemas::{
+    metadata::{MetadataKey as IndexerMetadataKey, MetadataValue as IndexerMetadataValue},
+    schema::indexer_metadata::InternalIndexerMetadataSchema,
+};
 use aptos_jellyfish_merkle::{node_type::NodeKey, StaleNodeIndex};
 use aptos_logger::info;
 use aptos_schemadb::{
@@ -64,11 +69,20 @@ pub(crate) fn get_state_merkle_commit_progress(
     )
 }
 
-pub(crate) fn truncate_ledger_db(ledger_db: Arc<LedgerDb>, target_version: Version) -> Result<()> {
+pub(crate) fn truncate_ledger_db(
+    ledger_db: Arc<LedgerDb>,
+    target_version: Version,
+    internal_indexer_db: Option<&InternalIndexerDB>,
+) -> Result<()> {
     let transaction_store = TransactionStore::new(Arc::clone(&ledger_db));
 
     let start_version = target_version + 1;
-    truncate_ledger_db_single_batch(&ledger_db, &transaction_store, start_version)?;
+    truncate_ledger_db_single_batch(
+        &ledger_db,
+        &transaction_store,
+        start_version,
+        internal_indexer_db,
+    )?;
     Ok(())
 }
 
@@ -315,6 +329,7 @@ fn truncate_ledger_db_single_batch(
     ledger_db: &LedgerDb,
     transaction_store: &TransactionStore,
     start_version: Version,
+    internal_indexer_db: Option<&InternalIndexerDB>,
 ) -> Result<()> {
     let batch = LedgerDbSchemaBatches::new();
 
@@ -331,7 +346,12 @@ fn truncate_ledger_db_single_batch(
     )?;
     delete_per_version_data(ledger_db, start_version, &batch)?;
 
-    delete_event_data(ledger_db, start_version, &batch.event_db_batches)?;
+    delete_event_data(
+        ledger_db,
+        start_version,
+        &batch.event_db_batches,
+        internal_indexer_db,
+    )?;
 
     truncate_transaction_accumulator(
         ledger_db.transaction_accumulator_db_raw(),
@@ -475,6 +495,7 @@ fn delete_event_data(
     ledger_db: &LedgerDb,
     start_version: Version,
     batch: &SchemaBatch,
+    internal_indexer_db: Option<&InternalIndexerDB>,
 ) -> Result<()> {
     if let Some(latest_version) = ledger_db.event_db().latest_version()? {
         if latest_version >= start_version {
@@ -483,15 +504,37 @@ fn delete_event_data(
                 latest_version = latest_version,
                 "Truncate event data."
             );
+            // Unlike the event by key indices, the event by type index is keyed by version, so
+            // entries of truncated events won't be overwritten and have to be deleted.
+            let type_indices_batch = internal_indexer_db
+                .filter(|indexer_db| indexer_db.event_by_type_enabled())
+                .map(|_| SchemaBatch::new());
             ledger_db.event_db().prune_events(
                 start_version,
                 latest_version + 1,
                 batch,
-                // Assuming same data will be overwritten into indices, we don't bother to deal
-                // with the existence or placement of indices
-                // TODO: prune data from internal indices
+                // Assuming same data will be overwritten into the event by key indices, we don't
+                // bother to deal with the existence or placement of them
                 None,
+                type_indices_batch.as_ref(),
             )?;
+            if let (Some(indexer_db), Some(type_indices_batch)) =
+                (internal_indexer_db, type_indices_batch)
+            {
+                // The index must be rebuilt from `start_version` once the events are re-committed.
+                if indexer_db
+                    .get_event_by_type_version()?
+                    .map_or(false, |version| version >= start_version)
+                {
+                    type_indices_batch.put::<InternalIndexerMetadataSchema>(
+                        &IndexerMetadataKey::EventByTypeVersion,
+                        &IndexerMetadataValue::Version(start_version - 1),
+                    )?;
+                }
+                indexer_db
+                    .get_inner_db_ref()
+                    .write_schemas(type_indices_batch)?;
+            }
         }
     }
     Ok(())
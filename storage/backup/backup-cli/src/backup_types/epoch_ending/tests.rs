@@ -89,6 +89,9 @@ fn end_to_end() {
                 concurrent_downloads: ConcurrentDownloadsOpt::default(),
                 replay_concurrency_level: ReplayConcurrencyLevelOpt::default(),
                 enable_state_indices: false,
+                partial_state: false,
+                partial_state_tracked_accounts: vec![],
+                partial_state_tracked_table_handles: vec![],
             }
             .try_into()
             .unwrap(),
@@ -223,6 +226,9 @@ async fn test_trusted_waypoints_impl(
             concurrent_downloads: ConcurrentDownloadsOpt::default(),
             replay_concurrency_level: ReplayConcurrencyLevelOpt::default(),
             enable_state_indices: false,
+            partial_state: false,
+            partial_state_tracked_accounts: vec![],
+            partial_state_tracked_table_handles: vec![],
         }
         .try_into()
         .unwrap(),
@@ -245,6 +251,9 @@ async fn test_trusted_waypoints_impl(
             concurrent_downloads: ConcurrentDownloadsOpt::default(),
             replay_concurrency_level: ReplayConcurrencyLevelOpt::default(),
             enable_state_indices: false,
+            partial_state: false,
+            partial_state_tracked_accounts: vec![],
+            partial_state_tracked_table_handles: vec![],
         }
         .try_into()
         .unwrap(),
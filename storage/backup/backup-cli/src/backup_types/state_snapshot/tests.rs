@@ -90,6 +90,9 @@ fn end_to_end() {
                 concurrent_downloads: ConcurrentDownloadsOpt::default(),
                 replay_concurrency_level: ReplayConcurrencyLevelOpt::default(),
                 enable_state_indices: false,
+                partial_state: false,
+                partial_state_tracked_accounts: vec![],
+                partial_state_tracked_table_handles: vec![],
             }
             .try_into()
             .unwrap(),
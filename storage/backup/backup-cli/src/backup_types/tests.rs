@@ -126,6 +126,9 @@ fn test_end_to_end_impl(d: TestData) {
         concurrent_downloads: ConcurrentDownloadsOpt::default(),
         replay_concurrency_level: ReplayConcurrencyLevelOpt::default(),
         enable_state_indices: false,
+        partial_state: false,
+        partial_state_tracked_accounts: vec![],
+        partial_state_tracked_table_handles: vec![],
     }
     .try_into()
     .unwrap();
@@ -20,6 +20,7 @@ use aptos_db::{
     backup::restore_handler::RestoreHandler,
     db::AptosDB,
     get_restore_handler::GetRestoreHandler,
+    partial_state::PartialStateFilter,
     state_restore::{
         StateSnapshotRestore, StateSnapshotRestoreMode, StateValueBatch, StateValueWriter,
     },
@@ -31,8 +32,10 @@ use aptos_jellyfish_merkle::{NodeBatch, TreeWriter};
 use aptos_logger::info;
 use aptos_storage_interface::{AptosDbError, Result};
 use aptos_types::{
+    account_address::AccountAddress,
     state_store::{
         state_key::StateKey, state_storage_usage::StateStorageUsage, state_value::StateValue,
+        table::TableHandle,
     },
     transaction::Version,
     waypoint::Waypoint,
@@ -167,6 +170,30 @@ pub struct GlobalRestoreOpt {
 
     #[clap(long, help = "Restore the state indices when restore the snapshot")]
     pub enable_state_indices: bool,
+
+    #[clap(
+        long,
+        help = "Restore into a DB for a node running in partial state mode, i.e., only persist \
+        the state values of the tracked accounts and tables. This must match the partial state \
+        config of the node."
+    )]
+    pub partial_state: bool,
+
+    #[clap(
+        long,
+        num_args = 0..,
+        requires = "partial_state",
+        help = "(multiple) The accounts tracked in partial state mode."
+    )]
+    pub partial_state_tracked_accounts: Vec<AccountAddress>,
+
+    #[clap(
+        long,
+        num_args = 0..,
+        requires = "partial_state",
+        help = "(multiple) The table handles tracked in partial state mode."
+    )]
+    pub partial_state_tracked_table_handles: Vec<TableHandle>,
 }
 
 pub enum RestoreRunMode {
@@ -305,6 +332,12 @@ impl TryFrom<GlobalRestoreOpt> for GlobalRestoreOptions {
             } else {
                 None
             };
+            let partial_state_filter = opt.partial_state.then(|| {
+                PartialStateFilter::new(
+                    opt.partial_state_tracked_accounts.clone(),
+                    opt.partial_state_tracked_table_handles.clone(),
+                )
+            });
             let restore_handler = Arc::new(AptosDB::open_kv_only(
                 StorageDirPaths::from_path(db_dir),
                 false,                       /* read_only */
@@ -314,6 +347,8 @@ impl TryFrom<GlobalRestoreOpt> for GlobalRestoreOptions {
                 BUFFERED_STATE_TARGET_ITEMS,
                 DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
                 internal_indexer_db,
+                partial_state_filter,
+                None,
             )?)
             .get_restore_handler();
 
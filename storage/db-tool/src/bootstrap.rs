@@ -58,6 +58,8 @@ impl Command {
             BUFFERED_STATE_TARGET_ITEMS,
             DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
             None,
+            None,
+            None,
         )
         .expect("Failed to open DB.");
         let db = DbReaderWriter::new(db);
@@ -0,0 +1,684 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{ensure, format_err, Result};
+use aptos_backup_cli::utils::RocksdbOpt;
+use aptos_config::config::{
+    StorageDirPaths, BUFFERED_STATE_TARGET_ITEMS, DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
+    NO_OP_STORAGE_PRUNER_CONFIG,
+};
+use aptos_crypto::hash::CryptoHash;
+use aptos_db::AptosDB;
+use aptos_logger::info;
+use aptos_storage_interface::{DbReader, MAX_REQUEST_LIMIT};
+use aptos_types::{
+    contract_event::{ContractEvent, FEE_STATEMENT_EVENT_TYPE},
+    fee_statement::FeeStatement,
+    transaction::{Transaction, TransactionInfo, Version},
+    write_set::{TransactionWrite, WriteOpKind, WriteSet},
+};
+use clap::{Parser, ValueEnum};
+use itertools::multizip;
+use parquet::{
+    basic::Compression,
+    data_type::{ByteArray, ByteArrayType, Int64Type},
+    file::{properties::WriterProperties, writer::SerializedFileWriter},
+    schema::parser::parse_message_type,
+};
+use rayon::prelude::*;
+use std::{
+    fs,
+    fs::File,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+
+const EXPORT_METADATA_FILE: &str = "export_metadata.json";
+
+/// Exports ledger data (transactions, events, write sets and fee statements) over a version
+/// range to columnar files, so that offline analysis doesn't need to touch production APIs.
+///
+/// The version range is split into chunks (aligned to multiples of the chunk size) that are
+/// exported in parallel. Each chunk is written to one file per table
+/// (`<output-dir>/<table>/<first_version>_<last_version>.<format>`), and chunks that were already
+/// exported are skipped, so an interrupted export can be resumed (or extended) by running the
+/// same command again. The chunk size is recorded in `<output-dir>/export_metadata.json`, and
+/// resuming with a different chunk size is refused (the chunks would not line up).
+#[derive(Parser)]
+pub struct Opt {
+    #[clap(long = "db-dir", value_parser)]
+    db_dir: PathBuf,
+
+    #[clap(
+        long,
+        value_parser,
+        help = "The directory to write the exported files to"
+    )]
+    output_dir: PathBuf,
+
+    #[clap(long, default_value_t = 0, help = "The first version to export")]
+    start_version: Version,
+
+    #[clap(
+        long,
+        help = "The last version to export (inclusive). Defaults to the latest synced version"
+    )]
+    end_version: Option<Version>,
+
+    #[clap(long, value_enum, default_value_t = ExportFormat::Parquet)]
+    format: ExportFormat,
+
+    #[clap(
+        long,
+        default_value_t = 10_000,
+        value_parser = clap::value_parser!(u64).range(1..=MAX_REQUEST_LIMIT),
+        help = "The number of versions exported to each file"
+    )]
+    chunk_size: u64,
+
+    #[clap(
+        long,
+        default_value_t = 4,
+        help = "The number of chunks exported in parallel"
+    )]
+    concurrency: usize,
+
+    #[clap(flatten)]
+    rocksdb_opt: RocksdbOpt,
+}
+
+impl Opt {
+    pub fn run(self) -> Result<()> {
+        let db = AptosDB::open(
+            StorageDirPaths::from_path(&self.db_dir),
+            true, /* readonly */
+            NO_OP_STORAGE_PRUNER_CONFIG,
+            self.rocksdb_opt.clone().into(),
+            false, /* indexer */
+            BUFFERED_STATE_TARGET_ITEMS,
+            DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
+            None,
+            None,
+            None,
+        )?;
+
+        // Identify the version range to export
+        let start_version = match db.get_first_txn_version()? {
+            Some(first_version) => self.start_version.max(first_version),
+            None => self.start_version,
+        };
+        let synced_version = db
+            .get_synced_version()?
+            .ok_or_else(|| format_err!("The DB is empty."))?;
+        let end_version = self.end_version.unwrap_or(synced_version);
+        ensure!(
+            end_version <= synced_version,
+            "end_version {} is newer than the latest synced version {}.",
+            end_version,
+            synced_version
+        );
+        ensure!(
+            start_version <= end_version,
+            "start_version {} is greater than end_version {}.",
+            start_version,
+            end_version
+        );
+
+        for table in ExportTable::ALL {
+            fs::create_dir_all(self.output_dir.join(table.name()))?;
+        }
+        check_or_write_metadata(&self.output_dir, self.chunk_size)?;
+
+        // Chunk boundaries are aligned to multiples of the chunk size, so that chunks of
+        // different runs (e.g., with different start versions) line up
+        let chunks: Vec<_> = (start_version / self.chunk_size..=end_version / self.chunk_size)
+            .map(|chunk_index| {
+                let chunk_first_version = chunk_index * self.chunk_size;
+                let chunk_last_version = chunk_first_version.saturating_add(self.chunk_size - 1);
+                (
+                    chunk_first_version.max(start_version),
+                    chunk_last_version.min(end_version),
+                )
+            })
+            .collect();
+        let exported_ranges = exported_ranges(&self.output_dir, self.format)?;
+        info!(
+            start_version = start_version,
+            end_version = end_version,
+            num_chunks = chunks.len(),
+            "Exporting ledger data."
+        );
+
+        let num_exported_chunks = AtomicU64::new(0);
+        let num_skipped_chunks = AtomicU64::new(0);
+        let thread_pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(self.concurrency)
+            .build()?;
+        thread_pool.install(|| {
+            chunks
+                .par_iter()
+                .try_for_each(|(first_version, last_version)| {
+                    if exported_ranges
+                        .iter()
+                        .any(|(first, last)| first <= first_version && last >= last_version)
+                    {
+                        num_skipped_chunks.fetch_add(1, Ordering::Relaxed);
+                        return Ok(());
+                    }
+
+                    let chunk_exporter = ChunkExporter::new(
+                        &self.output_dir,
+                        self.format,
+                        *first_version,
+                        *last_version,
+                    );
+                    chunk_exporter.export(&db)?;
+                    // Files of previous runs covering only a part of this chunk (e.g., the
+                    // last chunk, if it wasn't complete back then) are superseded
+                    for (first, last) in &exported_ranges {
+                        if first >= first_version
+                            && last <= last_version
+                            && (first, last) != (first_version, last_version)
+                        {
+                            chunk_exporter.remove_files(*first, *last)?;
+                        }
+                    }
+                    let num_exported = num_exported_chunks.fetch_add(1, Ordering::Relaxed) + 1;
+                    info!(
+                        first_version = first_version,
+                        last_version = last_version,
+                        "Exported chunk {} of {}.",
+                        num_exported,
+                        chunks.len(),
+                    );
+                    Ok::<(), anyhow::Error>(())
+                })
+        })?;
+
+        info!(
+            num_exported_chunks = num_exported_chunks.load(Ordering::Relaxed),
+            num_skipped_chunks = num_skipped_chunks.load(Ordering::Relaxed),
+            "Export finished."
+        );
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, ValueEnum)]
+pub enum ExportFormat {
+    Csv,
+    Parquet,
+}
+
+impl ExportFormat {
+    fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Parquet => "parquet",
+        }
+    }
+}
+
+/// The tables written by the export. The schema of each table is stable: columns are only
+/// ever appended, so that downstream datasets remain compatible.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ExportTable {
+    Transactions,
+    Events,
+    WriteSetChanges,
+    FeeStatements,
+}
+
+impl ExportTable {
+    pub const ALL: [ExportTable; 4] = [
+        ExportTable::Transactions,
+        ExportTable::Events,
+        ExportTable::WriteSetChanges,
+        ExportTable::FeeStatements,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ExportTable::Transactions => "transactions",
+            ExportTable::Events => "events",
+            ExportTable::WriteSetChanges => "write_set_changes",
+            ExportTable::FeeStatements => "fee_statements",
+        }
+    }
+
+    pub fn columns(&self) -> &'static [ExportColumn] {
+        use ColumnType::{Utf8, U64};
+        match self {
+            ExportTable::Transactions => &[
+                ExportColumn::new("version", U64, false),
+                ExportColumn::new("transaction_hash", Utf8, false),
+                ExportColumn::new("transaction_type", Utf8, false),
+                ExportColumn::new("sender", Utf8, true),
+                ExportColumn::new("sequence_number", U64, true),
+                ExportColumn::new("status", Utf8, false),
+                ExportColumn::new("gas_used", U64, false),
+                ExportColumn::new("state_change_hash", Utf8, false),
+                ExportColumn::new("event_root_hash", Utf8, false),
+                ExportColumn::new("num_events", U64, false),
+                ExportColumn::new("num_write_set_changes", U64, false),
+            ],
+            ExportTable::Events => &[
+                ExportColumn::new("version", U64, false),
+                ExportColumn::new("event_index", U64, false),
+                ExportColumn::new("type_tag", Utf8, false),
+                ExportColumn::new("event_key", Utf8, true),
+                ExportColumn::new("sequence_number", U64, true),
+                ExportColumn::new("data", Utf8, false),
+            ],
+            ExportTable::WriteSetChanges => &[
+                ExportColumn::new("version", U64, false),
+                ExportColumn::new("change_index", U64, false),
+                ExportColumn::new("state_key_hash", Utf8, false),
+                ExportColumn::new("state_key", Utf8, false),
+                ExportColumn::new("write_op", Utf8, false),
+                ExportColumn::new("value", Utf8, true),
+            ],
+            ExportTable::FeeStatements => &[
+                ExportColumn::new("version", U64, false),
+                ExportColumn::new("total_charge_gas_units", U64, false),
+                ExportColumn::new("execution_gas_units", U64, false),
+                ExportColumn::new("io_gas_units", U64, false),
+                ExportColumn::new("storage_fee_octas", U64, false),
+                ExportColumn::new("storage_fee_refund_octas", U64, false),
+            ],
+        }
+    }
+
+    /// Returns the parquet message type of the table
+    fn parquet_message_type(&self) -> String {
+        let fields: String = self
+            .columns()
+            .iter()
+            .map(|column| {
+                let repetition = if column.nullable {
+                    "optional"
+                } else {
+                    "required"
+                };
+                let physical_type = match column.column_type {
+                    ColumnType::U64 => "int64",
+                    ColumnType::Utf8 => "binary",
+                };
+                let logical_type = match column.column_type {
+                    ColumnType::U64 => "UINT_64",
+                    ColumnType::Utf8 => "UTF8",
+                };
+                format!(
+                    "{} {} {} ({}); ",
+                    repetition, physical_type, column.name, logical_type
+                )
+            })
+            .collect();
+        format!("message {} {{ {}}}", self.name(), fields)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ColumnType {
+    U64,
+    Utf8,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct ExportColumn {
+    pub name: &'static str,
+    pub column_type: ColumnType,
+    pub nullable: bool,
+}
+
+impl ExportColumn {
+    const fn new(name: &'static str, column_type: ColumnType, nullable: bool) -> Self {
+        Self {
+            name,
+            column_type,
+            nullable,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum ExportValue {
+    U64(u64),
+    Utf8(String),
+    Null,
+}
+
+impl ExportValue {
+    fn to_csv_field(&self) -> String {
+        match self {
+            ExportValue::U64(value) => value.to_string(),
+            ExportValue::Utf8(value) => value.clone(),
+            ExportValue::Null => String::new(),
+        }
+    }
+}
+
+impl From<u64> for ExportValue {
+    fn from(value: u64) -> Self {
+        ExportValue::U64(value)
+    }
+}
+
+impl From<String> for ExportValue {
+    fn from(value: String) -> Self {
+        ExportValue::Utf8(value)
+    }
+}
+
+impl<T: Into<ExportValue>> From<Option<T>> for ExportValue {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(ExportValue::Null, Into::into)
+    }
+}
+
+type ExportRow = Vec<ExportValue>;
+
+/// Exports a single chunk of versions (i.e., one file per table)
+struct ChunkExporter {
+    first_version: Version,
+    last_version: Version,
+    format: ExportFormat,
+    output_dir: PathBuf,
+}
+
+impl ChunkExporter {
+    fn new(
+        output_dir: &Path,
+        format: ExportFormat,
+        first_version: Version,
+        last_version: Version,
+    ) -> Self {
+        Self {
+            first_version,
+            last_version,
+            format,
+            output_dir: output_dir.to_path_buf(),
+        }
+    }
+
+    /// Returns the path of the file that holds the given table of this chunk
+    fn file_path(&self, table: ExportTable) -> PathBuf {
+        chunk_file_path(
+            &self.output_dir,
+            self.format,
+            table,
+            self.first_version,
+            self.last_version,
+        )
+    }
+
+    /// Removes the files of all tables of the given (superseded) version range
+    fn remove_files(&self, first_version: Version, last_version: Version) -> Result<()> {
+        for table in ExportTable::ALL {
+            let file_path = chunk_file_path(
+                &self.output_dir,
+                self.format,
+                table,
+                first_version,
+                last_version,
+            );
+            if file_path.exists() {
+                fs::remove_file(file_path)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn export(&self, db: &AptosDB) -> Result<()> {
+        let num_versions = self.last_version - self.first_version + 1;
+        let txns = db.get_transaction_iterator(self.first_version, num_versions)?;
+        let txn_infos = db.get_transaction_info_iterator(self.first_version, num_versions)?;
+        let events = db.get_events_iterator(self.first_version, num_versions)?;
+        let write_sets = db.get_write_set_iterator(self.first_version, num_versions)?;
+
+        let mut txn_rows = vec![];
+        let mut event_rows = vec![];
+        let mut write_set_rows = vec![];
+        let mut fee_statement_rows = vec![];
+        let mut version = self.first_version;
+        for (txn, txn_info, events, write_set) in multizip((txns, txn_infos, events, write_sets)) {
+            let (txn, txn_info, events, write_set) = (txn?, txn_info?, events?, write_set?);
+            txn_rows.push(transaction_row(
+                version, &txn, &txn_info, &events, &write_set,
+            ));
+            for (index, event) in events.iter().enumerate() {
+                event_rows.push(event_row(version, index, event));
+                if event.type_tag() == &*FEE_STATEMENT_EVENT_TYPE {
+                    let fee_statement: FeeStatement = bcs::from_bytes(event.event_data())?;
+                    fee_statement_rows.push(fee_statement_row(version, &fee_statement));
+                }
+            }
+            for (index, (state_key, write_op)) in write_set.iter().enumerate() {
+                write_set_rows.push(vec![
+                    version.into(),
+                    (index as u64).into(),
+                    state_key.hash().to_hex_literal().into(),
+                    hex::encode(bcs::to_bytes(state_key)?).into(),
+                    write_op_kind_name(write_op.write_op_kind())
+                        .to_string()
+                        .into(),
+                    write_op.bytes().map(hex::encode).into(),
+                ]);
+            }
+            version += 1;
+        }
+        ensure!(
+            version == self.last_version + 1,
+            "Expected to read up to version {}, but only read up to version {}.",
+            self.last_version,
+            version - 1
+        );
+
+        self.write_table(ExportTable::Transactions, &txn_rows)?;
+        self.write_table(ExportTable::Events, &event_rows)?;
+        self.write_table(ExportTable::WriteSetChanges, &write_set_rows)?;
+        self.write_table(ExportTable::FeeStatements, &fee_statement_rows)
+    }
+
+    /// Writes the rows of the given table to a temporary file first, and then moves the file
+    /// into place, so that partially written files are never mistaken for exported ones.
+    fn write_table(&self, table: ExportTable, rows: &[ExportRow]) -> Result<()> {
+        let file_path = self.file_path(table);
+        let temp_file_path = file_path.with_extension("tmp");
+        match self.format {
+            ExportFormat::Csv => write_csv(&temp_file_path, table, rows)?,
+            ExportFormat::Parquet => write_parquet(&temp_file_path, table, rows)?,
+        }
+        fs::rename(&temp_file_path, &file_path)?;
+        Ok(())
+    }
+}
+
+fn chunk_file_path(
+    output_dir: &Path,
+    format: ExportFormat,
+    table: ExportTable,
+    first_version: Version,
+    last_version: Version,
+) -> PathBuf {
+    output_dir.join(table.name()).join(format!(
+        "{:020}_{:020}.{}",
+        first_version,
+        last_version,
+        format.extension()
+    ))
+}
+
+/// Checks that previous runs exporting to the output directory used the same chunk size, and
+/// records the chunk size for the first run
+fn check_or_write_metadata(output_dir: &Path, chunk_size: u64) -> Result<()> {
+    let metadata_path = output_dir.join(EXPORT_METADATA_FILE);
+    if metadata_path.exists() {
+        let metadata: serde_json::Value = serde_json::from_slice(&fs::read(&metadata_path)?)?;
+        let exported_chunk_size = metadata["chunk_size"]
+            .as_u64()
+            .ok_or_else(|| format_err!("Missing chunk_size in {:?}.", metadata_path))?;
+        ensure!(
+            exported_chunk_size == chunk_size,
+            "The output directory was exported with chunk_size {}, but chunk_size {} was given.",
+            exported_chunk_size,
+            chunk_size
+        );
+        return Ok(());
+    }
+
+    let metadata = serde_json::json!({ "chunk_size": chunk_size });
+    fs::write(metadata_path, serde_json::to_vec_pretty(&metadata)?)?;
+    Ok(())
+}
+
+/// Returns the version ranges of the chunks whose files were written for all tables
+fn exported_ranges(output_dir: &Path, format: ExportFormat) -> Result<Vec<(Version, Version)>> {
+    let mut ranges = vec![];
+    for entry in fs::read_dir(output_dir.join(ExportTable::Transactions.name()))? {
+        let file_name = entry?.file_name();
+        let range = file_name
+            .to_str()
+            .and_then(|name| name.strip_suffix(&format!(".{}", format.extension())))
+            .and_then(|name| name.split_once('_'))
+            .and_then(|(first, last)| Some((first.parse().ok()?, last.parse().ok()?)));
+        if let Some((first_version, last_version)) = range {
+            if ExportTable::ALL.iter().all(|table| {
+                chunk_file_path(output_dir, format, *table, first_version, last_version).exists()
+            }) {
+                ranges.push((first_version, last_version));
+            }
+        }
+    }
+    Ok(ranges)
+}
+
+fn transaction_row(
+    version: Version,
+    txn: &Transaction,
+    txn_info: &TransactionInfo,
+    events: &[ContractEvent],
+    write_set: &WriteSet,
+) -> ExportRow {
+    let user_txn = txn.try_as_signed_user_txn();
+    vec![
+        version.into(),
+        txn_info.transaction_hash().to_hex_literal().into(),
+        txn.type_name().to_string().into(),
+        user_txn.map(|txn| txn.sender().to_hex_literal()).into(),
+        user_txn.map(|txn| txn.sequence_number()).into(),
+        format!("{:?}", txn_info.status()).into(),
+        txn_info.gas_used().into(),
+        txn_info.state_change_hash().to_hex_literal().into(),
+        txn_info.event_root_hash().to_hex_literal().into(),
+        (events.len() as u64).into(),
+        (write_set.iter().count() as u64).into(),
+    ]
+}
+
+fn event_row(version: Version, index: usize, event: &ContractEvent) -> ExportRow {
+    let (event_key, sequence_number) = match event {
+        ContractEvent::V1(event) => (Some(event.key().to_string()), Some(event.sequence_number())),
+        ContractEvent::V2(_) => (None, None),
+    };
+    vec![
+        version.into(),
+        (index as u64).into(),
+        event.type_tag().to_string().into(),
+        event_key.into(),
+        sequence_number.into(),
+        hex::encode(event.event_data()).into(),
+    ]
+}
+
+fn fee_statement_row(version: Version, fee_statement: &FeeStatement) -> ExportRow {
+    vec![
+        version.into(),
+        fee_statement.gas_used().into(),
+        fee_statement.execution_gas_used().into(),
+        fee_statement.io_gas_used().into(),
+        fee_statement.storage_fee_used().into(),
+        fee_statement.storage_fee_refund().into(),
+    ]
+}
+
+fn write_op_kind_name(write_op_kind: WriteOpKind) -> &'static str {
+    match write_op_kind {
+        WriteOpKind::Creation => "creation",
+        WriteOpKind::Modification => "modification",
+        WriteOpKind::Deletion => "deletion",
+    }
+}
+
+fn write_csv(file_path: &Path, table: ExportTable, rows: &[ExportRow]) -> Result<()> {
+    let mut writer = csv::Writer::from_path(file_path)?;
+    writer.write_record(table.columns().iter().map(|column| column.name))?;
+    for row in rows {
+        writer.write_record(row.iter().map(ExportValue::to_csv_field))?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+fn write_parquet(file_path: &Path, table: ExportTable, rows: &[ExportRow]) -> Result<()> {
+    let schema = Arc::new(parse_message_type(&table.parquet_message_type())?);
+    let properties = Arc::new(
+        WriterProperties::builder()
+            .set_compression(Compression::SNAPPY)
+            .build(),
+    );
+    let mut writer = SerializedFileWriter::new(File::create(file_path)?, schema, properties)?;
+
+    // All rows of a chunk are written to a single row group
+    let mut row_group_writer = writer.next_row_group()?;
+    for (column_index, column) in table.columns().iter().enumerate() {
+        let mut column_writer = row_group_writer
+            .next_column()?
+            .ok_or_else(|| format_err!("Missing parquet column writer: {}", column.name))?;
+
+        // Null values are only reflected in the definition levels
+        let values = rows.iter().map(|row| &row[column_index]);
+        let def_levels: Option<Vec<i16>> = column.nullable.then(|| {
+            values
+                .clone()
+                .map(|value| i16::from(*value != ExportValue::Null))
+                .collect()
+        });
+        match column.column_type {
+            ColumnType::U64 => {
+                let values = values
+                    .filter_map(|value| match value {
+                        ExportValue::U64(value) => Some(*value as i64),
+                        _ => None,
+                    })
+                    .collect::<Vec<_>>();
+                column_writer.typed::<Int64Type>().write_batch(
+                    &values,
+                    def_levels.as_deref(),
+                    None,
+                )?;
+            },
+            ColumnType::Utf8 => {
+                let values = values
+                    .filter_map(|value| match value {
+                        ExportValue::Utf8(value) => Some(ByteArray::from(value.as_str())),
+                        _ => None,
+                    })
+                    .collect::<Vec<_>>();
+                column_writer.typed::<ByteArrayType>().write_batch(
+                    &values,
+                    def_levels.as_deref(),
+                    None,
+                )?;
+            },
+        }
+        column_writer.close()?;
+    }
+    row_group_writer.close()?;
+    writer.close()?;
+
+    Ok(())
+}
@@ -141,6 +141,8 @@ impl Verifier {
             BUFFERED_STATE_TARGET_ITEMS,
             DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
             None,
+            None,
+            None,
         )?;
 
         let backup_handler = aptos_db.get_backup_handler();
@@ -70,6 +70,8 @@ impl Opt {
             BUFFERED_STATE_TARGET_ITEMS,
             DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
             None,
+            None,
+            None,
         )?)
         .get_restore_handler();
         let ret = ReplayVerifyCoordinator::new(
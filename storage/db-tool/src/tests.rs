@@ -64,6 +64,16 @@ fn test_various_cmd_parsing() {
         "--start-version",
         "Max",
     ]);
+    run_cmd(&[
+        "aptos-db-tool",
+        "export",
+        "--db-dir",
+        ".",
+        "--output-dir",
+        ".",
+        "--format",
+        "csv",
+    ]);
 }
 
 fn run_cmd(args: &[&str]) {
@@ -80,6 +90,7 @@ mod dbtool_tests {
         storage::{local_fs::LocalFs, BackupStorage},
         utils::test_utils::start_local_backup_service,
     };
+    use aptos_crypto::hash::CryptoHash;
     use aptos_db::AptosDB;
     use aptos_executor_test_helpers::integration_test_impl::{
         test_execution_with_storage_impl, test_execution_with_storage_impl_inner,
@@ -87,13 +98,17 @@ mod dbtool_tests {
     use aptos_storage_interface::DbReader;
     use aptos_temppath::TempPath;
     use aptos_types::{
-        state_store::state_key::{inner::StateKeyTag::AccessPath, prefix::StateKeyPrefix},
+        state_store::state_key::{
+            inner::StateKeyTag::AccessPath, prefix::StateKeyPrefix, StateKey,
+        },
         transaction::Version,
     };
     use clap::Parser;
+    use parquet::{file::reader::SerializedFileReader, record::RowAccessor};
     use std::{
         default::Default,
         fs,
+        fs::File,
         ops::Deref,
         path::{Path, PathBuf},
         sync::Arc,
@@ -525,6 +540,8 @@ mod dbtool_tests {
                     BUFFERED_STATE_TARGET_ITEMS_FOR_TEST,
                     1000,
                     Some(internal_indexer_db.clone()),
+                    None,
+                    None,
                 )
                 .unwrap(),
             );
@@ -690,6 +707,158 @@ mod dbtool_tests {
         rt.shutdown_timeout(Duration::from_secs(1));
     }
 
+    #[test]
+    fn test_export() {
+        let db_dir = TempPath::new();
+        let db = test_execution_with_storage_impl_inner(false, db_dir.path());
+        let synced_version = db.get_synced_version().unwrap().unwrap();
+        drop(db);
+
+        let rt = Runtime::new().unwrap();
+        let try_export =
+            |output_dir: &Path, format: &str, chunk_size: &str, extra_args: &[&str]| {
+                rt.block_on(
+                    DBTool::try_parse_from(
+                        [
+                            "aptos-db-tool",
+                            "export",
+                            "--db-dir",
+                            db_dir.path().to_str().unwrap(),
+                            "--output-dir",
+                            output_dir.to_str().unwrap(),
+                            "--format",
+                            format,
+                            "--chunk-size",
+                            chunk_size,
+                        ]
+                        .iter()
+                        .chain(extra_args),
+                    )?
+                    .run(),
+                )
+            };
+        let export = |output_dir: &Path, format: &str, extra_args: &[&str]| {
+            try_export(output_dir, format, "5", extra_args).unwrap();
+        };
+        let num_chunks = (synced_version as usize) / 5 + 1;
+        let list_files = |dir: PathBuf| {
+            let mut files: Vec<_> = fs::read_dir(dir)
+                .unwrap()
+                .map(|entry| entry.unwrap().path())
+                .collect();
+            files.sort();
+            files
+        };
+        let file_name = |file: &PathBuf| file.file_name().unwrap().to_str().unwrap().to_string();
+
+        // Export a part of the versions to csv, the chunks are aligned to the chunk size
+        let csv_dir = TempPath::new();
+        export(csv_dir.path(), "csv", &[
+            "--start-version",
+            "3",
+            "--end-version",
+            "11",
+        ]);
+        let txn_files: Vec<_> = list_files(csv_dir.path().join("transactions"))
+            .iter()
+            .map(file_name)
+            .collect();
+        assert_eq!(txn_files, vec![
+            "00000000000000000003_00000000000000000004.csv",
+            "00000000000000000005_00000000000000000009.csv",
+            "00000000000000000010_00000000000000000011.csv",
+        ]);
+
+        // Export all versions, and verify one row is written per transaction. The existing
+        // complete chunk is skipped, while the partial ones are superseded.
+        let modified_time = fs::metadata(csv_dir.path().join("transactions").join(&txn_files[1]))
+            .unwrap()
+            .modified()
+            .unwrap();
+        export(csv_dir.path(), "csv", &[]);
+        let txn_files = list_files(csv_dir.path().join("transactions"));
+        assert_eq!(txn_files.len(), num_chunks);
+        assert_eq!(
+            fs::metadata(&txn_files[1]).unwrap().modified().unwrap(),
+            modified_time
+        );
+        let num_rows: u64 = txn_files
+            .iter()
+            .map(|file| csv::Reader::from_path(file).unwrap().records().count() as u64)
+            .sum();
+        assert_eq!(num_rows, synced_version + 1);
+        for table in ["events", "write_set_changes", "fee_statements"] {
+            assert_eq!(list_files(csv_dir.path().join(table)).len(), num_chunks);
+        }
+
+        // Exporting again resumes from the existing files, i.e., nothing is rewritten
+        let modified_time = fs::metadata(&txn_files[0]).unwrap().modified().unwrap();
+        export(csv_dir.path(), "csv", &[]);
+        assert_eq!(
+            fs::metadata(&txn_files[0]).unwrap().modified().unwrap(),
+            modified_time
+        );
+
+        // Resuming with a different chunk size is refused, and chunk sizes beyond the
+        // request limit are rejected
+        assert!(try_export(csv_dir.path(), "csv", "10", &[]).is_err());
+        for chunk_size in ["0", "20001"] {
+            assert!(try_export(&csv_dir.path().join("other"), "csv", chunk_size, &[]).is_err());
+        }
+
+        // Export to parquet, and read the files back
+        let parquet_dir = TempPath::new();
+        export(parquet_dir.path(), "parquet", &[]);
+        for table in [
+            "transactions",
+            "events",
+            "write_set_changes",
+            "fee_statements",
+        ] {
+            let files = list_files(parquet_dir.path().join(table));
+            assert_eq!(files.len(), num_chunks);
+            assert!(files
+                .iter()
+                .all(|file| file.extension().unwrap() == "parquet"));
+        }
+        let read_parquet = |table: &str| {
+            list_files(parquet_dir.path().join(table))
+                .into_iter()
+                .flat_map(|file| {
+                    SerializedFileReader::new(File::open(file).unwrap())
+                        .unwrap()
+                        .into_iter()
+                        .map(|row| row.unwrap())
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>()
+        };
+        let txn_rows = read_parquet("transactions");
+        let versions: Vec<_> = txn_rows
+            .iter()
+            .map(|row| row.get_ulong(0).unwrap())
+            .collect();
+        assert_eq!(versions, (0..=synced_version).collect::<Vec<_>>());
+        let num_events: u64 = txn_rows.iter().map(|row| row.get_ulong(9).unwrap()).sum();
+        assert_eq!(read_parquet("events").len() as u64, num_events);
+        let num_write_set_changes: u64 =
+            txn_rows.iter().map(|row| row.get_ulong(10).unwrap()).sum();
+        let write_set_rows = read_parquet("write_set_changes");
+        assert_eq!(write_set_rows.len() as u64, num_write_set_changes);
+        // State keys are written as hex encoded BCS
+        for row in write_set_rows {
+            let state_key: StateKey =
+                bcs::from_bytes(&hex::decode(row.get_string(3).unwrap()).unwrap()).unwrap();
+            assert_eq!(
+                &state_key.hash().to_hex_literal(),
+                row.get_string(2).unwrap()
+            );
+        }
+        // Transactions without a sender (e.g., block metadata) have null values
+        assert!(txn_rows.iter().any(|row| row.get_string(3).is_err()));
+        rt.shutdown_timeout(Duration::from_secs(1));
+    }
+
     fn dir_size<P: AsRef<Path>>(path: P) -> u64 {
         let mut size = 0;
 
@@ -9,8 +9,8 @@ use aptos_config::config::internal_indexer_db_config::InternalIndexerDBConfig;
 use aptos_db_indexer_schemas::{
     metadata::{MetadataKey, MetadataValue, StateSnapshotProgress},
     schema::{
-        event_by_key::EventByKeySchema, event_by_version::EventByVersionSchema,
-        event_sequence_number::EventSequenceNumberSchema,
+        event_by_key::EventByKeySchema, event_by_type::EventByTypeSchema,
+        event_by_version::EventByVersionSchema, event_sequence_number::EventSequenceNumberSchema,
         indexer_metadata::InternalIndexerMetadataSchema, state_keys::StateKeysSchema,
         transaction_by_account::TransactionByAccountSchema,
         translated_v1_event::TranslatedV1EventSchema,
@@ -38,10 +38,12 @@ use aptos_types::{
     transaction::{AccountTransactionsWithProof, Transaction, Version},
     write_set::{TransactionWrite, WriteSet},
 };
+use move_core_types::language_storage::TypeTag;
 use std::{
     cmp::min,
     collections::HashSet,
     sync::{
+        atomic::{AtomicBool, Ordering},
         mpsc::{self, Receiver, Sender},
         Arc,
     },
@@ -126,6 +128,10 @@ impl InternalIndexerDB {
         self.get_version(&MetadataKey::EventV2TranslationVersion)
     }
 
+    pub fn get_event_by_type_version(&self) -> Result<Option<Version>> {
+        self.get_version(&MetadataKey::EventByTypeVersion)
+    }
+
     pub fn event_enabled(&self) -> bool {
         self.config.enable_event
     }
@@ -142,6 +148,10 @@ impl InternalIndexerDB {
         self.config.enable_statekeys
     }
 
+    pub fn event_by_type_enabled(&self) -> bool {
+        self.config.enable_event_by_type
+    }
+
     pub fn get_inner_db_ref(&self) -> &Arc<DB> {
         &self.db
     }
@@ -170,6 +180,19 @@ impl InternalIndexerDB {
         bail!("ledger version too new")
     }
 
+    /// Unlike the other indices, the event by type index can be backfilled (and thus lag behind
+    /// the latest indexed version), so its own progress is checked.
+    pub fn ensure_cover_event_by_type_version(&self, ledger_version: Version) -> Result<()> {
+        let event_by_type_version = self.get_event_by_type_version()?;
+        if let Some(event_by_type_version) = event_by_type_version {
+            if event_by_type_version >= ledger_version {
+                return Ok(());
+            }
+        }
+
+        bail!("ledger version too new for the event by type index")
+    }
+
     pub fn get_account_transaction_version_iter(
         &self,
         address: AccountAddress,
@@ -243,6 +266,37 @@ impl InternalIndexerDB {
         Ok(result)
     }
 
+    /// Given `type_tag` and `start_version`, returns module events of the type identified by
+    /// transaction version and index among all events emitted by the same transaction. Result
+    /// won't contain records with a transaction version > `ledger_version` and is in ascending
+    /// order.
+    pub fn lookup_events_by_type(
+        &self,
+        type_tag: &TypeTag,
+        start_version: Version,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<
+        Vec<(
+            Version, // transaction version it belongs to
+            u64,     // index among events for the same transaction
+        )>,
+    > {
+        let mut iter = self.db.iter::<EventByTypeSchema>()?;
+        iter.seek(&(type_tag.clone(), start_version, 0))?;
+
+        let mut result = Vec::new();
+        for res in iter.take(limit as usize) {
+            let ((event_type, ver, idx), ()) = res?;
+            if event_type != *type_tag || ver > ledger_version {
+                break;
+            }
+            result.push((ver, idx));
+        }
+
+        Ok(result)
+    }
+
     #[cfg(any(test, feature = "fuzzing"))]
     pub fn get_restore_version_and_progress(
         &self,
@@ -307,6 +361,10 @@ pub struct DBIndexer {
     sender: Sender<Option<SchemaBatch>>,
     committer_handle: Option<thread::JoinHandle<()>>,
     pub event_v2_translation_engine: EventV2TranslationEngine,
+    // True iff the event by type index lags behind the processed transactions (e.g., if the
+    // index was enabled on an existing database). Until it is backfilled, processed batches
+    // don't update the index.
+    event_by_type_backfill_pending: AtomicBool,
 }
 
 impl Drop for DBIndexer {
@@ -332,6 +390,13 @@ impl DBIndexer {
             let committer = DBCommitter::new(db, reciver);
             committer.run();
         });
+        let event_by_type_backfill_pending = indexer_db.event_by_type_enabled()
+            && indexer_db
+                .get_event_by_type_version()
+                .expect("Failed to get the event by type version")
+                != indexer_db
+                    .get_persisted_version()
+                    .expect("Failed to get the persisted version");
 
         Self {
             indexer_db,
@@ -342,9 +407,20 @@ impl DBIndexer {
                 db_reader,
                 internal_indexer_db,
             ),
+            event_by_type_backfill_pending: AtomicBool::new(event_by_type_backfill_pending),
         }
     }
 
+    /// Returns true iff the event by type index must be backfilled before it can
+    /// be updated by processed batches (see `backfill_events_by_type`).
+    pub fn event_by_type_backfill_pending(&self) -> bool {
+        self.event_by_type_backfill_pending.load(Ordering::Acquire)
+    }
+
+    fn event_by_type_indexing_enabled(&self) -> bool {
+        self.indexer_db.event_by_type_enabled() && !self.event_by_type_backfill_pending()
+    }
+
     pub fn get_main_db_lowest_viable_version(&self) -> Result<Version> {
         self.main_db_reader
             .get_first_txn_version()
@@ -468,6 +544,10 @@ impl DBIndexer {
                 })?;
             }
 
+            if self.event_by_type_indexing_enabled() {
+                Self::put_events_by_type(&batch, version, &events)?;
+            }
+
             if self.indexer_db.statekeys_enabled() {
                 writeset.iter().for_each(|(state_key, write_op)| {
                     if write_op.is_creation() || write_op.is_modification() {
@@ -521,6 +601,12 @@ impl DBIndexer {
                 &MetadataValue::Version(version - 1),
             )?;
         }
+        if self.event_by_type_indexing_enabled() {
+            batch.put::<InternalIndexerMetadataSchema>(
+                &MetadataKey::EventByTypeVersion,
+                &MetadataValue::Version(version - 1),
+            )?;
+        }
         batch.put::<InternalIndexerMetadataSchema>(
             &MetadataKey::LatestVersion,
             &MetadataValue::Version(version - 1),
@@ -531,6 +617,69 @@ impl DBIndexer {
         Ok(version)
     }
 
+    /// Backfills the event by type index for a batch of transactions that were already
+    /// processed by the indexer (e.g., if the index was enabled on an existing database).
+    /// Transactions from `start_version` up to (and including) `end_version` are indexed
+    /// in batches, and the next version to backfill is returned. `end_version` must be the
+    /// latest processed version: once it is backfilled, processed batches update the index.
+    pub fn backfill_events_by_type(
+        &self,
+        start_version: Version,
+        end_version: Version,
+    ) -> Result<Version> {
+        let _timer = TIMER
+            .with_label_values(&["backfill_events_by_type"])
+            .start_timer();
+        if start_version > end_version {
+            self.event_by_type_backfill_pending
+                .store(false, Ordering::Release);
+            return Ok(start_version);
+        }
+
+        let num_transactions = min(
+            self.indexer_db.config.batch_size as u64,
+            end_version - start_version + 1,
+        );
+        let event_vec_iter = self
+            .main_db_reader
+            .get_events_iterator(start_version, num_transactions)?;
+        let batch = SchemaBatch::new();
+        let mut version = start_version;
+        for events in event_vec_iter {
+            Self::put_events_by_type(&batch, version, &events?)?;
+            version += 1;
+        }
+        assert_eq!(num_transactions, version - start_version);
+
+        batch.put::<InternalIndexerMetadataSchema>(
+            &MetadataKey::EventByTypeVersion,
+            &MetadataValue::Version(version - 1),
+        )?;
+        self.sender
+            .send(Some(batch))
+            .map_err(|e| AptosDbError::Other(e.to_string()))?;
+        if version > end_version {
+            self.event_by_type_backfill_pending
+                .store(false, Ordering::Release);
+        }
+        Ok(version)
+    }
+
+    /// Adds the module events of the given transaction to the event by type index
+    fn put_events_by_type(
+        batch: &SchemaBatch,
+        version: Version,
+        events: &[ContractEvent],
+    ) -> Result<()> {
+        for (idx, event) in events.iter().enumerate() {
+            if let ContractEvent::V2(v2) = event {
+                batch
+                    .put::<EventByTypeSchema>(&(v2.type_tag().clone(), version, idx as u64), &())?;
+            }
+        }
+        Ok(())
+    }
+
     pub fn translate_event_v2_to_v1(
         &self,
         v2: &ContractEventV2,
@@ -625,6 +774,35 @@ impl DBIndexer {
         self.get_events_by_event_key(event_key, start, order, limit, ledger_version)
     }
 
+    pub fn get_events_by_type(
+        &self,
+        type_tag: &TypeTag,
+        start_version: Version,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<Vec<EventWithVersion>> {
+        self.indexer_db
+            .ensure_cover_event_by_type_version(ledger_version)?;
+        error_if_too_many_requested(limit, MAX_REQUEST_LIMIT)?;
+
+        self.indexer_db
+            .lookup_events_by_type(type_tag, start_version, limit, ledger_version)?
+            .into_iter()
+            .map(|(ver, idx)| {
+                let event = self
+                    .main_db_reader
+                    .get_event_by_version_and_index(ver, idx)?;
+                ensure!(
+                    event.type_tag() == type_tag,
+                    "Index broken, expected type:{}, actual:{}",
+                    type_tag,
+                    event.type_tag()
+                );
+                Ok(EventWithVersion::new(ver, event))
+            })
+            .collect()
+    }
+
     pub fn get_events_by_event_key(
         &self,
         event_key: &EventKey,
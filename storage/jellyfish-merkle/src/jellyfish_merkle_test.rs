@@ -805,3 +805,58 @@ proptest! {
         test_get_leaf_count(keys)
     }
 }
+
+#[test]
+fn test_get_with_multi_proof() {
+    let mut rng: StdRng = StdRng::from_seed([7u8; 32]);
+    let db = MockTreeStore::default();
+    let tree = JellyfishMerkleTree::new(&db);
+
+    // Insert a set of keys into the tree
+    let values: Vec<_> = (0..500).map(|_i| gen_value()).collect();
+    let kvs: Vec<_> = values
+        .iter()
+        .map(|value| (HashValue::random_with_rng(&mut rng), Some(value)))
+        .collect();
+    let (root, batch) = tree
+        .put_value_set_test(kvs.clone(), 0 /* version */)
+        .unwrap();
+    db.write_tree_update_batch(batch).unwrap();
+
+    // Prove a mix of existing and non-existing keys
+    let mut elements: Vec<_> = kvs
+        .iter()
+        .step_by(5)
+        .map(|(key, value)| (*key, value.map(|v| v.0)))
+        .chain((0..50).map(|_| (HashValue::random_with_rng(&mut rng), None)))
+        .collect();
+    elements.sort_by_key(|(key, _)| *key);
+    let keys: Vec<_> = elements.iter().map(|(key, _)| *key).collect();
+    let (values, multi_proof) = tree.get_with_multi_proof(&keys, 0).unwrap();
+
+    // Verify the values and the proof
+    for ((key, expected_hash), value) in elements.iter().zip(values.iter()) {
+        assert_eq!(value.as_ref().map(|v| v.0), *expected_hash);
+        assert_eq!(*value, tree.get_with_proof(*key, 0).unwrap().0);
+    }
+    multi_proof.verify_by_hash(root, &elements).unwrap();
+
+    // Verify the multi-proof is more compact than the individual proofs
+    let num_individual_siblings: usize = keys
+        .iter()
+        .map(|key| tree.get_with_proof(*key, 0).unwrap().1.siblings().len())
+        .sum();
+    assert!(multi_proof.siblings().len() < num_individual_siblings);
+
+    // Verify the proof can't be used to prove other values
+    let mut tampered_elements = elements.clone();
+    tampered_elements[0].1 = Some(HashValue::random_with_rng(&mut rng));
+    assert!(multi_proof
+        .verify_by_hash(root, &tampered_elements)
+        .is_err());
+
+    // Verify unsorted keys are rejected
+    let mut unsorted_keys = keys.clone();
+    unsorted_keys.swap(0, 1);
+    assert!(tree.get_with_multi_proof(&unsorted_keys, 0).is_err());
+}
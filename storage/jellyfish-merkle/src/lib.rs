@@ -89,7 +89,10 @@ use aptos_experimental_runtimes::thread_manager::THREAD_MANAGER;
 use aptos_storage_interface::{db_ensure as ensure, db_other_bail, AptosDbError, Result};
 use aptos_types::{
     nibble::{nibble_path::NibblePath, Nibble, ROOT_NIBBLE_HEIGHT},
-    proof::{SparseMerkleProof, SparseMerkleProofExt, SparseMerkleRangeProof},
+    proof::{
+        NodeInProof, SparseMerkleMultiProof, SparseMerkleProof, SparseMerkleProofExt,
+        SparseMerkleRangeProof,
+    },
     state_store::{state_key::StateKey, state_value::StateValue},
     transaction::Version,
 };
@@ -796,6 +799,162 @@ where
         db_other_bail!("Jellyfish Merkle tree has cyclic graph inside.");
     }
 
+    /// Returns the values (if any) of the given keys, along with a single proof that
+    /// authenticates all of them. The proof paths of the keys are merged, so nodes shared
+    /// by the paths are only read (and included in the proof) once. The keys must be sorted
+    /// in ascending order (without duplicates), and the values are returned in the same order.
+    pub fn get_with_multi_proof(
+        &self,
+        keys: &[HashValue],
+        version: Version,
+    ) -> Result<(
+        Vec<Option<(HashValue, (K, Version))>>,
+        SparseMerkleMultiProof,
+    )> {
+        ensure!(
+            keys.windows(2).all(|pair| pair[0] < pair[1]),
+            "Keys must be sorted and unique."
+        );
+
+        let mut values = Vec::with_capacity(keys.len());
+        let mut keys_and_proofs = Vec::with_capacity(keys.len());
+        if !keys.is_empty() {
+            self.collect_proofs_with_shared_paths(
+                NodeKey::new_empty_path(version),
+                0,
+                0,
+                keys,
+                Vec::with_capacity(8), // reduces reallocation
+                &mut values,
+                &mut keys_and_proofs,
+            )?;
+        }
+
+        let multi_proof = SparseMerkleMultiProof::from_proofs(&keys_and_proofs)
+            .map_err(|error| AptosDbError::Other(error.to_string()))?;
+        Ok((values, multi_proof))
+    }
+
+    /// Collects the values and proofs of the given keys, all of which share the path from
+    /// the root to the node at `node_key`. Keys are split by their next nibble, so every node
+    /// on the merged proof paths is only read once (instead of once per key).
+    fn collect_proofs_with_shared_paths(
+        &self,
+        node_key: NodeKey,
+        nibble_depth: usize,
+        num_consumed_nibbles: usize,
+        keys: &[HashValue],
+        siblings: Vec<NodeInProof>,
+        out_values: &mut Vec<Option<(HashValue, (K, Version))>>,
+        out_keys_and_proofs: &mut Vec<(HashValue, SparseMerkleProof)>,
+    ) -> Result<()> {
+        // We limit the depth here deliberately to avoid potential cyclic graph bugs
+        // in the tree structure.
+        if nibble_depth > ROOT_NIBBLE_HEIGHT {
+            db_other_bail!("Jellyfish Merkle tree has cyclic graph inside.");
+        }
+
+        let node = self
+            .reader
+            .get_node_with_tag(&node_key, "get_multi_proof")
+            .map_err(|err| {
+                if nibble_depth == 0 {
+                    AptosDbError::MissingRootError(node_key.version())
+                } else {
+                    err
+                }
+            })?;
+        match node {
+            Node::Internal(internal_node) => {
+                if internal_node.leaf_count() == 1 {
+                    // Logically this node should be a leaf node, it got pushed down for
+                    // sharding, skip the siblings.
+                    let (only_child_nibble, Child { version, .. }) =
+                        internal_node.children_sorted().next().unwrap();
+                    return self.collect_proofs_with_shared_paths(
+                        node_key.gen_child_node_key(*version, *only_child_nibble),
+                        nibble_depth + 1,
+                        num_consumed_nibbles,
+                        keys,
+                        siblings,
+                        out_values,
+                        out_keys_and_proofs,
+                    );
+                }
+
+                // Split the (sorted) keys into groups that share the next nibble
+                let mut group_start = 0;
+                while group_start < keys.len() {
+                    let queried_child_index = keys[group_start].nibble(num_consumed_nibbles);
+                    let group_end = group_start
+                        + keys[group_start..].partition_point(|key| {
+                            key.nibble(num_consumed_nibbles) == queried_child_index
+                        });
+                    let group_keys = &keys[group_start..group_end];
+
+                    let mut child_siblings = siblings.clone();
+                    let child_node_key = internal_node.get_child_with_siblings(
+                        &node_key,
+                        Nibble::from(queried_child_index),
+                        Some(self.reader),
+                        &mut child_siblings,
+                        nibble_depth * 4,
+                        0,
+                    )?;
+                    match child_node_key {
+                        Some(child_node_key) => self.collect_proofs_with_shared_paths(
+                            child_node_key,
+                            nibble_depth + 1,
+                            num_consumed_nibbles + 1,
+                            group_keys,
+                            child_siblings,
+                            out_values,
+                            out_keys_and_proofs,
+                        )?,
+                        None => {
+                            for key in group_keys {
+                                let proof = SparseMerkleProofExt::new_partial(
+                                    None,
+                                    child_siblings.clone(),
+                                    0,
+                                );
+                                out_values.push(None);
+                                out_keys_and_proofs.push((*key, proof.into()));
+                            }
+                        },
+                    }
+                    group_start = group_end;
+                }
+            },
+            Node::Leaf(leaf_node) => {
+                for key in keys {
+                    out_values.push(
+                        if leaf_node.account_key() == key {
+                            Some((leaf_node.value_hash(), leaf_node.value_index().clone()))
+                        } else {
+                            None
+                        },
+                    );
+                    let proof = SparseMerkleProofExt::new_partial(
+                        Some(leaf_node.clone().into()),
+                        siblings.clone(),
+                        0,
+                    );
+                    out_keys_and_proofs.push((*key, proof.into()));
+                }
+            },
+            Node::Null => {
+                for key in keys {
+                    out_values.push(None);
+                    out_keys_and_proofs
+                        .push((*key, SparseMerkleProofExt::new(None, vec![]).into()));
+                }
+            },
+        }
+
+        Ok(())
+    }
+
     /// Gets the proof that shows a list of keys up to `rightmost_key_to_prove` exist at `version`.
     pub fn get_range_proof(
         &self,
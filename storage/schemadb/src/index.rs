@@ -0,0 +1,179 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module provides typed secondary indexes: an index schema whose entries are derived from
+//! the records of a primary schema, and maintained in the same [`SchemaBatch`] as the primary
+//! records, so that the index is always updated atomically with them.
+//!
+//! To define an index, implement [`SecondaryIndex`] for a marker type:
+//! ```ignore
+//! struct EventByKeyIndex;
+//!
+//! impl SecondaryIndex for EventByKeyIndex {
+//!     type Primary = EventSchema;
+//!     type Index = EventByKeySchema;
+//!
+//!     fn index_entries(key: &Version, event: &ContractEvent) -> Result<Vec<IndexEntry<Self>>> {
+//!         Ok(vec![((*event.key(), event.sequence_number()), *key)])
+//!     }
+//! }
+//! ```
+//! and write the primary records with [`SchemaBatch::put_indexed`] and
+//! [`SchemaBatch::delete_indexed`]. Index entries can then be scanned with
+//! [`DB::iter_prefix`]/[`DB::iter_range`], and [`DB::check_index_consistency`] verifies that the
+//! index matches the primary records.
+
+use crate::{
+    schema::{KeyCodec, Schema, ValueCodec},
+    SchemaBatch, DB,
+};
+use anyhow::Result;
+use aptos_storage_interface::Result as DbResult;
+use std::collections::HashMap;
+
+/// Defines a secondary index over the records of a primary schema.
+pub trait SecondaryIndex: Send + Sync + 'static {
+    /// The schema of the indexed records.
+    type Primary: Schema;
+    /// The schema of the index entries, which must live in the same DB as the primary records.
+    type Index: Schema;
+
+    /// Returns the index entries of a primary record (possibly none, for sparse indexes). Must be
+    /// deterministic, since it's used to find the entries to delete when the record is updated.
+    fn index_entries(
+        key: &<Self::Primary as Schema>::Key,
+        value: &<Self::Primary as Schema>::Value,
+    ) -> Result<Vec<IndexEntry<Self>>>;
+}
+
+/// An entry of the index `I`.
+pub type IndexEntry<I> = (
+    <<I as SecondaryIndex>::Index as Schema>::Key,
+    <<I as SecondaryIndex>::Index as Schema>::Value,
+);
+
+/// The differences between an index and its primary records, found by
+/// [`DB::check_index_consistency`]. An entry with the wrong value is reported as both missing
+/// (with the expected value) and dangling (with the actual value).
+#[derive(Debug, Eq, PartialEq)]
+pub struct IndexInconsistencies<K, V> {
+    /// Entries derived from primary records, which are not in the index.
+    pub missing: Vec<(K, V)>,
+    /// Entries in the index, which are not derived from any primary record.
+    pub dangling: Vec<(K, V)>,
+}
+
+impl<K, V> IndexInconsistencies<K, V> {
+    pub fn is_consistent(&self) -> bool {
+        self.missing.is_empty() && self.dangling.is_empty()
+    }
+}
+
+impl SchemaBatch {
+    /// Adds a primary record to the batch, along with its entries of the index `I`. `old_value` is
+    /// the value being overwritten if any, whose index entries are deleted.
+    pub fn put_indexed<I: SecondaryIndex>(
+        &self,
+        key: &<I::Primary as Schema>::Key,
+        value: &<I::Primary as Schema>::Value,
+        old_value: Option<&<I::Primary as Schema>::Value>,
+    ) -> DbResult<()> {
+        self.put::<I::Primary>(key, value)?;
+        self.put_index_entries::<I>(key, value, old_value)
+    }
+
+    /// Adds the deletion of a primary record to the batch, along with its entries of the index `I`.
+    /// `value` is the value being deleted.
+    pub fn delete_indexed<I: SecondaryIndex>(
+        &self,
+        key: &<I::Primary as Schema>::Key,
+        value: &<I::Primary as Schema>::Value,
+    ) -> DbResult<()> {
+        self.delete::<I::Primary>(key)?;
+        self.delete_index_entries::<I>(key, value)
+    }
+
+    /// Adds the entries of the index `I` of a primary record to the batch, replacing those of
+    /// `old_value`. Useful when a primary schema has more than one index.
+    pub fn put_index_entries<I: SecondaryIndex>(
+        &self,
+        key: &<I::Primary as Schema>::Key,
+        value: &<I::Primary as Schema>::Value,
+        old_value: Option<&<I::Primary as Schema>::Value>,
+    ) -> DbResult<()> {
+        if let Some(old_value) = old_value {
+            // The deletions are applied before the puts, so entries shared by the old and the new
+            // values are kept.
+            self.delete_index_entries::<I>(key, old_value)?;
+        }
+        for (index_key, index_value) in I::index_entries(key, value)? {
+            self.put::<I::Index>(&index_key, &index_value)?;
+        }
+        Ok(())
+    }
+
+    /// Adds the deletions of the entries of the index `I` of a primary record to the batch.
+    pub fn delete_index_entries<I: SecondaryIndex>(
+        &self,
+        key: &<I::Primary as Schema>::Key,
+        value: &<I::Primary as Schema>::Value,
+    ) -> DbResult<()> {
+        for (index_key, _index_value) in I::index_entries(key, value)? {
+            self.delete::<I::Index>(&index_key)?;
+        }
+        Ok(())
+    }
+}
+
+impl DB {
+    /// Scans all the primary records and all the entries of the index `I`, returning the
+    /// differences between them. Holds all the expected index entries in memory.
+    pub fn check_index_consistency<I: SecondaryIndex>(
+        &self,
+    ) -> DbResult<IndexInconsistencies<<I::Index as Schema>::Key, <I::Index as Schema>::Value>>
+    {
+        // Encoded key -> (encoded value, entry).
+        let mut expected = HashMap::new();
+        let mut iter = self.iter::<I::Primary>()?;
+        iter.seek_to_first();
+        for record in iter {
+            let (key, value) = record?;
+            for (index_key, index_value) in I::index_entries(&key, &value)? {
+                expected.insert(
+                    <<I::Index as Schema>::Key as KeyCodec<I::Index>>::encode_key(&index_key)?,
+                    (
+                        <<I::Index as Schema>::Value as ValueCodec<I::Index>>::encode_value(
+                            &index_value,
+                        )?,
+                        (index_key, index_value),
+                    ),
+                );
+            }
+        }
+
+        let mut missing = Vec::new();
+        let mut dangling = Vec::new();
+        let mut iter = self.iter::<I::Index>()?;
+        iter.seek_to_first();
+        for entry in iter {
+            let (index_key, index_value) = entry?;
+            let encoded_key =
+                <<I::Index as Schema>::Key as KeyCodec<I::Index>>::encode_key(&index_key)?;
+            let encoded_value =
+                <<I::Index as Schema>::Value as ValueCodec<I::Index>>::encode_value(&index_value)?;
+            match expected.remove(&encoded_key) {
+                Some((expected_value, _)) if expected_value == encoded_value => {},
+                Some((_, expected_entry)) => {
+                    missing.push(expected_entry);
+                    dangling.push((index_key, index_value));
+                },
+                None => dangling.push((index_key, index_value)),
+            }
+        }
+
+        let mut not_found: Vec<_> = expected.into_iter().collect();
+        not_found.sort_by(|(key1, _), (key2, _)| key1.cmp(key2));
+        missing.extend(not_found.into_iter().map(|(_, (_, entry))| entry));
+        Ok(IndexInconsistencies { missing, dangling })
+    }
+}
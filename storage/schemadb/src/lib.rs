@@ -17,6 +17,7 @@
 mod metrics;
 #[macro_use]
 pub mod schema;
+pub mod index;
 pub mod iterator;
 
 use crate::{
@@ -44,6 +45,7 @@ pub use rocksdb::{
 use std::{
     collections::{HashMap, HashSet},
     iter::Iterator,
+    ops::{Bound, RangeBounds},
     path::Path,
 };
 
@@ -233,6 +235,12 @@ impl DB {
         Ok(Self::log_construct(name, open_mode, inner))
     }
 
+    /// Makes the latest changes of the primary instance visible, by replaying its latest MANIFEST
+    /// and WAL changes. Only valid for DBs opened with `open_cf_as_secondary`.
+    pub fn try_catch_up_with_primary(&self) -> DbResult<()> {
+        self.inner.try_catch_up_with_primary().into_db_res()
+    }
+
     fn log_construct(name: &str, open_mode: OpenMode, inner: rocksdb::DB) -> DB {
         info!(
             rocksdb_name = name,
@@ -313,6 +321,60 @@ impl DB {
         self.iter_with_direction::<S>(opts, ScanDirection::Backward)
     }
 
+    /// Returns a forward [`SchemaIterator`] on a certain schema, over the keys whose binary
+    /// representations are within the binary representations of the `range` bounds. An included
+    /// end bound includes all the keys starting with it, so that a key prefix can be used as one.
+    pub fn iter_range<S: Schema, SK: SeekKeyCodec<S>>(
+        &self,
+        range: impl RangeBounds<SK>,
+    ) -> DbResult<SchemaIterator<S>> {
+        let mut opts = ReadOptions::default();
+        match range.start_bound() {
+            Bound::Included(start) => {
+                opts.set_iterate_lower_bound(<SK as SeekKeyCodec<S>>::encode_seek_key(start)?)
+            },
+            Bound::Excluded(start) => opts.set_iterate_lower_bound(key_successor(
+                <SK as SeekKeyCodec<S>>::encode_seek_key(start)?,
+            )),
+            Bound::Unbounded => {},
+        }
+        match range.end_bound() {
+            Bound::Included(end) => {
+                if let Some(upper_bound) =
+                    prefix_upper_bound(&<SK as SeekKeyCodec<S>>::encode_seek_key(end)?)
+                {
+                    opts.set_iterate_upper_bound(upper_bound);
+                }
+            },
+            Bound::Excluded(end) => {
+                opts.set_iterate_upper_bound(<SK as SeekKeyCodec<S>>::encode_seek_key(end)?)
+            },
+            Bound::Unbounded => {},
+        }
+
+        let mut iter = self.iter_with_opts(opts)?;
+        iter.seek_to_first();
+        Ok(iter)
+    }
+
+    /// Returns a forward [`SchemaIterator`] on a certain schema, over the keys whose binary
+    /// representations start with that of `prefix`.
+    pub fn iter_prefix<S: Schema, SK: SeekKeyCodec<S>>(
+        &self,
+        prefix: &SK,
+    ) -> DbResult<SchemaIterator<S>> {
+        let prefix = <SK as SeekKeyCodec<S>>::encode_seek_key(prefix)?;
+        let mut opts = ReadOptions::default();
+        if let Some(upper_bound) = prefix_upper_bound(&prefix) {
+            opts.set_iterate_upper_bound(upper_bound);
+        }
+        opts.set_iterate_lower_bound(prefix);
+
+        let mut iter = self.iter_with_opts(opts)?;
+        iter.seek_to_first();
+        Ok(iter)
+    }
+
     /// Writes a group of records wrapped in a [`SchemaBatch`].
     pub fn write_schemas(&self, batch: SchemaBatch) -> DbResult<()> {
         // Function to determine if the counter should be sampled based on a sampling percentage
@@ -434,6 +496,24 @@ fn default_write_options() -> rocksdb::WriteOptions {
     opts
 }
 
+/// Returns the smallest key greater than `key`.
+fn key_successor(mut key: Vec<u8>) -> Vec<u8> {
+    key.push(0);
+    key
+}
+
+/// Returns the smallest key greater than all the keys starting with `prefix`, if any.
+fn prefix_upper_bound(prefix: &[u8]) -> Option<Vec<u8>> {
+    let mut upper_bound = prefix.to_vec();
+    while let Some(last) = upper_bound.pop() {
+        if last < u8::MAX {
+            upper_bound.push(last + 1);
+            return Some(upper_bound);
+        }
+    }
+    None
+}
+
 trait DeUnc: AsRef<Path> {
     fn de_unc(&self) -> &Path {
         // `dunce` is needed to "de-UNC" because rocksdb doesn't take Windows UNC paths like `\\?\C:\`
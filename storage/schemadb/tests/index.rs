@@ -0,0 +1,206 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::Result;
+use aptos_schemadb::{
+    define_schema,
+    index::{IndexEntry, IndexInconsistencies, SecondaryIndex},
+    schema::{KeyCodec, Schema, SeekKeyCodec, ValueCodec},
+    SchemaBatch, DB,
+};
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use rocksdb::DEFAULT_COLUMN_FAMILY_NAME;
+
+// Accounts by id, indexed by (owner, id).
+define_schema!(AccountSchema, AccountId, Account, "Account");
+define_schema!(AccountByOwnerSchema, OwnerAndId, (), "AccountByOwner");
+
+#[derive(Debug, Eq, PartialEq)]
+struct AccountId(u32);
+
+#[derive(Debug, Eq, PartialEq)]
+struct Account {
+    owner: u32,
+    balance: u32,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+struct OwnerAndId(u32, u32);
+
+struct Owner(u32);
+
+impl KeyCodec<AccountSchema> for AccountId {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        Ok(self.0.to_be_bytes().to_vec())
+    }
+
+    fn decode_key(data: &[u8]) -> Result<Self> {
+        let mut reader = std::io::Cursor::new(data);
+        Ok(AccountId(reader.read_u32::<BigEndian>()?))
+    }
+}
+
+impl ValueCodec<AccountSchema> for Account {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        let mut bytes = vec![];
+        bytes.write_u32::<BigEndian>(self.owner)?;
+        bytes.write_u32::<BigEndian>(self.balance)?;
+        Ok(bytes)
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self> {
+        let mut reader = std::io::Cursor::new(data);
+        Ok(Account {
+            owner: reader.read_u32::<BigEndian>()?,
+            balance: reader.read_u32::<BigEndian>()?,
+        })
+    }
+}
+
+impl KeyCodec<AccountByOwnerSchema> for OwnerAndId {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        let mut bytes = vec![];
+        bytes.write_u32::<BigEndian>(self.0)?;
+        bytes.write_u32::<BigEndian>(self.1)?;
+        Ok(bytes)
+    }
+
+    fn decode_key(data: &[u8]) -> Result<Self> {
+        let mut reader = std::io::Cursor::new(data);
+        Ok(OwnerAndId(
+            reader.read_u32::<BigEndian>()?,
+            reader.read_u32::<BigEndian>()?,
+        ))
+    }
+}
+
+impl ValueCodec<AccountByOwnerSchema> for () {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        Ok(vec![])
+    }
+
+    fn decode_value(_data: &[u8]) -> Result<Self> {
+        Ok(())
+    }
+}
+
+impl SeekKeyCodec<AccountByOwnerSchema> for Owner {
+    fn encode_seek_key(&self) -> Result<Vec<u8>> {
+        Ok(self.0.to_be_bytes().to_vec())
+    }
+}
+
+struct AccountByOwnerIndex;
+
+impl SecondaryIndex for AccountByOwnerIndex {
+    type Primary = AccountSchema;
+    type Index = AccountByOwnerSchema;
+
+    fn index_entries(key: &AccountId, value: &Account) -> Result<Vec<IndexEntry<Self>>> {
+        Ok(vec![(OwnerAndId(value.owner, key.0), ())])
+    }
+}
+
+fn open_db(dir: &aptos_temppath::TempPath) -> DB {
+    let mut db_opts = rocksdb::Options::default();
+    db_opts.create_if_missing(true);
+    db_opts.create_missing_column_families(true);
+    DB::open(
+        dir.path(),
+        "test",
+        vec![
+            DEFAULT_COLUMN_FAMILY_NAME,
+            AccountSchema::COLUMN_FAMILY_NAME,
+            AccountByOwnerSchema::COLUMN_FAMILY_NAME,
+        ],
+        &db_opts,
+    )
+    .expect("Failed to open DB.")
+}
+
+fn account(owner: u32, balance: u32) -> Account {
+    Account { owner, balance }
+}
+
+fn accounts_of(db: &DB, owner: u32) -> Vec<u32> {
+    db.iter_prefix::<AccountByOwnerSchema, _>(&Owner(owner))
+        .unwrap()
+        .map(|row| (row.unwrap().0).1)
+        .collect()
+}
+
+#[test]
+fn test_put_and_delete_indexed() {
+    let tmpdir = aptos_temppath::TempPath::new();
+    let db = open_db(&tmpdir);
+
+    let batch = SchemaBatch::new();
+    for (id, owner) in [(1, 10), (2, 20), (3, 10)] {
+        batch
+            .put_indexed::<AccountByOwnerIndex>(&AccountId(id), &account(owner, 0), None)
+            .unwrap();
+    }
+    db.write_schemas(batch).unwrap();
+    assert_eq!(accounts_of(&db, 10), [1, 3]);
+    assert_eq!(accounts_of(&db, 20), [2]);
+
+    // Transfer account 1 to owner 20, and update the balance of account 2, which keeps its entry.
+    let batch = SchemaBatch::new();
+    batch
+        .put_indexed::<AccountByOwnerIndex>(&AccountId(1), &account(20, 0), Some(&account(10, 0)))
+        .unwrap();
+    batch
+        .put_indexed::<AccountByOwnerIndex>(&AccountId(2), &account(20, 5), Some(&account(20, 0)))
+        .unwrap();
+    db.write_schemas(batch).unwrap();
+    assert_eq!(accounts_of(&db, 10), [3]);
+    assert_eq!(accounts_of(&db, 20), [1, 2]);
+    assert_eq!(
+        db.get::<AccountSchema>(&AccountId(2)).unwrap(),
+        Some(account(20, 5))
+    );
+
+    let batch = SchemaBatch::new();
+    batch
+        .delete_indexed::<AccountByOwnerIndex>(&AccountId(3), &account(10, 0))
+        .unwrap();
+    db.write_schemas(batch).unwrap();
+    assert_eq!(accounts_of(&db, 10), Vec::<u32>::new());
+    assert_eq!(db.get::<AccountSchema>(&AccountId(3)).unwrap(), None);
+
+    assert!(db
+        .check_index_consistency::<AccountByOwnerIndex>()
+        .unwrap()
+        .is_consistent());
+}
+
+#[test]
+fn test_check_index_consistency() {
+    let tmpdir = aptos_temppath::TempPath::new();
+    let db = open_db(&tmpdir);
+
+    let batch = SchemaBatch::new();
+    for (id, owner) in [(1, 10), (2, 20), (3, 30)] {
+        batch
+            .put_indexed::<AccountByOwnerIndex>(&AccountId(id), &account(owner, 0), None)
+            .unwrap();
+    }
+    db.write_schemas(batch).unwrap();
+
+    // Write records bypassing the index: a new account without entry, an entry without account
+    // and an account whose owner changed without its entry being updated.
+    db.put::<AccountSchema>(&AccountId(4), &account(40, 0))
+        .unwrap();
+    db.put::<AccountByOwnerSchema>(&OwnerAndId(50, 5), &())
+        .unwrap();
+    db.put::<AccountSchema>(&AccountId(2), &account(60, 0))
+        .unwrap();
+
+    assert_eq!(
+        db.check_index_consistency::<AccountByOwnerIndex>().unwrap(),
+        IndexInconsistencies {
+            missing: vec![(OwnerAndId(40, 4), ()), (OwnerAndId(60, 2), ())],
+            dangling: vec![(OwnerAndId(20, 2), ()), (OwnerAndId(50, 5), ())],
+        }
+    );
+}
@@ -530,3 +530,61 @@ fn test_iter_with_upper_bound() {
     iter.seek(&KeyPrefix1(1)).unwrap();
     assert_eq!(collect_values_mut(&mut iter), [122, 123, 125]);
 }
+
+#[test]
+fn test_iter_range() {
+    let db = TestDB::new();
+
+    let iter = db
+        .iter_range::<TestSchema, _>(TestKey(1, 0, 2)..TestKey(1, 1, 2))
+        .unwrap();
+    assert_eq!(collect_values(iter), [102, 104, 110]);
+
+    let iter = db
+        .iter_range::<TestSchema, _>(TestKey(1, 0, 2)..=TestKey(1, 1, 2))
+        .unwrap();
+    assert_eq!(collect_values(iter), [102, 104, 110, 112]);
+
+    let iter = db
+        .iter_range::<TestSchema, _>((
+            std::ops::Bound::Excluded(TestKey(1, 0, 2)),
+            std::ops::Bound::Unbounded,
+        ))
+        .unwrap();
+    assert_eq!(collect_values(iter), [104, 110, 112, 114, 200, 202]);
+
+    let iter = db.iter_range::<TestSchema, _>(..KeyPrefix2(1, 1)).unwrap();
+    assert_eq!(collect_values(iter), [100, 102, 104]);
+
+    let iter = db.iter_range::<TestSchema, _>(..=KeyPrefix1(1)).unwrap();
+    assert_eq!(collect_values(iter), [100, 102, 104, 110, 112, 114]);
+
+    let iter = db.iter_range::<TestSchema, _>(KeyPrefix1(2)..).unwrap();
+    assert_eq!(collect_values(iter), [200, 202]);
+
+    let iter = db
+        .iter_range::<TestSchema, _>(TestKey(1, 1, 3)..TestKey(1, 1, 4))
+        .unwrap();
+    assert_eq!(collect_values(iter), EMPTY);
+}
+
+#[test]
+fn test_iter_prefix() {
+    let db = TestDB::new();
+
+    let iter = db.iter_prefix::<TestSchema, _>(&KeyPrefix1(1)).unwrap();
+    assert_eq!(collect_values(iter), [100, 102, 104, 110, 112, 114]);
+
+    let iter = db.iter_prefix::<TestSchema, _>(&KeyPrefix2(1, 1)).unwrap();
+    assert_eq!(collect_values(iter), [110, 112, 114]);
+
+    let iter = db.iter_prefix::<TestSchema, _>(&KeyPrefix2(2, 1)).unwrap();
+    assert_eq!(collect_values(iter), EMPTY);
+
+    db.put::<TestSchema>(&TestKey(u32::MAX, 0, 0), &TestValue(900))
+        .unwrap();
+    let iter = db
+        .iter_prefix::<TestSchema, _>(&KeyPrefix1(u32::MAX))
+        .unwrap();
+    assert_eq!(collect_values(iter), [900]);
+}
@@ -13,8 +13,9 @@ use aptos_types::{
     event::EventKey,
     ledger_info::LedgerInfoWithSignatures,
     proof::{
-        AccumulatorConsistencyProof, SparseMerkleProof, SparseMerkleProofExt,
-        SparseMerkleRangeProof, TransactionAccumulatorRangeProof, TransactionAccumulatorSummary,
+        AccumulatorConsistencyProof, SparseMerkleMultiProof, SparseMerkleProof,
+        SparseMerkleProofExt, SparseMerkleRangeProof, TransactionAccumulatorRangeProof,
+        TransactionAccumulatorSummary,
     },
     state_proof::StateProof,
     state_store::{
@@ -31,14 +32,14 @@ use aptos_types::{
     write_set::WriteSet,
 };
 use serde::{Deserialize, Serialize};
-use std::{collections::HashMap, sync::Arc};
+use std::{collections::HashMap, path::Path, sync::Arc};
 use thiserror::Error;
 
 pub mod block_info;
 pub mod chunk_to_commit;
 pub mod errors;
 mod ledger_summary;
-mod metrics;
+pub mod metrics;
 #[cfg(any(test, feature = "fuzzing"))]
 pub mod mock;
 pub mod state_store;
@@ -178,6 +179,12 @@ pub trait DbReader: Send + Sync {
         /// [AptosDB::get_first_write_set_version]: ../aptosdb/struct.AptosDB.html#method.get_first_write_set_version
         fn get_first_write_set_version(&self) -> Result<Option<Version>>;
 
+        /// See [AptosDB::get_retained_versions].
+        ///
+        /// [AptosDB::get_retained_versions]: ../aptosdb/struct.AptosDB.html#method.get_retained_versions
+        fn get_retained_versions(&self, start_version: Version, limit: u64)
+            -> Result<Vec<Version>>;
+
         /// See [AptosDB::get_transaction_outputs].
         ///
         /// [AptosDB::get_transaction_outputs]: ../aptosdb/struct.AptosDB.html#method.get_transaction_outputs
@@ -374,6 +381,26 @@ pub trait DbReader: Send + Sync {
             root_depth: usize,
         ) -> Result<(Option<StateValue>, SparseMerkleProofExt)>;
 
+        /// Gets the state values of the given state keys at the given version, along with a
+        /// single sparse Merkle multi-proof that authenticates all of them against the state
+        /// Merkle tree root at the version. The state keys must be sorted by their hashes in
+        /// ascending order (without duplicates), and the values are returned in the same order.
+        fn get_state_values_with_multi_proof_by_version(
+            &self,
+            state_keys: &[StateKey],
+            version: Version,
+        ) -> Result<(Vec<Option<StateValue>>, SparseMerkleMultiProof)>;
+
+        /// Gets a state value by state key and version, along with the proof if the state Merkle
+        /// tree at that version is available. Otherwise (i.e. the version is beyond the state
+        /// Merkle prune window), the value is read from the state KV DB and returned unproven, if
+        /// unproven historical state reads are enabled.
+        fn get_state_value_with_optional_proof_by_version(
+            &self,
+            state_key: &StateKey,
+            version: Version,
+        ) -> Result<StateValueWithOptionalProof>;
+
         /// Gets the latest LedgerView no matter if db has been bootstrapped.
         /// Used by the Db-bootstrapper.
         fn get_pre_committed_ledger_summary(&self) -> Result<LedgerSummary>;
@@ -434,6 +461,10 @@ pub trait DbReader: Send + Sync {
         /// Returns if the state store pruner is enabled.
         fn is_state_merkle_pruner_enabled(&self) -> Result<bool>;
 
+        /// Returns if the state Merkle tree at the given version is already pruned, i.e., state
+        /// values at that version can only be read unproven.
+        fn is_state_merkle_pruned(&self, version: Version) -> Result<bool>;
+
         /// Get the state prune window config value.
         fn get_epoch_snapshot_prune_window(&self) -> Result<usize>;
 
@@ -449,6 +480,14 @@ pub trait DbReader: Send + Sync {
         /// Returns whether the internal indexer DB has been enabled or not
         fn indexer_enabled(&self) -> bool;
 
+        /// Returns whether the state KV DB is sharded or not. Note: some APIs (e.g., iterating
+        /// over state values by key prefix) are not supported by sharded DBs.
+        fn state_kv_sharding_enabled(&self) -> bool;
+
+        /// Returns whether the DB is running in partial state mode, i.e., only the state values
+        /// of tracked state keys are persisted. Such a DB can't serve state to other nodes.
+        fn partial_state_enabled(&self) -> bool;
+
         /// Returns state storage usage at the end of an epoch.
         fn get_state_storage_usage(&self, version: Option<Version>) -> Result<StateStorageUsage>;
 
@@ -583,6 +622,62 @@ pub trait DbWriter: Send + Sync {
     ) -> Result<()> {
         unimplemented!()
     }
+
+    /// Creates a consistent checkpoint of the live DB under `checkpoint_path`, which must not
+    /// exist yet. Commits are paused while the checkpoint is being created. `report_stage` is
+    /// called with the current stage (e.g., the name of the DB being checkpointed), so that
+    /// callers can report the progress.
+    fn create_online_checkpoint(
+        &self,
+        checkpoint_path: &Path,
+        report_stage: &dyn Fn(&str),
+    ) -> Result<DbCheckpointInfo> {
+        unimplemented!()
+    }
+}
+
+/// Describes a checkpoint created by `DbWriter::create_online_checkpoint()`.
+#[derive(Clone, Debug)]
+pub struct DbCheckpointInfo {
+    /// The latest committed version in the checkpoint.
+    pub version: Version,
+    /// The latest ledger info in the checkpoint.
+    pub ledger_info: Option<LedgerInfoWithSignatures>,
+}
+
+/// A state value returned by `DbReader::get_state_value_with_optional_proof_by_version()`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum StateValueWithOptionalProof {
+    /// The value, with the proof against the state root hash at the requested version.
+    Proven(Option<StateValue>, SparseMerkleProof),
+    /// The value read from the state KV DB, which can't be verified because the state Merkle tree
+    /// at the requested version is pruned.
+    Unproven(Option<StateValue>),
+}
+
+impl StateValueWithOptionalProof {
+    pub fn value(&self) -> Option<&StateValue> {
+        match self {
+            Self::Proven(value, _) | Self::Unproven(value) => value.as_ref(),
+        }
+    }
+
+    pub fn into_value(self) -> Option<StateValue> {
+        match self {
+            Self::Proven(value, _) | Self::Unproven(value) => value,
+        }
+    }
+
+    pub fn proof(&self) -> Option<&SparseMerkleProof> {
+        match self {
+            Self::Proven(_, proof) => Some(proof),
+            Self::Unproven(_) => None,
+        }
+    }
+
+    pub fn is_proven(&self) -> bool {
+        matches!(self, Self::Proven(..))
+    }
 }
 
 #[derive(Clone)]
@@ -3,7 +3,10 @@
 
 #![forbid(unsafe_code)]
 
-use aptos_metrics_core::{exponential_buckets, register_histogram_vec, HistogramVec};
+use aptos_metrics_core::{
+    exponential_buckets, register_histogram_vec, register_int_counter_vec, HistogramVec,
+    IntCounter, IntCounterVec,
+};
 use once_cell::sync::Lazy;
 
 pub static TIMER: Lazy<HistogramVec> = Lazy::new(|| {
@@ -15,3 +18,19 @@ pub static TIMER: Lazy<HistogramVec> = Lazy::new(|| {
     )
     .unwrap()
 });
+
+pub static STATE_CACHE_READS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_storage_interface_state_cache_reads",
+        "Number of reads from the cached state view, by whether the value was already cached.",
+        &["result"]
+    )
+    .unwrap()
+});
+
+/// [STATE_CACHE_READS] resolved by label up front, as it's counted on every state read.
+pub static STATE_CACHE_HITS: Lazy<IntCounter> =
+    Lazy::new(|| STATE_CACHE_READS.with_label_values(&["hit"]));
+
+pub static STATE_CACHE_MISSES: Lazy<IntCounter> =
+    Lazy::new(|| STATE_CACHE_READS.with_label_values(&["miss"]));
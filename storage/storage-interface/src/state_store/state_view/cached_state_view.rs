@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    metrics::TIMER,
+    metrics::{STATE_CACHE_HITS, STATE_CACHE_MISSES, TIMER},
     state_store::state_view::{async_proof_fetcher::AsyncProofFetcher, db_state_view::DbStateView},
     DbReader,
 };
@@ -206,7 +206,27 @@ impl CachedStateView {
                 .into_iter()
                 .for_each(|key| {
                     s.spawn(move |_| {
-                        self.get_state_value_bytes(key).expect("Must succeed.");
+                        self.get_or_fetch_state_value(key).expect("Must succeed.");
+                    })
+                });
+        });
+        Ok(())
+    }
+
+    /// Fetches the values of the given keys into the cache in parallel, so that reads of them
+    /// (e.g. during block execution) are served from memory. Reads done here are not counted as
+    /// cache hits or misses.
+    pub fn prime_cache_by_keys<'a, T: IntoIterator<Item = &'a StateKey> + Send>(
+        &self,
+        keys: T,
+    ) -> Result<()> {
+        IO_POOL.scope(|s| {
+            keys.into_iter()
+                .collect::<HashSet<_>>()
+                .into_iter()
+                .for_each(|key| {
+                    s.spawn(move |_| {
+                        self.get_or_fetch_state_value(key).expect("Must succeed.");
                     })
                 });
         });
@@ -221,6 +241,31 @@ impl CachedStateView {
         }
     }
 
+    /// Returns the value of the key from the cache, populating the cache first if needed. The
+    /// returned flag is true if the value was already cached.
+    fn get_or_fetch_state_value(&self, state_key: &StateKey) -> Result<(bool, Option<StateValue>)> {
+        // First check if the cache has the state value.
+        if let Some(version_and_value_opt) = self
+            .sharded_state_cache
+            .shard(state_key.get_shard_id())
+            .get(state_key)
+        {
+            // This can return None, which means the value has been deleted from the DB.
+            let value_opt = &version_and_value_opt.1;
+            return Ok((true, value_opt.clone()));
+        }
+        let version_and_state_value_option =
+            self.get_version_and_state_value_internal(state_key)?;
+        // Update the cache if still empty
+        let new_version_and_value = self
+            .sharded_state_cache
+            .shard(state_key.get_shard_id())
+            .entry(state_key.clone())
+            .or_insert(version_and_state_value_option);
+        let value_opt = &new_version_and_value.1;
+        Ok((false, value_opt.clone()))
+    }
+
     fn get_version_and_state_value_internal(
         &self,
         state_key: &StateKey,
@@ -307,26 +352,13 @@ impl TStateView for CachedStateView {
 
     fn get_state_value(&self, state_key: &StateKey) -> Result<Option<StateValue>> {
         let _timer = TIMER.with_label_values(&["get_state_value"]).start_timer();
-        // First check if the cache has the state value.
-        if let Some(version_and_value_opt) = self
-            .sharded_state_cache
-            .shard(state_key.get_shard_id())
-            .get(state_key)
-        {
-            // This can return None, which means the value has been deleted from the DB.
-            let value_opt = &version_and_value_opt.1;
-            return Ok(value_opt.clone());
+        let (cache_hit, value_opt) = self.get_or_fetch_state_value(state_key)?;
+        if cache_hit {
+            STATE_CACHE_HITS.inc();
+        } else {
+            STATE_CACHE_MISSES.inc();
         }
-        let version_and_state_value_option =
-            self.get_version_and_state_value_internal(state_key)?;
-        // Update the cache if still empty
-        let new_version_and_value = self
-            .sharded_state_cache
-            .shard(state_key.get_shard_id())
-            .entry(state_key.clone())
-            .or_insert(version_and_state_value_option);
-        let value_opt = &new_version_and_value.1;
-        Ok(value_opt.clone())
+        Ok(value_opt)
     }
 
     fn get_usage(&self) -> Result<StateStorageUsage> {
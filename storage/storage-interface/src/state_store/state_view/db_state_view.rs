@@ -11,7 +11,10 @@ use aptos_types::{
     },
     transaction::Version,
 };
-use std::sync::Arc;
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
 
 #[derive(Clone)]
 pub struct DbStateView {
@@ -87,6 +90,58 @@ impl DbStateViewAtVersion for Arc<dyn DbReader> {
     }
 }
 
+/// A state view that reads the state values from the state KV DB, so that it keeps serving them
+/// at versions whose state Merkle tree is already pruned (as long as the values themselves aren't
+/// pruned). If `mark_unproven` is set, it remembers whether any value it returned can't be proven
+/// anymore.
+pub struct OptionalProofDbStateView {
+    db: Arc<dyn DbReader>,
+    version: Version,
+    state_merkle_pruned: bool,
+    unproven: AtomicBool,
+}
+
+impl OptionalProofDbStateView {
+    pub fn new(
+        db: Arc<dyn DbReader>,
+        version: Version,
+        mark_unproven: bool,
+    ) -> StateViewResult<Self> {
+        let state_merkle_pruned = mark_unproven && db.is_state_merkle_pruned(version)?;
+        Ok(Self {
+            db,
+            version,
+            state_merkle_pruned,
+            unproven: AtomicBool::new(false),
+        })
+    }
+
+    /// Whether any value returned by this view so far can't be proven anymore.
+    pub fn is_unproven(&self) -> bool {
+        self.unproven.load(Ordering::Relaxed)
+    }
+}
+
+impl TStateView for OptionalProofDbStateView {
+    type Key = StateKey;
+
+    fn get_state_value(&self, state_key: &StateKey) -> StateViewResult<Option<StateValue>> {
+        let value = self
+            .db
+            .get_state_value_by_version(state_key, self.version)?;
+        if self.state_merkle_pruned {
+            self.unproven.store(true, Ordering::Relaxed);
+        }
+        Ok(value)
+    }
+
+    fn get_usage(&self) -> StateViewResult<StateStorageUsage> {
+        self.db
+            .get_state_storage_usage(self.version)
+            .map_err(Into::into)
+    }
+}
+
 pub trait VerifiedStateViewAtVersion {
     fn verified_state_view_at_version(
         &self,
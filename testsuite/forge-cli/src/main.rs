@@ -5,13 +5,13 @@
 #![allow(clippy::field_reassign_with_default)]
 
 use anyhow::{bail, format_err, Context, Result};
-use aptos_forge::{config::ForgeConfig, Options, *};
+use aptos_forge::{args::TransactionTypeArg, config::ForgeConfig, Options, *};
 use aptos_logger::Level;
 use clap::{Parser, Subcommand};
 use futures::{future, FutureExt};
 use rand::{rngs::ThreadRng, seq::SliceRandom, Rng};
 use serde_json::{json, Value};
-use std::{self, env, num::NonZeroUsize, process, time::Duration};
+use std::{self, env, num::NonZeroUsize, path::PathBuf, process, time::Duration};
 use sugars::{boxed, hmap};
 use suites::{
     dag::get_dag_test,
@@ -56,6 +56,12 @@ struct Args {
         default_value = "land_blocking"
     )]
     suite: String,
+    #[clap(
+        long,
+        num_args = 1..,
+        help = "Workloads defined in YAML/JSON spec files, to emit with equal weights instead of the transaction mix of the test suite"
+    )]
+    workload_spec: Vec<PathBuf>,
     #[clap(long, num_args = 0..)]
     changelog: Option<Vec<String>>,
 
@@ -268,6 +274,19 @@ fn main() -> Result<()> {
             if let Some(num_validator_fullnodes) = args.num_validator_fullnodes {
                 test_suite = test_suite.with_initial_fullnode_count(num_validator_fullnodes)
             }
+            if !args.workload_spec.is_empty() {
+                let transaction_mix_per_phase =
+                    TransactionTypeArg::workload_specs_to_transaction_mix_per_phase(
+                        &args.workload_spec,
+                        &[],
+                        &[],
+                    )
+                    .context("Failed to load workload specs")?;
+                let previous_emit_job = test_suite.get_emit_job().clone();
+                test_suite = test_suite.with_emit_job(
+                    previous_emit_job.transaction_mix_per_phase(transaction_mix_per_phase),
+                );
+            }
 
             // Run the test suite
             match test_cmd {
@@ -231,6 +231,7 @@ impl TransactionWorkload {
                 EmitJobMode::ConstTps { tps } => format!("T:{:.1}k", tps as f32 / 1000.0),
                 EmitJobMode::WaveTps { average_tps, .. } =>
                     format!("T:~{:.1}k", average_tps as f32 / 1000.0),
+                EmitJobMode::TraceReplay { time_scale, .. } => format!("R:{:.1}x", time_scale),
             },
             // ,
         )
@@ -13,6 +13,7 @@ use crate::{
     transaction::{AccountTransactionsWithProof, Version},
 };
 use anyhow::Result;
+use move_core_types::language_storage::TypeTag;
 
 #[derive(Clone, Copy, Eq, PartialEq)]
 pub enum Order {
@@ -43,6 +44,14 @@ pub trait IndexerReader: Send + Sync {
         ledger_version: Version,
     ) -> Result<Vec<EventWithVersion>>;
 
+    fn get_events_by_type(
+        &self,
+        type_tag: &TypeTag,
+        start_version: Version,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<Vec<EventWithVersion>>;
+
     fn get_account_transactions(
         &self,
         address: AccountAddress,
@@ -100,6 +100,9 @@ pub enum FeatureFlag {
     /// AIP-105 (https://github.com/aptos-foundation/AIPs/blob/main/aips/aip-105.md)
     NATIVE_MEMORY_OPERATIONS = 80,
     ENABLE_LOADER_V2 = 81,
+    /// Executes supported framework entry functions natively instead of in Move, charging the gas
+    /// defined for them in the gas schedule.
+    NATIVE_FAST_PATHS = 82,
 }
 
 impl FeatureFlag {
@@ -329,6 +332,10 @@ impl Features {
         self.is_enabled(FeatureFlag::ENABLE_LOADER_V2)
     }
 
+    pub fn is_native_fast_paths_enabled(&self) -> bool {
+        self.is_enabled(FeatureFlag::NATIVE_FAST_PATHS)
+    }
+
     pub fn get_max_identifier_size(&self) -> u64 {
         if self.is_enabled(FeatureFlag::LIMIT_MAX_IDENTIFIER_LENGTH) {
             IDENTIFIER_SIZE_MAX
@@ -26,7 +26,7 @@ use aptos_crypto::{
 #[cfg(any(test, feature = "fuzzing"))]
 use proptest_derive::Arbitrary;
 use serde::{Deserialize, Serialize};
-use std::{any::type_name, marker::PhantomData};
+use std::{any::type_name, iter::Peekable, marker::PhantomData};
 
 /// A proof that can be used authenticate an element in an accumulator given trusted root hash. For
 /// example, both `LedgerInfoToTransactionInfoProof` and `TransactionInfoToEventProof` can be
@@ -427,6 +427,324 @@ impl SparseMerkleProof {
     }
 }
 
+/// The bottom of the proof path of one (or more) keys in a `SparseMerkleMultiProof`. This is
+/// a subtree that contains at most one leaf, i.e., the subtree is either a single leaf or empty.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct SparseMerkleMultiProofLeaf {
+    /// The depth of the subtree root (i.e., the number of siblings in an individual proof).
+    depth: usize,
+    /// The only leaf in the subtree (or `None` if the subtree is empty).
+    leaf: Option<SparseMerkleLeafNode>,
+}
+
+impl SparseMerkleMultiProofLeaf {
+    pub fn new(depth: usize, leaf: Option<SparseMerkleLeafNode>) -> Self {
+        Self { depth, leaf }
+    }
+
+    /// Returns the depth of the subtree root.
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Returns the leaf node in the subtree (if any).
+    pub fn leaf(&self) -> Option<SparseMerkleLeafNode> {
+        self.leaf
+    }
+
+    /// Returns the hash of the subtree.
+    fn hash(&self) -> HashValue {
+        self.leaf
+            .map_or(*SPARSE_MERKLE_PLACEHOLDER_HASH, |leaf| leaf.hash())
+    }
+}
+
+/// A proof that can be used to authenticate multiple elements in a Sparse Merkle Tree given a
+/// trusted root hash. Unlike a list of individual `SparseMerkleProof`s, the proof paths of the
+/// elements are merged, so internal nodes shared by the paths are not duplicated, and siblings
+/// that can be computed from the other elements are omitted. For example, given the following
+/// sparse Merkle tree:
+///
+/// ```text
+///                   root
+///                  /     \
+///                 a       o
+///                / \     / \
+///               b   c   d   X
+/// ```
+///
+/// if the proof wants to show that `[b, d]` exist in the tree, it would only need the siblings
+/// `c` and `X` (as `a` and `o` can be computed).
+///
+/// The keys proven by the proof must be sorted in ascending order (and deduplicated).
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct SparseMerkleMultiProof {
+    /// The bottom of every proof path, ordered from left to right. Keys that end up in the same
+    /// subtree share the same entry.
+    leaves: Vec<SparseMerkleMultiProofLeaf>,
+
+    /// All siblings that can't be computed from the proven keys, including the default ones.
+    /// Siblings are ordered by a depth-first (pre-order) traversal of the proof paths, i.e., from
+    /// the root level to the bottom level, and from left to right.
+    siblings: Vec<HashValue>,
+}
+
+impl SparseMerkleMultiProof {
+    /// Constructs a new `SparseMerkleMultiProof` using the leaves and a list of siblings.
+    pub fn new(leaves: Vec<SparseMerkleMultiProofLeaf>, siblings: Vec<HashValue>) -> Self {
+        Self { leaves, siblings }
+    }
+
+    /// Constructs a new `SparseMerkleMultiProof` by merging the individual proofs of the given
+    /// keys. All proofs must be against the same tree, and the keys must be sorted in ascending
+    /// order (without duplicates).
+    pub fn from_proofs(keys_and_proofs: &[(HashValue, SparseMerkleProof)]) -> Result<Self> {
+        ensure_sorted_keys(keys_and_proofs.iter().map(|(key, _)| *key))?;
+
+        let mut multi_proof = Self::default();
+        if !keys_and_proofs.is_empty() {
+            multi_proof.merge_proofs(keys_and_proofs, 0)?;
+        }
+        Ok(multi_proof)
+    }
+
+    /// Returns the leaves in this proof.
+    pub fn leaves(&self) -> &[SparseMerkleMultiProofLeaf] {
+        &self.leaves
+    }
+
+    /// Returns the list of siblings in this proof.
+    pub fn siblings(&self) -> &[HashValue] {
+        &self.siblings
+    }
+
+    pub fn verify<V: CryptoHash>(
+        &self,
+        expected_root_hash: HashValue,
+        elements: &[(HashValue, Option<&V>)],
+    ) -> Result<()> {
+        let elements: Vec<_> = elements
+            .iter()
+            .map(|(key, value)| (*key, value.map(|v| v.hash())))
+            .collect();
+        self.verify_by_hash(expected_root_hash, &elements)
+    }
+
+    /// Verifies the given elements against the Sparse Merkle Tree using the provided proof. For
+    /// each element, if the hash is present, verifies that an element with the key and value
+    /// hash exists in the tree. Otherwise verifies that the key doesn't exist in the tree. The
+    /// elements must be sorted by key in ascending order (without duplicates).
+    pub fn verify_by_hash(
+        &self,
+        expected_root_hash: HashValue,
+        elements: &[(HashValue, Option<HashValue>)],
+    ) -> Result<()> {
+        ensure_sorted_keys(elements.iter().map(|(key, _)| *key))?;
+        if elements.is_empty() {
+            ensure!(
+                self.leaves.is_empty() && self.siblings.is_empty(),
+                "Sparse Merkle Tree multi-proof is not empty, but there are no elements to verify!"
+            );
+            return Ok(());
+        }
+
+        let mut leaf_iter = self.leaves.iter().peekable();
+        let mut sibling_iter = self.siblings.iter();
+        let actual_root_hash =
+            compute_multi_proof_hash(elements, 0, &mut leaf_iter, &mut sibling_iter)?;
+        ensure!(
+            leaf_iter.next().is_none() && sibling_iter.next().is_none(),
+            "Sparse Merkle Tree multi-proof has unused leaves or siblings!"
+        );
+        ensure!(
+            actual_root_hash == expected_root_hash,
+            "{}: Root hashes do not match. Actual root hash: {:x}. Expected root hash: {:x}.",
+            type_name::<Self>(),
+            actual_root_hash,
+            expected_root_hash,
+        );
+
+        Ok(())
+    }
+
+    /// Merges the given proofs (whose keys share the same path up to the specified
+    /// depth) into this proof.
+    fn merge_proofs(
+        &mut self,
+        keys_and_proofs: &[(HashValue, SparseMerkleProof)],
+        depth: usize,
+    ) -> Result<()> {
+        // If the proof path ends at this depth, all keys end up in the same subtree
+        let (_, first_proof) = &keys_and_proofs[0];
+        if first_proof.siblings().len() == depth {
+            for (key, proof) in keys_and_proofs {
+                ensure!(
+                    proof.siblings().len() == depth && proof.leaf() == first_proof.leaf(),
+                    "The proof of key {:x} doesn't match the other proofs in the same subtree!",
+                    key
+                );
+            }
+            self.leaves
+                .push(SparseMerkleMultiProofLeaf::new(depth, first_proof.leaf()));
+            return Ok(());
+        }
+
+        // Otherwise, split the keys by the bit at this depth
+        ensure!(
+            first_proof.siblings().len() > depth && depth < HashValue::LENGTH_IN_BITS,
+            "The proofs diverge at depth {}!",
+            depth
+        );
+        let split_index = keys_and_proofs.partition_point(|(key, _)| !key.bit(depth));
+        let (left, right) = keys_and_proofs.split_at(split_index);
+        if left.is_empty() || right.is_empty() {
+            // Only one side is proven, so the sibling on the other side is required
+            self.siblings.push(first_proof.siblings()[depth]);
+        }
+        if !left.is_empty() {
+            self.merge_proofs(left, depth + 1)?;
+        }
+        if !right.is_empty() {
+            self.merge_proofs(right, depth + 1)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Ensures that the given keys are sorted in ascending order (without duplicates)
+fn ensure_sorted_keys(keys: impl Iterator<Item = HashValue>) -> Result<()> {
+    let mut previous_key: Option<HashValue> = None;
+    for key in keys {
+        if let Some(previous_key) = previous_key {
+            ensure!(
+                previous_key < key,
+                "Keys are not sorted in ascending order (or contain duplicates)! \
+                 Previous key: {:x}. Key: {:x}.",
+                previous_key,
+                key
+            );
+        }
+        previous_key = Some(key);
+    }
+    Ok(())
+}
+
+/// Computes the hash of the subtree at the specified depth that contains the given
+/// elements, using (and consuming) the leaves and siblings of a multi-proof.
+fn compute_multi_proof_hash<'a>(
+    elements: &[(HashValue, Option<HashValue>)],
+    depth: usize,
+    leaf_iter: &mut Peekable<impl Iterator<Item = &'a SparseMerkleMultiProofLeaf>>,
+    sibling_iter: &mut impl Iterator<Item = &'a HashValue>,
+) -> Result<HashValue> {
+    let multi_proof_leaf = **leaf_iter
+        .peek()
+        .ok_or_else(|| format_err!("Sparse Merkle Tree multi-proof is missing leaves!"))?;
+    ensure!(
+        multi_proof_leaf.depth() >= depth,
+        "Sparse Merkle Tree multi-proof leaf at depth {} is above the current depth {}!",
+        multi_proof_leaf.depth(),
+        depth
+    );
+
+    // If the leaf is at this depth, all elements should end up in its subtree
+    if multi_proof_leaf.depth() == depth {
+        leaf_iter.next();
+        for (element_key, element_hash) in elements {
+            verify_multi_proof_leaf(multi_proof_leaf, *element_key, *element_hash)?;
+        }
+        return Ok(multi_proof_leaf.hash());
+    }
+
+    // Otherwise, split the elements by the bit at this depth and compute the child hashes
+    ensure!(
+        depth < HashValue::LENGTH_IN_BITS,
+        "Sparse Merkle Tree multi-proof has more than {} levels.",
+        HashValue::LENGTH_IN_BITS,
+    );
+    let split_index = elements.partition_point(|(key, _)| !key.bit(depth));
+    let (left, right) = elements.split_at(split_index);
+    let mut next_sibling = || {
+        sibling_iter
+            .next()
+            .copied()
+            .ok_or_else(|| format_err!("Sparse Merkle Tree multi-proof is missing siblings!"))
+    };
+    let (left_hash, right_hash) = if left.is_empty() {
+        let left_hash = next_sibling()?;
+        let right_hash = compute_multi_proof_hash(right, depth + 1, leaf_iter, sibling_iter)?;
+        (left_hash, right_hash)
+    } else if right.is_empty() {
+        let right_hash = next_sibling()?;
+        let left_hash = compute_multi_proof_hash(left, depth + 1, leaf_iter, sibling_iter)?;
+        (left_hash, right_hash)
+    } else {
+        (
+            compute_multi_proof_hash(left, depth + 1, leaf_iter, sibling_iter)?,
+            compute_multi_proof_hash(right, depth + 1, leaf_iter, sibling_iter)?,
+        )
+    };
+
+    Ok(SparseMerkleInternalNode::new(left_hash, right_hash).hash())
+}
+
+/// Verifies the given element against the leaf (i.e., the subtree) of a multi-proof
+fn verify_multi_proof_leaf(
+    multi_proof_leaf: SparseMerkleMultiProofLeaf,
+    element_key: HashValue,
+    element_hash: Option<HashValue>,
+) -> Result<()> {
+    match (element_hash, multi_proof_leaf.leaf()) {
+        (Some(hash), Some(leaf)) => {
+            // This is an inclusion proof, so the key and value hash should match the leaf
+            ensure!(
+                element_key == leaf.key,
+                "Keys do not match. Key in proof: {:x}. Expected key: {:x}.",
+                leaf.key,
+                element_key,
+            );
+            ensure!(
+                hash == leaf.value_hash,
+                "Value hashes do not match for key {:x}. Value hash in proof: {:x}. \
+                 Expected value hash: {:x}.",
+                element_key,
+                leaf.value_hash,
+                hash
+            );
+        },
+        (Some(hash), None) => {
+            bail!(
+                "Expected inclusion proof for key {:x}, value hash: {:x}. Found non-inclusion proof.",
+                element_key,
+                hash
+            )
+        },
+        (None, Some(leaf)) => {
+            // This is a non-inclusion proof, so the leaf should be the only existing key in
+            // the subtree that the element would have ended up in.
+            ensure!(
+                element_key != leaf.key,
+                "Expected non-inclusion proof, but key exists in proof. Key: {:x}.",
+                element_key,
+            );
+            ensure!(
+                element_key.common_prefix_bits_len(leaf.key) >= multi_proof_leaf.depth(),
+                "Key would not have ended up in the subtree where the provided key in proof \
+                 is the only existing key, if it existed. So this is not a valid \
+                 non-inclusion proof. Key: {:x}. Key in proof: {:x}.",
+                element_key,
+                leaf.key
+            );
+        },
+        (None, None) => {
+            // This is a non-inclusion proof, the element would end up in an empty subtree
+        },
+    }
+
+    Ok(())
+}
+
 /// An in-memory accumulator for storing a summary of the core transaction info
 /// accumulator. It is a summary in the sense that it only stores maximally
 /// frozen subtree nodes rather than storing all leaves and internal nodes.
@@ -11,9 +11,9 @@ use crate::{
     ledger_info::LedgerInfo,
     proof::{
         definition::MAX_ACCUMULATOR_PROOF_DEPTH, AccumulatorExtensionProof, AccumulatorRangeProof,
-        SparseMerkleInternalNode, SparseMerkleLeafNode, TestAccumulatorInternalNode,
-        TestAccumulatorProof, TransactionAccumulatorInternalNode, TransactionAccumulatorProof,
-        TransactionInfoListWithProof, TransactionInfoWithProof,
+        SparseMerkleInternalNode, SparseMerkleLeafNode, SparseMerkleMultiProof,
+        TestAccumulatorInternalNode, TestAccumulatorProof, TransactionAccumulatorInternalNode,
+        TransactionAccumulatorProof, TransactionInfoListWithProof, TransactionInfoWithProof,
     },
     state_store::state_value::StateValue,
     transaction::{
@@ -267,6 +267,110 @@ fn test_verify_three_element_sparse_merkle() {
     }
 }
 
+#[test]
+fn test_verify_sparse_merkle_multi_proof() {
+    //            root
+    //           /    \
+    //          a      default
+    //         / \
+    //     key1   b
+    //           / \
+    //       key2   key3
+    let key1 = b"hello".test_only_hash();
+    let key2 = b"world".test_only_hash();
+    let key3 = b"!".test_only_hash();
+    let non_existing_key1 = b"abc".test_only_hash();
+    let non_existing_key2 = b"def".test_only_hash();
+
+    let blob1 = StateValue::from(b"1".to_vec());
+    let blob2 = StateValue::from(b"2".to_vec());
+    let blob3 = StateValue::from(b"3".to_vec());
+
+    let leaf1 = SparseMerkleLeafNode::new(key1, blob1.hash());
+    let leaf2 = SparseMerkleLeafNode::new(key2, blob2.hash());
+    let leaf3 = SparseMerkleLeafNode::new(key3, blob3.hash());
+    let internal_b_hash = SparseMerkleInternalNode::new(leaf2.hash(), leaf3.hash()).hash();
+    let internal_a_hash = SparseMerkleInternalNode::new(leaf1.hash(), internal_b_hash).hash();
+    let root_hash =
+        SparseMerkleInternalNode::new(internal_a_hash, *SPARSE_MERKLE_PLACEHOLDER_HASH).hash();
+
+    // Construct the individual proofs (sorted by key)
+    let keys_and_proofs = vec![
+        (
+            key1,
+            SparseMerkleProof::new(Some(leaf1), vec![
+                *SPARSE_MERKLE_PLACEHOLDER_HASH,
+                internal_b_hash,
+            ]),
+        ),
+        (
+            non_existing_key1,
+            SparseMerkleProof::new(Some(leaf1), vec![
+                *SPARSE_MERKLE_PLACEHOLDER_HASH,
+                internal_b_hash,
+            ]),
+        ),
+        (
+            key3,
+            SparseMerkleProof::new(Some(leaf3), vec![
+                *SPARSE_MERKLE_PLACEHOLDER_HASH,
+                leaf1.hash(),
+                leaf2.hash(),
+            ]),
+        ),
+        (
+            non_existing_key2,
+            SparseMerkleProof::new(None, vec![internal_a_hash]),
+        ),
+    ];
+
+    // Merge the proofs and verify that only the siblings that can't be computed are included
+    let multi_proof = SparseMerkleMultiProof::from_proofs(&keys_and_proofs).unwrap();
+    assert_eq!(multi_proof.siblings(), &[leaf2.hash()]);
+    assert_eq!(multi_proof.leaves().len(), 3);
+
+    // The exact values exist (and the non-existing keys don't exist)
+    let elements = vec![
+        (key1, Some(&blob1)),
+        (non_existing_key1, None),
+        (key3, Some(&blob3)),
+        (non_existing_key2, None),
+    ];
+    assert!(multi_proof.verify(root_hash, &elements).is_ok());
+
+    // Trying to show that a key has another value
+    let mut invalid_elements = elements.clone();
+    invalid_elements[2] = (key3, Some(&blob2));
+    assert!(multi_proof.verify(root_hash, &invalid_elements).is_err());
+
+    // Trying to show that an existing key doesn't exist
+    let mut invalid_elements = elements.clone();
+    invalid_elements[0] = (key1, None);
+    assert!(multi_proof.verify(root_hash, &invalid_elements).is_err());
+
+    // Trying to use the proof for different keys
+    assert!(multi_proof.verify(root_hash, &elements[..2]).is_err());
+    assert!(multi_proof
+        .verify(root_hash, &[(key2, Some(&blob2)), (key3, Some(&blob3))])
+        .is_err());
+
+    // Trying to verify against a different root
+    assert!(multi_proof.verify(internal_a_hash, &elements).is_err());
+
+    // Unsorted keys are rejected
+    let mut unsorted_keys_and_proofs = keys_and_proofs.clone();
+    unsorted_keys_and_proofs.swap(0, 2);
+    assert!(SparseMerkleMultiProof::from_proofs(&unsorted_keys_and_proofs).is_err());
+    let mut unsorted_elements = elements.clone();
+    unsorted_elements.swap(0, 2);
+    assert!(multi_proof.verify(root_hash, &unsorted_elements).is_err());
+
+    // An empty proof only verifies an empty set of elements
+    let empty_proof = SparseMerkleMultiProof::from_proofs(&[]).unwrap();
+    assert!(empty_proof.verify::<StateValue>(root_hash, &[]).is_ok());
+    assert!(empty_proof.verify(root_hash, &elements).is_err());
+}
+
 #[test]
 fn test_verify_transaction() {
     //            root
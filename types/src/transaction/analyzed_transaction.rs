@@ -7,7 +7,7 @@ use crate::{
     on_chain_config::{CurrentTimeMicroseconds, Features, TransactionFeeBurnCap},
     state_store::{state_key::StateKey, table::TableHandle},
     transaction::{
-        signature_verified_transaction::SignatureVerifiedTransaction, Transaction,
+        signature_verified_transaction::SignatureVerifiedTransaction, EntryFunction, Transaction,
         TransactionPayload,
     },
     AptosCoinType, CoinType,
@@ -237,6 +237,54 @@ pub fn empty_rw_set() -> (Vec<StorageLocation>, Vec<StorageLocation>) {
     (vec![], vec![])
 }
 
+/// Returns the read and write hints for the supported entry functions, or None if the hints are
+/// not known.
+fn rw_set_for_entry_function(
+    sender_address: AccountAddress,
+    func: &EntryFunction,
+) -> Option<(Vec<StorageLocation>, Vec<StorageLocation>)> {
+    let receiver_address = || bcs::from_bytes(func.args().first()?).ok();
+    match (
+        *func.module().address(),
+        func.module().name().as_str(),
+        func.function().as_str(),
+    ) {
+        (AccountAddress::ONE, "coin", "transfer") => Some(rw_set_for_coin_transfer(
+            sender_address,
+            receiver_address()?,
+            true,
+        )),
+        (AccountAddress::ONE, "aptos_account", "transfer") => Some(rw_set_for_coin_transfer(
+            sender_address,
+            receiver_address()?,
+            false,
+        )),
+        (AccountAddress::ONE, "aptos_account", "create_account") => Some(
+            rw_set_for_create_account(sender_address, receiver_address()?),
+        ),
+        _ => None,
+    }
+}
+
+/// Returns the read and write hints of the transaction, or None if they are not known. Unlike
+/// [AnalyzedTransaction::new], does not panic on unsupported transactions, so it can be used
+/// to predict the state accessed by arbitrary blocks.
+pub fn try_get_read_write_hints(
+    txn: &SignatureVerifiedTransaction,
+) -> Option<(Vec<StorageLocation>, Vec<StorageLocation>)> {
+    match txn {
+        SignatureVerifiedTransaction::Valid(Transaction::UserTransaction(signed_txn)) => {
+            match signed_txn.payload() {
+                TransactionPayload::EntryFunction(func) => {
+                    rw_set_for_entry_function(signed_txn.sender(), func)
+                },
+                _ => None,
+            }
+        },
+        _ => Some(empty_rw_set()),
+    }
+}
+
 trait AnalyzedTransactionProvider {
     fn get_read_write_hints(&self) -> (Vec<StorageLocation>, Vec<StorageLocation>);
 }
@@ -246,39 +294,12 @@ impl AnalyzedTransactionProvider for Transaction {
         match self {
             Transaction::UserTransaction(signed_txn) => match signed_txn.payload() {
                 TransactionPayload::EntryFunction(func) => {
-                    match (
-                        *func.module().address(),
-                        func.module().name().as_str(),
-                        func.function().as_str(),
-                    ) {
-                        (AccountAddress::ONE, "coin", "transfer") => {
-                            let sender_address = signed_txn.sender();
-                            let receiver_address = bcs::from_bytes(&func.args()[0]).unwrap();
-                            rw_set_for_coin_transfer(
-                                sender_address,
-                                receiver_address,
-                                true,
-                            )
-                        },
-                        (AccountAddress::ONE, "aptos_account", "transfer") => {
-                            let sender_address = signed_txn.sender();
-                            let receiver_address = bcs::from_bytes(&func.args()[0]).unwrap();
-                            rw_set_for_coin_transfer(
-                                sender_address,
-                                receiver_address,
-                                false,
-                            )
-                        },
-                        (AccountAddress::ONE, "aptos_account", "create_account") => {
-                            let sender_address = signed_txn.sender();
-                            let receiver_address = bcs::from_bytes(&func.args()[0]).unwrap();
-                            rw_set_for_create_account(
-                                sender_address,
-                                receiver_address,
-                            )
-                        },
-                        _ => todo!("Only coin transfer and create account transactions are supported for now")
-                    }
+                    rw_set_for_entry_function(signed_txn.sender(), func).unwrap_or_else(|| {
+                        todo!(
+                            "Only coin transfer and create account transactions are supported \
+                             for now"
+                        )
+                    })
                 },
                 _ => todo!("Only entry function transactions are supported for now"),
             },
@@ -45,6 +45,7 @@ mod block_output;
 mod change_set;
 mod module;
 mod multisig;
+pub mod resource_usage;
 mod script;
 pub mod signature_verified_transaction;
 pub mod use_case;
@@ -73,6 +74,7 @@ use move_vm_types::delayed_values::delayed_field_id::{
 };
 pub use multisig::{ExecutionError, Multisig, MultisigTransactionPayload};
 use once_cell::sync::OnceCell;
+use resource_usage::TransactionResourceUsage;
 pub use script::{
     ArgumentABI, EntryABI, EntryFunction, EntryFunctionABI, Script, TransactionScriptABI,
     TypeArgumentABI,
@@ -1130,7 +1132,7 @@ impl TransactionAuxiliaryData {
 }
 
 /// The output of executing a transaction.
-#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, Eq, Serialize, Deserialize)]
 pub struct TransactionOutput {
     /// The list of writes this transaction intends to do.
     write_set: WriteSet,
@@ -1147,6 +1149,21 @@ pub struct TransactionOutput {
     /// The transaction auxiliary data that includes detail error info that is not used for calculating the hash
     #[serde(skip)]
     auxiliary_data: TransactionAuxiliaryData,
+
+    /// Resources used to execute the transaction, only set if resource accounting is enabled.
+    #[serde(skip)]
+    resource_usage: Option<TransactionResourceUsage>,
+}
+
+// Resource usage is not compared because it contains wall-clock execution time.
+impl PartialEq for TransactionOutput {
+    fn eq(&self, other: &Self) -> bool {
+        self.write_set == other.write_set
+            && self.events == other.events
+            && self.gas_used == other.gas_used
+            && self.status == other.status
+            && self.auxiliary_data == other.auxiliary_data
+    }
 }
 
 impl TransactionOutput {
@@ -1164,6 +1181,7 @@ impl TransactionOutput {
             gas_used,
             status,
             auxiliary_data,
+            resource_usage: None,
         }
     }
 
@@ -1174,6 +1192,7 @@ impl TransactionOutput {
             gas_used: 0,
             status: TransactionStatus::Keep(ExecutionStatus::Success),
             auxiliary_data: TransactionAuxiliaryData::None,
+            resource_usage: None,
         }
     }
 
@@ -1210,6 +1229,14 @@ impl TransactionOutput {
         &self.auxiliary_data
     }
 
+    pub fn resource_usage(&self) -> Option<&TransactionResourceUsage> {
+        self.resource_usage.as_ref()
+    }
+
+    pub fn set_resource_usage(&mut self, resource_usage: TransactionResourceUsage) {
+        self.resource_usage = Some(resource_usage);
+    }
+
     pub fn unpack(
         self,
     ) -> (
@@ -1225,6 +1252,7 @@ impl TransactionOutput {
             gas_used,
             status,
             auxiliary_data,
+            resource_usage: _,
         } = self;
         (write_set, events, gas_used, status, auxiliary_data)
     }
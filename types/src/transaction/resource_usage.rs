@@ -0,0 +1,258 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Opt-in accounting of resources used by transactions, for capacity planning and debugging.
+//!
+//! When enabled with [set_resource_accounting_enabled], the executing thread accumulates usage of
+//! the transaction it is currently executing (between [start_resource_accounting] and
+//! [finish_resource_accounting]), and the result is attached to the [TransactionOutput]. Because
+//! it measures wall-clock time, resource usage is not part of the output's equality, hash or
+//! serialization.
+
+use crate::transaction::TransactionOutput;
+use std::{
+    cell::RefCell,
+    fmt,
+    ops::AddAssign,
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
+
+static RESOURCE_ACCOUNTING_ENABLED: AtomicBool = AtomicBool::new(false);
+
+thread_local! {
+    static CURRENT_USAGE: RefCell<Option<TransactionResourceUsage>> = const { RefCell::new(None) };
+}
+
+/// Enables or disables resource accounting for all subsequently executed transactions.
+pub fn set_resource_accounting_enabled(enabled: bool) {
+    RESOURCE_ACCOUNTING_ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+pub fn is_resource_accounting_enabled() -> bool {
+    RESOURCE_ACCOUNTING_ENABLED.load(Ordering::Relaxed)
+}
+
+/// Starts accounting for the transaction about to be executed on this thread. No-op if resource
+/// accounting is disabled.
+pub fn start_resource_accounting() {
+    if is_resource_accounting_enabled() {
+        CURRENT_USAGE.with(|usage| *usage.borrow_mut() = Some(TransactionResourceUsage::default()));
+    }
+}
+
+/// Records usage for the transaction currently executed on this thread, if accounting for it has
+/// been started.
+pub fn record_resource_usage(f: impl FnOnce(&mut TransactionResourceUsage)) {
+    if is_resource_accounting_enabled() {
+        CURRENT_USAGE.with(|usage| {
+            if let Some(usage) = usage.borrow_mut().as_mut() {
+                f(usage);
+            }
+        });
+    }
+}
+
+/// Stops accounting for the transaction executed on this thread, returning its usage if the
+/// accounting has been started.
+pub fn finish_resource_accounting() -> Option<TransactionResourceUsage> {
+    CURRENT_USAGE.with(|usage| usage.borrow_mut().take())
+}
+
+/// Resources used by a single transaction (or by multiple ones, when aggregated).
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct TransactionResourceUsage {
+    /// Wall-clock time of the (last) execution of the transaction in the VM.
+    pub execution_time: Duration,
+    /// Number of resources read from storage, not counting resource groups.
+    pub resource_reads: u64,
+    /// Number of resources read from resource groups.
+    pub resource_group_reads: u64,
+    /// Number of table items read from storage.
+    pub table_item_reads: u64,
+    /// Number of modules loaded from storage, i.e., not found in module caches.
+    pub module_reads: u64,
+    /// Number of aggregator (V1) and delayed field reads.
+    pub aggregator_reads: u64,
+    /// Total size of the state values read, including sizes of the accessed resource groups.
+    pub bytes_read: u64,
+    /// Total size of the write set (keys and values) and of the emitted events.
+    pub bytes_written: u64,
+    /// Number of Move bytecode instructions executed.
+    pub num_instructions: u64,
+    /// Number of native function calls.
+    pub num_native_calls: u64,
+}
+
+impl TransactionResourceUsage {
+    pub fn num_state_reads(&self) -> u64 {
+        self.resource_reads
+            + self.resource_group_reads
+            + self.table_item_reads
+            + self.module_reads
+            + self.aggregator_reads
+    }
+
+    /// Sets the number of bytes written based on the committed output of the transaction.
+    pub fn set_bytes_written(&mut self, output: &TransactionOutput) {
+        let write_set_size = output
+            .write_set()
+            .iter()
+            .map(|(key, op)| key.size() + op.bytes_size())
+            .sum::<usize>();
+        let events_size = output.events().iter().map(|e| e.size()).sum::<usize>();
+        self.bytes_written = (write_set_size + events_size) as u64;
+    }
+}
+
+impl AddAssign<&TransactionResourceUsage> for TransactionResourceUsage {
+    fn add_assign(&mut self, other: &TransactionResourceUsage) {
+        self.execution_time += other.execution_time;
+        self.resource_reads += other.resource_reads;
+        self.resource_group_reads += other.resource_group_reads;
+        self.table_item_reads += other.table_item_reads;
+        self.module_reads += other.module_reads;
+        self.aggregator_reads += other.aggregator_reads;
+        self.bytes_read += other.bytes_read;
+        self.bytes_written += other.bytes_written;
+        self.num_instructions += other.num_instructions;
+        self.num_native_calls += other.num_native_calls;
+    }
+}
+
+impl fmt::Display for TransactionResourceUsage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "time: {}us, reads: {} (resources: {}, groups: {}, tables: {}, modules: {}, \
+             aggregators: {}), bytes read: {}, bytes written: {}, instructions: {}, \
+             native calls: {}",
+            self.execution_time.as_micros(),
+            self.num_state_reads(),
+            self.resource_reads,
+            self.resource_group_reads,
+            self.table_item_reads,
+            self.module_reads,
+            self.aggregator_reads,
+            self.bytes_read,
+            self.bytes_written,
+            self.num_instructions,
+            self.num_native_calls,
+        )
+    }
+}
+
+/// Resource usage aggregated over the transactions of a block.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct BlockResourceUsage {
+    /// Number of transactions with recorded resource usage.
+    pub num_transactions: usize,
+    pub total: TransactionResourceUsage,
+    pub max_execution_time: Duration,
+}
+
+impl BlockResourceUsage {
+    /// Aggregates resource usage of the outputs, skipping outputs without it. Returns [None] if
+    /// none of the outputs has resource usage recorded.
+    pub fn from_outputs<'a>(
+        outputs: impl IntoIterator<Item = &'a TransactionOutput>,
+    ) -> Option<Self> {
+        let mut block_usage = Self::default();
+        for usage in outputs.into_iter().filter_map(|o| o.resource_usage()) {
+            block_usage.add(usage);
+        }
+        (block_usage.num_transactions > 0).then_some(block_usage)
+    }
+
+    pub fn add(&mut self, usage: &TransactionResourceUsage) {
+        self.num_transactions += 1;
+        self.total += usage;
+        self.max_execution_time = self.max_execution_time.max(usage.execution_time);
+    }
+
+    pub fn merge(&mut self, other: &BlockResourceUsage) {
+        self.num_transactions += other.num_transactions;
+        self.total += &other.total;
+        self.max_execution_time = self.max_execution_time.max(other.max_execution_time);
+    }
+}
+
+impl fmt::Display for BlockResourceUsage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} txns, max time: {}us, total {}",
+            self.num_transactions,
+            self.max_execution_time.as_micros(),
+            self.total,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Mutex, MutexGuard, PoisonError};
+
+    /// Serializes the tests toggling the process-wide resource accounting.
+    static RESOURCE_ACCOUNTING_LOCK: Mutex<()> = Mutex::new(());
+
+    /// Disables the resource accounting when dropped (also when the test panics), while holding
+    /// [RESOURCE_ACCOUNTING_LOCK].
+    struct ResourceAccountingGuard {
+        _lock: MutexGuard<'static, ()>,
+    }
+
+    impl ResourceAccountingGuard {
+        fn new() -> Self {
+            let lock = RESOURCE_ACCOUNTING_LOCK
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner);
+            set_resource_accounting_enabled(false);
+            Self { _lock: lock }
+        }
+    }
+
+    impl Drop for ResourceAccountingGuard {
+        fn drop(&mut self) {
+            set_resource_accounting_enabled(false);
+        }
+    }
+
+    #[test]
+    fn test_recording_requires_enabled_and_started_accounting() {
+        let _guard = ResourceAccountingGuard::new();
+        start_resource_accounting();
+        record_resource_usage(|u| u.resource_reads += 1);
+        assert_eq!(finish_resource_accounting(), None);
+
+        set_resource_accounting_enabled(true);
+        record_resource_usage(|u| u.resource_reads += 1);
+        assert_eq!(finish_resource_accounting(), None);
+
+        start_resource_accounting();
+        record_resource_usage(|u| u.resource_reads += 1);
+        record_resource_usage(|u| u.bytes_read += 10);
+        let usage = finish_resource_accounting().unwrap();
+        assert_eq!(usage.resource_reads, 1);
+        assert_eq!(usage.bytes_read, 10);
+        assert_eq!(finish_resource_accounting(), None);
+    }
+
+    #[test]
+    fn test_block_aggregation() {
+        let usage = |micros, reads| TransactionResourceUsage {
+            execution_time: Duration::from_micros(micros),
+            table_item_reads: reads,
+            ..TransactionResourceUsage::default()
+        };
+
+        let mut block_usage = BlockResourceUsage::default();
+        block_usage.add(&usage(10, 2));
+        block_usage.add(&usage(30, 1));
+        assert_eq!(block_usage.num_transactions, 2);
+        assert_eq!(block_usage.max_execution_time, Duration::from_micros(30));
+        assert_eq!(block_usage.total.execution_time, Duration::from_micros(40));
+        assert_eq!(block_usage.total.num_state_reads(), 3);
+    }
+}